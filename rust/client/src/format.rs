@@ -0,0 +1,762 @@
+//! Rendering of command results as JSON, NDJSON, CSV or a table
+//!
+//! Results are the JSON values of the [rpc](crate::rpc) methods. Tabular
+//! formats render one row per record (block, transaction, account, ...) with
+//! nested objects flattened into dotted columns. Text results are written as
+//! is.
+//!
+//! Followed commands are rendered by a [FollowRenderer], which labels each
+//! record with its status & the sequence number of the event it was pushed
+//! for.
+//!
+//! Streamed results are rendered by a [StreamRenderer], which writes NDJSON &
+//! CSV records as their chunks arrive.
+
+use crate::rpc::FollowItem;
+use serde_json::{json, Map, Value};
+use std::{fmt, io::Write, str::FromStr};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Pretty-printed JSON, as sent by the server
+    #[default]
+    Json,
+
+    /// One compact JSON record per line
+    Ndjson,
+
+    /// Comma-separated values with a header row
+    Csv,
+
+    /// Aligned columns with a header row
+    Table,
+}
+
+impl OutputFormat {
+    pub const NAMES: [&'static str; 4] = ["json", "ndjson", "csv", "table"];
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "ndjson" => Ok(Self::Ndjson),
+            "csv" => Ok(Self::Csv),
+            "table" => Ok(Self::Table),
+            _ => anyhow::bail!("Unknown output format: {s}"),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Json => "json",
+            Self::Ndjson => "ndjson",
+            Self::Csv => "csv",
+            Self::Table => "table",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Records of the result's kind, recognized by its method
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResultKind {
+    Blocks,
+    Transactions,
+    InternalCommands,
+    Snarks,
+    Ledger,
+    StakingLedger,
+    Delegations,
+    Other,
+}
+
+impl ResultKind {
+    fn of_method(method: &str) -> Self {
+        match method.split('.').next().unwrap_or_default() {
+            "blocks" | "chain" => Self::Blocks,
+            "transactions" => Self::Transactions,
+            "internal-commands" => Self::InternalCommands,
+            "snarks" => Self::Snarks,
+            "accounts" | "ledgers" => Self::Ledger,
+            "staking-ledgers" if method.ends_with(".delegations") => Self::Delegations,
+            "staking-ledgers" if method.ends_with(".public-key") => Self::Delegations,
+            "staking-ledgers" => Self::StakingLedger,
+            _ => Self::Other,
+        }
+    }
+
+    /// Leading columns, in order
+    fn columns(self) -> &'static [&'static str] {
+        match self {
+            Self::Blocks => &[
+                "state_hash",
+                "blockchain_length",
+                "global_slot_since_genesis",
+                "parent_hash",
+                "canonicity",
+            ],
+            Self::Transactions => &["kind", "source", "receiver", "amount", "fee", "nonce"],
+            Self::InternalCommands => &[
+                "kind",
+                "sender",
+                "receiver",
+                "amount",
+                "state_hash",
+                "block_height",
+                "date_time",
+            ],
+            Self::Snarks => &["prover", "fee", "state_hash"],
+            Self::Ledger => &["public_key", "balance", "nonce", "delegate", "username"],
+            Self::StakingLedger => &["pk", "balance", "nonce", "delegate", "voting_for"],
+            Self::Delegations => &["pk", "epoch", "count_delegates", "total_delegated"],
+            Self::Other => &[],
+        }
+    }
+
+    /// Map of records nested in a single result object
+    fn collection_field(self) -> Option<&'static str> {
+        match self {
+            Self::StakingLedger => Some("staking_ledger"),
+            Self::Delegations => Some("delegations"),
+            _ => None,
+        }
+    }
+}
+
+/// Write the `method`'s JSON `result` to `writer` in the given format
+pub fn render<W: Write>(
+    format: OutputFormat,
+    method: &str,
+    result: &str,
+    writer: &mut W,
+) -> anyhow::Result<()> {
+    let value = match parse_result(format, result, writer)? {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    if format == OutputFormat::Json {
+        return Ok(writeln!(writer, "{}", result.trim_end())?);
+    }
+
+    let kind = ResultKind::of_method(method);
+    let records = records(kind, value);
+    match format {
+        OutputFormat::Json => unreachable!("JSON is written as is"),
+        OutputFormat::Ndjson => {
+            for record in records {
+                serde_json::to_writer(&mut *writer, &record)?;
+                writeln!(writer)?;
+            }
+        }
+        OutputFormat::Csv => {
+            let (columns, rows) = rows(kind, records, true);
+            let mut csv = csv::Writer::from_writer(writer);
+            csv.write_record(&columns)?;
+            for row in rows {
+                csv.write_record(&row)?;
+            }
+            csv.flush()?;
+        }
+        OutputFormat::Table => {
+            let (columns, rows) = rows(kind, records, true);
+            let widths: Vec<usize> = columns
+                .iter()
+                .enumerate()
+                .map(|(n, column)| {
+                    rows.iter()
+                        .map(|row| row[n].chars().count())
+                        .chain([column.len()])
+                        .max()
+                        .unwrap_or_default()
+                })
+                .collect();
+
+            for row in [columns].into_iter().chain(rows) {
+                let line: Vec<String> = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{cell:width$}"))
+                    .collect();
+                writeln!(writer, "{}", line.join("  ").trim_end())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Renders a followed command's result & the items pushed after it
+///
+/// JSON writes the result as is & each item as an object. Other formats write
+/// one record per line/row, those of the result with status `current`.
+/// Tabular columns are fixed by the first records.
+pub struct FollowRenderer {
+    format: OutputFormat,
+    kind: ResultKind,
+    columns: Option<(Vec<String>, Vec<usize>)>,
+}
+
+impl FollowRenderer {
+    pub fn new(format: OutputFormat, method: &str) -> Self {
+        Self {
+            format,
+            kind: ResultKind::of_method(method),
+            columns: None,
+        }
+    }
+
+    /// Write the followed call's JSON `result`
+    pub fn render_result<W: Write>(&mut self, result: &str, writer: &mut W) -> anyhow::Result<()> {
+        let value = match parse_result(self.format, result, writer)? {
+            Some(value) => value,
+            None => return Ok(()),
+        };
+        if self.format == OutputFormat::Json {
+            return Ok(writeln!(writer, "{}", result.trim_end())?);
+        }
+        self.render_records("current", None, records(self.kind, value), writer)
+    }
+
+    pub fn render_item<W: Write>(
+        &mut self,
+        item: &FollowItem,
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        if self.format == OutputFormat::Json {
+            let item = json!({
+                "status": item.status,
+                "seq_num": item.seq_num,
+                "data": item.data,
+            });
+            return Ok(writeln!(
+                writer,
+                "{}",
+                serde_json::to_string_pretty(&item)?
+            )?);
+        }
+        self.render_records(
+            &item.status.to_string(),
+            Some(item.seq_num),
+            vec![item.data.clone()],
+            writer,
+        )
+    }
+
+    fn render_records<W: Write>(
+        &mut self,
+        status: &str,
+        seq_num: Option<u32>,
+        records: Vec<Value>,
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        if matches!(self.format, OutputFormat::Json | OutputFormat::Ndjson) {
+            for record in records {
+                let record = json!({ "status": status, "seq_num": seq_num, "data": record });
+                serde_json::to_writer(&mut *writer, &record)?;
+                writeln!(writer)?;
+            }
+            return Ok(());
+        }
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let seq_num = seq_num
+            .map(|seq_num| seq_num.to_string())
+            .unwrap_or_default();
+        let (record_columns, record_rows) = rows(self.kind, records, true);
+        let record_rows: Vec<Vec<String>> = record_rows
+            .into_iter()
+            .map(|row| {
+                [status.to_string(), seq_num.clone()]
+                    .into_iter()
+                    .chain(row)
+                    .collect()
+            })
+            .collect();
+        let record_columns: Vec<String> = ["status".to_string(), "seq_num".to_string()]
+            .into_iter()
+            .chain(record_columns)
+            .collect();
+
+        // header & widths of the first records
+        let header = self.columns.is_none();
+        let (columns, widths) = self.columns.get_or_insert_with(|| {
+            let widths = record_columns
+                .iter()
+                .enumerate()
+                .map(|(n, column)| {
+                    record_rows
+                        .iter()
+                        .map(|row| row[n].chars().count())
+                        .chain([column.len()])
+                        .max()
+                        .unwrap_or_default()
+                })
+                .collect();
+            (record_columns.clone(), widths)
+        });
+        let rows = align_rows(columns, &record_columns, record_rows);
+        let header = header.then(|| columns.clone());
+
+        match self.format {
+            OutputFormat::Csv => {
+                let mut csv = csv::Writer::from_writer(writer);
+                for row in header.into_iter().chain(rows) {
+                    csv.write_record(&row)?;
+                }
+                csv.flush()?;
+            }
+            _ => {
+                for row in header.into_iter().chain(rows) {
+                    let line: Vec<String> = row
+                        .iter()
+                        .zip(widths.iter())
+                        .map(|(cell, width)| format!("{cell:width$}"))
+                        .collect();
+                    writeln!(writer, "{}", line.join("  ").trim_end())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders a result streamed in chunks, see [rpc](crate::rpc)
+///
+/// NDJSON & CSV records are written as soon as their chunk arrives, CSV
+/// columns are fixed by the records of the first chunk, null or not. Records are found in
+/// arrays, record maps & the collection fields of staking ledgers &
+/// delegations. Other results, text & tables are rendered once complete.
+pub struct StreamRenderer {
+    format: OutputFormat,
+    method: String,
+    kind: ResultKind,
+    splitter: RecordSplitter,
+    columns: Option<Vec<String>>,
+}
+
+impl StreamRenderer {
+    pub fn new(format: OutputFormat, method: &str) -> Self {
+        let kind = ResultKind::of_method(method);
+        let mut splitter = RecordSplitter::new(kind);
+        if !matches!(format, OutputFormat::Ndjson | OutputFormat::Csv) {
+            splitter.fallback = true;
+        }
+        Self {
+            format,
+            method: method.to_string(),
+            kind,
+            splitter,
+            columns: None,
+        }
+    }
+
+    /// Write the records completed by the next chunk of the result's JSON
+    pub fn render_chunk<W: Write>(&mut self, data: &str, writer: &mut W) -> anyhow::Result<()> {
+        let records = self
+            .splitter
+            .push(data)
+            .iter()
+            .map(|record| serde_json::from_str(record))
+            .collect::<Result<Vec<Value>, _>>()?;
+        self.render_records(records, writer)
+    }
+
+    /// Write the rest of the result once all of its chunks arrived
+    pub fn finish<W: Write>(self, writer: &mut W) -> anyhow::Result<()> {
+        if self.splitter.fallback {
+            return render(self.format, &self.method, &self.splitter.text, writer);
+        }
+        if self.splitter.record_depth.is_none() {
+            anyhow::bail!("Incomplete result of {}", self.method);
+        }
+        Ok(())
+    }
+
+    fn render_records<W: Write>(
+        &mut self,
+        records: Vec<Value>,
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        match self.format {
+            OutputFormat::Ndjson => {
+                for record in records {
+                    serde_json::to_writer(&mut *writer, &record)?;
+                    writeln!(writer)?;
+                }
+            }
+            OutputFormat::Csv => {
+                let (record_columns, record_rows) = rows(self.kind, records, false);
+                let header = self.columns.is_none();
+                let columns = self.columns.get_or_insert_with(|| record_columns.clone());
+                let rows = align_rows(columns, &record_columns, record_rows);
+                let header = header.then(|| columns.clone());
+
+                let mut csv = csv::Writer::from_writer(writer);
+                for row in header.into_iter().chain(rows) {
+                    csv.write_record(&row)?;
+                }
+                csv.flush()?;
+            }
+            _ => unreachable!("only NDJSON & CSV are split"),
+        }
+        Ok(())
+    }
+}
+
+/// Splits the JSON text of a streamed result into the text of its records,
+/// see [records]
+///
+/// Structural characters are ASCII, so the text is scanned bytewise
+struct RecordSplitter {
+    kind: ResultKind,
+
+    /// Open containers, innermost last
+    stack: Vec<Container>,
+    in_string: bool,
+    escaped: bool,
+
+    /// Reading a number, `true`, `false` or `null`
+    in_scalar: bool,
+
+    /// Depth of the records' container, once found
+    record_depth: Option<usize>,
+
+    /// Text of the result until its records are found
+    text: String,
+
+    /// The result has no records to split, it's rendered from [Self::text]
+    fallback: bool,
+
+    /// Text of the record or top level key being read
+    token: Option<Token>,
+    token_text: String,
+
+    /// Last top level key
+    key: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Container {
+    object: bool,
+    expect_key: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Record,
+    Key,
+}
+
+impl RecordSplitter {
+    fn new(kind: ResultKind) -> Self {
+        Self {
+            kind,
+            stack: vec![],
+            in_string: false,
+            escaped: false,
+            in_scalar: false,
+            record_depth: None,
+            text: String::new(),
+            fallback: false,
+            token: None,
+            token_text: String::new(),
+            key: String::new(),
+        }
+    }
+
+    /// Text of the records completed by the next chunk
+    fn push(&mut self, data: &str) -> Vec<String> {
+        let mut records = vec![];
+        if self.record_depth.is_none() {
+            self.text.push_str(data);
+        }
+        if self.fallback {
+            return records;
+        }
+
+        let mut token_start = 0;
+        for (n, byte) in data.bytes().enumerate() {
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+                    if self.token == Some(Token::Key) {
+                        self.token_text.push_str(&data[token_start..n]);
+                        self.key = std::mem::take(&mut self.token_text);
+                        self.token = None;
+                    } else if !self.is_key() {
+                        self.value_end(data, token_start, n + 1, &mut records);
+                    }
+                }
+                continue;
+            }
+            if self.in_scalar && matches!(byte, b',' | b']' | b'}' | b' ' | b'\t' | b'\n' | b'\r') {
+                self.in_scalar = false;
+                self.value_end(data, token_start, n, &mut records);
+            }
+
+            match byte {
+                b'"' => {
+                    self.in_string = true;
+                    if self.is_key() {
+                        if self.stack.len() == 1 && self.record_depth.is_none() {
+                            self.token = Some(Token::Key);
+                            token_start = n + 1;
+                        }
+                    } else {
+                        self.value_start(byte, n, &mut token_start);
+                    }
+                }
+                b'{' | b'[' => {
+                    self.value_start(byte, n, &mut token_start);
+                    self.stack.push(Container {
+                        object: byte == b'{',
+                        expect_key: byte == b'{',
+                    });
+                }
+                b'}' | b']' => {
+                    self.stack.pop();
+                    self.value_end(data, token_start, n + 1, &mut records);
+                }
+                b':' => {
+                    if let Some(container) = self.stack.last_mut() {
+                        container.expect_key = false;
+                    }
+                }
+                b',' => {
+                    if let Some(container) = self.stack.last_mut() {
+                        container.expect_key = container.object;
+                    }
+                }
+                b' ' | b'\t' | b'\n' | b'\r' => (),
+                _ => {
+                    if !self.in_scalar {
+                        self.in_scalar = true;
+                        self.value_start(byte, n, &mut token_start);
+                    }
+                }
+            }
+            if self.fallback {
+                return records;
+            }
+        }
+
+        if self.token.is_some() {
+            self.token_text.push_str(&data[token_start..]);
+        }
+        records
+    }
+
+    /// The next string is an object's key
+    fn is_key(&self) -> bool {
+        self.stack
+            .last()
+            .is_some_and(|container| container.object && container.expect_key)
+    }
+
+    /// A value starts with `byte` at index `n` of the chunk
+    fn value_start(&mut self, byte: u8, n: usize, token_start: &mut usize) {
+        let depth = self.stack.len();
+        if self.record_depth.is_none() {
+            match (depth, byte) {
+                // arrays of records
+                (0, b'[') => self.found_records(1),
+
+                // record maps & objects with a collection field
+                (0, b'{') if self.kind != ResultKind::Other => (),
+                (1, _) => match self.kind.collection_field() {
+                    Some(field) if self.key == field && byte == b'{' => self.found_records(2),
+                    Some(field) if self.key == field => self.fallback = true,
+                    Some(_) => (),
+                    None if byte == b'{' => self.found_records(1),
+                    None => self.fallback = true,
+                },
+                (0, _) => self.fallback = true,
+                _ => (),
+            }
+        }
+        if self.record_depth == Some(depth) {
+            self.token = Some(Token::Record);
+            *token_start = n;
+        }
+    }
+
+    /// A value ends before index `end` of the chunk
+    fn value_end(&mut self, data: &str, token_start: usize, end: usize, records: &mut Vec<String>) {
+        if self.record_depth == Some(self.stack.len()) && self.token == Some(Token::Record) {
+            self.token_text.push_str(&data[token_start..end]);
+            records.push(std::mem::take(&mut self.token_text));
+            self.token = None;
+        } else if self.record_depth.is_none() && self.stack.is_empty() {
+            // the result ended without records
+            self.fallback = true;
+        }
+    }
+
+    fn found_records(&mut self, depth: usize) {
+        self.record_depth = Some(depth);
+        self.text.clear();
+    }
+}
+
+/// JSON value of the result, `None` if it was text & written as is
+fn parse_result<W: Write>(
+    format: OutputFormat,
+    result: &str,
+    writer: &mut W,
+) -> anyhow::Result<Option<Value>> {
+    // text results are written as is, JSON text is parsed
+    match serde_json::from_str(result)? {
+        Value::String(text) => match serde_json::from_str::<Value>(&text) {
+            Ok(value @ (Value::Array(_) | Value::Object(_))) if format != OutputFormat::Json => {
+                Ok(Some(value))
+            }
+            _ => {
+                writeln!(writer, "{}", text.trim_end())?;
+                Ok(None)
+            }
+        },
+        value => Ok(Some(value)),
+    }
+}
+
+/// Records of the result, one per row
+fn records(kind: ResultKind, value: Value) -> Vec<Value> {
+    let value = match (kind.collection_field(), value) {
+        (Some(field), Value::Object(mut object))
+            if object.get(field).is_some_and(Value::is_object) =>
+        {
+            object.remove(field).expect("collection field")
+        }
+        (_, value) => value,
+    };
+
+    match value {
+        Value::Array(records) => records,
+
+        // ledgers are keyed by public key
+        Value::Object(object)
+            if kind != ResultKind::Other
+                && !object.is_empty()
+                && object.values().all(Value::is_object) =>
+        {
+            object.into_iter().map(|(_, record)| record).collect()
+        }
+        value => vec![value],
+    }
+}
+
+/// Columns & stringified cells of the records
+///
+/// Tagged records, e.g. `{"Payment": {..}}`, are untagged into a `kind`
+/// column & columns which are null in every row are dropped, if
+/// `drop_null_columns`
+fn rows(
+    kind: ResultKind,
+    records: Vec<Value>,
+    drop_null_columns: bool,
+) -> (Vec<String>, Vec<Vec<String>>) {
+    let flattened: Vec<Map<String, Value>> = records
+        .into_iter()
+        .map(|record| {
+            let mut flat = Map::new();
+            match record {
+                Value::Object(object) if object.len() == 1 && kind == ResultKind::Transactions => {
+                    let (tag, inner) = object.into_iter().next().expect("single entry");
+                    flat.insert("kind".into(), Value::String(tag));
+                    flatten(None, inner, &mut flat);
+                }
+                record => flatten(None, record, &mut flat),
+            }
+            flat
+        })
+        .collect();
+
+    let mut columns: Vec<String> = vec![];
+    for column in kind.columns() {
+        if flattened.iter().any(|flat| flat.contains_key(*column)) {
+            columns.push(column.to_string());
+        }
+    }
+    for flat in &flattened {
+        for column in flat.keys() {
+            if !columns.contains(column) {
+                columns.push(column.clone());
+            }
+        }
+    }
+    columns.retain(|column| {
+        !drop_null_columns
+            || flattened
+                .iter()
+                .any(|flat| flat.get(column).is_some_and(|value| !value.is_null()))
+    });
+
+    let rows = flattened
+        .iter()
+        .map(|flat| {
+            columns
+                .iter()
+                .map(|column| match flat.get(column) {
+                    None | Some(Value::Null) => String::new(),
+                    Some(Value::String(s)) => s.clone(),
+                    Some(value) => value.to_string(),
+                })
+                .collect()
+        })
+        .collect();
+    (columns, rows)
+}
+
+/// Cells of the rows in the order of `columns`, blank for missing columns
+fn align_rows(
+    columns: &[String],
+    record_columns: &[String],
+    record_rows: Vec<Vec<String>>,
+) -> Vec<Vec<String>> {
+    record_rows
+        .into_iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|column| {
+                    record_columns
+                        .iter()
+                        .position(|record_column| record_column == column)
+                        .map(|n| row[n].clone())
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Nested objects become dotted columns, arrays stay JSON
+fn flatten(prefix: Option<&str>, value: Value, flat: &mut Map<String, Value>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                let key = match prefix {
+                    Some(prefix) => format!("{prefix}.{key}"),
+                    None => key,
+                };
+                flatten(Some(&key), value, flat);
+            }
+        }
+        value => {
+            flat.insert(prefix.unwrap_or("value").to_string(), value);
+        }
+    }
+}
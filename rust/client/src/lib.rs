@@ -0,0 +1,523 @@
+//! Async client of a running mina indexer
+//!
+//! Typed queries are GraphQL documents, sent to the `/graphql` endpoint over
+//! HTTP or as `graphql` requests over the Unix domain socket's JSON-RPC
+//! protocol. Raw JSON-RPC calls, mirroring the client subcommands, are only
+//! served over the Unix domain socket. Queries are of the server's primary
+//! network, unless another is selected with [IndexerClient::on_network].
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use mina_indexer_client::IndexerClient;
+//!
+//! let client = IndexerClient::connect_uds("./mina-indexer.sock").await?;
+//! if let Some(best_tip) = client.best_tip().await? {
+//!     println!("{} at height {}", best_tip.state_hash, best_tip.block_height);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod format;
+pub mod rpc;
+pub mod types;
+
+use anyhow::{anyhow, bail, Context};
+use format::{FollowRenderer, OutputFormat};
+use rpc::RpcClient;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, value::RawValue, Value};
+use std::{io::Write, path::Path};
+use tokio::sync::Mutex;
+use types::*;
+
+/// GraphQL endpoint of the web server
+pub const ENDPOINT_GRAPHQL: &str = "/graphql";
+
+/// Max number of results of list queries, unless given
+pub const DEFAULT_LIMIT: u32 = 100;
+
+pub struct IndexerClient {
+    transport: Transport,
+
+    /// Name or genesis state hash of the queried network
+    network: Option<String>,
+}
+
+enum Transport {
+    Uds(Mutex<RpcClient>),
+    Http {
+        client: reqwest::Client,
+        graphql_url: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQLResponse {
+    data: Option<Value>,
+
+    #[serde(default)]
+    errors: Vec<GraphQLError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQLError {
+    message: String,
+}
+
+const BLOCK_FIELDS: &str = "
+    state_hash: stateHash
+    block_height: blockHeight
+    global_slot_since_genesis: globalSlotSinceGenesis
+    date_time: dateTime
+    canonical
+    creator
+    protocol_state: protocolState {
+        previous_state_hash: previousStateHash
+        consensus_state: consensusState {
+            epoch
+            slot
+            total_currency: totalCurrency
+        }
+    }
+    confirmations
+    is_final: isFinal";
+
+const TIMING_FIELDS: &str = "
+    timing {
+        initial_minimum_balance
+        cliff_time
+        cliff_amount
+        vesting_period
+        vesting_increment
+    }";
+
+const TRANSACTION_FIELDS: &str = "
+    hash
+    kind
+    from
+    to
+    amount
+    fee
+    nonce
+    memo
+    failure_reason: failureReason
+    block {
+        state_hash: stateHash
+        date_time: dateTime
+    }
+    block_height: blockHeight
+    global_slot: globalSlot
+    canonical
+    confirmations
+    is_final: isFinal";
+
+impl IndexerClient {
+    /// Client of the JSON-RPC server at `domain_socket_path`
+    pub async fn connect_uds(domain_socket_path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let domain_socket_path = domain_socket_path.as_ref();
+        let client = RpcClient::connect(domain_socket_path)
+            .await
+            .with_context(|| format!("Unable to connect to {}", domain_socket_path.display()))?;
+        Ok(Self {
+            transport: Transport::Uds(Mutex::new(client)),
+            network: None,
+        })
+    }
+
+    /// Client of the web server at `base_url`, e.g. `http://localhost:8080`
+    pub fn http(base_url: &str) -> Self {
+        Self {
+            transport: Transport::Http {
+                client: reqwest::Client::new(),
+                graphql_url: format!("{}{ENDPOINT_GRAPHQL}", base_url.trim_end_matches('/')),
+            },
+            network: None,
+        }
+    }
+
+    /// Query the network with the name or genesis state hash, instead of the
+    /// server's primary network
+    pub fn on_network(mut self, network: impl Into<String>) -> Self {
+        self.network = Some(network.into());
+        self
+    }
+
+    /// The params, selecting the queried network
+    fn network_params(&self, params: Option<Value>) -> Option<Value> {
+        let Some(network) = &self.network else {
+            return params;
+        };
+        let mut params = params.unwrap_or_else(|| json!({}));
+        if let Some(params) = params.as_object_mut() {
+            params.insert("network".to_string(), network.clone().into());
+        }
+        Some(params)
+    }
+
+    /// JSON-RPC call of `method`, over the Unix domain socket only
+    ///
+    /// Error responses are returned as [rpc::RpcError]
+    pub async fn call(&self, method: &str, params: Option<Value>) -> anyhow::Result<Box<RawValue>> {
+        match &self.transport {
+            Transport::Uds(client) => {
+                let params = self.network_params(params);
+                client.lock().await.call(method, params).await
+            }
+            Transport::Http { .. } => {
+                bail!("JSON-RPC calls are only served over the Unix domain socket")
+            }
+        }
+    }
+
+    /// JSON-RPC call of `method`, rendering its result to `writer`
+    ///
+    /// JSON results are written as they're streamed, without being
+    /// reassembled in memory, as are NDJSON & CSV records, see
+    /// [format::StreamRenderer]
+    pub async fn call_to_writer<W: Write>(
+        &self,
+        method: &str,
+        params: Option<Value>,
+        format: OutputFormat,
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        let client = match &self.transport {
+            Transport::Uds(client) => client,
+            Transport::Http { .. } => {
+                bail!("JSON-RPC calls are only served over the Unix domain socket")
+            }
+        };
+        let params = self.network_params(params);
+        let mut client = client.lock().await;
+        if format != OutputFormat::Json {
+            let mut renderer = format::StreamRenderer::new(format, method);
+            let result = client
+                .call_streamed(method, params, |data| renderer.render_chunk(data, writer))
+                .await?;
+            return match result {
+                Some(result) => format::render(format, method, result.get(), writer),
+                None => renderer.finish(writer),
+            };
+        }
+
+        // text is a JSON string, only written once decoded
+        let mut text: Option<String> = None;
+        let mut first_chunk = true;
+        let result = client
+            .call_streamed(method, params, |data| {
+                if std::mem::take(&mut first_chunk) && data.starts_with('"') {
+                    text = Some(String::new());
+                }
+                match &mut text {
+                    Some(text) => text.push_str(data),
+                    None => writer.write_all(data.as_bytes())?,
+                }
+                Ok(())
+            })
+            .await?;
+        match (result, text) {
+            (Some(result), _) => format::render(format, method, result.get(), writer),
+            (None, Some(text)) => format::render(format, method, &text, writer),
+            (None, None) => Ok(writeln!(writer)?),
+        }
+    }
+
+    /// Follow the `method`, writing its result & then each item pushed by the
+    /// server to `writer` until the connection closes
+    ///
+    /// `params` should set `"follow": true`, else no items are pushed
+    pub async fn follow_to_writer<W: Write>(
+        &self,
+        method: &str,
+        params: Option<Value>,
+        format: OutputFormat,
+        writer: &mut W,
+    ) -> anyhow::Result<()> {
+        let client = match &self.transport {
+            Transport::Uds(client) => client,
+            Transport::Http { .. } => {
+                bail!("Following is only served over the Unix domain socket")
+            }
+        };
+        let params = self.network_params(params);
+        let mut client = client.lock().await;
+        let mut renderer = FollowRenderer::new(format, method);
+
+        let result = client.call(method, params).await?;
+        renderer.render_result(result.get(), writer)?;
+        writer.flush()?;
+
+        while let Some(item) = client.next_item().await? {
+            renderer.render_item(&item, writer)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Data of the GraphQL `query`, errors are returned as such
+    pub async fn graphql<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: Value,
+    ) -> anyhow::Result<T> {
+        let request = json!({ "query": query, "variables": variables });
+        let response: GraphQLResponse = match &self.transport {
+            Transport::Uds(_) => {
+                let result = self.call(rpc::GRAPHQL_METHOD, Some(request)).await?;
+                serde_json::from_str(result.get())?
+            }
+            Transport::Http {
+                client,
+                graphql_url,
+            } => {
+                let mut request_builder = client.post(graphql_url);
+                if let Some(network) = &self.network {
+                    request_builder = request_builder.query(&[("network", network)]);
+                }
+                let response = request_builder
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(serde_json::to_vec(&request)?)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+                serde_json::from_slice(&response.bytes().await?)?
+            }
+        };
+
+        if let Some(error) = response.errors.first() {
+            bail!("GraphQL error: {}", error.message);
+        }
+        let data = response
+            .data
+            .ok_or_else(|| anyhow!("GraphQL response without data"))?;
+        Ok(serde_json::from_value(data)?)
+    }
+
+    /// Data of the GraphQL `query`'s `field`
+    async fn graphql_field<T: DeserializeOwned>(
+        &self,
+        field: &str,
+        query: &str,
+        variables: Value,
+    ) -> anyhow::Result<T> {
+        let mut data: Value = self.graphql(query, variables).await?;
+        Ok(serde_json::from_value(data[field].take())?)
+    }
+
+    pub async fn best_tip(&self) -> anyhow::Result<Option<Block>> {
+        let query = format!("query BestTip {{ block {{ {BLOCK_FIELDS} }} }}");
+        self.graphql_field("block", &query, json!({})).await
+    }
+
+    pub async fn block(&self, state_hash: &str) -> anyhow::Result<Option<Block>> {
+        let query = format!(
+            "query Block($state_hash: String!) {{
+                block(query: {{ stateHash: $state_hash }}) {{ {BLOCK_FIELDS} }}
+            }}"
+        );
+        self.graphql_field("block", &query, json!({ "state_hash": state_hash }))
+            .await
+    }
+
+    /// Blocks at the blockchain length, canonical & orphaned
+    pub async fn blocks_at_height(&self, height: u32) -> anyhow::Result<Vec<Block>> {
+        let query = format!(
+            "query BlocksAtHeight($height: Int!) {{
+                blocks(query: {{ blockHeight: $height }}, limit: {DEFAULT_LIMIT}) {{ {BLOCK_FIELDS} }}
+            }}"
+        );
+        self.graphql_field("blocks", &query, json!({ "height": height }))
+            .await
+    }
+
+    /// Best ledger account
+    pub async fn account(&self, public_key: &str) -> anyhow::Result<Option<Account>> {
+        let query = format!(
+            "query Account($public_key: String!) {{
+                accounts(query: {{ publicKey: $public_key }}, limit: 1) {{
+                    public_key: publicKey
+                    username
+                    balance
+                    nonce
+                    delegate
+                    time_locked: timeLocked
+                    {TIMING_FIELDS}
+                    is_genesis_account
+                }}
+            }}"
+        );
+        let accounts: Vec<Account> = self
+            .graphql_field("accounts", &query, json!({ "public_key": public_key }))
+            .await?;
+        Ok(accounts.into_iter().next())
+    }
+
+    /// Transaction in each of its containing blocks
+    pub async fn transaction(&self, hash: &str) -> anyhow::Result<Vec<Transaction>> {
+        let query = format!(
+            "query Transaction($hash: String!) {{
+                transactions(query: {{ hash: $hash }}, limit: {DEFAULT_LIMIT}) {{ {TRANSACTION_FIELDS} }}
+            }}"
+        );
+        self.graphql_field("transactions", &query, json!({ "hash": hash }))
+            .await
+    }
+
+    /// Transactions sent or received by the account within the bounds,
+    /// highest block first
+    pub async fn transactions_for(
+        &self,
+        public_key: &str,
+        bounds: HeightBounds,
+        limit: Option<u32>,
+    ) -> anyhow::Result<Vec<Transaction>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT);
+        let query = format!(
+            "query Transactions($query: TransactionQueryInput!, $limit: Int!) {{
+                transactions(query: $query, limit: $limit, sortBy: BLOCKHEIGHT_DESC) {{
+                    {TRANSACTION_FIELDS}
+                }}
+            }}"
+        );
+
+        // sent & received transactions are served by separate indices
+        let mut transactions: Vec<Transaction> = vec![];
+        for direction in ["from", "to"] {
+            let mut filter = height_filter(bounds);
+            filter[direction] = public_key.into();
+
+            let txns: Vec<Transaction> = self
+                .graphql_field(
+                    "transactions",
+                    &query,
+                    json!({ "query": filter, "limit": limit }),
+                )
+                .await?;
+            for txn in txns {
+                if !transactions.contains(&txn) {
+                    transactions.push(txn);
+                }
+            }
+        }
+
+        transactions.sort_by(|x, y| y.block_height.cmp(&x.block_height));
+        transactions.truncate(limit as usize);
+        Ok(transactions)
+    }
+
+    /// Internal commands received by the account within the bounds, highest
+    /// block first
+    pub async fn internal_commands_for(
+        &self,
+        public_key: &str,
+        bounds: HeightBounds,
+        limit: Option<u32>,
+    ) -> anyhow::Result<Vec<InternalCommand>> {
+        let query = "query InternalCommands($query: FeetransferQueryInput!, $limit: Int!) {
+            feetransfers(query: $query, limit: $limit, sortBy: BLOCKHEIGHT_DESC) {
+                recipient
+                fee
+                kind: type
+                state_hash: stateHash
+                block_height: blockHeight
+                date_time: dateTime
+                canonical
+            }
+        }";
+        let mut filter = height_filter(bounds);
+        filter["recipient"] = public_key.into();
+        self.graphql_field(
+            "feetransfers",
+            query,
+            json!({ "query": filter, "limit": limit.unwrap_or(DEFAULT_LIMIT) }),
+        )
+        .await
+    }
+
+    /// SNARK work of the prover within the bounds, highest block first
+    pub async fn snarks_for(
+        &self,
+        public_key: &str,
+        bounds: HeightBounds,
+        limit: Option<u32>,
+    ) -> anyhow::Result<Vec<Snark>> {
+        let query = "query Snarks($query: SnarkQueryInput!, $limit: Int!) {
+            snarks(query: $query, limit: $limit, sortBy: BLOCKHEIGHT_DESC) {
+                prover
+                fee
+                state_hash: stateHash
+                block_height: blockHeight
+                date_time: dateTime
+                canonical
+            }
+        }";
+        let mut filter = height_filter(bounds);
+        filter["prover"] = public_key.into();
+        self.graphql_field(
+            "snarks",
+            query,
+            json!({ "query": filter, "limit": limit.unwrap_or(DEFAULT_LIMIT) }),
+        )
+        .await
+    }
+
+    /// Staking ledger accounts of the epoch, highest stake first
+    pub async fn staking_ledger(
+        &self,
+        epoch: u32,
+        limit: Option<u32>,
+    ) -> anyhow::Result<Vec<StakingAccount>> {
+        self.stakes(json!({ "epoch": epoch }), limit.unwrap_or(DEFAULT_LIMIT))
+            .await
+    }
+
+    /// Staking ledger account of the epoch & its delegations
+    pub async fn staking_account(
+        &self,
+        epoch: u32,
+        public_key: &str,
+    ) -> anyhow::Result<Option<StakingAccount>> {
+        let accounts = self
+            .stakes(json!({ "epoch": epoch, "public_key": public_key }), 1)
+            .await?;
+        Ok(accounts.into_iter().next())
+    }
+
+    async fn stakes(&self, filter: Value, limit: u32) -> anyhow::Result<Vec<StakingAccount>> {
+        let query = format!(
+            "query Stakes($query: StakeQueryInput!, $limit: Int!) {{
+                stakes(query: $query, limit: $limit, sortBy: STAKE_DESC) {{
+                    epoch
+                    ledger_hash: ledgerHash
+                    public_key
+                    username
+                    balance: balanceNanomina
+                    nonce
+                    delegate
+                    voting_for
+                    {TIMING_FIELDS}
+                    delegation_totals: delegationTotals {{
+                        total_delegated: totalDelegatedNanomina
+                        count_delegates: countDelegates
+                        total_stake_percentage: totalStakePercentage
+                    }}
+                }}
+            }}"
+        );
+        self.graphql_field("stakes", &query, json!({ "query": filter, "limit": limit }))
+            .await
+    }
+}
+
+/// Query input of the block height bounds
+fn height_filter(bounds: HeightBounds) -> Value {
+    let mut filter = json!({});
+    if let Some(min) = bounds.min {
+        filter["blockHeight_gte"] = min.into();
+    }
+    if let Some(max) = bounds.max {
+        filter["blockHeight_lte"] = max.into();
+    }
+    filter
+}
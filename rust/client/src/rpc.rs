@@ -0,0 +1,491 @@
+//! JSON-RPC 2.0 over the Unix domain socket
+//!
+//! A connection starts with the [PREAMBLE] `MINA-INDEXER-RPC/1\n`, followed
+//! by frames, each a 4-byte big-endian length & that many bytes of JSON.
+//! Requests are answered in order until the client closes the connection.
+//!
+//! Methods mirror the client subcommands joined by `.`, e.g. `blocks.best-tip`
+//! or `staking-ledgers.epoch`, & params are their flags, e.g.
+//! `{"epoch": 42, "verbose": true}`. `rpc.methods` lists all methods & params.
+//! Any request may also set `network`, the name or genesis state hash of the
+//! indexed network to query, else the primary network is queried.
+//! `graphql` executes `{"query", "variables", "operationName"}` against the
+//! indexer's GraphQL schema.
+//!
+//! Results are the command's JSON output, else its text output as a string.
+//! Results over [STREAM_CHUNK_LEN] bytes are streamed as `rpc.chunk`
+//! notifications `{"id", "seq", "data"}` followed by the response with a
+//! `null` result. The concatenated `data` is the JSON text of the result.
+//! Streaming bounds the frames' length, not the server's memory: the command's
+//! output is complete before its first chunk is written.
+//!
+//! Commands called with `"follow": true` keep the connection open after their
+//! response. New items are pushed as `rpc.follow` notifications
+//! `{"id", "seq_num", "status", "data"}` as the indexer processes blocks, with
+//! status `new`, else `orphaned` for items of blocks orphaned by a reorg. The
+//! connection serves no further requests & ends when either side closes it.
+//!
+//! Connections without the preamble are served a single bincode-encoded
+//! command, as sent by older clients.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{value::RawValue, Value};
+use std::path::Path;
+use tokio::{
+    io::{
+        AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt,
+        BufReader,
+    },
+    net::{
+        unix::{OwnedReadHalf, OwnedWriteHalf},
+        UnixStream,
+    },
+};
+
+pub const PROTOCOL: &str = "MINA-INDEXER-RPC";
+pub const PROTOCOL_VERSION: u32 = 1;
+pub const PREAMBLE: &[u8] = b"MINA-INDEXER-RPC/1\n";
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// Max length of a frame's JSON
+pub const MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+/// Max length of a result's JSON text before it's streamed
+pub const STREAM_CHUNK_LEN: usize = 64 * 1024;
+
+pub const METHODS_METHOD: &str = "rpc.methods";
+pub const CHUNK_METHOD: &str = "rpc.chunk";
+pub const FOLLOW_METHOD: &str = "rpc.follow";
+pub const GRAPHQL_METHOD: &str = "graphql";
+
+/// JSON-RPC error codes
+pub mod codes {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+
+    pub const UNSUPPORTED_VERSION: i64 = -32000;
+    pub const INVALID_PUBLIC_KEY: i64 = -32001;
+    pub const INVALID_STATE_HASH: i64 = -32002;
+    pub const INVALID_TX_HASH: i64 = -32003;
+    pub const BEST_TIP_MISSING: i64 = -32004;
+    pub const BLOCK_MISSING: i64 = -32005;
+    pub const PATH_IS_DIRECTORY: i64 = -32006;
+    pub const NO_RESPONSE: i64 = -32007;
+    pub const UNKNOWN_NETWORK: i64 = -32008;
+    pub const NETWORK_INITIALIZING: i64 = -32009;
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("{message} ({code})")]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    pub jsonrpc: String,
+
+    /// Requests without an id are notifications & aren't answered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    pub method: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Response {
+    pub jsonrpc: String,
+    pub id: Option<Value>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Box<RawValue>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+/// Params of an `rpc.chunk` notification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chunk {
+    pub id: Value,
+    pub seq: u32,
+    pub data: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ChunkNotification {
+    jsonrpc: &'static str,
+    method: &'static str,
+    params: Chunk,
+}
+
+/// Params of an `rpc.follow` notification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FollowItem {
+    pub id: Value,
+
+    /// Sequence number of the event the item was derived from
+    pub seq_num: u32,
+    pub status: FollowStatus,
+    pub data: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FollowStatus {
+    New,
+    Orphaned,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FollowNotification {
+    jsonrpc: &'static str,
+    method: &'static str,
+    params: FollowItem,
+}
+
+/// Method of a server frame, only set for notifications
+#[derive(Debug, Deserialize)]
+struct Notification {
+    method: Option<String>,
+    params: Option<Value>,
+}
+
+/// Method listed by `rpc.methods`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MethodInfo {
+    pub method: String,
+    pub description: Option<String>,
+    pub params: Vec<ParamInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParamInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub required: bool,
+
+    /// Boolean flags are set with `true`, all other params take a value
+    pub flag: bool,
+    pub default: Option<String>,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(
+            codes::METHOD_NOT_FOUND,
+            format!("Method not found: {method}"),
+        )
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(codes::INVALID_PARAMS, message)
+    }
+}
+
+impl FollowNotification {
+    pub fn new(item: FollowItem) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION,
+            method: FOLLOW_METHOD,
+            params: item,
+        }
+    }
+}
+
+impl std::fmt::Display for FollowStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::New => write!(f, "new"),
+            Self::Orphaned => write!(f, "orphaned"),
+        }
+    }
+}
+
+impl Request {
+    pub fn new(id: u64, method: impl Into<String>, params: Option<Value>) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: Some(id.into()),
+            method: method.into(),
+            params,
+        }
+    }
+
+    pub fn from_frame(frame: &[u8]) -> Result<Self, RpcError> {
+        let value: Value = serde_json::from_slice(frame)
+            .map_err(|e| RpcError::new(codes::PARSE_ERROR, format!("Parse error: {e}")))?;
+        if value.is_array() {
+            return Err(RpcError::new(
+                codes::INVALID_REQUEST,
+                "Batch requests are not supported",
+            ));
+        }
+
+        let request: Self = serde_json::from_value(value)
+            .map_err(|e| RpcError::new(codes::INVALID_REQUEST, format!("Invalid request: {e}")))?;
+        if request.jsonrpc != JSONRPC_VERSION {
+            return Err(RpcError::new(
+                codes::INVALID_REQUEST,
+                format!("Invalid request: jsonrpc must be {JSONRPC_VERSION:?}"),
+            ));
+        }
+        Ok(request)
+    }
+}
+
+impl Response {
+    pub fn result(id: Value, result: Box<RawValue>) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: Some(id),
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn error(id: Option<Value>, error: RpcError) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Whether the start of a connection is (a prefix of) the preamble
+pub fn is_rpc_preamble(bytes: &[u8]) -> bool {
+    let len = bytes.len().min(PROTOCOL.len());
+    len > 0 && bytes[..len] == PROTOCOL.as_bytes()[..len]
+}
+
+/// Read the preamble line & check its protocol version
+pub async fn read_preamble<R>(reader: &mut R) -> Result<(), RpcError>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = vec![];
+    reader
+        .take(64)
+        .read_until(b'\n', &mut line)
+        .await
+        .map_err(|e| RpcError::new(codes::INTERNAL_ERROR, e.to_string()))?;
+
+    let line = String::from_utf8_lossy(&line);
+    let version = line
+        .trim_end()
+        .strip_prefix(PROTOCOL)
+        .and_then(|rest| rest.strip_prefix('/'))
+        .ok_or_else(|| RpcError::new(codes::INVALID_REQUEST, "Invalid preamble"))?;
+    if version != PROTOCOL_VERSION.to_string() {
+        return Err(RpcError::new(
+            codes::UNSUPPORTED_VERSION,
+            format!("Unsupported protocol version {version}, expected {PROTOCOL_VERSION}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Read the next frame, `None` if the peer closed the connection
+pub async fn read_frame<R>(reader: &mut R) -> anyhow::Result<Option<Vec<u8>>>
+where
+    R: AsyncRead + Unpin,
+{
+    let len = match reader.read_u32().await {
+        Ok(len) => len as usize,
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    if len > MAX_FRAME_LEN {
+        anyhow::bail!("Frame of {len} bytes exceeds the max of {MAX_FRAME_LEN}");
+    }
+
+    let mut frame = vec![0; len];
+    reader.read_exact(&mut frame).await?;
+    Ok(Some(frame))
+}
+
+pub async fn write_frame<W, T>(writer: &mut W, message: &T) -> anyhow::Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let frame = serde_json::to_vec(message)?;
+    writer.write_u32(frame.len() as u32).await?;
+    writer.write_all(&frame).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Write a command's output as the result of request `id`, streaming it if
+/// it's longer than [STREAM_CHUNK_LEN]
+///
+/// The whole `output` is buffered, only the client renders it incrementally
+pub async fn write_result<W>(writer: &mut W, id: Value, output: String) -> anyhow::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let result = if serde_json::from_str::<serde::de::IgnoredAny>(&output).is_ok() {
+        output
+    } else {
+        serde_json::to_string(&output)?
+    };
+
+    if result.len() <= STREAM_CHUNK_LEN {
+        let response = Response::result(id, RawValue::from_string(result)?);
+        return write_frame(writer, &response).await;
+    }
+
+    let mut rest = result.as_str();
+    let mut seq = 0;
+    while !rest.is_empty() {
+        let mut end = rest.len().min(STREAM_CHUNK_LEN);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        let (data, tail) = rest.split_at(end);
+        let chunk = ChunkNotification {
+            jsonrpc: JSONRPC_VERSION,
+            method: CHUNK_METHOD,
+            params: Chunk {
+                id: id.clone(),
+                seq,
+                data: data.to_string(),
+            },
+        };
+        write_frame(writer, &chunk).await?;
+
+        rest = tail;
+        seq += 1;
+    }
+
+    let response = Response::result(id, RawValue::from_string("null".to_string())?);
+    write_frame(writer, &response).await
+}
+
+/// JSON-RPC connection to the Unix domain socket server
+pub struct RpcClient {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+    next_id: u64,
+}
+
+impl RpcClient {
+    pub async fn connect(domain_socket_path: &Path) -> anyhow::Result<Self> {
+        let (reader, mut writer) = UnixStream::connect(domain_socket_path).await?.into_split();
+        writer.write_all(PREAMBLE).await?;
+        Ok(Self {
+            reader: BufReader::new(reader),
+            writer,
+            next_id: 0,
+        })
+    }
+
+    /// Result of the method call, reassembled if streamed
+    ///
+    /// Error responses are returned as [RpcError]
+    pub async fn call(
+        &mut self,
+        method: &str,
+        params: Option<Value>,
+    ) -> anyhow::Result<Box<RawValue>> {
+        let mut streamed = String::new();
+        let result = self
+            .call_streamed(method, params, |data| {
+                streamed.push_str(data);
+                Ok(())
+            })
+            .await?;
+        match result {
+            Some(result) => Ok(result),
+            None => Ok(RawValue::from_string(streamed)?),
+        }
+    }
+
+    /// Result of the method call, `None` if it was streamed to `on_chunk`
+    ///
+    /// Error responses are returned as [RpcError]
+    pub async fn call_streamed<F>(
+        &mut self,
+        method: &str,
+        params: Option<Value>,
+        mut on_chunk: F,
+    ) -> anyhow::Result<Option<Box<RawValue>>>
+    where
+        F: FnMut(&str) -> anyhow::Result<()>,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+        write_frame(&mut self.writer, &Request::new(id, method, params)).await?;
+
+        let mut streamed = false;
+        loop {
+            let frame = read_frame(&mut self.reader)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Connection closed before response {id}"))?;
+            let notification: Notification = serde_json::from_slice(&frame)?;
+            if notification.method.as_deref() == Some(CHUNK_METHOD) {
+                let chunk: Chunk = serde_json::from_value(notification.params.unwrap_or_default())?;
+                if chunk.id == id {
+                    on_chunk(&chunk.data)?;
+                    streamed = true;
+                }
+                continue;
+            }
+            if notification.method.is_some() {
+                continue;
+            }
+
+            let response: Response = serde_json::from_slice(&frame)?;
+            if response
+                .id
+                .as_ref()
+                .is_some_and(|response_id| *response_id != id)
+            {
+                continue;
+            }
+            if let Some(error) = response.error {
+                return Err(error.into());
+            }
+            if streamed {
+                return Ok(None);
+            }
+            return match response.result {
+                Some(result) => Ok(Some(result)),
+                None => Ok(Some(RawValue::from_string("null".to_string())?)),
+            };
+        }
+    }
+
+    /// Next item pushed after the response of a followed call, `None` once
+    /// the server closes the connection
+    pub async fn next_item(&mut self) -> anyhow::Result<Option<FollowItem>> {
+        while let Some(frame) = read_frame(&mut self.reader).await? {
+            let notification: Notification = serde_json::from_slice(&frame)?;
+            if notification.method.as_deref() == Some(FOLLOW_METHOD) {
+                let item = serde_json::from_value(notification.params.unwrap_or_default())?;
+                return Ok(Some(item));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,147 @@
+//! Typed results of [IndexerClient](crate::IndexerClient) queries
+//!
+//! Amounts & fees are in nanomina.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Block {
+    pub state_hash: String,
+    pub block_height: u32,
+    pub global_slot_since_genesis: u32,
+    pub date_time: String,
+    pub canonical: bool,
+    pub creator: String,
+    pub protocol_state: ProtocolState,
+
+    /// Number of blocks on top of the block in the best chain
+    pub confirmations: Option<u32>,
+
+    /// Whether the block has at least k confirmations or is at or below the
+    /// canonical root
+    pub is_final: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolState {
+    pub previous_state_hash: String,
+    pub consensus_state: ConsensusState,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsensusState {
+    pub epoch: u32,
+    pub slot: u32,
+    pub total_currency: u64,
+}
+
+/// Best ledger account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Account {
+    pub public_key: String,
+    pub username: Option<String>,
+    pub balance: u64,
+    pub nonce: u32,
+    pub delegate: String,
+    pub time_locked: bool,
+    pub timing: Option<Timing>,
+    pub is_genesis_account: bool,
+}
+
+/// Vesting schedule of a time-locked account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timing {
+    pub initial_minimum_balance: Option<u64>,
+    pub cliff_time: Option<u32>,
+    pub cliff_amount: Option<u64>,
+    pub vesting_period: Option<u32>,
+    pub vesting_increment: Option<u64>,
+}
+
+/// User command in a block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub hash: String,
+
+    /// `PAYMENT` or `STAKE_DELEGATION`
+    pub kind: String,
+    pub from: String,
+    pub to: String,
+    pub amount: u64,
+    pub fee: u64,
+    pub nonce: u32,
+    pub memo: String,
+
+    /// Empty unless the command failed
+    pub failure_reason: String,
+    pub block: TransactionBlock,
+    pub block_height: u32,
+    pub global_slot: u32,
+    pub canonical: bool,
+    pub confirmations: Option<u32>,
+    pub is_final: bool,
+}
+
+/// Block containing a transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionBlock {
+    pub state_hash: String,
+    pub date_time: String,
+}
+
+/// Fee transfer or coinbase
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InternalCommand {
+    pub recipient: String,
+    pub fee: u64,
+
+    /// `Coinbase`, `Fee_transfer` or `Fee_transfer_via_coinbase`
+    pub kind: String,
+    pub state_hash: String,
+    pub block_height: u32,
+    pub date_time: String,
+    pub canonical: bool,
+}
+
+/// SNARK work bought by a block producer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snark {
+    pub prover: String,
+    pub fee: u64,
+    pub state_hash: String,
+    pub block_height: u32,
+    pub date_time: String,
+    pub canonical: bool,
+}
+
+/// Staking ledger account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StakingAccount {
+    pub epoch: u32,
+    pub ledger_hash: String,
+    pub public_key: String,
+    pub username: Option<String>,
+    pub balance: u64,
+    pub nonce: u32,
+    pub delegate: String,
+    pub voting_for: String,
+    pub timing: Option<Timing>,
+    pub delegation_totals: DelegationTotals,
+}
+
+/// Stake delegated to an account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegationTotals {
+    pub total_delegated: u64,
+    pub count_delegates: u32,
+
+    /// Percentage of the total currency
+    pub total_stake_percentage: String,
+}
+
+/// Block height bounds (inclusive)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeightBounds {
+    pub min: Option<u32>,
+    pub max: Option<u32>,
+}
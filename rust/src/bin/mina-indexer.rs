@@ -1,24 +1,43 @@
-use clap::{Parser, Subcommand};
+use anyhow::{bail, Context};
+use clap::{Args, Parser, Subcommand};
 use log::{debug, error, info, warn, LevelFilter};
 use mina_indexer::{
     block::precomputed::PcbVersion,
     chain::Network,
     client,
     constants::*,
+    event::broadcast::event_channel,
+    health::{IndexerHealth, DEFAULT_MAX_SLOT_LAG},
     ledger::{
         self,
         genesis::{GenesisConstants, GenesisLedger, GenesisRoot},
     },
+    networks::{IndexedNetwork, IndexedNetworks},
     server::{
         initialize_indexer_database, start_indexer, IndexerConfiguration, InitializationMode,
     },
-    store::{restore_snapshot, version::IndexerStoreVersion, IndexerStore},
+    store::{
+        snapshot::{restore_snapshot, verify_snapshot},
+        version::IndexerStoreVersion,
+        IndexerStore,
+    },
     unix_socket_server::remove_unix_socket,
-    web::start_web_server,
+    web::{
+        graphql::{
+            build_schema,
+            limits::{
+                GraphQLLimits, DEFAULT_MAX_COMPLEXITY, DEFAULT_MAX_DEPTH, DEFAULT_MAX_LIST_LEN,
+                DEFAULT_TIMEOUT_SECS,
+            },
+        },
+        rosetta::broadcast::{Broadcaster, DaemonBroadcaster},
+        start_web_server,
+    },
+    webhook::delivery::WebhookDispatcher,
 };
 use std::{
     fs::{self, File},
-    io::Write,
+    io::{BufReader, BufWriter, Write},
     path::{Path, PathBuf},
     process,
     str::FromStr,
@@ -39,6 +58,14 @@ struct Cli {
     /// Path to the Unix domain socket file
     #[arg(long, default_value = "./mina-indexer.sock", num_args = 1)]
     socket: PathBuf,
+
+    /// Name or genesis state hash of the network client commands query
+    /// [default: the server's primary network]
+    #[arg(long, num_args = 1)]
+    network: Option<String>,
+
+    #[command(flatten)]
+    output: client::FormatArgs,
 }
 
 #[derive(Subcommand, Debug)]
@@ -58,7 +85,7 @@ enum IndexerCommand {
 
     /// Client commands
     #[clap(flatten)]
-    Client(#[command(subcommand)] client::ClientCli),
+    Client(client::ClientCli),
 
     /// Mina indexer version
     Version,
@@ -79,18 +106,10 @@ enum DatabaseCommand {
     Create(Box<DatabaseArgs>),
 
     /// Create a snapshot of a mina indexer database
-    Snapshot {
-        /// Full path to the snapshot file to be created
-        #[arg(long, default_value = "./snapshot")]
-        output_path: PathBuf,
-
-        /// Full path to a mina indexer database directory.
-        /// If null, snapshot a running indexer database.
-        #[arg(long)]
-        database_dir: Option<PathBuf>,
-    },
+    Snapshot(SnapshotArgs),
 
-    /// Restore an indexer database from an archived snapshot file
+    /// Restore an indexer database from an archived snapshot file.
+    /// Incremental snapshots are restored from their chain of base snapshots.
     Restore {
         /// Full path to the archive snapshot file
         #[arg(long, default_value = "./snapshot")]
@@ -101,6 +120,28 @@ enum DatabaseCommand {
         restore_dir: PathBuf,
     },
 
+    /// Export an indexer database as versioned NDJSON
+    Export {
+        /// Full path to a mina indexer database directory
+        #[arg(long)]
+        database_dir: PathBuf,
+
+        /// Full path to the NDJSON export file (default: stdout)
+        #[arg(long)]
+        output_path: Option<PathBuf>,
+    },
+
+    /// Import an NDJSON export into a new indexer database
+    Import {
+        /// Full path to the NDJSON export file
+        #[arg(long)]
+        input_path: PathBuf,
+
+        /// Full path to the new database directory
+        #[arg(long)]
+        database_dir: PathBuf,
+    },
+
     /// Query mina indexer database version
     Version {
         /// Output JSON data
@@ -109,6 +150,41 @@ enum DatabaseCommand {
     },
 }
 
+#[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+struct SnapshotArgs {
+    #[command(subcommand)]
+    command: Option<SnapshotCommand>,
+
+    /// Full path to the snapshot file to be created
+    #[arg(long, default_value = "./snapshot")]
+    output_path: PathBuf,
+
+    /// Full path to a mina indexer database directory.
+    /// If null, snapshot a running indexer database.
+    #[arg(long)]
+    database_dir: Option<PathBuf>,
+
+    /// Full path to the base snapshot file of an incremental snapshot.
+    /// Must be in the same directory as the new snapshot.
+    #[arg(long)]
+    base_snapshot: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum SnapshotCommand {
+    /// Verify a snapshot & its base snapshots without restoring it
+    Verify {
+        /// Full path to the archive snapshot file
+        #[arg(long, default_value = "./snapshot")]
+        snapshot_file: PathBuf,
+
+        /// Output JSON manifest
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 #[derive(Parser, Debug, Clone, Default)]
 #[command(author, version, about, long_about = None)]
 pub struct ServerArgs {
@@ -123,6 +199,32 @@ pub struct ServerArgs {
     #[arg(long, default_value_t = DEFAULT_WEB_PORT)]
     web_port: u16,
 
+    /// Max nesting depth of GraphQL queries
+    #[arg(long, default_value_t = DEFAULT_MAX_DEPTH)]
+    graphql_max_depth: usize,
+
+    /// Max computed cost of GraphQL queries
+    #[arg(long, default_value_t = DEFAULT_MAX_COMPLEXITY)]
+    graphql_max_complexity: usize,
+
+    /// Max number of results of each GraphQL list (larger limits are capped)
+    #[arg(long, default_value_t = DEFAULT_MAX_LIST_LEN)]
+    graphql_max_list_len: usize,
+
+    /// Timeout (sec) for executing GraphQL queries
+    #[arg(long, default_value_t = DEFAULT_TIMEOUT_SECS)]
+    graphql_timeout: u64,
+
+    /// Daemon GraphQL URL that Rosetta `/construction/submit` sends signed
+    /// transactions to
+    #[arg(long)]
+    rosetta_broadcast_url: Option<String>,
+
+    /// Max number of slots the best tip may trail the wall-clock slot by for
+    /// `/ready` to succeed
+    #[arg(long, default_value_t = DEFAULT_MAX_SLOT_LAG)]
+    ready_max_slot_lag: u32,
+
     /// Start with data consistency checks
     #[arg(long, default_value_t = false)]
     self_check: bool,
@@ -147,6 +249,11 @@ pub struct ServerArgs {
     #[arg(long)]
     missing_block_recovery_batch: Option<bool>,
 
+    /// Path to the config (JSON) of an additional network to index side by
+    /// side in the same database. May be repeated
+    #[arg(long = "additional-network", value_name = "FILE")]
+    additional_networks: Vec<PathBuf>,
+
     /// Indexer process ID
     #[arg(last = true)]
     pid: Option<u32>,
@@ -209,6 +316,12 @@ pub struct DatabaseArgs {
     #[arg(long, default_value_t = CANONICAL_UPDATE_THRESHOLD)]
     canonical_update_threshold: u32,
 
+    /// Number of epochs before the canonical root's epoch to retain full
+    /// blocks for. Older blocks are compacted & re-hydrated from the blocks
+    /// dir on demand (default: retain all full blocks)
+    #[arg(long)]
+    retain_full_blocks_epochs: Option<u32>,
+
     /// Start from a config file (bypasses other args)
     #[arg(long)]
     config: Option<PathBuf>,
@@ -231,16 +344,30 @@ impl ServerArgs {
         self.pid = Some(pid);
         self
     }
+
+    fn graphql_limits(&self) -> GraphQLLimits {
+        GraphQLLimits {
+            max_depth: self.graphql_max_depth,
+            max_complexity: self.graphql_max_complexity,
+            max_list_len: self.graphql_max_list_len,
+            timeout: Duration::from_secs(self.graphql_timeout),
+        }
+    }
 }
 
 #[tokio::main]
 pub async fn main() -> anyhow::Result<()> {
     let args = Cli::parse();
     let domain_socket_path = args.socket;
-    Toplevel::new(|s| async move {
-        s.start(SubsystemBuilder::new("Main", |s| async move {
+    let format = args.output.format;
+    let network = args.network;
+    Toplevel::new(move |s| async move {
+        s.start(SubsystemBuilder::new("Main", move |s| async move {
             match args.command {
-                IndexerCommand::Client(cli) => cli.run(domain_socket_path).await,
+                IndexerCommand::Client(cli) => {
+                    cli.run_with_format(domain_socket_path, format, network)
+                        .await
+                }
                 IndexerCommand::Database { db_command } => db_command.run(domain_socket_path).await,
                 IndexerCommand::Server { server_command } => {
                     server_command.run(s, domain_socket_path).await
@@ -275,6 +402,11 @@ impl ServerCommand {
         let database_dir = args.db.database_dir.clone();
         let web_hostname = args.web_hostname.clone();
         let web_port = args.web_port;
+        let graphql_limits = args.graphql_limits();
+        let broadcaster = args
+            .rosetta_broadcast_url
+            .clone()
+            .map(|url| Arc::new(DaemonBroadcaster::new(url)) as Arc<dyn Broadcaster>);
 
         // initialize logging
         stderrlog::new()
@@ -288,19 +420,103 @@ impl ServerCommand {
         check_or_write_pid_file(&database_dir);
 
         debug!("Building mina indexer configuration");
-        let config = process_indexer_configuration(args, mode, domain_socket_path.clone())?;
-        let db = Arc::new(IndexerStore::new(&database_dir)?);
+        let ready_max_slot_lag = args.ready_max_slot_lag;
+        let mut configs = vec![process_indexer_configuration(
+            args.clone(),
+            mode.clone(),
+            domain_socket_path.clone(),
+        )?];
+        for path in &args.additional_networks {
+            configs.push(additional_network_configuration(
+                &args,
+                path,
+                mode.clone(),
+                domain_socket_path.clone(),
+            )?);
+        }
+        check_distinct_networks(&configs)?;
 
-        info!("Starting the mina indexer filesystem watchers & UDS server");
-        let store = db.clone();
-        subsys.start(SubsystemBuilder::new("Indexer", move |s| {
-            start_indexer(s, config, store)
-        }));
+        // additional networks are namespaced by their genesis state hash
+        let namespaces: Vec<_> = configs[1..]
+            .iter()
+            .map(|config| config.genesis_hash.clone())
+            .collect();
+        let db = Arc::new(IndexerStore::new_with_networks(&database_dir, &namespaces)?);
+
+        let mut networks: Option<IndexedNetworks> = None;
+        for config in &configs {
+            let store = match config.genesis_hash == configs[0].genesis_hash {
+                true => db.clone(),
+                false => Arc::new(db.network(&config.genesis_hash)?),
+            };
+
+            // newly recorded events are broadcast to GraphQL subscriptions
+            let events = event_channel();
+
+            // all networks are served by the primary network's UDS server
+            let health = IndexerHealth::new(
+                (&config.initialization_mode).into(),
+                &config.genesis_constants,
+                ready_max_slot_lag,
+            );
+            let health = match &networks {
+                None => health,
+                Some(networks) => health.with_uds_server_of(&networks.primary().health),
+            };
+
+            // GraphQL queries are also served over the UDS
+            let network = IndexedNetwork {
+                name: config.network_name.clone(),
+                genesis_state_hash: config.genesis_hash.clone(),
+                schema: build_schema(store.clone(), events.clone(), graphql_limits),
+                health: Arc::new(health),
+                store,
+                events,
+                state: None,
+            };
+            match &networks {
+                None => networks = Some(IndexedNetworks::new(network)),
+                Some(networks) => networks.add(network),
+            }
+        }
+        let networks = networks.expect("primary network");
+        let genesis_constants = configs[0].genesis_constants.clone();
+
+        for (n, config) in configs.into_iter().enumerate() {
+            let suffix = match n {
+                0 => String::new(),
+                _ => format!(" {}", config.network_name),
+            };
+            let network = networks.get(Some(&config.network_name))?;
+
+            info!(
+                "Starting the {} mina indexer filesystem watchers",
+                config.network_name
+            );
+            let indexer_networks = networks.clone();
+            subsys.start(SubsystemBuilder::new(
+                format!("Indexer{suffix}"),
+                move |s| start_indexer(s, config, indexer_networks),
+            ));
+
+            info!("Starting the {} webhook dispatcher", network.name);
+            let receiver = network.events.subscribe();
+            subsys.start(SubsystemBuilder::new(
+                format!("Webhooks{suffix}"),
+                move |s| WebhookDispatcher::new(network.store).run(s, receiver),
+            ));
+        }
 
         info!("Starting the web server listening on {web_hostname}:{web_port}");
-        let store = db.clone();
         subsys.start(SubsystemBuilder::new("Web Server", move |s| {
-            start_web_server(s, store, (web_hostname, web_port))
+            start_web_server(
+                s,
+                networks,
+                genesis_constants,
+                graphql_limits,
+                broadcaster,
+                (web_hostname, web_port),
+            )
         }));
 
         subsys.on_shutdown_requested().await;
@@ -336,26 +552,62 @@ impl DatabaseCommand {
                     }
                 )
             }
-            Self::Snapshot {
-                output_path,
-                database_dir,
-            } => {
-                if let Some(database_dir) = database_dir {
-                    if !database_dir.exists() {
-                        error!("Database dir {database_dir:#?} does not exist");
-                    } else {
-                        info!("Creating snapshot of database dir {database_dir:#?}");
-                        let tmp_dir = TempDir::new()?;
-                        let db = IndexerStore::read_only(&database_dir, tmp_dir.as_ref())?;
-                        db.create_snapshot(&output_path)?;
+            Self::Snapshot(args) => match args.command {
+                Some(SnapshotCommand::Verify {
+                    snapshot_file,
+                    json,
+                }) => {
+                    info!("Verifying snapshot file {snapshot_file:#?}");
+                    match verify_snapshot(&snapshot_file) {
+                        Ok(manifest) if json => {
+                            println!("{}", serde_json::to_string_pretty(&manifest)?)
+                        }
+                        Ok(manifest) => println!(
+                            "Snapshot {snapshot_file:#?} is valid: db version {}, best tip height {}, {} files ({} archived{})",
+                            manifest.db_version,
+                            manifest
+                                .best_tip_height
+                                .map_or("unknown".to_string(), |height| height.to_string()),
+                            manifest.files.len(),
+                            manifest.archived_files().count(),
+                            manifest
+                                .base
+                                .as_ref()
+                                .map_or(String::new(), |base| format!(", base {}", base.file_name)),
+                        ),
+                        Err(e) => {
+                            error!("Invalid snapshot {snapshot_file:#?}: {e:#}");
+                            process::exit(1);
+                        }
                     }
-                } else {
-                    info!("Creating snapshot of running mina indexer");
-                    return client::ClientCli::CreateSnapshot { output_path }
+                }
+                None => {
+                    let SnapshotArgs {
+                        output_path,
+                        database_dir,
+                        base_snapshot,
+                        ..
+                    } = args;
+                    if let Some(database_dir) = database_dir {
+                        if !database_dir.exists() {
+                            error!("Database dir {database_dir:#?} does not exist");
+                        } else {
+                            info!("Creating snapshot of database dir {database_dir:#?}");
+                            let tmp_dir = TempDir::new()?;
+                            let db = IndexerStore::read_only(&database_dir, tmp_dir.as_ref())?;
+                            db.create_snapshot(&output_path, base_snapshot.as_deref())?;
+                        }
+                    } else {
+                        info!("Creating snapshot of running mina indexer");
+                        return client::ClientCli::CreateSnapshot {
+                            output_path,
+                            base_snapshot,
+                        }
                         .run(domain_socket_path)
                         .await;
+                    }
                 }
-            }
+            },
             Self::Restore {
                 snapshot_file,
                 restore_dir,
@@ -363,6 +615,50 @@ impl DatabaseCommand {
                 info!("Restoring mina indexer database from snapshot file {snapshot_file:#?} to {restore_dir:#?}");
                 restore_snapshot(&snapshot_file, &restore_dir).unwrap_or_else(|e| error!("{e}"))
             }
+            Self::Export {
+                database_dir,
+                output_path,
+            } => {
+                if !database_dir.exists() {
+                    error!("Database dir {database_dir:#?} does not exist");
+                    process::exit(1);
+                }
+
+                info!("Exporting database dir {database_dir:#?}");
+                let tmp_dir = TempDir::new()?;
+                let db = IndexerStore::read_only(&database_dir, tmp_dir.as_ref())?;
+                let summary = match output_path {
+                    Some(output_path) => {
+                        let mut writer = BufWriter::new(File::create(output_path)?);
+                        db.export_ndjson(&mut writer)?
+                    }
+                    None => db.export_ndjson(&mut BufWriter::new(std::io::stdout().lock()))?,
+                };
+                info!("Export complete: {summary:?}");
+            }
+            Self::Import {
+                input_path,
+                database_dir,
+            } => {
+                if !input_path.exists() {
+                    error!("Export file {input_path:#?} does not exist");
+                    process::exit(1);
+                }
+                if database_dir.exists() {
+                    error!("Database dir {database_dir:#?} must not exist");
+                    process::exit(1);
+                }
+
+                info!("Importing {input_path:#?} into database dir {database_dir:#?}");
+                let db = IndexerStore::new(&database_dir)?;
+                match db.import_ndjson(BufReader::new(File::open(&input_path)?)) {
+                    Ok(summary) => info!("Import complete: {summary:?}"),
+                    Err(e) => {
+                        error!("Import failed: {e:#}");
+                        process::exit(1);
+                    }
+                }
+            }
             Self::Create(args) => {
                 let database_dir = args.database_dir.clone();
                 debug!("Ensuring mina indexer database exists in {database_dir:#?}");
@@ -417,6 +713,7 @@ fn process_indexer_configuration(
     let prune_interval = args.db.prune_interval;
     let canonical_threshold = args.db.canonical_threshold;
     let canonical_update_threshold = args.db.canonical_update_threshold;
+    let retain_full_blocks_epochs = args.db.retain_full_blocks_epochs;
     let ledger_cadence = args.db.ledger_cadence;
     let reporting_freq = args.db.reporting_freq;
     let fetch_new_blocks_exe = args.fetch_new_blocks_exe;
@@ -459,6 +756,8 @@ fn process_indexer_configuration(
 
     let genesis_ledger = parse_genesis_ledger(args.db.genesis_ledger)?;
     Ok(IndexerConfiguration {
+        network_name: args.db.network.to_string(),
+        network: args.db.network,
         genesis_ledger,
         genesis_hash,
         genesis_constants,
@@ -469,6 +768,7 @@ fn process_indexer_configuration(
         prune_interval,
         canonical_threshold,
         canonical_update_threshold,
+        retain_full_blocks_epochs,
         initialization_mode: mode,
         ledger_cadence,
         reporting_freq,
@@ -481,6 +781,56 @@ fn process_indexer_configuration(
     })
 }
 
+/// Config of an additional network, the primary network's args with the
+/// network's genesis & block sources
+fn additional_network_configuration(
+    args: &ServerArgs,
+    path: &Path,
+    mode: InitializationMode,
+    domain_socket_path: PathBuf,
+) -> anyhow::Result<IndexerConfiguration> {
+    info!("Parsing additional network config at {path:#?}");
+    let network: AdditionalNetworkJson = serde_json::from_slice(&fs::read(path)?)
+        .with_context(|| format!("Invalid additional network config {path:#?}"))?;
+    let mut args = args.clone();
+    args.db.network = (&network.network as &str).into();
+    args.db.genesis_hash = network.genesis_hash;
+    args.db.genesis_ledger = network.genesis_ledger.map(Into::into);
+    args.db.genesis_constants = network.genesis_constants.map(Into::into);
+    args.db.constraint_system_digests = network.constraint_system_digests;
+    args.db.blocks_dir = network.blocks_dir.map(Into::into);
+    args.db.staking_ledgers_dir = network.staking_ledgers_dir.map(Into::into);
+
+    // block fetching & recovery are the primary network's
+    args.fetch_new_blocks_exe = None;
+    args.missing_block_recovery_exe = None;
+
+    let mut config = process_indexer_configuration(args, mode, domain_socket_path)?;
+    if let Some(name) = network.name {
+        config.network_name = name;
+    }
+    Ok(config)
+}
+
+/// Queries select the network by name or genesis state hash, both must be
+/// distinct
+fn check_distinct_networks(configs: &[IndexerConfiguration]) -> anyhow::Result<()> {
+    for (n, config) in configs.iter().enumerate() {
+        for other in &configs[..n] {
+            if config.network_name == other.network_name {
+                bail!("Duplicate network name {}", config.network_name);
+            }
+            if config.genesis_hash == other.genesis_hash {
+                bail!(
+                    "Duplicate network genesis state hash {}",
+                    config.genesis_hash
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
 fn parse_genesis_ledger(path: Option<PathBuf>) -> anyhow::Result<GenesisLedger> {
     let genesis_ledger = if let Some(path) = path {
         assert!(path.is_file(), "Ledger file does not exist at {path:#?}");
@@ -604,8 +954,15 @@ struct ServerArgsJson {
     prune_interval: u32,
     canonical_threshold: u32,
     canonical_update_threshold: u32,
+    retain_full_blocks_epochs: Option<u32>,
     web_hostname: String,
     web_port: u16,
+    graphql_max_depth: Option<usize>,
+    graphql_max_complexity: Option<usize>,
+    graphql_max_list_len: Option<usize>,
+    graphql_timeout: Option<u64>,
+    rosetta_broadcast_url: Option<String>,
+    ready_max_slot_lag: Option<u32>,
     pid: Option<u32>,
     fetch_new_blocks_exe: Option<String>,
     fetch_new_blocks_delay: Option<u64>,
@@ -613,6 +970,23 @@ struct ServerArgsJson {
     missing_block_recovery_delay: Option<u64>,
     missing_block_recovery_batch: Option<bool>,
     network: String,
+
+    #[serde(default)]
+    additional_networks: Vec<String>,
+}
+
+/// Config of an additional network, see [ServerArgs::additional_networks]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct AdditionalNetworkJson {
+    /// Name queries select the network by [default: `network`]
+    name: Option<String>,
+    network: String,
+    genesis_hash: String,
+    genesis_ledger: Option<String>,
+    genesis_constants: Option<String>,
+    constraint_system_digests: Option<Vec<String>>,
+    blocks_dir: Option<String>,
+    staking_ledgers_dir: Option<String>,
 }
 
 impl From<ServerArgs> for ServerArgsJson {
@@ -639,8 +1013,15 @@ impl From<ServerArgs> for ServerArgsJson {
             prune_interval: value.db.prune_interval,
             canonical_threshold: value.db.canonical_threshold,
             canonical_update_threshold: value.db.canonical_update_threshold,
+            retain_full_blocks_epochs: value.db.retain_full_blocks_epochs,
             web_hostname: value.web_hostname,
             web_port: value.web_port,
+            graphql_max_depth: Some(value.graphql_max_depth),
+            graphql_max_complexity: Some(value.graphql_max_complexity),
+            graphql_max_list_len: Some(value.graphql_max_list_len),
+            graphql_timeout: Some(value.graphql_timeout),
+            rosetta_broadcast_url: value.rosetta_broadcast_url,
+            ready_max_slot_lag: Some(value.ready_max_slot_lag),
             pid: value.pid,
             fetch_new_blocks_delay: value.fetch_new_blocks_delay,
             fetch_new_blocks_exe: value.fetch_new_blocks_exe.map(|p| p.display().to_string()),
@@ -650,6 +1031,11 @@ impl From<ServerArgs> for ServerArgsJson {
                 .map(|p| p.display().to_string()),
             missing_block_recovery_batch: value.missing_block_recovery_batch,
             network: value.db.network.to_string(),
+            additional_networks: value
+                .additional_networks
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect(),
         }
     }
 }
@@ -670,6 +1056,7 @@ impl From<ServerArgsJson> for ServerArgs {
             prune_interval: value.prune_interval,
             canonical_threshold: value.canonical_threshold,
             canonical_update_threshold: value.canonical_update_threshold,
+            retain_full_blocks_epochs: value.retain_full_blocks_epochs,
             config: None,
             network: (&value.network as &str).into(),
         };
@@ -677,6 +1064,14 @@ impl From<ServerArgsJson> for ServerArgs {
             db,
             web_hostname: value.web_hostname,
             web_port: value.web_port,
+            graphql_max_depth: value.graphql_max_depth.unwrap_or(DEFAULT_MAX_DEPTH),
+            graphql_max_complexity: value
+                .graphql_max_complexity
+                .unwrap_or(DEFAULT_MAX_COMPLEXITY),
+            graphql_max_list_len: value.graphql_max_list_len.unwrap_or(DEFAULT_MAX_LIST_LEN),
+            graphql_timeout: value.graphql_timeout.unwrap_or(DEFAULT_TIMEOUT_SECS),
+            rosetta_broadcast_url: value.rosetta_broadcast_url,
+            ready_max_slot_lag: value.ready_max_slot_lag.unwrap_or(DEFAULT_MAX_SLOT_LAG),
             self_check: false,
             pid: value.pid,
             fetch_new_blocks_delay: value.fetch_new_blocks_delay,
@@ -684,6 +1079,11 @@ impl From<ServerArgsJson> for ServerArgs {
             missing_block_recovery_delay: value.missing_block_recovery_delay,
             missing_block_recovery_exe: value.missing_block_recovery_exe.map(|p| p.into()),
             missing_block_recovery_batch: value.missing_block_recovery_batch,
+            additional_networks: value
+                .additional_networks
+                .into_iter()
+                .map(Into::into)
+                .collect(),
         }
     }
 }
@@ -694,6 +1094,11 @@ impl From<DatabaseArgs> for ServerArgs {
             db: value,
             web_hostname: DEFAULT_WEB_HOSTNAME.to_string(),
             web_port: DEFAULT_WEB_PORT,
+            graphql_max_depth: DEFAULT_MAX_DEPTH,
+            graphql_max_complexity: DEFAULT_MAX_COMPLEXITY,
+            graphql_max_list_len: DEFAULT_MAX_LIST_LEN,
+            graphql_timeout: DEFAULT_TIMEOUT_SECS,
+            ready_max_slot_lag: DEFAULT_MAX_SLOT_LAG,
             ..Default::default()
         }
     }
@@ -5,6 +5,7 @@ pub mod genesis;
 pub mod parser;
 pub mod precomputed;
 pub mod previous_state_hash;
+pub mod pruned;
 pub mod store;
 pub mod vrf_output;
 
@@ -40,6 +41,15 @@ pub struct BlockWithoutHeight {
     pub state_hash: BlockHash,
     pub blockchain_length: u32,
     pub global_slot_since_genesis: u32,
+
+    /// Number of blocks on top of the block in the best chain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmations: Option<u32>,
+
+    /// Whether the block has at least k confirmations or is at or below the
+    /// canonical root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_final: Option<bool>,
 }
 
 #[derive(Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize)]
@@ -99,6 +109,8 @@ impl From<Block> for BlockWithoutHeight {
             state_hash: value.state_hash.clone(),
             global_slot_since_genesis: value.global_slot_since_genesis,
             blockchain_length: value.blockchain_length,
+            confirmations: None,
+            is_final: None,
         }
     }
 }
@@ -111,6 +123,8 @@ impl BlockWithoutHeight {
             parent_hash: precomputed_block.previous_state_hash(),
             blockchain_length: precomputed_block.blockchain_length(),
             global_slot_since_genesis: precomputed_block.global_slot_since_genesis(),
+            confirmations: None,
+            is_final: None,
         }
     }
 
@@ -122,6 +136,16 @@ impl BlockWithoutHeight {
             parent_hash: block.parent_hash,
             blockchain_length: block.blockchain_length,
             global_slot_since_genesis: block.global_slot_since_genesis,
+            confirmations: None,
+            is_final: None,
+        }
+    }
+
+    pub fn with_confirmations(self, confirmations: Option<u32>, is_final: bool) -> Self {
+        Self {
+            confirmations,
+            is_final: Some(is_final),
+            ..self
         }
     }
 
@@ -173,6 +197,8 @@ impl From<&PrecomputedBlock> for BlockWithoutHeight {
             state_hash: value.state_hash(),
             blockchain_length: value.blockchain_length(),
             global_slot_since_genesis: value.global_slot_since_genesis(),
+            confirmations: None,
+            is_final: None,
         }
     }
 }
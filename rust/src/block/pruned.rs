@@ -0,0 +1,127 @@
+//! Compact representation of a precomputed block whose full contents have
+//! been pruned from the store
+
+use super::{
+    precomputed::{PcbVersion, PrecomputedBlock},
+    BlockHash, BlockWithoutHeight,
+};
+use crate::{
+    chain::Network,
+    command::{
+        internal::{InternalCommand, InternalCommandWithData},
+        signed::SignedCommandWithData,
+    },
+    ledger::{diff::LedgerDiff, public_key::PublicKey, LedgerHash},
+    snark_work::SnarkWorkSummary,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Header, commands, SNARK summaries & ledger diff of a block.
+/// The full precomputed block can be re-hydrated from the blocks dir.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrunedBlock {
+    // header
+    pub network: Network,
+    pub version: PcbVersion,
+    pub state_hash: BlockHash,
+    pub previous_state_hash: BlockHash,
+    pub genesis_state_hash: BlockHash,
+    pub blockchain_length: u32,
+    pub global_slot_since_genesis: u32,
+    pub epoch: u32,
+    pub scheduled_time: String,
+    pub timestamp: u64,
+    pub creator: PublicKey,
+    pub coinbase_receiver: PublicKey,
+
+    /// Not kept by blocks pruned before it was added
+    #[serde(default)]
+    pub block_stake_winner: Option<PublicKey>,
+
+    /// Coinbase receiver whose account the coinbase created, not kept by
+    /// blocks pruned before it was added
+    #[serde(default)]
+    pub new_coinbase_receiver: Option<PublicKey>,
+    pub staged_ledger_hash: LedgerHash,
+    pub last_vrf_output: String,
+    pub num_block_bytes: u64,
+
+    // body
+    pub user_commands: Vec<SignedCommandWithData>,
+    pub internal_commands: Vec<InternalCommandWithData>,
+    pub snarks: Vec<SnarkWorkSummary>,
+    pub ledger_diff: LedgerDiff,
+}
+
+impl PrunedBlock {
+    pub fn from_precomputed(block: &PrecomputedBlock, num_block_bytes: u64) -> Self {
+        Self {
+            network: block.network(),
+            version: block.version(),
+            state_hash: block.state_hash(),
+            previous_state_hash: block.previous_state_hash(),
+            genesis_state_hash: block.genesis_state_hash(),
+            blockchain_length: block.blockchain_length(),
+            global_slot_since_genesis: block.global_slot_since_genesis(),
+            epoch: block.epoch_count(),
+            scheduled_time: block.scheduled_time(),
+            timestamp: block.timestamp(),
+            creator: block.block_creator(),
+            coinbase_receiver: block.coinbase_receiver(),
+            block_stake_winner: Some(block.block_stake_winner()),
+            new_coinbase_receiver: block.accounts_created().1,
+            staged_ledger_hash: block.staged_ledger_hash(),
+            last_vrf_output: block.last_vrf_output(),
+            num_block_bytes,
+            user_commands: SignedCommandWithData::from_precomputed(block),
+            internal_commands: InternalCommand::from_precomputed(block)
+                .into_iter()
+                .map(|cmd| InternalCommandWithData::from_internal_cmd(cmd, block))
+                .collect(),
+            snarks: SnarkWorkSummary::from_precomputed(block),
+            ledger_diff: LedgerDiff::from_precomputed(block),
+        }
+    }
+
+    /// Name of the precomputed block file, i.e.
+    /// `{network}-{blockchain_length}-{state_hash}.json`
+    pub fn file_name(&self) -> String {
+        format!(
+            "{}-{}-{}.json",
+            self.network, self.blockchain_length, self.state_hash.0
+        )
+    }
+
+    /// Path of the precomputed block file in `blocks_dir`
+    pub fn file_path(&self, blocks_dir: &Path) -> PathBuf {
+        blocks_dir.join(self.file_name())
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} (length {}): {}",
+            self.network, self.blockchain_length, self.state_hash
+        )
+    }
+}
+
+impl From<&PrunedBlock> for BlockWithoutHeight {
+    fn from(value: &PrunedBlock) -> Self {
+        Self {
+            canonicity: None,
+            parent_hash: value.previous_state_hash.clone(),
+            state_hash: value.state_hash.clone(),
+            blockchain_length: value.blockchain_length,
+            global_slot_since_genesis: value.global_slot_since_genesis,
+            confirmations: None,
+            is_final: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for PrunedBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.summary())
+    }
+}
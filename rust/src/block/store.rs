@@ -1,10 +1,11 @@
-use super::{precomputed::PcbVersion, BlockComparison};
+use super::{precomputed::PcbVersion, pruned::PrunedBlock, BlockComparison};
 use crate::{
     block::{precomputed::PrecomputedBlock, BlockHash},
     event::db::DbEvent,
     ledger::public_key::PublicKey,
 };
 use speedb::{DBIterator, IteratorMode};
+use std::path::{Path, PathBuf};
 
 pub trait BlockStore {
     /// Add block to the store
@@ -15,8 +16,43 @@ pub trait BlockStore {
     ) -> anyhow::Result<Option<DbEvent>>;
 
     /// Get block from the store
+    ///
+    /// Pruned blocks are re-hydrated from the blocks dir on demand
     fn get_block(&self, state_hash: &BlockHash) -> anyhow::Result<Option<(PrecomputedBlock, u64)>>;
 
+    /////////////////////////////
+    // Block pruning functions //
+    /////////////////////////////
+
+    /// Set the blocks dir used to re-hydrate pruned blocks
+    fn set_blocks_dir(&self, blocks_dir: &Path) -> anyhow::Result<()>;
+
+    /// Get the blocks dir used to re-hydrate pruned blocks
+    fn get_blocks_dir(&self) -> anyhow::Result<Option<PathBuf>>;
+
+    /// Compact the full block to a [PrunedBlock]
+    ///
+    /// Returns `true` if the block was pruned
+    fn prune_block(&self, state_hash: &BlockHash) -> anyhow::Result<bool>;
+
+    /// Prune all blocks strictly below `max_height` from epochs strictly
+    /// less than `epoch`
+    ///
+    /// Returns the number of blocks pruned
+    fn prune_blocks(&self, epoch: u32, max_height: u32) -> anyhow::Result<u32>;
+
+    /// Check whether the block has been pruned
+    fn is_block_pruned(&self, state_hash: &BlockHash) -> anyhow::Result<bool>;
+
+    /// Get the compact version of a pruned block
+    fn get_pruned_block(&self, state_hash: &BlockHash) -> anyhow::Result<Option<PrunedBlock>>;
+
+    /// Re-hydrate a pruned block from its precomputed block file
+    fn rehydrate_block(
+        &self,
+        state_hash: &BlockHash,
+    ) -> anyhow::Result<Option<(PrecomputedBlock, u64)>>;
+
     //////////////////////////
     // Best block functions //
     //////////////////////////
@@ -146,6 +182,14 @@ pub trait BlockStore {
     /// Get children of a block
     fn get_block_children(&self, state_hash: &BlockHash) -> anyhow::Result<Vec<BlockHash>>;
 
+    /// Get the blocks of the chain ending at `previous` which are not
+    /// ancestors of `best_tip`, highest first (empty for an extension)
+    fn get_reorged_blocks(
+        &self,
+        previous: &BlockHash,
+        best_tip: &BlockHash,
+    ) -> anyhow::Result<Vec<BlockHash>>;
+
     /// Index block version
     fn set_block_version(&self, state_hash: &BlockHash, version: PcbVersion) -> anyhow::Result<()>;
 
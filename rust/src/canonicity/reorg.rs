@@ -0,0 +1,199 @@
+//! Records of best tip reorgs & their analytics
+
+use crate::{
+    block::BlockHash, command::signed::SignedCommandWithData, ledger::public_key::PublicKey,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// Best tip change which removed blocks from the best chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Reorg {
+    /// Sequence number of the reorg
+    pub index: u32,
+
+    #[schema(value_type = String)]
+    pub old_best_tip: BlockHash,
+    pub old_best_tip_height: u32,
+    #[schema(value_type = String)]
+    pub new_best_tip: BlockHash,
+    pub new_best_tip_height: u32,
+
+    /// Last block shared by the old & new best chains
+    #[schema(value_type = String)]
+    pub common_ancestor: BlockHash,
+    pub common_ancestor_height: u32,
+
+    /// Number of blocks removed from the best chain
+    pub depth: u32,
+
+    /// Epoch of the new best tip
+    pub epoch: u32,
+
+    /// Blocks removed from the best chain, highest first
+    pub uncanonicalized: Vec<ReorgBlock>,
+
+    /// Blocks added to the best chain, highest first
+    pub recanonicalized: Vec<ReorgBlock>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ReorgBlock {
+    #[schema(value_type = String)]
+    pub state_hash: BlockHash,
+    pub blockchain_length: u32,
+    pub global_slot: u32,
+
+    /// User commands of the block, empty if the block was unavailable
+    pub user_commands: Vec<ReorgUserCommand>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ReorgUserCommand {
+    pub txn_hash: String,
+    #[schema(value_type = String)]
+    pub sender: PublicKey,
+    #[schema(value_type = String)]
+    pub receiver: PublicKey,
+    pub amount: u64,
+    pub fee: u64,
+    pub nonce: u32,
+    pub is_applied: bool,
+}
+
+/// Selects reorg records, all set fields must match
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReorgFilter {
+    pub min_depth: Option<u32>,
+    pub epoch: Option<u32>,
+
+    /// Block which was uncanonicalized or recanonicalized
+    pub state_hash: Option<BlockHash>,
+
+    /// User command which was uncanonicalized or recanonicalized
+    pub txn_hash: Option<String>,
+
+    /// Sender or receiver of an uncanonicalized or recanonicalized user
+    /// command
+    pub public_key: Option<PublicKey>,
+}
+
+/// Summary of all recorded reorgs
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ReorgStats {
+    pub num_reorgs: u32,
+    pub max_depth: u32,
+
+    /// Total number of blocks removed from the best chain
+    pub num_uncanonicalized_blocks: u32,
+
+    /// Total number of user commands removed from the best chain
+    pub num_uncanonicalized_user_commands: u32,
+
+    /// Per epoch stats, in ascending epoch order
+    pub epochs: Vec<EpochReorgStats>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct EpochReorgStats {
+    pub epoch: u32,
+    pub num_reorgs: u32,
+    pub max_depth: u32,
+    pub num_uncanonicalized_blocks: u32,
+    pub num_uncanonicalized_user_commands: u32,
+}
+
+impl Reorg {
+    /// Blocks removed from or added to the best chain
+    pub fn blocks(&self) -> impl Iterator<Item = &ReorgBlock> {
+        self.uncanonicalized
+            .iter()
+            .chain(self.recanonicalized.iter())
+    }
+
+    /// Number of user commands removed from the best chain
+    pub fn num_uncanonicalized_user_commands(&self) -> u32 {
+        self.uncanonicalized
+            .iter()
+            .map(|block| block.user_commands.len() as u32)
+            .sum()
+    }
+}
+
+impl From<SignedCommandWithData> for ReorgUserCommand {
+    fn from(value: SignedCommandWithData) -> Self {
+        Self {
+            sender: value.command.source_pk(),
+            receiver: value.command.receiver_pk(),
+            amount: value.command.amount(),
+            fee: value.command.fee(),
+            nonce: value.nonce.0,
+            is_applied: value.status.is_applied(),
+            txn_hash: value.tx_hash,
+        }
+    }
+}
+
+impl ReorgFilter {
+    pub fn matches(&self, reorg: &Reorg) -> bool {
+        if self
+            .min_depth
+            .is_some_and(|min_depth| reorg.depth < min_depth)
+        {
+            return false;
+        }
+        if self.epoch.is_some_and(|epoch| reorg.epoch != epoch) {
+            return false;
+        }
+        if let Some(state_hash) = self.state_hash.as_ref() {
+            if !reorg.blocks().any(|block| block.state_hash == *state_hash) {
+                return false;
+            }
+        }
+        if let Some(txn_hash) = self.txn_hash.as_ref() {
+            if !reorg
+                .blocks()
+                .flat_map(|block| block.user_commands.iter())
+                .any(|cmd| cmd.txn_hash == *txn_hash)
+            {
+                return false;
+            }
+        }
+        if let Some(pk) = self.public_key.as_ref() {
+            if !reorg
+                .blocks()
+                .flat_map(|block| block.user_commands.iter())
+                .any(|cmd| cmd.sender == *pk || cmd.receiver == *pk)
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl ReorgStats {
+    pub fn new<'a>(reorgs: impl IntoIterator<Item = &'a Reorg>) -> Self {
+        let mut stats = Self::default();
+        let mut epochs: BTreeMap<u32, EpochReorgStats> = BTreeMap::new();
+        for reorg in reorgs {
+            let num_user_commands = reorg.num_uncanonicalized_user_commands();
+            stats.num_reorgs += 1;
+            stats.max_depth = stats.max_depth.max(reorg.depth);
+            stats.num_uncanonicalized_blocks += reorg.depth;
+            stats.num_uncanonicalized_user_commands += num_user_commands;
+
+            let epoch = epochs.entry(reorg.epoch).or_insert(EpochReorgStats {
+                epoch: reorg.epoch,
+                ..Default::default()
+            });
+            epoch.num_reorgs += 1;
+            epoch.max_depth = epoch.max_depth.max(reorg.depth);
+            epoch.num_uncanonicalized_blocks += reorg.depth;
+            epoch.num_uncanonicalized_user_commands += num_user_commands;
+        }
+        stats.epochs = epochs.into_values().collect();
+        stats
+    }
+}
@@ -1,6 +1,9 @@
 use crate::{
     block::BlockHash,
-    canonicity::{Canonicity, CanonicityUpdate},
+    canonicity::{
+        reorg::{Reorg, ReorgFilter, ReorgStats},
+        Canonicity, CanonicityUpdate,
+    },
 };
 
 pub trait CanonicityStore {
@@ -24,6 +27,25 @@ pub trait CanonicityStore {
         new_best_tip: &BlockHash,
     ) -> anyhow::Result<CanonicityUpdate>;
 
+    /// Record the reorg from the canonicity updates of a best tip change
+    ///
+    /// Returns `None` if no blocks were removed from the best chain
+    fn add_reorg(
+        &self,
+        old_best_tip: &BlockHash,
+        new_best_tip: &BlockHash,
+        updates: &CanonicityUpdate,
+    ) -> anyhow::Result<Option<Reorg>>;
+
+    /// Get the reorg record with the given index
+    fn get_reorg(&self, index: u32) -> anyhow::Result<Option<Reorg>>;
+
+    /// Get the most recent reorg records matching `filter`, newest first
+    fn get_reorgs(&self, filter: &ReorgFilter, limit: usize) -> anyhow::Result<Vec<Reorg>>;
+
+    /// Get the summary stats of all reorg records
+    fn get_reorg_stats(&self) -> anyhow::Result<ReorgStats>;
+
     /// Get the state hash of the canonical block at the given height
     fn get_canonical_hash_at_height(&self, height: u32) -> anyhow::Result<Option<BlockHash>>;
 
@@ -33,6 +55,22 @@ pub trait CanonicityStore {
     /// Get block canonicity
     fn get_block_canonicity(&self, state_hash: &BlockHash) -> anyhow::Result<Option<Canonicity>>;
 
+    /// Get the blockchain length of the canonical root, i.e. the highest
+    /// block added via [CanonicityStore::add_canonical_block]
+    fn get_max_canonical_blockchain_length(&self) -> anyhow::Result<Option<u32>>;
+
+    /// Get the number of blocks on top of the block in the best chain
+    ///
+    /// Returns `None` if the block isn't on the best chain
+    fn get_block_confirmations(&self, state_hash: &BlockHash) -> anyhow::Result<Option<u32>>;
+
+    /// Check whether the block is final, i.e. it's on the best chain & has at
+    /// least [MAINNET_TRANSITION_FRONTIER_K] confirmations or is at or below
+    /// the canonical root
+    ///
+    /// [MAINNET_TRANSITION_FRONTIER_K]: crate::constants::MAINNET_TRANSITION_FRONTIER_K
+    fn is_block_final(&self, state_hash: &BlockHash) -> anyhow::Result<bool>;
+
     /// Get the list of all known genesis state hashes
     fn get_known_genesis_state_hashes(&self) -> anyhow::Result<Vec<BlockHash>>;
 
@@ -1,16 +1,39 @@
-use crate::constants::MAINNET_GENESIS_HASH;
+use crate::{
+    constants::MAINNET_GENESIS_HASH,
+    rpc::{ClientError, RpcError},
+};
 use bincode::{config, Decode, Encode};
-use clap::{Parser, Subcommand};
-use std::{path::PathBuf, process};
-use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt, BufReader},
-    net::UnixStream,
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    Args, Parser, Subcommand,
+};
+use mina_indexer_client::{format::OutputFormat, IndexerClient};
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    process,
 };
 
 pub const BIN_CODE_CONFIG: config::Configuration = config::standard();
 pub const BUFFER_SIZE: usize = 1024;
 
-#[derive(Parser, Debug, Encode, Decode)]
+/// Output format of the client commands
+#[derive(Args, Debug)]
+pub struct FormatArgs {
+    /// Output format of client commands
+    #[arg(
+        long,
+        global = true,
+        default_value_t = OutputFormat::Json,
+        value_parser = PossibleValuesParser::new(OutputFormat::NAMES)
+            .map(|name| name.parse::<OutputFormat>().expect("possible value")),
+    )]
+    pub format: OutputFormat,
+}
+
+#[derive(Parser, Debug, Encode, Decode, Serialize)]
 #[command(author, version, about, long_about = None)]
 pub enum ClientCli {
     /// Query best ledger accounts
@@ -31,8 +54,19 @@ pub enum ClientCli {
         /// Full path to the new snapshot file
         #[arg(long, default_value = "./snapshot")]
         output_path: PathBuf,
+
+        /// Full path to the base snapshot file of an incremental snapshot
+        #[arg(long)]
+        base_snapshot: Option<PathBuf>,
     },
 
+    /// Query & compact the event log
+    #[clap(subcommand)]
+    Events(Events),
+
+    /// Show the health & readiness of a running mina indexer (JSON)
+    Health,
+
     /// Query staged ledgers
     #[clap(subcommand)]
     Ledgers(Ledgers),
@@ -71,11 +105,19 @@ pub enum ClientCli {
     #[clap(subcommand)]
     InternalCommands(InternalCommands),
 
+    /// Query best tip reorgs
+    #[clap(subcommand)]
+    Reorgs(Reorgs),
+
+    /// Register & manage outbound webhooks
+    #[clap(subcommand)]
+    Webhooks(Webhooks),
+
     /// Query a running mina indexer for database version
     DbVersion,
 }
 
-#[derive(Subcommand, Debug, Encode, Decode)]
+#[derive(Subcommand, Debug, Encode, Decode, Serialize)]
 #[command(author, version, about, long_about = None)]
 pub enum Accounts {
     /// Query accounts by public key
@@ -86,7 +128,7 @@ pub enum Accounts {
     },
 }
 
-#[derive(Subcommand, Debug, Encode, Decode)]
+#[derive(Subcommand, Debug, Encode, Decode, Serialize)]
 #[command(author, version, about, long_about = None)]
 pub enum Blocks {
     /// Query blocks by state hash
@@ -113,6 +155,10 @@ pub enum Blocks {
         /// Display the entire precomputed block
         #[arg(long, default_value_t = false)]
         verbose: bool,
+
+        /// Keep the connection open & print new best tips as they're indexed
+        #[arg(long, default_value_t = false)]
+        follow: bool,
     },
 
     /// Query blocks by global slot number
@@ -176,7 +222,7 @@ pub enum Blocks {
     },
 }
 
-#[derive(Subcommand, Debug, Encode, Decode)]
+#[derive(Subcommand, Debug, Encode, Decode, Serialize)]
 #[command(author, version, about, long_about = None)]
 pub enum Chain {
     /// Query the canonical chain
@@ -203,7 +249,53 @@ pub enum Chain {
     },
 }
 
-#[derive(Subcommand, Debug, Encode, Decode)]
+#[derive(Subcommand, Debug, Encode, Decode, Serialize)]
+#[command(author, version, about, long_about = None)]
+pub enum Events {
+    /// Query events by sequence number range & kind
+    Query {
+        /// First sequence number (inclusive)
+        #[arg(long, default_value_t = 0)]
+        start: u32,
+
+        /// Last sequence number (exclusive) [default: end of the log]
+        #[arg(long)]
+        end: Option<u32>,
+
+        /// Kind of events (new_block, new_best_tip, new_canonical_block,
+        /// new_ledger, new_staking_ledger, aggregate_delegations)
+        #[arg(long)]
+        kind: Option<String>,
+
+        /// Max number of events to return
+        #[arg(long, default_value_t = 100)]
+        limit: u32,
+
+        /// Path to write the events [default: stdout]
+        #[arg(long)]
+        path: Option<PathBuf>,
+
+        /// Keep the connection open & print new events as they're logged
+        #[arg(long, default_value_t = false)]
+        follow: bool,
+    },
+
+    /// Query event log checkpoints
+    Checkpoints {
+        /// Path to write the checkpoints [default: stdout]
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+
+    /// Remove events below the latest checkpoint
+    Compact {
+        /// Path to archive the removed events as NDJSON [default: discard]
+        #[arg(long)]
+        archive_path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug, Encode, Decode, Serialize)]
 #[command(author, version, about, long_about = None)]
 pub enum Ledgers {
     /// Query the best ledger
@@ -236,7 +328,7 @@ pub enum Ledgers {
     },
 }
 
-#[derive(Subcommand, Debug, Encode, Decode)]
+#[derive(Subcommand, Debug, Encode, Decode, Serialize)]
 #[command(author, version, about, long_about = None)]
 pub enum StakingLedgers {
     /// Query staking ledgers by ledger hash
@@ -296,7 +388,7 @@ pub enum StakingLedgers {
     },
 }
 
-#[derive(Subcommand, Debug, Encode, Decode)]
+#[derive(Subcommand, Debug, Encode, Decode, Serialize)]
 #[command(author, version, about, long_about = None)]
 pub enum Snarks {
     /// Query SNARK work by state hash
@@ -319,6 +411,10 @@ pub enum Snarks {
         /// State hash of block to query
         #[arg(long)]
         public_key: String,
+
+        /// Keep the connection open & print new SNARK work as it's indexed
+        #[arg(long, default_value_t = false)]
+        follow: bool,
     },
 
     /// Query top SNARK workers by total fees
@@ -329,7 +425,7 @@ pub enum Snarks {
     },
 }
 
-#[derive(Subcommand, Debug, Encode, Decode)]
+#[derive(Subcommand, Debug, Encode, Decode, Serialize)]
 #[command(author, version, about, long_about = None)]
 pub enum Transactions {
     /// Query transactions by hash
@@ -364,6 +460,10 @@ pub enum Transactions {
         /// Verbose transaction output
         #[arg(long, default_value_t = false)]
         verbose: bool,
+
+        /// Keep the connection open & print new transactions as they're indexed
+        #[arg(long, default_value_t = false)]
+        follow: bool,
     },
 
     /// Query transactions by state hash
@@ -382,7 +482,7 @@ pub enum Transactions {
     },
 }
 
-#[derive(Subcommand, Debug, Encode, Decode)]
+#[derive(Subcommand, Debug, Encode, Decode, Serialize)]
 #[command(author, version, about, long_about = None)]
 pub enum InternalCommands {
     /// Query internal commands by public key
@@ -405,28 +505,192 @@ pub enum InternalCommands {
         /// Retrieve public key's internal command info
         #[arg(long)]
         public_key: String,
+
+        /// Keep the connection open & print new internal commands as they're
+        /// indexed
+        #[arg(long, default_value_t = false)]
+        follow: bool,
+    },
+}
+
+#[derive(Subcommand, Debug, Encode, Decode, Serialize)]
+#[command(author, version, about, long_about = None)]
+pub enum Reorgs {
+    /// Query reorgs, newest first
+    List {
+        /// Min number of blocks removed from the best chain
+        #[arg(long)]
+        min_depth: Option<u32>,
+
+        /// Epoch of the new best tip
+        #[arg(long)]
+        epoch: Option<u32>,
+
+        /// Block which was uncanonicalized or recanonicalized
+        #[arg(long)]
+        state_hash: Option<String>,
+
+        /// User command which was uncanonicalized or recanonicalized
+        #[arg(long)]
+        txn_hash: Option<String>,
+
+        /// Sender or receiver of an uncanonicalized or recanonicalized user
+        /// command
+        #[arg(long)]
+        public_key: Option<String>,
+
+        /// Max number of reorgs to return
+        #[arg(long, default_value_t = 10)]
+        limit: u32,
+
+        /// Path to write the reorgs [default: stdout]
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+
+    /// Summary stats of all reorgs (max depth, frequency per epoch)
+    Stats {
+        /// Path to write the stats [default: stdout]
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug, Encode, Decode, Serialize)]
+#[command(author, version, about, long_about = None)]
+pub enum Webhooks {
+    /// Register a webhook receiving events from now on
+    Add {
+        /// URL the JSON payloads are POSTed to
+        #[arg(long)]
+        url: String,
+
+        /// Shared secret used to sign payloads (HMAC-SHA256)
+        #[arg(long)]
+        secret: String,
+
+        /// Comma-separated payload kinds (new_block, new_best_tip,
+        /// new_canonical_block, new_staking_ledger, user_command,
+        /// internal_command, reorg) [default: all]
+        #[arg(long)]
+        kinds: Option<String>,
+
+        /// Comma-separated public keys, one of which must be involved
+        /// [default: all]
+        #[arg(long)]
+        public_keys: Option<String>,
+
+        /// Minimum amount (nanomina) of user & internal commands
+        #[arg(long)]
+        min_amount: Option<u64>,
+
+        /// Number of blocks built on top of a block before its payloads are
+        /// delivered
+        #[arg(long, default_value_t = 0)]
+        confirmations: u32,
+    },
+
+    /// List registered webhooks & their delivery cursors
+    List {
+        /// Path to write the webhooks [default: stdout]
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+
+    /// Remove a registered webhook
+    Remove {
+        /// Id of the webhook
+        #[arg(long)]
+        id: u32,
     },
 }
 
 impl ClientCli {
     pub async fn run(&self, domain_socket_path: PathBuf) -> anyhow::Result<()> {
-        let conn = UnixStream::connect(domain_socket_path)
+        self.run_with_format(domain_socket_path, OutputFormat::default(), None)
+            .await
+    }
+
+    /// Render the command's output in the format, to stdout or its `--path`
+    ///
+    /// The output is written to `--path` by the client, as it's received.
+    /// Queries the `network` (name or genesis state hash), else the primary
+    /// network.
+    pub async fn run_with_format(
+        &self,
+        domain_socket_path: PathBuf,
+        format: OutputFormat,
+        network: Option<String>,
+    ) -> anyhow::Result<()> {
+        let mut client = IndexerClient::connect_uds(&domain_socket_path)
             .await
             .unwrap_or_else(|e| {
-                eprintln!("Unable to connect to the Unix domain socket server: {e}");
+                eprintln!("Unable to connect to the Unix domain socket server: {e:#}");
                 process::exit(111);
             });
-        let (reader, mut writer) = conn.into_split();
-        let mut reader = BufReader::new(reader);
-        let mut buffer = Vec::with_capacity(BUFFER_SIZE);
-        let encoded = bincode::encode_to_vec(self, BIN_CODE_CONFIG)?;
-
-        writer.write_all(&encoded).await?;
-        reader.read_to_end(&mut buffer).await?;
-
-        let msg = String::from_utf8(buffer)?;
-        let msg = msg.trim_end();
-        println!("{msg}");
-        Ok(())
+        if let Some(network) = network {
+            client = client.on_network(network);
+        }
+        let (method, mut params) = self.to_rpc()?;
+        let path = params
+            .as_mut()
+            .and_then(|params| params.as_object_mut())
+            .and_then(|params| params.remove("path"))
+            .and_then(|path| path.as_str().map(PathBuf::from));
+
+        let result = match &path {
+            Some(path) if path.is_dir() => {
+                let e = ClientError::PathIsDirectory(path.clone());
+                println!("{e}");
+                return Ok(());
+            }
+            Some(path) => {
+                let mut writer = BufWriter::new(File::create(path)?);
+                let result = write_output(&client, &method, params, format, &mut writer).await;
+                writer.flush()?;
+                if result.is_err() {
+                    drop(writer);
+                    std::fs::remove_file(path)?;
+                }
+                result.map(|_| println!("Output written to {}", path.display()))
+            }
+            None => {
+                let mut writer = BufWriter::new(io::stdout());
+                let result = write_output(&client, &method, params, format, &mut writer).await;
+                writer.flush()?;
+                result
+            }
+        };
+
+        match result.map_err(|e| e.downcast::<RpcError>()) {
+            Ok(()) => Ok(()),
+            Err(Ok(e)) => {
+                println!("{}", e.message);
+                Ok(())
+            }
+            Err(Err(e)) => Err(e),
+        }
+    }
+}
+
+/// Write the method's output, following it if `"follow": true`
+async fn write_output<W: Write>(
+    client: &IndexerClient,
+    method: &str,
+    params: Option<serde_json::Value>,
+    format: OutputFormat,
+    writer: &mut W,
+) -> anyhow::Result<()> {
+    let follow = params
+        .as_ref()
+        .and_then(|params| params.get("follow"))
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or_default();
+    if follow {
+        client
+            .follow_to_writer(method, params, format, writer)
+            .await
+    } else {
+        client.call_to_writer(method, params, format, writer).await
     }
 }
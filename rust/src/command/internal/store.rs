@@ -30,6 +30,10 @@ pub trait InternalCommandStore {
     fn internal_commands_global_slot_interator(&self, mode: speedb::IteratorMode)
         -> DBIterator<'_>;
 
+    /// Get internal commands iterator (by public key & block height) with
+    /// given mode
+    fn internal_commands_pk_height_iterator(&self, mode: speedb::IteratorMode) -> DBIterator<'_>;
+
     /// Increment internal commands per epoch count
     fn increment_internal_commands_epoch_count(&self, epoch: u32) -> anyhow::Result<()>;
 
@@ -43,6 +43,16 @@ impl SignedCommand {
         self.payload_common().fee.inner().inner()
     }
 
+    /// Amount of a payment, 0 for delegations
+    pub fn amount(&self) -> u64 {
+        match self.payload_body() {
+            mina_rs::SignedCommandPayloadBody::PaymentPayload(payment_payload) => {
+                payment_payload.t.t.amount.t.t
+            }
+            mina_rs::SignedCommandPayloadBody::StakeDelegation(_) => 0,
+        }
+    }
+
     pub fn fee_payer_pk(&self) -> PublicKey {
         self.payload_common().fee_payer_pk.into()
     }
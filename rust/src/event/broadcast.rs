@@ -0,0 +1,47 @@
+//! In-process broadcast of newly recorded events
+//!
+//! The indexer records events in the event log while processing precomputed
+//! blocks & staking ledgers. Afterwards, the newly recorded events are sent to
+//! every live receiver, e.g. GraphQL subscriptions.
+
+use super::{store::EventStore, SeqEvent};
+use crate::store::IndexerStore;
+use log::trace;
+use tokio::sync::broadcast;
+
+/// Number of events buffered per receiver before lagging receivers skip
+/// the oldest ones
+pub const EVENT_BROADCAST_CAPACITY: usize = 1024;
+
+pub type EventSender = broadcast::Sender<SeqEvent>;
+pub type EventReceiver = broadcast::Receiver<SeqEvent>;
+
+/// Create the event broadcast channel
+pub fn event_channel() -> EventSender {
+    broadcast::channel(EVENT_BROADCAST_CAPACITY).0
+}
+
+/// Broadcast all events recorded since `seq_num`
+///
+/// Returns the number of events sent
+pub fn broadcast_events_since(
+    store: &IndexerStore,
+    sender: &EventSender,
+    seq_num: u32,
+) -> anyhow::Result<usize> {
+    if sender.receiver_count() == 0 {
+        return Ok(0);
+    }
+
+    let events = store.get_events(seq_num, None, None, usize::MAX)?;
+    let num_events = events.len();
+    for event in events {
+        trace!("Broadcasting event {}: {:?}", event.seq_num, event.event);
+
+        // only fails if all receivers have been dropped in the meantime
+        if sender.send(event).is_err() {
+            break;
+        }
+    }
+    Ok(num_events)
+}
@@ -0,0 +1,44 @@
+//! Event log checkpoints
+//!
+//! A checkpoint records the state derived from all events preceding its
+//! sequence number. It is taken alongside each witness tree checkpoint, so
+//! events below the latest checkpoint are no longer needed to restore the
+//! indexer state & can be compacted.
+
+use crate::block::BlockHash;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventCheckpoint {
+    /// Sequence number of the first event following the checkpoint
+    pub seq_num: u32,
+
+    /// Witness tree best tip
+    pub best_tip: BlockHash,
+
+    /// Witness tree best tip blockchain length
+    pub best_tip_height: u32,
+
+    /// Witness tree canonical root
+    pub canonical_root: BlockHash,
+
+    /// Witness tree canonical root blockchain length
+    pub canonical_root_height: u32,
+
+    /// Number of blocks added to the witness tree
+    pub blocks_processed: u32,
+}
+
+/// Result of compacting the event log
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventCompaction {
+    /// Sequence number the event log was compacted to, i.e. the latest
+    /// checkpoint unless webhooks have yet to deliver earlier events
+    pub seq_num: u32,
+
+    /// Number of events removed from the event log
+    pub num_events: u32,
+
+    /// Number of removed events written to the archive
+    pub num_archived: u32,
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
 pub mod block;
+pub mod broadcast;
+pub mod checkpoint;
 pub mod db;
 pub mod ledger;
 pub mod store;
@@ -44,6 +46,95 @@ impl IndexerEvent {
             _ => 3,
         }
     }
+
+    /// Kind of a recorded event, `None` for witness tree events
+    pub fn event_kind(&self) -> Option<EventKind> {
+        use db::*;
+        match self {
+            Self::Db(DbEvent::Block(DbBlockEvent::NewBlock { .. })) => Some(EventKind::NewBlock),
+            Self::Db(DbEvent::Block(DbBlockEvent::NewBestTip { .. })) => {
+                Some(EventKind::NewBestTip)
+            }
+            Self::Db(DbEvent::Canonicity(DbCanonicityEvent::NewCanonicalBlock { .. })) => {
+                Some(EventKind::NewCanonicalBlock)
+            }
+            Self::Db(DbEvent::Ledger(DbLedgerEvent::NewLedger { .. })) => {
+                Some(EventKind::NewLedger)
+            }
+            Self::Db(DbEvent::StakingLedger(DbStakingLedgerEvent::NewStakingLedger { .. })) => {
+                Some(EventKind::NewStakingLedger)
+            }
+            Self::Db(DbEvent::StakingLedger(DbStakingLedgerEvent::AggregateDelegations {
+                ..
+            })) => Some(EventKind::AggregateDelegations),
+            Self::WitnessTree(_) => None,
+        }
+    }
+}
+
+/// Kinds of events recorded in the event log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    NewBlock,
+    NewBestTip,
+    NewCanonicalBlock,
+    NewLedger,
+    NewStakingLedger,
+    AggregateDelegations,
+}
+
+impl EventKind {
+    pub const ALL: [Self; 6] = [
+        Self::NewBlock,
+        Self::NewBestTip,
+        Self::NewCanonicalBlock,
+        Self::NewLedger,
+        Self::NewStakingLedger,
+        Self::AggregateDelegations,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NewBlock => "new_block",
+            Self::NewBestTip => "new_best_tip",
+            Self::NewCanonicalBlock => "new_canonical_block",
+            Self::NewLedger => "new_ledger",
+            Self::NewStakingLedger => "new_staking_ledger",
+            Self::AggregateDelegations => "aggregate_delegations",
+        }
+    }
+}
+
+impl std::str::FromStr for EventKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| {
+                let kinds: Vec<&str> = Self::ALL.iter().map(Self::as_str).collect();
+                anyhow::anyhow!(
+                    "Invalid event kind {s}, expected one of {}",
+                    kinds.join(", ")
+                )
+            })
+    }
+}
+
+impl std::fmt::Display for EventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Event log entry with its sequence number
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeqEvent {
+    pub seq_num: u32,
+    pub kind: EventKind,
+    pub event: IndexerEvent,
 }
 
 impl std::fmt::Debug for IndexerEvent {
@@ -1,5 +1,9 @@
-use crate::event::IndexerEvent;
+use crate::event::{
+    checkpoint::{EventCheckpoint, EventCompaction},
+    EventKind, IndexerEvent, SeqEvent,
+};
 use speedb::{DBIterator, IteratorMode};
+use std::io::Write;
 
 pub trait EventStore {
     /// Add event to db and return the next sequence number
@@ -13,9 +17,44 @@ pub trait EventStore {
     /// Get the next event sequence number
     fn get_next_seq_num(&self) -> anyhow::Result<u32>;
 
+    /// Get the sequence number of the first event in the log, i.e. the
+    /// compaction point
+    fn get_first_seq_num(&self) -> anyhow::Result<Option<u32>>;
+
     /// Returns the event log
     fn get_event_log(&self) -> anyhow::Result<Vec<IndexerEvent>>;
 
+    /// Get at most `limit` events with sequence numbers in `start..end` of
+    /// the given `kind` (default: all kinds)
+    fn get_events(
+        &self,
+        start: u32,
+        end: Option<u32>,
+        kind: Option<EventKind>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<SeqEvent>>;
+
     /// Returns the event log iterator
     fn event_log_iterator(&self, mode: IteratorMode) -> DBIterator<'_>;
+
+    ///////////////////////////
+    // Event log checkpoints //
+    ///////////////////////////
+
+    /// Record an event log checkpoint
+    fn add_event_checkpoint(&self, checkpoint: &EventCheckpoint) -> anyhow::Result<()>;
+
+    /// Get the most recent event log checkpoint
+    fn get_latest_event_checkpoint(&self) -> anyhow::Result<Option<EventCheckpoint>>;
+
+    /// Get all event log checkpoints in sequence order
+    fn get_event_checkpoints(&self) -> anyhow::Result<Vec<EventCheckpoint>>;
+
+    /// Remove all events below the latest checkpoint, writing them to
+    /// `archive` as NDJSON [SeqEvent]s if provided
+    ///
+    /// Events a registered webhook has yet to deliver, including block events
+    /// awaiting confirmations, are kept
+    fn compact_event_log(&self, archive: Option<&mut dyn Write>)
+        -> anyhow::Result<EventCompaction>;
 }
@@ -0,0 +1,238 @@
+//! Items pushed to clients following a command, derived from [SeqEvent]s
+//!
+//! Best tip changes push the items of the blocks joining the best chain as
+//! new & those of the blocks leaving it as orphaned.
+
+use crate::{
+    block::{precomputed::PrecomputedBlock, store::BlockStore, BlockHash, BlockWithoutHeight},
+    canonicity::store::CanonicityStore,
+    client::*,
+    command::{
+        internal::{store::InternalCommandStore, InternalCommandWithData},
+        signed::SignedCommandWithData,
+        Command,
+    },
+    event::{db::*, EventKind, IndexerEvent, SeqEvent},
+    ledger::public_key::PublicKey,
+    rpc::{FollowStatus, RpcError},
+    snark_work::SnarkWorkSummaryWithStateHash,
+    store::IndexerStore,
+};
+use serde_json::Value;
+
+/// What a following command pushes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Follow {
+    BestTip {
+        verbose: bool,
+    },
+    Transactions {
+        public_key: PublicKey,
+        verbose: bool,
+    },
+    InternalCommands {
+        public_key: PublicKey,
+    },
+    Snarks {
+        public_key: PublicKey,
+    },
+    Events {
+        kind: Option<EventKind>,
+    },
+}
+
+impl Follow {
+    /// What the command follows, `None` unless it's called with `--follow`
+    ///
+    /// Errors on params which would otherwise widen what's followed, e.g. an
+    /// invalid event kind
+    pub fn of_command(command: &ClientCli) -> Result<Option<Self>, RpcError> {
+        Ok(match command {
+            ClientCli::Blocks(Blocks::BestTip {
+                verbose,
+                follow: true,
+                ..
+            }) => Some(Self::BestTip { verbose: *verbose }),
+            ClientCli::Transactions(Transactions::PublicKey {
+                public_key,
+                verbose,
+                follow: true,
+                ..
+            }) => Some(Self::Transactions {
+                public_key: public_key.clone().into(),
+                verbose: *verbose,
+            }),
+            ClientCli::InternalCommands(InternalCommands::PublicKey {
+                public_key,
+                follow: true,
+                ..
+            }) => Some(Self::InternalCommands {
+                public_key: public_key.clone().into(),
+            }),
+            ClientCli::Snarks(Snarks::PublicKey {
+                public_key,
+                follow: true,
+                ..
+            }) => Some(Self::Snarks {
+                public_key: public_key.clone().into(),
+            }),
+            ClientCli::Events(Events::Query {
+                kind, follow: true, ..
+            }) => Some(Self::Events {
+                kind: kind
+                    .as_deref()
+                    .map(str::parse)
+                    .transpose()
+                    .map_err(|e: anyhow::Error| RpcError::invalid_params(e.to_string()))?,
+            }),
+            _ => None,
+        })
+    }
+
+    /// Items of the event, in order
+    ///
+    /// `best_tip` is the best tip prior to the event & is updated by best tip
+    /// events
+    pub fn items(
+        &self,
+        store: &IndexerStore,
+        event: &SeqEvent,
+        best_tip: &mut Option<BlockHash>,
+    ) -> anyhow::Result<Vec<(FollowStatus, Value)>> {
+        if let Self::Events { kind } = self {
+            if kind.map_or(true, |kind| kind == event.kind) {
+                return Ok(vec![(FollowStatus::New, serde_json::to_value(event)?)]);
+            }
+            return Ok(vec![]);
+        }
+
+        let state_hash = match &event.event {
+            IndexerEvent::Db(DbEvent::Block(DbBlockEvent::NewBestTip { state_hash, .. })) => {
+                state_hash
+            }
+            _ => return Ok(vec![]),
+        };
+        let (orphaned, joined) = match best_tip.replace(state_hash.clone()) {
+            Some(previous) if previous == *state_hash => return Ok(vec![]),
+            Some(previous) => (
+                store.get_reorged_blocks(&previous, state_hash)?,
+                store.get_reorged_blocks(state_hash, &previous)?,
+            ),
+            None => (vec![], vec![state_hash.clone()]),
+        };
+
+        let mut items = vec![];
+        for state_hash in &orphaned {
+            for item in self.block_items(store, state_hash)? {
+                items.push((FollowStatus::Orphaned, item));
+            }
+        }
+        if let Self::BestTip { .. } = self {
+            for item in self.block_items(store, state_hash)? {
+                items.push((FollowStatus::New, item));
+            }
+            return Ok(items);
+        }
+
+        // joined blocks, lowest first
+        for state_hash in joined.iter().rev() {
+            for item in self.block_items(store, state_hash)? {
+                items.push((FollowStatus::New, item));
+            }
+        }
+        Ok(items)
+    }
+
+    /// Items of the block, as output by the followed command
+    fn block_items(
+        &self,
+        store: &IndexerStore,
+        state_hash: &BlockHash,
+    ) -> anyhow::Result<Vec<Value>> {
+        // pruned blocks are served from their compact contents, only verbose
+        // best tips re-hydrate the full block
+        let pruned = store.get_pruned_block(state_hash)?;
+        let full_block = || -> anyhow::Result<Option<PrecomputedBlock>> {
+            Ok(store.get_block(state_hash)?.map(|(block, _)| block))
+        };
+
+        Ok(match self {
+            Self::BestTip { verbose } => {
+                let canonicity = match store.get_block_canonicity(state_hash)? {
+                    Some(canonicity) => canonicity,
+                    None => return Ok(vec![]),
+                };
+                let full = match pruned {
+                    Some(_) if !*verbose => None,
+                    _ => full_block()?,
+                };
+                let block = match (full, pruned) {
+                    (Some(block), _) if *verbose => {
+                        serde_json::to_value(block.with_canonicity(canonicity))?
+                    }
+                    (Some(block), _) => serde_json::to_value(BlockWithoutHeight::with_canonicity(
+                        &block, canonicity,
+                    ))?,
+                    // pruned block summary, also without its precomputed block file
+                    (None, Some(pruned)) => serde_json::to_value(BlockWithoutHeight {
+                        canonicity: Some(canonicity),
+                        ..(&pruned).into()
+                    })?,
+                    (None, None) => return Ok(vec![]),
+                };
+                vec![block]
+            }
+            Self::Transactions {
+                public_key,
+                verbose,
+            } => match pruned {
+                Some(pruned) => pruned.user_commands,
+                None => match full_block()? {
+                    Some(block) => SignedCommandWithData::from_precomputed(&block),
+                    None => return Ok(vec![]),
+                },
+            }
+            .into_iter()
+            .filter(|cmd| cmd.command.contains_public_key(public_key))
+            .map(|cmd| {
+                if *verbose {
+                    Value::from(cmd)
+                } else {
+                    Value::from(Command::from(cmd))
+                }
+            })
+            .collect(),
+            Self::InternalCommands { public_key } => store
+                .get_internal_commands(state_hash)?
+                .into_iter()
+                .filter(|cmd| match cmd {
+                    InternalCommandWithData::FeeTransfer {
+                        sender, receiver, ..
+                    } => sender == public_key || receiver == public_key,
+                    InternalCommandWithData::Coinbase { receiver, .. } => receiver == public_key,
+                })
+                .map(serde_json::to_value)
+                .collect::<Result<_, _>>()?,
+            Self::Snarks { public_key } => match pruned {
+                Some(pruned) => pruned
+                    .snarks
+                    .into_iter()
+                    .map(|snark| SnarkWorkSummaryWithStateHash {
+                        fee: snark.fee,
+                        prover: snark.prover,
+                        state_hash: state_hash.to_string(),
+                    })
+                    .collect(),
+                None => match full_block()? {
+                    Some(block) => SnarkWorkSummaryWithStateHash::from_precomputed(&block),
+                    None => return Ok(vec![]),
+                },
+            }
+            .into_iter()
+            .filter(|snark| snark.prover == *public_key)
+            .map(Value::from)
+            .collect(),
+            Self::Events { .. } => vec![],
+        })
+    }
+}
@@ -0,0 +1,146 @@
+//! Liveness & readiness of a running indexer
+//!
+//! The indexer is healthy while its process is up & the store can be read.
+//! It's ready once it's watching for new blocks, the UDS server is up & the
+//! best tip is within `max_slot_lag` slots of the wall-clock slot.
+
+use crate::{
+    block::store::BlockStore,
+    constants::{MAINNET_BLOCK_SLOT_TIME_MILLIS, MAINNET_GENESIS_TIMESTAMP},
+    ledger::genesis::GenesisConstants,
+    state::IndexerPhase,
+    store::IndexerStore,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Default number of slots the best tip may trail the wall-clock slot by
+pub const DEFAULT_MAX_SLOT_LAG: u32 = 10;
+
+/// Status shared by the indexer, UDS & web servers
+#[derive(Debug)]
+pub struct IndexerHealth {
+    phase: Mutex<IndexerPhase>,
+
+    /// Shared by the indexed networks, the primary network starts the server
+    uds_server_up: Arc<AtomicBool>,
+    max_slot_lag: u32,
+    genesis_timestamp: u64,
+    slot_duration: u64,
+    init_time: Instant,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthStatus {
+    pub healthy: bool,
+    pub db_open: bool,
+    pub uptime_secs: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadinessStatus {
+    pub ready: bool,
+    pub phase: IndexerPhase,
+    pub uds_server_up: bool,
+    pub best_tip_height: Option<u32>,
+    pub best_tip_global_slot: Option<u32>,
+    pub wall_clock_global_slot: u32,
+
+    /// Slots the best tip trails the wall-clock slot by
+    pub slot_lag: Option<u32>,
+    pub max_slot_lag: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexerStatus {
+    pub health: HealthStatus,
+    pub readiness: ReadinessStatus,
+}
+
+impl IndexerHealth {
+    /// Slot times are derived from the genesis constants
+    pub fn new(phase: IndexerPhase, constants: &GenesisConstants, max_slot_lag: u32) -> Self {
+        Self {
+            phase: Mutex::new(phase),
+            uds_server_up: Arc::new(AtomicBool::new(false)),
+            max_slot_lag,
+            genesis_timestamp: constants
+                .genesis_state_timestamp
+                .unwrap_or(MAINNET_GENESIS_TIMESTAMP),
+            slot_duration: constants
+                .block_window_duration_ms
+                .unwrap_or(MAINNET_BLOCK_SLOT_TIME_MILLIS),
+            init_time: Instant::now(),
+        }
+    }
+
+    /// Health of an additional network, which is served by the `primary`
+    /// network's UDS server
+    pub fn with_uds_server_of(mut self, primary: &IndexerHealth) -> Self {
+        self.uds_server_up = primary.uds_server_up.clone();
+        self
+    }
+
+    pub fn phase(&self) -> IndexerPhase {
+        *self.phase.lock().expect("phase lock")
+    }
+
+    pub fn set_phase(&self, phase: IndexerPhase) {
+        *self.phase.lock().expect("phase lock") = phase;
+    }
+
+    pub fn set_uds_server_up(&self, is_up: bool) {
+        self.uds_server_up.store(is_up, Ordering::Relaxed);
+    }
+
+    /// Global slot of the current wall-clock time
+    pub fn wall_clock_global_slot(&self) -> u32 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_millis() as u64);
+        (now.saturating_sub(self.genesis_timestamp) / self.slot_duration) as u32
+    }
+
+    pub fn health(&self, store: &IndexerStore) -> HealthStatus {
+        let db_open = store.get_best_block_hash().is_ok();
+        HealthStatus {
+            healthy: db_open,
+            db_open,
+            uptime_secs: self.init_time.elapsed().as_secs(),
+        }
+    }
+
+    pub fn readiness(&self, store: &IndexerStore) -> anyhow::Result<ReadinessStatus> {
+        let phase = self.phase();
+        let uds_server_up = self.uds_server_up.load(Ordering::Relaxed);
+        let best_tip_global_slot = store.get_best_block_global_slot()?;
+        let wall_clock_global_slot = self.wall_clock_global_slot();
+        let slot_lag = best_tip_global_slot.map(|slot| wall_clock_global_slot.saturating_sub(slot));
+
+        Ok(ReadinessStatus {
+            ready: phase == IndexerPhase::Watching
+                && uds_server_up
+                && slot_lag.is_some_and(|lag| lag <= self.max_slot_lag),
+            phase,
+            uds_server_up,
+            best_tip_height: store.get_best_block_height()?,
+            best_tip_global_slot,
+            wall_clock_global_slot,
+            slot_lag,
+            max_slot_lag: self.max_slot_lag,
+        })
+    }
+
+    pub fn status(&self, store: &IndexerStore) -> anyhow::Result<IndexerStatus> {
+        Ok(IndexerStatus {
+            health: self.health(store),
+            readiness: self.readiness(store)?,
+        })
+    }
+}
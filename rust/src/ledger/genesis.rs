@@ -71,6 +71,18 @@ pub struct GenesisConstants {
     pub slots_per_sub_window: Option<u32>,
     pub delta: Option<u32>,
     pub txpool_max_size: Option<u32>,
+
+    /// Milliseconds since the Unix epoch
+    pub genesis_state_timestamp: Option<u64>,
+
+    /// Slot duration in milliseconds
+    pub block_window_duration_ms: Option<u64>,
+
+    /// Coinbase reward in nanomina
+    pub coinbase_amount: Option<u64>,
+
+    /// Account creation fee in nanomina
+    pub account_creation_fee: Option<u64>,
 }
 
 impl GenesisConstants {
@@ -81,6 +93,10 @@ impl GenesisConstants {
             slots_per_epoch,
             slots_per_sub_window,
             txpool_max_size,
+            genesis_state_timestamp,
+            block_window_duration_ms,
+            coinbase_amount,
+            account_creation_fee,
         } = constants;
 
         if delta.is_some() {
@@ -98,6 +114,18 @@ impl GenesisConstants {
         if txpool_max_size.is_some() {
             self.txpool_max_size = txpool_max_size;
         }
+        if genesis_state_timestamp.is_some() {
+            self.genesis_state_timestamp = genesis_state_timestamp;
+        }
+        if block_window_duration_ms.is_some() {
+            self.block_window_duration_ms = block_window_duration_ms;
+        }
+        if coinbase_amount.is_some() {
+            self.coinbase_amount = coinbase_amount;
+        }
+        if account_creation_fee.is_some() {
+            self.account_creation_fee = account_creation_fee;
+        }
     }
 }
 
@@ -109,6 +137,10 @@ impl std::default::Default for GenesisConstants {
             txpool_max_size: Some(MAINNET_TXPOOL_MAX_SIZE),
             slots_per_epoch: Some(MAINNET_EPOCH_SLOT_COUNT),
             slots_per_sub_window: Some(MAINNET_SLOTS_PER_SUB_WINDOW),
+            genesis_state_timestamp: Some(MAINNET_GENESIS_TIMESTAMP),
+            block_window_duration_ms: Some(MAINNET_BLOCK_SLOT_TIME_MILLIS),
+            coinbase_amount: Some(MAINNET_COINBASE_REWARD),
+            account_creation_fee: Some(MAINNET_ACCOUNT_CREATION_FEE.0),
         }
     }
 }
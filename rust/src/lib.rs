@@ -7,16 +7,22 @@ pub mod client;
 pub mod command;
 pub mod constants;
 pub mod event;
+pub mod follow;
+pub mod health;
 pub mod ledger;
+pub mod metrics;
 pub mod mina_blocks;
+pub mod networks;
 pub mod proof_systems;
 pub mod protocol;
+pub mod rpc;
 pub mod server;
 pub mod snark_work;
 pub mod state;
 pub mod store;
 pub mod unix_socket_server;
 pub mod web;
+pub mod webhook;
 
 #[cfg(target_family = "unix")]
 pub mod platform {
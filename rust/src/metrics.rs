@@ -0,0 +1,282 @@
+//! Prometheus metrics served at `/metrics`
+//!
+//! Ingestion, witness tree, reorg & API metrics are recorded as they happen.
+//! Chain & store metrics are read from the store on each scrape.
+
+use crate::{
+    block::store::BlockStore,
+    canonicity::store::CanonicityStore,
+    constants::{MAINNET_BLOCK_SLOT_TIME_MILLIS, MAINNET_GENESIS_TIMESTAMP},
+    store::IndexerStore,
+};
+use prometheus::{
+    exponential_buckets, Encoder, Gauge, Histogram, HistogramOpts, HistogramVec, IntCounterVec,
+    IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::{
+    sync::OnceLock,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const NAMESPACE: &str = "mina_indexer";
+
+/// Block parser classification of ingested blocks, watched blocks are recent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IngestedBlockKind {
+    DeepCanonical,
+    Recent,
+    Orphaned,
+}
+
+impl IngestedBlockKind {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::DeepCanonical => "deep_canonical",
+            Self::Recent => "recent",
+            Self::Orphaned => "orphaned",
+        }
+    }
+}
+
+pub struct Metrics {
+    registry: Registry,
+
+    // ingestion
+    blocks_ingested: IntCounterVec,
+    block_ingestion_seconds: HistogramVec,
+
+    // witness tree
+    witness_tree_blocks: IntGauge,
+    dangling_branches: IntGauge,
+    reorg_depth: Histogram,
+
+    // chain, read on scrape
+    best_tip_height: IntGauge,
+    best_tip_lag_seconds: Gauge,
+    canonical_root_height: IntGauge,
+    canonical_root_lag_seconds: Gauge,
+    reorgs_recorded: IntGauge,
+    reorg_max_depth: IntGauge,
+
+    // store, read on scrape
+    live_data_size_bytes: IntGaugeVec,
+    estimated_keys: IntGaugeVec,
+    memtable_size_bytes: IntGaugeVec,
+
+    // API
+    http_requests: IntCounterVec,
+    http_request_seconds: HistogramVec,
+    graphql_requests: IntCounterVec,
+    graphql_request_seconds: HistogramVec,
+}
+
+/// The process' metrics
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(|| Metrics::new().expect("valid metrics"))
+}
+
+impl Metrics {
+    fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new_custom(Some(NAMESPACE.to_string()), None)?;
+        let latency_buckets = exponential_buckets(0.0005, 2.0, 16)?;
+        macro_rules! register {
+            ($metric:expr) => {{
+                let metric = $metric;
+                registry.register(Box::new(metric.clone()))?;
+                metric
+            }};
+        }
+
+        Ok(Self {
+            blocks_ingested: register!(IntCounterVec::new(
+                Opts::new("blocks_ingested_total", "Blocks ingested by type"),
+                &["kind"],
+            )?),
+            block_ingestion_seconds: register!(HistogramVec::new(
+                HistogramOpts::new(
+                    "block_ingestion_seconds",
+                    "Time to add a parsed block to the store & witness tree"
+                )
+                .buckets(latency_buckets.clone()),
+                &["kind"],
+            )?),
+            witness_tree_blocks: register!(IntGauge::new(
+                "witness_tree_blocks",
+                "Blocks in the root & dangling branches of the witness tree"
+            )?),
+            dangling_branches: register!(IntGauge::new(
+                "dangling_branches",
+                "Dangling branches of the witness tree"
+            )?),
+            reorg_depth: register!(Histogram::with_opts(
+                HistogramOpts::new(
+                    "reorg_depth",
+                    "Blocks removed from the best chain by reorgs since start"
+                )
+                .buckets(vec![1.0, 2.0, 3.0, 5.0, 10.0, 20.0, 50.0, 100.0, 290.0]),
+            )?),
+            best_tip_height: register!(IntGauge::new(
+                "best_tip_height",
+                "Blockchain length of the best tip"
+            )?),
+            best_tip_lag_seconds: register!(Gauge::new(
+                "best_tip_lag_seconds",
+                "Wall-clock time since the best tip's slot began"
+            )?),
+            canonical_root_height: register!(IntGauge::new(
+                "canonical_root_height",
+                "Blockchain length of the highest canonical block"
+            )?),
+            canonical_root_lag_seconds: register!(Gauge::new(
+                "canonical_root_lag_seconds",
+                "Wall-clock time since the canonical root's slot began"
+            )?),
+            reorgs_recorded: register!(IntGauge::new(
+                "reorgs_recorded",
+                "Reorgs recorded in the store"
+            )?),
+            reorg_max_depth: register!(IntGauge::new(
+                "reorg_max_depth",
+                "Depth of the deepest reorg recorded in the store"
+            )?),
+            live_data_size_bytes: register!(IntGaugeVec::new(
+                Opts::new(
+                    "speedb_live_data_size_bytes",
+                    "Estimated live data size per column family"
+                ),
+                &["cf"],
+            )?),
+            estimated_keys: register!(IntGaugeVec::new(
+                Opts::new(
+                    "speedb_estimated_keys",
+                    "Estimated number of keys per column family"
+                ),
+                &["cf"],
+            )?),
+            memtable_size_bytes: register!(IntGaugeVec::new(
+                Opts::new(
+                    "speedb_memtable_size_bytes",
+                    "Size of all memtables per column family"
+                ),
+                &["cf"],
+            )?),
+            http_requests: register!(IntCounterVec::new(
+                Opts::new("http_requests_total", "HTTP requests by route & status"),
+                &["method", "route", "status"],
+            )?),
+            http_request_seconds: register!(HistogramVec::new(
+                HistogramOpts::new("http_request_seconds", "HTTP request latency by route")
+                    .buckets(latency_buckets.clone()),
+                &["method", "route"],
+            )?),
+            graphql_requests: register!(IntCounterVec::new(
+                Opts::new(
+                    "graphql_requests_total",
+                    "GraphQL operations by endpoint & root field"
+                ),
+                &["endpoint", "field"],
+            )?),
+            graphql_request_seconds: register!(HistogramVec::new(
+                HistogramOpts::new(
+                    "graphql_request_seconds",
+                    "GraphQL operation latency by endpoint & root field"
+                )
+                .buckets(latency_buckets),
+                &["endpoint", "field"],
+            )?),
+            registry,
+        })
+    }
+
+    pub fn observe_block_ingestion(&self, kind: IngestedBlockKind, elapsed: Duration) {
+        self.blocks_ingested
+            .with_label_values(&[kind.label()])
+            .inc();
+        self.block_ingestion_seconds
+            .with_label_values(&[kind.label()])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub fn set_witness_tree(&self, num_blocks: u32, num_dangling: usize) {
+        self.witness_tree_blocks.set(num_blocks as i64);
+        self.dangling_branches.set(num_dangling as i64);
+    }
+
+    pub fn observe_reorg(&self, depth: u32) {
+        self.reorg_depth.observe(depth as f64);
+    }
+
+    pub fn observe_http_request(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        self.http_requests
+            .with_label_values(&[method, route, &status.to_string()])
+            .inc();
+        self.http_request_seconds
+            .with_label_values(&[method, route])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub fn observe_graphql_request(&self, endpoint: &str, field: &str, elapsed: Duration) {
+        self.graphql_requests
+            .with_label_values(&[endpoint, field])
+            .inc();
+        self.graphql_request_seconds
+            .with_label_values(&[endpoint, field])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Prometheus text format of all metrics, after reading the chain & store
+    /// metrics from `store`
+    pub fn gather(&self, store: &IndexerStore) -> anyhow::Result<String> {
+        self.update_from_store(store)?;
+
+        let mut buffer = vec![];
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    fn update_from_store(&self, store: &IndexerStore) -> anyhow::Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+        let slot_lag_seconds = |global_slot: u32| {
+            let slot_start =
+                MAINNET_GENESIS_TIMESTAMP + global_slot as u64 * MAINNET_BLOCK_SLOT_TIME_MILLIS;
+            now.saturating_sub(slot_start) as f64 / 1000.0
+        };
+
+        if let Some(best_tip) = store.get_best_block_hash()? {
+            if let Some(height) = store.get_block_height(&best_tip)? {
+                self.best_tip_height.set(height as i64);
+            }
+            if let Some(global_slot) = store.get_block_global_slot(&best_tip)? {
+                self.best_tip_lag_seconds.set(slot_lag_seconds(global_slot));
+            }
+        }
+
+        if let Some(height) = store.get_max_canonical_blockchain_length()? {
+            self.canonical_root_height.set(height as i64);
+            if let Some(canonical_root) = store.get_canonical_hash_at_height(height)? {
+                if let Some(global_slot) = store.get_block_global_slot(&canonical_root)? {
+                    self.canonical_root_lag_seconds
+                        .set(slot_lag_seconds(global_slot));
+                }
+            }
+        }
+
+        let reorg_stats = store.get_reorg_stats()?;
+        self.reorgs_recorded.set(reorg_stats.num_reorgs as i64);
+        self.reorg_max_depth.set(reorg_stats.max_depth as i64);
+
+        for cf in store.column_family_stats()? {
+            self.live_data_size_bytes
+                .with_label_values(&[cf.name])
+                .set(cf.live_data_size as i64);
+            self.estimated_keys
+                .with_label_values(&[cf.name])
+                .set(cf.num_keys as i64);
+            self.memtable_size_bytes
+                .with_label_values(&[cf.name])
+                .set(cf.memtable_size as i64);
+        }
+        Ok(())
+    }
+}
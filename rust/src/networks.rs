@@ -0,0 +1,83 @@
+//! Networks indexed side by side in one database
+//!
+//! The primary network's keys are unprefixed, each additional network's are
+//! namespaced by its genesis state hash (see [IndexerStore::network]) & each
+//! network runs its own ingestion pipeline. Queries select a network by name
+//! or genesis state hash with their `network` argument, else query the
+//! primary network.
+
+use crate::{
+    block::BlockHash, event::broadcast::EventSender, health::IndexerHealth, rpc::ClientError,
+    state::IndexerState, store::IndexerStore, web::graphql::IndexerSchema,
+};
+use std::sync::{Arc, RwLock as SyncRwLock};
+use tokio::sync::RwLock;
+
+/// An indexed network's handles
+#[derive(Clone)]
+pub struct IndexedNetwork {
+    /// Name queries select the network by, e.g. `mainnet`
+    pub name: String,
+    pub genesis_state_hash: BlockHash,
+    pub store: Arc<IndexerStore>,
+    pub events: EventSender,
+    pub health: Arc<IndexerHealth>,
+    pub schema: IndexerSchema,
+
+    /// Set once the network's ingestion pipeline is initialized
+    pub state: Option<Arc<RwLock<IndexerState>>>,
+}
+
+/// The indexed networks, the first is the primary network
+#[derive(Clone)]
+pub struct IndexedNetworks(Arc<SyncRwLock<Vec<IndexedNetwork>>>);
+
+impl IndexedNetwork {
+    /// The network's state, once it's initialized
+    pub fn state(&self) -> Result<&Arc<RwLock<IndexerState>>, ClientError> {
+        self.state
+            .as_ref()
+            .ok_or_else(|| ClientError::NetworkInitializing(self.name.clone()))
+    }
+
+    fn is_selected_by(&self, network: &str) -> bool {
+        self.name == network || self.genesis_state_hash.0 == network
+    }
+}
+
+impl IndexedNetworks {
+    pub fn new(primary: IndexedNetwork) -> Self {
+        Self(Arc::new(SyncRwLock::new(vec![primary])))
+    }
+
+    pub fn add(&self, network: IndexedNetwork) {
+        self.0.write().expect("networks lock").push(network);
+    }
+
+    /// Set the state of the network once its ingestion pipeline is
+    /// initialized
+    pub fn set_state(&self, name: &str, state: Arc<RwLock<IndexerState>>) {
+        let mut networks = self.0.write().expect("networks lock");
+        if let Some(network) = networks.iter_mut().find(|network| network.name == name) {
+            network.state = Some(state);
+        }
+    }
+
+    pub fn primary(&self) -> IndexedNetwork {
+        self.0.read().expect("networks lock")[0].clone()
+    }
+
+    /// The network selected by name or genesis state hash, else the primary
+    /// network
+    pub fn get(&self, network: Option<&str>) -> Result<IndexedNetwork, ClientError> {
+        let networks = self.0.read().expect("networks lock");
+        match network {
+            None => Ok(networks[0].clone()),
+            Some(network) => networks
+                .iter()
+                .find(|indexed| indexed.is_selected_by(network))
+                .cloned()
+                .ok_or_else(|| ClientError::UnknownNetwork(network.to_string())),
+        }
+    }
+}
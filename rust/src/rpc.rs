@@ -0,0 +1,230 @@
+//! JSON-RPC methods of the client subcommands
+//!
+//! See [mina_indexer_client::rpc] for the protocol.
+
+use crate::client::ClientCli;
+use clap::{Command, CommandFactory, Parser};
+pub use mina_indexer_client::rpc::*;
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Client command errors, each with its own JSON-RPC error code
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ClientError {
+    #[error("Invalid public key: {0}")]
+    InvalidPublicKey(String),
+
+    #[error("Invalid state hash: {0}")]
+    InvalidStateHash(String),
+
+    #[error("Invalid transaction hash: {0}")]
+    InvalidTxHash(String),
+
+    #[error("Best tip block missing from store")]
+    BestTipMissing,
+
+    #[error("Block missing from store: {0}")]
+    BlockMissing(String),
+
+    #[error("The path provided must not be a directory: {}", .0.display())]
+    PathIsDirectory(PathBuf),
+
+    #[error("no response 404")]
+    NoResponse,
+
+    #[error("Unknown network: {0}")]
+    UnknownNetwork(String),
+
+    #[error("Network {0} is initializing")]
+    NetworkInitializing(String),
+}
+
+impl ClientError {
+    pub fn code(&self) -> i64 {
+        match self {
+            Self::InvalidPublicKey(_) => codes::INVALID_PUBLIC_KEY,
+            Self::InvalidStateHash(_) => codes::INVALID_STATE_HASH,
+            Self::InvalidTxHash(_) => codes::INVALID_TX_HASH,
+            Self::BestTipMissing => codes::BEST_TIP_MISSING,
+            Self::BlockMissing(_) => codes::BLOCK_MISSING,
+            Self::PathIsDirectory(_) => codes::PATH_IS_DIRECTORY,
+            Self::NoResponse => codes::NO_RESPONSE,
+            Self::UnknownNetwork(_) => codes::UNKNOWN_NETWORK,
+            Self::NetworkInitializing(_) => codes::NETWORK_INITIALIZING,
+        }
+    }
+
+    /// The offending input
+    fn data(&self) -> Option<Value> {
+        match self {
+            Self::InvalidPublicKey(input)
+            | Self::InvalidStateHash(input)
+            | Self::InvalidTxHash(input)
+            | Self::BlockMissing(input)
+            | Self::UnknownNetwork(input)
+            | Self::NetworkInitializing(input) => Some(input.as_str().into()),
+            Self::PathIsDirectory(path) => Some(path.display().to_string().into()),
+            Self::BestTipMissing | Self::NoResponse => None,
+        }
+    }
+}
+
+impl From<ClientError> for RpcError {
+    fn from(value: ClientError) -> Self {
+        Self {
+            code: value.code(),
+            message: value.to_string(),
+            data: value.data(),
+        }
+    }
+}
+
+/// Coded error of a failed command, internal unless it's a [ClientError]
+pub fn command_error(e: anyhow::Error) -> RpcError {
+    match e.downcast::<ClientError>() {
+        Ok(e) => e.into(),
+        Err(e) => RpcError::new(codes::INTERNAL_ERROR, e.to_string()),
+    }
+}
+
+impl ClientCli {
+    /// Method & params of the command's request
+    pub fn to_rpc(&self) -> anyhow::Result<(String, Option<Value>)> {
+        let mut command = ClientCli::command();
+        let mut method = vec![];
+        let mut value = serde_json::to_value(self)?;
+        loop {
+            // unit variants are strings, all others single entry objects
+            let (variant, inner) = match value {
+                Value::String(variant) => (variant, None),
+                Value::Object(object) if object.len() == 1 => {
+                    let (variant, inner) = object.into_iter().next().expect("single entry");
+                    (variant, Some(inner))
+                }
+                _ => anyhow::bail!("Unexpected command encoding: {value}"),
+            };
+
+            let name = kebab_case(&variant);
+            command = command
+                .find_subcommand(&name)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Unknown subcommand {name}"))?;
+            method.push(name);
+
+            match inner {
+                Some(inner) if command.has_subcommands() => value = inner,
+                params => return Ok((method.join("."), params)),
+            }
+        }
+    }
+
+    /// Parse the command of a request, as if its params were given as flags
+    pub fn from_rpc(method: &str, params: Option<Value>) -> Result<Self, RpcError> {
+        let root = ClientCli::command();
+        let mut args = vec![root.get_name().to_string()];
+        let mut command = &root;
+        for name in method.split('.') {
+            command = command
+                .find_subcommand(name)
+                .ok_or_else(|| RpcError::method_not_found(method))?;
+            args.push(name.to_string());
+        }
+        if command.has_subcommands() {
+            return Err(RpcError::method_not_found(method));
+        }
+
+        let params = match params {
+            None | Some(Value::Null) => Default::default(),
+            Some(Value::Object(params)) => params,
+            Some(_) => return Err(RpcError::invalid_params("Params must be an object")),
+        };
+        for (key, value) in params {
+            let long = key.replace('_', "-");
+            let arg = command
+                .get_arguments()
+                .find(|arg| arg.get_long() == Some(long.as_str()))
+                .ok_or_else(|| RpcError::invalid_params(format!("Unknown param: {key}")))?;
+
+            match value {
+                Value::Null => (),
+                Value::Bool(set) if !arg.get_action().takes_values() => {
+                    if set {
+                        args.push(format!("--{long}"));
+                    }
+                }
+                Value::Bool(_) | Value::Number(_) | Value::String(_)
+                    if arg.get_action().takes_values() =>
+                {
+                    let value = match value {
+                        Value::String(value) => value,
+                        value => value.to_string(),
+                    };
+                    args.push(format!("--{long}={value}"));
+                }
+                _ => {
+                    return Err(RpcError::invalid_params(format!(
+                        "Invalid value for param {key}: {value}"
+                    )))
+                }
+            }
+        }
+
+        Self::try_parse_from(args)
+            .map_err(|e| RpcError::invalid_params(e.to_string().trim_end().to_string()))
+    }
+}
+
+/// Every method & its params
+pub fn methods() -> Vec<MethodInfo> {
+    fn collect(command: &Command, prefix: Option<&str>, methods: &mut Vec<MethodInfo>) {
+        for subcommand in command.get_subcommands() {
+            let method = match prefix {
+                Some(prefix) => format!("{prefix}.{}", subcommand.get_name()),
+                None => subcommand.get_name().to_string(),
+            };
+
+            if subcommand.has_subcommands() {
+                collect(subcommand, Some(&method), methods);
+                continue;
+            }
+
+            let params = subcommand
+                .get_arguments()
+                .filter_map(|arg| {
+                    let name = arg.get_long()?.replace('-', "_");
+                    Some(ParamInfo {
+                        name,
+                        description: arg.get_help().map(ToString::to_string),
+                        required: arg.is_required_set(),
+                        flag: !arg.get_action().takes_values(),
+                        default: arg
+                            .get_default_values()
+                            .first()
+                            .map(|value| value.to_string_lossy().to_string()),
+                    })
+                })
+                .collect();
+            methods.push(MethodInfo {
+                method,
+                description: subcommand.get_about().map(ToString::to_string),
+                params,
+            });
+        }
+    }
+
+    let mut methods = vec![];
+    collect(&ClientCli::command(), None, &mut methods);
+    methods
+}
+
+/// `BestTip` -> `best-tip`, as clap names subcommands
+fn kebab_case(variant: &str) -> String {
+    let mut name = String::new();
+    for (n, c) in variant.chars().enumerate() {
+        if c.is_uppercase() && n > 0 {
+            name.push('-');
+        }
+        name.push(c.to_ascii_lowercase());
+    }
+    name
+}
@@ -3,18 +3,25 @@ use crate::{
         self,
         parser::BlockParser,
         precomputed::{PcbVersion, PrecomputedBlock},
+        store::BlockStore,
         BlockHash,
     },
     chain::{chain_id, ChainId, Network},
     constants::*,
+    event::{
+        broadcast::{broadcast_events_since, EventSender},
+        store::EventStore,
+    },
     ledger::{
         genesis::{GenesisConstants, GenesisLedger},
         staking::{self, StakingLedger},
         store::LedgerStore,
     },
-    state::{IndexerState, IndexerStateConfig},
+    metrics::{metrics, IngestedBlockKind},
+    networks::{IndexedNetwork, IndexedNetworks},
+    state::{IndexerPhase, IndexerState, IndexerStateConfig},
     store::IndexerStore,
-    unix_socket_server::{create_socket_listener, handle_connection},
+    unix_socket_server::{create_socket_listener, handle_network_connections},
 };
 use log::{debug, error, info, trace};
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
@@ -26,6 +33,7 @@ use std::{
     path::{Path, PathBuf},
     process,
     sync::Arc,
+    time::Instant,
 };
 use tokio::{
     runtime::Handle,
@@ -43,6 +51,10 @@ pub struct IndexerVersion {
 
 #[derive(Clone, Debug)]
 pub struct IndexerConfiguration {
+    pub network: Network,
+
+    /// Name queries select the network by
+    pub network_name: String,
     pub genesis_ledger: GenesisLedger,
     pub genesis_hash: BlockHash,
     pub genesis_constants: GenesisConstants,
@@ -53,6 +65,7 @@ pub struct IndexerConfiguration {
     pub prune_interval: u32,
     pub canonical_threshold: u32,
     pub canonical_update_threshold: u32,
+    pub retain_full_blocks_epochs: Option<u32>,
     pub initialization_mode: InitializationMode,
     pub ledger_cadence: u32,
     pub reporting_freq: u32,
@@ -71,6 +84,16 @@ pub enum InitializationMode {
     Sync,
 }
 
+impl From<&InitializationMode> for IndexerPhase {
+    fn from(mode: &InitializationMode) -> Self {
+        match mode {
+            InitializationMode::BuildDB => Self::InitializingFromBlockDir,
+            InitializationMode::Replay => Self::Replaying,
+            InitializationMode::Sync => Self::SyncingFromDB,
+        }
+    }
+}
+
 /// Initializes indexer database
 ///
 /// The purpose of this mode is to create a known good initial
@@ -90,13 +113,22 @@ pub async fn initialize_indexer_database(
     Ok(())
 }
 
-/// Initializes witness tree, connects database, starts UDS server & runs the
-/// indexer
+/// Initializes witness tree, connects database & runs the indexer of one of
+/// the indexed networks. The primary network's indexer also starts the UDS
+/// server.
 pub async fn start_indexer(
     subsys: SubsystemHandle,
     config: IndexerConfiguration,
-    store: Arc<IndexerStore>,
+    networks: IndexedNetworks,
 ) -> anyhow::Result<()> {
+    let network_name = config.network_name.clone();
+    let IndexedNetwork {
+        store,
+        events,
+        health,
+        ..
+    } = networks.get(Some(&network_name))?;
+    let is_primary = networks.primary().name == network_name;
     let blocks_dir = config.blocks_dir.clone();
     let staking_ledgers_dir = config.staking_ledgers_dir.clone();
     let fetch_new_blocks_delay = config.fetch_new_blocks_delay;
@@ -115,7 +147,12 @@ pub async fn start_indexer(
     ));
 
     // read-only state
-    start_uds_server(&subsys, state.clone(), &domain_socket_path).await?;
+    networks.set_state(&network_name, state.clone());
+    if is_primary {
+        start_uds_server(&subsys, networks, &domain_socket_path).await?;
+        health.set_uds_server_up(true);
+    }
+    health.set_phase(IndexerPhase::Watching);
 
     // modifies the state
     let missing_block_recovery =
@@ -135,21 +172,22 @@ pub async fn start_indexer(
         missing_block_recovery,
         fetch_new_blocks,
         state.clone(),
+        events,
     )
     .await?;
 
     Ok(())
 }
 
-/// Starts UDS server with read-only state for summary
+/// Starts UDS server with the indexed networks' read-only states
 async fn start_uds_server(
     subsys: &SubsystemHandle,
-    state: Arc<RwLock<IndexerState>>,
+    networks: IndexedNetworks,
     domain_socket_path: &Path,
 ) -> anyhow::Result<()> {
     let listener = create_socket_listener(domain_socket_path);
     subsys.start(SubsystemBuilder::new("Socket Listener", {
-        move |subsys| handle_connection(listener, state, subsys)
+        move |subsys| handle_network_connections(listener, networks, subsys)
     }));
     Ok(())
 }
@@ -161,6 +199,7 @@ async fn initialize(
     info!("Initializing mina indexer database");
     let db_path = store.db_path.clone();
     let IndexerConfiguration {
+        network,
         genesis_ledger,
         genesis_hash,
         blocks_dir,
@@ -168,6 +207,7 @@ async fn initialize(
         prune_interval,
         canonical_threshold,
         canonical_update_threshold,
+        retain_full_blocks_epochs,
         initialization_mode,
         ledger_cadence,
         reporting_freq,
@@ -182,6 +222,9 @@ async fn initialize(
             error!("Failed to create blocks directory in {blocks_dir:#?}: {e}");
             process::exit(1);
         }
+
+        // pruned blocks are re-hydrated from the blocks dir
+        store.set_blocks_dir(blocks_dir)?;
     }
     if let Some(ref staking_ledgers_dir) = staking_ledgers_dir {
         if let Err(e) = fs::create_dir_all(staking_ledgers_dir) {
@@ -205,7 +248,7 @@ async fn initialize(
             .collect::<Vec<&str>>()
             .as_slice(),
     );
-    let indexer_version = IndexerVersion::new(&Network::Mainnet, &chain_id, &genesis_hash);
+    let indexer_version = IndexerVersion::new(&network, &chain_id, &genesis_hash);
     let state_config = IndexerStateConfig {
         genesis_hash: genesis_hash.clone(),
         indexer_store: store.clone(),
@@ -217,6 +260,7 @@ async fn initialize(
         canonical_update_threshold,
         ledger_cadence,
         reporting_freq,
+        retain_full_blocks_epochs,
     };
 
     let mut state = match initialization_mode {
@@ -274,6 +318,7 @@ async fn initialize(
                     canonical_update_threshold,
                     ledger_cadence,
                     reporting_freq,
+                    retain_full_blocks_epochs,
                 })
             {
                 let min_length_filter = state.replay_events(replay_state)?;
@@ -364,6 +409,7 @@ async fn run_indexer<P: AsRef<Path>>(
     missing_block_recovery: Option<MissingBlockRecoveryOptions>,
     fetch_new_blocks_opts: Option<FetchNewBlocksOptions>,
     state: Arc<RwLock<IndexerState>>,
+    events: EventSender,
 ) -> anyhow::Result<()> {
     // setup fs-based precomputed block & staking ledger watchers
     let (tx, mut rx) = mpsc::channel(4096);
@@ -412,7 +458,16 @@ async fn run_indexer<P: AsRef<Path>>(
             // watch for precomputed blocks & staking ledgers
             Some(res) = rx.recv() => {
                 match res {
-                    Ok(event) => process_event(event, &state).await?,
+                    Ok(event) => {
+                        let store = state.read().await.indexer_store.clone();
+                        let seq_num = store.as_ref().map(|store| store.get_next_seq_num()).transpose()?;
+                        process_event(event, &state).await?;
+
+                        // broadcast the events recorded while processing
+                        if let (Some(store), Some(seq_num)) = (store, seq_num) {
+                            broadcast_events_since(&store, &events, seq_num)?;
+                        }
+                    }
                     Err(e) => {
                         error!("Filesystem watcher error: {e}");
                         break;
@@ -484,10 +539,15 @@ async fn process_event(event: Event, state: &Arc<RwLock<IndexerState>>) -> anyho
                         }
 
                         // if the block isn't in the witness tree, pipeline it
+                        let block_time = Instant::now();
                         match state.block_pipeline(&block, path.metadata()?.len()) {
                             Ok(is_added) => {
                                 if is_added {
-                                    info!("Added block {}", block.summary())
+                                    info!("Added block {}", block.summary());
+                                    metrics().observe_block_ingestion(
+                                        IngestedBlockKind::Recent,
+                                        block_time.elapsed(),
+                                    );
                                 }
                             }
                             Err(e) => error!("Error adding block: {e}"),
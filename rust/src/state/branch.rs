@@ -2,6 +2,7 @@ use crate::{
     block::{precomputed::PrecomputedBlock, vrf_output::VrfOutput, Block, BlockHash},
     constants::*,
 };
+use anyhow::Context;
 use id_tree::{
     InsertBehavior::{AsRoot, UnderNode},
     LevelOrderTraversalIds,
@@ -56,6 +57,48 @@ impl Branch {
         Self { root, branches }
     }
 
+    /// Rebuilds a branch from its blocks in level order, see [Branch::blocks]
+    pub fn from_blocks(blocks: Vec<Block>) -> anyhow::Result<Self> {
+        let mut blocks = blocks.into_iter();
+        let root_block = blocks.next().context("Branch has no root block")?;
+        let mut node_ids = HashMap::new();
+        let mut branches = Tree::new();
+        let root_state_hash = root_block.state_hash.clone();
+        let root = branches.insert(Node::new(root_block), AsRoot)?;
+        node_ids.insert(root_state_hash, root.clone());
+
+        for block in blocks {
+            let parent_id = node_ids
+                .get(&block.parent_hash)
+                .with_context(|| format!("Parent missing from branch: {}", block.summary()))?
+                .clone();
+            let state_hash = block.state_hash.clone();
+            let node_id = branches.insert(Node::new(block), UnderNode(&parent_id))?;
+            node_ids.insert(state_hash, node_id);
+        }
+        Ok(Self { root, branches })
+    }
+
+    /// Returns the branch's blocks in level order, i.e. parents precede their
+    /// children
+    pub fn blocks(&self) -> Vec<Block> {
+        self.traverse_level_order_ids()
+            .map(|node_id| {
+                self.branches
+                    .get(&node_id)
+                    .expect("node_id from iterator")
+                    .data()
+                    .clone()
+            })
+            .collect()
+    }
+
+    /// Returns the node id of the block with the given state hash
+    pub fn node_id(&self, state_hash: &BlockHash) -> Option<NodeId> {
+        self.traverse_level_order_ids()
+            .find(|node_id| &self.branches.get(node_id).unwrap().data().state_hash == state_hash)
+    }
+
     pub fn is_empty(&self) -> bool {
         self.branches.height() == 0
     }
@@ -0,0 +1,36 @@
+use crate::{
+    block::{Block, BlockHash},
+    ledger::diff::LedgerDiff,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Persisted witness tree, restored at startup instead of rebuilding the
+/// witness tree from the event log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WitnessTreeCheckpoint {
+    /// Next event sequence number when the checkpoint was taken, i.e. only
+    /// events from this one on need to be replayed
+    pub seq_num: u32,
+
+    /// Root branch blocks in level order
+    pub root_branch: Vec<Block>,
+
+    /// Dangling branches' blocks in level order
+    pub dangling_branches: Vec<Vec<Block>>,
+
+    /// Best tip of the root branch
+    pub best_tip: BlockHash,
+
+    /// Canonical root of the root branch
+    pub canonical_root: BlockHash,
+
+    /// Ledger diffs following the canonical root
+    pub diffs_map: HashMap<BlockHash, LedgerDiff>,
+
+    /// Number of blocks added to the witness tree
+    pub blocks_processed: u32,
+
+    /// Number of block bytes added to the witness tree
+    pub bytes_processed: u64,
+}
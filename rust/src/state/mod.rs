@@ -1,4 +1,6 @@
 pub mod branch;
+pub mod checkpoint;
+pub mod store;
 pub mod summary;
 
 use crate::{
@@ -12,15 +14,18 @@ use crate::{
     canonicity::{store::CanonicityStore, Canonicity},
     chain::store::ChainStore,
     constants::*,
-    event::{db::*, store::*, witness_tree::*, IndexerEvent},
+    event::{checkpoint::EventCheckpoint, db::*, store::*, witness_tree::*, IndexerEvent},
     ledger::{
         diff::LedgerDiff, genesis::GenesisLedger, public_key::PublicKey,
         staking::parser::StakingLedgerParser, store::LedgerStore, username::Username, Ledger,
         LedgerHash,
     },
+    metrics::{metrics, IngestedBlockKind},
     server::IndexerVersion,
     state::{
         branch::Branch,
+        checkpoint::WitnessTreeCheckpoint,
+        store::WitnessTreeStore,
         summary::{
             DbStats, SummaryShort, SummaryVerbose, WitnessTreeSummaryShort,
             WitnessTreeSummaryVerbose,
@@ -41,7 +46,8 @@ use crate::{
 };
 use anyhow::{bail, Context};
 use id_tree::NodeId;
-use log::{debug, error, info, trace};
+use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     str::FromStr,
@@ -99,6 +105,10 @@ pub struct IndexerState {
     /// witness tree
     pub canonical_threshold: u32,
 
+    /// Number of epochs preceding the canonical root's epoch to retain full
+    /// blocks for (default: retain all full blocks)
+    pub retain_full_blocks_epochs: Option<u32>,
+
     /// Number of blocks added to the witness tree
     pub blocks_processed: u32,
 
@@ -120,7 +130,7 @@ pub struct Tip {
     pub node_id: NodeId,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IndexerPhase {
     InitializingFromBlockDir,
     SyncingFromDB,
@@ -156,6 +166,7 @@ pub struct IndexerStateConfig {
     pub canonical_update_threshold: u32,
     pub ledger_cadence: u32,
     pub reporting_freq: u32,
+    pub retain_full_blocks_epochs: Option<u32>,
 }
 
 impl IndexerStateConfig {
@@ -177,6 +188,7 @@ impl IndexerStateConfig {
             canonical_update_threshold: CANONICAL_UPDATE_THRESHOLD,
             ledger_cadence: LEDGER_CADENCE,
             reporting_freq: BLOCK_REPORTING_FREQ_NUM,
+            retain_full_blocks_epochs: None,
         }
     }
 }
@@ -261,6 +273,7 @@ impl IndexerState {
             prune_interval: config.prune_interval,
             canonical_threshold: config.canonical_threshold,
             canonical_update_threshold: config.canonical_update_threshold,
+            retain_full_blocks_epochs: config.retain_full_blocks_epochs,
             blocks_processed: 1, // genesis block
             bytes_processed: genesis_bytes,
             genesis_bytes,
@@ -293,6 +306,7 @@ impl IndexerState {
             prune_interval: config.prune_interval,
             canonical_threshold: config.canonical_threshold,
             canonical_update_threshold: config.canonical_update_threshold,
+            retain_full_blocks_epochs: config.retain_full_blocks_epochs,
             blocks_processed: 0, // no genesis block included
             genesis_bytes: 0,
             bytes_processed: 0,
@@ -351,6 +365,7 @@ impl IndexerState {
             prune_interval: PRUNE_INTERVAL_DEFAULT,
             canonical_threshold: MAINNET_CANONICAL_THRESHOLD,
             canonical_update_threshold: CANONICAL_UPDATE_THRESHOLD,
+            retain_full_blocks_epochs: None,
             blocks_processed: 1, // root block
             bytes_processed: root_block_bytes,
             genesis_bytes: root_block_bytes,
@@ -392,6 +407,7 @@ impl IndexerState {
                 if let Some((ParsedBlock::DeepCanonical(block), block_bytes)) =
                     block_parser.next_block().await?
                 {
+                    let block_time = Instant::now();
                     let state_hash = block.state_hash();
                     self.bytes_processed += block_bytes;
 
@@ -426,6 +442,10 @@ impl IndexerState {
                         };
                         self.canonical_root = self.best_tip.clone();
                     }
+                    metrics().observe_block_ingestion(
+                        IngestedBlockKind::DeepCanonical,
+                        block_time.elapsed(),
+                    );
                 } else {
                     bail!("Block unexpectedly missing");
                 }
@@ -482,16 +502,24 @@ impl IndexerState {
                             self.report_progress(block_parser, step_time, total_time)?;
                             step_time = Instant::now();
 
-                            match parsed_block {
-                                ParsedBlock::DeepCanonical(block) | ParsedBlock::Recent(block) => {
+                            let kind = match parsed_block {
+                                ParsedBlock::DeepCanonical(block) => {
+                                    info!("Adding block to witness tree {}", block.summary());
+                                    self.block_pipeline(&block, block_bytes)?;
+                                    IngestedBlockKind::DeepCanonical
+                                }
+                                ParsedBlock::Recent(block) => {
                                     info!("Adding block to witness tree {}", block.summary());
                                     self.block_pipeline(&block, block_bytes)?;
+                                    IngestedBlockKind::Recent
                                 }
                                 ParsedBlock::Orphaned(block) => {
                                     trace!("Adding orphaned block to store {}", block.summary());
                                     self.add_block_to_store(&block, block_bytes, true)?;
+                                    IngestedBlockKind::Orphaned
                                 }
-                            }
+                            };
+                            metrics().observe_block_ingestion(kind, step_time.elapsed());
                         }
                         Ok(None) => {
                             info!(
@@ -554,6 +582,7 @@ impl IndexerState {
                 self.add_canonical_block_to_store(block, &block.genesis_state_hash, None)
                     .unwrap()
             });
+            metrics().set_witness_tree(self.len(), self.dangling_branches.len());
         }
 
         Ok(true)
@@ -806,6 +835,7 @@ impl IndexerState {
 
             self.root_branch
                 .prune_transition_frontier(k, &best_tip_block);
+            self.checkpoint_witness_tree()?;
         }
         Ok(canonical_event)
     }
@@ -834,12 +864,37 @@ impl IndexerState {
             self.update_ledger(&new_canonical_blocks)?;
             self.update_ledger_store(&new_canonical_blocks)?;
             self.prune_diffs_map(&old_canonical_root_id)?;
+            self.prune_full_blocks()?;
 
             return Ok(new_canonical_blocks);
         }
         Ok(vec![])
     }
 
+    /// Compacts full blocks older than the retention window, i.e. blocks
+    /// below the canonical root from epochs preceding the canonical root's
+    /// epoch by more than `retain_full_blocks_epochs`
+    fn prune_full_blocks(&self) -> anyhow::Result<()> {
+        if let (Some(retain_epochs), Some(indexer_store)) =
+            (self.retain_full_blocks_epochs, self.indexer_store.as_ref())
+        {
+            let canonical_root = self.canonical_root_block();
+            if let Some(epoch) = indexer_store.get_block_epoch(&canonical_root.state_hash)? {
+                let num_pruned = indexer_store.prune_blocks(
+                    epoch.saturating_sub(retain_epochs),
+                    canonical_root.blockchain_length,
+                )?;
+                if num_pruned > 0 {
+                    info!(
+                        "Pruned {num_pruned} full blocks from epochs before {}",
+                        epoch.saturating_sub(retain_epochs)
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn is_canonical_updatable(&self) -> bool {
         self.best_tip_block().height - self.canonical_root_block().height
             >= self.canonical_update_threshold
@@ -1010,10 +1065,27 @@ impl IndexerState {
 
     /// Sync from an existing db
     ///
-    /// Short-circuits adding blocks to the witness tree by rooting the
-    /// witness tree at the most recent deep canonical block and only adding
-    /// the successive blocks
+    /// Restores the witness tree from the most recent checkpoint & only adds
+    /// the blocks since. Without a checkpoint, short-circuits adding blocks to
+    /// the witness tree by rooting the witness tree at the most recent deep
+    /// canonical block and only adding the successive blocks
     pub fn sync_from_db(&mut self) -> anyhow::Result<Option<u32>> {
+        if let Some(seq_num) = self.restore_witness_tree()? {
+            let indexer_store = self.indexer_store_or_panic().clone();
+            for block in new_blocks_since(&indexer_store, seq_num)? {
+                debug!("Sync: add block {}", block.summary());
+                self.add_block_to_witness_tree(&block, false)?;
+            }
+
+            self.staking_ledgers = staking_ledgers_from_store(&indexer_store);
+            self.blocks_processed = indexer_store.get_block_production_total_count()?;
+            self.bytes_processed = indexer_store
+                .database
+                .get(indexer_store.fixed_key(IndexerStore::NUM_BLOCK_BYTES_PROCESSED))?
+                .map_or(self.genesis_bytes, from_u64_be_bytes);
+            return Ok(Some(self.root_branch.root_block().blockchain_length));
+        }
+
         let mut min_length_filter = None;
         let mut witness_tree_blocks = vec![];
         let mut staking_ledgers = HashMap::new();
@@ -1083,18 +1155,7 @@ impl IndexerState {
                         });
 
                     // collect staking ledger data
-                    for (key, _) in indexer_store
-                        .staking_ledger_epoch_iterator(speedb::IteratorMode::End)
-                        .flatten()
-                    {
-                        if staking_ledger_epoch_key_genesis_state_hash(&key).0
-                            == MAINNET_GENESIS_HASH
-                        {
-                            let epoch = staking_ledger_epoch_key_epoch(&key);
-                            let ledger_hash = staking_ledger_epoch_key_ledger_hash(&key);
-                            staking_ledgers.insert(epoch, ledger_hash);
-                        }
-                    }
+                    staking_ledgers = staking_ledgers_from_store(indexer_store);
                 } else {
                     panic!("Fatal sync error: block missing from db {state_hash}")
                 }
@@ -1121,7 +1182,7 @@ impl IndexerState {
             self.blocks_processed = indexer_store.get_block_production_total_count()?;
             self.bytes_processed = indexer_store
                 .database
-                .get(IndexerStore::NUM_BLOCK_BYTES_PROCESSED)?
+                .get(indexer_store.fixed_key(IndexerStore::NUM_BLOCK_BYTES_PROCESSED))?
                 .map_or(self.genesis_bytes, from_u64_be_bytes);
         } else {
             panic!("Fatal sync error: no indexer store");
@@ -1137,11 +1198,30 @@ impl IndexerState {
     }
 
     /// Replay events on a mutable state
+    ///
+    /// Restores the witness tree from the most recent checkpoint & only
+    /// replays the events since
     pub fn replay_events(&mut self, state: &Self) -> anyhow::Result<Option<u32>> {
         let mut min_length_filter = None;
+        let mut start_seq_num = 0;
+        if let Some(seq_num) = self.restore_witness_tree()? {
+            min_length_filter = Some(self.canonical_root_block().blockchain_length);
+            start_seq_num = seq_num;
+        }
+
         if let Some(indexer_store) = state.indexer_store.as_ref() {
+            // compacted events can only be skipped via a checkpoint
+            if let Some(first_seq_num) = indexer_store.get_first_seq_num()? {
+                if first_seq_num > start_seq_num {
+                    bail!("Event log compacted below event {first_seq_num}, but no witness tree checkpoint was restored")
+                }
+            }
+
             indexer_store
-                .event_log_iterator(speedb::IteratorMode::Start)
+                .event_log_iterator(speedb::IteratorMode::From(
+                    &start_seq_num.to_be_bytes(),
+                    speedb::Direction::Forward,
+                ))
                 .flatten()
                 .for_each(|(_, bytes)| {
                     if let Ok(ref event) = serde_json::from_slice(&bytes[5..]) {
@@ -1327,6 +1407,123 @@ impl IndexerState {
         panic!("Fatal: no staking ledger with hash {ledger_hash} in store");
     }
 
+    /// Snapshot of the witness tree as of the current event log position
+    pub fn witness_tree_checkpoint(&self) -> anyhow::Result<WitnessTreeCheckpoint> {
+        let seq_num = match self.indexer_store.as_ref() {
+            Some(indexer_store) => indexer_store.get_next_seq_num()?,
+            None => 0,
+        };
+        Ok(WitnessTreeCheckpoint {
+            seq_num,
+            root_branch: self.root_branch.blocks(),
+            dangling_branches: self.dangling_branches.iter().map(Branch::blocks).collect(),
+            best_tip: self.best_tip.state_hash.clone(),
+            canonical_root: self.canonical_root.state_hash.clone(),
+            diffs_map: self.diffs_map.clone(),
+            blocks_processed: self.blocks_processed,
+            bytes_processed: self.bytes_processed,
+        })
+    }
+
+    /// Persist the witness tree to the underlying store & record the
+    /// corresponding event log checkpoint
+    pub fn checkpoint_witness_tree(&self) -> anyhow::Result<()> {
+        if let Some(indexer_store) = self.indexer_store.as_ref() {
+            let checkpoint = self.witness_tree_checkpoint()?;
+            debug!("Witness tree checkpoint at event {}", checkpoint.seq_num);
+            indexer_store.set_witness_tree_checkpoint(&checkpoint)?;
+
+            let best_tip = self.best_tip_block();
+            let canonical_root = self.canonical_root_block();
+            indexer_store.add_event_checkpoint(&EventCheckpoint {
+                seq_num: checkpoint.seq_num,
+                best_tip: best_tip.state_hash.clone(),
+                best_tip_height: best_tip.blockchain_length,
+                canonical_root: canonical_root.state_hash.clone(),
+                canonical_root_height: canonical_root.blockchain_length,
+                blocks_processed: self.blocks_processed,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Restore the witness tree from the most recent checkpoint
+    ///
+    /// Returns the sequence number of the first event to replay if a valid
+    /// checkpoint was restored
+    pub fn restore_witness_tree(&mut self) -> anyhow::Result<Option<u32>> {
+        let indexer_store = self.indexer_store_or_panic().clone();
+        match indexer_store
+            .get_witness_tree_checkpoint()
+            .and_then(|checkpoint| match checkpoint {
+                Some(checkpoint) => self.restore_witness_tree_checkpoint(checkpoint).map(Some),
+                None => Ok(None),
+            }) {
+            Ok(Some(seq_num)) => {
+                info!(
+                    "Restored witness tree checkpoint (best tip {}, canonical root {})",
+                    self.best_tip_block().summary(),
+                    self.canonical_root_block().summary(),
+                );
+                Ok(Some(seq_num))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => {
+                warn!("Unable to restore witness tree checkpoint, replaying events: {e}");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Only modifies the state if the entire checkpoint is valid
+    fn restore_witness_tree_checkpoint(
+        &mut self,
+        checkpoint: WitnessTreeCheckpoint,
+    ) -> anyhow::Result<u32> {
+        let root_branch = Branch::from_blocks(checkpoint.root_branch)?;
+        let dangling_branches = checkpoint
+            .dangling_branches
+            .into_iter()
+            .map(Branch::from_blocks)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let best_tip = Tip {
+            node_id: root_branch.node_id(&checkpoint.best_tip).with_context(|| {
+                format!("Best tip missing from root branch {}", checkpoint.best_tip)
+            })?,
+            state_hash: checkpoint.best_tip,
+        };
+        let canonical_root = Tip {
+            node_id: root_branch
+                .node_id(&checkpoint.canonical_root)
+                .with_context(|| {
+                    format!(
+                        "Canonical root missing from root branch {}",
+                        checkpoint.canonical_root
+                    )
+                })?,
+            state_hash: checkpoint.canonical_root,
+        };
+        let ledger = self
+            .indexer_store_or_panic()
+            .get_ledger_state_hash(&canonical_root.state_hash, false)?
+            .with_context(|| {
+                format!(
+                    "Canonical root ledger missing {}",
+                    canonical_root.state_hash
+                )
+            })?;
+
+        self.root_branch = root_branch;
+        self.dangling_branches = dangling_branches;
+        self.best_tip = best_tip;
+        self.canonical_root = canonical_root;
+        self.ledger = ledger;
+        self.diffs_map = checkpoint.diffs_map;
+        self.blocks_processed = checkpoint.blocks_processed;
+        self.bytes_processed = checkpoint.bytes_processed;
+        Ok(checkpoint.seq_num)
+    }
+
     fn indexer_store_or_panic(&self) -> &Arc<IndexerStore> {
         match self.indexer_store.as_ref() {
             Some(store) => store,
@@ -1600,6 +1797,50 @@ fn is_reverse_extension(branch: &Branch, precomputed_block: &PrecomputedBlock) -
     precomputed_block.state_hash() == branch.root_block().parent_hash
 }
 
+/// Blocks of the new block events from `seq_num` on
+fn new_blocks_since(
+    indexer_store: &IndexerStore,
+    seq_num: u32,
+) -> anyhow::Result<Vec<PrecomputedBlock>> {
+    let mut blocks = vec![];
+    for (_, bytes) in indexer_store
+        .event_log_iterator(speedb::IteratorMode::From(
+            &seq_num.to_be_bytes(),
+            speedb::Direction::Forward,
+        ))
+        .flatten()
+    {
+        if bytes[4] != IndexerEvent::NEW_BLOCK_KIND {
+            continue;
+        }
+        if let IndexerEvent::Db(DbEvent::Block(DbBlockEvent::NewBlock { state_hash, .. })) =
+            serde_json::from_slice(&bytes[5..])?
+        {
+            match indexer_store.get_block(&state_hash)? {
+                Some((block, _)) => blocks.push(block),
+                None => bail!("Fatal sync error: block missing from db {state_hash}"),
+            }
+        }
+    }
+    Ok(blocks)
+}
+
+/// Mainnet staking ledger hashes via epoch
+fn staking_ledgers_from_store(indexer_store: &IndexerStore) -> HashMap<u32, LedgerHash> {
+    let mut staking_ledgers = HashMap::new();
+    for (key, _) in indexer_store
+        .staking_ledger_epoch_iterator(speedb::IteratorMode::End)
+        .flatten()
+    {
+        if staking_ledger_epoch_key_genesis_state_hash(&key).0 == MAINNET_GENESIS_HASH {
+            let epoch = staking_ledger_epoch_key_epoch(&key);
+            let ledger_hash = staking_ledger_epoch_key_ledger_hash(&key);
+            staking_ledgers.insert(epoch, ledger_hash);
+        }
+    }
+    staking_ledgers
+}
+
 impl std::fmt::Display for IndexerState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "=== Root branch ===")?;
@@ -0,0 +1,10 @@
+use super::checkpoint::WitnessTreeCheckpoint;
+
+pub trait WitnessTreeStore {
+    /// Persist the witness tree checkpoint, replacing any previous ones
+    fn set_witness_tree_checkpoint(&self, checkpoint: &WitnessTreeCheckpoint)
+        -> anyhow::Result<()>;
+
+    /// Get the most recent witness tree checkpoint
+    fn get_witness_tree_checkpoint(&self) -> anyhow::Result<Option<WitnessTreeCheckpoint>>;
+}
@@ -124,20 +124,20 @@ impl AccountStore for IndexerStore {
             Greater => {
                 let old = self
                     .database
-                    .get(Self::TOTAL_NUM_ACCOUNTS_KEY)?
+                    .get(self.fixed_key(Self::TOTAL_NUM_ACCOUNTS_KEY))?
                     .map_or(0, from_be_bytes);
                 self.database.put(
-                    Self::TOTAL_NUM_ACCOUNTS_KEY,
+                    self.fixed_key(Self::TOTAL_NUM_ACCOUNTS_KEY),
                     old.saturating_add(adjust.unsigned_abs()).to_be_bytes(),
                 )?;
             }
             Less => {
                 let old = self
                     .database
-                    .get(Self::TOTAL_NUM_ACCOUNTS_KEY)?
+                    .get(self.fixed_key(Self::TOTAL_NUM_ACCOUNTS_KEY))?
                     .map_or(0, from_be_bytes);
                 self.database.put(
-                    Self::TOTAL_NUM_ACCOUNTS_KEY,
+                    self.fixed_key(Self::TOTAL_NUM_ACCOUNTS_KEY),
                     old.saturating_sub(adjust.unsigned_abs()).to_be_bytes(),
                 )?;
             }
@@ -148,7 +148,7 @@ impl AccountStore for IndexerStore {
     fn get_num_accounts(&self) -> anyhow::Result<Option<u32>> {
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_ACCOUNTS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_ACCOUNTS_KEY))?
             .map(from_be_bytes))
     }
 
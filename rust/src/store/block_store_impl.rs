@@ -2,6 +2,7 @@ use super::{column_families::ColumnFamilyHelpers, fixed_keys::FixedKeys};
 use crate::{
     block::{
         precomputed::{PcbVersion, PrecomputedBlock},
+        pruned::PrunedBlock,
         store::BlockStore,
         BlockComparison, BlockHash,
     },
@@ -9,6 +10,7 @@ use crate::{
     command::{internal::store::InternalCommandStore, store::UserCommandStore},
     event::{db::*, store::EventStore, IndexerEvent},
     ledger::{diff::LedgerDiff, public_key::PublicKey, store::LedgerStore},
+    metrics::metrics,
     snark_work::store::SnarkStore,
     store::{
         account::{AccountBalanceUpdate, AccountStore},
@@ -19,9 +21,12 @@ use crate::{
     },
 };
 use anyhow::{bail, Context};
-use log::{error, trace};
+use log::{debug, error, trace, warn};
 use speedb::{DBIterator, Direction, IteratorMode};
-use std::mem::size_of;
+use std::{
+    mem::size_of,
+    path::{Path, PathBuf},
+};
 
 impl BlockStore for IndexerStore {
     /// Add the given block at its indices and record a db event
@@ -41,7 +46,8 @@ impl BlockStore for IndexerStore {
             self.database
                 .get_pinned_cf(self.blocks_cf(), state_hash.0.as_bytes()),
             Ok(Some(_))
-        ) {
+        ) || self.is_block_pruned(&state_hash)?
+        {
             trace!("Block already present {}", block.summary());
             return Ok(None);
         }
@@ -128,10 +134,10 @@ impl BlockStore for IndexerStore {
         // increment bytes processed
         let bytes_processed = self
             .database
-            .get(Self::NUM_BLOCK_BYTES_PROCESSED)?
+            .get(self.fixed_key(Self::NUM_BLOCK_BYTES_PROCESSED))?
             .map_or(0, from_u64_be_bytes);
         self.database.put(
-            Self::NUM_BLOCK_BYTES_PROCESSED,
+            self.fixed_key(Self::NUM_BLOCK_BYTES_PROCESSED),
             (bytes_processed + num_block_bytes).to_be_bytes(),
         )?;
 
@@ -146,7 +152,7 @@ impl BlockStore for IndexerStore {
 
     fn get_block(&self, state_hash: &BlockHash) -> anyhow::Result<Option<(PrecomputedBlock, u64)>> {
         trace!("Getting block {state_hash}");
-        Ok(self
+        if let Some(block) = self
             .database
             .get_pinned_cf(self.blocks_cf(), state_hash.0.as_bytes())?
             .and_then(|bytes| {
@@ -154,7 +160,148 @@ impl BlockStore for IndexerStore {
                     .with_context(|| format!("{:?}", bytes.to_vec()))
                     .ok()
                     .map(|block| (block, from_u64_be_bytes(bytes[..size_of::<u64>()].to_vec())))
-            }))
+            })
+        {
+            return Ok(Some(block));
+        }
+        self.rehydrate_block(state_hash)
+    }
+
+    ///////////////////
+    // Block pruning //
+    ///////////////////
+
+    fn set_blocks_dir(&self, blocks_dir: &Path) -> anyhow::Result<()> {
+        trace!("Setting blocks dir {}", blocks_dir.display());
+        self.database.put(
+            self.fixed_key(Self::BLOCKS_DIR_KEY),
+            blocks_dir.display().to_string().as_bytes(),
+        )?;
+        Ok(())
+    }
+
+    fn get_blocks_dir(&self) -> anyhow::Result<Option<PathBuf>> {
+        trace!("Getting blocks dir");
+        Ok(self
+            .database
+            .get(self.fixed_key(Self::BLOCKS_DIR_KEY))?
+            .map(String::from_utf8)
+            .transpose()?
+            .map(PathBuf::from))
+    }
+
+    fn prune_block(&self, state_hash: &BlockHash) -> anyhow::Result<bool> {
+        trace!("Pruning block {state_hash}");
+        let (block, num_block_bytes) = match self
+            .database
+            .get_pinned_cf(self.blocks_cf(), state_hash.0.as_bytes())?
+        {
+            Some(bytes) => (
+                serde_json::from_slice::<PrecomputedBlock>(&bytes[size_of::<u64>()..])?,
+                from_u64_be_bytes(bytes[..size_of::<u64>()].to_vec()),
+            ),
+            None => return Ok(false),
+        };
+
+        // write the compact block before dropping the full block
+        let pruned = PrunedBlock::from_precomputed(&block, num_block_bytes);
+        self.database.put_cf(
+            self.blocks_pruned_cf(),
+            state_hash.0.as_bytes(),
+            serde_json::to_vec(&pruned)?,
+        )?;
+        self.database
+            .delete_cf(self.blocks_cf(), state_hash.0.as_bytes())?;
+        Ok(true)
+    }
+
+    fn prune_blocks(&self, epoch: u32, max_height: u32) -> anyhow::Result<u32> {
+        trace!("Pruning blocks below epoch {epoch} & height {max_height}");
+        let start_height = self
+            .database
+            .get(self.fixed_key(Self::PRUNED_BLOCKS_HEIGHT_KEY))?
+            .map_or(0, from_be_bytes);
+        let mut pruned_height = start_height;
+        let mut num_pruned = 0;
+
+        for (key, _) in self
+            .blocks_height_iterator(IteratorMode::From(
+                &to_be_bytes(start_height),
+                Direction::Forward,
+            ))
+            .flatten()
+        {
+            let height = block_u32_prefix_from_key(&key)?;
+            let state_hash = block_state_hash_from_key(&key)?;
+            if height >= max_height
+                || self
+                    .get_block_epoch(&state_hash)?
+                    .map_or(true, |block_epoch| block_epoch >= epoch)
+            {
+                break;
+            }
+
+            if self.prune_block(&state_hash)? {
+                num_pruned += 1;
+            }
+            pruned_height = height;
+        }
+
+        // all blocks below the pruned height have been pruned
+        self.database.put(
+            self.fixed_key(Self::PRUNED_BLOCKS_HEIGHT_KEY),
+            to_be_bytes(pruned_height),
+        )?;
+        Ok(num_pruned)
+    }
+
+    fn is_block_pruned(&self, state_hash: &BlockHash) -> anyhow::Result<bool> {
+        Ok(self
+            .database
+            .get_pinned_cf(self.blocks_pruned_cf(), state_hash.0.as_bytes())?
+            .is_some())
+    }
+
+    fn get_pruned_block(&self, state_hash: &BlockHash) -> anyhow::Result<Option<PrunedBlock>> {
+        trace!("Getting pruned block {state_hash}");
+        Ok(self
+            .database
+            .get_pinned_cf(self.blocks_pruned_cf(), state_hash.0.as_bytes())?
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok()))
+    }
+
+    fn rehydrate_block(
+        &self,
+        state_hash: &BlockHash,
+    ) -> anyhow::Result<Option<(PrecomputedBlock, u64)>> {
+        let pruned = match self.get_pruned_block(state_hash)? {
+            Some(pruned) => pruned,
+            None => return Ok(None),
+        };
+        let blocks_dir = match self.get_blocks_dir()? {
+            Some(blocks_dir) => blocks_dir,
+            None => {
+                warn!(
+                    "No blocks dir to re-hydrate pruned block {}",
+                    pruned.summary()
+                );
+                return Ok(None);
+            }
+        };
+
+        trace!("Re-hydrating pruned block {}", pruned.summary());
+        let path = pruned.file_path(&blocks_dir);
+        match PrecomputedBlock::parse_file(&path, pruned.version.clone()) {
+            Ok(block) => Ok(Some((block, pruned.num_block_bytes))),
+            Err(e) => {
+                warn!(
+                    "Unable to re-hydrate pruned block {} from {}: {e}",
+                    pruned.summary(),
+                    path.display()
+                );
+                Ok(None)
+            }
+        }
     }
 
     fn get_best_block(&self) -> anyhow::Result<Option<PrecomputedBlock>> {
@@ -169,7 +316,7 @@ impl BlockStore for IndexerStore {
         trace!("Getting best block hash");
         Ok(self
             .database
-            .get(Self::BEST_TIP_STATE_HASH_KEY)?
+            .get(self.fixed_key(Self::BEST_TIP_STATE_HASH_KEY))?
             .and_then(|bytes| BlockHash::from_bytes(&bytes).ok()))
     }
 
@@ -203,6 +350,13 @@ impl BlockStore for IndexerStore {
             // reorg updates
             // canonicity
             let canonicity_updates = self.reorg_canonicity_updates(&old, state_hash)?;
+            if let Some(reorg) = self.add_reorg(&old, state_hash, &canonicity_updates)? {
+                debug!(
+                    "Reorg of depth {} (length {}): {}",
+                    reorg.depth, reorg.new_best_tip_height, reorg.new_best_tip
+                );
+                metrics().observe_reorg(reorg.depth);
+            }
             self.update_canonicity(canonicity_updates)?;
 
             // balance-sorted accounts
@@ -215,8 +369,10 @@ impl BlockStore for IndexerStore {
         }
 
         // set new best tip
-        self.database
-            .put(Self::BEST_TIP_STATE_HASH_KEY, state_hash.0.as_bytes())?;
+        self.database.put(
+            self.fixed_key(Self::BEST_TIP_STATE_HASH_KEY),
+            state_hash.0.as_bytes(),
+        )?;
 
         // record new best tip event
         match self.get_block_height(state_hash)? {
@@ -549,6 +705,38 @@ impl BlockStore for IndexerStore {
         bail!("Block missing from store {state_hash}")
     }
 
+    fn get_reorged_blocks(
+        &self,
+        previous: &BlockHash,
+        best_tip: &BlockHash,
+    ) -> anyhow::Result<Vec<BlockHash>> {
+        trace!("Getting reorged blocks {previous} -> {best_tip}");
+        let height = |state_hash: &BlockHash| {
+            self.get_block_height(state_hash)?
+                .with_context(|| format!("Block height missing from store {state_hash}"))
+        };
+        let parent = |state_hash: &BlockHash| {
+            self.get_block_parent_hash(state_hash)?
+                .with_context(|| format!("Block parent missing from store {state_hash}"))
+        };
+
+        // walk both chains back to their common ancestor
+        let mut reorged = vec![];
+        let (mut previous, mut previous_height) = (previous.clone(), height(previous)?);
+        let (mut best_tip, mut best_tip_height) = (best_tip.clone(), height(best_tip)?);
+        while previous != best_tip {
+            if previous_height >= best_tip_height {
+                let parent = parent(&previous)?;
+                reorged.push(std::mem::replace(&mut previous, parent));
+                previous_height -= 1;
+            } else {
+                best_tip = parent(&best_tip)?;
+                best_tip_height -= 1;
+            }
+        }
+        Ok(reorged)
+    }
+
     fn get_block_version(&self, state_hash: &BlockHash) -> anyhow::Result<Option<PcbVersion>> {
         trace!("Getting block version {state_hash}");
         let key = state_hash.0.as_bytes();
@@ -750,8 +938,10 @@ impl BlockStore for IndexerStore {
 
         // increment total count
         let acc = self.get_block_production_total_count()?;
-        self.database
-            .put(Self::TOTAL_NUM_BLOCKS_KEY, to_be_bytes(acc + 1))?;
+        self.database.put(
+            self.fixed_key(Self::TOTAL_NUM_BLOCKS_KEY),
+            to_be_bytes(acc + 1),
+        )?;
 
         Ok(())
     }
@@ -793,7 +983,7 @@ impl BlockStore for IndexerStore {
         trace!("Getting total block production count");
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_BLOCKS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_BLOCKS_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -871,7 +1061,7 @@ impl BlockStore for IndexerStore {
         trace!("Getting blocks via height (mode: {})", display_mode(mode));
         for (key, _) in self.blocks_height_iterator(mode).flatten() {
             let state_hash = block_state_hash_from_key(&key)?;
-            blocks.push(self.get_block(&state_hash)?.expect("PCB").0);
+            blocks.push(get_full_block(self, &state_hash)?);
         }
         Ok(blocks)
     }
@@ -907,12 +1097,25 @@ impl BlockStore for IndexerStore {
         );
         for (key, _) in self.blocks_global_slot_iterator(mode).flatten() {
             let state_hash = block_state_hash_from_key(&key)?;
-            blocks.push(self.get_block(&state_hash)?.expect("PCB").0);
+            blocks.push(get_full_block(self, &state_hash)?);
         }
         Ok(blocks)
     }
 }
 
+/// Get the full block, pruned blocks must be re-hydrated from the blocks dir
+fn get_full_block(db: &IndexerStore, state_hash: &BlockHash) -> anyhow::Result<PrecomputedBlock> {
+    match db.get_block(state_hash)? {
+        Some((block, _)) => Ok(block),
+        None if db.is_block_pruned(state_hash)? => {
+            bail!(
+                "Block {state_hash} has been pruned and its precomputed block file is unavailable"
+            )
+        }
+        None => bail!("Block missing from store {state_hash}"),
+    }
+}
+
 /// `{block height BE}{state hash}`
 fn block_height_key(block: &PrecomputedBlock) -> Vec<u8> {
     let mut key = to_be_bytes(block.blockchain_length());
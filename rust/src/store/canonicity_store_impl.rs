@@ -1,14 +1,20 @@
 use super::{column_families::ColumnFamilyHelpers, fixed_keys::FixedKeys};
 use crate::{
     block::{store::BlockStore, BlockHash},
-    canonicity::{store::CanonicityStore, Canonicity, CanonicityDiff, CanonicityUpdate},
-    constants::MAINNET_GENESIS_HASH,
+    canonicity::{
+        reorg::{Reorg, ReorgBlock, ReorgFilter, ReorgStats},
+        store::CanonicityStore,
+        Canonicity, CanonicityDiff, CanonicityUpdate,
+    },
+    command::signed::SignedCommandWithData,
+    constants::{MAINNET_GENESIS_HASH, MAINNET_TRANSITION_FRONTIER_K},
     event::{db::*, store::EventStore, IndexerEvent},
     snark_work::store::SnarkStore,
-    store::{to_be_bytes, DBUpdate, IndexerStore},
+    store::{from_be_bytes, to_be_bytes, DBUpdate, IndexerStore},
 };
 use anyhow::Context;
-use log::trace;
+use log::{trace, warn};
+use speedb::IteratorMode;
 
 impl CanonicityStore for IndexerStore {
     fn add_canonical_block(
@@ -39,6 +45,15 @@ impl CanonicityStore for IndexerStore {
             state_hash.0.as_bytes(),
         )?;
 
+        // canonical root height
+        if self
+            .get_max_canonical_blockchain_length()?
+            .map_or(true, |max_height| height > max_height)
+        {
+            self.database
+                .put(self.fixed_key(Self::MAX_CANONICAL_KEY), to_be_bytes(height))?;
+        }
+
         // update top snarkers based on the incoming canonical block
         if let Some(completed_works) = self.get_snark_work_in_block(state_hash)? {
             self.update_top_snarkers(completed_works)?;
@@ -57,14 +72,14 @@ impl CanonicityStore for IndexerStore {
                 // add genesis state hash
                 genesis_state_hashes.push(genesis_state_hash.clone());
                 self.database.put(
-                    Self::KNOWN_GENESIS_STATE_HASHES_KEY,
+                    self.fixed_key(Self::KNOWN_GENESIS_STATE_HASHES_KEY),
                     serde_json::to_vec(&genesis_state_hashes)?,
                 )?;
 
                 // add genesis prev state hash
                 genesis_prev_state_hashes.push(genesis_prev_state_hash.clone());
                 self.database.put(
-                    Self::KNOWN_GENESIS_PREV_STATE_HASHES_KEY,
+                    self.fixed_key(Self::KNOWN_GENESIS_PREV_STATE_HASHES_KEY),
                     serde_json::to_vec(&genesis_prev_state_hashes)?,
                 )?;
             }
@@ -84,7 +99,7 @@ impl CanonicityStore for IndexerStore {
         trace!("Getting known genesis state hashes");
         Ok(self
             .database
-            .get_pinned(Self::KNOWN_GENESIS_STATE_HASHES_KEY)?
+            .get_pinned(self.fixed_key(Self::KNOWN_GENESIS_STATE_HASHES_KEY))?
             .map_or(vec![], |bytes| {
                 serde_json::from_slice(&bytes).expect("known genesis state hashes")
             }))
@@ -94,7 +109,7 @@ impl CanonicityStore for IndexerStore {
         trace!("Getting known genesis prev state hashes");
         Ok(self
             .database
-            .get_pinned(Self::KNOWN_GENESIS_PREV_STATE_HASHES_KEY)?
+            .get_pinned(self.fixed_key(Self::KNOWN_GENESIS_PREV_STATE_HASHES_KEY))?
             .map_or(vec![], |bytes| {
                 serde_json::from_slice(&bytes).expect("known genesis prev state hashes")
             }))
@@ -132,6 +147,47 @@ impl CanonicityStore for IndexerStore {
         Ok(None)
     }
 
+    fn get_max_canonical_blockchain_length(&self) -> anyhow::Result<Option<u32>> {
+        trace!("Getting max canonical blockchain length");
+        Ok(self
+            .database
+            .get_pinned(self.fixed_key(Self::MAX_CANONICAL_KEY))?
+            .map(|bytes| from_be_bytes(bytes.to_vec())))
+    }
+
+    fn get_block_confirmations(&self, state_hash: &BlockHash) -> anyhow::Result<Option<u32>> {
+        trace!("Getting confirmations of block {state_hash}");
+        let (Some(height), Some(best_height)) = (
+            self.get_block_height(state_hash)?,
+            self.get_best_block_height()?,
+        ) else {
+            return Ok(None);
+        };
+        if self.get_canonical_hash_at_height(height)?.as_ref() != Some(state_hash) {
+            return Ok(None);
+        }
+        Ok(Some(best_height.saturating_sub(height)))
+    }
+
+    fn is_block_final(&self, state_hash: &BlockHash) -> anyhow::Result<bool> {
+        trace!("Getting finality of block {state_hash}");
+        let Some(confirmations) = self.get_block_confirmations(state_hash)? else {
+            return Ok(false);
+        };
+        if confirmations >= MAINNET_TRANSITION_FRONTIER_K {
+            return Ok(true);
+        }
+        Ok(
+            match (
+                self.get_block_height(state_hash)?,
+                self.get_max_canonical_blockchain_length()?,
+            ) {
+                (Some(height), Some(root_height)) => height <= root_height,
+                _ => false,
+            },
+        )
+    }
+
     fn reorg_canonicity_updates(
         &self,
         old_best_tip: &BlockHash,
@@ -195,6 +251,130 @@ impl CanonicityStore for IndexerStore {
         Ok(DBUpdate { apply, unapply })
     }
 
+    fn add_reorg(
+        &self,
+        old_best_tip: &BlockHash,
+        new_best_tip: &BlockHash,
+        updates: &CanonicityUpdate,
+    ) -> anyhow::Result<Option<Reorg>> {
+        // the lowest uncanonicalized block's parent is the common ancestor
+        let Some(lowest) = updates.unapply.last() else {
+            return Ok(None);
+        };
+        trace!("Adding reorg {old_best_tip} -> {new_best_tip}");
+
+        let height = |state_hash: &BlockHash| {
+            self.get_block_height(state_hash)?
+                .with_context(|| format!("Block height missing from store {state_hash}"))
+        };
+        let reorg_block = |diff: &CanonicityDiff| -> anyhow::Result<ReorgBlock> {
+            let user_commands = match self.get_block(&diff.state_hash)? {
+                Some((block, _)) => SignedCommandWithData::from_precomputed(&block)
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+                None => {
+                    warn!(
+                        "Block unavailable, skipping its reorged user commands {}",
+                        diff.state_hash
+                    );
+                    vec![]
+                }
+            };
+            Ok(ReorgBlock {
+                state_hash: diff.state_hash.clone(),
+                blockchain_length: height(&diff.state_hash)?,
+                global_slot: diff.global_slot,
+                user_commands,
+            })
+        };
+
+        let common_ancestor = self
+            .get_block_parent_hash(&lowest.state_hash)?
+            .with_context(|| format!("Block parent missing from store {}", lowest.state_hash))?;
+        let uncanonicalized = updates
+            .unapply
+            .iter()
+            .map(reorg_block)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let recanonicalized = updates
+            .apply
+            .iter()
+            .map(reorg_block)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        // indices are sequential
+        let index = self
+            .database
+            .iterator_cf(self.reorgs_cf(), IteratorMode::End)
+            .flatten()
+            .next()
+            .map_or(Ok(0), |(key, _)| -> anyhow::Result<u32> {
+                Ok(u32::from_be_bytes(key[..4].try_into()?) + 1)
+            })?;
+        let reorg = Reorg {
+            index,
+            old_best_tip: old_best_tip.clone(),
+            old_best_tip_height: height(old_best_tip)?,
+            new_best_tip: new_best_tip.clone(),
+            new_best_tip_height: height(new_best_tip)?,
+            common_ancestor_height: height(&common_ancestor)?,
+            common_ancestor,
+            depth: uncanonicalized.len() as u32,
+            epoch: self.get_block_epoch(new_best_tip)?.unwrap_or_default(),
+            uncanonicalized,
+            recanonicalized,
+        };
+        self.database.put_cf(
+            self.reorgs_cf(),
+            to_be_bytes(index),
+            serde_json::to_vec(&reorg)?,
+        )?;
+        Ok(Some(reorg))
+    }
+
+    fn get_reorg(&self, index: u32) -> anyhow::Result<Option<Reorg>> {
+        trace!("Getting reorg {index}");
+        Ok(self
+            .database
+            .get_pinned_cf(self.reorgs_cf(), to_be_bytes(index))?
+            .map(|bytes| serde_json::from_slice(&bytes))
+            .transpose()?)
+    }
+
+    fn get_reorgs(&self, filter: &ReorgFilter, limit: usize) -> anyhow::Result<Vec<Reorg>> {
+        trace!("Getting reorgs {filter:?}");
+        let mut reorgs = vec![];
+        for (_, bytes) in self
+            .database
+            .iterator_cf(self.reorgs_cf(), IteratorMode::End)
+            .flatten()
+        {
+            if reorgs.len() >= limit {
+                break;
+            }
+
+            let reorg: Reorg = serde_json::from_slice(&bytes)?;
+            if filter.matches(&reorg) {
+                reorgs.push(reorg);
+            }
+        }
+        Ok(reorgs)
+    }
+
+    fn get_reorg_stats(&self) -> anyhow::Result<ReorgStats> {
+        trace!("Getting reorg stats");
+        let mut reorgs = vec![];
+        for (_, bytes) in self
+            .database
+            .iterator_cf(self.reorgs_cf(), IteratorMode::Start)
+            .flatten()
+        {
+            reorgs.push(serde_json::from_slice(&bytes)?);
+        }
+        Ok(ReorgStats::new(&reorgs))
+    }
+
     fn update_canonicity(&self, updates: CanonicityUpdate) -> anyhow::Result<()> {
         trace!("Updating block canonicities: {updates:?}");
         // unapply canonicities
@@ -27,7 +27,8 @@ impl ChainStore for IndexerStore {
         )?;
 
         // update current chain_id
-        self.database.put(Self::CHAIN_ID_KEY, chain_bytes)?;
+        self.database
+            .put(self.fixed_key(Self::CHAIN_ID_KEY), chain_bytes)?;
         Ok(())
     }
 
@@ -50,7 +51,7 @@ impl ChainStore for IndexerStore {
         trace!("Getting chain id");
         Ok(ChainId(String::from_utf8(
             self.database
-                .get(Self::CHAIN_ID_KEY)?
+                .get(self.fixed_key(Self::CHAIN_ID_KEY))?
                 .expect("chain id should exist in database"),
         )?))
     }
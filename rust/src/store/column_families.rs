@@ -22,6 +22,9 @@ pub trait ColumnFamilyHelpers {
     /// CF for storing all blocks
     fn blocks_cf(&self) -> &ColumnFamily;
 
+    /// CF for storing compact versions of pruned blocks
+    fn blocks_pruned_cf(&self) -> &ColumnFamily;
+
     /// CF for storing block heights
     fn block_height_cf(&self) -> &ColumnFamily;
 
@@ -91,6 +94,9 @@ pub trait ColumnFamilyHelpers {
     /// CF for storing canonical state hashes by global slot
     fn canonicity_slot_cf(&self) -> &ColumnFamily;
 
+    /// CF for storing best tip reorg records
+    fn reorgs_cf(&self) -> &ColumnFamily;
+
     ////////////////////////////
     // User command store CFs //
     ////////////////////////////
@@ -150,6 +156,9 @@ pub trait ColumnFamilyHelpers {
     /// CF for sorting internal commands by global slot
     fn internal_commands_slot_cf(&self) -> &ColumnFamily;
 
+    /// CF for sorting internal commands by public key & block height
+    fn internal_commands_pk_height_sort_cf(&self) -> &ColumnFamily;
+
     //////////////////////
     // Ledger store CFs //
     //////////////////////
@@ -284,4 +293,24 @@ pub trait ColumnFamilyHelpers {
 
     /// CF for storing indexer store events by sequence number
     fn events_cf(&self) -> &ColumnFamily;
+
+    /// CF for storing event log checkpoints by sequence number
+    fn events_checkpoints_cf(&self) -> &ColumnFamily;
+
+    ////////////////////////////
+    // Witness tree store CFs //
+    ////////////////////////////
+
+    /// CF for storing witness tree checkpoints
+    fn witness_tree_cf(&self) -> &ColumnFamily;
+
+    ///////////////////////
+    // Webhook store CFs //
+    ///////////////////////
+
+    /// CF for storing registered webhooks
+    fn webhooks_cf(&self) -> &ColumnFamily;
+
+    /// CF for storing webhook delivery cursors
+    fn webhook_cursors_cf(&self) -> &ColumnFamily;
 }
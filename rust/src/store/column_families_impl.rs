@@ -8,9 +8,7 @@ impl ColumnFamilyHelpers for IndexerStore {
 
     /// `pk -> balance`
     fn account_balance_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("account-balance")
-            .expect("account-balance column family exists")
+        self.cf("account-balance")
     }
 
     /// CF for sorting account's by balance
@@ -18,16 +16,12 @@ impl ColumnFamilyHelpers for IndexerStore {
     ///
     /// - `balance`: 8 BE bytes
     fn account_balance_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("account-balance-sort")
-            .expect("account-balance-sort column family exists")
+        self.cf("account-balance-sort")
     }
 
     /// 'state_hash -> balance updates`
     fn account_balance_updates_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("account-balance-updates")
-            .expect("account-balance-updates column family exists")
+        self.cf("account-balance-updates")
     }
 
     /////////////////////
@@ -38,16 +32,19 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// ```
     /// state_hash -> {num block bytes BE u64 bytes}{serde_json block bytes}
     fn blocks_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-state-hash")
-            .expect("blocks-state-hash column family exists")
+        self.cf("blocks-state-hash")
+    }
+
+    /// Pruned blocks CF
+    /// ```
+    /// state_hash -> {serde_json pruned block bytes}
+    fn blocks_pruned_cf(&self) -> &ColumnFamily {
+        self.cf("blocks-pruned")
     }
 
     /// `state_hash -> pcb version`
     fn block_version_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-version")
-            .expect("blocks-version column family exists")
+        self.cf("blocks-version")
     }
 
     /// ```
@@ -58,9 +55,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - global_slot: 4 BE bytes
     /// - state_hash:  [BlockHash::LEN] bytes
     fn blocks_global_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-global-slot-sort")
-            .expect("blocks-global-slot-sort column family exists")
+        self.cf("blocks-global-slot-sort")
     }
 
     /// ```
@@ -71,87 +66,59 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - block_height: 4 BE bytes
     /// - state_hash:   [BlockHash::LEN] bytes
     fn blocks_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-height-sort")
-            .expect("blocks-height-sort column family exists")
+        self.cf("blocks-height-sort")
     }
 
     fn block_height_to_global_slots_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-height-to-slots")
-            .expect("blocks-height-to-slots column family exists")
+        self.cf("blocks-height-to-slots")
     }
 
     fn block_global_slot_to_heights_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-slot-to-heights")
-            .expect("blocks-slot-to-heights column family exists")
+        self.cf("blocks-slot-to-heights")
     }
 
     fn block_parent_hash_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-parent-hash")
-            .expect("blocks-parent-hash column family exists")
+        self.cf("blocks-parent-hash")
     }
 
     fn block_height_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-height")
-            .expect("blocks-height column family exists")
+        self.cf("blocks-height")
     }
 
     fn block_global_slot_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-global-slot")
-            .expect("blocks-global-slot column family exists")
+        self.cf("blocks-global-slot")
     }
 
     fn block_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-epoch")
-            .expect("blocks-epoch column family exists")
+        self.cf("blocks-epoch")
     }
 
     fn block_genesis_state_hash_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-genesis-hash")
-            .expect("blocks-genesis-hash column family exists")
+        self.cf("blocks-genesis-hash")
     }
 
     fn block_creator_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-creator")
-            .expect("blocks-creator column family exists")
+        self.cf("blocks-creator")
     }
 
     fn block_coinbase_receiver_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-coinbase-receiver")
-            .expect("blocks-coinbase-receiver column family exists")
+        self.cf("blocks-coinbase-receiver")
     }
 
     fn block_coinbase_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("coinbase-receiver-height-sort")
-            .expect("coinbase-receiver-height-sort column family exists")
+        self.cf("coinbase-receiver-height-sort")
     }
 
     fn block_coinbase_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("coinbase-receiver-slot-sort")
-            .expect("coinbase-receiver-slot-sort column family exists")
+        self.cf("coinbase-receiver-slot-sort")
     }
 
     fn block_creator_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-creator-height-sort")
-            .expect("block-creator-height-sort column family exists")
+        self.cf("block-creator-height-sort")
     }
 
     fn block_creator_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-creator-slot-sort")
-            .expect("block-creator-slot-sort column family exists")
+        self.cf("block-creator-slot-sort")
     }
 
     /// CF for storing blocks at a fixed height:
@@ -159,9 +126,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     ///
     /// - `list of state hashes at height`: sorted from best to worst
     fn blocks_at_height_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-at-length")
-            .expect("blocks-at-length column family exists")
+        self.cf("blocks-at-length")
     }
 
     /// CF for storing blocks at a fixed global slot:
@@ -169,15 +134,11 @@ impl ColumnFamilyHelpers for IndexerStore {
     ///
     /// - `list of state hashes at slot`: sorted from best to worst
     fn blocks_at_global_slot_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-at-slot")
-            .expect("blocks-at-slot column family exists")
+        self.cf("blocks-at-slot")
     }
 
     fn block_comparison_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-comparison")
-            .expect("blocks-comparison column family exists")
+        self.cf("blocks-comparison")
     }
 
     ////////////////////////////
@@ -185,45 +146,31 @@ impl ColumnFamilyHelpers for IndexerStore {
     ////////////////////////////
 
     fn user_commands_pk_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-pk")
-            .expect("user-commands-pk column family exists")
+        self.cf("user-commands-pk")
     }
 
     fn user_commands_pk_num_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-pk-num")
-            .expect("user-commands-pk-num column family exists")
+        self.cf("user-commands-pk-num")
     }
 
     fn user_command_state_hashes_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-command-state-hashes")
-            .expect("user-command-state-hashes column family exists")
+        self.cf("user-command-state-hashes")
     }
 
     fn user_commands_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands")
-            .expect("user-commands column family exists")
+        self.cf("user-commands")
     }
 
     fn user_commands_per_block_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-block")
-            .expect("user-commands-block column family exists")
+        self.cf("user-commands-block")
     }
 
     fn user_commands_block_order_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-block-order")
-            .expect("user-commands-block-order column family exists")
+        self.cf("user-commands-block-order")
     }
 
     fn user_commands_num_containing_blocks_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-num-blocks")
-            .expect("user-commands-num-blocks column family exists")
+        self.cf("user-commands-num-blocks")
     }
 
     /// Key-value pairs
@@ -235,9 +182,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - txn_hash:   [TXN_HASH_LEN] bytes
     /// - state_hash: [BlockHash::LEN] bytes
     fn user_commands_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-height-sort")
-            .expect("user-commands-height-sort column family exists")
+        self.cf("user-commands-height-sort")
     }
 
     /// Key-value pairs
@@ -249,9 +194,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - txn_hash:   [TXN_HASH_LEN] bytes
     /// - state_hash: [BlockHash::LEN] bytes
     fn user_commands_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-slot-sort")
-            .expect("user-commands-slot-sort column family exists")
+        self.cf("user-commands-slot-sort")
     }
 
     /// Key-value pairs
@@ -261,9 +204,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// where
     /// - blockchain_length: 4 BE bytes
     fn user_commands_txn_hash_to_block_height_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-to-block-height")
-            .expect("user-commands-to-block-height column family exists")
+        self.cf("user-commands-to-block-height")
     }
 
     /// Key-value pairs
@@ -273,9 +214,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// where
     /// - global_slot: 4 BE bytes
     fn user_commands_txn_hash_to_global_slot_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-to-global-slot")
-            .expect("user-commands-to-global-slot column family exists")
+        self.cf("user-commands-to-global-slot")
     }
 
     /// Key-value pairs
@@ -289,9 +228,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - state_hash:  [BlockHash::LEN] bytes
     /// - amount:      8 BE bytes
     fn txn_from_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("txn-from-slot-sort")
-            .expect("txn-from-slot-sort column family exists")
+        self.cf("txn-from-slot-sort")
     }
 
     /// Key-value pairs
@@ -306,9 +243,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - state_hash:   [BlockHash::LEN] bytes
     /// - amount:       8 BE bytes
     fn txn_from_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("txn-from-height-sort")
-            .expect("txn-from-height-sort column family exists")
+        self.cf("txn-from-height-sort")
     }
 
     /// Key-value pairs
@@ -322,9 +257,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - state_hash:  [BlockHash::LEN] bytes
     /// - amount:      8 BE bytes
     fn txn_to_slot_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("txn-to-slot-sort")
-            .expect("txn-to-slot-sort column family exists")
+        self.cf("txn-to-slot-sort")
     }
 
     /// Key-value pairs
@@ -338,9 +271,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - state_hash:   [BlockHash::LEN] bytes
     /// - amount:       8 BE bytes
     fn txn_to_height_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("txn-to-height-sort")
-            .expect("txn-to-height-sort column family exists")
+        self.cf("txn-to-height-sort")
     }
 
     ////////////////////////////////
@@ -348,15 +279,24 @@ impl ColumnFamilyHelpers for IndexerStore {
     ////////////////////////////////
 
     fn internal_commands_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands")
-            .expect("internal-commands column family exists")
+        self.cf("internal-commands")
     }
 
     fn internal_commands_slot_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands-global-slot")
-            .expect("internal-commands-global-slot column family exists")
+        self.cf("internal-commands-global-slot")
+    }
+
+    /// Key-value pairs
+    /// ```
+    /// - key: {pk}{block_height}{state_hash}{index}
+    /// - val: [InternalCommandWithData] serde bytes
+    /// where
+    /// - pk:           [PublicKey::LEN] bytes
+    /// - block_height: 4 BE bytes
+    /// - state_hash:   [BlockHash::LEN] bytes
+    /// - index:        4 BE bytes, the command's index in the block
+    fn internal_commands_pk_height_sort_cf(&self) -> &ColumnFamily {
+        self.cf("internal-commands-pk-height-sort")
     }
 
     //////////////////////////
@@ -364,15 +304,18 @@ impl ColumnFamilyHelpers for IndexerStore {
     //////////////////////////
 
     fn canonicity_length_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("canonicity-length")
-            .expect("canonicity-length column family exists")
+        self.cf("canonicity-length")
     }
 
     fn canonicity_slot_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("canonicity-slot")
-            .expect("canonicity-slot column family exists")
+        self.cf("canonicity-slot")
+    }
+
+    /// CF for storing best tip reorg records
+    /// - key: reorg `index` (BE bytes)
+    /// - value: [Reorg](crate::canonicity::reorg::Reorg)
+    fn reorgs_cf(&self) -> &ColumnFamily {
+        self.cf("reorgs")
     }
 
     //////////////////////
@@ -380,21 +323,15 @@ impl ColumnFamilyHelpers for IndexerStore {
     //////////////////////
 
     fn ledgers_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("ledgers")
-            .expect("ledgers column family exists")
+        self.cf("ledgers")
     }
 
     fn block_ledger_diff_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-ledger-diff")
-            .expect("blocks-ledger-diff column family exists")
+        self.cf("blocks-ledger-diff")
     }
 
     fn block_staged_ledger_hash_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("blocks-staged-ledger-hash")
-            .expect("blocks-staged-ledger-hash column family exists")
+        self.cf("blocks-staged-ledger-hash")
     }
 
     /// CF for storing staking ledgers
@@ -406,9 +343,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - epoch:        4 BE bytes
     /// - ledger_hash:  [TXN_HASH_LEN] bytes
     fn staking_ledgers_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledgers")
-            .expect("staking-ledgers column family exists")
+        self.cf("staking-ledgers")
     }
 
     /// CF for storing staking ledger hashes
@@ -419,9 +354,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - epoch:        4 BE bytes
     /// - ledger hash:  [TXN_HASH_LEN] bytes
     fn staking_ledger_epoch_to_hash_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-epoch-to-hash")
-            .expect("staking-ledger-epoch-to-hash column family exists")
+        self.cf("staking-ledger-epoch-to-hash")
     }
 
     /// CF for storing staking ledger epochs
@@ -432,9 +365,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - ledger hash: [TXN_HASH_LEN] bytes
     /// - epoch:       4 BE bytes
     fn staking_ledger_hash_to_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-hash-to-epoch")
-            .expect("staking-ledger-hash-to-epoch column family exists")
+        self.cf("staking-ledger-hash-to-epoch")
     }
 
     /// CF for storing staking ledger genesis state hashes
@@ -445,9 +376,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - ledger_hash:  [TXN_HASH_LEN] bytes
     /// - genesis_hash: [BlockHash::LEN] bytes
     fn staking_ledger_genesis_hash_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-genesis-hash")
-            .expect("staking-ledger-genesis-hash column family exists")
+        self.cf("staking-ledger-genesis-hash")
     }
 
     /// CF for storing aggregated staking delegations
@@ -458,9 +387,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - genesis_hash: [BlockHash::LEN] bytes
     /// - epoch:        4 BE bytes
     fn staking_delegations_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-delegations")
-            .expect("staking-delegations column family exists")
+        self.cf("staking-delegations")
     }
 
     /// Key-value pairs
@@ -471,9 +398,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - balance: 8 BE bytes
     /// - pk:      [PublicKey::LEN] bytes
     fn staking_ledger_balance_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-balance")
-            .expect("staking-ledger-balance column family exists")
+        self.cf("staking-ledger-balance")
     }
 
     /// Key-value pairs
@@ -484,9 +409,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - stake: 8 BE bytes
     /// - pk:    [PublicKey::LEN] bytes
     fn staking_ledger_stake_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-stake")
-            .expect("staking-ledger-stake column family exists")
+        self.cf("staking-ledger-stake")
     }
 
     /////////////////////
@@ -494,28 +417,20 @@ impl ColumnFamilyHelpers for IndexerStore {
     /////////////////////
 
     fn snarks_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snarks")
-            .expect("snarks column family exists")
+        self.cf("snarks")
     }
 
     fn snark_top_producers_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-work-top-producers")
-            .expect("snark-work-top-producers column family exists")
+        self.cf("snark-work-top-producers")
     }
 
     fn snark_top_producers_sort_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-work-top-producers-sort")
-            .expect("snark-work-top-producers-sort column family exists")
+        self.cf("snark-work-top-producers-sort")
     }
 
     /// key: [snark_fee_prefix_key]
     fn snark_work_fees_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-work-fees")
-            .expect("snark-work-fees column family exists")
+        self.cf("snark-work-fees")
     }
 
     /// CF for storing/sorting SNARKs by prover
@@ -524,9 +439,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - slot:   4 BE bytes
     /// - index:  4 BE bytes
     fn snark_work_prover_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-work-prover")
-            .expect("snark-work-prover column family exists")
+        self.cf("snark-work-prover")
     }
 
     /// CF for storing/sorting SNARKs by prover and block height
@@ -535,9 +448,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - block height:   4 BE bytes
     /// - index:          4 BE bytes
     fn snark_work_prover_height_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snark-work-prover-height")
-            .expect("snark-work-prover column family exists")
+        self.cf("snark-work-prover-height")
     }
 
     ////////////////////////
@@ -545,22 +456,16 @@ impl ColumnFamilyHelpers for IndexerStore {
     ////////////////////////
 
     fn username_pk_num_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("username-pk-num")
-            .expect("username-pk-num column family exists")
+        self.cf("username-pk-num")
     }
 
     fn username_pk_index_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("username-pk-index")
-            .expect("username-pk-index column family exists")
+        self.cf("username-pk-index")
     }
 
     /// CF for storing state hash -> usernames
     fn usernames_per_block_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("usernames-per-block")
-            .expect("usernames-per-block column family exists")
+        self.cf("usernames-per-block")
     }
 
     /////////////////////
@@ -568,9 +473,7 @@ impl ColumnFamilyHelpers for IndexerStore {
     /////////////////////
 
     fn chain_id_to_network_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("chain-id-to-network")
-            .expect("chain-id-to-network column family exists")
+        self.cf("chain-id-to-network")
     }
 
     /////////////////////
@@ -578,9 +481,43 @@ impl ColumnFamilyHelpers for IndexerStore {
     /////////////////////
 
     fn events_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("events")
-            .expect("events column family exists")
+        self.cf("events")
+    }
+
+    /// CF for storing event log checkpoints
+    /// - key: `seq_num` (BE bytes)
+    /// - value: [EventCheckpoint](crate::event::checkpoint::EventCheckpoint)
+    fn events_checkpoints_cf(&self) -> &ColumnFamily {
+        self.cf("events-checkpoints")
+    }
+
+    ////////////////////////////
+    // Witness tree store CFs //
+    ////////////////////////////
+
+    /// CF for storing the most recent witness tree checkpoint
+    /// - key: `seq_num` (BE bytes)
+    /// - value: [WitnessTreeCheckpoint](crate::state::checkpoint::WitnessTreeCheckpoint)
+    fn witness_tree_cf(&self) -> &ColumnFamily {
+        self.cf("witness-tree")
+    }
+
+    ///////////////////////
+    // Webhook store CFs //
+    ///////////////////////
+
+    /// CF for storing registered webhooks
+    /// - key: `id` (BE bytes)
+    /// - value: [Webhook](crate::webhook::Webhook)
+    fn webhooks_cf(&self) -> &ColumnFamily {
+        self.cf("webhooks")
+    }
+
+    /// CF for storing webhook delivery cursors
+    /// - key: webhook `id` (BE bytes)
+    /// - value: [WebhookCursor](crate::webhook::WebhookCursor)
+    fn webhook_cursors_cf(&self) -> &ColumnFamily {
+        self.cf("webhook-cursors")
     }
 
     ////////////////////
@@ -591,143 +528,111 @@ impl ColumnFamilyHelpers for IndexerStore {
     /// - key: `{epoch BE bytes}{pk}`
     /// - value: number of blocks produced by `pk` in `epoch`
     fn block_production_pk_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-production-pk-epoch")
-            .expect("block-production-pk-epoch column family exists")
+        self.cf("block-production-pk-epoch")
     }
 
     /// CF for per account total block prodution info
     /// - key: pk
     /// - value: total number of blocks produced by pk
     fn block_production_pk_total_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-production-pk-total")
-            .expect("block-production-pk-total column family exists")
+        self.cf("block-production-pk-total")
     }
 
     /// CF for per epoch block production counts
     /// - key: epoch
     /// - value: number of blocks produced in epoch
     fn block_production_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-production-epoch")
-            .expect("block-production-epoch column family exists")
+        self.cf("block-production-epoch")
     }
 
     /// CF for per block SNARK counts
     /// - key: state hash
     /// - value: number of SNARKs in block
     fn block_snark_counts_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-snark-counts")
-            .expect("block-snark-counts column family exists")
+        self.cf("block-snark-counts")
     }
 
     /// CF for per block user command counts
     /// - key: state hash
     /// - value: number of user commands in block
     fn block_user_command_counts_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-user-command-counts")
-            .expect("block-user-command-counts column family exists")
+        self.cf("block-user-command-counts")
     }
 
     /// CF for per block internal command counts
     /// - key: state hash
     /// - value: number of internal commands in block
     fn block_internal_command_counts_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("block-internal-command-counts")
-            .expect("block-internal-command-counts column family exists")
+        self.cf("block-internal-command-counts")
     }
 
     /// CF for per epoch per account user commands
     /// - key: `{epoch BE bytes}{pk}`
     /// - value: number of `pk` user commands in `epoch`
     fn user_commands_pk_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-pk-epoch")
-            .expect("user-commands-pk-epoch column family exists")
+        self.cf("user-commands-pk-epoch")
     }
 
     /// CF for per account total user commands
     /// - key: `pk`
     /// - value: total number of `pk` user commands
     fn user_commands_pk_total_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-pk-total")
-            .expect("user-commands-pk-total column family exists")
+        self.cf("user-commands-pk-total")
     }
 
     /// CF for per epoch total user commands
     /// - key: `epoch`
     /// - value: number of user commands in `epoch`
     fn user_commands_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("user-commands-epoch")
-            .expect("user-commands-epoch column family exists")
+        self.cf("user-commands-epoch")
     }
 
     /// CF for per epoch per account internal commands
     /// - key: `{epoch BE bytes}{pk}`
     /// - value: number of `pk` internal commands in `epoch`
     fn internal_commands_pk_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands-pk-epoch")
-            .expect("internal-commands-pk-epoch column family exists")
+        self.cf("internal-commands-pk-epoch")
     }
 
     /// CF for per account total internal commands
     /// - key: `pk`
     /// - value: total number of `pk` internal commands
     fn internal_commands_pk_total_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands-pk-total")
-            .expect("internal-commands-pk-total column family exists")
+        self.cf("internal-commands-pk-total")
     }
 
     /// CF for per epoch total internal commands
     /// - key: `epoch`
     /// - value: number of internal commands in `epoch`
     fn internal_commands_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("internal-commands-epoch")
-            .expect("internal-commands-epoch column family exists")
+        self.cf("internal-commands-epoch")
     }
 
     /// CF for per epoch per account SNARKs
     /// - key: `{epoch BE bytes}{pk}`
     /// - value: number of `pk` SNARKs in `epoch`
     fn snarks_pk_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snarks-pk-epoch")
-            .expect("snarks-pk-epoch column family exists")
+        self.cf("snarks-pk-epoch")
     }
 
     /// CF for per account total SNARKs
     /// - key: `pk`
     /// - value: total number of `pk` SNARKs
     fn snarks_pk_total_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snarks-pk-total")
-            .expect("snarks-pk-total column family exists")
+        self.cf("snarks-pk-total")
     }
 
     /// CF for per epoch total SNARKs
     /// - key: `epoch`
     /// - value: number of SNARKs in `epoch`
     fn snarks_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("snarks-epoch")
-            .expect("snarks-epoch column family exists")
+        self.cf("snarks-epoch")
     }
 
     /// CF for per epoch total staking ledger accounts
     /// - key: `epoch`
     /// - value: number of staking ledgers in `epoch`
     fn staking_ledger_accounts_epoch_cf(&self) -> &ColumnFamily {
-        self.database
-            .cf_handle("staking-ledger-accounts-epoch")
-            .expect("staking-ledger-accounts-epoch column family exists")
+        self.cf("staking-ledger-accounts-epoch")
     }
 }
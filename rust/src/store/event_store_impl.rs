@@ -1,14 +1,19 @@
 use super::{column_families::ColumnFamilyHelpers, fixed_keys::FixedKeys};
 use crate::{
     event::{
+        checkpoint::{EventCheckpoint, EventCompaction},
         db::{DbBlockEvent, DbEvent},
         store::EventStore,
         witness_tree::WitnessTreeEvent,
-        IndexerEvent,
+        EventKind, IndexerEvent, SeqEvent,
     },
     store::{from_be_bytes, to_be_bytes, IndexerStore},
+    webhook::store::WebhookStore,
 };
-use log::trace;
+use anyhow::{bail, Context};
+use log::{info, trace};
+use speedb::{Direction, IteratorMode};
+use std::io::Write;
 
 impl EventStore for IndexerStore {
     fn add_event(&self, event: &IndexerEvent) -> anyhow::Result<u32> {
@@ -41,8 +46,10 @@ impl EventStore for IndexerStore {
 
         // increment event sequence number
         let next_seq_num = seq_num + 1;
-        self.database
-            .put(Self::NEXT_EVENT_SEQ_NUM_KEY, next_seq_num.to_be_bytes())?;
+        self.database.put(
+            self.fixed_key(Self::NEXT_EVENT_SEQ_NUM_KEY),
+            next_seq_num.to_be_bytes(),
+        )?;
 
         // return next event sequence number
         Ok(next_seq_num)
@@ -60,7 +67,7 @@ impl EventStore for IndexerStore {
         trace!("Getting next event sequence number");
         Ok(self
             .database
-            .get(Self::NEXT_EVENT_SEQ_NUM_KEY)?
+            .get(self.fixed_key(Self::NEXT_EVENT_SEQ_NUM_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -75,9 +82,158 @@ impl EventStore for IndexerStore {
         Ok(events)
     }
 
+    fn get_first_seq_num(&self) -> anyhow::Result<Option<u32>> {
+        trace!("Getting first event sequence number");
+        Ok(self
+            .event_log_iterator(IteratorMode::Start)
+            .flatten()
+            .next()
+            .map(|(key, _)| from_be_bytes(key.to_vec())))
+    }
+
+    fn get_events(
+        &self,
+        start: u32,
+        end: Option<u32>,
+        kind: Option<EventKind>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<SeqEvent>> {
+        trace!("Getting events {start}..{end:?} of kind {kind:?}");
+        let mut events = vec![];
+        for (key, bytes) in self
+            .event_log_iterator(IteratorMode::From(&start.to_be_bytes(), Direction::Forward))
+            .flatten()
+        {
+            let seq_num = from_be_bytes(key.to_vec());
+            if events.len() >= limit || end.map_or(false, |end| seq_num >= end) {
+                break;
+            }
+
+            let event: IndexerEvent = serde_json::from_slice(&bytes[5..])
+                .with_context(|| format!("Invalid event {seq_num}"))?;
+            if let Some(event_kind) = event.event_kind() {
+                if kind.map_or(true, |kind| kind == event_kind) {
+                    events.push(SeqEvent {
+                        seq_num,
+                        kind: event_kind,
+                        event,
+                    });
+                }
+            }
+        }
+        Ok(events)
+    }
+
     /// Key: sequence number (4 BE bytes)
     /// Value: event (serialized with [serde_json::to_vec])
     fn event_log_iterator(&self, mode: speedb::IteratorMode) -> speedb::DBIterator<'_> {
         self.database.iterator_cf(self.events_cf(), mode)
     }
+
+    ///////////////////////////
+    // Event log checkpoints //
+    ///////////////////////////
+
+    fn add_event_checkpoint(&self, checkpoint: &EventCheckpoint) -> anyhow::Result<()> {
+        trace!("Adding event checkpoint {}", checkpoint.seq_num);
+        self.database.put_cf(
+            self.events_checkpoints_cf(),
+            checkpoint.seq_num.to_be_bytes(),
+            serde_json::to_vec(checkpoint)?,
+        )?;
+        Ok(())
+    }
+
+    fn get_latest_event_checkpoint(&self) -> anyhow::Result<Option<EventCheckpoint>> {
+        trace!("Getting latest event checkpoint");
+        Ok(self
+            .database
+            .iterator_cf(self.events_checkpoints_cf(), IteratorMode::End)
+            .flatten()
+            .next()
+            .map(|(_, bytes)| serde_json::from_slice(&bytes))
+            .transpose()?)
+    }
+
+    fn get_event_checkpoints(&self) -> anyhow::Result<Vec<EventCheckpoint>> {
+        trace!("Getting event checkpoints");
+        let mut checkpoints = vec![];
+        for (_, bytes) in self
+            .database
+            .iterator_cf(self.events_checkpoints_cf(), IteratorMode::Start)
+            .flatten()
+        {
+            checkpoints.push(serde_json::from_slice(&bytes)?);
+        }
+        Ok(checkpoints)
+    }
+
+    fn compact_event_log(
+        &self,
+        mut archive: Option<&mut dyn Write>,
+    ) -> anyhow::Result<EventCompaction> {
+        let checkpoint = match self.get_latest_event_checkpoint()? {
+            Some(checkpoint) => checkpoint,
+            None => bail!("No event log checkpoint to compact to"),
+        };
+
+        // keep the events webhooks have yet to deliver
+        let mut compact_to = checkpoint.seq_num;
+        for webhook in self.get_webhooks()? {
+            let cursor = self.get_webhook_cursor(webhook.id)?.unwrap_or_default();
+            let pending = cursor.unconfirmed.iter().min().copied();
+            let undelivered = pending.map_or(cursor.seq_num, |seq_num| seq_num.min(cursor.seq_num));
+            if undelivered < compact_to {
+                info!(
+                    "Webhook {} holds back compaction to event {undelivered}",
+                    webhook.id
+                );
+                compact_to = undelivered;
+            }
+        }
+
+        let mut compaction = EventCompaction {
+            seq_num: compact_to,
+            ..Default::default()
+        };
+        for (key, bytes) in self.event_log_iterator(IteratorMode::Start).flatten() {
+            let seq_num = from_be_bytes(key.to_vec());
+            if seq_num >= compact_to {
+                break;
+            }
+
+            if let Some(archive) = archive.as_mut() {
+                let event: IndexerEvent = serde_json::from_slice(&bytes[5..])
+                    .with_context(|| format!("Invalid event {seq_num}"))?;
+                if let Some(kind) = event.event_kind() {
+                    serde_json::to_writer(
+                        &mut *archive,
+                        &SeqEvent {
+                            seq_num,
+                            kind,
+                            event,
+                        },
+                    )?;
+                    archive.write_all(b"\n")?;
+                    compaction.num_archived += 1;
+                }
+            }
+            compaction.num_events += 1;
+        }
+        if let Some(archive) = archive {
+            archive.flush()?;
+        }
+
+        // only discard events once they're archived
+        self.database.delete_range_cf(
+            self.events_cf(),
+            0u32.to_be_bytes(),
+            compact_to.to_be_bytes(),
+        )?;
+        info!(
+            "Compacted {} events below {compact_to} (checkpoint {})",
+            compaction.num_events, checkpoint.seq_num
+        );
+        Ok(compaction)
+    }
 }
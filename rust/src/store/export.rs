@@ -0,0 +1,367 @@
+//! Portable export & import of the [IndexerStore] as versioned NDJSON
+//!
+//! Each line of an export is an [ExportRecord]. The first line is always a
+//! [ExportRecord::Header], followed by the genesis ledgers, blocks (via
+//! height) with their user commands, internal commands & SNARK work, staged
+//! ledgers, staking ledgers & usernames.
+//!
+//! Commands, SNARK work & usernames are derived from the blocks, so an import
+//! re-indexes them from the exported blocks & only uses the exported
+//! usernames as a consistency check.
+
+use super::{column_families::ColumnFamilyHelpers, fixed_keys::FixedKeys, IndexerStore};
+use crate::{
+    block::{is_valid_state_hash, precomputed::PrecomputedBlock, store::BlockStore, BlockHash},
+    canonicity::{store::CanonicityStore, Canonicity},
+    chain::{store::ChainStore, ChainId, Network},
+    command::{
+        internal::{InternalCommand, InternalCommandWithData},
+        signed::SignedCommandWithData,
+    },
+    ledger::{
+        public_key::PublicKey, staking::StakingLedger, store::LedgerStore, username::Username,
+        Ledger,
+    },
+    snark_work::SnarkWorkSummary,
+    store::{
+        block_state_hash_from_key,
+        ledger_store_impl::{
+            staking_ledger_epoch_key_epoch, staking_ledger_epoch_key_genesis_state_hash,
+            staking_ledger_epoch_key_ledger_hash,
+        },
+        username::UsernameStore,
+        version::{IndexerStoreVersion, VersionStore},
+    },
+};
+use anyhow::{bail, Context};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use speedb::IteratorMode;
+use std::io::{BufRead, Write};
+
+/// Version of the NDJSON export format
+///
+/// Bump when the shape of an [ExportRecord] changes
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// A single line of an NDJSON export
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum ExportRecord {
+    Header {
+        format_version: u32,
+        db_version: IndexerStoreVersion,
+        chain_id: Option<String>,
+        network: Option<Network>,
+        best_tip: Option<BlockHash>,
+        best_tip_height: Option<u32>,
+    },
+    GenesisLedger {
+        state_hash: BlockHash,
+        ledger: Ledger,
+    },
+    Block {
+        canonicity: Option<Canonicity>,
+        num_block_bytes: u64,
+        block: Box<PrecomputedBlock>,
+    },
+    UserCommand(Box<SignedCommandWithData>),
+    InternalCommand(InternalCommandWithData),
+    Snark {
+        state_hash: BlockHash,
+        blockchain_length: u32,
+        snark: SnarkWorkSummary,
+    },
+    Ledger {
+        state_hash: BlockHash,
+        ledger: Ledger,
+    },
+    StakingLedger {
+        genesis_state_hash: BlockHash,
+        staking_ledger: StakingLedger,
+    },
+    Username {
+        public_key: PublicKey,
+        username: Username,
+    },
+}
+
+/// Number of records of each kind exported/imported
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportSummary {
+    pub blocks: u32,
+    pub user_commands: u32,
+    pub internal_commands: u32,
+    pub snarks: u32,
+    pub ledgers: u32,
+    pub staking_ledgers: u32,
+    pub usernames: u32,
+}
+
+impl IndexerStore {
+    /// Stream the store's contents to `writer` as NDJSON
+    pub fn export_ndjson<W: Write>(&self, writer: &mut W) -> anyhow::Result<ExportSummary> {
+        let mut summary = ExportSummary::default();
+        let chain_id = self
+            .database
+            .get(self.fixed_key(Self::CHAIN_ID_KEY))?
+            .map(String::from_utf8)
+            .transpose()?;
+        let network = match chain_id.as_ref() {
+            Some(chain_id) => self.get_network(&ChainId(chain_id.clone())).ok(),
+            None => None,
+        };
+
+        write_record(
+            writer,
+            &ExportRecord::Header {
+                format_version: EXPORT_FORMAT_VERSION,
+                db_version: self.get_db_version()?,
+                chain_id,
+                network,
+                best_tip: self.get_best_block_hash()?,
+                best_tip_height: self.get_best_block_height()?,
+            },
+        )?;
+
+        // genesis ledgers must precede blocks on import
+        let genesis_prev_state_hashes = self.get_known_genesis_prev_state_hashes()?;
+        for state_hash in genesis_prev_state_hashes.iter() {
+            if let Some(ledger) = self.get_ledger_state_hash(state_hash, false)? {
+                write_record(
+                    writer,
+                    &ExportRecord::GenesisLedger {
+                        state_hash: state_hash.clone(),
+                        ledger,
+                    },
+                )?;
+                summary.ledgers += 1;
+            }
+        }
+
+        // blocks via height with their commands & SNARK work
+        for (key, _) in self.blocks_height_iterator(IteratorMode::Start).flatten() {
+            let state_hash = block_state_hash_from_key(&key)?;
+            let (block, num_block_bytes) = self.get_block(&state_hash)?.with_context(|| {
+                format!("Block {state_hash} missing from store or pruned without its blocks dir")
+            })?;
+
+            for command in SignedCommandWithData::from_precomputed(&block) {
+                write_record(writer, &ExportRecord::UserCommand(Box::new(command)))?;
+                summary.user_commands += 1;
+            }
+            for command in InternalCommand::from_precomputed(&block) {
+                write_record(
+                    writer,
+                    &ExportRecord::InternalCommand(InternalCommandWithData::from_internal_cmd(
+                        command, &block,
+                    )),
+                )?;
+                summary.internal_commands += 1;
+            }
+            for snark in SnarkWorkSummary::from_precomputed(&block) {
+                write_record(
+                    writer,
+                    &ExportRecord::Snark {
+                        state_hash: state_hash.clone(),
+                        blockchain_length: block.blockchain_length(),
+                        snark,
+                    },
+                )?;
+                summary.snarks += 1;
+            }
+
+            write_record(
+                writer,
+                &ExportRecord::Block {
+                    canonicity: self.get_block_canonicity(&state_hash)?,
+                    num_block_bytes,
+                    block: Box::new(block),
+                },
+            )?;
+            summary.blocks += 1;
+        }
+
+        // staged ledgers are keyed by state hash, ledger hashes index them
+        for (key, _) in self
+            .database
+            .iterator_cf(self.ledgers_cf(), IteratorMode::Start)
+            .flatten()
+        {
+            let state_hash = String::from_utf8(key.to_vec())?;
+            if !is_valid_state_hash(&state_hash) {
+                continue;
+            }
+
+            let state_hash: BlockHash = state_hash.into();
+            if genesis_prev_state_hashes.contains(&state_hash) {
+                continue;
+            }
+            if let Some(ledger) = self.get_ledger_state_hash(&state_hash, false)? {
+                write_record(writer, &ExportRecord::Ledger { state_hash, ledger })?;
+                summary.ledgers += 1;
+            }
+        }
+
+        // staking ledgers
+        for (key, _) in self
+            .staking_ledger_epoch_iterator(IteratorMode::Start)
+            .flatten()
+        {
+            let genesis_state_hash = staking_ledger_epoch_key_genesis_state_hash(&key);
+            let epoch = staking_ledger_epoch_key_epoch(&key);
+            let ledger_hash = staking_ledger_epoch_key_ledger_hash(&key);
+            if let Some(staking_ledger) = self.get_staking_ledger_by_hash(
+                &ledger_hash,
+                Some(epoch),
+                Some(genesis_state_hash.clone()),
+            )? {
+                write_record(
+                    writer,
+                    &ExportRecord::StakingLedger {
+                        genesis_state_hash,
+                        staking_ledger,
+                    },
+                )?;
+                summary.staking_ledgers += 1;
+            }
+        }
+
+        // current usernames
+        for (key, _) in self
+            .database
+            .iterator_cf(self.username_pk_num_cf(), IteratorMode::Start)
+            .flatten()
+        {
+            let public_key: PublicKey = String::from_utf8(key.to_vec())?.into();
+            if let Some(username) = self.get_username(&public_key)? {
+                write_record(
+                    writer,
+                    &ExportRecord::Username {
+                        public_key,
+                        username,
+                    },
+                )?;
+                summary.usernames += 1;
+            }
+        }
+
+        writer.flush()?;
+        Ok(summary)
+    }
+
+    /// Import an NDJSON export into an empty store
+    ///
+    /// Blocks are indexed in the order they were exported. Canonical blocks
+    /// advance the best tip as they would during ingestion.
+    pub fn import_ndjson<R: BufRead>(&self, reader: R) -> anyhow::Result<ExportSummary> {
+        if self.get_best_block_hash()?.is_some() {
+            bail!("Cannot import into a non-empty database")
+        }
+
+        let mut summary = ExportSummary::default();
+        let mut usernames = vec![];
+        let mut lines = reader.lines().enumerate();
+
+        let header = lines.next().with_context(|| "Empty export")?.1?;
+        let best_tip = match serde_json::from_str(&header)
+            .with_context(|| "Invalid export header")?
+        {
+            ExportRecord::Header {
+                format_version,
+                db_version,
+                chain_id,
+                network,
+                best_tip,
+                ..
+            } => {
+                if format_version > EXPORT_FORMAT_VERSION {
+                    bail!("Unsupported export format version {format_version} (supported: {EXPORT_FORMAT_VERSION})")
+                }
+                info!("Importing export of db version {db_version}");
+
+                if let (Some(chain_id), Some(network)) = (chain_id, network) {
+                    self.set_chain_id_for_network(&ChainId(chain_id), &network)?;
+                }
+                best_tip
+            }
+            _ => bail!("Export must start with a header record"),
+        };
+
+        for (n, line) in lines {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+
+            let record: ExportRecord = serde_json::from_str(&line)
+                .with_context(|| format!("Invalid export record on line {}", n + 1))?;
+            match record {
+                ExportRecord::Header { .. } => bail!("Unexpected header on line {}", n + 1),
+                ExportRecord::GenesisLedger { state_hash, ledger } => {
+                    self.add_genesis_ledger(&state_hash, ledger)?;
+                    summary.ledgers += 1;
+                }
+                ExportRecord::Block {
+                    canonicity,
+                    num_block_bytes,
+                    block,
+                } => {
+                    let state_hash = block.state_hash();
+                    self.add_block(&block, num_block_bytes)?;
+
+                    if canonicity == Some(Canonicity::Canonical) {
+                        let genesis_state_hash = block.genesis_state_hash();
+                        let genesis_prev_state_hash = block.previous_state_hash();
+                        self.set_best_block(&state_hash)?;
+                        self.add_canonical_block(
+                            block.blockchain_length(),
+                            block.global_slot_since_genesis(),
+                            &state_hash,
+                            &genesis_state_hash,
+                            (state_hash == genesis_state_hash).then_some(&genesis_prev_state_hash),
+                        )?;
+                    }
+                    summary.blocks += 1;
+                }
+                ExportRecord::UserCommand(_) => summary.user_commands += 1,
+                ExportRecord::InternalCommand(_) => summary.internal_commands += 1,
+                ExportRecord::Snark { .. } => summary.snarks += 1,
+                ExportRecord::Ledger { state_hash, ledger } => {
+                    self.add_ledger_state_hash(&state_hash, ledger)?;
+                    summary.ledgers += 1;
+                }
+                ExportRecord::StakingLedger {
+                    genesis_state_hash,
+                    staking_ledger,
+                } => {
+                    self.add_staking_ledger(staking_ledger, &genesis_state_hash)?;
+                    summary.staking_ledgers += 1;
+                }
+                ExportRecord::Username {
+                    public_key,
+                    username,
+                } => usernames.push((public_key, username)),
+            }
+        }
+
+        if let Some(best_tip) = best_tip.as_ref() {
+            self.set_best_block(best_tip)?;
+        }
+
+        // usernames are re-indexed from the blocks up to the best tip
+        for (public_key, username) in usernames {
+            if self.get_username(&public_key)?.as_ref() != Some(&username) {
+                warn!("Imported username of {public_key} differs from the export: {username}");
+            }
+            summary.usernames += 1;
+        }
+        Ok(summary)
+    }
+}
+
+fn write_record<W: Write>(writer: &mut W, record: &ExportRecord) -> anyhow::Result<()> {
+    serde_json::to_writer(&mut *writer, record)?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
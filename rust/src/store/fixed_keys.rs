@@ -7,6 +7,9 @@ pub trait FixedKeys {
     const KNOWN_GENESIS_PREV_STATE_HASHES_KEY: &'static [u8] =
         "genesis_prev_state_hashes".as_bytes();
     const NUM_BLOCK_BYTES_PROCESSED: &'static [u8] = "num_block_bytes_processed".as_bytes();
+    const BLOCKS_DIR_KEY: &'static [u8] = "blocks_dir".as_bytes();
+    const PRUNED_BLOCKS_HEIGHT_KEY: &'static [u8] = "pruned_blocks_height".as_bytes();
+    const NEXT_WEBHOOK_ID_KEY: &'static [u8] = "next_webhook_id".as_bytes();
 
     // version info
     const INDEXER_STORE_VERSION_KEY: &'static [u8] = "indexer_store_version".as_bytes();
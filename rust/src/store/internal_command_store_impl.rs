@@ -3,8 +3,11 @@ use crate::{
     block::{precomputed::PrecomputedBlock, store::BlockStore, BlockHash},
     command::internal::{store::InternalCommandStore, InternalCommand, InternalCommandWithData},
     ledger::public_key::PublicKey,
-    store::{from_be_bytes, to_be_bytes, u32_prefix_key, IndexerStore},
+    store::{
+        from_be_bytes, pk_internal_command_sort_key, to_be_bytes, u32_prefix_key, IndexerStore,
+    },
 };
+use anyhow::bail;
 use log::trace;
 use speedb::DBIterator;
 
@@ -45,6 +48,7 @@ impl InternalCommandStore for IndexerStore {
             bytes
         }
 
+        let public_keys = block.all_public_keys();
         for (i, int_cmd) in internal_cmds_with_data.iter().enumerate() {
             let key =
                 internal_commmand_key(block.global_slot_since_genesis(), &block.state_hash().0, i);
@@ -53,9 +57,25 @@ impl InternalCommandStore for IndexerStore {
                 key,
                 serde_json::to_vec(&int_cmd)?,
             )?;
+
+            // sort by each involved public key & block height
+            for pk in &public_keys {
+                if int_cmd.contains_pk(pk) {
+                    self.database.put_cf(
+                        self.internal_commands_pk_height_sort_cf(),
+                        pk_internal_command_sort_key(
+                            pk.clone(),
+                            block.blockchain_length(),
+                            block.state_hash(),
+                            i as u32,
+                        ),
+                        serde_json::to_vec(&int_cmd)?,
+                    )?;
+                }
+            }
         }
 
-        for pk in block.all_public_keys() {
+        for pk in public_keys {
             trace!("Writing internal commands for {}", pk.0);
 
             let n = self.get_pk_num_internal_commands(&pk.0)?.unwrap_or(0);
@@ -93,13 +113,21 @@ impl InternalCommandStore for IndexerStore {
         state_hash: &BlockHash,
     ) -> anyhow::Result<Vec<InternalCommandWithData>> {
         trace!("Getting internal commands in block {}", state_hash.0);
-        let block = self.get_block(state_hash)?.expect("block to exist").0;
+
+        // pruned blocks keep their internal commands with data
+        if let Some(pruned) = self.get_pruned_block(state_hash)? {
+            return Ok(pruned.internal_commands);
+        }
 
         let key = format!("internal-{}", state_hash.0);
         if let Some(commands_bytes) = self
             .database
             .get_pinned_cf(self.internal_commands_cf(), key.as_bytes())?
         {
+            let block = match self.get_block(state_hash)? {
+                Some((block, _)) => block,
+                None => bail!("Block missing from store {state_hash}"),
+            };
             let res: Vec<InternalCommand> = serde_json::from_slice(&commands_bytes)?;
             return Ok(res
                 .into_iter()
@@ -163,6 +191,11 @@ impl InternalCommandStore for IndexerStore {
             .iterator_cf(self.internal_commands_slot_cf(), mode)
     }
 
+    fn internal_commands_pk_height_iterator(&self, mode: speedb::IteratorMode) -> DBIterator<'_> {
+        self.database
+            .iterator_cf(self.internal_commands_pk_height_sort_cf(), mode)
+    }
+
     fn get_internal_commands_epoch_count(&self, epoch: Option<u32>) -> anyhow::Result<u32> {
         let epoch = epoch.unwrap_or(self.get_current_epoch()?);
         trace!("Getting internal command epoch {epoch}");
@@ -186,7 +219,7 @@ impl InternalCommandStore for IndexerStore {
         trace!("Getting internal command total");
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_FEE_TRANSFERS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_FEE_TRANSFERS_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -194,9 +227,10 @@ impl InternalCommandStore for IndexerStore {
         trace!("Incrementing internal command total");
 
         let old = self.get_internal_commands_total_count()?;
-        Ok(self
-            .database
-            .put(Self::TOTAL_NUM_FEE_TRANSFERS_KEY, to_be_bytes(old + 1))?)
+        Ok(self.database.put(
+            self.fixed_key(Self::TOTAL_NUM_FEE_TRANSFERS_KEY),
+            to_be_bytes(old + 1),
+        )?)
     }
 
     fn get_internal_commands_pk_epoch_count(
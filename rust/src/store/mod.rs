@@ -8,6 +8,10 @@ pub mod fixed_keys;
 pub mod username;
 pub mod version;
 
+// snapshots & exports
+pub mod export;
+pub mod snapshot;
+
 // impls
 pub mod account_store_impl;
 pub mod block_store_impl;
@@ -21,6 +25,8 @@ pub mod snark_store_impl;
 pub mod user_command_store_impl;
 pub mod username_store_impl;
 pub mod version_store_impl;
+pub mod webhook_store_impl;
+pub mod witness_tree_store_impl;
 
 use self::fixed_keys::FixedKeys;
 use crate::{
@@ -28,23 +34,27 @@ use crate::{
     command::signed::TXN_HASH_LEN,
     ledger::{account::Nonce, public_key::PublicKey},
 };
-use anyhow::{anyhow, bail, Context};
-use log::{debug, info};
+use anyhow::{bail, Context};
+use log::debug;
 use serde::{Deserialize, Serialize};
-use speedb::{ColumnFamilyDescriptor, DBCompressionType, DB};
+use speedb::{ColumnFamily, ColumnFamilyDescriptor, DBCompressionType, DB};
 use std::{
-    fs::{self, read_dir, File},
-    io::{self, BufReader, BufWriter, Write},
+    io::Write,
     mem::size_of,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 use version::{IndexerStoreVersion, VersionStore};
 
 #[derive(Debug)]
 pub struct IndexerStore {
     pub db_path: PathBuf,
-    pub database: DB,
+    pub database: Arc<DB>,
     pub is_primary: bool,
+
+    /// Genesis state hash namespacing the column families & fixed keys of an
+    /// additional network, `None` for the primary network
+    pub namespace: Option<BlockHash>,
 }
 
 #[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
@@ -56,7 +66,7 @@ pub struct DBUpdate<T> {
 impl IndexerStore {
     /// Add the corresponding CF helper to [ColumnFamilyHelpers]
     /// & modify [IndexerStoreVersion] as needed!
-    const COLUMN_FAMILIES: [&'static str; 80] = [
+    const COLUMN_FAMILIES: [&'static str; 87] = [
         // accounts
         "account-balance",
         "account-balance-sort",
@@ -82,9 +92,11 @@ impl IndexerStore {
         "block-creator-slot-sort",
         "coinbase-receiver-height-sort",
         "coinbase-receiver-slot-sort",
+        "blocks-pruned",
         // canonicity
         "canonicity-length",
         "canonicity-slot",
+        "reorgs",
         // user commands
         "user-commands",
         "user-commands-pk",
@@ -112,8 +124,15 @@ impl IndexerStore {
         // internal commands
         "internal-commands",
         "internal-commands-global-slot",
+        "internal-commands-pk-height-sort",
         // indexer store events
         "events",
+        "events-checkpoints",
+        // witness tree checkpoints
+        "witness-tree",
+        // webhooks
+        "webhooks",
+        "webhook-cursors",
         // staged ledgers
         "ledgers",
         "blocks-ledger-diff",
@@ -158,6 +177,12 @@ impl IndexerStore {
 
     /// Creates a new _primary_ indexer store
     pub fn new(path: &Path) -> anyhow::Result<Self> {
+        Self::new_with_networks(path, &[])
+    }
+
+    /// Creates a new _primary_ indexer store with column families for each
+    /// additional network's namespace, see [IndexerStore::network]
+    pub fn new_with_networks(path: &Path, namespaces: &[BlockHash]) -> anyhow::Result<Self> {
         let mut cf_opts = speedb::Options::default();
         cf_opts.set_max_write_buffer_number(16);
         cf_opts.set_compression_type(DBCompressionType::Zstd);
@@ -167,18 +192,20 @@ impl IndexerStore {
         database_opts.create_missing_column_families(true);
         database_opts.create_if_missing(true);
 
-        let column_families: Vec<ColumnFamilyDescriptor> = Self::COLUMN_FAMILIES
-            .iter()
-            .map(|cf| ColumnFamilyDescriptor::new(*cf, cf_opts.clone()))
-            .collect();
+        let column_families: Vec<ColumnFamilyDescriptor> =
+            Self::column_family_names(path, namespaces, &database_opts)
+                .into_iter()
+                .map(|cf| ColumnFamilyDescriptor::new(cf, cf_opts.clone()))
+                .collect();
         let primary = Self {
             is_primary: true,
             db_path: path.into(),
-            database: speedb::DBWithThreadMode::open_cf_descriptors(
+            database: Arc::new(speedb::DBWithThreadMode::open_cf_descriptors(
                 &database_opts,
                 path,
                 column_families,
-            )?,
+            )?),
+            namespace: None,
         };
 
         // set db version
@@ -192,25 +219,14 @@ impl IndexerStore {
         Ok(primary)
     }
 
-    /// Create a snapshot of the Indexer store
-    pub fn create_snapshot(&self, output_file: &Path) -> Result<String, anyhow::Error> {
-        use speedb::checkpoint::Checkpoint;
-
-        let mut snapshot_temp_dir = output_file.to_path_buf();
-        snapshot_temp_dir.set_extension("tmp-snapshot");
-        Checkpoint::new(&self.database)?
-            .create_checkpoint(&snapshot_temp_dir)
-            .map_err(|e| anyhow!("Error creating database snapshot: {e}"))
-            .and_then(|_| {
-                persist_indexer_version(&IndexerStoreVersion::default(), &snapshot_temp_dir)?;
-                archive_directory(&snapshot_temp_dir, output_file)
-                    .with_context(|| "Failed to archive database")
-            })
-            .and_then(|_| {
-                fs::remove_dir_all(&snapshot_temp_dir)
-                    .with_context(|| format!("Failed to remove directory {snapshot_temp_dir:#?})"))
-            })
-            .map(|_| format!("Snapshot created and saved as {output_file:#?}"))
+    /// Create a snapshot of the Indexer store.
+    /// Incremental if `base_snapshot` is provided.
+    pub fn create_snapshot(
+        &self,
+        output_file: &Path,
+        base_snapshot: Option<&Path>,
+    ) -> anyhow::Result<String> {
+        snapshot::create_snapshot(self, output_file, base_snapshot)
     }
 
     /// Create a read-only instance of an indexer store
@@ -224,70 +240,85 @@ impl IndexerStore {
         database_opts.create_missing_column_families(true);
         database_opts.create_if_missing(true);
 
-        let column_families: Vec<ColumnFamilyDescriptor> = Self::COLUMN_FAMILIES
-            .iter()
-            .map(|cf| ColumnFamilyDescriptor::new(*cf, cf_opts.clone()))
-            .collect();
+        let column_families: Vec<ColumnFamilyDescriptor> =
+            Self::column_family_names(primary, &[], &database_opts)
+                .into_iter()
+                .map(|cf| ColumnFamilyDescriptor::new(cf, cf_opts.clone()))
+                .collect();
         let read_only = Self {
             is_primary: false,
             db_path: secondary.into(),
-            database: speedb::DBWithThreadMode::open_cf_descriptors_as_secondary(
+            database: Arc::new(speedb::DBWithThreadMode::open_cf_descriptors_as_secondary(
                 &database_opts,
                 primary,
                 secondary,
                 column_families,
-            )?,
+            )?),
+            namespace: None,
         };
         Ok(read_only)
     }
-}
 
-/// Restore a snapshot of the Indexer store
-pub fn restore_snapshot(snapshot_file: &PathBuf, restore_dir: &PathBuf) -> anyhow::Result<()> {
-    if !snapshot_file.exists() {
-        bail!("Snapshot file {snapshot_file:#?} does not exist")
-    } else if restore_dir.is_dir() {
-        bail!("Restore dir {restore_dir:#?} must not exist")
-    } else {
-        extract_archive_file(snapshot_file, restore_dir)
-            .with_context(|| format!("Failed to extract archive file {snapshot_file:#?}"))
-            .map(|_| info!(
-                "Snapshot successfully restored. Start mina indexer using `mina-indexer server start --database-dir {}`",
-                restore_dir.display()
-            ))
+    /// Store of the additional network with the given genesis state hash,
+    /// sharing this store's database
+    ///
+    /// The network's column families must have been created with
+    /// [IndexerStore::new_with_networks]
+    pub fn network(&self, genesis_state_hash: &BlockHash) -> anyhow::Result<Self> {
+        let namespace = Some(genesis_state_hash.clone());
+        for name in Self::COLUMN_FAMILIES {
+            let name = namespaced(namespace.as_ref(), name);
+            if self.database.cf_handle(&name).is_none() {
+                bail!("Network {genesis_state_hash} column family missing {name}");
+            }
+        }
+        Ok(Self {
+            db_path: self.db_path.clone(),
+            database: self.database.clone(),
+            is_primary: self.is_primary,
+            namespace,
+        })
     }
-}
 
-fn extract_archive_file(archive_file: &Path, output_dir: &Path) -> io::Result<()> {
-    debug!(
-        "Extracting {} to {}",
-        archive_file.display(),
-        output_dir.display()
-    );
-    fs::create_dir_all(output_dir)?;
+    /// Fixed key in the store's namespace
+    pub fn fixed_key(&self, key: &[u8]) -> Vec<u8> {
+        match &self.namespace {
+            Some(namespace) => [namespace.0.as_bytes(), b"/", key].concat(),
+            None => key.to_vec(),
+        }
+    }
 
-    let mut archive = tar::Archive::new(BufReader::new(File::open(archive_file)?));
-    archive.unpack(output_dir)
-}
+    /// Column family in the store's namespace
+    fn cf(&self, name: &str) -> &ColumnFamily {
+        let name = namespaced(self.namespace.as_ref(), name);
+        self.database
+            .cf_handle(&name)
+            .unwrap_or_else(|| panic!("{name} column family exists"))
+    }
 
-fn archive_directory(input_dir: impl AsRef<Path>, output_file: impl AsRef<Path>) -> io::Result<()> {
-    debug!(
-        "Compressing {} to {}",
-        input_dir.as_ref().display(),
-        output_file.as_ref().display()
-    );
-
-    let mut archive = tar::Builder::new(BufWriter::new(File::create(output_file)?));
-    read_dir(input_dir)?
-        .flatten()
-        .filter(|entry| entry.file_type().map_or(false, |ft| ft.is_file()))
-        .for_each(|file| {
-            archive
-                .append_path_with_name(file.path(), file.file_name())
-                .ok();
-        });
-
-    archive.finish()
+    /// Column families of the primary network & namespaces, as well as those
+    /// already in the database, since all must be opened
+    fn column_family_names(
+        path: &Path,
+        namespaces: &[BlockHash],
+        database_opts: &speedb::Options,
+    ) -> Vec<String> {
+        let mut names: Vec<String> = Self::COLUMN_FAMILIES
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        for namespace in namespaces {
+            for name in Self::COLUMN_FAMILIES {
+                names.push(namespaced(Some(namespace), name));
+            }
+        }
+        for name in DB::list_cf(database_opts, path).unwrap_or_default() {
+            if name != speedb::DEFAULT_COLUMN_FAMILY_NAME && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
 }
 
 impl<T> std::fmt::Debug for DBUpdate<T>
@@ -303,6 +334,14 @@ where
     }
 }
 
+/// Column family `name` prefixed by the network's namespace, if any
+fn namespaced(namespace: Option<&BlockHash>, name: &str) -> String {
+    match namespace {
+        Some(namespace) => format!("{namespace}/{name}"),
+        None => name.to_string(),
+    }
+}
+
 /// For [UserCommandStore]
 
 const COMMAND_KEY_PREFIX: &str = "user-";
@@ -433,6 +472,35 @@ pub fn state_hash_pk_txn_sort_key(key: &[u8]) -> BlockHash {
         .expect("state hash")
 }
 
+/// Key format for sorting internal commands by public key:
+/// `{pk}{block_height}{state_hash}{index}`
+/// ```
+/// - pk:           [PublicKey::LEN] bytes
+/// - block_height: 4 BE bytes
+/// - state_hash:   [BlockHash::LEN] bytes
+/// - index:        4 BE bytes
+pub fn pk_internal_command_sort_key(
+    pk: PublicKey,
+    block_height: u32,
+    state_hash: BlockHash,
+    index: u32,
+) -> Vec<u8> {
+    let mut bytes = pk.to_bytes();
+    bytes.append(&mut to_be_bytes(block_height));
+    bytes.append(&mut state_hash.to_bytes());
+    bytes.append(&mut to_be_bytes(index));
+    bytes
+}
+
+pub fn state_hash_pk_internal_command_sort_key(key: &[u8]) -> BlockHash {
+    BlockHash::from_bytes(&key[(PublicKey::LEN + size_of::<u32>())..][..BlockHash::LEN])
+        .expect("state hash")
+}
+
+pub fn index_pk_internal_command_sort_key(key: &[u8]) -> u32 {
+    from_be_bytes(key[(PublicKey::LEN + size_of::<u32>() + BlockHash::LEN)..].to_vec())
+}
+
 pub fn block_txn_index_key(state_hash: &BlockHash, index: u32) -> Vec<u8> {
     let mut key = state_hash.clone().to_bytes();
     key.append(&mut to_be_bytes(index));
@@ -499,4 +567,36 @@ impl IndexerStore {
             .unwrap()
             .unwrap()
     }
+
+    /// Size estimates of each column family
+    pub fn column_family_stats(&self) -> anyhow::Result<Vec<ColumnFamilyStats>> {
+        let mut stats = Vec::with_capacity(Self::COLUMN_FAMILIES.len());
+        for name in Self::COLUMN_FAMILIES {
+            let cf = self
+                .database
+                .cf_handle(&namespaced(self.namespace.as_ref(), name))
+                .with_context(|| format!("Column family missing {name}"))?;
+            let property = |property| -> anyhow::Result<u64> {
+                Ok(self
+                    .database
+                    .property_int_value_cf(cf, property)?
+                    .unwrap_or_default())
+            };
+            stats.push(ColumnFamilyStats {
+                name,
+                live_data_size: property(speedb::properties::ESTIMATE_LIVE_DATA_SIZE)?,
+                num_keys: property(speedb::properties::ESTIMATE_NUM_KEYS)?,
+                memtable_size: property(speedb::properties::CUR_SIZE_ALL_MEM_TABLES)?,
+            });
+        }
+        Ok(stats)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnFamilyStats {
+    pub name: &'static str,
+    pub live_data_size: u64,
+    pub num_keys: u64,
+    pub memtable_size: u64,
 }
@@ -0,0 +1,434 @@
+//! Full & incremental snapshots of the [IndexerStore]
+//!
+//! A snapshot is a tar archive of a speedb checkpoint, led by a
+//! [SnapshotManifest]. An incremental snapshot only archives the SST files
+//! which were added since its base snapshot, the rest are restored from the
+//! chain of base snapshots.
+
+use super::{version::IndexerStoreVersion, IndexerStore};
+use crate::block::store::BlockStore;
+use anyhow::{anyhow, bail, Context};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, read_dir, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+/// Name of the manifest entry in each snapshot archive
+pub const SNAPSHOT_MANIFEST_FILE: &str = "SNAPSHOT-MANIFEST.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// Version of the snapshotted db
+    pub db_version: IndexerStoreVersion,
+
+    /// Best tip height at the time of the snapshot
+    pub best_tip_height: Option<u32>,
+
+    /// Base snapshot of an incremental snapshot
+    pub base: Option<SnapshotBase>,
+
+    /// All files of the checkpoint, archived or not
+    pub files: Vec<SnapshotFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotBase {
+    /// File name of the base snapshot, relative to the incremental snapshot
+    pub file_name: String,
+
+    /// Checksum of the base snapshot's manifest
+    pub manifest_sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotFile {
+    pub name: String,
+    pub size: u64,
+    pub sha256: String,
+
+    /// `false` if the file is restored from a base snapshot
+    pub archived: bool,
+}
+
+impl SnapshotManifest {
+    pub fn is_incremental(&self) -> bool {
+        self.base.is_some()
+    }
+
+    pub fn archived_files(&self) -> impl Iterator<Item = &SnapshotFile> {
+        self.files.iter().filter(|file| file.archived)
+    }
+
+    fn file(&self, name: &str) -> Option<&SnapshotFile> {
+        self.files.iter().find(|file| file.name == name)
+    }
+}
+
+/// Archive a checkpoint of `store` to `output_file`.
+/// If `base_snapshot` is provided, only SST files missing from it are
+/// archived.
+pub fn create_snapshot(
+    store: &IndexerStore,
+    output_file: &Path,
+    base_snapshot: Option<&Path>,
+) -> anyhow::Result<String> {
+    use speedb::checkpoint::Checkpoint;
+
+    if output_file.exists() {
+        bail!("Snapshot file {output_file:#?} already exists")
+    }
+
+    // the base must be a valid snapshot living alongside the new one
+    let base = base_snapshot
+        .map(|base_snapshot| {
+            let (manifest, manifest_sha256) = verify_snapshot_chain(base_snapshot)
+                .with_context(|| format!("Invalid base snapshot {base_snapshot:#?}"))?;
+            if parent_dir(base_snapshot)? != parent_dir(output_file)? {
+                bail!("Base snapshot {base_snapshot:#?} must be in the same directory as {output_file:#?}")
+            }
+            let file_name = file_name(base_snapshot)?;
+            anyhow::Ok((
+                manifest,
+                SnapshotBase {
+                    file_name,
+                    manifest_sha256,
+                },
+            ))
+        })
+        .transpose()?;
+
+    let mut snapshot_temp_dir = output_file.to_path_buf();
+    snapshot_temp_dir.set_extension("tmp-snapshot");
+    Checkpoint::new(&store.database)?
+        .create_checkpoint(&snapshot_temp_dir)
+        .map_err(|e| anyhow!("Error creating database snapshot: {e}"))
+        .and_then(|_| {
+            super::persist_indexer_version(&IndexerStoreVersion::default(), &snapshot_temp_dir)?;
+
+            let mut files = vec![];
+            for entry in read_dir(&snapshot_temp_dir)?.flatten() {
+                if entry.file_type().map_or(false, |ft| ft.is_file()) {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    let (size, sha256) = checksum(File::open(entry.path())?)?;
+
+                    // SST files are immutable, only archive new ones
+                    let archived = !(is_sst_file(&name)
+                        && base.as_ref().map_or(false, |(manifest, _)| {
+                            manifest
+                                .file(&name)
+                                .map_or(false, |file| file.size == size && file.sha256 == sha256)
+                        }));
+                    files.push(SnapshotFile {
+                        name,
+                        size,
+                        sha256,
+                        archived,
+                    });
+                }
+            }
+            files.sort_by(|a, b| a.name.cmp(&b.name));
+
+            let manifest = SnapshotManifest {
+                db_version: IndexerStoreVersion::default(),
+                best_tip_height: store.get_best_block_height()?,
+                base: base.map(|(_, base)| base),
+                files,
+            };
+            archive_snapshot(&snapshot_temp_dir, &manifest, output_file)
+                .with_context(|| "Failed to archive database")
+        })
+        .and_then(|_| {
+            fs::remove_dir_all(&snapshot_temp_dir)
+                .with_context(|| format!("Failed to remove directory {snapshot_temp_dir:#?})"))
+        })
+        .map(|_| format!("Snapshot created and saved as {output_file:#?}"))
+}
+
+/// Restore a snapshot, rebuilding incremental snapshots from their chain of
+/// base snapshots. Restored files are checked against the manifest.
+pub fn restore_snapshot(snapshot_file: &Path, restore_dir: &Path) -> anyhow::Result<()> {
+    if !snapshot_file.exists() {
+        bail!("Snapshot file {snapshot_file:#?} does not exist")
+    } else if restore_dir.is_dir() {
+        bail!("Restore dir {restore_dir:#?} must not exist")
+    }
+
+    // snapshots created before manifests were introduced are plain archives
+    if read_manifest(snapshot_file)?.is_none() {
+        extract_archive_file(snapshot_file, restore_dir)
+            .with_context(|| format!("Failed to extract archive file {snapshot_file:#?}"))?;
+    } else {
+        restore_snapshot_chain(snapshot_file, restore_dir)?;
+    }
+
+    info!(
+        "Snapshot successfully restored. Start mina indexer using `mina-indexer server start --database-dir {}`",
+        restore_dir.display()
+    );
+    Ok(())
+}
+
+fn restore_snapshot_chain(snapshot_file: &Path, restore_dir: &Path) -> anyhow::Result<()> {
+    let chain = snapshot_chain(snapshot_file)?;
+    verify_chain(&chain)?;
+
+    // extract the files of the last manifest from the archives which hold them
+    let (_, manifest, _) = chain.last().expect("non-empty snapshot chain");
+    fs::create_dir_all(restore_dir)?;
+    for (archive_file, archive_manifest, _) in &chain {
+        debug!(
+            "Extracting {} to {}",
+            archive_file.display(),
+            restore_dir.display()
+        );
+
+        let mut archive = tar::Archive::new(BufReader::new(File::open(archive_file)?));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let name = entry_name(&entry)?;
+            let needed = archive_manifest.file(&name).map_or(false, |file| {
+                file.archived
+                    && manifest
+                        .file(&name)
+                        .map_or(false, |restored| restored.sha256 == file.sha256)
+            });
+
+            // later snapshots overwrite earlier ones
+            if needed {
+                let mut file = BufWriter::new(File::create(restore_dir.join(&name))?);
+                io::copy(&mut entry, &mut file)?;
+                file.flush()?;
+            }
+        }
+    }
+
+    // check restored files
+    for file in &manifest.files {
+        let path = restore_dir.join(&file.name);
+        if !path.is_file() {
+            bail!("Restored file {path:#?} is missing")
+        }
+        let (size, sha256) = checksum(File::open(&path)?)?;
+        if size != file.size || sha256 != file.sha256 {
+            bail!("Restored file {path:#?} does not match the snapshot manifest")
+        }
+    }
+    Ok(())
+}
+
+/// Verify a snapshot & its chain of base snapshots without restoring it
+///
+/// Returns the snapshot's manifest
+pub fn verify_snapshot(snapshot_file: &Path) -> anyhow::Result<SnapshotManifest> {
+    if !snapshot_file.exists() {
+        bail!("Snapshot file {snapshot_file:#?} does not exist")
+    }
+    verify_snapshot_chain(snapshot_file).map(|(manifest, _)| manifest)
+}
+
+/// Read the manifest of a snapshot archive, if present
+pub fn read_manifest(snapshot_file: &Path) -> anyhow::Result<Option<SnapshotManifest>> {
+    Ok(read_manifest_with_checksum(snapshot_file)?.map(|(manifest, _)| manifest))
+}
+
+fn read_manifest_with_checksum(
+    snapshot_file: &Path,
+) -> anyhow::Result<Option<(SnapshotManifest, String)>> {
+    let mut archive = tar::Archive::new(BufReader::new(File::open(snapshot_file)?));
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry_name(&entry)? == SNAPSHOT_MANIFEST_FILE {
+            let mut bytes = vec![];
+            entry.read_to_end(&mut bytes)?;
+            let manifest = serde_json::from_slice(&bytes)
+                .with_context(|| format!("Invalid snapshot manifest in {snapshot_file:#?}"))?;
+            return Ok(Some((manifest, hex::encode(Sha256::digest(&bytes)))));
+        }
+    }
+    Ok(None)
+}
+
+fn verify_snapshot_chain(snapshot_file: &Path) -> anyhow::Result<(SnapshotManifest, String)> {
+    let chain = snapshot_chain(snapshot_file)?;
+    verify_chain(&chain)?;
+
+    let (_, manifest, manifest_sha256) = chain.into_iter().last().expect("non-empty chain");
+    Ok((manifest, manifest_sha256))
+}
+
+/// Snapshot archives from the full base snapshot to `snapshot_file`
+fn snapshot_chain(
+    snapshot_file: &Path,
+) -> anyhow::Result<Vec<(PathBuf, SnapshotManifest, String)>> {
+    let mut chain = vec![];
+    let mut visited = HashSet::new();
+    let mut next = Some(snapshot_file.to_path_buf());
+    let mut expected_sha256: Option<String> = None;
+
+    while let Some(snapshot_file) = next.take() {
+        if !visited.insert(snapshot_file.canonicalize()?) {
+            bail!("Snapshot chain contains a cycle at {snapshot_file:#?}")
+        }
+
+        let (manifest, manifest_sha256) = read_manifest_with_checksum(&snapshot_file)?
+            .with_context(|| format!("Snapshot manifest missing from {snapshot_file:#?}"))?;
+        if let Some(expected) = expected_sha256.take() {
+            if expected != manifest_sha256 {
+                bail!("Base snapshot {snapshot_file:#?} does not match its checksum")
+            }
+        }
+        if let Some(base) = manifest.base.as_ref() {
+            let base_file = snapshot_file
+                .parent()
+                .unwrap_or(Path::new("."))
+                .join(&base.file_name);
+            if !base_file.exists() {
+                bail!("Base snapshot {base_file:#?} of {snapshot_file:#?} does not exist")
+            }
+            expected_sha256 = Some(base.manifest_sha256.clone());
+            next = Some(base_file);
+        }
+        chain.push((snapshot_file, manifest, manifest_sha256));
+    }
+
+    chain.reverse();
+    Ok(chain)
+}
+
+/// Checks each archive's contents against its manifest & that every
+/// unarchived file is archived by a base snapshot
+fn verify_chain(chain: &[(PathBuf, SnapshotManifest, String)]) -> anyhow::Result<()> {
+    let mut available: HashMap<String, String> = HashMap::new();
+    for (snapshot_file, manifest, _) in chain {
+        verify_archive(snapshot_file, manifest)?;
+
+        for file in manifest.files.iter().filter(|file| !file.archived) {
+            if available.get(&file.name) != Some(&file.sha256) {
+                bail!(
+                    "File {} of snapshot {snapshot_file:#?} is missing from its base snapshots",
+                    file.name
+                )
+            }
+        }
+        for file in manifest.archived_files() {
+            available.insert(file.name.clone(), file.sha256.clone());
+        }
+    }
+    Ok(())
+}
+
+fn verify_archive(snapshot_file: &Path, manifest: &SnapshotManifest) -> anyhow::Result<()> {
+    debug!("Verifying snapshot archive {}", snapshot_file.display());
+
+    let mut archive = tar::Archive::new(BufReader::new(File::open(snapshot_file)?));
+    let mut seen = HashSet::new();
+    for entry in archive.entries()? {
+        let entry = entry?;
+        let name = entry_name(&entry)?;
+        if name == SNAPSHOT_MANIFEST_FILE {
+            continue;
+        }
+
+        match manifest.file(&name) {
+            Some(file) if file.archived => {
+                let (size, sha256) = checksum(entry)?;
+                if size != file.size || sha256 != file.sha256 {
+                    bail!("Checksum mismatch for {name} in snapshot {snapshot_file:#?}")
+                }
+                seen.insert(name);
+            }
+            _ => bail!("Unexpected file {name} in snapshot {snapshot_file:#?}"),
+        }
+    }
+
+    if let Some(file) = manifest
+        .archived_files()
+        .find(|file| !seen.contains(&file.name))
+    {
+        bail!(
+            "File {} is missing from snapshot {snapshot_file:#?}",
+            file.name
+        )
+    }
+    Ok(())
+}
+
+fn archive_snapshot(
+    input_dir: &Path,
+    manifest: &SnapshotManifest,
+    output_file: &Path,
+) -> anyhow::Result<()> {
+    debug!(
+        "Compressing {} to {}",
+        input_dir.display(),
+        output_file.display()
+    );
+
+    let mut archive = tar::Builder::new(BufWriter::new(File::create(output_file)?));
+    let manifest_bytes = serde_json::to_vec_pretty(manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    archive.append_data(
+        &mut header,
+        SNAPSHOT_MANIFEST_FILE,
+        manifest_bytes.as_slice(),
+    )?;
+
+    for file in manifest.archived_files() {
+        archive.append_path_with_name(input_dir.join(&file.name), &file.name)?;
+    }
+    archive.into_inner()?.flush()?;
+    Ok(())
+}
+
+fn extract_archive_file(archive_file: &Path, output_dir: &Path) -> io::Result<()> {
+    debug!(
+        "Extracting {} to {}",
+        archive_file.display(),
+        output_dir.display()
+    );
+    fs::create_dir_all(output_dir)?;
+
+    let mut archive = tar::Archive::new(BufReader::new(File::open(archive_file)?));
+    archive.unpack(output_dir)
+}
+
+/// Size & hex-encoded SHA-256 checksum of the reader's contents
+fn checksum(mut reader: impl Read) -> io::Result<(u64, String)> {
+    let mut hasher = Sha256::new();
+    let size = io::copy(&mut reader, &mut hasher)?;
+    Ok((size, hex::encode(hasher.finalize())))
+}
+
+fn parent_dir(path: &Path) -> io::Result<PathBuf> {
+    path.parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+        .canonicalize()
+}
+
+fn is_sst_file(name: &str) -> bool {
+    name.ends_with(".sst")
+}
+
+fn file_name(path: &Path) -> anyhow::Result<String> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .with_context(|| format!("Invalid snapshot file {path:#?}"))
+}
+
+/// Only plain file names are allowed in snapshot archives
+fn entry_name<R: Read>(entry: &tar::Entry<R>) -> anyhow::Result<String> {
+    let path = entry.path()?;
+    match path.file_name() {
+        Some(name) if path.components().count() == 1 => Ok(name.to_string_lossy().to_string()),
+        _ => bail!("Invalid snapshot archive entry {path:#?}"),
+    }
+}
@@ -335,7 +335,7 @@ impl SnarkStore for IndexerStore {
         trace!("Getting total SNARKs count");
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_SNARKS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_SNARKS_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -343,9 +343,10 @@ impl SnarkStore for IndexerStore {
         trace!("Incrementing total SNARKs count");
 
         let old = self.get_snarks_total_count()?;
-        Ok(self
-            .database
-            .put(Self::TOTAL_NUM_SNARKS_KEY, to_be_bytes(old + 1))?)
+        Ok(self.database.put(
+            self.fixed_key(Self::TOTAL_NUM_SNARKS_KEY),
+            to_be_bytes(old + 1),
+        )?)
     }
 
     fn get_snarks_pk_epoch_count(&self, pk: &PublicKey, epoch: Option<u32>) -> anyhow::Result<u32> {
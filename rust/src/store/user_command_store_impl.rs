@@ -426,7 +426,7 @@ impl UserCommandStore for IndexerStore {
         trace!("Getting user command total");
         Ok(self
             .database
-            .get(Self::TOTAL_NUM_USER_COMMANDS_KEY)?
+            .get(self.fixed_key(Self::TOTAL_NUM_USER_COMMANDS_KEY))?
             .map_or(0, from_be_bytes))
     }
 
@@ -434,9 +434,10 @@ impl UserCommandStore for IndexerStore {
         trace!("Incrementing user command total");
 
         let old = self.get_user_commands_total_count()?;
-        Ok(self
-            .database
-            .put(Self::TOTAL_NUM_USER_COMMANDS_KEY, to_be_bytes(old + 1))?)
+        Ok(self.database.put(
+            self.fixed_key(Self::TOTAL_NUM_USER_COMMANDS_KEY),
+            to_be_bytes(old + 1),
+        )?)
     }
 
     fn get_user_commands_pk_epoch_count(
@@ -0,0 +1,130 @@
+use super::{
+    column_families::ColumnFamilyHelpers, fixed_keys::FixedKeys, from_be_bytes, to_be_bytes,
+    IndexerStore,
+};
+use crate::{
+    block::store::BlockStore,
+    event::store::EventStore,
+    webhook::{store::WebhookStore, Webhook, WebhookCursor, WebhookFilter},
+};
+use anyhow::bail;
+use log::trace;
+use speedb::IteratorMode;
+
+impl WebhookStore for IndexerStore {
+    fn add_webhook(
+        &self,
+        url: &str,
+        secret: &str,
+        filter: WebhookFilter,
+    ) -> anyhow::Result<Webhook> {
+        trace!("Adding webhook {url}");
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            bail!("Invalid webhook url {url}, expected an http(s) url")
+        }
+        if secret.is_empty() {
+            bail!("Webhook secret must not be empty")
+        }
+
+        // ids are never reused, removed webhooks keep their id
+        let id = match self
+            .database
+            .get(self.fixed_key(Self::NEXT_WEBHOOK_ID_KEY))?
+        {
+            Some(bytes) => from_be_bytes(bytes),
+            // stores predating the counter
+            None => self
+                .database
+                .iterator_cf(self.webhooks_cf(), IteratorMode::End)
+                .flatten()
+                .next()
+                .map_or(Ok(0), |(key, _)| -> anyhow::Result<u32> {
+                    Ok(u32::from_be_bytes(key[..4].try_into()?) + 1)
+                })?,
+        };
+        self.database.put(
+            self.fixed_key(Self::NEXT_WEBHOOK_ID_KEY),
+            to_be_bytes(id + 1),
+        )?;
+        let webhook = Webhook {
+            id,
+            url: url.to_string(),
+            secret: secret.to_string(),
+            filter,
+        };
+        self.database.put_cf(
+            self.webhooks_cf(),
+            id.to_be_bytes(),
+            serde_json::to_vec(&webhook)?,
+        )?;
+
+        // deliver events recorded from now on
+        self.set_webhook_cursor(
+            id,
+            &WebhookCursor {
+                seq_num: self.get_next_seq_num()?,
+                best_tip: self.get_best_block_hash()?,
+                ..Default::default()
+            },
+        )?;
+        Ok(webhook)
+    }
+
+    fn get_webhook(&self, id: u32) -> anyhow::Result<Option<Webhook>> {
+        trace!("Getting webhook {id}");
+        Ok(self
+            .database
+            .get_cf(self.webhooks_cf(), id.to_be_bytes())?
+            .map(|bytes| serde_json::from_slice(&bytes))
+            .transpose()?)
+    }
+
+    fn get_webhooks(&self) -> anyhow::Result<Vec<Webhook>> {
+        trace!("Getting webhooks");
+        let mut webhooks = vec![];
+        for (_, bytes) in self
+            .database
+            .iterator_cf(self.webhooks_cf(), IteratorMode::Start)
+            .flatten()
+        {
+            webhooks.push(serde_json::from_slice(&bytes)?);
+        }
+        Ok(webhooks)
+    }
+
+    fn remove_webhook(&self, id: u32) -> anyhow::Result<bool> {
+        trace!("Removing webhook {id}");
+        let is_registered = self.get_webhook(id)?.is_some();
+        self.database
+            .delete_cf(self.webhooks_cf(), id.to_be_bytes())?;
+        self.database
+            .delete_cf(self.webhook_cursors_cf(), id.to_be_bytes())?;
+        Ok(is_registered)
+    }
+
+    fn set_webhook_cursor(&self, id: u32, cursor: &WebhookCursor) -> anyhow::Result<()> {
+        trace!("Setting webhook {id} cursor {}", cursor.seq_num);
+        self.database.put_cf(
+            self.webhook_cursors_cf(),
+            id.to_be_bytes(),
+            serde_json::to_vec(cursor)?,
+        )?;
+
+        // checked after the write, a concurrent removal deletes the webhook
+        // before its cursor so either side drops the cursor
+        if self.get_webhook(id)?.is_none() {
+            self.database
+                .delete_cf(self.webhook_cursors_cf(), id.to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn get_webhook_cursor(&self, id: u32) -> anyhow::Result<Option<WebhookCursor>> {
+        trace!("Getting webhook {id} cursor");
+        Ok(self
+            .database
+            .get_cf(self.webhook_cursors_cf(), id.to_be_bytes())?
+            .map(|bytes| serde_json::from_slice(&bytes))
+            .transpose()?)
+    }
+}
@@ -0,0 +1,37 @@
+use super::{column_families::ColumnFamilyHelpers, IndexerStore};
+use crate::state::{checkpoint::WitnessTreeCheckpoint, store::WitnessTreeStore};
+use log::trace;
+use speedb::IteratorMode;
+
+impl WitnessTreeStore for IndexerStore {
+    fn set_witness_tree_checkpoint(
+        &self,
+        checkpoint: &WitnessTreeCheckpoint,
+    ) -> anyhow::Result<()> {
+        trace!("Setting witness tree checkpoint {}", checkpoint.seq_num);
+
+        // only the most recent checkpoint is kept
+        self.database.delete_range_cf(
+            self.witness_tree_cf(),
+            0u32.to_be_bytes(),
+            u32::MAX.to_be_bytes(),
+        )?;
+        self.database.put_cf(
+            self.witness_tree_cf(),
+            checkpoint.seq_num.to_be_bytes(),
+            serde_json::to_vec(checkpoint)?,
+        )?;
+        Ok(())
+    }
+
+    fn get_witness_tree_checkpoint(&self) -> anyhow::Result<Option<WitnessTreeCheckpoint>> {
+        trace!("Getting witness tree checkpoint");
+        Ok(self
+            .database
+            .iterator_cf(self.witness_tree_cf(), IteratorMode::End)
+            .flatten()
+            .next()
+            .map(|(_, bytes)| serde_json::from_slice(&bytes))
+            .transpose()?)
+    }
+}
@@ -3,9 +3,16 @@ use crate::{
         self, precomputed::PrecomputedBlockWithCanonicity, store::BlockStore, BlockHash,
         BlockWithoutHeight,
     },
-    canonicity::store::CanonicityStore,
+    canonicity::{reorg::ReorgFilter, store::CanonicityStore},
     client::*,
     command::{internal::store::InternalCommandStore, signed, store::UserCommandStore, Command},
+    event::{
+        broadcast::{EventReceiver, EventSender},
+        store::EventStore,
+        EventKind,
+    },
+    follow::Follow,
+    health::IndexerHealth,
     ledger::{
         self,
         public_key::{self, PublicKey},
@@ -13,9 +20,13 @@ use crate::{
         store::LedgerStore,
         LedgerHash,
     },
+    networks::{IndexedNetwork, IndexedNetworks},
+    rpc::{self, ClientError},
     snark_work::store::SnarkStore,
     state::{summary::SummaryShort, IndexerState},
-    store::version::VersionStore,
+    store::{version::VersionStore, IndexerStore},
+    web::graphql::IndexerSchema,
+    webhook::{store::WebhookStore, WebhookFilter},
 };
 use anyhow::{bail, Context};
 use log::{debug, error, info, trace, warn};
@@ -25,9 +36,9 @@ use std::{
     sync::Arc,
 };
 use tokio::{
-    io::AsyncWriteExt,
+    io::{AsyncBufRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
     net::{UnixListener, UnixStream},
-    sync::RwLock,
+    sync::{broadcast::error::RecvError, mpsc, RwLock},
 };
 use tokio_graceful_shutdown::{FutureExt, SubsystemHandle};
 
@@ -42,134 +53,348 @@ pub fn create_socket_listener(domain_socket_path: &Path) -> UnixListener {
     listener
 }
 
-async fn parse_conn_to_cli(stream: &UnixStream) -> anyhow::Result<ClientCli> {
+/// Accept connections until shutdown, serving each on its own task
+pub async fn handle_connection(
+    listener: UnixListener,
+    state: Arc<RwLock<IndexerState>>,
+    health: Arc<IndexerHealth>,
+    schema: IndexerSchema,
+    events: EventSender,
+    subsys: SubsystemHandle,
+) -> anyhow::Result<()> {
+    let network = {
+        let indexer_state = state.read().await;
+        IndexedNetwork {
+            name: indexer_state.version.network.to_string(),
+            genesis_state_hash: indexer_state.version.genesis_state_hash.clone(),
+            store: indexer_state
+                .indexer_store
+                .clone()
+                .context("Unable to get a handle on indexer store...")?,
+            events,
+            health,
+            schema,
+            state: Some(state.clone()),
+        }
+    };
+    handle_network_connections(listener, IndexedNetworks::new(network), subsys).await
+}
+
+/// Accept connections until shutdown, serving each on its own task with
+/// requests for any of the indexed networks
+pub async fn handle_network_connections(
+    listener: UnixListener,
+    networks: IndexedNetworks,
+    subsys: SubsystemHandle,
+) -> anyhow::Result<()> {
+    let (shutdown_tx, mut shutdown_rx) = mpsc::unbounded_channel();
     loop {
-        stream.readable().await?;
+        tokio::select! {
+            accepted = listener.accept().cancel_on_shutdown(&subsys) => {
+                let (connection, _) = match accepted {
+                    Ok(connection) => connection?,
+                    Err(_) => break,
+                };
 
-        let mut buffer = Vec::with_capacity(BUFFER_SIZE);
-        match stream.try_read_buf(&mut buffer) {
-            Ok(0) => break,
-            Ok(n) => {
-                buffer.truncate(n);
+                let networks = networks.clone();
+                let shutdown_tx = shutdown_tx.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve_connection(connection, networks, shutdown_tx).await {
+                        error!("Unix domain socket connection error: {e}");
+                    }
+                });
             }
-            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+            Some(()) = shutdown_rx.recv() => subsys.request_shutdown(),
+        }
+    }
+    Ok(())
+}
+
+/// Serve a JSON-RPC connection if the client sends the [rpc::PREAMBLE],
+/// else a single bincode-encoded command for the primary network
+async fn serve_connection(
+    mut connection: UnixStream,
+    networks: IndexedNetworks,
+    shutdown_tx: mpsc::UnboundedSender<()>,
+) -> anyhow::Result<()> {
+    let mut buffer = Vec::with_capacity(BUFFER_SIZE);
+    if connection.read_buf(&mut buffer).await? == 0 {
+        return Ok(());
+    }
+
+    if rpc::is_rpc_preamble(&buffer) {
+        let (reader, writer) = connection.into_split();
+        let reader = BufReader::new(std::io::Cursor::new(buffer).chain(reader));
+        return serve_rpc(reader, writer, networks, shutdown_tx).await;
+    }
+
+    let (command, _): (ClientCli, usize) = bincode::decode_from_slice(&buffer, BIN_CODE_CONFIG)?;
+    let network = networks.primary();
+    let response = match network.state() {
+        Ok(state) => match execute(command, &*state.read().await, &network.health, &shutdown_tx) {
+            Ok(Some(response)) => response,
+            Ok(None) => serde_json::to_string("no response 404")?,
+            Err(e) => e.to_string(),
+        },
+        Err(e) => e.to_string(),
+    };
+    connection.write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+/// Answer framed JSON-RPC requests until the client hangs up
+async fn serve_rpc<R, W>(
+    mut reader: R,
+    mut writer: W,
+    networks: IndexedNetworks,
+    shutdown_tx: mpsc::UnboundedSender<()>,
+) -> anyhow::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    if let Err(e) = rpc::read_preamble(&mut reader).await {
+        rpc::write_frame(&mut writer, &rpc::Response::error(None, e)).await?;
+        return Ok(());
+    }
+
+    while let Some(frame) = rpc::read_frame(&mut reader).await? {
+        let request = match rpc::Request::from_frame(&frame) {
+            Ok(request) => request,
+            Err(e) => {
+                rpc::write_frame(&mut writer, &rpc::Response::error(None, e)).await?;
                 continue;
             }
-            Err(e) => {
-                return Err(e.into());
+        };
+
+        trace!("Received JSON-RPC request {}", request.method);
+        let id = request.id.clone();
+        let mut params = request.params;
+        let network = network_param(&mut params).and_then(|network| {
+            networks
+                .get(network.as_deref())
+                .map_err(rpc::RpcError::from)
+        });
+
+        let mut following = None;
+        let result = match (request.method.as_str(), network) {
+            (rpc::METHODS_METHOD, _) => Ok(Some(serde_json::to_string(&rpc::methods())?)),
+            (_, Err(e)) => Err(e),
+            (rpc::GRAPHQL_METHOD, Ok(network)) => execute_graphql(&network.schema, params).await,
+            (method, Ok(network)) => match (ClientCli::from_rpc(method, params), network.state()) {
+                (Ok(command), Ok(state)) => match Follow::of_command(&command) {
+                    Ok(follow) => {
+                        // subscribe with the state the result is computed from
+                        let state = state.read().await;
+                        if let (Some(follow), Some(store)) = (follow, state.indexer_store.as_ref())
+                        {
+                            let best_tip = store.get_best_block_hash()?;
+                            let events = network.events.subscribe();
+                            following = Some((follow, events, store.clone(), best_tip));
+                        }
+                        execute(command, &state, &network.health, &shutdown_tx)
+                            .map_err(rpc::command_error)
+                    }
+                    Err(e) => Err(e),
+                },
+                (Err(e), _) => Err(e),
+                (_, Err(e)) => Err(e.into()),
+            },
+        };
+        if !matches!(result, Ok(Some(_))) {
+            following = None;
+        }
+
+        // notifications are not answered
+        if let Some(id) = id {
+            match result {
+                Ok(Some(output)) => rpc::write_result(&mut writer, id, output).await?,
+                Ok(None) => {
+                    let e = ClientError::NoResponse.into();
+                    rpc::write_frame(&mut writer, &rpc::Response::error(Some(id), e)).await?
+                }
+                Err(e) => rpc::write_frame(&mut writer, &rpc::Response::error(Some(id), e)).await?,
+            }
+            if let Some((follow, events, store, best_tip)) = following {
+                return serve_follow(reader, writer, follow, events, &store, best_tip, request.id)
+                    .await;
             }
         }
-        let (command, _): (ClientCli, usize) =
-            bincode::decode_from_slice(&buffer, BIN_CODE_CONFIG)?;
-        return Ok(command);
     }
-    bail!("Unexpected Unix domain socket read error");
+    Ok(())
 }
 
-#[allow(clippy::just_underscores_and_digits)]
-pub async fn handle_connection(
-    listener: UnixListener,
-    state: Arc<RwLock<IndexerState>>,
-    subsys: SubsystemHandle,
-) -> anyhow::Result<()> {
-    use helpers::*;
+/// Remove the `network` param, the name or genesis state hash of the network
+/// the request is for
+fn network_param(params: &mut Option<serde_json::Value>) -> Result<Option<String>, rpc::RpcError> {
+    let network = params
+        .as_mut()
+        .and_then(serde_json::Value::as_object_mut)
+        .and_then(|params| params.remove("network"));
+    match network {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(network)) => Ok(Some(network)),
+        Some(_) => Err(rpc::RpcError::invalid_params("network must be a string")),
+    }
+}
 
+/// Push the followed command's items until either side closes the connection
+///
+/// Frames sent by the client are ignored
+async fn serve_follow<R, W>(
+    mut reader: R,
+    mut writer: W,
+    follow: Follow,
+    mut events: EventReceiver,
+    store: &IndexerStore,
+    mut best_tip: Option<BlockHash>,
+    id: Option<serde_json::Value>,
+) -> anyhow::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let id = id.unwrap_or_default();
+
+    // one read of the client's frames across iterations, since read_frame
+    // isn't cancel safe & a frame read cancelled midway desyncs the connection
+    let closed = async {
+        while rpc::read_frame(&mut reader).await?.is_some() {}
+        anyhow::Ok(())
+    };
+    tokio::pin!(closed);
     loop {
-        let (connection, _) = match listener.accept().cancel_on_shutdown(&subsys).await {
-            Ok(connection) => connection,
-            Err(_) => break,
-        }?;
-
-        let state = state.read().await;
-        let db = if let Some(store) = state.indexer_store.as_ref() {
-            store
-        } else {
-            bail!("Unable to get a handle on indexer store...");
-        };
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    for (status, data) in follow.items(store, &event, &mut best_tip)? {
+                        let item = rpc::FollowItem {
+                            id: id.clone(),
+                            seq_num: event.seq_num,
+                            status,
+                            data,
+                        };
+                        rpc::write_frame(&mut writer, &rpc::FollowNotification::new(item)).await?;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Follower of {follow:?} skipped {skipped} events")
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            },
+            closed = &mut closed => return closed,
+        }
+    }
+}
 
-        let command = parse_conn_to_cli(&connection).await?;
-        let (_, mut writer) = connection.into_split();
+/// Execute the GraphQL request of the params, errors are part of its response
+async fn execute_graphql(
+    schema: &IndexerSchema,
+    params: Option<serde_json::Value>,
+) -> Result<Option<String>, rpc::RpcError> {
+    let request: async_graphql::Request = params
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| rpc::RpcError::invalid_params(e.to_string()))?
+        .ok_or_else(|| rpc::RpcError::invalid_params("Missing GraphQL request"))?;
+    let response = schema.execute(request).await;
+    serde_json::to_string(&response)
+        .map(Some)
+        .map_err(|e| rpc::RpcError::new(rpc::codes::INTERNAL_ERROR, e.to_string()))
+}
 
-        let response_json = match command {
-            ClientCli::Accounts(__) => match __ {
-                Accounts::PublicKey { public_key: pk } => {
-                    info!("Received account command for {pk}");
+/// Run a client command against the indexer state
+///
+/// Returns `None` if the command has no output
+#[allow(clippy::just_underscores_and_digits)]
+fn execute(
+    command: ClientCli,
+    state: &IndexerState,
+    health: &IndexerHealth,
+    shutdown_tx: &mpsc::UnboundedSender<()>,
+) -> anyhow::Result<Option<String>> {
+    use helpers::*;
 
-                    if let Some(best_tip) = db.get_best_block()? {
-                        if let Some(ledger) =
-                            db.get_ledger_state_hash(&best_tip.state_hash(), false)?
-                        {
-                            if !public_key::is_valid_public_key(&pk) {
-                                invalid_public_key(&pk)
+    let db = if let Some(store) = state.indexer_store.as_ref() {
+        store
+    } else {
+        bail!("Unable to get a handle on indexer store...");
+    };
+
+    Ok(match command {
+        ClientCli::Accounts(__) => match __ {
+            Accounts::PublicKey { public_key: pk } => {
+                info!("Received account command for {pk}");
+
+                if let Some(best_tip) = db.get_best_block()? {
+                    if let Some(ledger) = db.get_ledger_state_hash(&best_tip.state_hash(), false)? {
+                        if !public_key::is_valid_public_key(&pk) {
+                            return Err(invalid_public_key(&pk));
+                        } else {
+                            let pk: PublicKey = pk.into();
+                            let account = ledger.accounts.get(&pk);
+                            if let Some(account) = account {
+                                info!("Writing account {pk} to client");
+                                Some(format!("{account}"))
                             } else {
-                                let pk: PublicKey = pk.into();
-                                let account = ledger.accounts.get(&pk);
-                                if let Some(account) = account {
-                                    info!("Writing account {pk} to client");
-                                    Some(format!("{account}"))
-                                } else {
-                                    warn!("Account {pk} does not exist");
-                                    Some(format!("Account {pk} does not exist"))
-                                }
+                                warn!("Account {pk} does not exist");
+                                Some(format!("Account {pk} does not exist"))
                             }
-                        } else {
-                            error!("Best ledger not in database {}", best_tip.summary());
-                            Some(format!(
-                                "Best ledger not in database {}",
-                                best_tip.summary()
-                            ))
                         }
                     } else {
-                        best_tip_missing_from_db()
+                        error!("Best ledger not in database {}", best_tip.summary());
+                        Some(format!(
+                            "Best ledger not in database {}",
+                            best_tip.summary()
+                        ))
                     }
+                } else {
+                    return Err(best_tip_missing_from_db());
                 }
-            },
-            ClientCli::Blocks(__) => match __ {
-                Blocks::BestTip { verbose, path } => {
-                    info!("Received best-tip command");
-
-                    if let Some(best_tip) = db.get_best_block()? {
-                        let block_str = if let Some(canonicity) =
-                            db.get_block_canonicity(&best_tip.state_hash())?
-                        {
-                            if verbose {
-                                serde_json::to_string_pretty(&best_tip.with_canonicity(canonicity))?
-                            } else {
-                                let block =
-                                    BlockWithoutHeight::with_canonicity(&best_tip, canonicity);
-                                serde_json::to_string_pretty(&block)?
-                            }
-                        } else {
-                            block_missing_from_db(&best_tip.state_hash().0)
-                        };
-
-                        if path.is_some() {
-                            let path = &path.unwrap();
-                            info!("Writing best tip block to {:?}", path);
-                            std::fs::write(path, block_str)?;
-                            Some(format!("Best block written to {}", path.display()))
+            }
+        },
+        ClientCli::Blocks(__) => match __ {
+            Blocks::BestTip { verbose, path, .. } => {
+                info!("Received best-tip command");
+
+                if let Some(best_tip) = db.get_best_block()? {
+                    let block_str = if let Some(canonicity) =
+                        db.get_block_canonicity(&best_tip.state_hash())?
+                    {
+                        if verbose {
+                            serde_json::to_string_pretty(&best_tip.with_canonicity(canonicity))?
                         } else {
-                            info!("Writing best tip block to stdout");
-                            Some(block_str)
+                            let block = BlockWithoutHeight::with_canonicity(&best_tip, canonicity);
+                            serde_json::to_string_pretty(&block)?
                         }
                     } else {
-                        best_tip_missing_from_db()
+                        return Err(block_missing_from_db(&best_tip.state_hash().0));
+                    };
+
+                    if path.is_some() {
+                        let path = &path.unwrap();
+                        info!("Writing best tip block to {:?}", path);
+                        std::fs::write(path, block_str)?;
+                        Some(format!("Best block written to {}", path.display()))
+                    } else {
+                        info!("Writing best tip block to stdout");
+                        Some(block_str)
                     }
+                } else {
+                    return Err(best_tip_missing_from_db());
                 }
-                Blocks::StateHash {
-                    state_hash,
-                    verbose,
-                    path,
-                } => {
-                    info!("Received block-state-hash command");
-                    if !block::is_valid_state_hash(&state_hash) {
-                        invalid_state_hash(&state_hash)
-                    } else if let Ok(Some((ref block, _))) =
-                        db.get_block(&state_hash.clone().into())
-                    {
-                        let block_str = if let Some(canonicity) =
-                            db.get_block_canonicity(&block.state_hash())?
-                        {
+            }
+            Blocks::StateHash {
+                state_hash,
+                verbose,
+                path,
+            } => {
+                info!("Received block-state-hash command");
+                if !block::is_valid_state_hash(&state_hash) {
+                    return Err(invalid_state_hash(&state_hash));
+                } else if let Ok(Some((ref block, _))) = db.get_block(&state_hash.clone().into()) {
+                    let block_str =
+                        if let Some(canonicity) = db.get_block_canonicity(&block.state_hash())? {
                             if verbose {
                                 serde_json::to_string_pretty(&block.with_canonicity(canonicity))?
                             } else {
@@ -177,105 +402,175 @@ pub async fn handle_connection(
                                 serde_json::to_string_pretty(&block)?
                             }
                         } else {
-                            block_missing_from_db(&block.state_hash().0)
+                            return Err(block_missing_from_db(&block.state_hash().0));
                         };
-                        if path.is_some() {
-                            let path = &path.unwrap();
-                            info!("Writing block {state_hash} to {:?}", path);
-                            std::fs::write(path, block_str)?;
-                            Some(format!(
-                                "Block {} written to {:?}",
-                                block.state_hash().0,
-                                path
-                            ))
-                        } else {
-                            info!("Writing block to stdout {}", block.summary());
-                            Some(block_str)
-                        }
-                    } else {
-                        error!("Block at state hash not present in store: {}", state_hash);
+                    if path.is_some() {
+                        let path = &path.unwrap();
+                        info!("Writing block {state_hash} to {:?}", path);
+                        std::fs::write(path, block_str)?;
                         Some(format!(
-                            "Block at state hash not present in store: {state_hash}"
+                            "Block {} written to {:?}",
+                            block.state_hash().0,
+                            path
                         ))
+                    } else {
+                        info!("Writing block to stdout {}", block.summary());
+                        Some(block_str)
                     }
+                } else {
+                    error!("Block at state hash not present in store: {}", state_hash);
+                    Some(format!(
+                        "Block at state hash not present in store: {state_hash}"
+                    ))
                 }
-                Blocks::Height {
-                    height,
-                    verbose,
-                    path,
-                } => {
-                    info!("Received blocks-at-height {height} command");
-                    let blocks_at_height = db.get_blocks_at_height(height)?;
-                    let blocks_str = if verbose {
-                        let blocks: Vec<PrecomputedBlockWithCanonicity> = blocks_at_height
-                            .iter()
-                            .flat_map(|state_hash| {
-                                if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
-                                    let block = db
-                                        .get_block(state_hash)
-                                        .with_context(|| {
-                                            format!("block missing from store {state_hash}")
-                                        })
-                                        .unwrap()
-                                        .unwrap()
-                                        .0;
-                                    Some(block.with_canonicity(canonicity))
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect();
-                        serde_json::to_string(&blocks)?
-                    } else {
-                        let blocks: Vec<BlockWithoutHeight> = blocks_at_height
-                            .iter()
-                            .flat_map(|state_hash| {
-                                if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
-                                    let block = db
-                                        .get_block(state_hash)
-                                        .with_context(|| {
-                                            format!("block missing from store {state_hash}")
-                                        })
-                                        .unwrap()
-                                        .unwrap()
-                                        .0;
-                                    Some(BlockWithoutHeight::with_canonicity(&block, canonicity))
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect();
-                        format_vec_jq_compatible(&blocks)
-                    };
+            }
+            Blocks::Height {
+                height,
+                verbose,
+                path,
+            } => {
+                info!("Received blocks-at-height {height} command");
+                let blocks_at_height = db.get_blocks_at_height(height)?;
+                let blocks_str = if verbose {
+                    let blocks: Vec<PrecomputedBlockWithCanonicity> = blocks_at_height
+                        .iter()
+                        .flat_map(|state_hash| {
+                            if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
+                                let block = db
+                                    .get_block(state_hash)
+                                    .with_context(|| {
+                                        format!("block missing from store {state_hash}")
+                                    })
+                                    .unwrap()
+                                    .unwrap()
+                                    .0;
+                                Some(block.with_canonicity(canonicity))
+                            } else {
+                                None
+                            }
+                        })
+                        .collect();
+                    serde_json::to_string(&blocks)?
+                } else {
+                    let blocks: Vec<BlockWithoutHeight> = blocks_at_height
+                        .iter()
+                        .flat_map(|state_hash| {
+                            if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
+                                let block = db
+                                    .get_block(state_hash)
+                                    .with_context(|| {
+                                        format!("block missing from store {state_hash}")
+                                    })
+                                    .unwrap()
+                                    .unwrap()
+                                    .0;
+                                Some(BlockWithoutHeight::with_canonicity(&block, canonicity))
+                            } else {
+                                None
+                            }
+                        })
+                        .collect();
+                    format_vec_jq_compatible(&blocks)
+                };
 
-                    if path.is_none() {
-                        info!("Writing blocks at height {height} to stdout");
-                        Some(blocks_str)
+                if path.is_none() {
+                    info!("Writing blocks at height {height} to stdout");
+                    Some(blocks_str)
+                } else {
+                    let path = path.unwrap();
+                    if !path.is_dir() {
+                        info!("Writing blocks at height {height} to {}", path.display());
+
+                        std::fs::write(path.clone(), blocks_str)?;
+                        Some(format!(
+                            "Blocks at height {height} written to {}",
+                            path.display()
+                        ))
                     } else {
-                        let path = path.unwrap();
-                        if !path.is_dir() {
-                            info!("Writing blocks at height {height} to {}", path.display());
+                        return Err(file_must_not_be_a_directory(&path));
+                    }
+                }
+            }
+            Blocks::Slot {
+                slot,
+                verbose,
+                path,
+            } => {
+                info!("Received blocks-at-slot {slot} command");
+                let slot: u32 = slot.parse()?;
+                let blocks_at_slot = db.get_blocks_at_slot(slot)?;
+                let blocks_str = if verbose {
+                    let blocks: Vec<PrecomputedBlockWithCanonicity> = blocks_at_slot
+                        .iter()
+                        .flat_map(|state_hash| {
+                            if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
+                                let block = db
+                                    .get_block(state_hash)
+                                    .with_context(|| {
+                                        format!("block missing from store {state_hash}")
+                                    })
+                                    .unwrap()
+                                    .unwrap()
+                                    .0;
+                                Some(block.with_canonicity(canonicity))
+                            } else {
+                                None
+                            }
+                        })
+                        .collect();
+                    serde_json::to_string(&blocks)?
+                } else {
+                    let blocks: Vec<BlockWithoutHeight> = blocks_at_slot
+                        .iter()
+                        .flat_map(|state_hash| {
+                            if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
+                                let block = db
+                                    .get_block(state_hash)
+                                    .with_context(|| {
+                                        format!("block missing from store {state_hash}")
+                                    })
+                                    .unwrap()
+                                    .unwrap()
+                                    .0;
+                                Some(BlockWithoutHeight::with_canonicity(&block, canonicity))
+                            } else {
+                                None
+                            }
+                        })
+                        .collect();
+                    format_vec_jq_compatible(&blocks)
+                };
 
-                            std::fs::write(path.clone(), blocks_str)?;
-                            Some(format!(
-                                "Blocks at height {height} written to {}",
-                                path.display()
-                            ))
-                        } else {
-                            file_must_not_be_a_directory(&path)
-                        }
+                if path.is_none() {
+                    info!("Writing blocks at slot {slot} to stdout");
+                    Some(blocks_str)
+                } else {
+                    let path = path.unwrap();
+                    if !path.is_dir() {
+                        info!("Writing blocks at slot {slot} to {}", path.display());
+
+                        std::fs::write(path.clone(), blocks_str)?;
+                        Some(format!(
+                            "Blocks at slot {slot} written to {}",
+                            path.display()
+                        ))
+                    } else {
+                        return Err(file_must_not_be_a_directory(&path));
                     }
                 }
-                Blocks::Slot {
-                    slot,
-                    verbose,
-                    path,
-                } => {
-                    info!("Received blocks-at-slot {slot} command");
-                    let slot: u32 = slot.parse()?;
-                    let blocks_at_slot = db.get_blocks_at_slot(slot)?;
+            }
+            Blocks::PublicKey {
+                public_key: pk,
+                verbose,
+                path,
+            } => {
+                info!("Received blocks-at-public-key command {pk}");
+                if !public_key::is_valid_public_key(&pk) {
+                    return Err(invalid_public_key(&pk));
+                } else {
+                    let blocks_at_pk = db.get_blocks_at_public_key(&pk.clone().into())?;
                     let blocks_str = if verbose {
-                        let blocks: Vec<PrecomputedBlockWithCanonicity> = blocks_at_slot
+                        let blocks: Vec<PrecomputedBlockWithCanonicity> = blocks_at_pk
                             .iter()
                             .flat_map(|state_hash| {
                                 if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
@@ -295,7 +590,7 @@ pub async fn handle_connection(
                             .collect();
                         serde_json::to_string(&blocks)?
                     } else {
-                        let blocks: Vec<BlockWithoutHeight> = blocks_at_slot
+                        let blocks: Vec<BlockWithoutHeight> = blocks_at_pk
                             .iter()
                             .flat_map(|state_hash| {
                                 if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
@@ -317,921 +612,1036 @@ pub async fn handle_connection(
                     };
 
                     if path.is_none() {
-                        info!("Writing blocks at slot {slot} to stdout");
+                        info!("Writing blocks at public key {pk} to stdout");
                         Some(blocks_str)
                     } else {
                         let path = path.unwrap();
                         if !path.is_dir() {
-                            info!("Writing blocks at slot {slot} to {}", path.display());
+                            info!("Writing blocks at public key {pk} to {}", path.display());
 
                             std::fs::write(path.clone(), blocks_str)?;
                             Some(format!(
-                                "Blocks at slot {slot} written to {}",
+                                "Blocks at public key {pk} written to {}",
                                 path.display()
                             ))
                         } else {
-                            file_must_not_be_a_directory(&path)
+                            return Err(file_must_not_be_a_directory(&path));
                         }
                     }
                 }
-                Blocks::PublicKey {
-                    public_key: pk,
-                    verbose,
-                    path,
-                } => {
-                    info!("Received blocks-at-public-key command {pk}");
-                    if !public_key::is_valid_public_key(&pk) {
-                        invalid_public_key(&pk)
+            }
+            Blocks::Children {
+                state_hash,
+                verbose,
+                path,
+            } => {
+                info!("Received block-children command for block {state_hash}");
+                let children = db.get_block_children(&state_hash.clone().into())?;
+                let blocks_str = if verbose {
+                    let blocks: Vec<PrecomputedBlockWithCanonicity> = children
+                        .iter()
+                        .flat_map(|state_hash| {
+                            if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
+                                let block = db
+                                    .get_block(state_hash)
+                                    .with_context(|| {
+                                        format!("block missing from store {state_hash}")
+                                    })
+                                    .unwrap()
+                                    .unwrap()
+                                    .0;
+                                Some(block.with_canonicity(canonicity))
+                            } else {
+                                None
+                            }
+                        })
+                        .collect();
+                    serde_json::to_string(&blocks)?
+                } else {
+                    let blocks: Vec<BlockWithoutHeight> = children
+                        .iter()
+                        .flat_map(|state_hash| {
+                            if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
+                                let block = db
+                                    .get_block(state_hash)
+                                    .with_context(|| {
+                                        format!("block missing from store {state_hash}")
+                                    })
+                                    .unwrap()
+                                    .unwrap()
+                                    .0;
+                                Some(BlockWithoutHeight::with_canonicity(&block, canonicity))
+                            } else {
+                                None
+                            }
+                        })
+                        .collect();
+                    format_vec_jq_compatible(&blocks)
+                };
+
+                if path.is_none() {
+                    info!("Writing children of block {} to stdout", state_hash);
+                    Some(blocks_str)
+                } else {
+                    let path = path.unwrap();
+                    if !path.is_dir() {
+                        info!(
+                            "Writing children of block {} to {}",
+                            state_hash,
+                            path.display()
+                        );
+
+                        std::fs::write(path.clone(), blocks_str)?;
+                        Some(format!(
+                            "Children of block {state_hash} written to {}",
+                            path.display()
+                        ))
                     } else {
-                        let blocks_at_pk = db.get_blocks_at_public_key(&pk.clone().into())?;
-                        let blocks_str = if verbose {
-                            let blocks: Vec<PrecomputedBlockWithCanonicity> = blocks_at_pk
+                        return Err(file_must_not_be_a_directory(&path));
+                    }
+                }
+            }
+        },
+        ClientCli::Chain(__) => match __ {
+            Chain::Best {
+                num,
+                verbose,
+                start_state_hash,
+                end_state_hash,
+                path,
+            } => {
+                info!("Received best-chain command");
+                let start_state_hash: BlockHash = start_state_hash.into();
+                if let Some(best_tip) = db.get_best_block()? {
+                    let end_state_hash: String = {
+                        if end_state_hash.is_none() {
+                            best_tip.state_hash().0
+                        } else {
+                            let end_state_hash = &end_state_hash.unwrap();
+                            if !block::is_valid_state_hash(end_state_hash) {
+                                best_tip.state_hash().0
+                            } else {
+                                end_state_hash.into()
+                            }
+                        }
+                    };
+
+                    if !block::is_valid_state_hash(&start_state_hash.0) {
+                        return Err(invalid_state_hash(&start_state_hash.0));
+                    } else if let (Some((end_block, _)), Some((start_block, _))) = (
+                        db.get_block(&end_state_hash.into())?,
+                        db.get_block(&start_state_hash)?,
+                    ) {
+                        let start_height = start_block.blockchain_length();
+                        let end_height = end_block.blockchain_length();
+                        let mut parent_hash = end_block.previous_state_hash();
+                        let mut best_chain = vec![end_block];
+
+                        // constrain by num and state hash bound
+                        for _ in 1..num.min(end_height.saturating_sub(start_height) + 1) {
+                            if let Some((parent_pcb, _)) = db.get_block(&parent_hash)? {
+                                let curr_hash: BlockHash = parent_pcb.state_hash();
+                                parent_hash = parent_pcb.previous_state_hash();
+                                best_chain.push(parent_pcb);
+
+                                if curr_hash == start_state_hash {
+                                    break;
+                                }
+                            } else {
+                                break;
+                            }
+                        }
+
+                        let best_chain_str = if verbose {
+                            let best_chain: Vec<PrecomputedBlockWithCanonicity> = best_chain
                                 .iter()
-                                .flat_map(|state_hash| {
+                                .flat_map(|block| {
                                     if let Ok(Some(canonicity)) =
-                                        db.get_block_canonicity(state_hash)
+                                        db.get_block_canonicity(&block.state_hash())
                                     {
-                                        let block = db
-                                            .get_block(state_hash)
-                                            .with_context(|| {
-                                                format!("block missing from store {state_hash}")
-                                            })
-                                            .unwrap()
-                                            .unwrap()
-                                            .0;
                                         Some(block.with_canonicity(canonicity))
                                     } else {
                                         None
                                     }
                                 })
                                 .collect();
-                            serde_json::to_string(&blocks)?
+                            serde_json::to_string(&best_chain)?
                         } else {
-                            let blocks: Vec<BlockWithoutHeight> = blocks_at_pk
+                            let best_chain: Vec<BlockWithoutHeight> = best_chain
                                 .iter()
-                                .flat_map(|state_hash| {
+                                .flat_map(|block| {
                                     if let Ok(Some(canonicity)) =
-                                        db.get_block_canonicity(state_hash)
+                                        db.get_block_canonicity(&block.state_hash())
                                     {
-                                        let block = db
-                                            .get_block(state_hash)
-                                            .with_context(|| {
-                                                format!("block missing from store {state_hash}")
-                                            })
-                                            .unwrap()
-                                            .unwrap()
-                                            .0;
-                                        Some(BlockWithoutHeight::with_canonicity(
-                                            &block, canonicity,
-                                        ))
+                                        Some(BlockWithoutHeight::with_canonicity(block, canonicity))
                                     } else {
                                         None
                                     }
                                 })
                                 .collect();
-                            format_vec_jq_compatible(&blocks)
+                            format_vec_jq_compatible(&best_chain)
                         };
 
                         if path.is_none() {
-                            info!("Writing blocks at public key {pk} to stdout");
-                            Some(blocks_str)
+                            info!("Writing best chain to stdout");
+                            Some(best_chain_str)
                         } else {
                             let path = path.unwrap();
                             if !path.is_dir() {
-                                info!("Writing blocks at public key {pk} to {}", path.display());
+                                info!("Writing best chain to {}", path.display());
 
-                                std::fs::write(path.clone(), blocks_str)?;
-                                Some(format!(
-                                    "Blocks at public key {pk} written to {}",
-                                    path.display()
-                                ))
+                                std::fs::write(path.clone(), best_chain_str)?;
+                                Some(format!("Best chain written to {}", path.display()))
                             } else {
-                                file_must_not_be_a_directory(&path)
+                                return Err(file_must_not_be_a_directory(&path));
                             }
                         }
+                    } else {
+                        None
                     }
+                } else {
+                    return Err(best_tip_missing_from_db());
                 }
-                Blocks::Children {
-                    state_hash,
-                    verbose,
-                    path,
-                } => {
-                    info!("Received block-children command for block {state_hash}");
-                    let children = db.get_block_children(&state_hash.clone().into())?;
-                    let blocks_str = if verbose {
-                        let blocks: Vec<PrecomputedBlockWithCanonicity> = children
-                            .iter()
-                            .flat_map(|state_hash| {
-                                if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
-                                    let block = db
-                                        .get_block(state_hash)
-                                        .with_context(|| {
-                                            format!("block missing from store {state_hash}")
-                                        })
-                                        .unwrap()
-                                        .unwrap()
-                                        .0;
-                                    Some(block.with_canonicity(canonicity))
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect();
-                        serde_json::to_string(&blocks)?
-                    } else {
-                        let blocks: Vec<BlockWithoutHeight> = children
-                            .iter()
-                            .flat_map(|state_hash| {
-                                if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
-                                    let block = db
-                                        .get_block(state_hash)
-                                        .with_context(|| {
-                                            format!("block missing from store {state_hash}")
-                                        })
-                                        .unwrap()
-                                        .unwrap()
-                                        .0;
-                                    Some(BlockWithoutHeight::with_canonicity(&block, canonicity))
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect();
-                        format_vec_jq_compatible(&blocks)
-                    };
-
-                    if path.is_none() {
-                        info!("Writing children of block {} to stdout", state_hash);
-                        Some(blocks_str)
-                    } else {
-                        let path = path.unwrap();
-                        if !path.is_dir() {
-                            info!(
-                                "Writing children of block {} to {}",
-                                state_hash,
-                                path.display()
-                            );
-
-                            std::fs::write(path.clone(), blocks_str)?;
-                            Some(format!(
-                                "Children of block {state_hash} written to {}",
-                                path.display()
-                            ))
+            }
+        },
+        ClientCli::CreateSnapshot {
+            output_path,
+            base_snapshot,
+        } => {
+            info!("Received create-snapshot command");
+            match db.create_snapshot(&output_path, base_snapshot.as_deref()) {
+                Err(e) => Some(e.to_string()),
+                Ok(s) => Some(s),
+            }
+        }
+        ClientCli::Events(__) => match __ {
+            Events::Query {
+                start,
+                end,
+                kind,
+                limit,
+                path,
+                ..
+            } => {
+                info!("Received events command {start}..{end:?} kind {kind:?}");
+                match kind.as_deref().map(str::parse::<EventKind>).transpose() {
+                    Err(e) => Some(e.to_string()),
+                    Ok(kind) => {
+                        let events = db.get_events(start, end, kind, limit as usize)?;
+                        let events_str = serde_json::to_string_pretty(&events)?;
+                        if path.is_none() {
+                            info!("Writing events to stdout");
+                            Some(events_str)
                         } else {
-                            file_must_not_be_a_directory(&path)
-                        }
-                    }
-                }
-            },
-            ClientCli::Chain(__) => match __ {
-                Chain::Best {
-                    num,
-                    verbose,
-                    start_state_hash,
-                    end_state_hash,
-                    path,
-                } => {
-                    info!("Received best-chain command");
-                    let start_state_hash: BlockHash = start_state_hash.into();
-                    if let Some(best_tip) = db.get_best_block()? {
-                        let end_state_hash: String = {
-                            if end_state_hash.is_none() {
-                                best_tip.state_hash().0
-                            } else {
-                                let end_state_hash = &end_state_hash.unwrap();
-                                if !block::is_valid_state_hash(end_state_hash) {
-                                    best_tip.state_hash().0
-                                } else {
-                                    end_state_hash.into()
-                                }
-                            }
-                        };
-
-                        if !block::is_valid_state_hash(&start_state_hash.0) {
-                            invalid_state_hash(&start_state_hash.0)
-                        } else if let (Some((end_block, _)), Some((start_block, _))) = (
-                            db.get_block(&end_state_hash.into())?,
-                            db.get_block(&start_state_hash)?,
-                        ) {
-                            let start_height = start_block.blockchain_length();
-                            let end_height = end_block.blockchain_length();
-                            let mut parent_hash = end_block.previous_state_hash();
-                            let mut best_chain = vec![end_block];
-
-                            // constrain by num and state hash bound
-                            for _ in 1..num.min(end_height.saturating_sub(start_height) + 1) {
-                                if let Some((parent_pcb, _)) = db.get_block(&parent_hash)? {
-                                    let curr_hash: BlockHash = parent_pcb.state_hash();
-                                    parent_hash = parent_pcb.previous_state_hash();
-                                    best_chain.push(parent_pcb);
-
-                                    if curr_hash == start_state_hash {
-                                        break;
-                                    }
-                                } else {
-                                    break;
-                                }
-                            }
-
-                            let best_chain_str = if verbose {
-                                let best_chain: Vec<PrecomputedBlockWithCanonicity> = best_chain
-                                    .iter()
-                                    .flat_map(|block| {
-                                        if let Ok(Some(canonicity)) =
-                                            db.get_block_canonicity(&block.state_hash())
-                                        {
-                                            Some(block.with_canonicity(canonicity))
-                                        } else {
-                                            None
-                                        }
-                                    })
-                                    .collect();
-                                serde_json::to_string(&best_chain)?
-                            } else {
-                                let best_chain: Vec<BlockWithoutHeight> = best_chain
-                                    .iter()
-                                    .flat_map(|block| {
-                                        if let Ok(Some(canonicity)) =
-                                            db.get_block_canonicity(&block.state_hash())
-                                        {
-                                            Some(BlockWithoutHeight::with_canonicity(
-                                                block, canonicity,
-                                            ))
-                                        } else {
-                                            None
-                                        }
-                                    })
-                                    .collect();
-                                format_vec_jq_compatible(&best_chain)
-                            };
+                            let path = path.unwrap();
+                            if !path.is_dir() {
+                                info!("Writing events to {}", path.display());
 
-                            if path.is_none() {
-                                info!("Writing best chain to stdout");
-                                Some(best_chain_str)
+                                std::fs::write(&path, events_str)?;
+                                Some(format!("Events written to {}", path.display()))
                             } else {
-                                let path = path.unwrap();
-                                if !path.is_dir() {
-                                    info!("Writing best chain to {}", path.display());
-
-                                    std::fs::write(path.clone(), best_chain_str)?;
-                                    Some(format!("Best chain written to {}", path.display()))
-                                } else {
-                                    file_must_not_be_a_directory(&path)
-                                }
+                                return Err(file_must_not_be_a_directory(&path));
                             }
-                        } else {
-                            None
                         }
-                    } else {
-                        best_tip_missing_from_db()
                     }
                 }
-            },
-            ClientCli::CreateSnapshot { output_path } => {
-                info!("Received create-snapshot command");
-                match db.create_snapshot(&output_path) {
-                    Err(e) => Some(e.to_string()),
-                    Ok(s) => Some(s),
-                }
             }
-            ClientCli::Ledgers(__) => match __ {
-                Ledgers::Best { path } => {
-                    info!("Received best-ledger command");
-                    if let Some(best_tip) = db.get_best_block()? {
-                        if let Some(ledger) =
-                            db.get_ledger_state_hash(&best_tip.state_hash(), false)?
-                        {
-                            let ledger = ledger.to_string_pretty();
-
-                            if path.is_none() {
-                                debug!("Writing best ledger to stdout");
-                                Some(ledger)
-                            } else {
-                                let path = path.unwrap();
-                                if path.is_dir() {
-                                    file_must_not_be_a_directory(&path)
-                                } else {
-                                    debug!("Writing best ledger to {}", path.display());
+            Events::Checkpoints { path } => {
+                info!("Received event checkpoints command");
+                let checkpoints_str = serde_json::to_string_pretty(&db.get_event_checkpoints()?)?;
+                if path.is_none() {
+                    info!("Writing event checkpoints to stdout");
+                    Some(checkpoints_str)
+                } else {
+                    let path = path.unwrap();
+                    if !path.is_dir() {
+                        info!("Writing event checkpoints to {}", path.display());
 
-                                    std::fs::write(path.clone(), ledger)?;
-                                    Some(format!("Best ledger written to {}", path.display()))
-                                }
-                            }
-                        } else {
-                            error!("Best ledger cannot be calculated {}", best_tip.summary());
-                            Some(format!(
-                                "Best ledger cannot be calculated {}",
-                                best_tip.summary()
-                            ))
-                        }
+                        std::fs::write(&path, checkpoints_str)?;
+                        Some(format!("Event checkpoints written to {}", path.display()))
                     } else {
-                        best_tip_missing_from_db()
+                        return Err(file_must_not_be_a_directory(&path));
                     }
                 }
-                Ledgers::Hash { hash, path } => {
-                    info!("Received ledger command for {hash}");
-
-                    // check if ledger or state hash and use appropriate getter
-                    if block::is_valid_state_hash(&hash) {
-                        trace!("{hash} is a state hash");
-                        if let Some(ledger) =
-                            db.get_ledger_state_hash(&hash.clone().into(), true)?
-                        {
-                            let ledger = ledger.to_string_pretty();
-                            if path.is_none() {
-                                debug!("Writing ledger at state hash {hash} to stdout");
-                                Some(ledger)
-                            } else {
-                                let path = path.unwrap();
-                                if !path.is_dir() {
-                                    debug!("Writing ledger at {hash} to {}", path.display());
-
-                                    std::fs::write(path.clone(), ledger)?;
-                                    Some(format!(
-                                        "Ledger at state hash {hash} written to {}",
-                                        path.display()
-                                    ))
-                                } else {
-                                    file_must_not_be_a_directory(&path)
-                                }
-                            }
-                        } else {
-                            error!("Ledger at state hash {hash} is not in the store");
-                            Some(format!("Ledger at state hash {hash} is not in the store"))
-                        }
-                    } else if ledger::is_valid_ledger_hash(&hash) {
-                        trace!("{hash} is a ledger hash");
-
-                        if let Some(ledger) = db.get_ledger(&LedgerHash(hash.clone()))? {
-                            let ledger = ledger.to_string_pretty();
-                            if path.is_none() {
-                                debug!("Writing ledger at hash {hash} to stdout");
-                                Some(ledger)
-                            } else {
-                                let path = path.unwrap();
-                                if !path.is_dir() {
-                                    debug!("Writing ledger at {hash} to {}", path.display());
-
-                                    std::fs::write(path.clone(), ledger)?;
-                                    Some(format!(
-                                        "Ledger at hash {hash} written to {}",
-                                        path.display()
-                                    ))
-                                } else {
-                                    file_must_not_be_a_directory(&path)
-                                }
-                            }
-                        } else {
-                            error!("Ledger at {hash} is not in the store");
-                            Some(format!("Ledger at {hash} is not in the store"))
-                        }
+            }
+            Events::Compact { archive_path } => {
+                info!("Received compact events command");
+                if let Some(path) = archive_path.as_ref().filter(|path| path.is_dir()) {
+                    return Err(file_must_not_be_a_directory(path));
+                } else {
+                    let compaction = if let Some(path) = archive_path.as_ref() {
+                        info!("Archiving compacted events to {}", path.display());
+                        let mut archive = std::io::BufWriter::new(std::fs::File::create(path)?);
+                        db.compact_event_log(Some(&mut archive))
                     } else {
-                        error!("Invalid ledger or state hash: {hash}");
-                        Some(format!("Invalid ledger or state hash: {hash}"))
+                        db.compact_event_log(None)
+                    };
+
+                    match compaction {
+                        Ok(compaction) => Some(serde_json::to_string_pretty(&compaction)?),
+                        Err(e) => Some(e.to_string()),
                     }
                 }
-                Ledgers::Height { height, path } => {
-                    info!("Received ledger-at-height {height} command");
+            }
+        },
+        ClientCli::Ledgers(__) => match __ {
+            Ledgers::Best { path } => {
+                info!("Received best-ledger command");
+                if let Some(best_tip) = db.get_best_block()? {
+                    if let Some(ledger) = db.get_ledger_state_hash(&best_tip.state_hash(), false)? {
+                        let ledger = ledger.to_string_pretty();
 
-                    if let Ok(Some(best_tip_height)) = db.get_best_block_height() {
-                        if height > best_tip_height {
-                            // ahead of witness tree - cannot compute
-                            Some(format!("Invalid query: ledger at height {height} cannot be determined from a chain of length {best_tip_height}"))
+                        if path.is_none() {
+                            debug!("Writing best ledger to stdout");
+                            Some(ledger)
                         } else {
-                            let ledger_str = db
-                                .get_ledger_at_height(height, true)?
-                                .unwrap()
-                                .to_string_pretty();
-                            if path.is_none() {
-                                debug!("Writing ledger at height {height} to stdout");
-                                Some(ledger_str)
+                            let path = path.unwrap();
+                            if path.is_dir() {
+                                return Err(file_must_not_be_a_directory(&path));
                             } else {
-                                let path = path.unwrap();
-                                if !path.is_dir() {
-                                    debug!(
-                                        "Writing ledger at height {height} to {}",
-                                        path.display()
-                                    );
-
-                                    std::fs::write(&path, ledger_str)?;
-                                    Some(format!(
-                                        "Ledger at height {height} written to {}",
-                                        path.display()
-                                    ))
-                                } else {
-                                    file_must_not_be_a_directory(&path)
-                                }
+                                debug!("Writing best ledger to {}", path.display());
+
+                                std::fs::write(path.clone(), ledger)?;
+                                Some(format!("Best ledger written to {}", path.display()))
                             }
                         }
                     } else {
-                        best_tip_missing_from_db()
+                        error!("Best ledger cannot be calculated {}", best_tip.summary());
+                        Some(format!(
+                            "Best ledger cannot be calculated {}",
+                            best_tip.summary()
+                        ))
                     }
+                } else {
+                    return Err(best_tip_missing_from_db());
                 }
-            },
-            ClientCli::StakingLedgers(__) => match __ {
-                StakingLedgers::Hash { hash, path } => {
-                    info!("Received staking-ledgers-hash command for {hash}");
-
-                    if ledger::is_valid_ledger_hash(&hash) {
-                        trace!("{hash} is a ledger hash");
+            }
+            Ledgers::Hash { hash, path } => {
+                info!("Received ledger command for {hash}");
+
+                // check if ledger or state hash and use appropriate getter
+                if block::is_valid_state_hash(&hash) {
+                    trace!("{hash} is a state hash");
+                    if let Some(ledger) = db.get_ledger_state_hash(&hash.clone().into(), true)? {
+                        let ledger = ledger.to_string_pretty();
+                        if path.is_none() {
+                            debug!("Writing ledger at state hash {hash} to stdout");
+                            Some(ledger)
+                        } else {
+                            let path = path.unwrap();
+                            if !path.is_dir() {
+                                debug!("Writing ledger at {hash} to {}", path.display());
 
-                        if let Some(staking_ledger) =
-                            db.get_staking_ledger_by_hash(&hash.clone().into(), None, None)?
-                        {
-                            let ledger_json = serde_json::to_string_pretty(&staking_ledger)?;
-                            if path.is_none() {
-                                debug!("Writing staking ledger at hash {hash} to stdout");
-                                Some(ledger_json)
+                                std::fs::write(path.clone(), ledger)?;
+                                Some(format!(
+                                    "Ledger at state hash {hash} written to {}",
+                                    path.display()
+                                ))
                             } else {
-                                let path = path.unwrap();
-                                if !path.is_dir() {
-                                    debug!("Writing ledger at {hash} to {}", path.display());
-
-                                    std::fs::write(path.clone(), ledger_json)?;
-                                    Some(format!(
-                                        "Staking ledger at hash {hash} written to {}",
-                                        path.display()
-                                    ))
-                                } else {
-                                    file_must_not_be_a_directory(&path)
-                                }
+                                return Err(file_must_not_be_a_directory(&path));
                             }
-                        } else {
-                            error!("Staking ledger at {hash} is not in the store");
-                            Some(format!("Staking ledger at {hash} is not in the store"))
                         }
                     } else {
-                        error!("Invalid ledger hash: {hash}");
-                        Some(format!("Invalid ledger hash: {hash}"))
+                        error!("Ledger at state hash {hash} is not in the store");
+                        Some(format!("Ledger at state hash {hash} is not in the store"))
                     }
-                }
-                StakingLedgers::Epoch {
-                    epoch,
-                    genesis_state_hash,
-                    path,
-                } => {
-                    info!("Received staking-ledgers-epoch {epoch} command");
-
-                    if !block::is_valid_state_hash(&genesis_state_hash) {
-                        invalid_state_hash(&genesis_state_hash)
-                    } else if let Some(staking_ledger) =
-                        db.get_staking_ledger_at_epoch(epoch, Some(genesis_state_hash.into()))?
-                    {
-                        let ledger_json = serde_json::to_string_pretty(&staking_ledger)?;
+                } else if ledger::is_valid_ledger_hash(&hash) {
+                    trace!("{hash} is a ledger hash");
+
+                    if let Some(ledger) = db.get_ledger(&LedgerHash(hash.clone()))? {
+                        let ledger = ledger.to_string_pretty();
                         if path.is_none() {
-                            debug!("Writing staking ledger at epoch {epoch} to stdout");
-                            Some(ledger_json)
+                            debug!("Writing ledger at hash {hash} to stdout");
+                            Some(ledger)
                         } else {
                             let path = path.unwrap();
-
                             if !path.is_dir() {
-                                debug!("Writing ledger at epoch {epoch} to {}", path.display());
+                                debug!("Writing ledger at {hash} to {}", path.display());
 
-                                std::fs::write(path.clone(), ledger_json)?;
+                                std::fs::write(path.clone(), ledger)?;
                                 Some(format!(
-                                    "Staking ledger at epoch {epoch} written to {}",
+                                    "Ledger at hash {hash} written to {}",
                                     path.display()
                                 ))
                             } else {
-                                file_must_not_be_a_directory(&path)
+                                return Err(file_must_not_be_a_directory(&path));
                             }
                         }
                     } else {
-                        error!("Staking ledger at epoch {epoch} is not in the store");
-                        Some(format!(
-                            "Staking ledger at epoch {epoch} is not in the store"
-                        ))
+                        error!("Ledger at {hash} is not in the store");
+                        Some(format!("Ledger at {hash} is not in the store"))
                     }
+                } else {
+                    error!("Invalid ledger or state hash: {hash}");
+                    Some(format!("Invalid ledger or state hash: {hash}"))
                 }
-                StakingLedgers::PublicKey {
-                    epoch,
-                    genesis_state_hash,
-                    public_key: pk,
-                } => {
-                    info!(
-                        "Received staking-delegations command for pk {} epoch {}",
-                        pk, epoch,
-                    );
-
-                    if !block::is_valid_state_hash(&genesis_state_hash) {
-                        invalid_state_hash(&genesis_state_hash)
-                    } else if !public_key::is_valid_public_key(&pk) {
-                        invalid_public_key(&pk)
-                    } else if let Some(aggregated_delegations) =
-                        db.get_delegations_epoch(epoch, &Some(genesis_state_hash.into()))?
-                    {
-                        let pk: PublicKey = pk.into();
-                        let epoch = aggregated_delegations.epoch;
-                        let network = aggregated_delegations.network;
-                        let total_delegations = aggregated_delegations.total_delegations;
-                        let count_delegates = aggregated_delegations
-                            .delegations
-                            .get(&pk)
-                            .and_then(|agg_del| agg_del.count_delegates);
-                        let total_delegated = aggregated_delegations
-                            .delegations
-                            .get(&pk)
-                            .and_then(|agg_del| agg_del.total_delegated);
-                        let delegates = aggregated_delegations
-                            .delegations
-                            .get(&pk)
-                            .map_or(vec![], |agg_del| {
-                                agg_del.delegates.iter().cloned().collect()
-                            });
-                        Some(serde_json::to_string_pretty(
-                            &AggregatedEpochStakeDelegation {
-                                pk,
-                                epoch,
-                                network,
-                                count_delegates,
-                                total_delegated,
-                                total_stake: total_delegations,
-                                delegates,
-                            },
-                        )?)
+            }
+            Ledgers::Height { height, path } => {
+                info!("Received ledger-at-height {height} command");
+
+                if let Ok(Some(best_tip_height)) = db.get_best_block_height() {
+                    if height > best_tip_height {
+                        // ahead of witness tree - cannot compute
+                        Some(format!("Invalid query: ledger at height {height} cannot be determined from a chain of length {best_tip_height}"))
                     } else {
-                        error!("Public key {pk} is missing from staking ledger epoch {epoch}");
-                        Some(format!(
-                            "Public key {pk} is missing from staking ledger epoch {epoch}"
-                        ))
-                    }
-                }
-                StakingLedgers::Delegations {
-                    epoch,
-                    genesis_state_hash,
-                    path,
-                } => {
-                    info!("Received staking-delegations command for epoch {}", epoch);
-
-                    let aggregated_delegations =
-                        db.get_delegations_epoch(epoch, &Some(genesis_state_hash.into()))?;
-                    if let Some(agg_del_str) = aggregated_delegations
-                        .map(|agg_del| serde_json::to_string_pretty(&agg_del).unwrap())
-                    {
+                        let ledger_str = db
+                            .get_ledger_at_height(height, true)?
+                            .unwrap()
+                            .to_string_pretty();
                         if path.is_none() {
-                            debug!(
-                                "Writing aggregated staking delegations epoch {} to stdout",
-                                epoch
-                            );
-                            Some(agg_del_str)
+                            debug!("Writing ledger at height {height} to stdout");
+                            Some(ledger_str)
                         } else {
                             let path = path.unwrap();
                             if !path.is_dir() {
-                                debug!(
-                                    "Writing aggregated staking delegations epoch {} to {}",
-                                    epoch,
-                                    path.display()
-                                );
+                                debug!("Writing ledger at height {height} to {}", path.display());
 
-                                std::fs::write(&path, agg_del_str)?;
+                                std::fs::write(&path, ledger_str)?;
                                 Some(format!(
-                                    "Aggregated staking delegations epoch {} written to {}",
-                                    epoch,
+                                    "Ledger at height {height} written to {}",
                                     path.display()
                                 ))
                             } else {
-                                file_must_not_be_a_directory(&path)
+                                return Err(file_must_not_be_a_directory(&path));
                             }
                         }
-                    } else {
-                        error!("Unable to aggregate staking delegations epoch {}", epoch);
-                        Some(format!(
-                            "Unable to aggregate staking delegations epoch {}",
-                            epoch
-                        ))
                     }
+                } else {
+                    return Err(best_tip_missing_from_db());
                 }
-            },
-            ClientCli::Snarks(__) => match __ {
-                Snarks::PublicKey {
-                    public_key: pk,
-                    path,
-                } => {
-                    info!("Received SNARK work command for public key {pk}");
-
-                    if !public_key::is_valid_public_key(&pk) {
-                        invalid_public_key(&pk)
-                    } else {
-                        let snarks = db
-                            .get_snark_work_by_public_key(&pk.clone().into())?
-                            .unwrap_or(vec![]);
-                        let snarks_str = format_vec_jq_compatible(&snarks);
+            }
+        },
+        ClientCli::StakingLedgers(__) => match __ {
+            StakingLedgers::Hash { hash, path } => {
+                info!("Received staking-ledgers-hash command for {hash}");
 
+                if ledger::is_valid_ledger_hash(&hash) {
+                    trace!("{hash} is a ledger hash");
+
+                    if let Some(staking_ledger) =
+                        db.get_staking_ledger_by_hash(&hash.clone().into(), None, None)?
+                    {
+                        let ledger_json = serde_json::to_string_pretty(&staking_ledger)?;
                         if path.is_none() {
-                            debug!("Writing SNARK work for public key {pk} to stdout");
-                            Some(snarks_str)
+                            debug!("Writing staking ledger at hash {hash} to stdout");
+                            Some(ledger_json)
                         } else {
                             let path = path.unwrap();
-
                             if !path.is_dir() {
-                                debug!(
-                                    "Writing SNARK work for public key {pk} to {}",
-                                    path.display()
-                                );
+                                debug!("Writing ledger at {hash} to {}", path.display());
 
-                                std::fs::write(&path, snarks_str)?;
+                                std::fs::write(path.clone(), ledger_json)?;
                                 Some(format!(
-                                    "SNARK work for public key {pk} written to {}",
+                                    "Staking ledger at hash {hash} written to {}",
                                     path.display()
                                 ))
                             } else {
-                                file_must_not_be_a_directory(&path)
+                                return Err(file_must_not_be_a_directory(&path));
                             }
                         }
+                    } else {
+                        error!("Staking ledger at {hash} is not in the store");
+                        Some(format!("Staking ledger at {hash} is not in the store"))
                     }
+                } else {
+                    error!("Invalid ledger hash: {hash}");
+                    Some(format!("Invalid ledger hash: {hash}"))
                 }
-                Snarks::StateHash { state_hash, path } => {
-                    info!("Received SNARK work command for state hash {state_hash}");
-
-                    if !block::is_valid_state_hash(&state_hash) {
-                        invalid_state_hash(&state_hash)
+            }
+            StakingLedgers::Epoch {
+                epoch,
+                genesis_state_hash,
+                path,
+            } => {
+                info!("Received staking-ledgers-epoch {epoch} command");
+
+                if !block::is_valid_state_hash(&genesis_state_hash) {
+                    return Err(invalid_state_hash(&genesis_state_hash));
+                } else if let Some(staking_ledger) =
+                    db.get_staking_ledger_at_epoch(epoch, Some(genesis_state_hash.into()))?
+                {
+                    let ledger_json = serde_json::to_string_pretty(&staking_ledger)?;
+                    if path.is_none() {
+                        debug!("Writing staking ledger at epoch {epoch} to stdout");
+                        Some(ledger_json)
                     } else {
-                        db.get_snark_work_in_block(&state_hash.clone().into())?
-                            .and_then(|snarks| {
-                                let snarks_str = format_vec_jq_compatible(&snarks);
-                                if path.is_none() {
-                                    debug!("Writing SNARK work for block {state_hash} to stdout");
-                                    Some(snarks_str)
-                                } else {
-                                    let path = path.unwrap();
-
-                                    if !path.is_dir() {
-                                        debug!(
-                                            "Writing SNARK work for block {state_hash} to {}",
-                                            path.display()
-                                        );
-
-                                        std::fs::write(&path, snarks_str).unwrap();
-                                        Some(format!(
-                                            "SNARK work for block {state_hash} written to {}",
-                                            path.display()
-                                        ))
-                                    } else {
-                                        file_must_not_be_a_directory(&path)
-                                    }
-                                }
-                            })
+                        let path = path.unwrap();
+
+                        if !path.is_dir() {
+                            debug!("Writing ledger at epoch {epoch} to {}", path.display());
+
+                            std::fs::write(path.clone(), ledger_json)?;
+                            Some(format!(
+                                "Staking ledger at epoch {epoch} written to {}",
+                                path.display()
+                            ))
+                        } else {
+                            return Err(file_must_not_be_a_directory(&path));
+                        }
                     }
+                } else {
+                    error!("Staking ledger at epoch {epoch} is not in the store");
+                    Some(format!(
+                        "Staking ledger at epoch {epoch} is not in the store"
+                    ))
                 }
-                Snarks::Top { num } => {
-                    info!("Received top {num} SNARKers command");
+            }
+            StakingLedgers::PublicKey {
+                epoch,
+                genesis_state_hash,
+                public_key: pk,
+            } => {
+                info!(
+                    "Received staking-delegations command for pk {} epoch {}",
+                    pk, epoch,
+                );
+
+                if !block::is_valid_state_hash(&genesis_state_hash) {
+                    return Err(invalid_state_hash(&genesis_state_hash));
+                } else if !public_key::is_valid_public_key(&pk) {
+                    return Err(invalid_public_key(&pk));
+                } else if let Some(aggregated_delegations) =
+                    db.get_delegations_epoch(epoch, &Some(genesis_state_hash.into()))?
+                {
+                    let pk: PublicKey = pk.into();
+                    let epoch = aggregated_delegations.epoch;
+                    let network = aggregated_delegations.network;
+                    let total_delegations = aggregated_delegations.total_delegations;
+                    let count_delegates = aggregated_delegations
+                        .delegations
+                        .get(&pk)
+                        .and_then(|agg_del| agg_del.count_delegates);
+                    let total_delegated = aggregated_delegations
+                        .delegations
+                        .get(&pk)
+                        .and_then(|agg_del| agg_del.total_delegated);
+                    let delegates = aggregated_delegations
+                        .delegations
+                        .get(&pk)
+                        .map_or(vec![], |agg_del| {
+                            agg_del.delegates.iter().cloned().collect()
+                        });
                     Some(serde_json::to_string_pretty(
-                        &db.get_top_snark_workers_by_fees(num)?,
+                        &AggregatedEpochStakeDelegation {
+                            pk,
+                            epoch,
+                            network,
+                            count_delegates,
+                            total_delegated,
+                            total_stake: total_delegations,
+                            delegates,
+                        },
                     )?)
+                } else {
+                    error!("Public key {pk} is missing from staking ledger epoch {epoch}");
+                    Some(format!(
+                        "Public key {pk} is missing from staking ledger epoch {epoch}"
+                    ))
                 }
-            },
-            ClientCli::Shutdown => {
-                info!("Received shutdown command");
-                writer
-                    .write_all(b"Shutting down the Mina Indexer daemon...")
-                    .await?;
-                subsys.request_shutdown();
-                return Ok(());
             }
-            ClientCli::Summary {
-                verbose,
-                json,
+            StakingLedgers::Delegations {
+                epoch,
+                genesis_state_hash,
                 path,
             } => {
-                info!("Received summary command");
+                info!("Received staking-delegations command for epoch {}", epoch);
+
+                let aggregated_delegations =
+                    db.get_delegations_epoch(epoch, &Some(genesis_state_hash.into()))?;
+                if let Some(agg_del_str) = aggregated_delegations
+                    .map(|agg_del| serde_json::to_string_pretty(&agg_del).unwrap())
+                {
+                    if path.is_none() {
+                        debug!(
+                            "Writing aggregated staking delegations epoch {} to stdout",
+                            epoch
+                        );
+                        Some(agg_del_str)
+                    } else {
+                        let path = path.unwrap();
+                        if !path.is_dir() {
+                            debug!(
+                                "Writing aggregated staking delegations epoch {} to {}",
+                                epoch,
+                                path.display()
+                            );
 
-                let summary = state.summary_verbose();
-                let summary_str = if verbose {
-                    format_json(&summary, json)
+                            std::fs::write(&path, agg_del_str)?;
+                            Some(format!(
+                                "Aggregated staking delegations epoch {} written to {}",
+                                epoch,
+                                path.display()
+                            ))
+                        } else {
+                            return Err(file_must_not_be_a_directory(&path));
+                        }
+                    }
                 } else {
-                    let summary: SummaryShort = summary.clone().into();
-                    format_json(&summary, json)
-                };
+                    error!("Unable to aggregate staking delegations epoch {}", epoch);
+                    Some(format!(
+                        "Unable to aggregate staking delegations epoch {}",
+                        epoch
+                    ))
+                }
+            }
+        },
+        ClientCli::Snarks(__) => match __ {
+            Snarks::PublicKey {
+                public_key: pk,
+                path,
+                ..
+            } => {
+                info!("Received SNARK work command for public key {pk}");
 
-                if path.is_none() {
-                    info!("Writing summary to stdout");
-                    Some(summary_str)
+                if !public_key::is_valid_public_key(&pk) {
+                    return Err(invalid_public_key(&pk));
                 } else {
-                    let path = path.unwrap();
-                    if !path.is_dir() {
-                        info!("Writing summary to {}", path.display());
+                    let snarks = db
+                        .get_snark_work_by_public_key(&pk.clone().into())?
+                        .unwrap_or(vec![]);
+                    let snarks_str = format_vec_jq_compatible(&snarks);
 
-                        std::fs::write(&path, summary_str)?;
-                        Some(format!("Summary written to {}", path.display()))
+                    if path.is_none() {
+                        debug!("Writing SNARK work for public key {pk} to stdout");
+                        Some(snarks_str)
                     } else {
-                        file_must_not_be_a_directory(&path)
+                        let path = path.unwrap();
+
+                        if !path.is_dir() {
+                            debug!(
+                                "Writing SNARK work for public key {pk} to {}",
+                                path.display()
+                            );
+
+                            std::fs::write(&path, snarks_str)?;
+                            Some(format!(
+                                "SNARK work for public key {pk} written to {}",
+                                path.display()
+                            ))
+                        } else {
+                            return Err(file_must_not_be_a_directory(&path));
+                        }
                     }
                 }
             }
-            ClientCli::Transactions(__) => match __ {
-                Transactions::PublicKey {
-                    public_key: pk,
-                    verbose,
-                    start_state_hash,
-                    end_state_hash,
-                    path,
-                } => {
-                    let start_state_hash: BlockHash = start_state_hash.into();
-                    let end_state_hash: BlockHash = {
-                        let raw = end_state_hash.unwrap_or("x".to_string());
-                        if &raw == "x" {
-                            // dummy value replaced with best block state hash
-                            if let Some(best_tip) = db.get_best_block()? {
-                                best_tip.state_hash()
-                            } else {
-                                best_tip_missing_from_db().unwrap().into()
-                            }
+            Snarks::StateHash { state_hash, path } => {
+                info!("Received SNARK work command for state hash {state_hash}");
+
+                if !block::is_valid_state_hash(&state_hash) {
+                    return Err(invalid_state_hash(&state_hash));
+                } else if let Some(snarks) =
+                    db.get_snark_work_in_block(&state_hash.clone().into())?
+                {
+                    let snarks_str = format_vec_jq_compatible(&snarks);
+                    if path.is_none() {
+                        debug!("Writing SNARK work for block {state_hash} to stdout");
+                        Some(snarks_str)
+                    } else {
+                        let path = path.unwrap();
+
+                        if !path.is_dir() {
+                            debug!(
+                                "Writing SNARK work for block {state_hash} to {}",
+                                path.display()
+                            );
+
+                            std::fs::write(&path, snarks_str)?;
+                            Some(format!(
+                                "SNARK work for block {state_hash} written to {}",
+                                path.display()
+                            ))
+                        } else {
+                            return Err(file_must_not_be_a_directory(&path));
+                        }
+                    }
+                } else {
+                    None
+                }
+            }
+            Snarks::Top { num } => {
+                info!("Received top {num} SNARKers command");
+                Some(serde_json::to_string_pretty(
+                    &db.get_top_snark_workers_by_fees(num)?,
+                )?)
+            }
+        },
+        ClientCli::Health => {
+            info!("Received health command");
+            Some(serde_json::to_string_pretty(&health.status(db)?)?)
+        }
+        ClientCli::Shutdown => {
+            info!("Received shutdown command");
+            shutdown_tx.send(())?;
+            Some("Shutting down the Mina Indexer daemon...".to_string())
+        }
+        ClientCli::Summary {
+            verbose,
+            json,
+            path,
+        } => {
+            info!("Received summary command");
+
+            let summary = state.summary_verbose();
+            let summary_str = if verbose {
+                format_json(&summary, json)
+            } else {
+                let summary: SummaryShort = summary.clone().into();
+                format_json(&summary, json)
+            };
+
+            if path.is_none() {
+                info!("Writing summary to stdout");
+                Some(summary_str)
+            } else {
+                let path = path.unwrap();
+                if !path.is_dir() {
+                    info!("Writing summary to {}", path.display());
+
+                    std::fs::write(&path, summary_str)?;
+                    Some(format!("Summary written to {}", path.display()))
+                } else {
+                    return Err(file_must_not_be_a_directory(&path));
+                }
+            }
+        }
+        ClientCli::Transactions(__) => match __ {
+            Transactions::PublicKey {
+                public_key: pk,
+                verbose,
+                start_state_hash,
+                end_state_hash,
+                path,
+                ..
+            } => {
+                let start_state_hash: BlockHash = start_state_hash.into();
+                let end_state_hash: BlockHash = {
+                    let raw = end_state_hash.unwrap_or("x".to_string());
+                    if &raw == "x" {
+                        // dummy value replaced with best block state hash
+                        if let Some(best_tip) = db.get_best_block()? {
+                            best_tip.state_hash()
                         } else {
-                            raw.into()
+                            return Err(best_tip_missing_from_db());
                         }
+                    } else {
+                        raw.into()
+                    }
+                };
+                info!("Received tx-public-key command for {pk}");
+
+                if !public_key::is_valid_public_key(&pk) {
+                    return Err(invalid_public_key(&pk));
+                } else if !block::is_valid_state_hash(&start_state_hash.0) {
+                    return Err(invalid_state_hash(&start_state_hash.0));
+                } else if !block::is_valid_state_hash(&end_state_hash.0) {
+                    return Err(invalid_state_hash(&end_state_hash.0));
+                } else {
+                    let transactions = db
+                        .get_user_commands_for_public_key(&pk.clone().into())?
+                        .unwrap_or_default();
+                    let transaction_str = if verbose {
+                        format_vec_jq_compatible(&transactions)
+                    } else {
+                        let txs: Vec<Command> =
+                            transactions.into_iter().map(Command::from).collect();
+                        format_vec_jq_compatible(&txs)
                     };
-                    info!("Received tx-public-key command for {pk}");
-
-                    if !public_key::is_valid_public_key(&pk) {
-                        invalid_public_key(&pk)
-                    } else if !block::is_valid_state_hash(&start_state_hash.0) {
-                        invalid_state_hash(&start_state_hash.0)
-                    } else if !block::is_valid_state_hash(&end_state_hash.0) {
-                        invalid_state_hash(&end_state_hash.0)
+
+                    if path.is_none() {
+                        debug!("Writing transactions for {pk} to stdout");
+                        Some(transaction_str)
                     } else {
-                        let transactions = db
-                            .get_user_commands_for_public_key(&pk.clone().into())?
-                            .unwrap_or_default();
-                        let transaction_str = if verbose {
-                            format_vec_jq_compatible(&transactions)
-                        } else {
-                            let txs: Vec<Command> =
-                                transactions.into_iter().map(Command::from).collect();
-                            format_vec_jq_compatible(&txs)
-                        };
+                        let path = path.unwrap();
+                        if !path.is_dir() {
+                            debug!("Writing transactions for {pk} to {}", path.display());
 
-                        if path.is_none() {
-                            debug!("Writing transactions for {pk} to stdout");
-                            Some(transaction_str)
+                            std::fs::write(&path, transaction_str)?;
+                            Some(format!(
+                                "Transactions for {pk} written to {}",
+                                path.display()
+                            ))
                         } else {
-                            let path = path.unwrap();
-                            if !path.is_dir() {
-                                debug!("Writing transactions for {pk} to {}", path.display());
-
-                                std::fs::write(&path, transaction_str)?;
-                                Some(format!(
-                                    "Transactions for {pk} written to {}",
-                                    path.display()
-                                ))
-                            } else {
-                                file_must_not_be_a_directory(&path)
-                            }
+                            return Err(file_must_not_be_a_directory(&path));
                         }
                     }
                 }
-                Transactions::Hash { hash, verbose } => {
-                    info!("Received tx-hash command for {hash}");
-                    if !signed::is_valid_tx_hash(&hash) {
-                        invalid_tx_hash(&hash)
-                    } else {
-                        db.get_user_command(&hash, 0)?.map(|cmd| {
-                            if verbose {
-                                format!("{cmd:?}")
+            }
+            Transactions::Hash { hash, verbose } => {
+                info!("Received tx-hash command for {hash}");
+                if !signed::is_valid_tx_hash(&hash) {
+                    return Err(invalid_tx_hash(&hash));
+                } else {
+                    db.get_user_command(&hash, 0)?
+                        .map(|cmd| -> anyhow::Result<String> {
+                            let state_hash = cmd.state_hash.clone();
+                            let mut json: serde_json::Value = if verbose {
+                                cmd.into()
                             } else {
-                                let cmd: Command = cmd.into();
-                                format!("{cmd:?}")
+                                Command::from(cmd).into()
+                            };
+                            if let Some(obj) = json.as_object_mut() {
+                                obj.insert(
+                                    "confirmations".into(),
+                                    db.get_block_confirmations(&state_hash)?.into(),
+                                );
+                                obj.insert(
+                                    "is_final".into(),
+                                    db.is_block_final(&state_hash)?.into(),
+                                );
                             }
+                            Ok(serde_json::to_string_pretty(&json)?)
                         })
-                    }
+                        .transpose()?
                 }
-                Transactions::StateHash {
-                    state_hash,
-                    verbose,
-                    path,
-                } => {
-                    info!("Received tx-state-hash command for {state_hash}");
-                    if !block::is_valid_state_hash(&state_hash) {
-                        invalid_state_hash(&state_hash)
-                    } else {
-                        let block_hash = BlockHash(state_hash.to_owned());
-                        db.get_block_user_commands(&block_hash)
-                            .unwrap_or_default()
-                            .map(|cmds| {
-                                let transaction_str = if verbose {
-                                    format_vec_jq_compatible(&cmds)
-                                } else {
-                                    let cmds: Vec<Command> =
-                                        cmds.into_iter().map(Command::from).collect();
-                                    format_vec_jq_compatible(&cmds)
-                                };
-                                if path.is_none() {
-                                    debug!("Writing transactions for {state_hash} to stdout");
-                                    transaction_str
-                                } else {
-                                    let path = path.unwrap();
-                                    if !path.is_dir() {
-                                        debug!(
-                                            "Writing transactions for {state_hash} to {}",
-                                            path.display()
-                                        );
-
-                                        std::fs::write(&path, transaction_str).unwrap();
-                                        format!(
-                                            "Transactions for {state_hash} written to {}",
-                                            path.display()
-                                        )
-                                    } else {
-                                        file_must_not_be_a_directory(&path).unwrap()
-                                    }
-                                }
-                            })
-                    }
-                }
-            },
-            ClientCli::InternalCommands(__) => match __ {
-                InternalCommands::PublicKey {
-                    path,
-                    public_key: pk,
-                } => {
-                    if !public_key::is_valid_public_key(&pk) {
-                        invalid_public_key(&pk)
-                    } else {
-                        let internal_cmds =
-                            db.get_internal_commands_public_key(&pk.clone().into())?;
-                        let internal_cmds_str = serde_json::to_string_pretty(&internal_cmds)?;
-
+            }
+            Transactions::StateHash {
+                state_hash,
+                verbose,
+                path,
+            } => {
+                info!("Received tx-state-hash command for {state_hash}");
+                if !block::is_valid_state_hash(&state_hash) {
+                    return Err(invalid_state_hash(&state_hash));
+                } else {
+                    let block_hash = BlockHash(state_hash.to_owned());
+                    if let Some(cmds) = db.get_block_user_commands(&block_hash).unwrap_or_default()
+                    {
+                        let transaction_str = if verbose {
+                            format_vec_jq_compatible(&cmds)
+                        } else {
+                            let cmds: Vec<Command> = cmds.into_iter().map(Command::from).collect();
+                            format_vec_jq_compatible(&cmds)
+                        };
                         if path.is_none() {
-                            debug!("Writing internal commands for {} to stdout", pk);
-                            Some(internal_cmds_str)
+                            debug!("Writing transactions for {state_hash} to stdout");
+                            Some(transaction_str)
                         } else {
                             let path = path.unwrap();
                             if !path.is_dir() {
                                 debug!(
-                                    "Writing internal commands for {} to {}",
-                                    pk,
+                                    "Writing transactions for {state_hash} to {}",
                                     path.display()
                                 );
 
-                                std::fs::write(&path, internal_cmds_str)?;
+                                std::fs::write(&path, transaction_str)?;
                                 Some(format!(
-                                    "Internal commands for {} written to {}",
-                                    pk,
+                                    "Transactions for {state_hash} written to {}",
                                     path.display()
                                 ))
                             } else {
-                                file_must_not_be_a_directory(&path)
+                                return Err(file_must_not_be_a_directory(&path));
                             }
                         }
+                    } else {
+                        None
                     }
                 }
-                InternalCommands::StateHash { path, state_hash } => {
-                    info!("Received internal-state-hash command for {}", state_hash);
+            }
+        },
+        ClientCli::InternalCommands(__) => match __ {
+            InternalCommands::PublicKey {
+                path,
+                public_key: pk,
+                ..
+            } => {
+                if !public_key::is_valid_public_key(&pk) {
+                    return Err(invalid_public_key(&pk));
+                } else {
+                    let internal_cmds = db.get_internal_commands_public_key(&pk.clone().into())?;
+                    let internal_cmds_str = serde_json::to_string_pretty(&internal_cmds)?;
 
-                    if !block::is_valid_state_hash(&state_hash) {
-                        invalid_state_hash(&state_hash)
+                    if path.is_none() {
+                        debug!("Writing internal commands for {} to stdout", pk);
+                        Some(internal_cmds_str)
                     } else {
-                        let internal_cmds_str = serde_json::to_string_pretty(
-                            &db.get_internal_commands(&state_hash.clone().into())?,
-                        )?;
+                        let path = path.unwrap();
+                        if !path.is_dir() {
+                            debug!("Writing internal commands for {} to {}", pk, path.display());
 
-                        if path.is_none() {
+                            std::fs::write(&path, internal_cmds_str)?;
+                            Some(format!(
+                                "Internal commands for {} written to {}",
+                                pk,
+                                path.display()
+                            ))
+                        } else {
+                            return Err(file_must_not_be_a_directory(&path));
+                        }
+                    }
+                }
+            }
+            InternalCommands::StateHash { path, state_hash } => {
+                info!("Received internal-state-hash command for {}", state_hash);
+
+                if !block::is_valid_state_hash(&state_hash) {
+                    return Err(invalid_state_hash(&state_hash));
+                } else {
+                    let internal_cmds_str = serde_json::to_string_pretty(
+                        &db.get_internal_commands(&state_hash.clone().into())?,
+                    )?;
+
+                    if path.is_none() {
+                        debug!(
+                            "Writing block internal commands for {} to stdout",
+                            state_hash
+                        );
+                        Some(internal_cmds_str)
+                    } else {
+                        let path = path.unwrap();
+                        if !path.is_dir() {
                             debug!(
-                                "Writing block internal commands for {} to stdout",
-                                state_hash
+                                "Writing block internal commands for {} to {}",
+                                state_hash,
+                                path.display()
                             );
-                            Some(internal_cmds_str)
+
+                            std::fs::write(&path, internal_cmds_str)?;
+                            Some(format!(
+                                "Block internal commands for {} written to {}",
+                                state_hash,
+                                path.display()
+                            ))
                         } else {
-                            let path = path.unwrap();
-                            if !path.is_dir() {
-                                debug!(
-                                    "Writing block internal commands for {} to {}",
-                                    state_hash,
-                                    path.display()
-                                );
+                            return Err(file_must_not_be_a_directory(&path));
+                        }
+                    }
+                }
+            }
+        },
+        ClientCli::Reorgs(__) => match __ {
+            Reorgs::List {
+                min_depth,
+                epoch,
+                state_hash,
+                txn_hash,
+                public_key,
+                limit,
+                path,
+            } => {
+                info!("Received reorgs command");
+                if let Some(state_hash) = state_hash
+                    .as_ref()
+                    .filter(|state_hash| !block::is_valid_state_hash(state_hash))
+                {
+                    return Err(invalid_state_hash(state_hash));
+                } else if let Some(txn_hash) = txn_hash
+                    .as_ref()
+                    .filter(|txn_hash| !signed::is_valid_tx_hash(txn_hash))
+                {
+                    return Err(invalid_tx_hash(txn_hash));
+                } else if let Some(pk) = public_key
+                    .as_ref()
+                    .filter(|pk| !public_key::is_valid_public_key(pk))
+                {
+                    return Err(invalid_public_key(pk));
+                } else {
+                    let filter = ReorgFilter {
+                        min_depth,
+                        epoch,
+                        state_hash: state_hash.map(Into::into),
+                        txn_hash,
+                        public_key: public_key.map(Into::into),
+                    };
+                    let reorgs_str =
+                        serde_json::to_string_pretty(&db.get_reorgs(&filter, limit as usize)?)?;
+                    if path.is_none() {
+                        info!("Writing reorgs to stdout");
+                        Some(reorgs_str)
+                    } else {
+                        let path = path.unwrap();
+                        if !path.is_dir() {
+                            info!("Writing reorgs to {}", path.display());
 
-                                std::fs::write(&path, internal_cmds_str)?;
-                                Some(format!(
-                                    "Block internal commands for {} written to {}",
-                                    state_hash,
-                                    path.display()
-                                ))
-                            } else {
-                                file_must_not_be_a_directory(&path)
-                            }
+                            std::fs::write(&path, reorgs_str)?;
+                            Some(format!("Reorgs written to {}", path.display()))
+                        } else {
+                            return Err(file_must_not_be_a_directory(&path));
                         }
                     }
                 }
-            },
-            ClientCli::DbVersion => {
-                Some(format!("mina-indexer database v{}", db.get_db_version()?))
             }
-        };
+            Reorgs::Stats { path } => {
+                info!("Received reorg stats command");
+                let stats_str = serde_json::to_string_pretty(&db.get_reorg_stats()?)?;
+                if path.is_none() {
+                    info!("Writing reorg stats to stdout");
+                    Some(stats_str)
+                } else {
+                    let path = path.unwrap();
+                    if !path.is_dir() {
+                        info!("Writing reorg stats to {}", path.display());
 
-        let response = if let Some(response_json) = response_json {
-            response_json
-        } else {
-            serde_json::to_string("no response 404")?
-        };
-        writer.write_all(response.as_bytes()).await?;
-    }
-    Ok(())
+                        std::fs::write(&path, stats_str)?;
+                        Some(format!("Reorg stats written to {}", path.display()))
+                    } else {
+                        return Err(file_must_not_be_a_directory(&path));
+                    }
+                }
+            }
+        },
+        ClientCli::Webhooks(__) => match __ {
+            Webhooks::Add {
+                url,
+                secret,
+                kinds,
+                public_keys,
+                min_amount,
+                confirmations,
+            } => {
+                info!("Received add webhook command {url}");
+                match WebhookFilter::parse(
+                    kinds.as_deref(),
+                    public_keys.as_deref(),
+                    min_amount,
+                    confirmations,
+                )
+                .and_then(|filter| db.add_webhook(&url, &secret, filter))
+                {
+                    Ok(webhook) => Some(serde_json::to_string_pretty(&webhook.redacted())?),
+                    Err(e) => Some(e.to_string()),
+                }
+            }
+            Webhooks::List { path } => {
+                info!("Received list webhooks command");
+                let mut webhooks = vec![];
+                for webhook in db.get_webhooks()? {
+                    webhooks.push(serde_json::json!({
+                        "webhook": webhook.redacted(),
+                        "cursor": db.get_webhook_cursor(webhook.id)?,
+                    }));
+                }
+
+                let webhooks_str = serde_json::to_string_pretty(&webhooks)?;
+                if path.is_none() {
+                    info!("Writing webhooks to stdout");
+                    Some(webhooks_str)
+                } else {
+                    let path = path.unwrap();
+                    if !path.is_dir() {
+                        info!("Writing webhooks to {}", path.display());
+
+                        std::fs::write(&path, webhooks_str)?;
+                        Some(format!("Webhooks written to {}", path.display()))
+                    } else {
+                        return Err(file_must_not_be_a_directory(&path));
+                    }
+                }
+            }
+            Webhooks::Remove { id } => {
+                info!("Received remove webhook command {id}");
+                if db.remove_webhook(id)? {
+                    Some(format!("Removed webhook {id}"))
+                } else {
+                    Some(format!("Webhook {id} is not registered"))
+                }
+            }
+        },
+        ClientCli::DbVersion => Some(format!("mina-indexer database v{}", db.get_db_version()?)),
+    })
 }
 
-fn file_must_not_be_a_directory(path: &std::path::Path) -> Option<String> {
-    Some(format!(
-        "The path provided must not be a directory: {}",
-        path.display()
-    ))
+fn file_must_not_be_a_directory(path: &std::path::Path) -> anyhow::Error {
+    ClientError::PathIsDirectory(path.to_path_buf()).into()
 }
 
 fn try_replace_old_socket(e: io::Error, unix_socket_path: &Path) -> io::Result<UnixListener> {
@@ -1253,35 +1663,29 @@ pub fn remove_unix_socket(unix_socket_path: &Path) -> io::Result<()> {
 mod helpers {
     use super::*;
 
-    pub fn invalid_public_key(input: &str) -> Option<String> {
-        let msg = format!("Invalid public key: {input}");
+    pub fn invalid_public_key(input: &str) -> anyhow::Error {
         error!("Invalid public key: {}", input);
-        Some(msg)
+        ClientError::InvalidPublicKey(input.to_string()).into()
     }
 
-    pub fn invalid_tx_hash(input: &str) -> Option<String> {
-        let msg = format!("Invalid transaction hash: {input}");
+    pub fn invalid_tx_hash(input: &str) -> anyhow::Error {
         error!("Invalid transaction hash: {}", input);
-        Some(msg)
+        ClientError::InvalidTxHash(input.to_string()).into()
     }
 
-    pub fn invalid_state_hash(input: &str) -> Option<String> {
-        let msg = format!("Invalid state hash: {input}");
+    pub fn invalid_state_hash(input: &str) -> anyhow::Error {
         error!("Invalid state hash: {}", input);
-        Some(msg)
+        ClientError::InvalidStateHash(input.to_string()).into()
     }
 
-    pub fn block_missing_from_db(state_hash: &str) -> String {
-        let msg = format!("Block missing from store: {state_hash}");
+    pub fn block_missing_from_db(state_hash: &str) -> anyhow::Error {
         error!("Block missing from store: {}", state_hash);
-        msg
+        ClientError::BlockMissing(state_hash.to_string()).into()
     }
 
-    /// Always returns `Some`, safe to `.unwrap()`
-    pub fn best_tip_missing_from_db() -> Option<String> {
-        let msg = "Best tip block missing from store";
+    pub fn best_tip_missing_from_db() -> anyhow::Error {
         error!("Best tip block missing from store");
-        Some(msg.to_string())
+        ClientError::BestTipMissing.into()
     }
 
     pub fn format_vec_jq_compatible<T>(vec: &Vec<T>) -> String
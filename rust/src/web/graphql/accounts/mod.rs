@@ -1,20 +1,23 @@
 use super::db;
 use crate::{
-    block::store::BlockStore,
-    command::{internal::store::InternalCommandStore, store::UserCommandStore},
     ledger::{account, public_key::PublicKey, store::LedgerStore},
-    snark_work::store::SnarkStore,
-    store::{account::AccountStore, username::UsernameStore},
-    web::graphql::Timing,
+    store::{account::AccountStore, username::UsernameStore, IndexerStore},
+    web::graphql::{
+        limits::list_limit,
+        loaders::{loader, PkCounts, PkCountsLoader, UsernameLoader},
+        pagination::{Page, SortKeyConnection},
+        Timing,
+    },
 };
-use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
+use async_graphql::{ComplexObject, Context, Enum, InputObject, Object, Result, SimpleObject};
 use log::warn;
-use speedb::IteratorMode;
+use speedb::{Direction, IteratorMode};
+use std::sync::Arc;
 
 #[derive(SimpleObject)]
+#[graphql(complex)]
 pub struct Account {
     public_key: String,
-    username: Option<String>,
     delegate: String,
     balance: u64,
     nonce: u32,
@@ -23,30 +26,65 @@ pub struct Account {
 
     #[graphql(name = "is_genesis_account")]
     is_genesis_account: bool,
+}
+
+#[ComplexObject]
+impl Account {
+    async fn username(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let username = loader::<UsernameLoader>(ctx)
+            .load_one(self.public_key.clone().into())
+            .await?;
+        Ok(username.or(Some("Unknown".to_string())))
+    }
 
     #[graphql(name = "pk_epoch_num_blocks")]
-    pk_epoch_num_blocks: u32,
+    async fn pk_epoch_num_blocks(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.epoch_num_blocks)
+    }
 
     #[graphql(name = "pk_total_num_blocks")]
-    pk_total_num_blocks: u32,
+    async fn pk_total_num_blocks(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.total_num_blocks)
+    }
 
     #[graphql(name = "pk_epoch_num_snarks")]
-    pk_epoch_num_snarks: u32,
+    async fn pk_epoch_num_snarks(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.epoch_num_snarks)
+    }
 
     #[graphql(name = "pk_total_num_snarks")]
-    pk_total_num_snarks: u32,
+    async fn pk_total_num_snarks(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.total_num_snarks)
+    }
 
     #[graphql(name = "pk_epoch_num_user_commands")]
-    pk_epoch_num_user_commands: u32,
+    async fn pk_epoch_num_user_commands(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.epoch_num_user_commands)
+    }
 
     #[graphql(name = "pk_total_num_user_commands")]
-    pk_total_num_user_commands: u32,
+    async fn pk_total_num_user_commands(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.total_num_user_commands)
+    }
 
     #[graphql(name = "pk_epoch_num_internal_commands")]
-    pk_epoch_num_internal_commands: u32,
+    async fn pk_epoch_num_internal_commands(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.epoch_num_internal_commands)
+    }
 
     #[graphql(name = "pk_total_num_internal_commands")]
-    pk_total_num_internal_commands: u32,
+    async fn pk_total_num_internal_commands(&self, ctx: &Context<'_>) -> Result<u32> {
+        Ok(self.pk_counts(ctx).await?.total_num_internal_commands)
+    }
+}
+
+impl Account {
+    async fn pk_counts(&self, ctx: &Context<'_>) -> Result<PkCounts> {
+        Ok(loader::<PkCountsLoader>(ctx)
+            .load_one((self.public_key.clone().into(), None))
+            .await?
+            .unwrap_or_default())
+    }
 }
 
 #[derive(InputObject)]
@@ -84,6 +122,7 @@ pub struct AccountQueryRoot;
 
 #[Object]
 impl AccountQueryRoot {
+    #[graphql(complexity = "limit.saturating_mul(child_complexity)")]
     async fn accounts<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -91,113 +130,92 @@ impl AccountQueryRoot {
         sort_by: Option<AccountSortByInput>,
         #[graphql(default = 100)] limit: usize,
     ) -> Result<Vec<Account>> {
+        let mut page = Page::list(list_limit(ctx, limit)).with_deadline(ctx);
+        accounts_page(db(ctx), query, sort_by, &mut page)?;
+        Ok(page.into_nodes())
+    }
+
+    /// Value best ledger accounts connection, paged by `first` & `after`
+    #[graphql(complexity = "first.saturating_mul(child_complexity)")]
+    async fn accounts_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<AccountQueryInput>,
+        sort_by: Option<AccountSortByInput>,
+        #[graphql(default = 100)] first: usize,
+        after: Option<String>,
+    ) -> Result<SortKeyConnection<Account>> {
         let db = db(ctx);
-        let best_ledger = match db.get_best_ledger() {
-            Ok(Some(ledger)) => ledger,
-            Ok(None) | Err(_) => {
-                return Ok(vec![]);
-            }
+        let total_count = match query {
+            None => db.get_num_accounts()?,
+            Some(_) => None,
         };
+        let mut page = Page::new(list_limit(ctx, first), after)?.with_deadline(ctx);
+        accounts_page(db, query, sort_by, &mut page)?;
+        Ok(page.into_connection(total_count))
+    }
+}
 
-        // public key query handler
-        if let Some(public_key) = query.as_ref().and_then(|q| q.public_key.clone()) {
-            let pk: PublicKey = public_key.into();
-            return Ok(best_ledger
+/// Collect the page of best ledger accounts matching the query
+fn accounts_page(
+    db: &Arc<IndexerStore>,
+    query: Option<AccountQueryInput>,
+    sort_by: Option<AccountSortByInput>,
+    page: &mut Page<Account>,
+) -> Result<()> {
+    let best_ledger = match db.get_best_ledger() {
+        Ok(Some(ledger)) => ledger,
+        Ok(None) | Err(_) => {
+            return Ok(());
+        }
+    };
+
+    // public key query handler
+    if let Some(public_key) = query.as_ref().and_then(|q| q.public_key.clone()) {
+        let pk: PublicKey = public_key.into();
+        return page.extend(
+            best_ledger
                 .accounts
                 .get(&pk)
                 .into_iter()
-                .filter_map(|acct| {
-                    let username = match db.get_username(&pk) {
-                        Ok(None) | Err(_) => None,
-                        Ok(Some(username)) => Some(username.0),
-                    };
-                    if query.as_ref().unwrap().matches(acct, username.as_ref()) {
-                        Some(Account::from((
-                            acct.clone(),
-                            db.get_block_production_pk_epoch_count(&pk, None)
-                                .expect("pk epoch block count"),
-                            db.get_block_production_pk_total_count(&pk)
-                                .expect("pk total block count"),
-                            db.get_snarks_pk_epoch_count(&pk, None)
-                                .expect("pk epoch snark count"),
-                            db.get_snarks_pk_total_count(&pk)
-                                .expect("pk total snark count"),
-                            db.get_user_commands_pk_epoch_count(&pk, None)
-                                .expect("pk epoch user command count"),
-                            db.get_user_commands_pk_total_count(&pk)
-                                .expect("pk total user command count"),
-                            db.get_internal_commands_pk_epoch_count(&pk, None)
-                                .expect("pk epoch internal command count"),
-                            db.get_internal_commands_pk_total_count(&pk)
-                                .expect("pk total internal command count"),
-                            username,
-                        )))
-                    } else {
-                        None
-                    }
-                })
-                .collect());
-        }
+                .filter(|acct| query.as_ref().unwrap().matches(db, acct))
+                .map(|acct| Account::from(acct.clone())),
+        );
+    }
 
-        // default query handler use balance-sorted accounts
-        let mut accounts = Vec::new();
-        let mode = match sort_by {
-            Some(AccountSortByInput::BalanceAsc) => IteratorMode::Start,
-            Some(AccountSortByInput::BalanceDesc) | None => IteratorMode::End,
+    // default query handler use balance-sorted accounts
+    let (mode, direction) = match sort_by {
+        Some(AccountSortByInput::BalanceAsc) => (IteratorMode::Start, Direction::Forward),
+        Some(AccountSortByInput::BalanceDesc) | None => (IteratorMode::End, Direction::Reverse),
+    };
+    let mode = match page.seek(&[]) {
+        [] => mode,
+        after => IteratorMode::From(after, direction),
+    };
+
+    for (key, _) in db.account_balance_iterator(mode).flatten() {
+        page.check_deadline()?;
+        let pk = PublicKey::from_bytes(&key[8..])?;
+        let account = match best_ledger.accounts.get(&pk) {
+            Some(account) => account,
+            None => {
+                warn!("Failed to find public key in best ledger: {pk}");
+                continue;
+            }
         };
 
-        for (key, _) in db.account_balance_iterator(mode).flatten() {
-            let pk = PublicKey::from_bytes(&key[8..])?;
-            let account = match best_ledger.accounts.get(&pk) {
-                Some(account) => account,
-                None => {
-                    warn!("Failed to find public key in best ledger: {pk}");
-                    continue;
-                }
-            };
-
-            let username = match db.get_username(&pk) {
-                Ok(None) | Err(_) => None,
-                Ok(Some(username)) => Some(username.0),
-            };
-            if query
-                .as_ref()
-                .map_or(true, |q| q.matches(account, username.as_ref()))
-            {
-                let account = Account::from((
-                    account.clone(),
-                    db.get_block_production_pk_epoch_count(&pk, None)
-                        .expect("pk epoch block count"),
-                    db.get_block_production_pk_total_count(&pk)
-                        .expect("pk total block count"),
-                    db.get_snarks_pk_epoch_count(&pk, None)
-                        .expect("pk epoch snark count"),
-                    db.get_snarks_pk_total_count(&pk)
-                        .expect("pk total snark count"),
-                    db.get_user_commands_pk_epoch_count(&pk, None)
-                        .expect("pk epoch user command count"),
-                    db.get_user_commands_pk_total_count(&pk)
-                        .expect("pk total user command count"),
-                    db.get_internal_commands_pk_epoch_count(&pk, None)
-                        .expect("pk epoch internal command count"),
-                    db.get_internal_commands_pk_total_count(&pk)
-                        .expect("pk total internal command count"),
-                    username,
-                ));
-
-                accounts.push(account);
-                if accounts.len() == limit {
-                    break;
-                }
+        if query.as_ref().map_or(true, |q| q.matches(db, account)) {
+            let account = Account::from(account.clone());
+            if page.push(&key, account) {
+                break;
             }
         }
-
-        Ok(accounts)
     }
+    Ok(())
 }
 
 impl AccountQueryInput {
-    fn matches(&self, account: &account::Account, username: Option<&String>) -> bool {
+    fn matches(&self, db: &IndexerStore, account: &account::Account) -> bool {
         let AccountQueryInput {
             public_key,
             username: query_username_prefix,
@@ -214,7 +232,8 @@ impl AccountQueryInput {
             }
         }
         if let Some(username_prefix) = query_username_prefix {
-            if username.map_or(true, |u| !u.starts_with(username_prefix)) {
+            let username = db.get_username(&account.public_key).ok().flatten();
+            if username.map_or(true, |u| !u.0.starts_with(username_prefix)) {
                 return false;
             }
         }
@@ -252,51 +271,16 @@ impl AccountQueryInput {
     }
 }
 
-impl
-    From<(
-        account::Account,
-        u32,
-        u32,
-        u32,
-        u32,
-        u32,
-        u32,
-        u32,
-        u32,
-        Option<String>,
-    )> for Account
-{
-    fn from(
-        account: (
-            account::Account,
-            u32,
-            u32,
-            u32,
-            u32,
-            u32,
-            u32,
-            u32,
-            u32,
-            Option<String>,
-        ),
-    ) -> Self {
+impl From<account::Account> for Account {
+    fn from(account: account::Account) -> Self {
         Self {
-            public_key: account.0.public_key.0,
-            delegate: account.0.delegate.0,
-            nonce: account.0.nonce.map_or(0, |n| n.0),
-            balance: account.0.balance.0,
-            time_locked: account.0.timing.is_some(),
-            timing: account.0.timing.map(|t| t.into()),
-            is_genesis_account: account.0.genesis_account,
-            pk_epoch_num_blocks: account.1,
-            pk_total_num_blocks: account.2,
-            pk_epoch_num_snarks: account.3,
-            pk_total_num_snarks: account.4,
-            pk_epoch_num_user_commands: account.5,
-            pk_total_num_user_commands: account.6,
-            pk_epoch_num_internal_commands: account.7,
-            pk_total_num_internal_commands: account.8,
-            username: account.9.or(Some("Unknown".to_string())),
+            public_key: account.public_key.0,
+            delegate: account.delegate.0,
+            nonce: account.nonce.map_or(0, |n| n.0),
+            balance: account.balance.0,
+            time_locked: account.timing.is_some(),
+            timing: account.timing.map(|t| t.into()),
+            is_genesis_account: account.genesis_account,
         }
     }
 }
@@ -1,9 +1,13 @@
 use super::{
     db, gen::BlockProtocolStateConsensusStateQueryInput, get_block_canonicity,
-    millis_to_iso_date_string, transactions::TransactionWithoutBlock, MAINNET_COINBASE_REWARD, PK,
+    millis_to_iso_date_string, pruned_block_error, transactions::TransactionWithoutBlock,
+    MAINNET_COINBASE_REWARD, PK,
 };
 use crate::{
-    block::{is_valid_state_hash, precomputed::PrecomputedBlock, store::BlockStore, BlockHash},
+    block::{
+        is_valid_state_hash, precomputed::PrecomputedBlock, pruned::PrunedBlock, store::BlockStore,
+        BlockHash,
+    },
     command::{
         internal::{store::InternalCommandStore, InternalCommand, InternalCommandWithData},
         signed::SignedCommandWithData,
@@ -20,11 +24,18 @@ use crate::{
         block_state_hash_from_key, block_u32_prefix_from_key, from_be_bytes, pk_key_prefix,
         to_be_bytes, IndexerStore,
     },
-    web::graphql::gen::BlockQueryInput,
+    web::graphql::{
+        filter::{Filter, Filterable},
+        gen::BlockQueryInput,
+        limits::{check_deadline, list_limit},
+        loaders::{block_cache, block_canonicity, loader, BlockLoader, PrecomputedBlockCache},
+        pagination::{Page, SortKeyConnection},
+    },
 };
-use anyhow::Context;
-use async_graphql::{self, Enum, Object, Result, SimpleObject};
+use async_graphql::{self, ComplexObject, Enum, Object, Result, SimpleObject};
 use log::error;
+use serde::Serialize;
+use serde_json::Value;
 use speedb::{Direction, IteratorMode};
 use std::{collections::HashSet, sync::Arc};
 
@@ -90,87 +101,39 @@ impl BlocksQueryRoot {
                 return Ok(None);
             }
 
-            let pcb = match db.get_block(&state_hash.clone().into())? {
-                Some((pcb, _)) => pcb,
+            let filter = Filter::new(&query);
+            let pcb = match find_block(db, block_cache(ctx), &state_hash.into(), &filter)? {
+                Some(pcb) => pcb,
                 None => return Ok(None),
             };
-            let canonical = get_block_canonicity(db, &state_hash);
-            let block_num_snarks = db
-                .get_block_snarks_count(&pcb.state_hash())
-                .expect("snark counts")
-                .unwrap_or_default();
-            let block_num_user_commands = db
-                .get_block_user_commands_count(&pcb.state_hash())
-                .expect("user command counts")
-                .unwrap_or_default();
-            let block_num_internal_commands = db
-                .get_block_internal_commands_count(&pcb.state_hash())
-                .expect("internal command counts")
-                .unwrap_or_default();
-            let block = Block {
-                canonical,
-                epoch_num_blocks,
-                total_num_blocks,
-                block_num_snarks,
-                block_num_user_commands,
-                block_num_internal_commands,
-                block: BlockWithoutCanonicity::new(
-                    &pcb,
-                    canonical,
-                    epoch_num_user_commands,
-                    total_num_user_commands,
-                ),
-                num_unique_block_producers_last_n_blocks: None,
-            };
-            if query.unwrap().matches(&block) {
+            let block = Block::from_stored(db, &pcb, block_counts(db)?);
+            if filter.matches(&block)? {
                 return Ok(Some(block));
             }
             return Ok(None);
         }
 
         // else iterate over height-sorted blocks
+        let cache = block_cache(ctx);
+        let counts = block_counts(db)?;
+        let filter = Filter::new(&query);
         for (key, _) in db
             .blocks_height_iterator(speedb::IteratorMode::End)
             .flatten()
         {
+            check_deadline(ctx)?;
             let state_hash = block_state_hash_from_key(&key)?;
-            let pcb = get_block(db, &state_hash);
-            let canonical = get_block_canonicity(db, &state_hash.0);
-            let block_num_snarks = db
-                .get_block_snarks_count(&pcb.state_hash())
-                .expect("snark counts")
-                .unwrap_or_default();
-            let block_num_user_commands = db
-                .get_block_user_commands_count(&pcb.state_hash())
-                .expect("user command counts")
-                .unwrap_or_default();
-            let block_num_internal_commands = db
-                .get_block_internal_commands_count(&pcb.state_hash())
-                .expect("internal command counts")
-                .unwrap_or_default();
-            let block = Block {
-                canonical,
-                epoch_num_blocks,
-                total_num_blocks,
-                block_num_snarks,
-                block_num_user_commands,
-                block_num_internal_commands,
-                block: BlockWithoutCanonicity::new(
-                    &pcb,
-                    canonical,
-                    epoch_num_user_commands,
-                    total_num_user_commands,
-                ),
-                num_unique_block_producers_last_n_blocks: None,
-            };
+            let pcb = get_block(db, cache, &state_hash, &filter)?;
+            let block = Block::from_stored(db, &pcb, counts);
 
-            if query.as_ref().map_or(true, |q| q.matches(&block)) {
+            if filter.matches(&block)? {
                 return Ok(Some(block));
             }
         }
         Ok(None)
     }
 
+    #[graphql(complexity = "limit.saturating_mul(child_complexity)")]
     async fn blocks<'ctx>(
         &self,
         ctx: &async_graphql::Context<'ctx>,
@@ -178,359 +141,351 @@ impl BlocksQueryRoot {
         #[graphql(default = 100)] limit: usize,
         sort_by: Option<BlockSortByInput>,
     ) -> Result<Vec<Block>> {
-        use speedb::{Direction::*, IteratorMode::*};
-        use BlockSortByInput::*;
+        let mut page = Page::list(list_limit(ctx, limit)).with_deadline(ctx);
+        blocks_page(db(ctx), block_cache(ctx), query, sort_by, &mut page)?;
+        Ok(page.into_nodes())
+    }
+
+    /// Value blocks connection, paged by `first` & `after`
+    #[graphql(complexity = "first.saturating_mul(child_complexity)")]
+    async fn blocks_connection<'ctx>(
+        &self,
+        ctx: &async_graphql::Context<'ctx>,
+        query: Option<BlockQueryInput>,
+        sort_by: Option<BlockSortByInput>,
+        #[graphql(default = 100)] first: usize,
+        after: Option<String>,
+    ) -> Result<SortKeyConnection<Block>> {
         let db = db(ctx);
+        let total_count = match query {
+            None => Some(db.get_block_production_total_count()?),
+            Some(_) => None,
+        };
+        let mut page = Page::new(list_limit(ctx, first), after)?.with_deadline(ctx);
+        blocks_page(db, block_cache(ctx), query, sort_by, &mut page)?;
+        Ok(page.into_connection(total_count))
+    }
+}
 
-        // unique block producer query
-        if let Some(mut num_blocks) = query
-            .as_ref()
-            .and_then(|q| q.unique_block_producers_last_n_blocks)
-        {
-            const MAX_NUM_BLOCKS: u32 = 1000;
-            num_blocks = num_blocks.min(MAX_NUM_BLOCKS);
-
-            if let Some(best_height) = db.get_best_block_height()? {
-                let start_height = 1.max(best_height.saturating_sub(num_blocks));
-                let mut producers = HashSet::new();
-                for (key, _) in db
-                    .blocks_height_iterator(IteratorMode::From(
-                        &to_be_bytes(best_height + 1),
-                        Direction::Reverse,
-                    ))
-                    .flatten()
-                {
-                    let height = from_be_bytes(key[..8].to_vec());
-                    if height <= start_height {
-                        break;
-                    }
+/// Collect the page of blocks matching the query
+fn blocks_page(
+    db: &Arc<IndexerStore>,
+    cache: &PrecomputedBlockCache,
+    query: Option<BlockQueryInput>,
+    sort_by: Option<BlockSortByInput>,
+    page: &mut Page<Block>,
+) -> Result<()> {
+    use speedb::{Direction::*, IteratorMode::*};
+    use BlockSortByInput::*;
 
-                    let state_hash = block_state_hash_from_key(&key)?;
-                    if let Some(creator) = db.get_block_creator(&state_hash)? {
-                        producers.insert(creator);
-                        continue;
-                    }
-                    error!("Block creator index missing (length {height}) {state_hash}")
+    const UNIQUE_BLOCK_PRODUCERS: &str = "unique_block_producers_last_n_blocks";
+    let filter = Filter::new(&query);
+
+    // unique block producer query
+    if let Some(mut num_blocks) = query
+        .as_ref()
+        .and_then(|q| q.unique_block_producers_last_n_blocks)
+    {
+        if filter.fields().any(|field| field != UNIQUE_BLOCK_PRODUCERS) {
+            return Err(async_graphql::Error::new(format!(
+                "Filter {UNIQUE_BLOCK_PRODUCERS} cannot be combined with other filters"
+            )));
+        }
+
+        const MAX_NUM_BLOCKS: u32 = 1000;
+        num_blocks = num_blocks.min(MAX_NUM_BLOCKS);
+
+        if let Some(best_height) = db.get_best_block_height()? {
+            let start_height = 1.max(best_height.saturating_sub(num_blocks));
+            let mut producers = HashSet::new();
+            for (key, _) in db
+                .blocks_height_iterator(IteratorMode::From(
+                    &to_be_bytes(best_height + 1),
+                    Direction::Reverse,
+                ))
+                .flatten()
+            {
+                page.check_deadline()?;
+                let height = from_be_bytes(key[..8].to_vec());
+                if height <= start_height {
+                    break;
                 }
-                return Ok(vec![Block {
-                    num_unique_block_producers_last_n_blocks: Some(producers.len() as u32),
-                    ..Default::default()
-                }]);
+
+                let state_hash = block_state_hash_from_key(&key)?;
+                if let Some(creator) = db.get_block_creator(&state_hash)? {
+                    producers.insert(creator);
+                    continue;
+                }
+                error!("Block creator index missing (length {height}) {state_hash}")
             }
+            return page.extend([Block {
+                num_unique_block_producers_last_n_blocks: Some(producers.len() as u32),
+                ..Default::default()
+            }]);
         }
+    }
 
-        let epoch_num_blocks = db.get_block_production_epoch_count(None)?;
-        let total_num_blocks = db.get_block_production_total_count()?;
-        let epoch_num_snarks = db.get_snarks_epoch_count(None).expect("epoch SNARK count");
-        let total_num_snarks = db.get_snarks_total_count().expect("total SNARK count");
-        let epoch_num_user_commands = db
-            .get_user_commands_epoch_count(None)
-            .expect("epoch user command count");
-        let total_num_user_commands = db
-            .get_user_commands_total_count()
-            .expect("total user command count");
-        let epoch_num_internal_commands = db
-            .get_internal_commands_epoch_count(None)
-            .expect("epoch internal command count");
-        let total_num_internal_commands = db
-            .get_internal_commands_total_count()
-            .expect("total internal command count");
-        let counts = [
-            epoch_num_blocks,
-            total_num_blocks,
-            epoch_num_snarks,
-            total_num_snarks,
-            epoch_num_user_commands,
-            total_num_user_commands,
-            epoch_num_internal_commands,
-            total_num_internal_commands,
-        ];
-        let mut blocks = Vec::new();
-        let sort_by = sort_by.unwrap_or(BlockHeightDesc);
-
-        // state hash query
-        if let Some(state_hash) = query.as_ref().and_then(|q| q.state_hash.clone()) {
-            let block = db.get_block(&state_hash.clone().into())?;
-            return Ok(block
-                .iter()
-                .filter_map(|(b, _)| precomputed_matches_query(db, &query, b, counts))
-                .collect());
+    let filter = filter.without(UNIQUE_BLOCK_PRODUCERS);
+    let counts = block_counts(db)?;
+    let sort_by = sort_by.unwrap_or(BlockHeightDesc);
+
+    // state hash query
+    if let Some(state_hash) = query.as_ref().and_then(|q| q.state_hash.clone()) {
+        let mut blocks = vec![];
+        if let Some(pcb) = find_block(db, cache, &state_hash.into(), &filter)? {
+            blocks.extend(precomputed_matches_query(db, &filter, &pcb, counts)?);
         }
+        return page.extend(blocks);
+    }
 
-        // block height query
-        if let Some(block_height) = query.as_ref().and_then(|q| q.block_height) {
-            for state_hash in db.get_blocks_at_height(block_height)?.iter() {
-                let pcb = get_block(db, state_hash);
-                if let Some(block) = precomputed_matches_query(db, &query, &pcb, counts) {
-                    blocks.push(block);
-                    if blocks.len() == limit {
-                        break;
-                    }
-                }
-            }
-            return Ok(blocks);
+    // block height query
+    if let Some(block_height) = query.as_ref().and_then(|q| q.block_height) {
+        let mut blocks = vec![];
+        for state_hash in db.get_blocks_at_height(block_height)?.iter() {
+            let pcb = get_block(db, cache, state_hash, &filter)?;
+            blocks.extend(precomputed_matches_query(db, &filter, &pcb, counts)?);
         }
+        return page.extend(blocks);
+    }
 
-        // global slot query
-        if let Some(global_slot) = query
-            .as_ref()
-            .and_then(|q| q.protocol_state.as_ref())
-            .and_then(|protocol_state| protocol_state.consensus_state.as_ref())
-            .and_then(|consensus_state| consensus_state.slot_since_genesis)
-            .or(query.as_ref().and_then(|q| q.global_slot_since_genesis))
-        {
-            for state_hash in db.get_blocks_at_slot(global_slot)?.iter() {
-                let pcb = get_block(db, state_hash);
-                if let Some(block) = precomputed_matches_query(db, &query, &pcb, counts) {
-                    blocks.push(block);
-                    if blocks.len() == limit {
-                        break;
-                    }
-                }
-            }
-            return Ok(blocks);
+    // global slot query
+    if let Some(global_slot) = query
+        .as_ref()
+        .and_then(|q| q.protocol_state.as_ref())
+        .and_then(|protocol_state| protocol_state.consensus_state.as_ref())
+        .and_then(|consensus_state| consensus_state.slot_since_genesis)
+        .or(query.as_ref().and_then(|q| q.global_slot_since_genesis))
+    {
+        let mut blocks = vec![];
+        for state_hash in db.get_blocks_at_slot(global_slot)?.iter() {
+            let pcb = get_block(db, cache, state_hash, &filter)?;
+            blocks.extend(precomputed_matches_query(db, &filter, &pcb, counts)?);
         }
+        return page.extend(blocks);
+    }
 
-        // coinbase receiver query
-        if let Some(coinbase_receiver) = query.as_ref().and_then(|q| {
-            q.coinbase_receiver
-                .as_ref()
-                .and_then(|cb| cb.public_key.clone())
-        }) {
-            let start = coinbase_receiver.as_bytes().to_vec();
-            let mut end = start.clone();
-            end.append(&mut to_be_bytes(u32::MAX));
-
-            let iter = match sort_by {
-                BlockHeightAsc => db.coinbase_receiver_block_height_iterator(From(&start, Forward)),
-                BlockHeightDesc => db.coinbase_receiver_block_height_iterator(From(&end, Reverse)),
-                GlobalSlotAsc => db.coinbase_receiver_global_slot_iterator(From(&start, Forward)),
-                GlobalSlotDesc => db.coinbase_receiver_global_slot_iterator(From(&end, Reverse)),
-            };
-            for (key, _) in iter.flatten() {
-                if pk_key_prefix(&key).0 != coinbase_receiver {
+    // coinbase receiver query
+    if let Some(coinbase_receiver) = query.as_ref().and_then(|q| {
+        q.coinbase_receiver
+            .as_ref()
+            .and_then(|cb| cb.public_key.clone())
+    }) {
+        let start = coinbase_receiver.as_bytes().to_vec();
+        let mut end = start.clone();
+        end.append(&mut to_be_bytes(u32::MAX));
+
+        let iter = match sort_by {
+            BlockHeightAsc => {
+                db.coinbase_receiver_block_height_iterator(From(page.seek(&start), Forward))
+            }
+            BlockHeightDesc => {
+                db.coinbase_receiver_block_height_iterator(From(page.seek(&end), Reverse))
+            }
+            GlobalSlotAsc => {
+                db.coinbase_receiver_global_slot_iterator(From(page.seek(&start), Forward))
+            }
+            GlobalSlotDesc => {
+                db.coinbase_receiver_global_slot_iterator(From(page.seek(&end), Reverse))
+            }
+        };
+        for (key, _) in iter.flatten() {
+            page.check_deadline()?;
+            if pk_key_prefix(&key).0 != coinbase_receiver {
+                break;
+            }
+            let state_hash = block_state_hash_from_key(&key)?;
+            let pcb = get_block(db, cache, &state_hash, &filter)?;
+            if let Some(block) = precomputed_matches_query(db, &filter, &pcb, counts)? {
+                if page.push(&key, block) {
                     break;
                 }
-                let state_hash = block_state_hash_from_key(&key)?;
-                let pcb = get_block(db, &state_hash);
-                if let Some(block) = precomputed_matches_query(db, &query, &pcb, counts) {
-                    blocks.push(block);
-                    if blocks.len() == limit {
-                        break;
-                    }
-                }
             }
-            return Ok(blocks);
         }
+        return Ok(());
+    }
 
-        // creator account query
-        if let Some(creator_account) = query.as_ref().and_then(|q| {
-            q.creator_account
-                .as_ref()
-                .and_then(|cb| cb.public_key.clone())
-        }) {
-            // properly set the upper bound for block height
-            let upper_bound = match (
-                query.as_ref().and_then(|q| q.block_height_lt),
-                query.as_ref().and_then(|q| q.block_height_lte),
-            ) {
-                (Some(lt), Some(lte)) => std::cmp::min(lte, lt - 1),
-                (Some(lt), None) => lt - 1,
-                (None, Some(lte)) => lte,
-                (None, None) => u32::MAX,
-            };
-            let start = creator_account.as_bytes().to_vec();
-            let mut end = start.clone();
-            end.append(&mut to_be_bytes(upper_bound));
-
-            let iter = match sort_by {
-                BlockHeightAsc => db.block_creator_block_height_iterator(From(&start, Forward)),
-                BlockHeightDesc => db.block_creator_block_height_iterator(From(&end, Reverse)),
-                GlobalSlotAsc => db.block_creator_global_slot_iterator(From(&start, Forward)),
-                GlobalSlotDesc => db.block_creator_global_slot_iterator(From(&end, Reverse)),
-            };
-            for (key, _) in iter.flatten() {
-                if pk_key_prefix(&key).0 != creator_account {
+    // creator account query
+    if let Some(creator_account) = query.as_ref().and_then(|q| {
+        q.creator_account
+            .as_ref()
+            .and_then(|cb| cb.public_key.clone())
+    }) {
+        // properly set the upper bound for block height
+        let upper_bound = match (
+            query.as_ref().and_then(|q| q.block_height_lt),
+            query.as_ref().and_then(|q| q.block_height_lte),
+        ) {
+            (Some(lt), Some(lte)) => std::cmp::min(lte, lt - 1),
+            (Some(lt), None) => lt - 1,
+            (None, Some(lte)) => lte,
+            (None, None) => u32::MAX,
+        };
+        let start = creator_account.as_bytes().to_vec();
+        let mut end = start.clone();
+        end.append(&mut to_be_bytes(upper_bound));
+
+        let iter = match sort_by {
+            BlockHeightAsc => {
+                db.block_creator_block_height_iterator(From(page.seek(&start), Forward))
+            }
+            BlockHeightDesc => {
+                db.block_creator_block_height_iterator(From(page.seek(&end), Reverse))
+            }
+            GlobalSlotAsc => {
+                db.block_creator_global_slot_iterator(From(page.seek(&start), Forward))
+            }
+            GlobalSlotDesc => db.block_creator_global_slot_iterator(From(page.seek(&end), Reverse)),
+        };
+        for (key, _) in iter.flatten() {
+            page.check_deadline()?;
+            if pk_key_prefix(&key).0 != creator_account {
+                break;
+            }
+            let state_hash = block_state_hash_from_key(&key)?;
+            let pcb = get_block(db, cache, &state_hash, &filter)?;
+            if let Some(block) = precomputed_matches_query(db, &filter, &pcb, counts)? {
+                if page.push(&key, block) {
                     break;
                 }
-                let state_hash = block_state_hash_from_key(&key)?;
-                let pcb = get_block(db, &state_hash);
-                if let Some(block) = precomputed_matches_query(db, &query, &pcb, counts) {
-                    blocks.push(block);
-                    if blocks.len() == limit {
-                        break;
-                    }
-                }
             }
-            return Ok(blocks);
         }
+        return Ok(());
+    }
 
-        // block height bounded query
-        if query.as_ref().map_or(false, |q| {
-            q.block_height_gt.is_some()
-                || q.block_height_gte.is_some()
-                || q.block_height_lt.is_some()
-                || q.block_height_lte.is_some()
-        }) {
-            let (min, max) = {
-                let BlockQueryInput {
-                    block_height_gt,
-                    block_height_gte,
-                    block_height_lt,
-                    block_height_lte,
-                    ..
-                } = query.as_ref().expect("query will contain a value");
-                let min_bound = match (*block_height_gte, *block_height_gt) {
-                    (Some(gte), Some(gt)) => std::cmp::max(gte, gt + 1),
-                    (Some(gte), None) => gte,
-                    (None, Some(gt)) => gt + 1,
-                    (None, None) => 1,
-                };
-
-                let max_bound = match (*block_height_lte, *block_height_lt) {
-                    (Some(lte), Some(lt)) => std::cmp::min(lte, lt - 1),
-                    (Some(lte), None) => lte,
-                    (None, Some(lt)) => lt - 1,
-                    (None, None) => db.get_best_block_height()?.unwrap(),
-                };
-                (min_bound, max_bound)
+    // block height bounded query
+    if query.as_ref().map_or(false, |q| {
+        q.block_height_gt.is_some()
+            || q.block_height_gte.is_some()
+            || q.block_height_lt.is_some()
+            || q.block_height_lte.is_some()
+    }) {
+        let (min, max) = {
+            let BlockQueryInput {
+                block_height_gt,
+                block_height_gte,
+                block_height_lt,
+                block_height_lte,
+                ..
+            } = query.as_ref().expect("query will contain a value");
+            let min_bound = match (*block_height_gte, *block_height_gt) {
+                (Some(gte), Some(gt)) => std::cmp::max(gte, gt + 1),
+                (Some(gte), None) => gte,
+                (None, Some(gt)) => gt + 1,
+                (None, None) => 1,
             };
 
-            let start = to_be_bytes(min);
-            let end = to_be_bytes(max + 1);
-            let mode = match sort_by {
-                BlockHeightAsc => From(&start, Forward),
-                _ => From(&end, Reverse),
+            let max_bound = match (*block_height_lte, *block_height_lt) {
+                (Some(lte), Some(lt)) => std::cmp::min(lte, lt - 1),
+                (Some(lte), None) => lte,
+                (None, Some(lt)) => lt - 1,
+                (None, None) => db.get_best_block_height()?.unwrap(),
             };
-            for (key, _) in db.blocks_height_iterator(mode).flatten() {
-                let height = block_u32_prefix_from_key(&key)?;
-                if height < min || height > max {
-                    break;
-                }
+            (min_bound, max_bound)
+        };
 
-                let state_hash = block_state_hash_from_key(&key)?;
-                let pcb = get_block(db, &state_hash);
-                if let Some(block_with_canonicity) =
-                    precomputed_matches_query(db, &query, &pcb, counts)
-                {
-                    blocks.push(block_with_canonicity);
-                    if blocks.len() == limit {
-                        break;
-                    }
-                }
-            }
-            reorder(db, &mut blocks, sort_by);
-            return Ok(blocks);
+        // iterate the index of the sort order, bounded by the slots of the
+        // blocks in the height range if it isn't the height index
+        let bounds = match sort_by {
+            BlockHeightAsc | BlockHeightDesc => Some((min, max)),
+            GlobalSlotAsc | GlobalSlotDesc => blocks_range(
+                page,
+                db.blocks_height_iterator(From(&to_be_bytes(min), Forward)),
+                (min, max),
+                |state_hash| db.get_block_global_slot(state_hash),
+            )?,
+        };
+        if let Some(bounds) = bounds {
+            push_blocks_in_range(db, cache, &filter, counts, sort_by, bounds, page)?;
         }
+        return Ok(());
+    }
 
-        // global slot bounded query
-        let consensus_state = query
-            .as_ref()
-            .and_then(|f| f.protocol_state.as_ref())
-            .and_then(|f| f.consensus_state.as_ref());
-        if consensus_state.map_or(false, |q| {
-            q.slot_since_genesis_gt.is_some()
-                || q.slot_since_genesis_gte.is_some()
-                || q.slot_since_genesis_lt.is_some()
-                || q.slot_since_genesis_lte.is_some()
-        }) {
-            let (min, max) = {
-                let BlockProtocolStateConsensusStateQueryInput {
-                    slot_since_genesis_lte,
-                    slot_since_genesis_lt,
-                    slot_since_genesis_gte,
-                    slot_since_genesis_gt,
-                    ..
-                } = consensus_state
-                    .as_ref()
-                    .expect("consensus will have a value");
-                let min_bound = match (*slot_since_genesis_gte, *slot_since_genesis_gt) {
-                    (Some(gte), Some(gt)) => std::cmp::max(gte, gt + 1),
-                    (Some(gte), None) => gte,
-                    (None, Some(gt)) => gt + 1,
-                    (None, None) => 0,
-                };
-
-                let max_bound = match (*slot_since_genesis_lte, *slot_since_genesis_lt) {
-                    (Some(lte), Some(lt)) => std::cmp::min(lte, lt - 1),
-                    (Some(lte), None) => lte,
-                    (None, Some(lt)) => lt - 1,
-                    (None, None) => db.get_best_block_global_slot()?.unwrap(),
-                };
-                (min_bound, max_bound)
+    // global slot bounded query
+    let consensus_state = query
+        .as_ref()
+        .and_then(|f| f.protocol_state.as_ref())
+        .and_then(|f| f.consensus_state.as_ref());
+    if consensus_state.map_or(false, |q| {
+        q.slot_since_genesis_gt.is_some()
+            || q.slot_since_genesis_gte.is_some()
+            || q.slot_since_genesis_lt.is_some()
+            || q.slot_since_genesis_lte.is_some()
+    }) {
+        let (min, max) = {
+            let BlockProtocolStateConsensusStateQueryInput {
+                slot_since_genesis_lte,
+                slot_since_genesis_lt,
+                slot_since_genesis_gte,
+                slot_since_genesis_gt,
+                ..
+            } = consensus_state
+                .as_ref()
+                .expect("consensus will have a value");
+            let min_bound = match (*slot_since_genesis_gte, *slot_since_genesis_gt) {
+                (Some(gte), Some(gt)) => std::cmp::max(gte, gt + 1),
+                (Some(gte), None) => gte,
+                (None, Some(gt)) => gt + 1,
+                (None, None) => 0,
             };
 
-            let start = to_be_bytes(min);
-            let end = to_be_bytes(max + 1);
-            let mode = match sort_by {
-                GlobalSlotAsc => From(&start, Forward),
-                _ => From(&end, Reverse),
+            let max_bound = match (*slot_since_genesis_lte, *slot_since_genesis_lt) {
+                (Some(lte), Some(lt)) => std::cmp::min(lte, lt - 1),
+                (Some(lte), None) => lte,
+                (None, Some(lt)) => lt - 1,
+                (None, None) => db.get_best_block_global_slot()?.unwrap(),
             };
-            for (key, _) in db.blocks_global_slot_iterator(mode).flatten() {
-                let slot = block_u32_prefix_from_key(&key)?;
-                if slot < min || slot > max {
-                    break;
-                }
+            (min_bound, max_bound)
+        };
 
-                let state_hash = block_state_hash_from_key(&key)?;
-                let pcb = get_block(db, &state_hash);
-                if let Some(block_with_canonicity) =
-                    precomputed_matches_query(db, &query, &pcb, counts)
-                {
-                    blocks.push(block_with_canonicity);
-                    if blocks.len() == limit {
-                        break;
-                    }
-                }
-            }
-            reorder(db, &mut blocks, sort_by);
-            return Ok(blocks);
+        // iterate the index of the sort order, bounded by the heights of the
+        // blocks in the slot range if it isn't the slot index
+        let bounds = match sort_by {
+            GlobalSlotAsc | GlobalSlotDesc => Some((min, max)),
+            BlockHeightAsc | BlockHeightDesc => blocks_range(
+                page,
+                db.blocks_global_slot_iterator(From(&to_be_bytes(min), Forward)),
+                (min, max),
+                |state_hash| db.get_block_height(state_hash),
+            )?,
+        };
+        if let Some(bounds) = bounds {
+            push_blocks_in_range(db, cache, &filter, counts, sort_by, bounds, page)?;
         }
+        return Ok(());
+    }
 
-        // default query handler
-        let start = to_be_bytes(0);
-        let end = to_be_bytes(u32::MAX);
-        let iter = match sort_by {
-            BlockHeightAsc => db.blocks_height_iterator(From(&start, Forward)),
-            BlockHeightDesc => db.blocks_height_iterator(From(&end, Reverse)),
-            GlobalSlotAsc => db.blocks_global_slot_iterator(From(&start, Forward)),
-            GlobalSlotDesc => db.blocks_global_slot_iterator(From(&end, Reverse)),
-        };
-        for (key, _) in iter.flatten() {
-            let state_hash = block_state_hash_from_key(&key)?;
-            let pcb = db
-                .get_block(&state_hash)?
-                .with_context(|| format!("block missing from store hash {state_hash}"))
-                .expect("block")
-                .0;
-            let block = Block::from_precomputed(db, &pcb, counts);
-
-            if query.as_ref().map_or(true, |q| q.matches(&block)) {
-                blocks.push(block);
-                if blocks.len() == limit {
-                    break;
-                }
-            }
+    // default query handler
+    let start = to_be_bytes(0);
+    let end = to_be_bytes(u32::MAX);
+    let iter = match sort_by {
+        BlockHeightAsc => db.blocks_height_iterator(From(page.seek(&start), Forward)),
+        BlockHeightDesc => db.blocks_height_iterator(From(page.seek(&end), Reverse)),
+        GlobalSlotAsc => db.blocks_global_slot_iterator(From(page.seek(&start), Forward)),
+        GlobalSlotDesc => db.blocks_global_slot_iterator(From(page.seek(&end), Reverse)),
+    };
+    for (key, _) in iter.flatten() {
+        page.check_deadline()?;
+        let state_hash = block_state_hash_from_key(&key)?;
+        let pcb = get_block(db, cache, &state_hash, &filter)?;
+        let block = Block::from_stored(db, &pcb, counts);
+
+        if filter.matches(&block)? && page.push(&key, block) {
+            break;
         }
-        Ok(blocks)
     }
+    Ok(())
 }
 
 fn precomputed_matches_query(
     db: &Arc<IndexerStore>,
-    query: &Option<BlockQueryInput>,
-    block: &PrecomputedBlock,
+    filter: &Filter,
+    block: &StoredBlock,
     counts: [u32; 8],
-) -> Option<Block> {
-    let block_with_canonicity = Block::from_precomputed(db, block, counts);
-    if query
-        .as_ref()
-        .map_or(true, |q| q.matches(&block_with_canonicity))
-    {
-        Some(block_with_canonicity)
-    } else {
-        None
-    }
+) -> Result<Option<Block>> {
+    let block_with_canonicity = Block::from_stored(db, block, counts);
+    Ok(filter
+        .matches(&block_with_canonicity)?
+        .then_some(block_with_canonicity))
 }
 
 #[derive(Enum, Copy, Clone, Eq, PartialEq)]
@@ -548,7 +503,8 @@ pub enum BlockSortByInput {
     GlobalSlotDesc,
 }
 
-#[derive(Default, SimpleObject)]
+#[derive(Default, SimpleObject, Serialize)]
+#[graphql(complex)]
 pub struct Block {
     /// Value canonical
     pub canonical: bool,
@@ -579,10 +535,44 @@ pub struct Block {
 
     /// Value block
     #[graphql(flatten)]
+    #[serde(flatten)]
     pub block: BlockWithoutCanonicity,
 }
 
-#[derive(Default, SimpleObject)]
+impl Filterable for Block {
+    const NAME: &'static str = "Block";
+
+    fn filter_fields(&self) -> Value {
+        let mut fields = serde_json::to_value(self).unwrap_or_default();
+        fields["transactions"]["user_commands"] = self
+            .block
+            .transactions
+            .user_commands
+            .iter()
+            .map(|cmd| cmd.filter_fields(&self.block.state_hash, &self.block.date_time))
+            .collect();
+        fields
+    }
+}
+
+#[ComplexObject]
+impl Block {
+    /// Value number of blocks on top of the block in the best chain
+    async fn confirmations(&self, ctx: &async_graphql::Context<'_>) -> Result<Option<u32>> {
+        Ok(block_canonicity(ctx, &self.block.state_hash)
+            .await?
+            .confirmations)
+    }
+
+    /// Value is final
+    async fn is_final(&self, ctx: &async_graphql::Context<'_>) -> Result<bool> {
+        Ok(block_canonicity(ctx, &self.block.state_hash)
+            .await?
+            .is_final)
+    }
+}
+
+#[derive(Default, SimpleObject, Serialize)]
 pub struct BlockWithoutCanonicity {
     /// Value state_hash
     state_hash: String,
@@ -627,8 +617,7 @@ pub struct BlockWithoutCanonicity {
     snark_jobs: Vec<SnarkJob>,
 }
 
-#[derive(SimpleObject)]
-
+#[derive(SimpleObject, Serialize)]
 struct SnarkJob {
     /// Value block state hash
     block_state_hash: String,
@@ -646,7 +635,7 @@ struct SnarkJob {
     prover: String,
 }
 
-#[derive(Default, SimpleObject)]
+#[derive(Default, SimpleObject, Serialize)]
 struct Transactions {
     /// Value coinbase
     coinbase: String,
@@ -661,19 +650,22 @@ struct Transactions {
     user_commands: Vec<TransactionWithoutBlock>,
 }
 
-#[derive(Default, SimpleObject)]
+#[derive(Default, SimpleObject, Serialize)]
 struct BlockFeetransfer {
     pub fee: String,
     pub recipient: String,
 
     #[graphql(name = "type")]
+    #[serde(rename = "type")]
     pub feetransfer_kind: String,
 }
 
-#[derive(Default, SimpleObject)]
+#[derive(Default, SimpleObject, Serialize)]
+#[graphql(complex)]
 struct ConsensusState {
     /// Value total currency
-    total_currency: u64,
+    #[graphql(skip)]
+    total_currency: Option<u64>,
 
     /// Value block height
     blockchain_length: u32,
@@ -688,13 +680,15 @@ struct ConsensusState {
     epoch: u32,
 
     /// Value has ancestors the same checkpoint window
-    has_ancestor_in_same_checkpoint_window: bool,
+    #[graphql(skip)]
+    has_ancestor_in_same_checkpoint_window: Option<bool>,
 
     /// Value last VRF output
     last_vrf_output: String,
 
     /// Value minimum window density
-    min_window_density: u32,
+    #[graphql(skip)]
+    min_window_density: Option<u32>,
 
     /// Value current slot
     slot: u32,
@@ -703,13 +697,100 @@ struct ConsensusState {
     slot_since_genesis: u32,
 
     /// Value next epoch data
-    next_epoch_data: NextEpochData,
+    #[graphql(skip)]
+    next_epoch_data: Option<NextEpochData>,
+
+    /// Value next epoch data
+    #[graphql(skip)]
+    staking_epoch_data: Option<StakingEpochData>,
+
+    #[graphql(skip)]
+    #[serde(skip)]
+    state_hash: String,
+}
+
+/// Fields which are only kept in the full precomputed block, pruned blocks are
+/// re-hydrated to resolve them
+#[ComplexObject]
+impl ConsensusState {
+    /// Value total currency
+    async fn total_currency(&self, ctx: &async_graphql::Context<'_>) -> Result<Option<u64>> {
+        full_block_field(ctx, self.total_currency, &self.state_hash, |state| {
+            state.consensus_state.total_currency
+        })
+        .await
+    }
+
+    /// Value has ancestors the same checkpoint window
+    async fn has_ancestor_in_same_checkpoint_window(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> Result<Option<bool>> {
+        full_block_field(
+            ctx,
+            self.has_ancestor_in_same_checkpoint_window,
+            &self.state_hash,
+            |state| state.consensus_state.has_ancestor_in_same_checkpoint_window,
+        )
+        .await
+    }
+
+    /// Value minimum window density
+    async fn min_window_density(&self, ctx: &async_graphql::Context<'_>) -> Result<Option<u32>> {
+        full_block_field(ctx, self.min_window_density, &self.state_hash, |state| {
+            state.consensus_state.min_window_density
+        })
+        .await
+    }
+
+    /// Value next epoch data
+    async fn next_epoch_data(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> Result<Option<NextEpochData>> {
+        full_block_field(
+            ctx,
+            self.next_epoch_data.clone(),
+            &self.state_hash,
+            |state| state.consensus_state.next_epoch_data,
+        )
+        .await
+    }
 
     /// Value next epoch data
-    staking_epoch_data: StakingEpochData,
+    async fn staking_epoch_data(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> Result<Option<StakingEpochData>> {
+        full_block_field(
+            ctx,
+            self.staking_epoch_data.clone(),
+            &self.state_hash,
+            |state| state.consensus_state.staking_epoch_data,
+        )
+        .await
+    }
+}
+
+/// The field, taken from the re-hydrated full block if the block has been
+/// pruned, an error if its precomputed block file is unavailable
+async fn full_block_field<T>(
+    ctx: &async_graphql::Context<'_>,
+    field: Option<T>,
+    state_hash: &str,
+    of: impl FnOnce(ProtocolState) -> Option<T>,
+) -> Result<Option<T>> {
+    if field.is_some() {
+        return Ok(field);
+    }
+    let block = loader::<BlockLoader>(ctx)
+        .load_one(BlockHash::from(state_hash.to_owned()))
+        .await?
+        .ok_or_else(|| pruned_block_error(state_hash))?;
+    Ok(of(ProtocolState::new(&block)))
 }
 
-#[derive(Default, SimpleObject)]
+#[derive(Default, Clone, SimpleObject, Serialize)]
 struct StakingEpochData {
     /// Value seed
     seed: String,
@@ -727,7 +808,7 @@ struct StakingEpochData {
     ledger: StakingEpochDataLedger,
 }
 
-#[derive(Default, SimpleObject)]
+#[derive(Default, Clone, SimpleObject, Serialize)]
 struct NextEpochData {
     /// Value seed
     seed: String,
@@ -745,7 +826,7 @@ struct NextEpochData {
     ledger: NextEpochDataLedger,
 }
 
-#[derive(Default, SimpleObject)]
+#[derive(Default, Clone, SimpleObject, Serialize)]
 struct NextEpochDataLedger {
     /// Value hash
     hash: String,
@@ -754,7 +835,7 @@ struct NextEpochDataLedger {
     total_currency: u64,
 }
 
-#[derive(Default, SimpleObject)]
+#[derive(Default, Clone, SimpleObject, Serialize)]
 struct StakingEpochDataLedger {
     /// Value hash
     hash: String,
@@ -763,7 +844,8 @@ struct StakingEpochDataLedger {
     total_currency: u64,
 }
 
-#[derive(Default, SimpleObject)]
+#[derive(Default, SimpleObject, Serialize)]
+#[graphql(complex)]
 struct BlockchainState {
     /// Value utc_date as numeric string
     utc_date: String,
@@ -772,13 +854,35 @@ struct BlockchainState {
     date: String,
 
     /// Value snarked ledger hash
-    snarked_ledger_hash: String,
+    #[graphql(skip)]
+    snarked_ledger_hash: Option<String>,
 
     /// Value staged ledger hash
     staged_ledger_hash: String,
+
+    #[graphql(skip)]
+    #[serde(skip)]
+    state_hash: String,
+}
+
+#[ComplexObject]
+impl BlockchainState {
+    /// Value snarked ledger hash
+    async fn snarked_ledger_hash(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> Result<Option<String>> {
+        full_block_field(
+            ctx,
+            self.snarked_ledger_hash.clone(),
+            &self.state_hash,
+            |state| state.blockchain_state.snarked_ledger_hash,
+        )
+        .await
+    }
 }
 
-#[derive(Default, SimpleObject)]
+#[derive(Default, SimpleObject, Serialize)]
 struct ProtocolState {
     /// Value parent state hash
     previous_state_hash: String,
@@ -790,22 +894,10 @@ struct ProtocolState {
     consensus_state: ConsensusState,
 }
 
-impl BlockWithoutCanonicity {
-    pub fn new(
-        block: &PrecomputedBlock,
-        canonical: bool,
-        epoch_num_user_commands: u32,
-        total_num_user_commands: u32,
-    ) -> Self {
-        let winner_account = block.block_creator().0;
-        let date_time = millis_to_iso_date_string(block.timestamp().try_into().unwrap());
-        let pk_creator = block.consensus_state().block_creator;
-        let creator = CompressedPubKey::from(&pk_creator).into_address();
-        let scheduled_time = block.scheduled_time().clone();
-        let received_time = millis_to_iso_date_string(scheduled_time.parse::<i64>().unwrap());
+impl ProtocolState {
+    /// Protocol state of the full precomputed block
+    fn new(block: &PrecomputedBlock) -> Self {
         let previous_state_hash = block.previous_state_hash().0;
-        let tx_fees = block.tx_fees();
-        let snark_fees = block.snark_fees();
         let utc_date = block.timestamp().to_string();
 
         // blockchain state
@@ -922,8 +1014,72 @@ impl BlockWithoutCanonicity {
             .t
             .t;
 
+        Self {
+            previous_state_hash,
+            blockchain_state: BlockchainState {
+                date: utc_date.clone(),
+                utc_date,
+                snarked_ledger_hash: Some(snarked_ledger_hash),
+                staged_ledger_hash,
+                state_hash: block.state_hash().0,
+            },
+            consensus_state: ConsensusState {
+                total_currency: Some(total_currency),
+                blockchain_length,
+                block_height,
+                epoch,
+                epoch_count,
+                has_ancestor_in_same_checkpoint_window: Some(
+                    has_ancestor_in_same_checkpoint_window,
+                ),
+                last_vrf_output,
+                min_window_density: Some(min_window_density),
+                slot,
+                slot_since_genesis,
+                state_hash: block.state_hash().0,
+                next_epoch_data: Some(NextEpochData {
+                    seed,
+                    epoch_length,
+                    start_checkpoint,
+                    lock_checkpoint,
+                    ledger: NextEpochDataLedger {
+                        hash: ledger_hash,
+                        total_currency: ledger_total_currency,
+                    },
+                }),
+                staking_epoch_data: Some(StakingEpochData {
+                    seed: staking_seed,
+                    epoch_length: staking_epoch_length,
+                    start_checkpoint: staking_start_checkpoint,
+                    lock_checkpoint: staking_lock_checkpoint,
+                    ledger: StakingEpochDataLedger {
+                        hash: staking_ledger_hash,
+                        total_currency: staking_ledger_total_currency,
+                    },
+                }),
+            },
+        }
+    }
+}
+
+impl BlockWithoutCanonicity {
+    pub fn new(
+        block: &PrecomputedBlock,
+        canonical: bool,
+        epoch_num_user_commands: u32,
+        total_num_user_commands: u32,
+    ) -> Self {
+        let winner_account = block.block_creator().0;
+        let date_time = millis_to_iso_date_string(block.timestamp().try_into().unwrap());
+        let pk_creator = block.consensus_state().block_creator;
+        let creator = CompressedPubKey::from(&pk_creator).into_address();
+        let scheduled_time = block.scheduled_time().clone();
+        let received_time = millis_to_iso_date_string(scheduled_time.parse::<i64>().unwrap());
+        let tx_fees = block.tx_fees();
+        let snark_fees = block.snark_fees();
+        let block_height = block.blockchain_length();
         let coinbase_receiver_account = block.coinbase_receiver().0;
-        let supercharged = consensus_state.supercharge_coinbase;
+        let supercharged = block.consensus_state().supercharge_coinbase;
         let coinbase: u64 = if supercharged {
             2 * MAINNET_COINBASE_REWARD
         } else {
@@ -972,45 +1128,124 @@ impl BlockWithoutCanonicity {
             },
             creator,
             received_time,
+            protocol_state: ProtocolState::new(block),
+            tx_fees: tx_fees.to_string(),
+            snark_fees: snark_fees.to_string(),
+            transactions: Transactions {
+                coinbase: coinbase.to_string(),
+                coinbase_receiver_account: PK {
+                    public_key: coinbase_receiver_account,
+                },
+                fee_transfer: fee_transfers,
+                user_commands,
+            },
+        }
+    }
+
+    /// Block from the compact contents of a pruned block, fields which are only
+    /// kept in the full precomputed block resolve to an error
+    pub fn from_pruned(
+        block: &PrunedBlock,
+        canonical: bool,
+        epoch_num_user_commands: u32,
+        total_num_user_commands: u32,
+    ) -> Self {
+        let state_hash = block.state_hash.0.clone();
+        let block_height = block.blockchain_length;
+        let date_time = millis_to_iso_date_string(block.timestamp as i64);
+        let received_time =
+            millis_to_iso_date_string(block.scheduled_time.parse::<i64>().unwrap_or_default());
+        let creator = block.creator.0.clone();
+        let utc_date = block.timestamp.to_string();
+        let tx_fees: u64 = block
+            .user_commands
+            .iter()
+            .map(|cmd| cmd.command.fee())
+            .sum();
+        let snark_fees: u64 = block.snarks.iter().map(|snark| snark.fee).sum();
+        let coinbase = block
+            .internal_commands
+            .iter()
+            .find_map(|cmd| match cmd {
+                InternalCommandWithData::Coinbase { amount, .. } => Some(*amount),
+                InternalCommandWithData::FeeTransfer { .. } => None,
+            })
+            .unwrap_or(MAINNET_COINBASE_REWARD);
+
+        let fee_transfers: Vec<BlockFeetransfer> = block
+            .internal_commands
+            .iter()
+            .filter(|x| matches!(x, InternalCommandWithData::FeeTransfer { .. }))
+            .map(|ft| ft.clone().into())
+            .collect();
+
+        let user_commands: Vec<TransactionWithoutBlock> = block
+            .user_commands
+            .iter()
+            .map(|cmd| {
+                TransactionWithoutBlock::new(
+                    cmd.clone(),
+                    canonical,
+                    epoch_num_user_commands,
+                    total_num_user_commands,
+                )
+            })
+            .collect();
+
+        let snark_jobs: Vec<SnarkJob> = block
+            .snarks
+            .iter()
+            .map(|snark| {
+                (
+                    snark.clone(),
+                    state_hash.clone(),
+                    block_height,
+                    date_time.clone(),
+                )
+                    .into()
+            })
+            .collect();
+
+        Self {
+            date_time,
+            snark_jobs,
+            state_hash: state_hash.clone(),
+            block_height,
+            global_slot_since_genesis: block.global_slot_since_genesis,
+            coinbase_receiver: PK {
+                public_key: block.coinbase_receiver.0.clone(),
+            },
+            winner_account: PK {
+                public_key: creator.clone(),
+            },
+            creator_account: PK {
+                public_key: creator.clone(),
+            },
+            creator,
+            received_time,
             protocol_state: ProtocolState {
-                previous_state_hash,
+                previous_state_hash: block.previous_state_hash.0.clone(),
                 blockchain_state: BlockchainState {
                     date: utc_date.clone(),
                     utc_date,
-                    snarked_ledger_hash,
-                    staged_ledger_hash,
+                    snarked_ledger_hash: None,
+                    staged_ledger_hash: block.staged_ledger_hash.0.clone(),
+                    state_hash: state_hash.clone(),
                 },
                 consensus_state: ConsensusState {
-                    total_currency,
-                    blockchain_length,
+                    total_currency: None,
+                    blockchain_length: block_height,
                     block_height,
-                    epoch,
-                    epoch_count,
-                    has_ancestor_in_same_checkpoint_window,
-                    last_vrf_output,
-                    min_window_density,
-                    slot,
-                    slot_since_genesis,
-                    next_epoch_data: NextEpochData {
-                        seed,
-                        epoch_length,
-                        start_checkpoint,
-                        lock_checkpoint,
-                        ledger: NextEpochDataLedger {
-                            hash: ledger_hash,
-                            total_currency: ledger_total_currency,
-                        },
-                    },
-                    staking_epoch_data: StakingEpochData {
-                        seed: staking_seed,
-                        epoch_length: staking_epoch_length,
-                        start_checkpoint: staking_start_checkpoint,
-                        lock_checkpoint: staking_lock_checkpoint,
-                        ledger: StakingEpochDataLedger {
-                            hash: staking_ledger_hash,
-                            total_currency: staking_ledger_total_currency,
-                        },
-                    },
+                    epoch: block.epoch,
+                    epoch_count: block.epoch,
+                    has_ancestor_in_same_checkpoint_window: None,
+                    last_vrf_output: block.last_vrf_output.clone(),
+                    min_window_density: None,
+                    slot: block.global_slot_since_genesis - (block.epoch * 7140),
+                    slot_since_genesis: block.global_slot_since_genesis,
+                    next_epoch_data: None,
+                    staking_epoch_data: None,
+                    state_hash,
                 },
             },
             tx_fees: tx_fees.to_string(),
@@ -1018,7 +1253,7 @@ impl BlockWithoutCanonicity {
             transactions: Transactions {
                 coinbase: coinbase.to_string(),
                 coinbase_receiver_account: PK {
-                    public_key: coinbase_receiver_account,
+                    public_key: block.coinbase_receiver.0.clone(),
                 },
                 fee_transfer: fee_transfers,
                 user_commands,
@@ -1027,261 +1262,196 @@ impl BlockWithoutCanonicity {
     }
 }
 
-impl BlockQueryInput {
-    pub fn matches(&self, block: &Block) -> bool {
-        let Self {
-            creator_account,
-            coinbase_receiver,
-            canonical,
-            or,
-            and,
-            state_hash,
-            block_height: blockchain_length,
-            global_slot_since_genesis,
-            block_height_gt,
-            block_height_gte,
-            block_height_lt,
-            block_height_lte,
-            protocol_state,
-            ..
-        } = self;
-        if let Some(canonical) = canonical {
-            if block.canonical != *canonical {
-                return false;
-            }
-        }
-        if let Some(state_hash) = state_hash {
-            if block.block.state_hash != *state_hash {
-                return false;
-            }
-        }
-        if let Some(blockchain_length) = blockchain_length {
-            if block.block.block_height != *blockchain_length {
-                return false;
-            }
-        }
+/// Full precomputed block, or the compact contents of a pruned block
+enum StoredBlock {
+    Full(Arc<PrecomputedBlock>),
+    Pruned(PrunedBlock),
+}
 
-        // global slot
-        if protocol_state
-            .as_ref()
-            .and_then(|protocol_state| protocol_state.consensus_state.as_ref())
-            .and_then(|consensus_state| consensus_state.slot_since_genesis)
-            .or(*global_slot_since_genesis)
-            .map_or(false, |global_slot| {
-                block
-                    .block
-                    .protocol_state
-                    .consensus_state
-                    .slot_since_genesis
-                    != global_slot
-                    || block.block.global_slot_since_genesis != global_slot
-            })
-        {
-            return false;
+/// Fields which are only kept in the full precomputed block
+const FULL_BLOCK_FIELDS: [&[&str]; 6] = [
+    &["protocol_state", "blockchain_state", "snarked_ledger_hash"],
+    &["protocol_state", "consensus_state", "total_currency"],
+    &[
+        "protocol_state",
+        "consensus_state",
+        "has_ancestor_in_same_checkpoint_window",
+    ],
+    &["protocol_state", "consensus_state", "min_window_density"],
+    &["protocol_state", "consensus_state", "next_epoch_data"],
+    &["protocol_state", "consensus_state", "staking_epoch_data"],
+];
+
+/// Get the block. Pruned blocks are served from their compact contents,
+/// they're only re-hydrated if the filter has a condition on a field which is
+/// only kept in the full block, resolvers of those fields re-hydrate on demand
+fn find_block(
+    db: &Arc<IndexerStore>,
+    cache: &PrecomputedBlockCache,
+    state_hash: &BlockHash,
+    filter: &Filter,
+) -> Result<Option<StoredBlock>> {
+    let Some(pruned) = db.get_pruned_block(state_hash)? else {
+        return Ok(cache.get(db, state_hash)?.map(StoredBlock::Full));
+    };
+    if FULL_BLOCK_FIELDS
+        .iter()
+        .any(|path| filter.has_condition_on(path))
+    {
+        if let Some(pcb) = cache.get(db, state_hash)? {
+            return Ok(Some(StoredBlock::Full(pcb)));
         }
+    }
+    Ok(Some(StoredBlock::Pruned(pruned)))
+}
 
-        // epoch slot
-        if protocol_state
-            .as_ref()
-            .and_then(|protocol_state| protocol_state.consensus_state.as_ref())
-            .and_then(|consensus_state| consensus_state.slot)
-            .map_or(false, |slot| {
-                block.block.protocol_state.consensus_state.slot != slot
-            })
-        {
-            return false;
-        }
+fn get_block(
+    db: &Arc<IndexerStore>,
+    cache: &PrecomputedBlockCache,
+    state_hash: &BlockHash,
+    filter: &Filter,
+) -> Result<StoredBlock> {
+    find_block(db, cache, state_hash, filter)?
+        .ok_or_else(|| format!("block missing from store {state_hash}").into())
+}
 
-        // block_height_gt(e) & block_height_lt(e)
-        if let Some(height) = block_height_gt {
-            if block.block.block_height <= *height {
-                return false;
-            }
-        }
-        if let Some(height) = block_height_gte {
-            if block.block.block_height < *height {
-                return false;
-            }
-        }
-        if let Some(height) = block_height_lt {
-            if block.block.block_height >= *height {
-                return false;
-            }
-        }
-        if let Some(height) = block_height_lte {
-            if block.block.block_height > *height {
-                return false;
-            }
-        }
+/// Push the blocks matching the filter whose height or slot, the sort key
+/// prefix of `sort_by`'s index, is within `min..=max`, in `sort_by` order
+///
+/// Iteration order is the page order, so cursors stay on their blocks
+fn push_blocks_in_range(
+    db: &Arc<IndexerStore>,
+    cache: &PrecomputedBlockCache,
+    filter: &Filter,
+    counts: [u32; 8],
+    sort_by: BlockSortByInput,
+    (min, max): (u32, u32),
+    page: &mut Page<Block>,
+) -> Result<()> {
+    use speedb::{Direction::*, IteratorMode::*};
+    use BlockSortByInput::*;
 
-        // global_slot_gt(e) & global_slot_lt(e)
-        if let Some(global_slot) = protocol_state
-            .as_ref()
-            .and_then(|f| f.consensus_state.as_ref())
-            .and_then(|f| f.slot_since_genesis_gt)
-        {
-            if block.block.global_slot_since_genesis <= global_slot {
-                return false;
-            }
-        }
-        if let Some(global_slot) = protocol_state
-            .as_ref()
-            .and_then(|f| f.consensus_state.as_ref())
-            .and_then(|f| f.slot_since_genesis_gte)
-        {
-            if block.block.global_slot_since_genesis < global_slot {
-                return false;
-            }
-        }
-        if let Some(global_slot) = protocol_state
-            .as_ref()
-            .and_then(|f| f.consensus_state.as_ref())
-            .and_then(|f| f.slot_since_genesis_lt)
-        {
-            if block.block.global_slot_since_genesis >= global_slot {
-                return false;
-            }
-        }
-        if let Some(global_slot) = protocol_state
-            .as_ref()
-            .and_then(|f| f.consensus_state.as_ref())
-            .and_then(|f| f.slot_since_genesis_lte)
-        {
-            if block.block.global_slot_since_genesis > global_slot {
-                return false;
-            }
+    let start = to_be_bytes(min);
+    let end = to_be_bytes(max.saturating_add(1));
+    let iter = match sort_by {
+        BlockHeightAsc => db.blocks_height_iterator(From(page.seek(&start), Forward)),
+        BlockHeightDesc => db.blocks_height_iterator(From(page.seek(&end), Reverse)),
+        GlobalSlotAsc => db.blocks_global_slot_iterator(From(page.seek(&start), Forward)),
+        GlobalSlotDesc => db.blocks_global_slot_iterator(From(page.seek(&end), Reverse)),
+    };
+    for (key, _) in iter.flatten() {
+        page.check_deadline()?;
+        let prefix = block_u32_prefix_from_key(&key)?;
+        if prefix < min || prefix > max {
+            break;
         }
 
-        // creator account
-        if let Some(creator_account) = creator_account {
-            if let Some(public_key) = creator_account.public_key.as_ref() {
-                if block.block.creator_account.public_key != *public_key {
-                    return false;
-                }
+        let state_hash = block_state_hash_from_key(&key)?;
+        let pcb = get_block(db, cache, &state_hash, filter)?;
+        if let Some(block) = precomputed_matches_query(db, filter, &pcb, counts)? {
+            if page.push(&key, block) {
+                break;
             }
         }
+    }
+    Ok(())
+}
 
-        // coinbase receiver
-        if let Some(coinbase_receiver) = coinbase_receiver {
-            if let Some(public_key) = coinbase_receiver.public_key.as_ref() {
-                if block.block.coinbase_receiver.public_key != *public_key {
-                    return false;
-                }
-            }
+/// Range of the `value`s of the blocks iterated in ascending order, while
+/// their sort key prefix is within `min..=max`, `None` if there are none
+fn blocks_range(
+    page: &Page<Block>,
+    iter: speedb::DBIterator<'_>,
+    (min, max): (u32, u32),
+    value: impl Fn(&BlockHash) -> anyhow::Result<Option<u32>>,
+) -> Result<Option<(u32, u32)>> {
+    let mut range: Option<(u32, u32)> = None;
+    for (key, _) in iter.flatten() {
+        page.check_deadline()?;
+        let prefix = block_u32_prefix_from_key(&key)?;
+        if prefix > max {
+            break;
         }
-
-        // conjunction
-        if let Some(query) = and {
-            if !query.iter().all(|and| and.matches(block)) {
-                return false;
-            }
+        if prefix < min {
+            continue;
         }
 
-        // disjunction
-        if let Some(query) = or {
-            if !query.is_empty() && !query.iter().any(|or| or.matches(block)) {
-                return false;
-            }
+        if let Some(value) = value(&block_state_hash_from_key(&key)?)? {
+            range = Some(match range {
+                Some((lo, hi)) => (lo.min(value), hi.max(value)),
+                None => (value, value),
+            });
         }
-        true
     }
+    Ok(range)
 }
 
-fn get_block(db: &Arc<IndexerStore>, state_hash: &BlockHash) -> PrecomputedBlock {
-    db.get_block(state_hash)
-        .with_context(|| format!("block missing from store {state_hash}"))
-        .unwrap()
-        .unwrap()
-        .0
+/// Epoch & total block, SNARK, user command & internal command counts
+/// used by [Block::from_precomputed]
+pub(crate) fn block_counts(db: &Arc<IndexerStore>) -> anyhow::Result<[u32; 8]> {
+    let epoch_num_blocks = db.get_block_production_epoch_count(None)?;
+    let total_num_blocks = db.get_block_production_total_count()?;
+    let epoch_num_snarks = db.get_snarks_epoch_count(None)?;
+    let total_num_snarks = db.get_snarks_total_count()?;
+    let epoch_num_user_commands = db.get_user_commands_epoch_count(None)?;
+    let total_num_user_commands = db.get_user_commands_total_count()?;
+    let epoch_num_internal_commands = db.get_internal_commands_epoch_count(None)?;
+    let total_num_internal_commands = db.get_internal_commands_total_count()?;
+    Ok([
+        epoch_num_blocks,
+        total_num_blocks,
+        epoch_num_snarks,
+        total_num_snarks,
+        epoch_num_user_commands,
+        total_num_user_commands,
+        epoch_num_internal_commands,
+        total_num_internal_commands,
+    ])
 }
 
-fn reorder(db: &Arc<IndexerStore>, blocks: &mut [Block], sort_by: BlockSortByInput) {
-    use std::cmp::Ordering::{self, *};
-    use BlockSortByInput::*;
+impl Block {
+    pub fn from_precomputed(
+        db: &Arc<IndexerStore>,
+        block: &PrecomputedBlock,
+        counts: [u32; 8],
+    ) -> Self {
+        Self::with_counts(db, &block.state_hash(), counts, |canonical| {
+            BlockWithoutCanonicity::new(block, canonical, counts[4], counts[5])
+        })
+    }
 
-    fn height_cmp(db: &Arc<IndexerStore>, a: &Block, b: &Block, cmp: Ordering) -> Ordering {
-        if cmp == Equal {
-            match (a.canonical, b.canonical) {
-                (true, _) => Less,
-                (_, true) => Greater,
-                _ => db
-                    .block_cmp(
-                        &a.block.state_hash.clone().into(),
-                        &b.block.state_hash.clone().into(),
-                    )
-                    .unwrap()
-                    .unwrap(),
-            }
-        } else {
-            cmp
-        }
+    pub fn from_pruned(db: &Arc<IndexerStore>, block: &PrunedBlock, counts: [u32; 8]) -> Self {
+        Self::with_counts(db, &block.state_hash, counts, |canonical| {
+            BlockWithoutCanonicity::from_pruned(block, canonical, counts[4], counts[5])
+        })
     }
-    fn slot_cmp(db: &Arc<IndexerStore>, a: &Block, b: &Block, cmp: Ordering) -> Ordering {
-        if cmp == Equal {
-            match (a.canonical, b.canonical) {
-                (true, _) => Less,
-                (_, true) => Greater,
-                _ => db
-                    .block_cmp(
-                        &a.block.state_hash.clone().into(),
-                        &b.block.state_hash.clone().into(),
-                    )
-                    .unwrap()
-                    .unwrap(),
-            }
-        } else {
-            cmp
+
+    fn from_stored(db: &Arc<IndexerStore>, block: &StoredBlock, counts: [u32; 8]) -> Self {
+        match block {
+            StoredBlock::Full(pcb) => Self::from_precomputed(db, pcb, counts),
+            StoredBlock::Pruned(pruned) => Self::from_pruned(db, pruned, counts),
         }
     }
-    match sort_by {
-        BlockHeightAsc => blocks
-            .sort_by(|a, b| height_cmp(db, a, b, a.block.block_height.cmp(&b.block.block_height))),
-        BlockHeightDesc => blocks
-            .sort_by(|a, b| height_cmp(db, a, b, b.block.block_height.cmp(&a.block.block_height))),
-        GlobalSlotAsc => blocks.sort_by(|a, b| {
-            slot_cmp(
-                db,
-                a,
-                b,
-                a.block
-                    .global_slot_since_genesis
-                    .cmp(&b.block.global_slot_since_genesis),
-            )
-        }),
-        GlobalSlotDesc => blocks.sort_by(|a, b| {
-            slot_cmp(
-                db,
-                a,
-                b,
-                b.block
-                    .global_slot_since_genesis
-                    .cmp(&a.block.global_slot_since_genesis),
-            )
-        }),
-    }
-}
 
-impl Block {
-    pub fn from_precomputed(
+    fn with_counts(
         db: &Arc<IndexerStore>,
-        block: &PrecomputedBlock,
+        state_hash: &BlockHash,
         counts: [u32; 8],
+        block: impl FnOnce(bool) -> BlockWithoutCanonicity,
     ) -> Self {
         let epoch_num_blocks = counts[0];
         let total_num_blocks = counts[1];
-        let epoch_num_user_commands = counts[4];
-        let total_num_user_commands = counts[5];
-        let canonical = get_block_canonicity(db, &block.state_hash().0);
+        let canonical = get_block_canonicity(db, &state_hash.0);
         let block_num_snarks = db
-            .get_block_snarks_count(&block.state_hash())
+            .get_block_snarks_count(state_hash)
             .expect("snark counts")
             .unwrap_or_default();
         let block_num_user_commands = db
-            .get_block_user_commands_count(&block.state_hash())
+            .get_block_user_commands_count(state_hash)
             .expect("user command counts")
             .unwrap_or_default();
         let block_num_internal_commands = db
-            .get_block_internal_commands_count(&block.state_hash())
+            .get_block_internal_commands_count(state_hash)
             .expect("internal command counts")
             .unwrap_or_default();
         Self {
@@ -1291,12 +1461,7 @@ impl Block {
             block_num_snarks,
             block_num_user_commands,
             block_num_internal_commands,
-            block: BlockWithoutCanonicity::new(
-                block,
-                canonical,
-                epoch_num_user_commands,
-                total_num_user_commands,
-            ),
+            block: block(canonical),
             num_unique_block_producers_last_n_blocks: None,
         }
     }
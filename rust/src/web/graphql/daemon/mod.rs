@@ -0,0 +1,582 @@
+//! Read-only subset of the Mina daemon's GraphQL schema
+//!
+//! Served at `/daemon-graphql` so tooling written against the daemon can
+//! query the indexer unchanged. Field & scalar names follow the daemon's
+//! (pre-Berkeley) schema, unsigned integers are serialized as strings. Blocks
+//! are those of the best chain.
+
+use super::{db, limits::list_limit, pruned_block_error};
+use crate::{
+    block::{is_valid_state_hash, precomputed::PrecomputedBlock, store::BlockStore, BlockHash},
+    canonicity::store::CanonicityStore,
+    command::{
+        internal::{InternalCommand, InternalCommandWithData},
+        signed::SignedCommandWithData,
+        CommandStatusData,
+    },
+    constants::*,
+    ledger::{account, genesis, public_key::PublicKey, store::LedgerStore, LedgerHash},
+    proof_systems::signer::pubkey::CompressedPubKey,
+    protocol::serialization_types::version_bytes,
+    store::{account::AccountStore, IndexerStore},
+    web::{
+        graphql::{
+            limits::{GraphQLLimits, Timeout},
+            metrics::OperationMetrics,
+        },
+        ENDPOINT_DAEMON_GRAPHQL,
+    },
+};
+use async_graphql::{
+    extensions::Analyzer, EmptyMutation, EmptySubscription, Enum, InputValueError,
+    InputValueResult, Object, Result, Scalar, ScalarType, Schema, SimpleObject, Value,
+};
+use std::{sync::Arc, time::Instant};
+
+pub type DaemonSchema = Schema<DaemonQueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Build the daemon compatible schema of the network with the genesis
+/// `constants`, subject to `limits`
+pub fn build_daemon_schema(
+    store: Arc<IndexerStore>,
+    constants: genesis::GenesisConstants,
+    limits: GraphQLLimits,
+) -> DaemonSchema {
+    Schema::build(DaemonQueryRoot, EmptyMutation, EmptySubscription)
+        .data(store)
+        .data(constants)
+        .data(limits)
+        .data(StartTime(Instant::now()))
+        .limit_depth(limits.max_depth)
+        .limit_complexity(limits.max_complexity)
+        .extension(Analyzer)
+        .extension(Timeout(limits.timeout))
+        .extension(OperationMetrics(ENDPOINT_DAEMON_GRAPHQL))
+        .finish()
+}
+
+/// Daemon uptime is the web server's uptime
+struct StartTime(Instant);
+
+fn network_constants<'a>(ctx: &'a async_graphql::Context) -> &'a genesis::GenesisConstants {
+    ctx.data::<genesis::GenesisConstants>()
+        .expect("Genesis constants should be in the context")
+}
+
+/// Scalars serialized as strings
+macro_rules! string_scalar {
+    ($ty:ident, $name:literal, $inner:ty) => {
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $ty(pub $inner);
+
+        #[Scalar(name = $name)]
+        impl ScalarType for $ty {
+            fn parse(value: Value) -> InputValueResult<Self> {
+                match &value {
+                    Value::String(s) => s
+                        .parse()
+                        .map(Self)
+                        .map_err(|_| InputValueError::expected_type(value)),
+                    Value::Number(n) => n
+                        .to_string()
+                        .parse()
+                        .map(Self)
+                        .map_err(|_| InputValueError::expected_type(value)),
+                    _ => Err(InputValueError::expected_type(value)),
+                }
+            }
+
+            fn to_value(&self) -> Value {
+                Value::String(self.0.to_string())
+            }
+        }
+    };
+}
+
+string_scalar!(UInt32, "UInt32", u32);
+string_scalar!(UInt64, "UInt64", u64);
+string_scalar!(TokenId, "TokenId", u64);
+string_scalar!(PublicKeyScalar, "PublicKey", String);
+
+#[derive(Default)]
+pub struct DaemonQueryRoot;
+
+#[Object]
+impl DaemonQueryRoot {
+    async fn daemon_status(&self, ctx: &async_graphql::Context<'_>) -> Result<DaemonStatus> {
+        let db = db(ctx);
+        let best_block = db.get_best_block()?;
+        let blockchain_length = best_block.as_ref().map(|block| block.blockchain_length());
+        let uptime = ctx
+            .data::<StartTime>()
+            .map_or(0, |start| start.0.elapsed().as_secs());
+
+        Ok(DaemonStatus {
+            blockchain_length,
+            highest_block_length_received: blockchain_length.unwrap_or_default(),
+            sync_status: if best_block.is_some() {
+                SyncStatus::Synced
+            } else {
+                SyncStatus::Bootstrap
+            },
+            commit_id: GIT_COMMIT_HASH.to_string(),
+            uptime_secs: uptime,
+            state_hash: best_block.as_ref().map(|block| block.state_hash().0),
+            num_accounts: db.get_num_accounts()?,
+            ledger_merkle_root: best_block
+                .as_ref()
+                .map(|block| block.staged_ledger_hash().0),
+        })
+    }
+
+    async fn genesis_constants(&self, ctx: &async_graphql::Context<'_>) -> GenesisConstants {
+        let constants = network_constants(ctx);
+        let genesis_timestamp = constants
+            .genesis_state_timestamp
+            .unwrap_or(MAINNET_GENESIS_TIMESTAMP);
+
+        GenesisConstants {
+            account_creation_fee: UInt64(
+                constants
+                    .account_creation_fee
+                    .unwrap_or(MAINNET_ACCOUNT_CREATION_FEE.0),
+            ),
+            coinbase: UInt64(constants.coinbase_amount.unwrap_or(MAINNET_COINBASE_REWARD)),
+            genesis_timestamp: millis_to_iso_date_string(genesis_timestamp as i64),
+        }
+    }
+
+    /// Account of the best ledger, only the default token is supported
+    async fn account(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        public_key: PublicKeyScalar,
+        token: Option<UInt64>,
+    ) -> Result<Option<Account>> {
+        if token.is_some_and(|token| token.0 != 1) {
+            return Ok(None);
+        }
+
+        let db = db(ctx);
+        let (Some(best_hash), Some(best_height)) =
+            (db.get_best_block_hash()?, db.get_best_block_height()?)
+        else {
+            return Ok(None);
+        };
+        let global_slot = db.get_best_block_global_slot()?.unwrap_or_default();
+
+        Ok(db.get_best_ledger()?.and_then(|ledger| {
+            ledger
+                .accounts
+                .get(&PublicKey::from(public_key.0))
+                .map(|account| Account::new(account, global_slot, best_height, &best_hash))
+        }))
+    }
+
+    /// Best chain blocks ending at the best tip, oldest first
+    #[graphql(
+        complexity = "max_length.unwrap_or(MAINNET_TRANSITION_FRONTIER_K as usize) \
+                            .saturating_mul(child_complexity)"
+    )]
+    async fn best_chain(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        max_length: Option<usize>,
+    ) -> Result<Option<Vec<Block>>> {
+        let db = db(ctx);
+        let max_length = list_limit(
+            ctx,
+            max_length.unwrap_or(MAINNET_TRANSITION_FRONTIER_K as usize),
+        );
+
+        let mut blocks = vec![];
+        let mut state_hash = db.get_best_block_hash()?;
+        while let Some(hash) = state_hash {
+            if blocks.len() >= max_length {
+                break;
+            }
+            let block = precomputed_block(db, &hash)?;
+            state_hash = (block.blockchain_length() > 1)
+                .then(|| block.previous_state_hash())
+                .filter(|parent| db.get_block_height(parent).ok().flatten().is_some());
+            blocks.push(Block::new(&block, network_constants(ctx)));
+        }
+
+        blocks.reverse();
+        Ok(Some(blocks))
+    }
+
+    /// Best chain block via its state hash or height
+    async fn block(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        state_hash: Option<String>,
+        height: Option<u32>,
+    ) -> Result<Block> {
+        let db = db(ctx);
+        let state_hash = match (state_hash, height) {
+            (Some(state_hash), None) => {
+                if !is_valid_state_hash(&state_hash) {
+                    return Err(async_graphql::Error::new(format!(
+                        "Invalid state hash {state_hash}"
+                    )));
+                }
+                BlockHash(state_hash)
+            }
+            (None, Some(height)) => best_chain_hash_at_height(db, height)?.ok_or_else(|| {
+                async_graphql::Error::new(format!("No best chain block at height {height}"))
+            })?,
+            _ => {
+                return Err(async_graphql::Error::new(
+                    "Provide either a stateHash or a height",
+                ))
+            }
+        };
+
+        Ok(Block::new(
+            &precomputed_block(db, &state_hash)?,
+            network_constants(ctx),
+        ))
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct DaemonStatus {
+    blockchain_length: Option<u32>,
+    highest_block_length_received: u32,
+    sync_status: SyncStatus,
+    commit_id: String,
+    uptime_secs: u64,
+    state_hash: Option<String>,
+    num_accounts: Option<u32>,
+    ledger_merkle_root: Option<String>,
+}
+
+/// The indexer is synced once it has a best tip
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum SyncStatus {
+    Bootstrap,
+    Synced,
+}
+
+#[derive(SimpleObject)]
+pub struct GenesisConstants {
+    account_creation_fee: UInt64,
+    coinbase: UInt64,
+    genesis_timestamp: String,
+}
+
+#[derive(SimpleObject)]
+pub struct Account {
+    public_key: PublicKeyScalar,
+    token: TokenId,
+    nonce: Option<String>,
+    inferred_nonce: Option<String>,
+    balance: AnnotatedBalance,
+    delegate: Option<PublicKeyScalar>,
+    receipt_chain_hash: Option<String>,
+    voting_for: Option<String>,
+    timing: AccountTiming,
+}
+
+#[derive(SimpleObject)]
+pub struct AnnotatedBalance {
+    total: UInt64,
+    liquid: Option<UInt64>,
+    locked: Option<UInt64>,
+    block_height: UInt32,
+    state_hash: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct AccountTiming {
+    initial_minimum_balance: Option<UInt64>,
+    cliff_time: Option<UInt32>,
+    cliff_amount: Option<UInt64>,
+    vesting_period: Option<UInt32>,
+    vesting_increment: Option<UInt64>,
+}
+
+#[derive(SimpleObject)]
+pub struct Block {
+    creator: PublicKeyScalar,
+    state_hash: String,
+    protocol_state: ProtocolState,
+    transactions: Transactions,
+    command_transaction_count: u32,
+}
+
+#[derive(SimpleObject)]
+pub struct ProtocolState {
+    previous_state_hash: String,
+    blockchain_state: BlockchainState,
+    consensus_state: ConsensusState,
+}
+
+#[derive(SimpleObject)]
+pub struct BlockchainState {
+    /// Milliseconds since the Unix epoch
+    date: String,
+    utc_date: String,
+    snarked_ledger_hash: String,
+    staged_ledger_hash: String,
+}
+
+#[derive(SimpleObject)]
+pub struct ConsensusState {
+    blockchain_length: UInt32,
+    block_height: UInt32,
+    epoch_count: UInt32,
+    epoch: UInt32,
+    slot: UInt32,
+    slot_since_genesis: UInt32,
+    min_window_density: UInt32,
+    last_vrf_output: String,
+    total_currency: UInt64,
+    has_ancestor_in_same_checkpoint_window: bool,
+    supercharged_coinbase: bool,
+    block_stake_winner: PublicKeyScalar,
+    block_creator: PublicKeyScalar,
+
+    /// Misspelled as in the daemon's schema
+    coinbase_receiever: PublicKeyScalar,
+}
+
+#[derive(SimpleObject)]
+pub struct Transactions {
+    user_commands: Vec<UserCommand>,
+    fee_transfer: Vec<FeeTransfer>,
+    coinbase: UInt64,
+    coinbase_receiver: Option<PublicKeyScalar>,
+}
+
+#[derive(SimpleObject)]
+pub struct UserCommand {
+    hash: String,
+    kind: String,
+    nonce: u32,
+    from: PublicKeyScalar,
+    to: PublicKeyScalar,
+    amount: UInt64,
+    fee: UInt64,
+    fee_token: TokenId,
+    token: TokenId,
+    valid_until: UInt32,
+
+    /// Base58 encoded memo
+    memo: String,
+    is_delegation: bool,
+    failure_reason: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct FeeTransfer {
+    recipient: PublicKeyScalar,
+    fee: UInt64,
+
+    #[graphql(name = "type")]
+    kind: String,
+}
+
+/////////////
+// Helpers //
+/////////////
+
+fn precomputed_block(db: &IndexerStore, state_hash: &BlockHash) -> Result<PrecomputedBlock> {
+    db.get_block(state_hash)?
+        .map(|(block, _)| block)
+        .ok_or_else(|| match db.get_block_height(state_hash) {
+            Ok(Some(_)) => pruned_block_error(&state_hash.0),
+            _ => async_graphql::Error::new(format!("Block {state_hash} not found")),
+        })
+}
+
+/// Canonical block at the height or, above the canonical tip, the best tip's
+/// ancestor
+fn best_chain_hash_at_height(db: &IndexerStore, height: u32) -> anyhow::Result<Option<BlockHash>> {
+    if let Some(state_hash) = db.get_canonical_hash_at_height(height)? {
+        return Ok(Some(state_hash));
+    }
+
+    let (Some(mut state_hash), Some(mut best_height)) =
+        (db.get_best_block_hash()?, db.get_best_block_height()?)
+    else {
+        return Ok(None);
+    };
+    while best_height > height {
+        match db.get_block_parent_hash(&state_hash)? {
+            Some(parent_hash) => state_hash = parent_hash,
+            None => return Ok(None),
+        }
+        best_height -= 1;
+    }
+    Ok((best_height == height).then_some(state_hash))
+}
+
+impl Account {
+    fn new(
+        account: &account::Account,
+        global_slot: u32,
+        best_height: u32,
+        best_hash: &BlockHash,
+    ) -> Self {
+        let nonce = account.nonce.unwrap_or_default().to_string();
+        let locked = account.current_minimum_balance(global_slot);
+        let timing = account.timing.as_ref();
+
+        Self {
+            public_key: PublicKeyScalar(account.public_key.0.clone()),
+            token: TokenId(account.token.unwrap_or(1)),
+            nonce: Some(nonce.clone()),
+            inferred_nonce: Some(nonce),
+            balance: AnnotatedBalance {
+                total: UInt64(account.balance.0),
+                liquid: Some(UInt64(account.balance.0.saturating_sub(locked))),
+                locked: Some(UInt64(locked)),
+                block_height: UInt32(best_height),
+                state_hash: Some(best_hash.0.clone()),
+            },
+            delegate: Some(PublicKeyScalar(account.delegate.0.clone())),
+            receipt_chain_hash: account
+                .receipt_chain_hash
+                .as_ref()
+                .map(|hash| hash.0.clone()),
+            voting_for: account.voting_for.as_ref().map(|hash| hash.0.clone()),
+            timing: AccountTiming {
+                initial_minimum_balance: timing.map(|t| UInt64(t.initial_minimum_balance)),
+                cliff_time: timing.map(|t| UInt32(t.cliff_time)),
+                cliff_amount: timing.map(|t| UInt64(t.cliff_amount)),
+                vesting_period: timing.map(|t| UInt32(t.vesting_period)),
+                vesting_increment: timing.map(|t| UInt64(t.vesting_increment)),
+            },
+        }
+    }
+}
+
+impl Block {
+    /// Coinbase & slots are those of the network with the genesis `constants`
+    fn new(block: &PrecomputedBlock, constants: &genesis::GenesisConstants) -> Self {
+        let blockchain_state = block.blockchain_state();
+        let staged_ledger_hash = blockchain_state
+            .staged_ledger_hash
+            .clone()
+            .t
+            .t
+            .non_snark
+            .t
+            .ledger_hash;
+        let consensus_state = block.consensus_state();
+        let epoch = block.epoch_count();
+        let slot_since_genesis = block.global_slot_since_genesis();
+        let creator = CompressedPubKey::from(&consensus_state.block_creator).into_address();
+        let supercharged_coinbase = consensus_state.supercharge_coinbase;
+        let coinbase = constants.coinbase_amount.unwrap_or(MAINNET_COINBASE_REWARD);
+        let slots_per_epoch = constants
+            .slots_per_epoch
+            .unwrap_or(MAINNET_EPOCH_SLOT_COUNT);
+
+        let user_commands: Vec<UserCommand> = SignedCommandWithData::from_precomputed(block)
+            .into_iter()
+            .map(Into::<UserCommand>::into)
+            .collect();
+        let fee_transfer = InternalCommand::from_precomputed(block)
+            .into_iter()
+            .filter_map(
+                |cmd| match InternalCommandWithData::from_internal_cmd(cmd, block) {
+                    InternalCommandWithData::FeeTransfer {
+                        receiver,
+                        amount,
+                        kind,
+                        ..
+                    } => Some(FeeTransfer {
+                        recipient: PublicKeyScalar(receiver.0),
+                        fee: UInt64(amount),
+                        kind: kind.to_string(),
+                    }),
+                    InternalCommandWithData::Coinbase { .. } => None,
+                },
+            )
+            .collect();
+
+        Self {
+            creator: PublicKeyScalar(creator.clone()),
+            state_hash: block.state_hash().0,
+            command_transaction_count: user_commands.len() as u32,
+            protocol_state: ProtocolState {
+                previous_state_hash: block.previous_state_hash().0,
+                blockchain_state: BlockchainState {
+                    date: block.timestamp().to_string(),
+                    utc_date: block.timestamp().to_string(),
+                    snarked_ledger_hash: LedgerHash::from_hashv1(
+                        blockchain_state.snarked_ledger_hash,
+                    )
+                    .0,
+                    staged_ledger_hash: LedgerHash::from_hashv1(staged_ledger_hash).0,
+                },
+                consensus_state: ConsensusState {
+                    blockchain_length: UInt32(block.blockchain_length()),
+                    block_height: UInt32(block.blockchain_length()),
+                    epoch_count: UInt32(epoch),
+                    epoch: UInt32(epoch),
+                    slot: UInt32(slot_since_genesis - epoch * slots_per_epoch),
+                    slot_since_genesis: UInt32(slot_since_genesis),
+                    min_window_density: UInt32(consensus_state.min_window_density.t.t),
+                    last_vrf_output: block.last_vrf_output(),
+                    total_currency: UInt64(consensus_state.total_currency.t.t),
+                    has_ancestor_in_same_checkpoint_window: consensus_state
+                        .has_ancestor_in_same_checkpoint_window,
+                    supercharged_coinbase,
+                    block_stake_winner: PublicKeyScalar(block.block_stake_winner().0),
+                    block_creator: PublicKeyScalar(creator),
+                    coinbase_receiever: PublicKeyScalar(block.coinbase_receiver().0),
+                },
+            },
+            transactions: Transactions {
+                user_commands,
+                fee_transfer,
+                coinbase: UInt64(if supercharged_coinbase {
+                    2 * coinbase
+                } else {
+                    coinbase
+                }),
+                coinbase_receiver: Some(PublicKeyScalar(block.coinbase_receiver().0)),
+            },
+        }
+    }
+}
+
+impl From<SignedCommandWithData> for UserCommand {
+    fn from(cmd: SignedCommandWithData) -> Self {
+        let command = &cmd.command;
+        let common = command.payload_common();
+        let is_delegation = command.is_delegation();
+
+        Self {
+            hash: cmd.tx_hash.clone(),
+            kind: if is_delegation {
+                "STAKE_DELEGATION"
+            } else {
+                "PAYMENT"
+            }
+            .to_string(),
+            nonce: cmd.nonce.0,
+            from: PublicKeyScalar(command.source_pk().0),
+            to: PublicKeyScalar(command.receiver_pk().0),
+            amount: UInt64(command.amount()),
+            fee: UInt64(command.fee()),
+            fee_token: TokenId(common.fee_token.t.t.t),
+            token: TokenId(1),
+            valid_until: UInt32(common.valid_until.t.t as u32),
+            memo: bs58::encode(&common.memo.t.0)
+                .with_check_version(version_bytes::USER_COMMAND_MEMO)
+                .into_string(),
+            is_delegation,
+            failure_reason: match &cmd.status {
+                CommandStatusData::Applied { .. } => None,
+                CommandStatusData::Failed(reasons, _) => {
+                    reasons.first().map(|reason| reason.to_string())
+                }
+            },
+        }
+    }
+}
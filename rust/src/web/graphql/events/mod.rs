@@ -0,0 +1,214 @@
+use crate::{
+    event::{checkpoint, db::*, store::EventStore, IndexerEvent, SeqEvent},
+    web::graphql::{db, limits::list_limit},
+};
+use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum EventKind {
+    NewBlock,
+    NewBestTip,
+    NewCanonicalBlock,
+    NewLedger,
+    NewStakingLedger,
+    AggregateDelegations,
+}
+
+#[derive(InputObject)]
+pub struct EventQueryInput {
+    kind: Option<EventKind>,
+
+    #[graphql(name = "seqNum_gte")]
+    seq_num_gte: Option<u32>,
+
+    #[graphql(name = "seqNum_lt")]
+    seq_num_lt: Option<u32>,
+}
+
+#[derive(SimpleObject)]
+pub struct Event {
+    /// Value sequence number
+    seq_num: u32,
+
+    /// Value event kind
+    kind: EventKind,
+
+    /// Value block state hash
+    state_hash: Option<String>,
+
+    /// Value blockchain length
+    blockchain_length: Option<u32>,
+
+    /// Value (staking) ledger hash
+    ledger_hash: Option<String>,
+
+    /// Value staking epoch
+    epoch: Option<u32>,
+
+    /// Value genesis state hash
+    genesis_state_hash: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct EventCheckpoint {
+    /// Value sequence number of the first event following the checkpoint
+    seq_num: u32,
+
+    /// Value best tip state hash
+    best_tip: String,
+
+    /// Value best tip blockchain length
+    best_tip_height: u32,
+
+    /// Value canonical root state hash
+    canonical_root: String,
+
+    /// Value canonical root blockchain length
+    canonical_root_height: u32,
+
+    /// Value number of blocks processed
+    blocks_processed: u32,
+}
+
+#[derive(Default)]
+pub struct EventsQueryRoot;
+
+#[Object]
+impl EventsQueryRoot {
+    /// Audit the event log via sequence number range & kind
+    #[graphql(complexity = "limit.saturating_mul(child_complexity)")]
+    async fn events<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<EventQueryInput>,
+        #[graphql(default = 100)] limit: usize,
+    ) -> Result<Vec<Event>> {
+        let db = db(ctx);
+        let (start, end, kind) = match query {
+            Some(query) => (
+                query.seq_num_gte.unwrap_or_default(),
+                query.seq_num_lt,
+                query.kind.map(Into::into),
+            ),
+            None => (0, None, None),
+        };
+
+        Ok(db
+            .get_events(start, end, kind, list_limit(ctx, limit))?
+            .into_iter()
+            .map(Event::from)
+            .collect())
+    }
+
+    /// Event log checkpoints, the event log is compacted to the latest one
+    async fn event_checkpoints<'ctx>(&self, ctx: &Context<'ctx>) -> Result<Vec<EventCheckpoint>> {
+        let db = db(ctx);
+        Ok(db
+            .get_event_checkpoints()?
+            .into_iter()
+            .map(EventCheckpoint::from)
+            .collect())
+    }
+}
+
+impl From<EventKind> for crate::event::EventKind {
+    fn from(value: EventKind) -> Self {
+        match value {
+            EventKind::NewBlock => Self::NewBlock,
+            EventKind::NewBestTip => Self::NewBestTip,
+            EventKind::NewCanonicalBlock => Self::NewCanonicalBlock,
+            EventKind::NewLedger => Self::NewLedger,
+            EventKind::NewStakingLedger => Self::NewStakingLedger,
+            EventKind::AggregateDelegations => Self::AggregateDelegations,
+        }
+    }
+}
+
+impl From<crate::event::EventKind> for EventKind {
+    fn from(value: crate::event::EventKind) -> Self {
+        use crate::event::EventKind::*;
+        match value {
+            NewBlock => Self::NewBlock,
+            NewBestTip => Self::NewBestTip,
+            NewCanonicalBlock => Self::NewCanonicalBlock,
+            NewLedger => Self::NewLedger,
+            NewStakingLedger => Self::NewStakingLedger,
+            AggregateDelegations => Self::AggregateDelegations,
+        }
+    }
+}
+
+impl From<SeqEvent> for Event {
+    fn from(value: SeqEvent) -> Self {
+        let mut event = Self {
+            seq_num: value.seq_num,
+            kind: value.kind.into(),
+            state_hash: None,
+            blockchain_length: None,
+            ledger_hash: None,
+            epoch: None,
+            genesis_state_hash: None,
+        };
+
+        if let IndexerEvent::Db(db_event) = value.event {
+            match db_event {
+                DbEvent::Block(
+                    DbBlockEvent::NewBlock {
+                        state_hash,
+                        blockchain_length,
+                    }
+                    | DbBlockEvent::NewBestTip {
+                        state_hash,
+                        blockchain_length,
+                    },
+                )
+                | DbEvent::Canonicity(DbCanonicityEvent::NewCanonicalBlock {
+                    state_hash,
+                    blockchain_length,
+                }) => {
+                    event.state_hash = Some(state_hash.0);
+                    event.blockchain_length = Some(blockchain_length);
+                }
+                DbEvent::Ledger(DbLedgerEvent::NewLedger {
+                    state_hash,
+                    blockchain_length,
+                    ledger_hash,
+                }) => {
+                    event.state_hash = Some(state_hash.0);
+                    event.blockchain_length = Some(blockchain_length);
+                    event.ledger_hash = Some(ledger_hash.0);
+                }
+                DbEvent::StakingLedger(DbStakingLedgerEvent::NewStakingLedger {
+                    epoch,
+                    ledger_hash,
+                    genesis_state_hash,
+                }) => {
+                    event.epoch = Some(epoch);
+                    event.ledger_hash = Some(ledger_hash.0);
+                    event.genesis_state_hash = Some(genesis_state_hash.0);
+                }
+                DbEvent::StakingLedger(DbStakingLedgerEvent::AggregateDelegations {
+                    epoch,
+                    genesis_state_hash,
+                }) => {
+                    event.epoch = Some(epoch);
+                    event.genesis_state_hash = Some(genesis_state_hash.0);
+                }
+            }
+        }
+        event
+    }
+}
+
+impl From<checkpoint::EventCheckpoint> for EventCheckpoint {
+    fn from(value: checkpoint::EventCheckpoint) -> Self {
+        Self {
+            seq_num: value.seq_num,
+            best_tip: value.best_tip.0,
+            best_tip_height: value.best_tip_height,
+            canonical_root: value.canonical_root.0,
+            canonical_root_height: value.canonical_root_height,
+            blocks_processed: value.blocks_processed,
+        }
+    }
+}
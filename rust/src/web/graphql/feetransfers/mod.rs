@@ -4,19 +4,21 @@ use super::{
     get_block_canonicity,
 };
 use crate::{
-    block::{precomputed::PrecomputedBlock, store::BlockStore, BlockHash},
+    block::{store::BlockStore, BlockHash},
     canonicity::{store::CanonicityStore, Canonicity},
-    command::{
-        internal::{store::InternalCommandStore, InternalCommandWithData},
-        store::UserCommandStore,
-    },
+    command::internal::{store::InternalCommandStore, InternalCommandWithData},
     constants::*,
-    snark_work::store::SnarkStore,
-    store::IndexerStore,
-    web::graphql::db,
+    store::{block_state_hash_from_key, block_u32_prefix_from_key, to_be_bytes, IndexerStore},
+    web::graphql::{
+        db,
+        limits::{list_limit, BLOCK_READ_COST},
+        loaders::{block_cache, loader, BlockLoader, BlockSummaryLoader, PrecomputedBlockCache},
+        pagination::{Page, SortKeyConnection},
+    },
 };
 use anyhow::Context as aContext;
 use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
+use speedb::{Direction, IteratorMode};
 use std::sync::Arc;
 
 #[derive(SimpleObject, Debug)]
@@ -42,9 +44,6 @@ pub struct FeetransferWithMeta {
     /// Value canonicity
     pub canonical: bool,
 
-    /// Value optional block
-    pub block: Option<PrecomputedBlock>,
-
     /// Value feetranser
     pub feetransfer: Feetransfer,
 }
@@ -60,41 +59,44 @@ impl FeetransferWithMeta {
         &self.feetransfer
     }
 
+    #[graphql(complexity = "BLOCK_READ_COST + child_complexity")]
     async fn block_state_hash<'ctx>(&self, ctx: &Context<'ctx>) -> Result<Option<Block>> {
-        let db = db(ctx);
-        let epoch_num_blocks = db.get_block_production_epoch_count(None)?;
-        let total_num_blocks = db.get_block_production_total_count()?;
-        let epoch_num_user_commands = db.get_user_commands_epoch_count(None)?;
-        let total_num_user_commands = db.get_user_commands_total_count()?;
-
-        if let Some(block) = self.block.clone() {
-            let block_num_snarks = db
-                .get_block_snarks_count(&block.state_hash())?
-                .unwrap_or_default();
-            let block_num_user_commands = db
-                .get_block_user_commands_count(&block.state_hash())?
-                .unwrap_or_default();
-            let block_num_internal_commands = db
-                .get_block_internal_commands_count(&block.state_hash())?
-                .unwrap_or_default();
-            Ok(Some(Block {
-                block: BlockWithoutCanonicity::new(
+        let state_hash = BlockHash::from(self.feetransfer.state_hash.clone());
+        let Some(summary) = loader::<BlockSummaryLoader>(ctx)
+            .load_one(state_hash.clone())
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // pruned blocks are served from their compact contents
+        let block = match db(ctx).get_pruned_block(&state_hash)? {
+            Some(pruned) => BlockWithoutCanonicity::from_pruned(
+                &pruned,
+                self.canonical,
+                summary.epoch_num_user_commands,
+                summary.total_num_user_commands,
+            ),
+            None => match loader::<BlockLoader>(ctx).load_one(state_hash).await? {
+                Some(block) => BlockWithoutCanonicity::new(
                     &block,
                     self.canonical,
-                    epoch_num_user_commands,
-                    total_num_user_commands,
+                    summary.epoch_num_user_commands,
+                    summary.total_num_user_commands,
                 ),
-                canonical: self.canonical,
-                epoch_num_blocks,
-                total_num_blocks,
-                block_num_snarks,
-                block_num_user_commands,
-                block_num_internal_commands,
-                num_unique_block_producers_last_n_blocks: None,
-            }))
-        } else {
-            Ok(None)
-        }
+                None => return Ok(None),
+            },
+        };
+        Ok(Some(Block {
+            block,
+            canonical: self.canonical,
+            epoch_num_blocks: summary.epoch_num_blocks,
+            total_num_blocks: summary.total_num_blocks,
+            block_num_snarks: summary.block_num_snarks,
+            block_num_user_commands: summary.block_num_user_commands,
+            block_num_internal_commands: summary.block_num_internal_commands,
+            num_unique_block_producers_last_n_blocks: None,
+        }))
     }
 }
 
@@ -149,6 +151,7 @@ pub struct FeetransferQueryRoot;
 
 #[Object]
 impl FeetransferQueryRoot {
+    #[graphql(complexity = "limit.saturating_mul(child_complexity)")]
     async fn feetransfers<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -156,241 +159,260 @@ impl FeetransferQueryRoot {
         sort_by: Option<FeetransferSortByInput>,
         #[graphql(default = 100)] limit: usize,
     ) -> Result<Vec<FeetransferWithMeta>> {
+        let mut page = Page::list(list_limit(ctx, limit)).with_deadline(ctx);
+        feetransfers_page(db(ctx), block_cache(ctx), query, sort_by, &mut page)?;
+        Ok(page.into_nodes())
+    }
+
+    /// Value fee transfers connection, paged by `first` & `after`
+    #[graphql(complexity = "first.saturating_mul(child_complexity)")]
+    async fn feetransfers_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<FeetransferQueryInput>,
+        sort_by: Option<FeetransferSortByInput>,
+        #[graphql(default = 100)] first: usize,
+        after: Option<String>,
+    ) -> Result<SortKeyConnection<FeetransferWithMeta>> {
         let db = db(ctx);
-        let epoch_num_internal_commands = db.get_internal_commands_epoch_count(None)?;
-        let total_num_internal_commands = db.get_internal_commands_total_count()?;
+        let total_count = match query {
+            None => Some(db.get_internal_commands_total_count()?),
+            Some(_) => None,
+        };
+        let mut page = Page::new(list_limit(ctx, first), after)?.with_deadline(ctx);
+        feetransfers_page(db, block_cache(ctx), query, sort_by, &mut page)?;
+        Ok(page.into_connection(total_count))
+    }
+}
 
-        //state_hash
-        if let Some(state_hash) = query
-            .as_ref()
-            .and_then(|f| f.block_state_hash.as_ref())
-            .and_then(|f| f.state_hash.clone())
-        {
-            return Ok(get_fee_transfers_for_state_hash(
-                db,
-                &query,
-                &state_hash.into(),
-                sort_by,
-                limit,
-                epoch_num_internal_commands,
-                total_num_internal_commands,
-            ));
-        }
+/// Collect the page of fee transfers matching the query
+fn feetransfers_page(
+    db: &Arc<IndexerStore>,
+    cache: &PrecomputedBlockCache,
+    query: Option<FeetransferQueryInput>,
+    sort_by: Option<FeetransferSortByInput>,
+    page: &mut Page<FeetransferWithMeta>,
+) -> Result<()> {
+    let epoch_num_internal_commands = db.get_internal_commands_epoch_count(None)?;
+    let total_num_internal_commands = db.get_internal_commands_total_count()?;
+
+    //state_hash
+    if let Some(state_hash) = query
+        .as_ref()
+        .and_then(|f| f.block_state_hash.as_ref())
+        .and_then(|f| f.state_hash.clone())
+    {
+        return page.extend(get_fee_transfers_for_state_hash(
+            db,
+            cache,
+            &query,
+            &state_hash.into(),
+            sort_by,
+            epoch_num_internal_commands,
+            total_num_internal_commands,
+        ));
+    }
 
-        // block height bounded query
-        if query.as_ref().map_or(false, |q| {
-            q.block_height_gt.is_some()
-                || q.block_height_gte.is_some()
-                || q.block_height_lt.is_some()
-                || q.block_height_lte.is_some()
-        }) {
-            let mut feetransfers = Vec::new();
-            let (min, max) = {
-                let FeetransferQueryInput {
-                    block_height_gt,
-                    block_height_gte,
-                    block_height_lt,
-                    block_height_lte,
-                    ..
-                } = query.as_ref().expect("query will contain a value");
-                let min_bound = match (*block_height_gte, *block_height_gt) {
-                    (Some(gte), Some(gt)) => std::cmp::max(gte, gt + 1),
-                    (Some(gte), None) => gte,
-                    (None, Some(gt)) => gt + 1,
-                    (None, None) => 1,
-                };
+    // block height bounded query
+    if query.as_ref().map_or(false, |q| {
+        q.block_height_gt.is_some()
+            || q.block_height_gte.is_some()
+            || q.block_height_lt.is_some()
+            || q.block_height_lte.is_some()
+    }) {
+        let (min, max) = {
+            let FeetransferQueryInput {
+                block_height_gt,
+                block_height_gte,
+                block_height_lt,
+                block_height_lte,
+                ..
+            } = query.as_ref().expect("query will contain a value");
+            let min_bound = match (*block_height_gte, *block_height_gt) {
+                (Some(gte), Some(gt)) => std::cmp::max(gte, gt + 1),
+                (Some(gte), None) => gte,
+                (None, Some(gt)) => gt + 1,
+                (None, None) => 1,
+            };
 
-                let max_bound = match (*block_height_lte, *block_height_lt) {
-                    (Some(lte), Some(lt)) => std::cmp::min(lte, lt - 1),
-                    (Some(lte), None) => lte,
-                    (None, Some(lt)) => lt - 1,
-                    (None, None) => db.get_best_block_height()?.unwrap(),
-                };
-                (min_bound, max_bound)
+            let max_bound = match (*block_height_lte, *block_height_lt) {
+                (Some(lte), Some(lt)) => std::cmp::min(lte, lt - 1),
+                (Some(lte), None) => lte,
+                (None, Some(lt)) => lt - 1,
+                (None, None) => db.get_best_block_height()?.unwrap(),
             };
+            (min_bound, max_bound)
+        };
 
-            let mut block_heights: Vec<u32> = (min..=max).collect();
-            let sort_by = sort_by.unwrap_or(FeetransferSortByInput::BlockHeightDesc);
-            if sort_by == FeetransferSortByInput::BlockHeightDesc {
-                block_heights.reverse()
+        let (start, direction) = match sort_by.unwrap_or(FeetransferSortByInput::BlockHeightDesc) {
+            FeetransferSortByInput::BlockHeightAsc => (to_be_bytes(min), Direction::Forward),
+            FeetransferSortByInput::BlockHeightDesc => {
+                (to_be_bytes(max.saturating_add(1)), Direction::Reverse)
+            }
+        };
+        let mode = IteratorMode::From(page.seek_group(&start), direction);
+        'outer: for (key, _) in db.blocks_height_iterator(mode).flatten() {
+            page.check_deadline()?;
+            let height = block_u32_prefix_from_key(&key)?;
+            if height < min || height > max {
+                break;
             }
 
-            'outer: for height in block_heights {
-                for state_hash in db.get_blocks_at_height(height)?.iter() {
-                    let canonical = get_block_canonicity(db, &state_hash.0);
-                    let block = db
-                        .get_block(state_hash)?
-                        .with_context(|| format!("block missing from store {state_hash}"))
-                        .unwrap()
-                        .0;
-                    for internal_cmd in InternalCommandWithData::from_precomputed(&block) {
-                        let ft = Feetransfer::from((
-                            internal_cmd,
-                            epoch_num_internal_commands,
-                            total_num_internal_commands,
-                        ));
-                        let feetransfer_with_meta = FeetransferWithMeta {
-                            canonical,
-                            feetransfer: ft,
-                            block: Some(block.clone()),
-                        };
-                        if query
-                            .as_ref()
-                            .map_or(true, |q| q.matches(&feetransfer_with_meta))
-                        {
-                            feetransfers.push(feetransfer_with_meta);
-                            if feetransfers.len() == limit {
-                                break 'outer;
-                            }
-                        }
-                    }
+            let state_hash = block_state_hash_from_key(&key)?;
+            let canonical = get_block_canonicity(db, &state_hash.0);
+            let block = cache
+                .get(db, &state_hash)?
+                .with_context(|| format!("block missing from store {state_hash}"))?;
+            for (n, internal_cmd) in InternalCommandWithData::from_precomputed(&block)
+                .into_iter()
+                .enumerate()
+            {
+                let ft = Feetransfer::from((
+                    internal_cmd,
+                    epoch_num_internal_commands,
+                    total_num_internal_commands,
+                ));
+                let feetransfer_with_meta = FeetransferWithMeta {
+                    canonical,
+                    feetransfer: ft,
+                };
+                if query
+                    .as_ref()
+                    .map_or(true, |q| q.matches(&feetransfer_with_meta))
+                    && page.push_nth(&key, n as u32, feetransfer_with_meta)
+                {
+                    break 'outer;
                 }
             }
-            return Ok(feetransfers);
         }
+        return Ok(());
+    }
 
-        // recipient
-        if let Some(recipient) = query.as_ref().and_then(|q| q.recipient.clone()) {
-            let mut fee_transfers: Vec<FeetransferWithMeta> = db
-                .get_internal_commands_public_key(&recipient.into())?
-                .into_iter()
-                .map(|internal_command| {
-                    let ft = Feetransfer::from((
-                        internal_command,
-                        epoch_num_internal_commands,
-                        total_num_internal_commands,
-                    ));
-                    let state_hash = ft.state_hash.clone();
-                    let pcb = db
-                        .get_block(&BlockHash::from(state_hash.clone()))
-                        .unwrap()
-                        .unwrap()
-                        .0;
-                    let canonical = get_block_canonicity(db, &state_hash);
-                    FeetransferWithMeta {
-                        canonical,
-                        feetransfer: ft,
-                        block: Some(pcb),
-                    }
-                })
-                .filter(|ft| query.as_ref().map_or(true, |q| q.matches(ft)))
-                .collect();
-            fee_transfers.truncate(limit);
-            return Ok(fee_transfers);
+    // recipient
+    if let Some(recipient) = query.as_ref().and_then(|q| q.recipient.clone()) {
+        let mut internal_commands: Vec<FeetransferWithMeta> = db
+            .get_internal_commands_public_key(&recipient.into())?
+            .into_iter()
+            .map(|internal_command| {
+                let ft = Feetransfer::from((
+                    internal_command,
+                    epoch_num_internal_commands,
+                    total_num_internal_commands,
+                ));
+                let canonical = get_block_canonicity(db, &ft.state_hash);
+                FeetransferWithMeta {
+                    canonical,
+                    feetransfer: ft,
+                }
+            })
+            .filter(|ft| query.as_ref().map_or(true, |q| q.matches(ft)))
+            .collect();
+
+        // stable, so commands of the same block keep their order
+        match sort_by.unwrap_or(FeetransferSortByInput::BlockHeightDesc) {
+            FeetransferSortByInput::BlockHeightAsc => internal_commands
+                .sort_by(|a, b| a.feetransfer.block_height.cmp(&b.feetransfer.block_height)),
+            FeetransferSortByInput::BlockHeightDesc => internal_commands
+                .sort_by(|a, b| b.feetransfer.block_height.cmp(&a.feetransfer.block_height)),
         }
-        get_fee_transfers(
-            db,
-            query,
-            sort_by,
-            limit,
-            epoch_num_internal_commands,
-            total_num_internal_commands,
-        )
+        return page.extend(internal_commands);
     }
+    get_fee_transfers(
+        db,
+        query,
+        sort_by,
+        page,
+        epoch_num_internal_commands,
+        total_num_internal_commands,
+    )
 }
 
 fn get_fee_transfers(
     db: &Arc<IndexerStore>,
     query: Option<FeetransferQueryInput>,
     sort_by: Option<FeetransferSortByInput>,
-    limit: usize,
+    page: &mut Page<FeetransferWithMeta>,
     epoch_num_internal_commands: u32,
     total_num_internal_commands: u32,
-) -> Result<Vec<FeetransferWithMeta>> {
-    let mut fee_transfers = Vec::new();
-    let mode = if let Some(FeetransferSortByInput::BlockHeightAsc) = sort_by {
-        speedb::IteratorMode::Start
+) -> Result<()> {
+    let (start, direction) = if let Some(FeetransferSortByInput::BlockHeightAsc) = sort_by {
+        (to_be_bytes(0), Direction::Forward)
     } else {
-        speedb::IteratorMode::End
+        (to_be_bytes(u32::MAX), Direction::Reverse)
     };
+    let mode = IteratorMode::From(page.seek(&start), direction);
 
-    for (_, value) in db.internal_commands_global_slot_interator(mode).flatten() {
+    for (key, value) in db.internal_commands_global_slot_interator(mode).flatten() {
+        page.check_deadline()?;
         let internal_command = serde_json::from_slice::<InternalCommandWithData>(&value)?;
         let ft = Feetransfer::from((
             internal_command,
             epoch_num_internal_commands,
             total_num_internal_commands,
         ));
-        let state_hash = ft.state_hash.clone();
-        let canonical = get_block_canonicity(db, &state_hash);
-        let pcb = db
-            .get_block(&BlockHash::from(state_hash.clone()))
-            .unwrap()
-            .unwrap()
-            .0;
+        let canonical = get_block_canonicity(db, &ft.state_hash);
         let feetransfer_with_meta = FeetransferWithMeta {
             canonical,
             feetransfer: ft,
-            block: Some(pcb),
         };
 
         if query
             .as_ref()
             .map_or(true, |q| q.matches(&feetransfer_with_meta))
+            && page.push(&key, feetransfer_with_meta)
         {
-            fee_transfers.push(feetransfer_with_meta);
-        }
-
-        if fee_transfers.len() >= limit {
             break;
         }
     }
-    Ok(fee_transfers)
+    Ok(())
 }
 
 fn get_fee_transfers_for_state_hash(
     db: &Arc<IndexerStore>,
+    cache: &PrecomputedBlockCache,
     query: &Option<FeetransferQueryInput>,
     state_hash: &BlockHash,
     sort_by: Option<FeetransferSortByInput>,
-    limit: usize,
     epoch_num_internal_commands: u32,
     total_num_internal_commands: u32,
 ) -> Vec<FeetransferWithMeta> {
-    let pcb = match db.get_block(state_hash) {
-        Ok(Some(pcb)) => pcb.0,
-        _ => return vec![],
+    let internal_commands = match db.get_pruned_block(state_hash) {
+        Ok(Some(pruned)) => pruned.internal_commands,
+        _ => match cache.get(db, state_hash) {
+            Ok(Some(pcb)) => InternalCommandWithData::from_precomputed(&pcb),
+            _ => return vec![],
+        },
     };
     let canonical = match db.get_block_canonicity(state_hash) {
         Ok(Some(canonicity)) => matches!(canonicity, Canonicity::Canonical),
         _ => false,
     };
-    match db.get_internal_commands(state_hash) {
-        Ok(internal_commands) => {
-            let mut internal_commands: Vec<FeetransferWithMeta> = internal_commands
-                .into_iter()
-                .map(|ft| FeetransferWithMeta {
-                    canonical,
-                    feetransfer: Feetransfer::from((
-                        ft,
-                        epoch_num_internal_commands,
-                        total_num_internal_commands,
-                    )),
-                    block: Some(pcb.clone()),
-                })
-                .filter(|ft| query.as_ref().map_or(true, |q| q.matches(ft)))
-                .collect();
-
-            if let Some(sort_by) = sort_by {
-                match sort_by {
-                    FeetransferSortByInput::BlockHeightAsc => {
-                        internal_commands.sort_by(|a, b| {
-                            a.feetransfer.block_height.cmp(&b.feetransfer.block_height)
-                        });
-                    }
-                    FeetransferSortByInput::BlockHeightDesc => {
-                        internal_commands.sort_by(|a, b| {
-                            b.feetransfer.block_height.cmp(&a.feetransfer.block_height)
-                        });
-                    }
-                }
+    let mut internal_commands: Vec<FeetransferWithMeta> = internal_commands
+        .into_iter()
+        .map(|ft| FeetransferWithMeta {
+            canonical,
+            feetransfer: Feetransfer::from((
+                ft,
+                epoch_num_internal_commands,
+                total_num_internal_commands,
+            )),
+        })
+        .filter(|ft| query.as_ref().map_or(true, |q| q.matches(ft)))
+        .collect();
+
+    if let Some(sort_by) = sort_by {
+        match sort_by {
+            FeetransferSortByInput::BlockHeightAsc => {
+                internal_commands
+                    .sort_by(|a, b| a.feetransfer.block_height.cmp(&b.feetransfer.block_height));
+            }
+            FeetransferSortByInput::BlockHeightDesc => {
+                internal_commands
+                    .sort_by(|a, b| b.feetransfer.block_height.cmp(&a.feetransfer.block_height));
             }
-
-            internal_commands.truncate(limit);
-            internal_commands
         }
-        Err(_) => vec![],
     }
+    internal_commands
 }
 
 impl From<(InternalCommandWithData, u32, u32)> for Feetransfer {
@@ -457,8 +479,7 @@ impl FeetransferQueryInput {
             }
         }
 
-        let pcb = ft.block.as_ref().expect("block will exist");
-        let blockchain_length = pcb.blockchain_length();
+        let blockchain_length = ft.feetransfer.block_height;
 
         // block_height_gt(e) & block_height_lt(e)
         if let Some(height) = block_height_gt {
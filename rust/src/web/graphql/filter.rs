@@ -0,0 +1,333 @@
+//! Evaluation of the MinaExplorer-compatible query inputs
+//!
+//! A query input is a tree of `{field}_{operator}` conditions combined with
+//! `and` & `or`. The input is evaluated against the serialized fields of the
+//! node, so every operator behaves the same on every field. A condition on a
+//! field the node doesn't have is an error rather than a silent mismatch.
+
+use async_graphql::{Error, InputType, Result};
+use chrono::DateTime;
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+
+/// Node which can be filtered by a query input
+pub trait Filterable {
+    /// GraphQL type name, used in errors
+    const NAME: &'static str;
+
+    /// Fields of the node as seen by the filter
+    fn filter_fields(&self) -> Value;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    In,
+    Nin,
+    Exists,
+}
+
+/// Operator suffixes, longest first
+const OPERATORS: [(&str, Operator); 8] = [
+    ("_exists", Operator::Exists),
+    ("_gte", Operator::Gte),
+    ("_lte", Operator::Lte),
+    ("_nin", Operator::Nin),
+    ("_ne", Operator::Ne),
+    ("_gt", Operator::Gt),
+    ("_lt", Operator::Lt),
+    ("_in", Operator::In),
+];
+
+/// Compiled query input
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    conditions: Map<String, Value>,
+}
+
+impl Filter {
+    /// Compile the query input, `None` matches everything
+    pub fn new<T: InputType>(input: &T) -> Self {
+        match normalize(input.to_value().into_json().unwrap_or_default()) {
+            Value::Object(conditions) => Self { conditions },
+            _ => Self::default(),
+        }
+    }
+
+    /// Remove the condition on `field`, for conditions handled by the
+    /// resolver itself
+    pub fn without(mut self, field: &str) -> Self {
+        self.conditions.remove(field);
+        self
+    }
+
+    /// Fields with a condition
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.conditions.keys().map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    /// Whether some condition, possibly within `and`/`or`, is on the nested
+    /// field at `path`
+    pub fn has_condition_on(&self, path: &[&str]) -> bool {
+        has_condition_on(&self.conditions, path)
+    }
+
+    /// Whether the node satisfies every condition
+    pub fn matches<T: Filterable>(&self, node: &T) -> Result<bool> {
+        if self.is_empty() {
+            return Ok(true);
+        }
+        eval(&self.conditions, &node.filter_fields(), T::NAME, "")
+    }
+}
+
+/// snake_case keys & no null conditions
+fn normalize(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (snake_case(&key), normalize(value)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(normalize).collect()),
+        value => value,
+    }
+}
+
+fn snake_case(key: &str) -> String {
+    let mut snake = String::with_capacity(key.len() + 4);
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            snake.push('_');
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn eval(conditions: &Map<String, Value>, node: &Value, name: &str, path: &str) -> Result<bool> {
+    let Value::Object(fields) = node else {
+        return Err(Error::new(format!(
+            "Filter {path} on {name} expects an object"
+        )));
+    };
+
+    // every condition is checked so unsupported ones are always reported
+    let mut matches = true;
+    for (key, operand) in conditions {
+        let key_path = match path {
+            "" => key.to_owned(),
+            _ => format!("{path}.{key}"),
+        };
+        matches &= match key.as_str() {
+            "and" => {
+                let mut all = true;
+                for filter in operands(operand) {
+                    all &= eval(filter, node, name, path)?;
+                }
+                all
+            }
+            "or" => {
+                let filters: Vec<_> = operands(operand).collect();
+                let mut any = filters.is_empty();
+                for filter in filters {
+                    any |= eval(filter, node, name, path)?;
+                }
+                any
+            }
+            _ => {
+                let (field, op) = split_operator(key, fields);
+                let value = fields.get(field).ok_or_else(|| {
+                    Error::new(format!("Filter {key_path} is not supported on {name}"))
+                })?;
+                compare(value, op, operand, name, &key_path)?
+            }
+        };
+    }
+    Ok(matches)
+}
+
+fn has_condition_on(conditions: &Map<String, Value>, path: &[&str]) -> bool {
+    let Some((field, nested)) = path.split_first() else {
+        return true;
+    };
+    conditions.iter().any(|(key, operand)| match key.as_str() {
+        "and" | "or" => operands(operand).any(|filter| has_condition_on(filter, path)),
+        _ if key == field => {
+            nested.is_empty() || operands(operand).any(|filter| has_condition_on(filter, nested))
+        }
+        _ => {
+            nested.is_empty()
+                && OPERATORS
+                    .iter()
+                    .any(|(suffix, _)| key.strip_suffix(suffix) == Some(field))
+        }
+    })
+}
+
+/// Nested filters of an `and`/`or`/list condition
+fn operands(operand: &Value) -> impl Iterator<Item = &Map<String, Value>> {
+    let filters: Vec<_> = match operand {
+        Value::Array(values) => values.iter().filter_map(Value::as_object).collect(),
+        Value::Object(filter) => vec![filter],
+        _ => vec![],
+    };
+    filters.into_iter()
+}
+
+/// Field & operator of the key, a field of the node takes precedence
+fn split_operator<'a>(key: &'a str, fields: &Map<String, Value>) -> (&'a str, Operator) {
+    if !fields.contains_key(key) {
+        for (suffix, op) in OPERATORS {
+            if let Some(field) = key.strip_suffix(suffix) {
+                if !field.is_empty() {
+                    return (field, op);
+                }
+            }
+        }
+    }
+    (key, Operator::Eq)
+}
+
+fn compare(value: &Value, op: Operator, operand: &Value, name: &str, path: &str) -> Result<bool> {
+    match op {
+        Operator::Eq => equals(value, operand, name, path),
+        Operator::Ne => Ok(!equals(value, operand, name, path)?),
+        Operator::In | Operator::Nin => {
+            let Value::Array(candidates) = operand else {
+                return Err(Error::new(format!(
+                    "Filter {path} on {name} expects a list"
+                )));
+            };
+            let mut any = false;
+            for candidate in candidates.iter().filter(|c| !c.is_null()) {
+                any |= equals(value, candidate, name, path)?;
+            }
+            Ok(any == (op == Operator::In))
+        }
+        Operator::Exists => match operand {
+            Value::Bool(exists) => Ok(exists_value(value) == *exists),
+            _ => Err(Error::new(format!(
+                "Filter {path} on {name} expects a boolean"
+            ))),
+        },
+        Operator::Gt | Operator::Gte | Operator::Lt | Operator::Lte => {
+            if value.is_object() || value.is_array() {
+                return Err(Error::new(format!(
+                    "Filter {path} on {name} compares a scalar field, not an object or list"
+                )));
+            }
+            if value.is_null() {
+                return Ok(false);
+            }
+            let Some(ordering) = order(value, operand) else {
+                return Err(Error::new(format!(
+                    "Filter {path} on {name} cannot compare {value} with {operand}"
+                )));
+            };
+            Ok(match op {
+                Operator::Gt => ordering.is_gt(),
+                Operator::Gte => ordering.is_ge(),
+                Operator::Lt => ordering.is_lt(),
+                _ => ordering.is_le(),
+            })
+        }
+    }
+}
+
+fn exists_value(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(values) => !values.is_empty(),
+        _ => true,
+    }
+}
+
+/// Equality of the field with the operand
+/// - nested objects are filters on the field's fields
+/// - a list field matches if some element matches each filter
+fn equals(value: &Value, operand: &Value, name: &str, path: &str) -> Result<bool> {
+    match (value, operand) {
+        (Value::Object(_), Value::Object(filter)) => eval(filter, value, name, path),
+        (Value::Array(elements), Value::Object(_) | Value::Array(_)) => {
+            let mut all = true;
+            for filter in operands(operand) {
+                let mut any = false;
+                for element in elements {
+                    any |= eval(filter, element, name, path)?;
+                }
+                all &= any;
+            }
+            Ok(all)
+        }
+        (Value::Object(_) | Value::Array(_), _) | (_, Value::Object(_) | Value::Array(_)) => {
+            Err(Error::new(format!(
+                "Filter {path} on {name} cannot compare {value} with {operand}"
+            )))
+        }
+        (Value::Null, _) => Ok(false),
+        (Value::Bool(a), Value::Bool(b)) => Ok(a == b),
+        _ => Ok(order(value, operand) == Some(Ordering::Equal)),
+    }
+}
+
+/// Order of scalars
+/// - numbers & numeric strings (`Long`) numerically
+/// - RFC 3339 strings (`DateTime`) chronologically
+/// - other strings lexicographically
+fn order(value: &Value, operand: &Value) -> Option<Ordering> {
+    match (value, operand) {
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::String(a), Value::String(b)) => {
+            if let (Ok(a), Ok(b)) = (a.parse::<i128>(), b.parse::<i128>()) {
+                return Some(a.cmp(&b));
+            }
+            if let (Ok(a), Ok(b)) = (
+                DateTime::parse_from_rfc3339(a),
+                DateTime::parse_from_rfc3339(b),
+            ) {
+                return Some(a.cmp(&b));
+            }
+            Some(a.cmp(b))
+        }
+        (Value::Number(_) | Value::String(_), Value::Number(_) | Value::String(_)) => {
+            match (integer(value), integer(operand)) {
+                (Some(a), Some(b)) => Some(a.cmp(&b)),
+                _ => float(value)?.partial_cmp(&float(operand)?),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn integer(value: &Value) -> Option<i128> {
+    match value {
+        Value::Number(n) => n
+            .as_u64()
+            .map(i128::from)
+            .or_else(|| n.as_i64().map(i128::from)),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
@@ -6,9 +6,9 @@ pub struct TransactionSourceQueryInput {
     pub and: Option<Vec<TransactionSourceQueryInput>>,
     pub or: Option<Vec<TransactionSourceQueryInput>>,
     pub public_key: Option<String>,
-    // pub public_key_ne: Option<String>,
-    // pub public_key_in: Option<Vec<Option<String>>>,
-    // pub public_key_nin: Option<Vec<Option<String>>>,
+    pub public_key_ne: Option<String>,
+    pub public_key_in: Option<Vec<Option<String>>>,
+    pub public_key_nin: Option<Vec<Option<String>>>,
 }
 
 #[derive(InputObject)]
@@ -16,9 +16,9 @@ pub struct TransactionFromAccountQueryInput {
     pub and: Option<Vec<TransactionFromAccountQueryInput>>,
     pub or: Option<Vec<TransactionFromAccountQueryInput>>,
     pub token: Option<u64>,
-    // pub token_ne: Option<u64>,
-    // pub token_nin: Option<Vec<Option<u64>>>,
-    // pub token_in: Option<Vec<Option<u64>>>,
+    pub token_ne: Option<u64>,
+    pub token_nin: Option<Vec<Option<u64>>>,
+    pub token_in: Option<Vec<Option<u64>>>,
 }
 
 #[derive(InputObject)]
@@ -585,6 +585,10 @@ pub struct BlockQueryInput {
 
     #[graphql(name = "blockHeight_lte")]
     pub block_height_lte: Option<u32>,
+
+    pub transactions: Option<BlockTransactionQueryInput>,
+    pub snark_jobs: Option<Vec<Option<BlockSnarkJobQueryInput>>>,
+    pub winner_account: Option<BlockWinnerAccountQueryInput>,
 }
 
 #[derive(InputObject)]
@@ -877,94 +881,98 @@ pub struct TransactionQueryInput {
     // boolean operators
     pub and: Option<Vec<TransactionQueryInput>>,
     pub or: Option<Vec<TransactionQueryInput>>,
-    // pub memo_exists: Option<bool>,
-    // pub from_ne: Option<String>,
-    // pub fee_token_in: Option<Vec<Option<u64>>>,
-    // pub token_nin: Option<Vec<Option<u64>>>,
-    // pub token_gte: Option<u64>,
-    // pub nonce_nin: Option<Vec<Option<u64>>>,
-    // pub to_nin: Option<Vec<Option<String>>>,
-    // pub date_time_nin: Option<Vec<Option<DateTime>>>,
-    // pub fee_token_nin: Option<Vec<Option<u64>>>,
-    // pub token_ne: Option<u64>,
-    // pub block_height_nin: Option<Vec<Option<u32>>>,
-    // pub from_nin: Option<Vec<Option<String>>>,
-    // pub to_in: Option<Vec<Option<String>>>,
-    // pub memo_in: Option<Vec<Option<String>>>,
-    // pub fee_nin: Option<Vec<Option<u64>>>,
-    // pub kind_in: Option<Vec<Option<String>>>,
-    // pub date_time_in: Option<Vec<Option<DateTime>>>,
-    // pub amount_in: Option<Vec<Option<u64>>>,
-    // pub memo_nin: Option<Vec<Option<String>>>,
-    // pub nonce_in: Option<Vec<Option<u32>>>,
-    // pub block_height_in: Option<Vec<Option<u32>>>,
-    // pub token_in: Option<Vec<Option<i64>>>,
-    // pub fee_in: Option<Vec<Option<u64>>>,
-    // pub canonical_exists: Option<bool>,
-    // pub memo_lt: Option<String>,
-    // pub memo_gte: Option<String>,
-    // pub to_account_exists: Option<bool>,
-    // pub kind_lte: Option<String>,
-    // pub token_lt: Option<i64>,
-    // pub fee_exists: Option<bool>,
-    // pub memo_gt: Option<String>,
-    // pub canonical_ne: Option<bool>,
-    // pub hash_gt: Option<String>,
-    // pub receiver_exists: Option<bool>,
-    // pub date_time_exists: Option<bool>,
-    // pub fee_token_gte: Option<u64>,
+    pub memo_exists: Option<bool>,
+    pub from_ne: Option<String>,
+    pub fee_token_in: Option<Vec<Option<u64>>>,
+    pub token_nin: Option<Vec<Option<u64>>>,
+    pub token_gte: Option<u64>,
+    pub nonce_nin: Option<Vec<Option<u32>>>,
+    pub to_nin: Option<Vec<Option<String>>>,
+    pub date_time_nin: Option<Vec<Option<DateTime>>>,
+    pub fee_token_nin: Option<Vec<Option<u64>>>,
+    pub token_ne: Option<u64>,
+    #[graphql(name = "blockHeight_nin")]
+    pub block_height_nin: Option<Vec<Option<u32>>>,
+    pub from_nin: Option<Vec<Option<String>>>,
+    pub to_in: Option<Vec<Option<String>>>,
+    pub memo_in: Option<Vec<Option<String>>>,
+    pub fee_nin: Option<Vec<Option<u64>>>,
+    pub kind_in: Option<Vec<Option<String>>>,
+    pub date_time_in: Option<Vec<Option<DateTime>>>,
+    pub amount_in: Option<Vec<Option<u64>>>,
+    pub memo_nin: Option<Vec<Option<String>>>,
+    pub nonce_in: Option<Vec<Option<u32>>>,
+    #[graphql(name = "blockHeight_in")]
+    pub block_height_in: Option<Vec<Option<u32>>>,
+    pub token_in: Option<Vec<Option<u64>>>,
+    pub fee_in: Option<Vec<Option<u64>>>,
+    pub canonical_exists: Option<bool>,
+    pub memo_lt: Option<String>,
+    pub memo_gte: Option<String>,
+    pub to_account_exists: Option<bool>,
+    pub kind_lte: Option<String>,
+    pub token_lt: Option<u64>,
+    pub fee_exists: Option<bool>,
+    pub memo_gt: Option<String>,
+    pub canonical_ne: Option<bool>,
+    pub hash_gt: Option<String>,
+    pub receiver_exists: Option<bool>,
+    pub date_time_exists: Option<bool>,
+    pub fee_token_gte: Option<u64>,
     // pub id_in: Option<Vec<Option<String>>>,
-    // pub is_delegation_exists: Option<bool>,
-    // pub date_time_ne: Option<DateTime>,
-    // pub kind_gt: Option<String>,
-    // pub amount_ne: Option<u64>,
-    // pub to_gte: Option<String>,
-    // pub fee_payer_exists: Option<bool>,
-    // pub kind_lt: Option<String>,
+    pub is_delegation_exists: Option<bool>,
+    pub date_time_ne: Option<DateTime>,
+    pub kind_gt: Option<String>,
+    pub amount_ne: Option<u64>,
+    pub to_gte: Option<String>,
+    pub fee_payer_exists: Option<bool>,
+    pub kind_lt: Option<String>,
     // pub id_lt: Option<String>,
-    // pub hash_ne: Option<String>,
-    // pub block_height_exists: Option<bool>,
-    // pub fee_token_lt: Option<u64>,
-    // pub hash_gte: Option<String>,
-    // pub to_exists: Option<bool>,
-    // pub hash_lt: Option<String>,
-    // pub hash_exists: Option<bool>,
+    pub hash_ne: Option<String>,
+    #[graphql(name = "blockHeight_exists")]
+    pub block_height_exists: Option<bool>,
+    pub fee_token_lt: Option<u64>,
+    pub hash_gte: Option<String>,
+    pub to_exists: Option<bool>,
+    pub hash_lt: Option<String>,
+    pub hash_exists: Option<bool>,
     // pub id_gte: Option<String>,
-    // pub kind_exists: Option<bool>,
-    // pub fee_ne: Option<u64>,
-    // pub memo_ne: Option<String>,
-    // pub nonce_ne: Option<u32>,
-    // pub from_in: Option<Vec<Option<String>>>,
+    pub kind_exists: Option<bool>,
+    pub fee_ne: Option<u64>,
+    pub memo_ne: Option<String>,
+    pub nonce_ne: Option<u32>,
+    pub from_in: Option<Vec<Option<String>>>,
     // pub id_ne: Option<String>,
-    // pub amount_nin: Option<Vec<Option<u64>>>,
-    // pub kind_gte: Option<String>,
-    // pub to_gt: Option<String>,
-    // pub kind_ne: Option<String>,
-    // pub token_exists: Option<bool>,
+    pub amount_nin: Option<Vec<Option<u64>>>,
+    pub kind_gte: Option<String>,
+    pub to_gt: Option<String>,
+    pub kind_ne: Option<String>,
+    pub token_exists: Option<bool>,
     // pub id_nin: Option<Vec<Option<String>>>,
-    // pub fee_token_ne: Option<u64>,
-    // pub block_exists: Option<bool>,
-    // pub kind_nin: Option<Vec<Option<String>>>,
-    // pub to_ne: Option<String>,
+    pub fee_token_ne: Option<u64>,
+    pub block_exists: Option<bool>,
+    pub kind_nin: Option<Vec<Option<String>>>,
+    pub to_ne: Option<String>,
     // pub id_gt: Option<String>,
-    // pub block_height_ne: Option<u32>,
-    // pub hash_nin: Option<Vec<Option<String>>>,
-    // pub to_lte: Option<String>,
-    // pub fee_token_exists: Option<bool>,
-    // pub fee_token_gt: Option<u64>,
-    // pub token_gt: Option<u64>,
-    // pub source_exists: Option<bool>,
-    // pub hash_lte: Option<String>,
+    #[graphql(name = "blockHeight_ne")]
+    pub block_height_ne: Option<u32>,
+    pub hash_nin: Option<Vec<Option<String>>>,
+    pub to_lte: Option<String>,
+    pub fee_token_exists: Option<bool>,
+    pub fee_token_gt: Option<u64>,
+    pub token_gt: Option<u64>,
+    pub source_exists: Option<bool>,
+    pub hash_lte: Option<String>,
     // pub id_lte: Option<String>,
-    // pub hash_in: Option<Vec<Option<String>>>,
-    // pub nonce_exists: Option<bool>,
-    // pub fee_token_lte: Option<u64>,
-    // pub token_lte: Option<u64>,
-    // pub is_delegation_ne: Option<bool>,
-    // pub memo_lte: Option<String>,
+    pub hash_in: Option<Vec<Option<String>>>,
+    pub nonce_exists: Option<bool>,
+    pub fee_token_lte: Option<u64>,
+    pub token_lte: Option<u64>,
+    pub is_delegation_ne: Option<bool>,
+    pub memo_lte: Option<String>,
     // pub id_exists: Option<bool>,
-    // pub amount_exists: Option<bool>,
-    // pub to_lt: Option<String>,
+    pub amount_exists: Option<bool>,
+    pub to_lt: Option<String>,
 }
 
 #[derive(InputObject)]
@@ -972,9 +980,9 @@ pub struct TransactionReceiverQueryInput {
     pub public_key: Option<String>,
     pub and: Option<Vec<TransactionReceiverQueryInput>>,
     pub or: Option<Vec<TransactionReceiverQueryInput>>,
-    // pub public_key_ne: Option<String>,
-    // pub public_key_in: Option<Vec<Option<String>>>,
-    // pub public_key_nin: Option<Vec<Option<String>>>,
+    pub public_key_ne: Option<String>,
+    pub public_key_in: Option<Vec<Option<String>>>,
+    pub public_key_nin: Option<Vec<Option<String>>>,
 }
 
 #[derive(InputObject)]
@@ -982,9 +990,9 @@ pub struct TransactionToAccountQueryInput {
     pub token: Option<u64>,
     pub and: Option<Vec<TransactionToAccountQueryInput>>,
     pub or: Option<Vec<TransactionToAccountQueryInput>>,
-    // pub token_ne: Option<u64>,
-    // pub token_in: Option<Vec<Option<u64>>>,
-    // pub token_nin: Option<Vec<Option<u64>>>,
+    pub token_ne: Option<u64>,
+    pub token_in: Option<Vec<Option<u64>>>,
+    pub token_nin: Option<Vec<Option<u64>>>,
 }
 
 #[derive(InputObject)]
@@ -992,7 +1000,7 @@ pub struct TransactionFeePayerQueryInput {
     pub token: Option<u64>,
     pub and: Option<Vec<TransactionFeePayerQueryInput>>,
     pub or: Option<Vec<TransactionFeePayerQueryInput>>,
-    // pub token_ne: Option<u64>,
-    // pub token_in: Option<Vec<Option<u64>>>,
-    // pub token_nin: Option<Vec<Option<u64>>>,
+    pub token_ne: Option<u64>,
+    pub token_in: Option<Vec<Option<u64>>>,
+    pub token_nin: Option<Vec<Option<u64>>>,
 }
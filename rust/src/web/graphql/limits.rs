@@ -0,0 +1,136 @@
+//! Limits protecting the GraphQL endpoint from expensive queries
+//!
+//! Queries deeper than `max_depth` or costlier than `max_complexity` are
+//! rejected during validation. The cost of each query is reported in the
+//! `analyzer` response extension. List results are capped at `max_list_len`
+//! & execution is aborted after `timeout`.
+//!
+//! Resolvers iterate the store synchronously, so they can't be interrupted
+//! by the timeout. Instead each request carries a [Deadline] which resolvers
+//! check on each iterated entry.
+
+use async_graphql::{
+    async_trait::async_trait,
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextExecute, NextPrepareRequest},
+    Context, Request, Response, ServerError, ServerResult,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+pub const DEFAULT_MAX_DEPTH: usize = 16;
+pub const DEFAULT_MAX_COMPLEXITY: usize = 25_000;
+pub const DEFAULT_MAX_LIST_LEN: usize = 1_000;
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Cost of a field which reads a full precomputed block from the store
+pub const BLOCK_READ_COST: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphQLLimits {
+    /// Max nesting depth of a query
+    pub max_depth: usize,
+
+    /// Max computed cost of a query
+    pub max_complexity: usize,
+
+    /// Max number of results returned by a list or connection field
+    pub max_list_len: usize,
+
+    /// Max execution time of a query
+    pub timeout: Duration,
+}
+
+impl Default for GraphQLLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_complexity: DEFAULT_MAX_COMPLEXITY,
+            max_list_len: DEFAULT_MAX_LIST_LEN,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+        }
+    }
+}
+
+impl GraphQLLimits {
+    /// Cap the requested number of results
+    pub fn cap(&self, limit: usize) -> usize {
+        limit.min(self.max_list_len)
+    }
+}
+
+/// Cap the requested number of results of a list field
+pub(crate) fn list_limit(ctx: &Context, limit: usize) -> usize {
+    ctx.data::<GraphQLLimits>()
+        .map_or(limit, |limits| limits.cap(limit))
+}
+
+/// Point in time after which the request's resolvers fail
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline {
+    at: Instant,
+    timeout: Duration,
+}
+
+impl Deadline {
+    pub fn after(timeout: Duration) -> Self {
+        Self {
+            at: Instant::now() + timeout,
+            timeout,
+        }
+    }
+
+    /// Error once the deadline has passed
+    pub fn check(&self) -> async_graphql::Result<()> {
+        if Instant::now() >= self.at {
+            return Err(timed_out(self.timeout).into());
+        }
+        Ok(())
+    }
+}
+
+/// Check the request's deadline, if any
+pub(crate) fn check_deadline(ctx: &Context) -> async_graphql::Result<()> {
+    ctx.data_opt::<Deadline>().map_or(Ok(()), Deadline::check)
+}
+
+fn timed_out(timeout: Duration) -> String {
+    format!("Query timed out after {}ms", timeout.as_millis())
+}
+
+/// Sets each request's [Deadline] & aborts query execution after the
+/// configured timeout
+pub struct Timeout(pub Duration);
+
+impl ExtensionFactory for Timeout {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(TimeoutExtension(self.0))
+    }
+}
+
+struct TimeoutExtension(Duration);
+
+#[async_trait]
+impl Extension for TimeoutExtension {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        next.run(ctx, request.data(Deadline::after(self.0))).await
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        match tokio::time::timeout(self.0, next.run(ctx, operation_name)).await {
+            Ok(response) => response,
+            Err(_) => Response::from_errors(vec![ServerError::new(timed_out(self.0), None)]),
+        }
+    }
+}
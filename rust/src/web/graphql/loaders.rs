@@ -0,0 +1,255 @@
+//! Batched loaders for nested GraphQL resolvers
+//!
+//! Sibling rows of a list resolve their nested fields concurrently, so each
+//! [DataLoader] collects their keys into a single batch, reading every key
+//! once. Deserialized precomputed blocks are additionally kept in a bounded
+//! LRU [PrecomputedBlockCache] shared across requests.
+
+use super::get_block_canonicity;
+use crate::{
+    block::{precomputed::PrecomputedBlock, store::BlockStore, BlockHash},
+    canonicity::store::CanonicityStore,
+    command::{internal::store::InternalCommandStore, store::UserCommandStore},
+    ledger::public_key::PublicKey,
+    snark_work::store::SnarkStore,
+    store::{username::UsernameStore, IndexerStore},
+};
+use async_graphql::{
+    dataloader::{DataLoader, Loader},
+    Context, Error, Result,
+};
+use lru::LruCache;
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+/// Number of deserialized precomputed blocks kept in memory
+pub const BLOCK_CACHE_CAPACITY: usize = 256;
+
+/// Bounded LRU cache of deserialized precomputed blocks
+pub struct PrecomputedBlockCache(Mutex<LruCache<BlockHash, Arc<PrecomputedBlock>>>);
+
+impl Default for PrecomputedBlockCache {
+    fn default() -> Self {
+        Self::new(BLOCK_CACHE_CAPACITY)
+    }
+}
+
+impl PrecomputedBlockCache {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self(Mutex::new(LruCache::new(capacity)))
+    }
+
+    /// Get the block from the cache, reading it from the store on a miss.
+    /// Pruned blocks are re-hydrated, so callers only needing their compact
+    /// contents read them with `get_pruned_block` instead.
+    pub fn get(
+        &self,
+        db: &IndexerStore,
+        state_hash: &BlockHash,
+    ) -> anyhow::Result<Option<Arc<PrecomputedBlock>>> {
+        if let Some(block) = self.lock().get(state_hash) {
+            return Ok(Some(block.clone()));
+        }
+
+        // read outside the lock, concurrent misses may both deserialize
+        Ok(db.get_block(state_hash)?.map(|(block, _)| {
+            let block = Arc::new(block);
+            self.lock().put(state_hash.clone(), block.clone());
+            block
+        }))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruCache<BlockHash, Arc<PrecomputedBlock>>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+pub(crate) fn block_cache<'a>(ctx: &'a Context) -> &'a PrecomputedBlockCache {
+    ctx.data::<Arc<PrecomputedBlockCache>>()
+        .expect("Block cache should be in the context")
+}
+
+pub(crate) fn loader<'a, T: Send + Sync + 'static>(ctx: &'a Context) -> &'a DataLoader<T> {
+    ctx.data::<DataLoader<T>>()
+        .expect("Data loader should be in the context")
+}
+
+/// Canonicity, confirmations & finality of the block
+pub(crate) async fn block_canonicity(
+    ctx: &Context<'_>,
+    state_hash: &str,
+) -> Result<BlockCanonicity> {
+    Ok(loader::<CanonicityLoader>(ctx)
+        .load_one(BlockHash::from(state_hash.to_owned()))
+        .await?
+        .unwrap_or_default())
+}
+
+/// Precomputed blocks, via the [PrecomputedBlockCache]
+pub struct BlockLoader {
+    pub db: Arc<IndexerStore>,
+    pub cache: Arc<PrecomputedBlockCache>,
+}
+
+impl Loader<BlockHash> for BlockLoader {
+    type Value = Arc<PrecomputedBlock>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[BlockHash]) -> Result<HashMap<BlockHash, Self::Value>> {
+        let mut blocks = HashMap::with_capacity(keys.len());
+        for state_hash in keys {
+            if let Some(block) = self.cache.get(&self.db, state_hash)? {
+                blocks.insert(state_hash.clone(), block);
+            }
+        }
+        Ok(blocks)
+    }
+}
+
+/// Counts of a block, along with the chain-wide counts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockSummary {
+    pub block_num_snarks: u32,
+    pub block_num_user_commands: u32,
+    pub block_num_internal_commands: u32,
+    pub epoch_num_blocks: u32,
+    pub total_num_blocks: u32,
+    pub epoch_num_user_commands: u32,
+    pub total_num_user_commands: u32,
+}
+
+pub struct BlockSummaryLoader(pub Arc<IndexerStore>);
+
+impl Loader<BlockHash> for BlockSummaryLoader {
+    type Value = BlockSummary;
+    type Error = Error;
+
+    async fn load(&self, keys: &[BlockHash]) -> Result<HashMap<BlockHash, Self::Value>> {
+        let db = &self.0;
+
+        // chain-wide counts are read once per batch
+        let epoch_num_blocks = db.get_block_production_epoch_count(None)?;
+        let total_num_blocks = db.get_block_production_total_count()?;
+        let epoch_num_user_commands = db.get_user_commands_epoch_count(None)?;
+        let total_num_user_commands = db.get_user_commands_total_count()?;
+
+        let mut summaries = HashMap::with_capacity(keys.len());
+        for state_hash in keys {
+            summaries.insert(
+                state_hash.clone(),
+                BlockSummary {
+                    block_num_snarks: db.get_block_snarks_count(state_hash)?.unwrap_or_default(),
+                    block_num_user_commands: db
+                        .get_block_user_commands_count(state_hash)?
+                        .unwrap_or_default(),
+                    block_num_internal_commands: db
+                        .get_block_internal_commands_count(state_hash)?
+                        .unwrap_or_default(),
+                    epoch_num_blocks,
+                    total_num_blocks,
+                    epoch_num_user_commands,
+                    total_num_user_commands,
+                },
+            );
+        }
+        Ok(summaries)
+    }
+}
+
+/// Canonicity, confirmations & finality of a block
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockCanonicity {
+    pub canonical: bool,
+    pub confirmations: Option<u32>,
+    pub is_final: bool,
+}
+
+pub struct CanonicityLoader(pub Arc<IndexerStore>);
+
+impl Loader<BlockHash> for CanonicityLoader {
+    type Value = BlockCanonicity;
+    type Error = Error;
+
+    async fn load(&self, keys: &[BlockHash]) -> Result<HashMap<BlockHash, Self::Value>> {
+        let db = &self.0;
+        let mut canonicities = HashMap::with_capacity(keys.len());
+        for state_hash in keys {
+            canonicities.insert(
+                state_hash.clone(),
+                BlockCanonicity {
+                    canonical: get_block_canonicity(db, &state_hash.0),
+                    confirmations: db.get_block_confirmations(state_hash)?,
+                    is_final: db.is_block_final(state_hash)?,
+                },
+            );
+        }
+        Ok(canonicities)
+    }
+}
+
+pub struct UsernameLoader(pub Arc<IndexerStore>);
+
+impl Loader<PublicKey> for UsernameLoader {
+    type Value = String;
+    type Error = Error;
+
+    async fn load(&self, keys: &[PublicKey]) -> Result<HashMap<PublicKey, Self::Value>> {
+        let mut usernames = HashMap::with_capacity(keys.len());
+        for pk in keys {
+            if let Some(username) = self.0.get_username(pk)? {
+                usernames.insert(pk.clone(), username.0);
+            }
+        }
+        Ok(usernames)
+    }
+}
+
+/// Block production, SNARK, user & internal command counts of a public key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PkCounts {
+    pub epoch_num_blocks: u32,
+    pub total_num_blocks: u32,
+    pub epoch_num_snarks: u32,
+    pub total_num_snarks: u32,
+    pub epoch_num_user_commands: u32,
+    pub total_num_user_commands: u32,
+    pub epoch_num_internal_commands: u32,
+    pub total_num_internal_commands: u32,
+}
+
+/// Per public key counts, in the given epoch (default: current epoch)
+pub struct PkCountsLoader(pub Arc<IndexerStore>);
+
+impl Loader<(PublicKey, Option<u32>)> for PkCountsLoader {
+    type Value = PkCounts;
+    type Error = Error;
+
+    async fn load(
+        &self,
+        keys: &[(PublicKey, Option<u32>)],
+    ) -> Result<HashMap<(PublicKey, Option<u32>), Self::Value>> {
+        let db = &self.0;
+        let mut counts = HashMap::with_capacity(keys.len());
+        for (pk, epoch) in keys {
+            counts.insert(
+                (pk.clone(), *epoch),
+                PkCounts {
+                    epoch_num_blocks: db.get_block_production_pk_epoch_count(pk, *epoch)?,
+                    total_num_blocks: db.get_block_production_pk_total_count(pk)?,
+                    epoch_num_snarks: db.get_snarks_pk_epoch_count(pk, *epoch)?,
+                    total_num_snarks: db.get_snarks_pk_total_count(pk)?,
+                    epoch_num_user_commands: db.get_user_commands_pk_epoch_count(pk, *epoch)?,
+                    total_num_user_commands: db.get_user_commands_pk_total_count(pk)?,
+                    epoch_num_internal_commands: db
+                        .get_internal_commands_pk_epoch_count(pk, *epoch)?,
+                    total_num_internal_commands: db.get_internal_commands_pk_total_count(pk)?,
+                },
+            );
+        }
+        Ok(counts)
+    }
+}
@@ -0,0 +1,116 @@
+//! Per root field request counts & latency of a GraphQL endpoint
+
+use crate::metrics::metrics;
+use async_graphql::{
+    async_trait::async_trait,
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextExecute, NextParseQuery},
+    parser::types::{DocumentOperations, ExecutableDocument, Selection, SelectionSet},
+    Response, ServerResult, Variables,
+};
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+/// Records the operations executed at `endpoint`, labelled by the root field
+/// they select rather than their client chosen name to bound the number of
+/// series, like the HTTP requests' route patterns. Operations selecting
+/// several root fields are labelled `multiple`
+pub struct OperationMetrics(pub &'static str);
+
+impl ExtensionFactory for OperationMetrics {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(OperationMetricsExtension {
+            endpoint: self.0,
+            document: Mutex::new(None),
+        })
+    }
+}
+
+struct OperationMetricsExtension {
+    endpoint: &'static str,
+
+    /// The request's parsed query
+    document: Mutex<Option<ExecutableDocument>>,
+}
+
+#[async_trait]
+impl Extension for OperationMetricsExtension {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let document = next.run(ctx, query, variables).await?;
+        *self.document.lock().expect("document lock") = Some(document.clone());
+        Ok(document)
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let start = Instant::now();
+        let response = next.run(ctx, operation_name).await;
+
+        // the query is validated by now, so its root fields are schema fields
+        let field = self
+            .document
+            .lock()
+            .expect("document lock")
+            .as_ref()
+            .map_or_else(
+                || "unknown".to_string(),
+                |doc| root_field(doc, operation_name),
+            );
+        metrics().observe_graphql_request(self.endpoint, &field, start.elapsed());
+        response
+    }
+}
+
+/// Root field selected by the executed operation
+fn root_field(document: &ExecutableDocument, operation_name: Option<&str>) -> String {
+    let operation = match (&document.operations, operation_name) {
+        (DocumentOperations::Single(operation), _) => operation,
+        (DocumentOperations::Multiple(operations), Some(name)) => match operations.get(name) {
+            Some(operation) => operation,
+            None => return "unknown".to_string(),
+        },
+        (DocumentOperations::Multiple(_), None) => return "unknown".to_string(),
+    };
+
+    let mut fields = BTreeSet::new();
+    collect_root_fields(document, &operation.node.selection_set.node, &mut fields);
+    match fields.len() {
+        0 => "unknown".to_string(),
+        1 => fields.into_iter().next().expect("root field").to_string(),
+        _ => "multiple".to_string(),
+    }
+}
+
+fn collect_root_fields<'a>(
+    document: &'a ExecutableDocument,
+    selection_set: &'a SelectionSet,
+    fields: &mut BTreeSet<&'a str>,
+) {
+    for selection in &selection_set.items {
+        match &selection.node {
+            Selection::Field(field) => {
+                fields.insert(field.node.name.node.as_str());
+            }
+            Selection::FragmentSpread(spread) => {
+                if let Some(fragment) = document.fragments.get(&spread.node.fragment_name.node) {
+                    collect_root_fields(document, &fragment.node.selection_set.node, fields);
+                }
+            }
+            Selection::InlineFragment(fragment) => {
+                collect_root_fields(document, &fragment.node.selection_set.node, fields)
+            }
+        }
+    }
+}
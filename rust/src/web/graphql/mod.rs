@@ -1,20 +1,37 @@
 pub mod accounts;
 pub mod blocks;
+pub mod daemon;
+pub mod events;
 pub mod feetransfers;
+pub mod filter;
 pub mod gen;
+pub mod limits;
+pub mod loaders;
+pub mod metrics;
+pub mod pagination;
+pub mod reorgs;
 pub mod snarks;
 pub mod staged_ledgers;
 pub mod stakes;
+pub mod subscriptions;
 pub mod transactions;
 pub mod version;
 
-use super::ENDPOINT_GRAPHQL;
-use crate::{constants::*, store::IndexerStore};
+use super::{ENDPOINT_DAEMON_GRAPHQL, ENDPOINT_GRAPHQL};
+use crate::{constants::*, event::broadcast::EventSender, store::IndexerStore};
 use actix_web::HttpResponse;
 use async_graphql::{
-    http::GraphiQLSource, Context, EmptyMutation, EmptySubscription, InputValueError,
-    InputValueResult, MergedObject, Scalar, ScalarType, Schema, SimpleObject, Value,
+    dataloader::DataLoader, extensions::Analyzer, http::GraphiQLSource, Context, EmptyMutation,
+    InputValueError, InputValueResult, MergedObject, Scalar, ScalarType, Schema, SimpleObject,
+    Value,
 };
+use limits::{GraphQLLimits, Timeout};
+use loaders::{
+    BlockLoader, BlockSummaryLoader, CanonicityLoader, PkCountsLoader, PrecomputedBlockCache,
+    UsernameLoader,
+};
+use metrics::OperationMetrics;
+use serde::Serialize;
 use std::sync::Arc;
 
 #[derive(MergedObject, Default)]
@@ -26,6 +43,8 @@ pub struct Root(
     feetransfers::FeetransferQueryRoot,
     snarks::SnarkQueryRoot,
     staged_ledgers::StagedLedgerQueryRoot,
+    events::EventsQueryRoot,
+    reorgs::ReorgsQueryRoot,
     version::VersionQueryRoot,
 );
 
@@ -47,17 +66,72 @@ pub struct Timing {
     pub vesting_increment: Option<u64>,
 }
 
+pub type IndexerSchema = Schema<Root, EmptyMutation, subscriptions::SubscriptionRoot>;
+
 /// Build schema for all endpoints
-pub fn build_schema(store: Arc<IndexerStore>) -> Schema<Root, EmptyMutation, EmptySubscription> {
-    Schema::build(Root::default(), EmptyMutation, EmptySubscription)
-        .data(store)
-        .finish()
+///
+/// Subscriptions are fed by the events broadcast via `events`. Queries are
+/// subject to `limits` & report their cost in the `analyzer` extension.
+/// Nested resolvers batch their reads via the [loaders].
+pub fn build_schema(
+    store: Arc<IndexerStore>,
+    events: EventSender,
+    limits: GraphQLLimits,
+) -> IndexerSchema {
+    let cache = Arc::new(PrecomputedBlockCache::default());
+    Schema::build(
+        Root::default(),
+        EmptyMutation,
+        subscriptions::SubscriptionRoot,
+    )
+    .data(DataLoader::new(
+        BlockLoader {
+            db: store.clone(),
+            cache: cache.clone(),
+        },
+        tokio::spawn,
+    ))
+    .data(DataLoader::new(
+        BlockSummaryLoader(store.clone()),
+        tokio::spawn,
+    ))
+    .data(DataLoader::new(
+        CanonicityLoader(store.clone()),
+        tokio::spawn,
+    ))
+    .data(DataLoader::new(UsernameLoader(store.clone()), tokio::spawn))
+    .data(DataLoader::new(PkCountsLoader(store.clone()), tokio::spawn))
+    .data(cache)
+    .data(store)
+    .data(events)
+    .data(limits)
+    .limit_depth(limits.max_depth)
+    .limit_complexity(limits.max_complexity)
+    .extension(Analyzer)
+    .extension(Timeout(limits.timeout))
+    .extension(OperationMetrics(ENDPOINT_GRAPHQL))
+    .finish()
 }
 
 pub async fn indexer_graphiql() -> actix_web::Result<HttpResponse> {
     Ok(HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
-        .body(GraphiQLSource::build().endpoint(ENDPOINT_GRAPHQL).finish()))
+        .body(
+            GraphiQLSource::build()
+                .endpoint(ENDPOINT_GRAPHQL)
+                .subscription_endpoint(ENDPOINT_GRAPHQL)
+                .finish(),
+        ))
+}
+
+pub async fn daemon_graphiql() -> actix_web::Result<HttpResponse> {
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(
+            GraphiQLSource::build()
+                .endpoint(ENDPOINT_DAEMON_GRAPHQL)
+                .finish(),
+        ))
 }
 
 pub(crate) fn db<'a>(ctx: &'a Context) -> &'a Arc<IndexerStore> {
@@ -65,6 +139,14 @@ pub(crate) fn db<'a>(ctx: &'a Context) -> &'a Arc<IndexerStore> {
         .expect("Database should be in the context")
 }
 
+/// Error returned for fields which require the full contents of a pruned
+/// block that cannot be re-hydrated from the blocks dir
+pub(crate) fn pruned_block_error(state_hash: &str) -> async_graphql::Error {
+    async_graphql::Error::new(format!(
+        "Block {state_hash} has been pruned and its precomputed block file is unavailable"
+    ))
+}
+
 #[derive(Debug, Clone)]
 pub struct Long(pub String);
 
@@ -82,7 +164,7 @@ impl ScalarType for Long {
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord, Serialize)]
 pub struct DateTime(pub String);
 
 #[Scalar]
@@ -112,7 +194,7 @@ pub(crate) fn get_block_canonicity(db: &Arc<IndexerStore>, state_hash: &str) ->
         .unwrap_or(false)
 }
 
-#[derive(Default, Clone, Debug, PartialEq, SimpleObject)]
+#[derive(Default, Clone, Debug, PartialEq, SimpleObject, Serialize)]
 #[graphql(name = "PublicKey")]
 pub(crate) struct PK {
     pub public_key: String,
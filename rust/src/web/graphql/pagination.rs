@@ -0,0 +1,196 @@
+//! Relay-style connections over speedb sort keys
+//!
+//! Edge cursors are the opaque, base64 encoded sort keys of the iterator
+//! backing the query, so fetching the next page seeks the iterator to the
+//! cursor instead of rescanning. Queries answered from an in-memory list
+//! encode the item's offset instead.
+
+use super::limits::Deadline;
+use async_graphql::{
+    connection::{Connection, CursorType, Edge},
+    Context, OutputType, SimpleObject,
+};
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+
+/// Opaque cursor encoding the sort key of an edge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortKeyCursor(pub Vec<u8>);
+
+impl CursorType for SortKeyCursor {
+    type Error = String;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        match BASE64_URL_SAFE_NO_PAD.decode(s) {
+            Ok(key) if !key.is_empty() => Ok(Self(key)),
+            _ => Err(format!("Invalid cursor {s}")),
+        }
+    }
+
+    fn encode_cursor(&self) -> String {
+        BASE64_URL_SAFE_NO_PAD.encode(&self.0)
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ConnectionFields {
+    /// Value total count, if the query is unfiltered
+    pub total_count: Option<u32>,
+}
+
+pub type SortKeyConnection<T> = Connection<SortKeyCursor, T, ConnectionFields>;
+
+/// Collects a page of nodes & their sort keys
+pub struct Page<T> {
+    first: usize,
+    after: Option<Vec<u8>>,
+    lookahead: bool,
+    keys: Vec<Vec<u8>>,
+    nodes: Vec<T>,
+    has_next_page: bool,
+    deadline: Option<Deadline>,
+}
+
+impl<T: OutputType> Page<T> {
+    /// Connection page of `first` nodes after the `after` cursor
+    pub fn new(first: usize, after: Option<String>) -> async_graphql::Result<Self> {
+        let after = after
+            .map(|cursor| SortKeyCursor::decode_cursor(&cursor).map(|cursor| cursor.0))
+            .transpose()?;
+        Ok(Self {
+            first,
+            after,
+            lookahead: true,
+            keys: vec![],
+            nodes: vec![],
+            has_next_page: false,
+            deadline: None,
+        })
+    }
+
+    /// Page of a plain list query, stops at `limit` nodes without looking
+    /// for a next page
+    pub fn list(limit: usize) -> Self {
+        Self {
+            first: limit,
+            after: None,
+            lookahead: false,
+            keys: vec![],
+            nodes: vec![],
+            has_next_page: false,
+            deadline: None,
+        }
+    }
+
+    /// Stop collecting the page at the request's deadline, if any
+    pub fn with_deadline(mut self, ctx: &Context) -> Self {
+        self.deadline = ctx.data_opt::<Deadline>().copied();
+        self
+    }
+
+    /// Error once the request's deadline has passed, called for each iterated
+    /// entry
+    pub fn check_deadline(&self) -> async_graphql::Result<()> {
+        self.deadline.as_ref().map_or(Ok(()), Deadline::check)
+    }
+
+    /// Iterator start key, the cursor's if resuming, else `start`
+    pub fn seek<'a>(&'a self, start: &'a [u8]) -> &'a [u8] {
+        self.after.as_deref().unwrap_or(start)
+    }
+
+    /// Add the node at `key`, returns `true` once the page is complete
+    ///
+    /// The cursor's own entry is skipped
+    pub fn push(&mut self, key: &[u8], node: T) -> bool {
+        if self.after.as_deref() == Some(key) {
+            return false;
+        }
+        if self.nodes.len() == self.first {
+            self.has_next_page = true;
+            return true;
+        }
+
+        self.keys.push(key.to_vec());
+        self.nodes.push(node);
+        !self.lookahead && self.nodes.len() == self.first
+    }
+
+    /// Iterator start key of a query yielding several nodes per iterator
+    /// entry, the cursor's entry if resuming, else `start`
+    pub fn seek_group<'a>(&'a self, start: &'a [u8]) -> &'a [u8] {
+        match self.after.as_deref() {
+            Some(after) if after.len() > 4 => &after[..after.len() - 4],
+            _ => start,
+        }
+    }
+
+    /// Add the `n`th node of the iterator entry at `key`, returns `true` once
+    /// the page is complete
+    ///
+    /// Nodes of the cursor's entry up to & including the cursor's are skipped
+    pub fn push_nth(&mut self, key: &[u8], n: u32, node: T) -> bool {
+        if let Some(after) = self.after.as_deref() {
+            if after.len() == key.len() + 4
+                && after.starts_with(key)
+                && n.to_be_bytes()[..] <= after[key.len()..]
+            {
+                return false;
+            }
+        }
+        let mut key = key.to_vec();
+        key.extend(n.to_be_bytes());
+        self.push(&key, node)
+    }
+
+    /// Add in-memory `nodes`, keyed by their offset
+    pub fn extend(&mut self, nodes: impl IntoIterator<Item = T>) -> async_graphql::Result<()> {
+        let start = match self.after.as_deref() {
+            Some(after) => {
+                let offset: [u8; 4] = after
+                    .try_into()
+                    .map_err(|_| SortKeyCursor(after.to_vec()).invalid())?;
+                u32::from_be_bytes(offset) as usize + 1
+            }
+            None => 0,
+        };
+        for (offset, node) in nodes.into_iter().enumerate().skip(start) {
+            if self.push(&(offset as u32).to_be_bytes(), node) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn into_nodes(self) -> Vec<T> {
+        self.nodes
+    }
+
+    pub fn into_connection(self, total_count: Option<u32>) -> SortKeyConnection<T> {
+        let mut connection = Connection::with_additional_fields(
+            self.after.is_some(),
+            self.has_next_page,
+            ConnectionFields { total_count },
+        );
+        connection.edges = self
+            .keys
+            .into_iter()
+            .zip(self.nodes)
+            .map(|(key, node)| Edge::new(SortKeyCursor(key), node))
+            .collect();
+        connection
+    }
+}
+
+impl SortKeyCursor {
+    fn invalid(&self) -> String {
+        format!("Invalid cursor {}", self.encode_cursor())
+    }
+}
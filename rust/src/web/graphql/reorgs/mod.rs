@@ -0,0 +1,254 @@
+use super::{db, limits::list_limit};
+use crate::{
+    block::BlockHash,
+    canonicity::{reorg, store::CanonicityStore},
+    ledger::public_key::PublicKey,
+};
+use async_graphql::{Context, InputObject, Object, Result, SimpleObject};
+
+#[derive(InputObject)]
+pub struct ReorgQueryInput {
+    #[graphql(name = "depth_gte")]
+    depth_gte: Option<u32>,
+
+    epoch: Option<u32>,
+
+    /// Uncanonicalized or recanonicalized block
+    state_hash: Option<String>,
+
+    /// Uncanonicalized or recanonicalized user command
+    txn_hash: Option<String>,
+
+    /// Sender or receiver of an uncanonicalized or recanonicalized user
+    /// command
+    public_key: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct Reorg {
+    /// Value reorg index
+    index: u32,
+
+    /// Value old best tip state hash
+    old_best_tip: String,
+
+    /// Value old best tip blockchain length
+    old_best_tip_height: u32,
+
+    /// Value new best tip state hash
+    new_best_tip: String,
+
+    /// Value new best tip blockchain length
+    new_best_tip_height: u32,
+
+    /// Value common ancestor state hash
+    common_ancestor: String,
+
+    /// Value common ancestor blockchain length
+    common_ancestor_height: u32,
+
+    /// Value number of blocks removed from the best chain
+    depth: u32,
+
+    /// Value epoch of the new best tip
+    epoch: u32,
+
+    /// Value blocks removed from the best chain
+    uncanonicalized: Vec<ReorgBlock>,
+
+    /// Value blocks added to the best chain
+    recanonicalized: Vec<ReorgBlock>,
+}
+
+#[derive(SimpleObject)]
+pub struct ReorgBlock {
+    /// Value state hash
+    state_hash: String,
+
+    /// Value blockchain length
+    blockchain_length: u32,
+
+    /// Value global slot since genesis
+    global_slot: u32,
+
+    /// Value user commands
+    user_commands: Vec<ReorgUserCommand>,
+}
+
+#[derive(SimpleObject)]
+pub struct ReorgUserCommand {
+    /// Value txn hash
+    txn_hash: String,
+
+    /// Value sender
+    sender: String,
+
+    /// Value receiver
+    receiver: String,
+
+    /// Value amount (nanomina)
+    amount: u64,
+
+    /// Value fee (nanomina)
+    fee: u64,
+
+    /// Value nonce
+    nonce: u32,
+
+    /// Value applied
+    is_applied: bool,
+}
+
+#[derive(SimpleObject)]
+pub struct ReorgStats {
+    /// Value total number of reorgs
+    num_reorgs: u32,
+
+    /// Value max reorg depth
+    max_depth: u32,
+
+    /// Value total number of blocks removed from the best chain
+    num_uncanonicalized_blocks: u32,
+
+    /// Value total number of user commands removed from the best chain
+    num_uncanonicalized_user_commands: u32,
+
+    /// Value per epoch stats
+    epochs: Vec<EpochReorgStats>,
+}
+
+#[derive(SimpleObject)]
+pub struct EpochReorgStats {
+    /// Value epoch
+    epoch: u32,
+
+    /// Value number of reorgs
+    num_reorgs: u32,
+
+    /// Value max reorg depth
+    max_depth: u32,
+
+    /// Value number of blocks removed from the best chain
+    num_uncanonicalized_blocks: u32,
+
+    /// Value number of user commands removed from the best chain
+    num_uncanonicalized_user_commands: u32,
+}
+
+#[derive(Default)]
+pub struct ReorgsQueryRoot;
+
+#[Object]
+impl ReorgsQueryRoot {
+    /// Best tip reorgs, newest first
+    #[graphql(complexity = "limit.saturating_mul(child_complexity)")]
+    async fn reorgs<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<ReorgQueryInput>,
+        #[graphql(default = 100)] limit: usize,
+    ) -> Result<Vec<Reorg>> {
+        let db = db(ctx);
+        let filter = query.map(reorg::ReorgFilter::from).unwrap_or_default();
+        Ok(db
+            .get_reorgs(&filter, list_limit(ctx, limit))?
+            .into_iter()
+            .map(Reorg::from)
+            .collect())
+    }
+
+    /// Summary stats of all best tip reorgs
+    async fn reorg_stats<'ctx>(&self, ctx: &Context<'ctx>) -> Result<ReorgStats> {
+        let db = db(ctx);
+        Ok(db.get_reorg_stats()?.into())
+    }
+}
+
+impl From<ReorgQueryInput> for reorg::ReorgFilter {
+    fn from(value: ReorgQueryInput) -> Self {
+        Self {
+            min_depth: value.depth_gte,
+            epoch: value.epoch,
+            state_hash: value.state_hash.map(BlockHash::from),
+            txn_hash: value.txn_hash,
+            public_key: value.public_key.map(PublicKey::from),
+        }
+    }
+}
+
+impl From<reorg::Reorg> for Reorg {
+    fn from(value: reorg::Reorg) -> Self {
+        Self {
+            index: value.index,
+            old_best_tip: value.old_best_tip.0,
+            old_best_tip_height: value.old_best_tip_height,
+            new_best_tip: value.new_best_tip.0,
+            new_best_tip_height: value.new_best_tip_height,
+            common_ancestor: value.common_ancestor.0,
+            common_ancestor_height: value.common_ancestor_height,
+            depth: value.depth,
+            epoch: value.epoch,
+            uncanonicalized: value
+                .uncanonicalized
+                .into_iter()
+                .map(ReorgBlock::from)
+                .collect(),
+            recanonicalized: value
+                .recanonicalized
+                .into_iter()
+                .map(ReorgBlock::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<reorg::ReorgBlock> for ReorgBlock {
+    fn from(value: reorg::ReorgBlock) -> Self {
+        Self {
+            state_hash: value.state_hash.0,
+            blockchain_length: value.blockchain_length,
+            global_slot: value.global_slot,
+            user_commands: value
+                .user_commands
+                .into_iter()
+                .map(ReorgUserCommand::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<reorg::ReorgUserCommand> for ReorgUserCommand {
+    fn from(value: reorg::ReorgUserCommand) -> Self {
+        Self {
+            txn_hash: value.txn_hash,
+            sender: value.sender.0,
+            receiver: value.receiver.0,
+            amount: value.amount,
+            fee: value.fee,
+            nonce: value.nonce,
+            is_applied: value.is_applied,
+        }
+    }
+}
+
+impl From<reorg::ReorgStats> for ReorgStats {
+    fn from(value: reorg::ReorgStats) -> Self {
+        Self {
+            num_reorgs: value.num_reorgs,
+            max_depth: value.max_depth,
+            num_uncanonicalized_blocks: value.num_uncanonicalized_blocks,
+            num_uncanonicalized_user_commands: value.num_uncanonicalized_user_commands,
+            epochs: value
+                .epochs
+                .into_iter()
+                .map(|epoch| EpochReorgStats {
+                    epoch: epoch.epoch,
+                    num_reorgs: epoch.num_reorgs,
+                    max_depth: epoch.max_depth,
+                    num_uncanonicalized_blocks: epoch.num_uncanonicalized_blocks,
+                    num_uncanonicalized_user_commands: epoch.num_uncanonicalized_user_commands,
+                })
+                .collect(),
+        }
+    }
+}
@@ -3,8 +3,18 @@ use crate::{
     constants::*,
     ledger::public_key::PublicKey,
     snark_work::{store::SnarkStore, SnarkWorkSummary, SnarkWorkSummaryWithStateHash},
-    store::{block_state_hash_from_key, from_be_bytes, to_be_bytes, IndexerStore},
-    web::graphql::{db, gen::BlockQueryInput, get_block_canonicity},
+    store::{
+        block_state_hash_from_key, block_u32_prefix_from_key, from_be_bytes, to_be_bytes,
+        IndexerStore,
+    },
+    web::graphql::{
+        db,
+        gen::BlockQueryInput,
+        get_block_canonicity,
+        limits::list_limit,
+        loaders::{block_cache, PrecomputedBlockCache},
+        pagination::{Page, SortKeyConnection},
+    },
 };
 use anyhow::Context as aContext;
 use async_graphql::{ComplexObject, Context, Enum, InputObject, Object, Result, SimpleObject};
@@ -36,7 +46,7 @@ pub struct SnarkWithCanonicity {
 
     /// Value optional block
     #[graphql(skip)]
-    pub pcb: PrecomputedBlock,
+    pub pcb: Arc<PrecomputedBlock>,
 
     /// Value snark
     #[graphql(flatten)]
@@ -93,6 +103,7 @@ pub struct SnarkQueryRoot;
 
 #[Object]
 impl SnarkQueryRoot {
+    #[graphql(complexity = "limit.saturating_mul(child_complexity)")]
     async fn snarks<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -100,320 +111,265 @@ impl SnarkQueryRoot {
         sort_by: Option<SnarkSortByInput>,
         #[graphql(default = 100)] limit: usize,
     ) -> Result<Vec<SnarkWithCanonicity>> {
-        let db = db(ctx);
-        let mut snarks = Vec::new();
-        let sort_by = sort_by.unwrap_or(SnarkSortByInput::BlockHeightDesc);
-
-        // state hash
-        if let Some(state_hash) = query
-            .as_ref()
-            .and_then(|q| q.block.as_ref())
-            .and_then(|block| block.state_hash.clone())
-        {
-            let mut snarks: Vec<SnarkWithCanonicity> = db
-                .get_block(&state_hash.into())?
-                .into_iter()
-                .flat_map(|(block, _)| {
-                    SnarkWorkSummaryWithStateHash::from_precomputed(&block)
-                        .into_iter()
-                        .filter_map(|s| snark_summary_matches_query(db, &query, s).ok().flatten())
-                        .collect::<Vec<SnarkWithCanonicity>>()
-                })
-                .collect();
+        let mut page = Page::list(list_limit(ctx, limit)).with_deadline(ctx);
+        snarks_page(db(ctx), block_cache(ctx), query, sort_by, &mut page)?;
+        Ok(page.into_nodes())
+    }
 
-            match sort_by {
-                SnarkSortByInput::BlockHeightAsc => snarks.reverse(),
-                SnarkSortByInput::BlockHeightDesc => (),
-            }
+    /// Value SNARKs connection, paged by `first` & `after`
+    #[graphql(complexity = "first.saturating_mul(child_complexity)")]
+    async fn snarks_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<SnarkQueryInput>,
+        sort_by: Option<SnarkSortByInput>,
+        #[graphql(default = 100)] first: usize,
+        after: Option<String>,
+    ) -> Result<SortKeyConnection<SnarkWithCanonicity>> {
+        let db = db(ctx);
+        let total_count = match query {
+            None => Some(db.get_snarks_total_count()?),
+            Some(_) => None,
+        };
+        let mut page = Page::new(list_limit(ctx, first), after)?.with_deadline(ctx);
+        snarks_page(db, block_cache(ctx), query, sort_by, &mut page)?;
+        Ok(page.into_connection(total_count))
+    }
+}
 
-            snarks.truncate(limit);
-            return Ok(snarks);
+/// Collect the page of SNARKs matching the query
+fn snarks_page(
+    db: &Arc<IndexerStore>,
+    cache: &PrecomputedBlockCache,
+    query: Option<SnarkQueryInput>,
+    sort_by: Option<SnarkSortByInput>,
+    page: &mut Page<SnarkWithCanonicity>,
+) -> Result<()> {
+    let counts = (
+        db.get_snarks_epoch_count(None)?,
+        db.get_snarks_total_count()?,
+    );
+    let sort_by = sort_by.unwrap_or(SnarkSortByInput::BlockHeightDesc);
+    let direction = match sort_by {
+        SnarkSortByInput::BlockHeightAsc => speedb::Direction::Forward,
+        SnarkSortByInput::BlockHeightDesc => speedb::Direction::Reverse,
+    };
+
+    // state hash
+    if let Some(state_hash) = query
+        .as_ref()
+        .and_then(|q| q.block.as_ref())
+        .and_then(|block| block.state_hash.clone())
+    {
+        let mut snarks: Vec<SnarkWithCanonicity> = cache
+            .get(db, &state_hash.into())?
+            .into_iter()
+            .flat_map(|block| {
+                SnarkWorkSummaryWithStateHash::from_precomputed(&block)
+                    .into_iter()
+                    .filter_map(|s| snark_summary_matches_query(db, &query, &block, s, counts))
+                    .collect::<Vec<SnarkWithCanonicity>>()
+            })
+            .collect();
+
+        match sort_by {
+            SnarkSortByInput::BlockHeightAsc => snarks.reverse(),
+            SnarkSortByInput::BlockHeightDesc => (),
         }
+        return page.extend(snarks);
+    }
 
-        // block height
-        if let Some(block_height) = query.as_ref().and_then(|q| q.block_height) {
-            let mut snarks: Vec<SnarkWithCanonicity> = db
-                .get_blocks_at_height(block_height)?
-                .iter()
-                .flat_map(|state_hash| {
-                    let block = db
-                        .get_block(state_hash)
-                        .with_context(|| format!("block missing from store {state_hash}"))
-                        .unwrap()
-                        .unwrap()
-                        .0;
-                    SnarkWorkSummaryWithStateHash::from_precomputed(&block)
-                        .into_iter()
-                        .filter_map(|s| snark_summary_matches_query(db, &query, s).ok().flatten())
-                        .collect::<Vec<SnarkWithCanonicity>>()
-                })
-                .collect();
+    // block height
+    if let Some(block_height) = query.as_ref().and_then(|q| q.block_height) {
+        let mut snarks = vec![];
+        for state_hash in db.get_blocks_at_height(block_height)? {
+            let block = cache
+                .get(db, &state_hash)?
+                .with_context(|| format!("block missing from store {state_hash}"))?;
+            snarks.extend(
+                SnarkWorkSummaryWithStateHash::from_precomputed(&block)
+                    .into_iter()
+                    .filter_map(|s| snark_summary_matches_query(db, &query, &block, s, counts)),
+            );
+        }
 
-            match sort_by {
-                SnarkSortByInput::BlockHeightAsc => snarks.reverse(),
-                SnarkSortByInput::BlockHeightDesc => (),
-            }
+        match sort_by {
+            SnarkSortByInput::BlockHeightAsc => snarks.reverse(),
+            SnarkSortByInput::BlockHeightDesc => (),
+        }
+        return page.extend(snarks);
+    }
 
-            snarks.truncate(limit);
-            return Ok(snarks);
+    // prover query filter and sort by height
+    if let (Some(prover), Some(block_height_lte)) = (
+        query.as_ref().and_then(|q| q.prover.clone()),
+        query.as_ref().and_then(|q| q.block_height_lte),
+    ) {
+        let mut start = prover.as_bytes().to_vec();
+        if sort_by == SnarkSortByInput::BlockHeightDesc {
+            start.append(&mut to_be_bytes(block_height_lte));
+            start.append(&mut to_be_bytes(u32::MAX));
         }
-        // prover query filter and sort by height
-        if let (Some(prover), Some(block_height_lte)) = (
-            query.as_ref().and_then(|q| q.prover.clone()),
-            query.as_ref().and_then(|q| q.block_height_lte),
-        ) {
-            let mut start = prover.as_bytes().to_vec();
-            let mode = match sort_by {
-                SnarkSortByInput::BlockHeightAsc => {
-                    speedb::IteratorMode::From(&start, speedb::Direction::Forward)
-                }
-                SnarkSortByInput::BlockHeightDesc => {
-                    start.append(&mut to_be_bytes(block_height_lte));
-                    start.append(&mut to_be_bytes(u32::MAX));
-                    speedb::IteratorMode::From(&start, speedb::Direction::Reverse)
-                }
-            };
-
-            let mut snarks = Vec::new();
-            // key should be typed
-            'outer: for (key, snark) in db.snark_prover_height_iterator(mode).flatten() {
-                // exit if prover isn't the same
-                if key[..PublicKey::LEN] != *prover.as_bytes() {
-                    break;
-                }
-                let block_height = from_be_bytes(key[PublicKey::LEN..PublicKey::LEN + 4].to_vec());
-                let blocks_at_height = db.get_blocks_at_height(block_height)?;
-                for state_hash in blocks_at_height {
-                    let canonical = get_block_canonicity(db, &state_hash.0);
-                    let pcb = db
-                        .get_block(&state_hash)?
-                        .with_context(|| format!("block missing from store {state_hash}"))
-                        .expect("blocks exists")
-                        .0;
-                    let snark = serde_json::from_slice(&snark)?;
-                    let sw = SnarkWithCanonicity {
-                        canonical,
-                        pcb,
-                        snark: (
-                            snark,
-                            state_hash,
-                            db.get_snarks_epoch_count(None).expect("epoch snarks count"),
-                            db.get_snarks_total_count().expect("total snarks count"),
-                        )
-                            .into(),
-                    };
-                    if query.as_ref().map_or(true, |q| q.matches(&sw)) {
-                        snarks.push(sw);
-                        if snarks.len() == limit {
-                            break 'outer;
-                        }
-                    }
-                }
+        let mode = speedb::IteratorMode::From(page.seek_group(&start), direction);
+
+        // key should be typed
+        'outer: for (key, snark) in db.snark_prover_height_iterator(mode).flatten() {
+            page.check_deadline()?;
+            // exit if prover isn't the same
+            if key[..PublicKey::LEN] != *prover.as_bytes() {
+                break;
             }
-            return Ok(snarks);
-        }
-
-        // prover query
-        if let Some(prover) = query.as_ref().and_then(|q| q.prover.clone()) {
-            let mut start = prover.as_bytes().to_vec();
-            let mode = match sort_by {
-                SnarkSortByInput::BlockHeightAsc => {
-                    speedb::IteratorMode::From(&start, speedb::Direction::Forward)
-                }
-                SnarkSortByInput::BlockHeightDesc => {
-                    let mut pk_prefix = PublicKey::PREFIX.as_bytes().to_vec();
-                    *pk_prefix.last_mut().unwrap_or(&mut 0) += 1;
-                    start.append(&mut to_be_bytes(u32::MAX));
-                    start.append(&mut pk_prefix);
-                    speedb::IteratorMode::From(&start, speedb::Direction::Reverse)
-                }
-            };
-
-            let mut snarks = Vec::new();
-            'outer: for (key, snark) in db.snark_prover_iterator(mode).flatten() {
-                if key[..PublicKey::LEN] != *prover.as_bytes() {
-                    break;
-                }
-
-                let global_slot = from_be_bytes(key[PublicKey::LEN..PublicKey::LEN + 4].to_vec());
-                let blocks_at_slot = db.get_blocks_at_slot(global_slot)?;
-                for state_hash in blocks_at_slot {
-                    let canonical = get_block_canonicity(db, &state_hash.0);
-                    let pcb = db
-                        .get_block(&state_hash)?
-                        .with_context(|| format!("block missing from store {state_hash}"))
-                        .expect("block exists")
-                        .0;
-                    let snark = serde_json::from_slice(&snark)?;
-                    let sw = SnarkWithCanonicity {
-                        canonical,
-                        pcb,
-                        snark: (
-                            snark,
-                            state_hash,
-                            db.get_snarks_epoch_count(None).expect("epoch snarks count"),
-                            db.get_snarks_total_count().expect("total snarks count"),
-                        )
-                            .into(),
-                    };
-                    if query.as_ref().map_or(true, |q| q.matches(&sw)) {
-                        snarks.push(sw);
-                        if snarks.len() == limit {
-                            break 'outer;
-                        }
-                    }
+            let block_height = from_be_bytes(key[PublicKey::LEN..PublicKey::LEN + 4].to_vec());
+            let blocks_at_height = db.get_blocks_at_height(block_height)?;
+            for (n, state_hash) in blocks_at_height.into_iter().enumerate() {
+                let canonical = get_block_canonicity(db, &state_hash.0);
+                let pcb = cache
+                    .get(db, &state_hash)?
+                    .with_context(|| format!("block missing from store {state_hash}"))?;
+                let snark = serde_json::from_slice(&snark)?;
+                let sw = SnarkWithCanonicity {
+                    canonical,
+                    pcb,
+                    snark: (snark, state_hash, counts.0, counts.1).into(),
+                };
+                if query.as_ref().map_or(true, |q| q.matches(&sw))
+                    && page.push_nth(&key, n as u32, sw)
+                {
+                    break 'outer;
                 }
             }
-            return Ok(snarks);
         }
+        return Ok(());
+    }
 
-        // block height bounded query
-        if query.as_ref().map_or(false, |q| {
-            q.block_height_gt.is_some()
-                || q.block_height_gte.is_some()
-                || q.block_height_lt.is_some()
-                || q.block_height_lte.is_some()
-        }) {
-            let mut snarks = Vec::new();
-            let (min, max) = {
-                let SnarkQueryInput {
-                    block_height_gt,
-                    block_height_gte,
-                    block_height_lt,
-                    block_height_lte,
-                    ..
-                } = query.as_ref().expect("query will contain a value");
-                let min_bound = match (*block_height_gte, *block_height_gt) {
-                    (Some(gte), Some(gt)) => std::cmp::max(gte, gt + 1),
-                    (Some(gte), None) => gte,
-                    (None, Some(gt)) => gt + 1,
-                    (None, None) => 1,
-                };
-
-                let max_bound = match (*block_height_lte, *block_height_lt) {
-                    (Some(lte), Some(lt)) => std::cmp::min(lte, lt - 1),
-                    (Some(lte), None) => lte,
-                    (None, Some(lt)) => lt - 1,
-                    (None, None) => db.get_best_block_height()?.unwrap(),
-                };
-                (min_bound, max_bound)
-            };
+    // prover query
+    if let Some(prover) = query.as_ref().and_then(|q| q.prover.clone()) {
+        let mut start = prover.as_bytes().to_vec();
+        if sort_by == SnarkSortByInput::BlockHeightDesc {
+            let mut pk_prefix = PublicKey::PREFIX.as_bytes().to_vec();
+            *pk_prefix.last_mut().unwrap_or(&mut 0) += 1;
+            start.append(&mut to_be_bytes(u32::MAX));
+            start.append(&mut pk_prefix);
+        }
+        let mode = speedb::IteratorMode::From(page.seek_group(&start), direction);
 
-            let mut block_heights: Vec<u32> = (min..=max).collect();
-            if sort_by == SnarkSortByInput::BlockHeightDesc {
-                block_heights.reverse()
+        'outer: for (key, snark) in db.snark_prover_iterator(mode).flatten() {
+            page.check_deadline()?;
+            if key[..PublicKey::LEN] != *prover.as_bytes() {
+                break;
             }
 
-            'outer: for height in block_heights {
-                for state_hash in db.get_blocks_at_height(height)? {
-                    let canonical = get_block_canonicity(db, &state_hash.0);
-                    let block = db
-                        .get_block(&state_hash)?
-                        .with_context(|| format!("block missing from store {state_hash}"))
-                        .unwrap()
-                        .0;
-                    let snark_work = db.get_snark_work_in_block(&state_hash)?;
-                    let snarks_with_canonicity = snark_work.map_or(vec![], |summaries| {
-                        summaries
-                            .into_iter()
-                            .map(|snark| SnarkWithCanonicity {
-                                canonical,
-                                pcb: block.clone(),
-                                snark: (
-                                    snark,
-                                    state_hash.clone(),
-                                    db.get_snarks_epoch_count(None).expect("epoch snarks count"),
-                                    db.get_snarks_total_count().expect("total snarks count"),
-                                )
-                                    .into(),
-                            })
-                            .collect()
-                    });
-
-                    for sw in snarks_with_canonicity {
-                        if query.as_ref().map_or(true, |q| q.matches(&sw)) {
-                            snarks.push(sw);
-
-                            if snarks.len() == limit {
-                                break 'outer;
-                            }
-                        }
-                    }
+            let global_slot = from_be_bytes(key[PublicKey::LEN..PublicKey::LEN + 4].to_vec());
+            let blocks_at_slot = db.get_blocks_at_slot(global_slot)?;
+            for (n, state_hash) in blocks_at_slot.into_iter().enumerate() {
+                let canonical = get_block_canonicity(db, &state_hash.0);
+                let pcb = cache
+                    .get(db, &state_hash)?
+                    .with_context(|| format!("block missing from store {state_hash}"))?;
+                let snark = serde_json::from_slice(&snark)?;
+                let sw = SnarkWithCanonicity {
+                    canonical,
+                    pcb,
+                    snark: (snark, state_hash, counts.0, counts.1).into(),
+                };
+                if query.as_ref().map_or(true, |q| q.matches(&sw))
+                    && page.push_nth(&key, n as u32, sw)
+                {
+                    break 'outer;
                 }
             }
-            return Ok(snarks);
         }
+        return Ok(());
+    }
 
-        // general query
-        let mode = match sort_by {
-            SnarkSortByInput::BlockHeightAsc => speedb::IteratorMode::Start,
-            SnarkSortByInput::BlockHeightDesc => speedb::IteratorMode::End,
+    // block height bounded query
+    let (min, max) = if query.as_ref().map_or(false, |q| {
+        q.block_height_gt.is_some()
+            || q.block_height_gte.is_some()
+            || q.block_height_lt.is_some()
+            || q.block_height_lte.is_some()
+    }) {
+        let SnarkQueryInput {
+            block_height_gt,
+            block_height_gte,
+            block_height_lt,
+            block_height_lte,
+            ..
+        } = query.as_ref().expect("query will contain a value");
+        let min_bound = match (*block_height_gte, *block_height_gt) {
+            (Some(gte), Some(gt)) => std::cmp::max(gte, gt + 1),
+            (Some(gte), None) => gte,
+            (None, Some(gt)) => gt + 1,
+            (None, None) => 1,
         };
 
-        'outer: for (key, _) in db.blocks_height_iterator(mode).flatten() {
-            let state_hash = block_state_hash_from_key(&key)?;
-            let block = db.get_block(&state_hash)?.expect("block to be returned").0;
-            let canonical = get_block_canonicity(db, &state_hash.0);
-            let snark_work = db.get_snark_work_in_block(&state_hash)?;
-            let snarks_with_canonicity = snark_work.map_or(vec![], |summaries| {
-                summaries
-                    .into_iter()
-                    .map(|snark| SnarkWithCanonicity {
-                        canonical,
-                        pcb: block.clone(),
-                        snark: (
-                            snark,
-                            state_hash.clone(),
-                            db.get_snarks_epoch_count(None).expect("epoch snarks count"),
-                            db.get_snarks_total_count().expect("total snarks count"),
-                        )
-                            .into(),
-                    })
-                    .collect()
-            });
-
-            for sw in snarks_with_canonicity {
-                if query.as_ref().map_or(true, |q| q.matches(&sw)) {
-                    snarks.push(sw);
-
-                    if snarks.len() == limit {
-                        break 'outer;
-                    }
-                }
+        let max_bound = match (*block_height_lte, *block_height_lt) {
+            (Some(lte), Some(lt)) => std::cmp::min(lte, lt - 1),
+            (Some(lte), None) => lte,
+            (None, Some(lt)) => lt - 1,
+            (None, None) => db.get_best_block_height()?.unwrap(),
+        };
+        (min_bound, max_bound)
+    } else {
+        // general query
+        (0, u32::MAX)
+    };
+
+    let start = match direction {
+        speedb::Direction::Forward => to_be_bytes(min),
+        speedb::Direction::Reverse => to_be_bytes(max.saturating_add(1)),
+    };
+    let mode = speedb::IteratorMode::From(page.seek_group(&start), direction);
+    'outer: for (key, _) in db.blocks_height_iterator(mode).flatten() {
+        page.check_deadline()?;
+        let height = block_u32_prefix_from_key(&key)?;
+        if height < min || height > max {
+            break;
+        }
+
+        let state_hash = block_state_hash_from_key(&key)?;
+        let block = cache
+            .get(db, &state_hash)?
+            .with_context(|| format!("block missing from store {state_hash}"))?;
+        let canonical = get_block_canonicity(db, &state_hash.0);
+        let snark_work = db.get_snark_work_in_block(&state_hash)?;
+        let snarks_with_canonicity = snark_work.map_or(vec![], |summaries| {
+            summaries
+                .into_iter()
+                .map(|snark| SnarkWithCanonicity {
+                    canonical,
+                    pcb: block.clone(),
+                    snark: (snark, state_hash.clone(), counts.0, counts.1).into(),
+                })
+                .collect()
+        });
+
+        for (n, sw) in snarks_with_canonicity.into_iter().enumerate() {
+            if query.as_ref().map_or(true, |q| q.matches(&sw)) && page.push_nth(&key, n as u32, sw)
+            {
+                break 'outer;
             }
         }
-        Ok(snarks)
     }
+    Ok(())
 }
 
 fn snark_summary_matches_query(
     db: &Arc<IndexerStore>,
     query: &Option<SnarkQueryInput>,
+    block: &Arc<PrecomputedBlock>,
     snark: SnarkWorkSummaryWithStateHash,
-) -> anyhow::Result<Option<SnarkWithCanonicity>> {
-    let canonical = get_block_canonicity(db, &snark.state_hash);
-    Ok(db
-        .get_block(&snark.state_hash.clone().into())?
-        .and_then(|(block, _)| {
-            let snark_with_canonicity = SnarkWithCanonicity {
-                pcb: block,
-                canonical,
-                snark: (
-                    snark,
-                    db.get_snarks_epoch_count(None).expect("epoch snarks count"),
-                    db.get_snarks_total_count().expect("total snarks count"),
-                )
-                    .into(),
-            };
-            if query
-                .as_ref()
-                .map_or(true, |q| q.matches(&snark_with_canonicity))
-            {
-                Some(snark_with_canonicity)
-            } else {
-                None
-            }
-        }))
+    (epoch_num_snarks, total_num_snarks): (u32, u32),
+) -> Option<SnarkWithCanonicity> {
+    let snark_with_canonicity = SnarkWithCanonicity {
+        pcb: block.clone(),
+        canonical: get_block_canonicity(db, &snark.state_hash),
+        snark: (snark, epoch_num_snarks, total_num_snarks).into(),
+    };
+    query
+        .as_ref()
+        .map_or(true, |q| q.matches(&snark_with_canonicity))
+        .then_some(snark_with_canonicity)
 }
 
 impl From<(SnarkWorkSummary, BlockHash, u32, u32)> for Snark {
@@ -1,4 +1,4 @@
-use super::db;
+use super::{db, limits::list_limit};
 use crate::ledger::{account::Account, store::LedgerStore};
 use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
 use rust_decimal::{prelude::ToPrimitive, Decimal};
@@ -27,7 +27,10 @@ pub struct StagedLedgerQueryRoot;
 #[Object]
 impl StagedLedgerQueryRoot {
     // Cache for 1 hour
-    #[graphql(cache_control(max_age = 3600))]
+    #[graphql(
+        cache_control(max_age = 3600),
+        complexity = "limit.saturating_mul(child_complexity)"
+    )]
     async fn staged_ledger_accounts<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -36,6 +39,7 @@ impl StagedLedgerQueryRoot {
         #[graphql(default = 100)] limit: usize,
     ) -> Result<Option<Vec<StagedLedgerAccount>>> {
         let db = db(ctx);
+        let limit = list_limit(ctx, limit);
 
         // ledger hash query
         if let Some(ledger_hash) = query.as_ref().and_then(|q| q.ledger_hash.clone()) {
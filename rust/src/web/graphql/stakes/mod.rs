@@ -13,7 +13,11 @@ use crate::{
         username::UsernameStore,
         IndexerStore,
     },
-    web::graphql::Timing,
+    web::graphql::{
+        limits::list_limit,
+        pagination::{Page, SortKeyConnection},
+        Timing,
+    },
 };
 use async_graphql::{ComplexObject, Context, Enum, InputObject, Object, Result, SimpleObject};
 use rust_decimal::{prelude::ToPrimitive, Decimal};
@@ -51,7 +55,10 @@ pub struct StakeQueryRoot;
 #[Object]
 impl StakeQueryRoot {
     // Cache for 1 day
-    #[graphql(cache_control(max_age = 86400))]
+    #[graphql(
+        cache_control(max_age = 86400),
+        complexity = "limit.saturating_mul(child_complexity)"
+    )]
     async fn stakes<'ctx>(
         &self,
         ctx: &Context<'ctx>,
@@ -61,13 +68,6 @@ impl StakeQueryRoot {
     ) -> Result<Vec<StakesLedgerAccountWithMeta>> {
         let db = db(ctx);
 
-        // default to current epoch
-        let curr_epoch = db.get_current_epoch()?;
-        let epoch = match query {
-            Some(ref query) => query.epoch.unwrap_or(curr_epoch),
-            None => curr_epoch,
-        };
-
         // short-circuited epoch number query
         if limit == 0 {
             if let Some(ledger_hash) = query.as_ref().and_then(|q| q.ledger_hash.clone()) {
@@ -82,79 +82,121 @@ impl StakeQueryRoot {
             }
         }
 
-        // if ledger hash is provided as a query input, use it for the ledger
-        // otherwise, use the provided or current epoch number
-        let staking_ledger = {
-            let opt = if let Some((Some(ledger_hash), query_epoch)) =
-                query.as_ref().map(|q| (q.ledger_hash.clone(), q.epoch))
-            {
-                db.get_staking_ledger_by_hash(&ledger_hash.into(), query_epoch, None)?
-            } else {
-                db.get_staking_ledger_at_epoch(epoch, None)?
-            };
-            match opt {
-                Some(staking_ledger) => staking_ledger,
-                None => return Ok(vec![]),
-            }
+        let mut page = Page::list(list_limit(ctx, limit)).with_deadline(ctx);
+        stakes_page(db, query.as_ref(), sort_by, &mut page)?;
+        Ok(page.into_nodes())
+    }
+
+    /// Value staking ledger accounts connection, paged by `first` & `after`
+    #[graphql(
+        cache_control(max_age = 86400),
+        complexity = "first.saturating_mul(child_complexity)"
+    )]
+    async fn stakes_connection<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        query: Option<StakeQueryInput>,
+        sort_by: Option<StakeSortByInput>,
+        #[graphql(default = 100)] first: usize,
+        after: Option<String>,
+    ) -> Result<SortKeyConnection<StakesLedgerAccountWithMeta>> {
+        let mut page = Page::new(list_limit(ctx, first), after)?.with_deadline(ctx);
+        let num_accounts = stakes_page(db(ctx), query.as_ref(), sort_by, &mut page)?;
+        let total_count = match query {
+            Some(StakeQueryInput {
+                delegate: Some(_), ..
+            })
+            | Some(StakeQueryInput {
+                public_key: Some(_),
+                ..
+            }) => None,
+            _ => num_accounts,
         };
+        Ok(page.into_connection(total_count))
+    }
+}
 
-        // Delegations will be present if the staking ledger is
-        // (use the staking ledger's epoch)
-        let epoch = staking_ledger.epoch;
-        let ledger_hash = staking_ledger.ledger_hash.clone().0;
-        let delegations = db.get_delegations_epoch(epoch, &None)?.unwrap();
-
-        // balance- & stake-sorted queries
-        let mut accounts = Vec::new();
-        let iter = match sort_by {
-            Some(StakeSortByInput::StakeDesc) | None => {
-                db.staking_ledger_stake_iterator(IteratorMode::From(
-                    &staking_ledger_sort_key(epoch, u64::MAX, ""),
-                    Direction::Reverse,
-                ))
-            }
-            Some(StakeSortByInput::StakeAsc) => db.staking_ledger_stake_iterator(
-                IteratorMode::From(&staking_ledger_sort_key(epoch, 0, ""), Direction::Forward),
-            ),
-            Some(StakeSortByInput::BalanceDesc) => {
-                db.staking_ledger_balance_iterator(IteratorMode::From(
-                    &staking_ledger_sort_key(epoch, u64::MAX, ""),
-                    Direction::Reverse,
-                ))
-            }
-            Some(StakeSortByInput::BalanceAsc) => db.staking_ledger_balance_iterator(
-                IteratorMode::From(&staking_ledger_sort_key(epoch, 0, ""), Direction::Forward),
-            ),
+/// Collect the page of staking ledger accounts matching the query, returns
+/// the number of accounts in the staking ledger
+fn stakes_page(
+    db: &Arc<IndexerStore>,
+    query: Option<&StakeQueryInput>,
+    sort_by: Option<StakeSortByInput>,
+    page: &mut Page<StakesLedgerAccountWithMeta>,
+) -> Result<Option<u32>> {
+    // default to current epoch
+    let curr_epoch = db.get_current_epoch()?;
+    let epoch = match query {
+        Some(query) => query.epoch.unwrap_or(curr_epoch),
+        None => curr_epoch,
+    };
+
+    // if ledger hash is provided as a query input, use it for the ledger
+    // otherwise, use the provided or current epoch number
+    let staking_ledger = {
+        let opt = if let Some((Some(ledger_hash), query_epoch)) =
+            query.map(|q| (q.ledger_hash.clone(), q.epoch))
+        {
+            db.get_staking_ledger_by_hash(&ledger_hash.into(), query_epoch, None)?
+        } else {
+            db.get_staking_ledger_at_epoch(epoch, None)?
         };
+        match opt {
+            Some(staking_ledger) => staking_ledger,
+            None => return Ok(None),
+        }
+    };
+
+    // Delegations will be present if the staking ledger is
+    // (use the staking ledger's epoch)
+    let epoch = staking_ledger.epoch;
+    let ledger_hash = staking_ledger.ledger_hash.clone().0;
+    let delegations = db.get_delegations_epoch(epoch, &None)?.unwrap();
+
+    // balance- & stake-sorted queries
+    let (start, direction) = match sort_by {
+        Some(StakeSortByInput::StakeDesc) | Some(StakeSortByInput::BalanceDesc) | None => (
+            staking_ledger_sort_key(epoch, u64::MAX, ""),
+            Direction::Reverse,
+        ),
+        Some(StakeSortByInput::StakeAsc) | Some(StakeSortByInput::BalanceAsc) => {
+            (staking_ledger_sort_key(epoch, 0, ""), Direction::Forward)
+        }
+    };
+    let mode = IteratorMode::From(page.seek(&start), direction);
+    let iter = match sort_by {
+        Some(StakeSortByInput::StakeDesc) | Some(StakeSortByInput::StakeAsc) | None => {
+            db.staking_ledger_stake_iterator(mode)
+        }
+        Some(StakeSortByInput::BalanceDesc) | Some(StakeSortByInput::BalanceAsc) => {
+            db.staking_ledger_balance_iterator(mode)
+        }
+    };
 
-        for (key, value) in iter.flatten() {
-            let key_epoch = staking_ledger_sort_key_epoch(&key);
-            if key_epoch != epoch {
-                break;
-            }
-            let account: StakingAccount = serde_json::from_slice(&value)?;
-            if StakeQueryInput::matches_staking_account(
-                query.as_ref(),
-                &account,
-                &ledger_hash,
-                epoch,
-            ) {
-                accounts.push(StakesLedgerAccountWithMeta::new(
+    for (key, value) in iter.flatten() {
+        page.check_deadline()?;
+        let key_epoch = staking_ledger_sort_key_epoch(&key);
+        if key_epoch != epoch {
+            break;
+        }
+        let account: StakingAccount = serde_json::from_slice(&value)?;
+        if StakeQueryInput::matches_staking_account(query, &account, &ledger_hash, epoch)
+            && page.push(
+                &key,
+                StakesLedgerAccountWithMeta::new(
                     db,
                     account,
                     &delegations,
                     staking_ledger.epoch,
                     staking_ledger.ledger_hash.0.clone(),
                     staking_ledger.total_currency,
-                ));
-
-                if accounts.len() == limit {
-                    break;
-                }
-            }
+                ),
+            )
+        {
+            break;
         }
-        Ok(accounts)
     }
+    Ok(Some(staking_ledger.staking_ledger.len() as u32))
 }
 
 #[derive(SimpleObject, Default)]
@@ -0,0 +1,229 @@
+use super::{
+    blocks::{block_counts, Block},
+    db,
+    transactions::Transaction,
+};
+use crate::{
+    block::{store::BlockStore, BlockHash},
+    command::{signed::SignedCommandWithData, store::UserCommandStore},
+    event::{broadcast::EventSender, db::*, IndexerEvent, SeqEvent},
+    ledger::public_key::{is_valid_public_key, PublicKey},
+    store::IndexerStore,
+};
+use async_graphql::{
+    futures_util::{future, stream, Stream, StreamExt},
+    Context, Result, SimpleObject, Subscription,
+};
+use log::{error, warn};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+#[derive(Default)]
+pub struct SubscriptionRoot;
+
+#[derive(SimpleObject)]
+pub struct BestTipChange {
+    /// Value new best tip state hash
+    state_hash: String,
+
+    /// Value new best tip blockchain length
+    blockchain_length: u32,
+
+    /// Value previous best tip state hash
+    previous_state_hash: Option<String>,
+
+    /// Value number of blocks of the previous best chain which are not
+    /// ancestors of the new best tip (0 for an extension)
+    reorg_depth: u32,
+
+    /// Value new best tip block
+    block: Option<Block>,
+}
+
+#[derive(SimpleObject)]
+pub struct StakingLedgerAdded {
+    /// Value staking ledger epoch
+    epoch: u32,
+
+    /// Value staking ledger hash
+    ledger_hash: String,
+
+    /// Value genesis state hash
+    genesis_state_hash: String,
+}
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// Blocks added to the witness tree
+    async fn new_block(&self, ctx: &Context<'_>) -> impl Stream<Item = Block> {
+        let db = db(ctx).clone();
+        events(ctx).filter_map(move |event| {
+            future::ready(match event.event {
+                IndexerEvent::Db(DbEvent::Block(DbBlockEvent::NewBlock { state_hash, .. })) => {
+                    block(&db, &state_hash)
+                }
+                _ => None,
+            })
+        })
+    }
+
+    /// Best tip updates with the depth of the reorg they cause
+    async fn best_tip_changed(&self, ctx: &Context<'_>) -> impl Stream<Item = BestTipChange> {
+        let db = db(ctx).clone();
+        let best_tip = db.get_best_block_hash().ok().flatten();
+        events(ctx)
+            .scan(best_tip, move |best_tip, event| {
+                let change = match event.event {
+                    IndexerEvent::Db(DbEvent::Block(DbBlockEvent::NewBestTip {
+                        state_hash,
+                        blockchain_length,
+                    })) => {
+                        let reorg_depth = match best_tip.as_ref() {
+                            Some(previous) => db
+                                .get_reorged_blocks(previous, &state_hash)
+                                .map(|reorged| reorged.len() as u32)
+                                .unwrap_or_else(|e| {
+                                    error!("Failed to compute reorg depth for {state_hash}: {e}");
+                                    0
+                                }),
+                            None => 0,
+                        };
+                        let change = BestTipChange {
+                            block: block(&db, &state_hash),
+                            state_hash: state_hash.0.clone(),
+                            blockchain_length,
+                            previous_state_hash: best_tip.take().map(|hash| hash.0),
+                            reorg_depth,
+                        };
+                        *best_tip = Some(state_hash);
+                        Some(change)
+                    }
+                    _ => None,
+                };
+                future::ready(Some(change))
+            })
+            .filter_map(future::ready)
+    }
+
+    /// Blocks which become canonical
+    async fn new_canonical_block(&self, ctx: &Context<'_>) -> impl Stream<Item = Block> {
+        let db = db(ctx).clone();
+        events(ctx).filter_map(move |event| {
+            future::ready(match event.event {
+                IndexerEvent::Db(DbEvent::Canonicity(DbCanonicityEvent::NewCanonicalBlock {
+                    state_hash,
+                    ..
+                })) => block(&db, &state_hash),
+                _ => None,
+            })
+        })
+    }
+
+    /// User commands of newly added blocks, optionally involving `public_key`
+    async fn new_transaction(
+        &self,
+        ctx: &Context<'_>,
+        public_key: Option<String>,
+    ) -> Result<impl Stream<Item = Transaction>> {
+        let public_key: Option<PublicKey> = match public_key {
+            Some(pk) if !is_valid_public_key(&pk) => {
+                return Err(async_graphql::Error::new(format!(
+                    "Invalid public key: {pk}"
+                )))
+            }
+            pk => pk.map(Into::into),
+        };
+
+        let db = db(ctx).clone();
+        Ok(events(ctx).flat_map(move |event| {
+            let transactions = match event.event {
+                IndexerEvent::Db(DbEvent::Block(DbBlockEvent::NewBlock { state_hash, .. })) => {
+                    transactions(&db, &state_hash, public_key.as_ref())
+                }
+                _ => vec![],
+            };
+            stream::iter(transactions)
+        }))
+    }
+
+    /// Staking ledgers added to the store
+    async fn staking_ledger_added(
+        &self,
+        ctx: &Context<'_>,
+    ) -> impl Stream<Item = StakingLedgerAdded> {
+        events(ctx).filter_map(|event| {
+            future::ready(match event.event {
+                IndexerEvent::Db(DbEvent::StakingLedger(
+                    DbStakingLedgerEvent::NewStakingLedger {
+                        epoch,
+                        ledger_hash,
+                        genesis_state_hash,
+                    },
+                )) => Some(StakingLedgerAdded {
+                    epoch,
+                    ledger_hash: ledger_hash.0,
+                    genesis_state_hash: genesis_state_hash.0,
+                }),
+                _ => None,
+            })
+        })
+    }
+}
+
+/// Stream of newly recorded events
+///
+/// Lagging subscribers skip the events they missed
+fn events(ctx: &Context<'_>) -> impl Stream<Item = SeqEvent> {
+    let receiver = ctx
+        .data::<EventSender>()
+        .expect("Event sender should be in the context")
+        .subscribe();
+    stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((event, receiver)),
+                Err(RecvError::Lagged(num_skipped)) => {
+                    warn!("Subscriber lagged behind, skipped {num_skipped} events")
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+fn block(db: &Arc<IndexerStore>, state_hash: &BlockHash) -> Option<Block> {
+    let block = || -> anyhow::Result<Option<Block>> {
+        let counts = block_counts(db)?;
+        Ok(db
+            .get_block(state_hash)?
+            .map(|(block, _)| Block::from_precomputed(db, &block, counts)))
+    };
+    block().unwrap_or_else(|e| {
+        error!("Failed to get block {state_hash}: {e}");
+        None
+    })
+}
+
+fn transactions(
+    db: &Arc<IndexerStore>,
+    state_hash: &BlockHash,
+    public_key: Option<&PublicKey>,
+) -> Vec<Transaction> {
+    let transactions = || -> anyhow::Result<Vec<Transaction>> {
+        let block = match db.get_block(state_hash)? {
+            Some((block, _)) => block,
+            None => return Ok(vec![]),
+        };
+        let epoch_num_user_commands = db.get_user_commands_epoch_count(None)?;
+        let total_num_user_commands = db.get_user_commands_total_count()?;
+        Ok(SignedCommandWithData::from_precomputed(&block)
+            .into_iter()
+            .filter(|cmd| public_key.map_or(true, |pk| cmd.command.contains_public_key(pk)))
+            .map(|cmd| Transaction::new(cmd, db, epoch_num_user_commands, total_num_user_commands))
+            .collect())
+    };
+    transactions().unwrap_or_else(|e| {
+        error!("Failed to get transactions of block {state_hash}: {e}");
+        vec![]
+    })
+}
@@ -1,11 +1,12 @@
 use super::{date_time_to_scalar, db, get_block_canonicity, PK};
 use crate::{
-    block::store::BlockStore,
+    block::{store::BlockStore, BlockHash},
     command::{
         decode_memo,
         signed::{self, SignedCommand, SignedCommandWithData},
         store::{
-            user_commands_iterator_state_hash, user_commands_iterator_txn_hash, UserCommandStore,
+            user_commands_iterator_state_hash, user_commands_iterator_txn_hash,
+            user_commands_iterator_u32_prefix, UserCommandStore,
         },
         CommandStatusData,
     },
@@ -17,10 +18,19 @@ use crate::{
         pk_key_prefix, pk_txn_sort_key_prefix, state_hash_pk_txn_sort_key, to_be_bytes,
         txn_hash_of_key, IndexerStore,
     },
-    web::graphql::{gen::TransactionQueryInput, DateTime},
+    web::graphql::{
+        filter::{Filter, Filterable},
+        gen::TransactionQueryInput,
+        limits::list_limit,
+        loaders::{block_cache, block_canonicity, PrecomputedBlockCache},
+        pagination::{Page, SortKeyConnection},
+        DateTime,
+    },
 };
 use anyhow::Context as aContext;
-use async_graphql::{Context, Enum, Object, Result, SimpleObject};
+use async_graphql::{ComplexObject, Context, Enum, Object, Result, SimpleObject};
+use serde::Serialize;
+use serde_json::{json, Value};
 use speedb::{Direction, IteratorMode};
 use std::sync::Arc;
 
@@ -38,17 +48,22 @@ impl TransactionsQueryRoot {
         let epoch_num_user_commands = db.get_user_commands_epoch_count(None)?;
         let total_num_user_commands = db.get_user_commands_total_count()?;
 
-        if let Some(hash) = query.hash {
-            if signed::is_valid_tx_hash(&hash) {
-                return Ok(db.get_user_command(&hash, 0)?.map(|cmd| {
-                    Transaction::new(cmd, db, epoch_num_user_commands, total_num_user_commands)
-                }));
+        if let Some(hash) = query.hash.as_ref() {
+            if signed::is_valid_tx_hash(hash) {
+                if let Some(cmd) = db.get_user_command(hash, 0)? {
+                    let txn =
+                        Transaction::new(cmd, db, epoch_num_user_commands, total_num_user_commands);
+                    if Filter::new(&query).matches(&txn)? {
+                        return Ok(Some(txn));
+                    }
+                }
             }
         }
 
         Ok(None)
     }
 
+    #[graphql(complexity = "limit.saturating_mul(child_complexity)")]
     pub async fn transactions(
         &self,
         ctx: &Context<'_>,
@@ -56,241 +71,292 @@ impl TransactionsQueryRoot {
         #[graphql(default = 100)] limit: usize,
         sort_by: Option<TransactionSortByInput>,
     ) -> Result<Vec<Transaction>> {
-        let db = db(ctx);
-        let epoch_num_user_commands = db.get_user_commands_epoch_count(None)?;
-        let total_num_user_commands = db.get_user_commands_total_count()?;
-
-        let sort_by = sort_by.unwrap_or(TransactionSortByInput::BlockHeightDesc);
+        let mut page = Page::list(list_limit(ctx, limit)).with_deadline(ctx);
+        transactions_page(db(ctx), block_cache(ctx), query, sort_by, &mut page)?;
+        Ok(page.into_nodes())
+    }
 
-        // transaction filtered by state hash
-        if let Some(state_hash) = query
-            .as_ref()
-            .and_then(|input| input.block.as_ref())
-            .and_then(|block| block.state_hash.clone())
-        {
-            let query = query.expect("query input to exists");
-            let mut transactions: Vec<Transaction> = db
-                .get_block(&state_hash.into())?
-                .into_iter()
-                .flat_map(|(b, _)| SignedCommandWithData::from_precomputed(&b))
-                .map(|cmd| {
-                    Transaction::new(cmd, db, epoch_num_user_commands, total_num_user_commands)
-                })
-                .filter(|txn| query.matches(txn))
-                .collect();
-            reorder_asc(&mut transactions, sort_by);
-            transactions.truncate(limit);
-            return Ok(transactions);
-        }
+    /// Value transactions connection, paged by `first` & `after`
+    #[graphql(complexity = "first.saturating_mul(child_complexity)")]
+    pub async fn transactions_connection(
+        &self,
+        ctx: &Context<'_>,
+        query: Option<TransactionQueryInput>,
+        sort_by: Option<TransactionSortByInput>,
+        #[graphql(default = 100)] first: usize,
+        after: Option<String>,
+    ) -> Result<SortKeyConnection<Transaction>> {
+        let db = db(ctx);
+        let total_count = match query {
+            None => Some(db.get_user_commands_total_count()?),
+            Some(_) => None,
+        };
+        let mut page = Page::new(list_limit(ctx, first), after)?.with_deadline(ctx);
+        transactions_page(db, block_cache(ctx), query, sort_by, &mut page)?;
+        Ok(page.into_connection(total_count))
+    }
+}
 
-        // txn hash query (no state hash)
-        if let Some(txn_hash) = query.as_ref().and_then(|input| input.hash.clone()) {
-            let query = query.expect("query input to exists");
-            let mut transactions: Vec<Transaction> = vec![];
-            if let Ok(Some(state_hashes)) = db.get_user_command_state_hashes(&txn_hash) {
-                transactions = state_hashes
-                    .iter()
-                    .flat_map(|state_hash| db.get_block(state_hash).expect("block"))
-                    .flat_map(|(b, _)| SignedCommandWithData::from_precomputed(&b))
-                    .map(|cmd| {
-                        Transaction::new(cmd, db, epoch_num_user_commands, total_num_user_commands)
-                    })
-                    .filter(|txn| query.matches(txn))
-                    .collect();
-                transactions.truncate(limit);
+/// Collect the page of transactions matching the query
+fn transactions_page(
+    db: &Arc<IndexerStore>,
+    cache: &PrecomputedBlockCache,
+    query: Option<TransactionQueryInput>,
+    sort_by: Option<TransactionSortByInput>,
+    page: &mut Page<Transaction>,
+) -> Result<()> {
+    let epoch_num_user_commands = db.get_user_commands_epoch_count(None)?;
+    let total_num_user_commands = db.get_user_commands_total_count()?;
+
+    let sort_by = sort_by.unwrap_or(TransactionSortByInput::BlockHeightDesc);
+    let filter = Filter::new(&query);
+
+    // transaction filtered by state hash
+    if let Some(state_hash) = query
+        .as_ref()
+        .and_then(|input| input.block.as_ref())
+        .and_then(|block| block.state_hash.clone())
+    {
+        let mut transactions = vec![];
+        let state_hash = BlockHash::from(state_hash);
+        let user_commands = match db.get_pruned_block(&state_hash)? {
+            Some(pruned) => pruned.user_commands,
+            None => cache
+                .get(db, &state_hash)?
+                .map(|block| SignedCommandWithData::from_precomputed(&block))
+                .unwrap_or_default(),
+        };
+        for cmd in user_commands {
+            let txn = Transaction::new(cmd, db, epoch_num_user_commands, total_num_user_commands);
+            if filter.matches(&txn)? {
+                transactions.push(txn);
             }
-            return Ok(transactions);
         }
+        reorder_asc(&mut transactions, sort_by);
+        return page.extend(transactions);
+    }
 
-        // block height query
-        if let Some(block_height) = query.as_ref().and_then(|input| input.block_height) {
-            let mut transactions = vec![];
-            let query = query.expect("query input to exists");
-            'outer: for state_hash in db.get_blocks_at_height(block_height)? {
-                let block = db
-                    .get_block(&state_hash)
-                    .with_context(|| format!("block missing from store {state_hash}"))
-                    .unwrap()
-                    .unwrap()
-                    .0;
+    // txn hash query (no state hash)
+    if let Some(txn_hashes) = query.as_ref().and_then(|input| {
+        input.hash.clone().map(|hash| vec![hash]).or_else(|| {
+            input
+                .hash_in
+                .as_ref()
+                .map(|in_| in_.iter().flatten().cloned().collect())
+        })
+    }) {
+        let mut transactions = vec![];
+        for txn_hash in txn_hashes {
+            for state_hash in db
+                .get_user_command_state_hashes(&txn_hash)
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+            {
+                let block = cache
+                    .get(db, &state_hash)?
+                    .with_context(|| format!("block missing from store {state_hash}"))?;
                 for cmd in SignedCommandWithData::from_precomputed(&block) {
                     let txn =
                         Transaction::new(cmd, db, epoch_num_user_commands, total_num_user_commands);
-                    if query.matches(&txn) {
+                    if filter.matches(&txn)? {
                         transactions.push(txn);
-                        if transactions.len() == limit {
-                            break 'outer;
-                        }
                     }
                 }
             }
-            reorder_asc(&mut transactions, sort_by);
-            return Ok(transactions);
         }
+        return page.extend(transactions);
+    }
 
-        // iterator mode & direction determined by desired sorting
-        let mut transactions = Vec::new();
-        let (start_slot, direction) = match sort_by {
-            TransactionSortByInput::BlockHeightAsc | TransactionSortByInput::DateTimeAsc => {
-                (0, Direction::Forward)
-            }
-            TransactionSortByInput::BlockHeightDesc | TransactionSortByInput::DateTimeDesc => {
-                (u32::MAX, Direction::Reverse)
-            }
-        };
-
-        // from/to account (sender/receiver) query
-        if query
-            .as_ref()
-            .map_or(false, |q| q.from.as_ref().or(q.to.as_ref()).is_some())
-        {
-            let query = query.expect("query input to exisist");
-            let pk = query
-                .from
-                .as_ref()
-                .or(query.to.as_ref())
-                .expect("pk to exist");
-            let start = pk_txn_sort_key_prefix((pk as &str).into(), start_slot);
-            let mode = IteratorMode::From(&start, direction);
-            let txn_iter = if query.from.is_some() {
-                db.txn_from_height_iterator(mode).flatten()
-            } else {
-                db.txn_to_height_iterator(mode).flatten()
-            };
-            for (key, _) in txn_iter {
-                // public key bytes
-                let txn_pk = pk_key_prefix(&key);
-                if txn_pk.0 != *pk {
-                    break;
-                }
-                let txn_state_hash = state_hash_pk_txn_sort_key(&key);
-                let txn_hash = txn_hash_of_key(&key);
-                let cmd = db
-                    .get_user_command_state_hash(&txn_hash, &txn_state_hash)?
-                    .expect("command at txn hash and state hash");
+    // block height query
+    if let Some(block_height) = query.as_ref().and_then(|input| input.block_height) {
+        let mut transactions = vec![];
+        for state_hash in db.get_blocks_at_height(block_height)? {
+            let block = cache
+                .get(db, &state_hash)?
+                .with_context(|| format!("block missing from store {state_hash}"))?;
+            for cmd in SignedCommandWithData::from_precomputed(&block) {
                 let txn =
                     Transaction::new(cmd, db, epoch_num_user_commands, total_num_user_commands);
-
-                // include matching txns
-                if query.matches(&txn) {
+                if filter.matches(&txn)? {
                     transactions.push(txn);
-
-                    if transactions.len() == limit {
-                        break;
-                    }
-                };
+                }
             }
-            return Ok(transactions);
         }
+        reorder_asc(&mut transactions, sort_by);
+        return page.extend(transactions);
+    }
 
-        // block height bounded query
-        if query.as_ref().map_or(false, |q| {
-            q.block_height_gt.is_some()
-                || q.block_height_gte.is_some()
-                || q.block_height_lt.is_some()
-                || q.block_height_lte.is_some()
-        }) {
-            let query = query.expect("query input to exists");
-            let (min, max) = {
-                let TransactionQueryInput {
-                    block_height_gt,
-                    block_height_gte,
-                    block_height_lt,
-                    block_height_lte,
-                    ..
-                } = query;
-                let min_bound = match (block_height_gte, block_height_gt) {
-                    (Some(gte), Some(gt)) => std::cmp::max(gte, gt + 1),
-                    (Some(gte), None) => gte,
-                    (None, Some(gt)) => gt + 1,
-                    (None, None) => 1,
-                };
-
-                let max_bound = match (block_height_lte, block_height_lt) {
-                    (Some(lte), Some(lt)) => std::cmp::min(lte, lt - 1),
-                    (Some(lte), None) => lte,
-                    (None, Some(lt)) => lt - 1,
-                    (None, None) => db.get_best_block_height()?.unwrap(),
-                };
-                (min_bound, max_bound)
-            };
-
-            let mut block_heights: Vec<u32> = (min..=max).collect();
-            if sort_by == TransactionSortByInput::BlockHeightDesc {
-                block_heights.reverse();
-            }
-            let mut transactions = Vec::new();
-
-            'outer: for height in block_heights {
-                for state_hash in db.get_blocks_at_height(height)? {
-                    let block = db
-                        .get_block(&state_hash)
-                        .with_context(|| format!("block missing from store {state_hash}"))
-                        .unwrap()
-                        .unwrap()
-                        .0;
-                    for cmd in SignedCommandWithData::from_precomputed(&block) {
-                        let txn = Transaction::new(
-                            cmd,
-                            db,
-                            epoch_num_user_commands,
-                            total_num_user_commands,
-                        );
-
-                        if query.matches(&txn) {
-                            transactions.push(txn);
-                            if transactions.len() == limit {
-                                break 'outer;
-                            }
-                        }
-                    }
-                }
-            }
-            return Ok(transactions);
+    // iterator mode & direction determined by desired sorting
+    let (start_slot, direction) = match sort_by {
+        TransactionSortByInput::BlockHeightAsc | TransactionSortByInput::DateTimeAsc => {
+            (0, Direction::Forward)
         }
+        TransactionSortByInput::BlockHeightDesc | TransactionSortByInput::DateTimeDesc => {
+            (u32::MAX, Direction::Reverse)
+        }
+    };
+
+    // from/to account (sender/receiver) query
+    if query
+        .as_ref()
+        .map_or(false, |q| q.from.as_ref().or(q.to.as_ref()).is_some())
+    {
+        let query = query.expect("query input to exisist");
+        let pk = query
+            .from
+            .as_ref()
+            .or(query.to.as_ref())
+            .expect("pk to exist");
+        let start = pk_txn_sort_key_prefix((pk as &str).into(), start_slot);
+        let mode = IteratorMode::From(page.seek(&start), direction);
+        let txn_iter = if query.from.is_some() {
+            db.txn_from_height_iterator(mode).flatten()
+        } else {
+            db.txn_to_height_iterator(mode).flatten()
+        };
+        for (key, _) in txn_iter {
+            page.check_deadline()?;
+            // public key bytes
+            let txn_pk = pk_key_prefix(&key);
+            if txn_pk.0 != *pk {
+                break;
+            }
+            let txn_state_hash = state_hash_pk_txn_sort_key(&key);
+            let txn_hash = txn_hash_of_key(&key);
+            let cmd = db
+                .get_user_command_state_hash(&txn_hash, &txn_state_hash)?
+                .expect("command at txn hash and state hash");
+            let txn = Transaction::new(cmd, db, epoch_num_user_commands, total_num_user_commands);
+
+            // include matching txns
+            if filter.matches(&txn)? && page.push(&key, txn) {
+                break;
+            }
+        }
+        return Ok(());
+    }
 
-        let iter = match sort_by {
-            TransactionSortByInput::BlockHeightAsc => {
-                db.user_commands_height_iterator(IteratorMode::From(&[0], Direction::Forward))
-            }
-            TransactionSortByInput::BlockHeightDesc => db.user_commands_height_iterator(
-                IteratorMode::From(&to_be_bytes(u32::MAX), Direction::Reverse),
-            ),
-            TransactionSortByInput::DateTimeAsc => {
-                db.user_commands_slot_iterator(IteratorMode::From(&[0], Direction::Forward))
-            }
-            TransactionSortByInput::DateTimeDesc => db.user_commands_slot_iterator(
-                IteratorMode::From(&to_be_bytes(u32::MAX), Direction::Reverse),
-            ),
+    // block height/global slot bounded query, served by the matching index
+    let height_bounds = query.as_ref().and_then(|q| {
+        u32_bounds(
+            q.block_height_gt,
+            q.block_height_gte,
+            q.block_height_lt,
+            q.block_height_lte,
+        )
+    });
+    let slot_bounds = query.as_ref().and_then(|q| {
+        u32_bounds(
+            q.global_slot_gt,
+            q.global_slot_gte,
+            q.global_slot_lt,
+            q.global_slot_lte,
+        )
+    });
+    if let Some(((min, max), by_height)) = height_bounds
+        .map(|bounds| (bounds, true))
+        .or(slot_bounds.map(|bounds| (bounds, false)))
+    {
+        if min > max {
+            return Ok(());
+        }
+        let start = match direction {
+            Direction::Forward => to_be_bytes(min),
+            Direction::Reverse => match max.checked_add(1) {
+                Some(max) => to_be_bytes(max),
+                None => vec![u8::MAX; 5],
+            },
+        };
+        let mode = IteratorMode::From(page.seek(&start), direction);
+        let iter = if by_height {
+            db.user_commands_height_iterator(mode)
+        } else {
+            db.user_commands_slot_iterator(mode)
         };
         for (key, _) in iter.flatten() {
-            if let Some(ref q) = query {
-                // early exit if txn hashes don't match if we're filtering by it
-                if q.hash.is_some() && q.hash != user_commands_iterator_txn_hash(&key).ok() {
-                    continue;
-                }
+            page.check_deadline()?;
+            let prefix = user_commands_iterator_u32_prefix(&key);
+            if prefix < min || prefix > max {
+                break;
             }
             let txn_hash = user_commands_iterator_txn_hash(&key)?;
             let state_hash = user_commands_iterator_state_hash(&key)?;
             let txn = Transaction::new(
                 db.get_user_command_state_hash(&txn_hash, &state_hash)?
-                    .unwrap(),
+                    .expect("command at txn hash and state hash"),
                 db,
                 epoch_num_user_commands,
                 total_num_user_commands,
             );
-
-            if query.as_ref().map_or(true, |q| q.matches(&txn)) {
-                transactions.push(txn);
-
-                if transactions.len() == limit {
-                    break;
-                }
-            };
+            if filter.matches(&txn)? && page.push(&key, txn) {
+                break;
+            }
         }
+        return Ok(());
+    }
 
-        Ok(transactions)
+    let start = match direction {
+        Direction::Forward => vec![0],
+        Direction::Reverse => to_be_bytes(u32::MAX),
+    };
+    let mode = IteratorMode::From(page.seek(&start), direction);
+    let iter = match sort_by {
+        TransactionSortByInput::BlockHeightAsc | TransactionSortByInput::BlockHeightDesc => {
+            db.user_commands_height_iterator(mode)
+        }
+        TransactionSortByInput::DateTimeAsc | TransactionSortByInput::DateTimeDesc => {
+            db.user_commands_slot_iterator(mode)
+        }
+    };
+    for (key, _) in iter.flatten() {
+        page.check_deadline()?;
+        if let Some(ref q) = query {
+            // early exit if txn hashes don't match if we're filtering by it
+            if q.hash.is_some() && q.hash != user_commands_iterator_txn_hash(&key).ok() {
+                continue;
+            }
+        }
+        let txn_hash = user_commands_iterator_txn_hash(&key)?;
+        let state_hash = user_commands_iterator_state_hash(&key)?;
+        let txn = Transaction::new(
+            db.get_user_command_state_hash(&txn_hash, &state_hash)?
+                .unwrap(),
+            db,
+            epoch_num_user_commands,
+            total_num_user_commands,
+        );
+
+        if filter.matches(&txn)? && page.push(&key, txn) {
+            break;
+        }
     }
+    Ok(())
+}
+
+/// Inclusive range of the `_gt`/`_gte`/`_lt`/`_lte` bounds, if any
+fn u32_bounds(
+    gt: Option<u32>,
+    gte: Option<u32>,
+    lt: Option<u32>,
+    lte: Option<u32>,
+) -> Option<(u32, u32)> {
+    gt.or(gte).or(lt).or(lte)?;
+    let gt = match gt.map(|gt| gt.checked_add(1)) {
+        Some(None) => return Some((1, 0)),
+        gt => gt.flatten(),
+    };
+    let min = gt.into_iter().chain(gte).max().unwrap_or(0);
+    let max = match lt {
+        Some(0) => return Some((1, 0)),
+        lt => lt
+            .map(|lt| lt - 1)
+            .into_iter()
+            .chain(lte)
+            .min()
+            .unwrap_or(u32::MAX),
+    };
+    Some((min, max))
 }
 
 fn reorder_asc<T>(values: &mut [T], sort_by: TransactionSortByInput) {
@@ -303,7 +369,7 @@ fn reorder_asc<T>(values: &mut [T], sort_by: TransactionSortByInput) {
 }
 
 impl Transaction {
-    fn new(
+    pub(crate) fn new(
         cmd: SignedCommandWithData,
         db: &Arc<IndexerStore>,
         epoch_num_user_commands: u32,
@@ -390,271 +456,44 @@ impl TransactionWithoutBlock {
     }
 }
 
-impl TransactionQueryInput {
-    fn matches(&self, transaction_with_block: &Transaction) -> bool {
-        let transaction = transaction_with_block.transaction.clone();
-        let TransactionQueryInput {
-            hash,
-            canonical,
-            kind,
-            memo,
-            from,
-            to,
-            fee,
-            fee_gt,
-            fee_gte,
-            fee_lt,
-            fee_lte,
-            fee_token,
-            amount,
-            amount_gt,
-            amount_gte,
-            amount_lte,
-            amount_lt,
-            block_height,
-            block_height_gt,
-            block_height_gte,
-            block_height_lt,
-            block_height_lte,
-            global_slot,
-            global_slot_gt,
-            global_slot_gte,
-            global_slot_lt,
-            global_slot_lte,
-            date_time,
-            date_time_gt,
-            date_time_gte,
-            date_time_lt,
-            date_time_lte,
-            nonce,
-            nonce_lte,
-            nonce_gt,
-            nonce_lt,
-            nonce_gte,
-            and,
-            or,
-            block,
-            fee_payer: _,
-            source: _,
-            from_account: _,
-            receiver: _,
-            to_account: _,
-            token: _,
-            is_delegation: _,
-        } = self;
-        if let Some(state_hash) = block.as_ref().and_then(|b| b.state_hash.clone()) {
-            if transaction_with_block.block.state_hash != state_hash {
-                return false;
-            }
-        }
-        if let Some(hash) = hash {
-            if transaction.hash != *hash {
-                return false;
-            }
-        }
-        if let Some(kind) = kind {
-            if transaction.kind != *kind {
-                return false;
-            }
-        }
-        if let Some(canonical) = canonical {
-            if transaction.canonical != *canonical {
-                return false;
-            }
-        }
-        if let Some(from) = from {
-            if transaction.from != *from {
-                return false;
-            }
-        }
-        if let Some(to) = to {
-            if transaction.to != *to {
-                return false;
-            }
-        }
-        if let Some(memo) = memo {
-            if transaction.memo != *memo {
-                return false;
-            }
-        }
-        if let Some(fee_token) = fee_token {
-            if transaction.token != Some(*fee_token) {
-                return false;
-            }
-        }
-        if let Some(query) = and {
-            if query.iter().all(|and| and.matches(transaction_with_block)) {
-                return false;
-            }
-        }
-        if let Some(query) = or {
-            if !query.is_empty() && query.iter().any(|or| or.matches(transaction_with_block)) {
-                return false;
-            }
-        }
-
-        // amount
-        if let Some(amount) = amount {
-            if transaction_with_block.transaction.amount != *amount {
-                return false;
-            }
-        }
-        if let Some(amount_gt) = amount_gt {
-            if transaction_with_block.transaction.amount <= *amount_gt {
-                return false;
-            }
-        }
-        if let Some(amount_gte) = amount_gte {
-            if transaction_with_block.transaction.amount < *amount_gte {
-                return false;
-            }
-        }
-        if let Some(amount_lt) = amount_lt {
-            if transaction_with_block.transaction.amount >= *amount_lt {
-                return false;
-            }
-        }
-        if let Some(amount_lte) = amount_lte {
-            if transaction_with_block.transaction.amount > *amount_lte {
-                return false;
-            }
-        }
-
-        // fee
-        if let Some(fee) = fee {
-            if transaction.fee != *fee {
-                return false;
-            }
-        }
-        if let Some(fee_gt) = fee_gt {
-            if transaction_with_block.transaction.fee <= *fee_gt {
-                return false;
-            }
-        }
-        if let Some(fee_gte) = fee_gte {
-            if transaction_with_block.transaction.fee < *fee_gte {
-                return false;
-            }
-        }
-        if let Some(fee_lt) = fee_lt {
-            if transaction_with_block.transaction.fee >= *fee_lt {
-                return false;
-            }
-        }
-        if let Some(fee_lte) = fee_lte {
-            if transaction_with_block.transaction.fee > *fee_lte {
-                return false;
-            }
-        }
-
-        // block height
-        if let Some(block_height) = block_height {
-            if transaction_with_block.transaction.block_height != *block_height {
-                return false;
-            }
-        }
-        if let Some(block_height_gt) = block_height_gt {
-            if transaction_with_block.transaction.block_height <= *block_height_gt {
-                return false;
-            }
-        }
-        if let Some(block_height_gte) = block_height_gte {
-            if transaction_with_block.transaction.block_height < *block_height_gte {
-                return false;
-            }
-        }
-        if let Some(block_height_lt) = block_height_lt {
-            if transaction_with_block.transaction.block_height >= *block_height_lt {
-                return false;
-            }
-        }
-        if let Some(block_height_lte) = block_height_lte {
-            if transaction_with_block.transaction.block_height > *block_height_lte {
-                return false;
-            }
-        }
-
-        // global slot
-        if let Some(global_slot) = global_slot {
-            if transaction_with_block.transaction.global_slot != *global_slot {
-                return false;
-            }
-        }
-        if let Some(global_slot_gt) = global_slot_gt {
-            if transaction_with_block.transaction.global_slot <= *global_slot_gt {
-                return false;
-            }
-        }
-        if let Some(global_slot_gte) = global_slot_gte {
-            if transaction_with_block.transaction.global_slot < *global_slot_gte {
-                return false;
-            }
-        }
-        if let Some(global_slot_lt) = global_slot_lt {
-            if transaction_with_block.transaction.global_slot >= *global_slot_lt {
-                return false;
-            }
-        }
-        if let Some(global_slot_lte) = global_slot_lte {
-            if transaction_with_block.transaction.global_slot > *global_slot_lte {
-                return false;
-            }
-        }
-
-        // date time
-        if let Some(date_time) = date_time {
-            if transaction_with_block.block.date_time != *date_time {
-                return false;
-            }
-        }
-        if let Some(date_time_gt) = date_time_gt {
-            if transaction_with_block.block.date_time < *date_time_gt {
-                return false;
-            }
-        }
-        if let Some(date_time_gte) = date_time_gte {
-            if transaction_with_block.block.date_time < *date_time_gte {
-                return false;
-            }
-        }
-        if let Some(date_time_lt) = date_time_lt {
-            if transaction_with_block.block.date_time >= *date_time_lt {
-                return false;
-            }
-        }
-        if let Some(date_time_lte) = date_time_lte {
-            if transaction_with_block.block.date_time > *date_time_lte {
-                return false;
-            }
-        }
+impl Filterable for Transaction {
+    const NAME: &'static str = "Transaction";
+
+    fn filter_fields(&self) -> Value {
+        let mut fields = self
+            .transaction
+            .filter_fields(&self.block.state_hash, &self.block.date_time.0);
+        fields["block"] = json!({
+            "state_hash": self.block.state_hash,
+            "date_time": self.block.date_time,
+            "canonical": self.transaction.canonical,
+            "block_height": self.transaction.block_height,
+            "global_slot_since_genesis": self.transaction.global_slot,
+        });
+        fields
+    }
+}
 
-        // nonce
-        if let Some(nonce) = nonce {
-            if transaction_with_block.transaction.nonce != *nonce {
-                return false;
-            }
-        }
-        if let Some(nonce_gt) = nonce_gt {
-            if transaction_with_block.transaction.nonce <= *nonce_gt {
-                return false;
-            }
-        }
-        if let Some(nonce_gte) = nonce_gte {
-            if transaction_with_block.transaction.nonce < *nonce_gte {
-                return false;
-            }
-        }
-        if let Some(nonce_lt) = nonce_lt {
-            if transaction_with_block.transaction.nonce >= *nonce_lt {
-                return false;
-            }
-        }
-        if let Some(nonce_lte) = nonce_lte {
-            if transaction_with_block.transaction.nonce > *nonce_lte {
-                return false;
-            }
+impl TransactionWithoutBlock {
+    /// Fields of the user command as seen by filters, including the
+    /// MinaExplorer fields derived from the command & its block
+    pub(crate) fn filter_fields(&self, block_state_hash: &str, date_time: &str) -> Value {
+        let account = |public_key: &str| json!({ "public_key": public_key, "token": self.token });
+        let mut fields = serde_json::to_value(self).unwrap_or_default();
+        if let Value::Object(map) = &mut fields {
+            map.insert("block_state_hash".into(), block_state_hash.into());
+            map.insert("date_time".into(), date_time.into());
+            map.insert(
+                "is_delegation".into(),
+                (self.kind == "STAKE_DELEGATION").into(),
+            );
+            map.insert("fee_token".into(), json!(self.token));
+            map.insert("source".into(), account(&self.from));
+            map.insert("fee_payer".into(), account(&self.from));
+            map.insert("from_account".into(), account(&self.from));
+            map.insert("to_account".into(), account(&self.to));
         }
-        true
+        fields
     }
 }
 
@@ -671,7 +510,7 @@ pub enum TransactionSortByInput {
     DateTimeDesc,
 }
 
-#[derive(Clone, Debug, SimpleObject)]
+#[derive(Clone, Debug, SimpleObject, Serialize)]
 pub struct TransactionWithoutBlock {
     amount: u64,
     block_height: u32,
@@ -696,15 +535,34 @@ pub struct TransactionWithoutBlock {
     total_num_user_commands: u32,
 }
 
-#[derive(Clone, Debug, SimpleObject)]
+#[derive(Clone, Debug, SimpleObject, Serialize)]
+#[graphql(complex)]
 pub struct Transaction {
     block: TransactionBlock,
 
     #[graphql(flatten)]
+    #[serde(flatten)]
     transaction: TransactionWithoutBlock,
 }
 
-#[derive(Clone, Debug, PartialEq, SimpleObject)]
+#[ComplexObject]
+impl Transaction {
+    /// Value number of blocks on top of the containing block in the best chain
+    async fn confirmations(&self, ctx: &Context<'_>) -> Result<Option<u32>> {
+        Ok(block_canonicity(ctx, &self.block.state_hash)
+            .await?
+            .confirmations)
+    }
+
+    /// Value is final
+    async fn is_final(&self, ctx: &Context<'_>) -> Result<bool> {
+        Ok(block_canonicity(ctx, &self.block.state_hash)
+            .await?
+            .is_final)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, SimpleObject, Serialize)]
 struct TransactionBlock {
     date_time: DateTime,
     state_hash: String,
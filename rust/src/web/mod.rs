@@ -1,18 +1,40 @@
 pub mod graphql;
 pub mod rest;
+pub mod rosetta;
 
-pub const ENDPOINT_GRAPHQL: &str = "/graphql";
+pub use mina_indexer_client::ENDPOINT_GRAPHQL;
+pub const ENDPOINT_DAEMON_GRAPHQL: &str = "/daemon-graphql";
+pub const ENDPOINT_METRICS: &str = "/metrics";
+pub const ENDPOINT_HEALTH: &str = "/health";
+pub const ENDPOINT_READY: &str = "/ready";
 
 use self::{
-    graphql::{build_schema, indexer_graphiql},
-    rest::{accounts, blockchain, blocks, locked_balances::LockedBalances},
+    graphql::{
+        daemon::{build_daemon_schema, DaemonSchema},
+        daemon_graphiql, indexer_graphiql,
+        limits::GraphQLLimits,
+        IndexerSchema,
+    },
+    rest::{
+        accounts, blockchain, blocks, internal_commands, ledgers, locked_balances::LockedBalances,
+        openapi, reorgs, snarks, staking_ledgers, transactions, usernames, NetworkParams,
+    },
+    rosetta::broadcast::Broadcaster,
+};
+use crate::{
+    health::IndexerHealth,
+    ledger::genesis::GenesisConstants,
+    metrics::metrics,
+    networks::{IndexedNetwork, IndexedNetworks},
+    store::IndexerStore,
 };
-use crate::store::IndexerStore;
 use actix_cors::Cors;
-use actix_web::{guard, middleware, web, web::Data, App, HttpServer};
-use async_graphql_actix_web::GraphQL;
-use log::warn;
-use std::{net, sync::Arc};
+use actix_web::{
+    dev::Service, guard, middleware, web, web::Data, App, HttpRequest, HttpResponse, HttpServer,
+};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
+use log::{error, warn};
+use std::{net, sync::Arc, time::Instant};
 use tokio_graceful_shutdown::{FutureExt, SubsystemHandle};
 
 fn load_locked_balances() -> LockedBalances {
@@ -25,31 +47,198 @@ fn load_locked_balances() -> LockedBalances {
     }
 }
 
+/// Schema of the network selected by the `network` query param
+///
+/// Each network has its own schema over its own store, so the network is
+/// selected before the query is parsed, by the same query param as the REST
+/// endpoints, rather than by a GraphQL argument
+fn network_schema(
+    req: &HttpRequest,
+    networks: &IndexedNetworks,
+) -> actix_web::Result<IndexerSchema> {
+    let params = web::Query::<NetworkParams>::from_query(req.query_string())?;
+    networks
+        .get(params.network.as_deref())
+        .map(|network| network.schema)
+        .map_err(actix_web::error::ErrorNotFound)
+}
+
+/// GraphQL queries & mutations
+async fn graphql(
+    networks: Data<IndexedNetworks>,
+    req: HttpRequest,
+    request: GraphQLRequest,
+) -> actix_web::Result<GraphQLResponse> {
+    let schema = network_schema(&req, &networks)?;
+    Ok(schema.execute(request.into_inner()).await.into())
+}
+
+/// Mina daemon compatible GraphQL queries, of the primary network only
+pub async fn daemon_graphql(
+    networks: Data<IndexedNetworks>,
+    schema: Data<DaemonSchema>,
+    req: HttpRequest,
+    request: GraphQLRequest,
+) -> actix_web::Result<GraphQLResponse> {
+    let params = web::Query::<NetworkParams>::from_query(req.query_string())?;
+    let network = networks
+        .get(params.network.as_deref())
+        .map_err(actix_web::error::ErrorNotFound)?;
+    let primary = networks.primary();
+    if network.name != primary.name {
+        return Err(actix_web::error::ErrorBadRequest(format!(
+            "{ENDPOINT_DAEMON_GRAPHQL} only serves the primary network {}",
+            primary.name
+        )));
+    }
+    Ok(schema.execute(request.into_inner()).await.into())
+}
+
+/// GraphQL subscriptions over WebSocket (graphql-ws & graphql-transport-ws)
+async fn graphql_subscription(
+    networks: Data<IndexedNetworks>,
+    req: HttpRequest,
+    payload: web::Payload,
+) -> actix_web::Result<HttpResponse> {
+    GraphQLSubscription::new(network_schema(&req, &networks)?).start(&req, payload)
+}
+
+/// Prometheus metrics in text format
+async fn prometheus_metrics(store: Data<Arc<IndexerStore>>) -> HttpResponse {
+    match metrics().gather(&store) {
+        Ok(body) => HttpResponse::Ok()
+            .content_type("text/plain; version=0.0.4")
+            .body(body),
+        Err(e) => {
+            error!("Error gathering metrics: {e}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Liveness, 503 if the store can't be read
+async fn health(store: Data<Arc<IndexerStore>>, health: Data<Arc<IndexerHealth>>) -> HttpResponse {
+    let status = health.health(&store);
+    if status.healthy {
+        HttpResponse::Ok().json(status)
+    } else {
+        HttpResponse::ServiceUnavailable().json(status)
+    }
+}
+
+/// Readiness, 503 while syncing or lagging behind the wall-clock slot
+async fn ready(store: Data<Arc<IndexerStore>>, health: Data<Arc<IndexerHealth>>) -> HttpResponse {
+    match health.readiness(&store) {
+        Ok(status) if status.ready => HttpResponse::Ok().json(status),
+        Ok(status) => HttpResponse::ServiceUnavailable().json(status),
+        Err(e) => {
+            error!("Error checking readiness: {e}");
+            HttpResponse::ServiceUnavailable().finish()
+        }
+    }
+}
+
+/// Serves REST & GraphQL queries of the indexed networks, selected by the
+/// `network` query param. Rosetta, the daemon GraphQL API, metrics & health
+/// are the primary network's, as are the `genesis_constants`.
 pub async fn start_web_server<A: net::ToSocketAddrs>(
     subsys: SubsystemHandle,
-    state: Arc<IndexerStore>,
+    networks: IndexedNetworks,
+    genesis_constants: GenesisConstants,
+    graphql_limits: GraphQLLimits,
+    broadcaster: Option<Arc<dyn Broadcaster>>,
     addrs: A,
 ) -> anyhow::Result<()> {
     let locked = Arc::new(load_locked_balances());
+    let IndexedNetwork {
+        store: state,
+        health,
+        ..
+    } = networks.primary();
+    let daemon_schema = build_daemon_schema(state.clone(), genesis_constants, graphql_limits);
 
     let _ = HttpServer::new(move || {
-        App::new()
+        let app = App::new()
             .app_data(Data::new(state.clone()))
+            .app_data(Data::new(networks.clone()))
             .app_data(Data::new(locked.clone()))
-            .service(blocks::get_blocks)
+            .app_data(Data::new(health.clone()))
+            .app_data(Data::new(daemon_schema.clone()));
+
+        // Rosetta submissions are rejected without a broadcaster
+        let app = match &broadcaster {
+            Some(broadcaster) => app.app_data(Data::new(broadcaster.clone())),
+            None => app,
+        };
+        app.service(blocks::get_blocks)
             .service(blocks::get_block)
             .service(accounts::get_account)
             .service(blockchain::get_blockchain_summary)
+            .service(reorgs::get_reorgs)
+            .service(reorgs::get_reorg_stats)
+            .service(transactions::get_transactions)
+            .service(transactions::get_transaction)
+            .service(transactions::get_account_transactions)
+            .service(transactions::get_block_transactions)
+            .service(internal_commands::get_internal_commands)
+            .service(internal_commands::get_account_internal_commands)
+            .service(internal_commands::get_block_internal_commands)
+            .service(snarks::get_account_snarks)
+            .service(snarks::get_block_snarks)
+            .service(staking_ledgers::get_staking_ledger)
+            .service(staking_ledgers::get_staking_ledger_account)
+            .service(staking_ledgers::get_staking_delegations)
+            .service(usernames::get_username)
+            .service(ledgers::get_ledger)
+            .service(openapi::get_openapi)
+            .service(openapi::get_docs)
+            .configure(rosetta::configure)
             .service(
                 web::resource(ENDPOINT_GRAPHQL)
                     .guard(guard::Post())
-                    .to(GraphQL::new(build_schema(state.clone()))),
+                    .to(graphql),
+            )
+            .service(
+                web::resource(ENDPOINT_GRAPHQL)
+                    .guard(guard::Get())
+                    .guard(guard::Header("upgrade", "websocket"))
+                    .to(graphql_subscription),
             )
             .service(
                 web::resource(ENDPOINT_GRAPHQL)
                     .guard(guard::Get())
                     .to(indexer_graphiql),
             )
+            .service(
+                web::resource(ENDPOINT_DAEMON_GRAPHQL)
+                    .guard(guard::Post())
+                    .to(daemon_graphql),
+            )
+            .service(
+                web::resource(ENDPOINT_DAEMON_GRAPHQL)
+                    .guard(guard::Get())
+                    .to(daemon_graphiql),
+            )
+            .route(ENDPOINT_METRICS, web::get().to(prometheus_metrics))
+            .route(ENDPOINT_HEALTH, web::get().to(self::health))
+            .route(ENDPOINT_READY, web::get().to(ready))
+            .wrap_fn(|req, srv| {
+                // requests are labelled by route pattern to bound cardinality
+                let start = Instant::now();
+                let method = req.method().to_string();
+                let route = req.match_pattern().unwrap_or_else(|| "unmatched".into());
+                let response = srv.call(req);
+                async move {
+                    let response = response.await?;
+                    metrics().observe_http_request(
+                        &method,
+                        &route,
+                        response.status().as_u16(),
+                        start.elapsed(),
+                    );
+                    Ok(response)
+                }
+            })
             .wrap(Cors::permissive())
             .wrap(middleware::Logger::default())
     })
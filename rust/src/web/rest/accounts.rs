@@ -1,39 +1,72 @@
+use super::{
+    pagination::{json_response, not_found},
+    NetworkParams, NetworkStore,
+};
 use crate::{
     block::store::BlockStore,
     command::{internal::store::InternalCommandStore, store::UserCommandStore},
-    ledger::{account, public_key::PublicKey, store::LedgerStore},
+    ledger::{public_key::PublicKey, store::LedgerStore},
     snark_work::store::SnarkStore,
-    store::IndexerStore,
 };
 use actix_web::{
     get,
-    http::header::ContentType,
-    web::{self, Data},
+    web::{self},
     HttpResponse,
 };
 use log::debug;
-use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Best ledger account & its activity counts
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Account {
+    pub public_key: String,
+    pub username: Option<String>,
+
+    /// Balance (nanomina)
+    pub balance: u64,
+    pub nonce: u32,
+    pub delegate: String,
+    pub genesis_account: bool,
+    pub voting_for: Option<String>,
+    pub token: Option<u64>,
+    pub timing: Option<AccountTiming>,
+    pub epoch_num_blocks: u32,
+    pub total_num_blocks: u32,
+    pub epoch_num_snarks: u32,
+    pub total_num_snarks: u32,
+    pub epoch_num_user_commands: u32,
+    pub total_num_user_commands: u32,
+    pub epoch_num_internal_commands: u32,
+    pub total_num_internal_commands: u32,
+}
+
+/// Vesting schedule of a time-locked account
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AccountTiming {
+    /// Initial minimum balance (nanomina)
+    pub initial_minimum_balance: u64,
+    pub cliff_time: u32,
+
+    /// Cliff amount (nanomina)
+    pub cliff_amount: u64,
+    pub vesting_period: u32,
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct Account {
-    #[serde(flatten)]
-    account: account::Account,
-    epoch_num_blocks: u32,
-    total_num_blocks: u32,
-    epoch_num_snarks: u32,
-    total_num_snarks: u32,
-    epoch_num_user_commands: u32,
-    total_num_user_commands: u32,
-    epoch_num_internal_commands: u32,
-    total_num_internal_commands: u32,
+    /// Vesting increment (nanomina)
+    pub vesting_increment: u64,
 }
 
+/// Account in the best ledger
+#[utoipa::path(
+    tag = "accounts",
+    params(("public_key" = String, Path, description = "Account public key"), NetworkParams),
+    responses(
+        (status = 200, body = Account),
+        (status = 404, description = "Account not found", body = ErrorResponse),
+    )
+)]
 #[get("/accounts/{public_key}")]
-pub async fn get_account(
-    store: Data<Arc<IndexerStore>>,
-    public_key: web::Path<String>,
-) -> HttpResponse {
+pub async fn get_account(store: NetworkStore, public_key: web::Path<String>) -> HttpResponse {
     let db = store.as_ref();
     if let Ok(Some(ledger)) = db.get_best_ledger() {
         debug!("Found best ledger");
@@ -42,7 +75,21 @@ pub async fn get_account(
         if let Some(account) = account {
             debug!("Found account in ledger: {:?}", account);
             let account = Account {
-                account: account.clone(),
+                public_key: account.public_key.0.clone(),
+                username: account.username.as_ref().map(|username| username.0.clone()),
+                balance: account.balance.0,
+                nonce: account.nonce.map_or(0, |nonce| nonce.0),
+                delegate: account.delegate.0.clone(),
+                genesis_account: account.genesis_account,
+                voting_for: account.voting_for.as_ref().map(|hash| hash.0.clone()),
+                token: account.token,
+                timing: account.timing.as_ref().map(|timing| AccountTiming {
+                    initial_minimum_balance: timing.initial_minimum_balance,
+                    cliff_time: timing.cliff_time,
+                    cliff_amount: timing.cliff_amount,
+                    vesting_period: timing.vesting_period,
+                    vesting_increment: timing.vesting_increment,
+                }),
                 epoch_num_blocks: db
                     .get_block_production_pk_epoch_count(pk, None)
                     .unwrap_or_default(),
@@ -64,11 +111,8 @@ pub async fn get_account(
                     .get_internal_commands_pk_total_count(pk)
                     .unwrap_or_default(),
             };
-            let body = serde_json::to_string_pretty(&account).unwrap();
-            return HttpResponse::Ok()
-                .content_type(ContentType::json())
-                .body(body);
+            return json_response(&account);
         }
     }
-    HttpResponse::NotFound().finish()
+    not_found(format!("Account {public_key} not found"))
 }
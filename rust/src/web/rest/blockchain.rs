@@ -1,3 +1,7 @@
+use super::{
+    pagination::{json_response, not_found},
+    NetworkParams, NetworkStore,
+};
 use crate::{
     block::{precomputed::PrecomputedBlock, store::BlockStore},
     chain::store::ChainStore,
@@ -11,18 +15,18 @@ use crate::{
     store::{
         account::AccountStore,
         version::{IndexerStoreVersion, VersionStore},
-        IndexerStore,
     },
     web::rest::locked_balances::LockedBalances,
 };
-use actix_web::{get, http::header::ContentType, web::Data, HttpResponse};
+use actix_web::{get, web::Data, HttpResponse};
 use chrono::DateTime;
 use log::trace;
 use serde::Serialize;
 use std::sync::Arc;
+use utoipa::ToSchema;
 
 /// Returns blockchain summary information about the current chain
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockchainSummary {
     blockchain_length: u32,
@@ -178,9 +182,18 @@ fn calculate_summary(input: SummaryInput) -> Option<BlockchainSummary> {
     })
 }
 
+/// Summary of the best chain & the indexer
+#[utoipa::path(
+    tag = "blockchain",
+    params(NetworkParams),
+    responses(
+        (status = 200, body = BlockchainSummary),
+        (status = 404, description = "No best tip", body = ErrorResponse),
+    )
+)]
 #[get("/summary")]
 pub async fn get_blockchain_summary(
-    store: Data<Arc<IndexerStore>>,
+    store: NetworkStore,
     locked_balances: Data<Arc<LockedBalances>>,
 ) -> HttpResponse {
     let db = store.as_ref();
@@ -241,11 +254,8 @@ pub async fn get_blockchain_summary(
             total_num_accounts,
         }) {
             trace!("Blockchain summary: {summary:?}");
-            let body = serde_json::to_string_pretty(summary).expect("blockchain summary");
-            return HttpResponse::Ok()
-                .content_type(ContentType::json())
-                .body(body);
+            return json_response(summary);
         }
     }
-    HttpResponse::NotFound().finish()
+    not_found("Best tip not found")
 }
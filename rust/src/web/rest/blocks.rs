@@ -1,22 +1,27 @@
+use super::{
+    pagination::{internal_error, json_response, not_found},
+    NetworkParams, NetworkStore,
+};
 use crate::{
     block::{
-        is_valid_state_hash, precomputed::PrecomputedBlock, store::BlockStore, BlockWithoutHeight,
+        is_valid_state_hash, precomputed::PrecomputedBlock, store::BlockStore, BlockHash,
+        BlockWithoutHeight,
     },
-    canonicity::store::CanonicityStore,
+    canonicity::{self, store::CanonicityStore},
     store::IndexerStore,
 };
 use actix_web::{
     get,
-    http::header::ContentType,
-    web::{self, Data},
+    web::{self},
     HttpResponse,
 };
-use anyhow::Context as aContext;
-use serde::Deserialize;
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct Params {
+    /// Max number of blocks [default: 10, max: 100]
     limit: Option<u32>,
 }
 
@@ -24,15 +29,110 @@ fn get_limit(limit: Option<u32>) -> u32 {
     limit.map(|value| value.min(100)).unwrap_or(10)
 }
 
-fn format_blocks(blocks: Vec<BlockWithoutHeight>) -> String {
-    format!("{blocks:#?}").replace(",\n]", "\n]")
+/// Block summary
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Block {
+    pub canonicity: Option<Canonicity>,
+    pub parent_hash: String,
+    pub state_hash: String,
+    pub blockchain_length: u32,
+    pub global_slot_since_genesis: u32,
+
+    /// Number of blocks on top of the block in the best chain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmations: Option<u32>,
+
+    /// Whether the block has at least k confirmations or is at or below the
+    /// canonical root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_final: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub enum Canonicity {
+    Canonical,
+    Orphaned,
+    Pending,
+}
+
+/// Block via state hash, or the blocks at a blockchain length or slot
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum BlockResponse {
+    Block(Block),
+    Blocks(Vec<Block>),
+}
+
+impl From<canonicity::Canonicity> for Canonicity {
+    fn from(value: canonicity::Canonicity) -> Self {
+        match value {
+            canonicity::Canonicity::Canonical => Self::Canonical,
+            canonicity::Canonicity::Orphaned => Self::Orphaned,
+            canonicity::Canonicity::Pending => Self::Pending,
+        }
+    }
+}
+
+impl From<BlockWithoutHeight> for Block {
+    fn from(value: BlockWithoutHeight) -> Self {
+        Self {
+            canonicity: value.canonicity.map(Into::into),
+            parent_hash: value.parent_hash.0,
+            state_hash: value.state_hash.0,
+            blockchain_length: value.blockchain_length,
+            global_slot_since_genesis: value.global_slot_since_genesis,
+            confirmations: value.confirmations,
+            is_final: value.is_final,
+        }
+    }
+}
+
+/// Add the block's confirmations & finality
+fn with_confirmations(db: &IndexerStore, block: BlockWithoutHeight) -> Block {
+    let confirmations = db
+        .get_block_confirmations(&block.state_hash)
+        .unwrap_or_default();
+    let is_final = db.is_block_final(&block.state_hash).unwrap_or_default();
+    block.with_confirmations(confirmations, is_final).into()
+}
+
+/// Blocks with known canonicity
+fn get_blocks_with_canonicity(
+    db: &IndexerStore,
+    state_hashes: &[BlockHash],
+) -> anyhow::Result<Vec<Block>> {
+    let mut blocks = vec![];
+    for state_hash in state_hashes {
+        if let Some(canonicity) = db.get_block_canonicity(state_hash)? {
+            // pruned blocks keep everything in the summary
+            if let Some(pruned) = db.get_pruned_block(state_hash)? {
+                let block = BlockWithoutHeight {
+                    canonicity: Some(canonicity),
+                    ..(&pruned).into()
+                };
+                blocks.push(with_confirmations(db, block));
+            } else if let Some((block, _)) = db.get_block(state_hash)? {
+                blocks.push(with_confirmations(
+                    db,
+                    BlockWithoutHeight::with_canonicity(&block, canonicity),
+                ));
+            }
+        }
+    }
+    Ok(blocks)
 }
 
+/// Best chain blocks, best tip first
+#[utoipa::path(
+    tag = "blocks",
+    params(Params, NetworkParams),
+    responses(
+        (status = 200, body = Vec<Block>),
+        (status = 404, description = "No best tip", body = ErrorResponse),
+    )
+)]
 #[get("/blocks")]
-pub async fn get_blocks(
-    store: Data<Arc<IndexerStore>>,
-    params: web::Query<Params>,
-) -> HttpResponse {
+pub async fn get_blocks(store: NetworkStore, params: web::Query<Params>) -> HttpResponse {
     let db = store.as_ref();
     let limit = get_limit(params.limit);
 
@@ -54,34 +154,51 @@ pub async fn get_blocks(
             }
         }
 
-        let best_chain: Vec<BlockWithoutHeight> = best_chain
+        let best_chain: Vec<Block> = best_chain
             .iter()
             .flat_map(|block| {
                 if let Ok(Some(canonicity)) = db.get_block_canonicity(&block.state_hash()) {
-                    Some(BlockWithoutHeight::with_canonicity(block, canonicity))
+                    Some(with_confirmations(
+                        db,
+                        BlockWithoutHeight::with_canonicity(block, canonicity),
+                    ))
                 } else {
                     None
                 }
             })
             .collect();
-        return HttpResponse::Ok()
-            .content_type(ContentType::json())
-            .body(format_blocks(best_chain));
+        return json_response(&best_chain);
     }
-    HttpResponse::NotFound().finish()
+    not_found("Best tip not found")
 }
 
+/// Block via state hash, or the blocks at `height=<blockchain length>` or
+/// `slot=<global slot>`
+#[utoipa::path(
+    tag = "blocks",
+    params(
+        ("input" = String, Path, description = "State hash, `height=<blockchain length>` or `slot=<global slot>`"),
+        NetworkParams,
+    ),
+    responses(
+        (status = 200, body = BlockResponse),
+        (status = 404, description = "Block not found", body = ErrorResponse),
+    )
+)]
 #[get("/blocks/{input}")]
-pub async fn get_block(store: Data<Arc<IndexerStore>>, input: web::Path<String>) -> HttpResponse {
+pub async fn get_block(store: NetworkStore, input: web::Path<String>) -> HttpResponse {
     let db = store.as_ref();
 
     // via state hash
     if is_valid_state_hash(&input) {
+        if let Ok(Some(ref block)) = db.get_pruned_block(&input.clone().into()) {
+            let block = with_confirmations(db, block.into());
+            return json_response(&BlockResponse::Block(block));
+        }
+
         if let Ok(Some((ref block, _))) = db.get_block(&input.clone().into()) {
-            let block: BlockWithoutHeight = block.into();
-            return HttpResponse::Ok()
-                .content_type(ContentType::json())
-                .body(format!("{block:?}"));
+            let block = with_confirmations(db, block.into());
+            return json_response(&BlockResponse::Block(block));
         }
     }
 
@@ -90,25 +207,10 @@ pub async fn get_block(store: Data<Arc<IndexerStore>>, input: web::Path<String>)
     if (*input).starts_with(height_prefix) {
         if let Ok(height) = input[height_prefix.len()..].parse::<u32>() {
             if let Ok(blocks) = db.get_blocks_at_height(height) {
-                let blocks: Vec<BlockWithoutHeight> = blocks
-                    .iter()
-                    .flat_map(|state_hash| {
-                        if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
-                            let block = db
-                                .get_block(state_hash)
-                                .with_context(|| format!("block missing from store {state_hash}"))
-                                .unwrap()
-                                .unwrap()
-                                .0;
-                            Some(BlockWithoutHeight::with_canonicity(&block, canonicity))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-                return HttpResponse::Ok()
-                    .content_type(ContentType::json())
-                    .body(format_blocks(blocks));
+                return match get_blocks_with_canonicity(db, &blocks) {
+                    Ok(blocks) => json_response(&BlockResponse::Blocks(blocks)),
+                    Err(e) => internal_error(e),
+                };
             }
         }
     }
@@ -118,28 +220,13 @@ pub async fn get_block(store: Data<Arc<IndexerStore>>, input: web::Path<String>)
     if (*input).starts_with(slot_prefix) {
         if let Ok(slot) = input[slot_prefix.len()..].parse::<u32>() {
             if let Ok(blocks) = db.get_blocks_at_slot(slot) {
-                let blocks: Vec<BlockWithoutHeight> = blocks
-                    .iter()
-                    .flat_map(|state_hash| {
-                        if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
-                            let block = db
-                                .get_block(state_hash)
-                                .with_context(|| format!("block missing from store {state_hash}"))
-                                .unwrap()
-                                .unwrap()
-                                .0;
-                            Some(BlockWithoutHeight::with_canonicity(&block, canonicity))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-                return HttpResponse::Ok()
-                    .content_type(ContentType::json())
-                    .body(format_blocks(blocks));
+                return match get_blocks_with_canonicity(db, &blocks) {
+                    Ok(blocks) => json_response(&BlockResponse::Blocks(blocks)),
+                    Err(e) => internal_error(e),
+                };
             }
         }
     }
 
-    HttpResponse::NotFound().finish()
+    not_found(format!("Block {} not found", *input))
 }
@@ -0,0 +1,194 @@
+use super::{
+    is_canonical,
+    pagination::{
+        bad_request, internal_error, not_found, page_response, paginate, paginate_iterator,
+        PageParams,
+    },
+    NetworkParams, NetworkStore,
+};
+use crate::{
+    block::{is_valid_state_hash, store::BlockStore, BlockHash},
+    command::internal::{store::InternalCommandStore, InternalCommandWithData},
+    constants::millis_to_iso_date_string,
+    ledger::public_key::{is_valid_public_key, PublicKey},
+};
+use actix_web::{
+    get,
+    web::{self},
+    HttpResponse,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Coinbase or fee transfer in a block
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct InternalCommand {
+    /// `Coinbase`, `Fee_transfer` or `Fee_transfer_via_coinbase`
+    pub kind: String,
+
+    /// Fee transfer sender, `None` for coinbases
+    pub sender: Option<String>,
+    pub receiver: String,
+
+    /// Amount (nanomina)
+    pub amount: u64,
+
+    /// Containing block
+    pub state_hash: String,
+    pub block_height: u32,
+    pub date_time: String,
+    pub canonical: bool,
+}
+
+impl InternalCommand {
+    pub fn new(cmd: InternalCommandWithData, canonical: bool) -> Self {
+        match cmd {
+            InternalCommandWithData::FeeTransfer {
+                sender,
+                receiver,
+                amount,
+                state_hash,
+                kind,
+                date_time,
+                block_height,
+            } => Self {
+                kind: kind.to_string(),
+                sender: Some(sender.0),
+                receiver: receiver.0,
+                amount,
+                state_hash: state_hash.0,
+                block_height,
+                date_time: millis_to_iso_date_string(date_time),
+                canonical,
+            },
+            InternalCommandWithData::Coinbase {
+                receiver,
+                amount,
+                state_hash,
+                kind,
+                date_time,
+                block_height,
+            } => Self {
+                kind: kind.to_string(),
+                sender: None,
+                receiver: receiver.0,
+                amount,
+                state_hash: state_hash.0,
+                block_height,
+                date_time: millis_to_iso_date_string(date_time),
+                canonical,
+            },
+        }
+    }
+
+    fn state_hash(cmd: &InternalCommandWithData) -> &BlockHash {
+        match cmd {
+            InternalCommandWithData::FeeTransfer { state_hash, .. }
+            | InternalCommandWithData::Coinbase { state_hash, .. } => state_hash,
+        }
+    }
+}
+
+/// Internal commands of all blocks, highest global slot first
+#[utoipa::path(
+    tag = "internal commands",
+    params(PageParams, NetworkParams),
+    responses(
+        (status = 200, body = InternalCommandPage),
+        (status = 400, description = "Invalid cursor", body = ErrorResponse),
+    )
+)]
+#[get("/internal-commands")]
+pub async fn get_internal_commands(
+    store: NetworkStore,
+    params: web::Query<PageParams>,
+) -> HttpResponse {
+    let db = store.as_ref();
+    page_response(paginate_iterator(
+        |mode| db.internal_commands_global_slot_interator(mode),
+        &params,
+        |_, value| {
+            let cmd: InternalCommandWithData = serde_json::from_slice(value)?;
+            let canonical = is_canonical(db, InternalCommand::state_hash(&cmd))?;
+            Ok((!params.canonical || canonical).then(|| InternalCommand::new(cmd, canonical)))
+        },
+    ))
+}
+
+/// Internal commands involving the account, highest block first
+#[utoipa::path(
+    tag = "internal commands",
+    params(("public_key" = String, Path, description = "Account public key"), PageParams, NetworkParams),
+    responses(
+        (status = 200, body = InternalCommandPage),
+        (status = 400, description = "Invalid public key or cursor", body = ErrorResponse),
+    )
+)]
+#[get("/accounts/{public_key}/internal-commands")]
+pub async fn get_account_internal_commands(
+    store: NetworkStore,
+    public_key: web::Path<String>,
+    params: web::Query<PageParams>,
+) -> HttpResponse {
+    let db = store.as_ref();
+    if !is_valid_public_key(&public_key) {
+        return bad_request(format!("Invalid public key {public_key}"));
+    }
+
+    let pk: PublicKey = public_key.into_inner().into();
+    let internal_commands = || -> anyhow::Result<Vec<InternalCommand>> {
+        let mut internal_commands = vec![];
+        for cmd in db.get_internal_commands_public_key(&pk)?.into_iter().rev() {
+            let canonical = is_canonical(db, InternalCommand::state_hash(&cmd))?;
+            if !params.canonical || canonical {
+                internal_commands.push(InternalCommand::new(cmd, canonical));
+            }
+        }
+        Ok(internal_commands)
+    };
+    page_response(internal_commands().and_then(|cmds| paginate(cmds, &params)))
+}
+
+/// Internal commands of the block, in block order
+#[utoipa::path(
+    tag = "internal commands",
+    params(("state_hash" = String, Path, description = "Block state hash"), PageParams, NetworkParams),
+    responses(
+        (status = 200, body = InternalCommandPage),
+        (status = 400, description = "Invalid state hash or cursor", body = ErrorResponse),
+        (status = 404, description = "Block not found", body = ErrorResponse),
+    )
+)]
+#[get("/blocks/{state_hash}/internal-commands")]
+pub async fn get_block_internal_commands(
+    store: NetworkStore,
+    state_hash: web::Path<String>,
+    params: web::Query<PageParams>,
+) -> HttpResponse {
+    let db = store.as_ref();
+    if !is_valid_state_hash(&state_hash) {
+        return bad_request(format!("Invalid state hash {state_hash}"));
+    }
+
+    let state_hash: BlockHash = state_hash.into_inner().into();
+    let internal_commands = || -> anyhow::Result<Option<Vec<InternalCommand>>> {
+        if db.get_block_height(&state_hash)?.is_none() {
+            return Ok(None);
+        }
+        let canonical = is_canonical(db, &state_hash)?;
+        if params.canonical && !canonical {
+            return Ok(Some(vec![]));
+        }
+        Ok(Some(
+            db.get_internal_commands(&state_hash)?
+                .into_iter()
+                .map(|cmd| InternalCommand::new(cmd, canonical))
+                .collect(),
+        ))
+    };
+    match internal_commands() {
+        Ok(Some(cmds)) => page_response(paginate(cmds, &params)),
+        Ok(None) => not_found(format!("Block {state_hash} not found")),
+        Err(e) => internal_error(e),
+    }
+}
@@ -0,0 +1,147 @@
+use super::{
+    is_canonical,
+    pagination::{
+        bad_request, internal_error, json_response, not_found, paginate, InvalidCursor, PageParams,
+    },
+    NetworkParams, NetworkStore,
+};
+use crate::{
+    block::{is_valid_state_hash, store::BlockStore, BlockHash},
+    canonicity::store::CanonicityStore,
+    ledger::{account, store::LedgerStore},
+    store::IndexerStore,
+};
+use actix_web::{
+    get,
+    web::{self},
+    HttpResponse,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Page of a block's staged ledger accounts
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StagedLedgerPage {
+    /// Block the ledger is associated with
+    pub state_hash: String,
+    pub block_height: u32,
+    pub canonical: bool,
+    pub staged_ledger_hash: Option<String>,
+    pub num_accounts: usize,
+
+    pub data: Vec<LedgerAccount>,
+    pub next_cursor: Option<String>,
+}
+
+/// Staged ledger account
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LedgerAccount {
+    pub public_key: String,
+
+    /// Balance (nanomina)
+    pub balance: u64,
+    pub nonce: u32,
+    pub delegate: String,
+    pub voting_for: Option<String>,
+    pub genesis_account: bool,
+}
+
+impl From<account::Account> for LedgerAccount {
+    fn from(account: account::Account) -> Self {
+        Self {
+            public_key: account.public_key.0,
+            balance: account.balance.0,
+            nonce: account.nonce.map_or(0, |nonce| nonce.0),
+            delegate: account.delegate.0,
+            voting_for: account.voting_for.map(|voting_for| voting_for.0),
+            genesis_account: account.genesis_account,
+        }
+    }
+}
+
+/// Resolve the ledger input to a block state hash
+///
+/// `input` is a state hash, a canonical blockchain length or `best`
+fn resolve_state_hash(db: &IndexerStore, input: &str) -> anyhow::Result<Option<BlockHash>> {
+    if input == "best" {
+        db.get_best_block_hash()
+    } else if let Ok(height) = input.parse::<u32>() {
+        db.get_canonical_hash_at_height(height)
+    } else {
+        Ok(Some(input.to_string().into()))
+    }
+}
+
+/// Staged ledger accounts of the block, highest balance first
+#[utoipa::path(
+    tag = "ledgers",
+    params(
+        ("input" = String, Path, description = "Block state hash, canonical blockchain length or `best`"),
+        PageParams,
+        NetworkParams,
+    ),
+    responses(
+        (status = 200, body = StagedLedgerPage),
+        (status = 400, description = "Invalid input or cursor", body = ErrorResponse),
+        (status = 404, description = "Ledger not found", body = ErrorResponse),
+    )
+)]
+#[get("/ledgers/{input}")]
+pub async fn get_ledger(
+    store: NetworkStore,
+    input: web::Path<String>,
+    params: web::Query<PageParams>,
+) -> HttpResponse {
+    let db = store.as_ref();
+    if input.as_str() != "best" && input.parse::<u32>().is_err() && !is_valid_state_hash(&input) {
+        return bad_request(format!(
+            "Invalid ledger input {input}, expected a state hash, blockchain length or best"
+        ));
+    }
+
+    let page = || -> anyhow::Result<Option<StagedLedgerPage>> {
+        let Some(state_hash) = resolve_state_hash(db, &input)? else {
+            return Ok(None);
+        };
+        let Some(block_height) = db.get_block_height(&state_hash)? else {
+            return Ok(None);
+        };
+        let canonical = is_canonical(db, &state_hash)?;
+        if params.canonical && !canonical {
+            return Ok(None);
+        }
+        let Some(ledger) = db.get_ledger_state_hash(&state_hash, false)? else {
+            return Ok(None);
+        };
+
+        let num_accounts = ledger.accounts.len();
+        let mut accounts: Vec<LedgerAccount> = ledger
+            .accounts
+            .into_values()
+            .map(LedgerAccount::from)
+            .collect();
+        accounts.sort_by(|a, b| {
+            b.balance
+                .cmp(&a.balance)
+                .then_with(|| a.public_key.cmp(&b.public_key))
+        });
+        let page = paginate(accounts, &params)?;
+        Ok(Some(StagedLedgerPage {
+            staged_ledger_hash: db
+                .get_block_staged_ledger_hash(&state_hash)?
+                .map(|ledger_hash| ledger_hash.0),
+            state_hash: state_hash.0,
+            block_height,
+            canonical,
+            num_accounts,
+            data: page.data,
+            next_cursor: page.next_cursor,
+        }))
+    };
+    match page() {
+        Ok(Some(page)) => json_response(&page),
+        Ok(None) => not_found(format!("Ledger {input} not found")),
+        Err(e) if e.is::<InvalidCursor>() => bad_request(e),
+        Err(e) => internal_error(e),
+    }
+}
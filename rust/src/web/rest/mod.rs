@@ -1,4 +1,79 @@
 pub mod accounts;
 pub mod blockchain;
 pub mod blocks;
+pub mod internal_commands;
+pub mod ledgers;
 pub mod locked_balances;
+pub mod openapi;
+pub mod pagination;
+pub mod reorgs;
+pub mod snarks;
+pub mod staking_ledgers;
+pub mod transactions;
+pub mod usernames;
+
+use crate::{
+    block::BlockHash,
+    canonicity::{store::CanonicityStore, Canonicity},
+    networks::IndexedNetworks,
+    store::IndexerStore,
+};
+use actix_web::{
+    dev::Payload,
+    error::InternalError,
+    web::{Data, Query},
+    FromRequest, HttpRequest,
+};
+use serde::Deserialize;
+use std::{
+    future::{ready, Ready},
+    ops::Deref,
+    sync::Arc,
+};
+use utoipa::IntoParams;
+
+/// Network selector accepted by every endpoint
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct NetworkParams {
+    /// Name or genesis state hash of the network [default: primary network]
+    pub network: Option<String>,
+}
+
+/// Store of the network selected by the `network` query param
+pub struct NetworkStore(Arc<IndexerStore>);
+
+impl Deref for NetworkStore {
+    type Target = Arc<IndexerStore>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromRequest for NetworkStore {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(network_store(req))
+    }
+}
+
+fn network_store(req: &HttpRequest) -> Result<NetworkStore, actix_web::Error> {
+    let params = Query::<NetworkParams>::from_query(req.query_string())?;
+    let networks = req
+        .app_data::<Data<IndexedNetworks>>()
+        .expect("indexed networks app data");
+    match networks.get(params.network.as_deref()) {
+        Ok(network) => Ok(NetworkStore(network.store)),
+        Err(e) => {
+            Err(InternalError::from_response(e.to_string(), pagination::not_found(&e)).into())
+        }
+    }
+}
+
+/// Whether the block is canonical
+pub(crate) fn is_canonical(db: &IndexerStore, state_hash: &BlockHash) -> anyhow::Result<bool> {
+    Ok(db.get_block_canonicity(state_hash)? == Some(Canonicity::Canonical))
+}
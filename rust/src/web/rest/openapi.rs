@@ -0,0 +1,111 @@
+//! OpenAPI 3 document of the REST endpoints & its Swagger UI
+
+use super::{
+    accounts, blockchain, blocks, internal_commands, ledgers,
+    pagination::{self, json_response},
+    reorgs, snarks, staking_ledgers, transactions, usernames,
+};
+use crate::{
+    canonicity::reorg::{EpochReorgStats, Reorg, ReorgBlock, ReorgStats, ReorgUserCommand},
+    constants::VERSION,
+};
+use actix_web::{get, http::header::ContentType, HttpResponse};
+use utoipa::OpenApi;
+
+pub const ENDPOINT_OPENAPI: &str = "/openapi.json";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Mina Indexer REST API"),
+    paths(
+        accounts::get_account,
+        blockchain::get_blockchain_summary,
+        blocks::get_blocks,
+        blocks::get_block,
+        internal_commands::get_internal_commands,
+        internal_commands::get_account_internal_commands,
+        internal_commands::get_block_internal_commands,
+        ledgers::get_ledger,
+        reorgs::get_reorgs,
+        reorgs::get_reorg_stats,
+        snarks::get_account_snarks,
+        snarks::get_block_snarks,
+        staking_ledgers::get_staking_ledger,
+        staking_ledgers::get_staking_ledger_account,
+        staking_ledgers::get_staking_delegations,
+        transactions::get_transactions,
+        transactions::get_transaction,
+        transactions::get_account_transactions,
+        transactions::get_block_transactions,
+        usernames::get_username,
+    ),
+    components(schemas(
+        accounts::Account,
+        accounts::AccountTiming,
+        blockchain::BlockchainSummary,
+        blocks::Block,
+        blocks::BlockResponse,
+        blocks::Canonicity,
+        internal_commands::InternalCommand,
+        ledgers::LedgerAccount,
+        ledgers::StagedLedgerPage,
+        pagination::ErrorResponse,
+        pagination::InternalCommandPage,
+        pagination::SnarkPage,
+        pagination::TransactionPage,
+        Reorg,
+        ReorgBlock,
+        ReorgUserCommand,
+        ReorgStats,
+        EpochReorgStats,
+        snarks::Snark,
+        staking_ledgers::StakingAccount,
+        staking_ledgers::StakingDelegation,
+        staking_ledgers::StakingDelegationsPage,
+        staking_ledgers::StakingLedgerPage,
+        transactions::Transaction,
+        usernames::AccountUsername,
+    ))
+)]
+pub struct ApiDoc;
+
+/// OpenAPI document with the indexer's version
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+    doc.info.version = VERSION.to_string();
+    doc.info.description = None;
+    doc.info.license = None;
+    doc
+}
+
+#[get("/openapi.json")]
+pub async fn get_openapi() -> HttpResponse {
+    json_response(&openapi())
+}
+
+/// Swagger UI of the OpenAPI document
+#[get("/docs")]
+pub async fn get_docs() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(format!(
+            r##"<!DOCTYPE html>
+<html lang="en">
+  <head>
+    <meta charset="utf-8" />
+    <title>Mina Indexer REST API</title>
+    <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+  </head>
+  <body>
+    <div id="swagger-ui"></div>
+    <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js" crossorigin></script>
+    <script>
+      window.onload = () => {{
+        window.ui = SwaggerUIBundle({{ url: "{ENDPOINT_OPENAPI}", dom_id: "#swagger-ui" }});
+      }};
+    </script>
+  </body>
+</html>
+"##
+        ))
+}
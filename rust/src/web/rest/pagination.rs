@@ -0,0 +1,243 @@
+//! Cursor-based pagination & JSON responses shared by the REST endpoints
+//!
+//! Cursors are opaque to clients. List endpoints backed by a speedb
+//! iterator encode the sort key of the next entry, others encode the offset
+//! of the next item.
+
+use super::{internal_commands::InternalCommand, snarks::Snark, transactions::Transaction};
+use actix_web::{http::header::ContentType, HttpResponse, HttpResponseBuilder};
+use log::error;
+use serde::{Deserialize, Serialize};
+use speedb::{DBIterator, Direction, IteratorMode};
+use std::{cmp::Ordering, iter::Peekable};
+use utoipa::{IntoParams, ToSchema};
+
+pub const DEFAULT_PAGE_LIMIT: u32 = 10;
+pub const MAX_PAGE_LIMIT: u32 = 100;
+
+/// Max number of entries scanned for a page, including filtered out entries
+pub const MAX_PAGE_SCAN: usize = 10_000;
+
+/// Entry of a speedb iterator
+pub type IteratorEntry = Result<(Box<[u8]>, Box<[u8]>), speedb::Error>;
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageParams {
+    /// Max number of items [default: 10, max: 100]
+    pub limit: Option<u32>,
+
+    /// Cursor of the page, from the previous page's `next_cursor`
+    pub cursor: Option<String>,
+
+    /// Only return items of canonical blocks
+    #[serde(default)]
+    pub canonical: bool,
+}
+
+/// Cursor which wasn't issued by the endpoint
+#[derive(Debug)]
+pub struct InvalidCursor(pub String);
+
+/// Page of items & the cursor of the next page, if any
+#[derive(Debug, Serialize, ToSchema)]
+#[aliases(
+    TransactionPage = Page<Transaction>,
+    InternalCommandPage = Page<InternalCommand>,
+    SnarkPage = Page<Snark>
+)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+/// Error response body
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+impl PageParams {
+    pub fn limit(&self) -> usize {
+        self.limit
+            .map_or(DEFAULT_PAGE_LIMIT, |limit| limit.min(MAX_PAGE_LIMIT)) as usize
+    }
+
+    fn cursor_bytes(&self) -> anyhow::Result<Option<Vec<u8>>> {
+        match self.cursor.as_deref() {
+            Some(cursor) => match hex::decode(cursor) {
+                Ok(bytes) if !bytes.is_empty() => Ok(Some(bytes)),
+                _ => Err(InvalidCursor(cursor.to_string()).into()),
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn offset(&self) -> anyhow::Result<usize> {
+        match self.cursor_bytes()? {
+            Some(bytes) => match <[u8; 4]>::try_from(bytes.as_slice()) {
+                Ok(offset) => Ok(u32::from_be_bytes(offset) as usize),
+                Err(_) => Err(InvalidCursor(hex::encode(bytes)).into()),
+            },
+            None => Ok(0),
+        }
+    }
+}
+
+impl std::fmt::Display for InvalidCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid cursor {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidCursor {}
+
+/// Page through `items` in order
+pub fn paginate<T>(items: Vec<T>, params: &PageParams) -> anyhow::Result<Page<T>> {
+    let offset = params.offset()?;
+    let end = offset.saturating_add(params.limit());
+    let next_cursor = (end < items.len()).then(|| hex::encode((end as u32).to_be_bytes()));
+    Ok(Page {
+        data: items
+            .into_iter()
+            .skip(offset)
+            .take(params.limit())
+            .collect(),
+        next_cursor,
+    })
+}
+
+/// Page through the entries of a speedb iterator in descending key order
+///
+/// `item` maps an entry to an item or `None` if it's filtered out
+pub fn paginate_iterator<'a, T>(
+    iterator: impl FnOnce(IteratorMode) -> DBIterator<'a>,
+    params: &PageParams,
+    item: impl FnMut(&[u8], &[u8]) -> anyhow::Result<Option<T>>,
+) -> anyhow::Result<Page<T>> {
+    paginate_prefix_iterator(&[], None, iterator, params, item)
+}
+
+/// Page through the entries whose keys start with `prefix` in descending key
+/// order, starting at the cursor or else at `end` (default: the last entry)
+///
+/// At most [MAX_PAGE_SCAN] entries are scanned, so a page of a sparse filter
+/// may hold fewer than `limit` items & still have a next cursor
+pub fn paginate_prefix_iterator<T, I>(
+    prefix: &[u8],
+    end: Option<&[u8]>,
+    iterator: impl FnOnce(IteratorMode) -> I,
+    params: &PageParams,
+    mut item: impl FnMut(&[u8], &[u8]) -> anyhow::Result<Option<T>>,
+) -> anyhow::Result<Page<T>>
+where
+    I: Iterator<Item = IteratorEntry>,
+{
+    let cursor = params.cursor_bytes()?;
+    let mode = match (cursor.as_deref(), end) {
+        (Some(key), _) if !key.starts_with(prefix) => {
+            return Err(InvalidCursor(hex::encode(key)).into())
+        }
+        (Some(key), _) | (None, Some(key)) => IteratorMode::From(key, Direction::Reverse),
+        (None, None) => IteratorMode::End,
+    };
+
+    let mut data = vec![];
+    for (n, (key, value)) in iterator(mode).flatten().enumerate() {
+        if !key.starts_with(prefix) {
+            break;
+        }
+        if data.len() == params.limit() || n == MAX_PAGE_SCAN {
+            return Ok(Page {
+                data,
+                next_cursor: Some(hex::encode(key)),
+            });
+        }
+        if let Some(item) = item(&key, &value)? {
+            data.push(item);
+        }
+    }
+    Ok(Page {
+        data,
+        next_cursor: None,
+    })
+}
+
+/// Merge two iterators of descending keys, yielding entries with the same key
+/// once
+pub fn merge_descending<I>(a: I, b: I) -> MergeDescending<I>
+where
+    I: Iterator<Item = IteratorEntry>,
+{
+    MergeDescending {
+        a: a.peekable(),
+        b: b.peekable(),
+    }
+}
+
+pub struct MergeDescending<I: Iterator> {
+    a: Peekable<I>,
+    b: Peekable<I>,
+}
+
+impl<I> Iterator for MergeDescending<I>
+where
+    I: Iterator<Item = IteratorEntry>,
+{
+    type Item = IteratorEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // errors are yielded as soon as they're reached
+        let ordering = match (self.a.peek(), self.b.peek()) {
+            (Some(Ok((a, _))), Some(Ok((b, _)))) => a.cmp(b),
+            (Some(Err(_)), _) | (Some(_), None) => Ordering::Greater,
+            (_, Some(Err(_))) | (None, Some(_)) => Ordering::Less,
+            (None, None) => return None,
+        };
+        match ordering {
+            Ordering::Greater => self.a.next(),
+            Ordering::Less => self.b.next(),
+            Ordering::Equal => {
+                self.b.next();
+                self.a.next()
+            }
+        }
+    }
+}
+
+pub fn json_response<T: Serialize>(value: &T) -> HttpResponse {
+    match serde_json::to_string_pretty(value) {
+        Ok(body) => HttpResponse::Ok()
+            .content_type(ContentType::json())
+            .body(body),
+        Err(e) => internal_error(e.into()),
+    }
+}
+
+fn error_response(mut builder: HttpResponseBuilder, msg: impl std::fmt::Display) -> HttpResponse {
+    builder.json(ErrorResponse {
+        error: msg.to_string(),
+    })
+}
+
+pub fn bad_request(msg: impl std::fmt::Display) -> HttpResponse {
+    error_response(HttpResponse::BadRequest(), msg)
+}
+
+pub fn not_found(msg: impl std::fmt::Display) -> HttpResponse {
+    error_response(HttpResponse::NotFound(), msg)
+}
+
+pub fn internal_error(e: anyhow::Error) -> HttpResponse {
+    error!("REST request failed: {e:#}");
+    error_response(HttpResponse::InternalServerError(), "Internal server error")
+}
+
+/// Respond with the page, or a bad request for invalid cursors
+pub fn page_response<T: Serialize>(page: anyhow::Result<Page<T>>) -> HttpResponse {
+    match page {
+        Ok(page) => json_response(&page),
+        Err(e) if e.is::<InvalidCursor>() => bad_request(e),
+        Err(e) => internal_error(e),
+    }
+}
@@ -0,0 +1,83 @@
+use super::{
+    pagination::{internal_error, json_response},
+    NetworkParams, NetworkStore,
+};
+use crate::canonicity::{reorg::ReorgFilter, store::CanonicityStore};
+use actix_web::{
+    get,
+    web::{self},
+    HttpResponse,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct Params {
+    /// Max number of reorgs [default: 10, max: 100]
+    limit: Option<u32>,
+
+    /// Min number of blocks removed from the best chain
+    min_depth: Option<u32>,
+
+    /// Epoch of the new best tip
+    epoch: Option<u32>,
+
+    /// Block removed from or added to the best chain
+    state_hash: Option<String>,
+
+    /// Transaction of a block removed from or added to the best chain
+    txn_hash: Option<String>,
+
+    /// Sender or receiver of such a transaction
+    public_key: Option<String>,
+}
+
+fn get_limit(limit: Option<u32>) -> u32 {
+    limit.map(|value| value.min(100)).unwrap_or(10)
+}
+
+/// Best tip reorgs, newest first
+#[utoipa::path(
+    tag = "reorgs",
+    params(Params, NetworkParams),
+    responses(
+        (status = 200, body = Vec<Reorg>),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[get("/reorgs")]
+pub async fn get_reorgs(store: NetworkStore, params: web::Query<Params>) -> HttpResponse {
+    let db = store.as_ref();
+    let params = params.into_inner();
+    let filter = ReorgFilter {
+        min_depth: params.min_depth,
+        epoch: params.epoch,
+        state_hash: params.state_hash.map(Into::into),
+        txn_hash: params.txn_hash,
+        public_key: params.public_key.map(Into::into),
+    };
+
+    match db.get_reorgs(&filter, get_limit(params.limit) as usize) {
+        Ok(reorgs) => json_response(&reorgs),
+        Err(e) => internal_error(e.context("Failed to get reorgs")),
+    }
+}
+
+/// Summary stats of all best tip reorgs
+#[utoipa::path(
+    tag = "reorgs",
+    params(NetworkParams),
+    responses(
+        (status = 200, body = ReorgStats),
+        (status = 500, body = ErrorResponse),
+    )
+)]
+#[get("/reorgs/stats")]
+pub async fn get_reorg_stats(store: NetworkStore) -> HttpResponse {
+    let db = store.as_ref();
+    match db.get_reorg_stats() {
+        Ok(stats) => json_response(&stats),
+        Err(e) => internal_error(e.context("Failed to get reorg stats")),
+    }
+}
@@ -0,0 +1,129 @@
+use super::{
+    is_canonical,
+    pagination::{bad_request, internal_error, not_found, page_response, paginate, PageParams},
+    NetworkParams, NetworkStore,
+};
+use crate::{
+    block::{is_valid_state_hash, store::BlockStore, BlockHash},
+    ledger::public_key::{is_valid_public_key, PublicKey},
+    snark_work::store::SnarkStore,
+};
+use actix_web::{
+    get,
+    web::{self},
+    HttpResponse,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// SNARK work included in a block
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Snark {
+    pub prover: String,
+
+    /// Fee (nanomina)
+    pub fee: u64,
+
+    /// Containing block
+    pub state_hash: String,
+    pub block_height: u32,
+    pub canonical: bool,
+}
+
+/// SNARK work proven by the account, highest block first
+#[utoipa::path(
+    tag = "snarks",
+    params(("public_key" = String, Path, description = "Prover public key"), PageParams, NetworkParams),
+    responses(
+        (status = 200, body = SnarkPage),
+        (status = 400, description = "Invalid public key or cursor", body = ErrorResponse),
+    )
+)]
+#[get("/accounts/{public_key}/snarks")]
+pub async fn get_account_snarks(
+    store: NetworkStore,
+    public_key: web::Path<String>,
+    params: web::Query<PageParams>,
+) -> HttpResponse {
+    let db = store.as_ref();
+    if !is_valid_public_key(&public_key) {
+        return bad_request(format!("Invalid public key {public_key}"));
+    }
+
+    let pk: PublicKey = public_key.into_inner().into();
+    let snarks = || -> anyhow::Result<Vec<Snark>> {
+        let mut snarks = vec![];
+        for snark in db
+            .get_snark_work_by_public_key(&pk)?
+            .unwrap_or_default()
+            .into_iter()
+            .rev()
+        {
+            let state_hash: BlockHash = snark.state_hash.into();
+            let canonical = is_canonical(db, &state_hash)?;
+            if params.canonical && !canonical {
+                continue;
+            }
+            snarks.push(Snark {
+                prover: snark.prover.0,
+                fee: snark.fee,
+                block_height: db.get_block_height(&state_hash)?.unwrap_or_default(),
+                state_hash: state_hash.0,
+                canonical,
+            });
+        }
+        Ok(snarks)
+    };
+    page_response(snarks().and_then(|snarks| paginate(snarks, &params)))
+}
+
+/// SNARK work of the block, in block order
+#[utoipa::path(
+    tag = "snarks",
+    params(("state_hash" = String, Path, description = "Block state hash"), PageParams, NetworkParams),
+    responses(
+        (status = 200, body = SnarkPage),
+        (status = 400, description = "Invalid state hash or cursor", body = ErrorResponse),
+        (status = 404, description = "Block not found", body = ErrorResponse),
+    )
+)]
+#[get("/blocks/{state_hash}/snarks")]
+pub async fn get_block_snarks(
+    store: NetworkStore,
+    state_hash: web::Path<String>,
+    params: web::Query<PageParams>,
+) -> HttpResponse {
+    let db = store.as_ref();
+    if !is_valid_state_hash(&state_hash) {
+        return bad_request(format!("Invalid state hash {state_hash}"));
+    }
+
+    let state_hash: BlockHash = state_hash.into_inner().into();
+    let snarks = || -> anyhow::Result<Option<Vec<Snark>>> {
+        let Some(block_height) = db.get_block_height(&state_hash)? else {
+            return Ok(None);
+        };
+        let canonical = is_canonical(db, &state_hash)?;
+        if params.canonical && !canonical {
+            return Ok(Some(vec![]));
+        }
+        Ok(Some(
+            db.get_snark_work_in_block(&state_hash)?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|snark| Snark {
+                    prover: snark.prover.0,
+                    fee: snark.fee,
+                    state_hash: state_hash.0.clone(),
+                    block_height,
+                    canonical,
+                })
+                .collect(),
+        ))
+    };
+    match snarks() {
+        Ok(Some(snarks)) => page_response(paginate(snarks, &params)),
+        Ok(None) => not_found(format!("Block {state_hash} not found")),
+        Err(e) => internal_error(e),
+    }
+}
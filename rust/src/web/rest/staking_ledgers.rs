@@ -0,0 +1,294 @@
+use super::{
+    pagination::{
+        bad_request, internal_error, json_response, not_found, paginate, InvalidCursor, PageParams,
+    },
+    NetworkParams, NetworkStore,
+};
+use crate::{
+    block::{is_valid_state_hash, store::BlockStore, BlockHash},
+    constants::MAINNET_GENESIS_HASH,
+    ledger::{
+        public_key::{is_valid_public_key, PublicKey},
+        staking::{AggregatedEpochStakeDelegations, StakingLedger},
+        store::LedgerStore,
+    },
+    store::IndexerStore,
+};
+use actix_web::{
+    get,
+    web::{self},
+    HttpResponse,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct GenesisHashParams {
+    /// Genesis state hash of the network [default: the best tip's]
+    genesis_state_hash: Option<String>,
+}
+
+/// Page of a staking ledger's accounts
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StakingLedgerPage {
+    pub epoch: u32,
+    pub ledger_hash: String,
+    pub genesis_state_hash: String,
+
+    /// Total currency (nanomina)
+    pub total_currency: u64,
+
+    pub data: Vec<StakingAccount>,
+    pub next_cursor: Option<String>,
+}
+
+/// Staking ledger account & its aggregated delegations
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StakingAccount {
+    pub public_key: String,
+
+    /// Balance (nanomina)
+    pub balance: u64,
+    pub delegate: String,
+    pub nonce: Option<u32>,
+    pub voting_for: String,
+
+    /// Stake delegated to the account (nanomina)
+    pub total_delegated: Option<u64>,
+    pub count_delegates: Option<u32>,
+}
+
+/// Page of a staking ledger's delegations
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StakingDelegationsPage {
+    pub epoch: u32,
+    pub ledger_hash: String,
+    pub genesis_state_hash: String,
+
+    /// Total stake delegated (nanomina)
+    pub total_delegations: u64,
+
+    pub data: Vec<StakingDelegation>,
+    pub next_cursor: Option<String>,
+}
+
+/// Stake delegated to a delegate
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StakingDelegation {
+    pub public_key: String,
+
+    /// Stake delegated to the account (nanomina)
+    pub total_delegated: u64,
+    pub count_delegates: u32,
+}
+
+fn genesis_state_hash(
+    db: &IndexerStore,
+    params: &GenesisHashParams,
+) -> Result<BlockHash, HttpResponse> {
+    match params.genesis_state_hash.as_ref() {
+        Some(hash) if !is_valid_state_hash(hash) => {
+            Err(bad_request(format!("Invalid genesis state hash {hash}")))
+        }
+        Some(hash) => Ok(hash.clone().into()),
+        None => Ok(db
+            .get_best_block_genesis_hash()
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| MAINNET_GENESIS_HASH.into())),
+    }
+}
+
+fn staking_ledger(
+    db: &IndexerStore,
+    epoch: u32,
+    genesis_state_hash: &BlockHash,
+) -> anyhow::Result<Option<(StakingLedger, Option<AggregatedEpochStakeDelegations>)>> {
+    let Some(ledger) = db.get_staking_ledger_at_epoch(epoch, Some(genesis_state_hash.clone()))?
+    else {
+        return Ok(None);
+    };
+    let delegations = db.get_delegations_epoch(epoch, &Some(genesis_state_hash.clone()))?;
+    Ok(Some((ledger, delegations)))
+}
+
+fn staking_account(
+    account: &crate::ledger::staking::StakingAccount,
+    delegations: Option<&AggregatedEpochStakeDelegations>,
+) -> StakingAccount {
+    let delegation = delegations.and_then(|delegations| delegations.delegations.get(&account.pk));
+    StakingAccount {
+        public_key: account.pk.0.clone(),
+        balance: account.balance,
+        delegate: account.delegate.0.clone(),
+        nonce: account.nonce.map(|nonce| nonce.0),
+        voting_for: account.voting_for.0.clone(),
+        total_delegated: delegation.and_then(|delegation| delegation.total_delegated),
+        count_delegates: delegation.and_then(|delegation| delegation.count_delegates),
+    }
+}
+
+fn respond<T: Serialize>(page: anyhow::Result<Option<T>>, epoch: u32) -> HttpResponse {
+    match page {
+        Ok(Some(page)) => json_response(&page),
+        Ok(None) => not_found(format!("Staking ledger for epoch {epoch} not found")),
+        Err(e) if e.is::<InvalidCursor>() => bad_request(e),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// Staking ledger accounts of the epoch, highest balance first
+#[utoipa::path(
+    tag = "staking ledgers",
+    params(("epoch" = u32, Path, description = "Staking epoch"), GenesisHashParams, PageParams, NetworkParams),
+    responses(
+        (status = 200, body = StakingLedgerPage),
+        (status = 400, description = "Invalid genesis state hash or cursor", body = ErrorResponse),
+        (status = 404, description = "Staking ledger not found", body = ErrorResponse),
+    )
+)]
+#[get("/staking-ledgers/{epoch}")]
+pub async fn get_staking_ledger(
+    store: NetworkStore,
+    epoch: web::Path<u32>,
+    genesis: web::Query<GenesisHashParams>,
+    params: web::Query<PageParams>,
+) -> HttpResponse {
+    let db = store.as_ref();
+    let epoch = epoch.into_inner();
+    let genesis_state_hash = match genesis_state_hash(db, &genesis) {
+        Ok(genesis_state_hash) => genesis_state_hash,
+        Err(response) => return response,
+    };
+
+    let page = || -> anyhow::Result<Option<StakingLedgerPage>> {
+        let Some((ledger, delegations)) = staking_ledger(db, epoch, &genesis_state_hash)? else {
+            return Ok(None);
+        };
+        let mut accounts: Vec<StakingAccount> = ledger
+            .staking_ledger
+            .values()
+            .map(|account| staking_account(account, delegations.as_ref()))
+            .collect();
+        accounts.sort_by(|a, b| {
+            b.balance
+                .cmp(&a.balance)
+                .then_with(|| a.public_key.cmp(&b.public_key))
+        });
+        let page = paginate(accounts, &params)?;
+        Ok(Some(StakingLedgerPage {
+            epoch,
+            ledger_hash: ledger.ledger_hash.0,
+            genesis_state_hash: ledger.genesis_state_hash.0,
+            total_currency: ledger.total_currency,
+            data: page.data,
+            next_cursor: page.next_cursor,
+        }))
+    };
+    respond(page(), epoch)
+}
+
+/// Staking ledger account of the epoch
+#[utoipa::path(
+    tag = "staking ledgers",
+    params(
+        ("epoch" = u32, Path, description = "Staking epoch"),
+        ("public_key" = String, Path, description = "Account public key"),
+        GenesisHashParams,
+        NetworkParams,
+    ),
+    responses(
+        (status = 200, body = StakingAccount),
+        (status = 400, description = "Invalid public key or genesis state hash", body = ErrorResponse),
+        (status = 404, description = "Staking ledger or account not found", body = ErrorResponse),
+    )
+)]
+#[get("/staking-ledgers/{epoch}/accounts/{public_key}")]
+pub async fn get_staking_ledger_account(
+    store: NetworkStore,
+    path: web::Path<(u32, String)>,
+    genesis: web::Query<GenesisHashParams>,
+) -> HttpResponse {
+    let db = store.as_ref();
+    let (epoch, public_key) = path.into_inner();
+    if !is_valid_public_key(&public_key) {
+        return bad_request(format!("Invalid public key {public_key}"));
+    }
+    let genesis_state_hash = match genesis_state_hash(db, &genesis) {
+        Ok(genesis_state_hash) => genesis_state_hash,
+        Err(response) => return response,
+    };
+
+    match staking_ledger(db, epoch, &genesis_state_hash) {
+        Ok(Some((ledger, delegations))) => {
+            match ledger
+                .staking_ledger
+                .get(&PublicKey::from(public_key.clone()))
+            {
+                Some(account) => json_response(&staking_account(account, delegations.as_ref())),
+                None => not_found(format!(
+                    "Account {public_key} not found in the staking ledger for epoch {epoch}"
+                )),
+            }
+        }
+        page => respond(page.map(|_| None::<()>), epoch),
+    }
+}
+
+/// Aggregated delegations of the epoch, highest total delegated first
+#[utoipa::path(
+    tag = "staking ledgers",
+    params(("epoch" = u32, Path, description = "Staking epoch"), GenesisHashParams, PageParams, NetworkParams),
+    responses(
+        (status = 200, body = StakingDelegationsPage),
+        (status = 400, description = "Invalid genesis state hash or cursor", body = ErrorResponse),
+        (status = 404, description = "Staking ledger not found", body = ErrorResponse),
+    )
+)]
+#[get("/staking-ledgers/{epoch}/delegations")]
+pub async fn get_staking_delegations(
+    store: NetworkStore,
+    epoch: web::Path<u32>,
+    genesis: web::Query<GenesisHashParams>,
+    params: web::Query<PageParams>,
+) -> HttpResponse {
+    let db = store.as_ref();
+    let epoch = epoch.into_inner();
+    let genesis_state_hash = match genesis_state_hash(db, &genesis) {
+        Ok(genesis_state_hash) => genesis_state_hash,
+        Err(response) => return response,
+    };
+
+    let page = || -> anyhow::Result<Option<StakingDelegationsPage>> {
+        let Some(aggregated) =
+            db.get_delegations_epoch(epoch, &Some(genesis_state_hash.clone()))?
+        else {
+            return Ok(None);
+        };
+        let mut delegations: Vec<StakingDelegation> = aggregated
+            .delegations
+            .values()
+            .map(|delegation| StakingDelegation {
+                public_key: delegation.pk.0.clone(),
+                total_delegated: delegation.total_delegated.unwrap_or_default(),
+                count_delegates: delegation.count_delegates.unwrap_or_default(),
+            })
+            .collect();
+        delegations.sort_by(|a, b| {
+            b.total_delegated
+                .cmp(&a.total_delegated)
+                .then_with(|| a.public_key.cmp(&b.public_key))
+        });
+        let page = paginate(delegations, &params)?;
+        Ok(Some(StakingDelegationsPage {
+            epoch,
+            ledger_hash: aggregated.ledger_hash.0,
+            genesis_state_hash: aggregated.genesis_state_hash.0,
+            total_delegations: aggregated.total_delegations,
+            data: page.data,
+            next_cursor: page.next_cursor,
+        }))
+    };
+    respond(page(), epoch)
+}
@@ -0,0 +1,266 @@
+use super::{
+    is_canonical,
+    pagination::{
+        bad_request, internal_error, merge_descending, not_found, page_response, paginate,
+        paginate_iterator, paginate_prefix_iterator, PageParams,
+    },
+    NetworkParams, NetworkStore,
+};
+use crate::{
+    block::{is_valid_state_hash, store::BlockStore, BlockHash},
+    command::{
+        decode_memo,
+        signed::{is_valid_tx_hash, SignedCommandWithData},
+        store::{
+            user_commands_iterator_state_hash, user_commands_iterator_txn_hash, UserCommandStore,
+        },
+        CommandStatusData,
+    },
+    constants::millis_to_iso_date_string,
+    ledger::public_key::{is_valid_public_key, PublicKey},
+    store::{pk_txn_sort_key_prefix, state_hash_pk_txn_sort_key, txn_hash_of_key},
+};
+use actix_web::{
+    get,
+    web::{self},
+    HttpResponse,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// User command in a block
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Transaction {
+    pub hash: String,
+
+    /// `PAYMENT` or `STAKE_DELEGATION`
+    pub kind: String,
+    pub from: String,
+    pub to: String,
+    pub fee_payer: String,
+
+    /// Amount (nanomina), 0 for delegations
+    pub amount: u64,
+
+    /// Fee (nanomina)
+    pub fee: u64,
+    pub nonce: u32,
+    pub memo: String,
+
+    /// `APPLIED` or `FAILED`
+    pub status: String,
+    pub failure_reason: Option<String>,
+
+    /// Containing block
+    pub state_hash: String,
+    pub block_height: u32,
+    pub global_slot: u32,
+    pub date_time: String,
+    pub canonical: bool,
+}
+
+impl Transaction {
+    pub fn new(cmd: SignedCommandWithData, canonical: bool) -> Self {
+        let (status, failure_reason) = match &cmd.status {
+            CommandStatusData::Applied { .. } => ("APPLIED", None),
+            CommandStatusData::Failed(failed_types, _) => (
+                "FAILED",
+                failed_types.first().map(|reason| reason.to_string()),
+            ),
+        };
+        let kind = if cmd.command.is_delegation() {
+            "STAKE_DELEGATION"
+        } else {
+            "PAYMENT"
+        };
+        Self {
+            hash: cmd.tx_hash,
+            kind: kind.to_string(),
+            from: cmd.command.source_pk().0,
+            to: cmd.command.receiver_pk().0,
+            fee_payer: cmd.command.fee_payer_pk().0,
+            amount: cmd.command.amount(),
+            fee: cmd.command.fee(),
+            nonce: cmd.nonce.0,
+            memo: decode_memo(&cmd.command.payload_common().memo.t.0),
+            status: status.to_string(),
+            failure_reason,
+            state_hash: cmd.state_hash.0,
+            block_height: cmd.blockchain_length,
+            global_slot: cmd.global_slot_since_genesis,
+            date_time: millis_to_iso_date_string(cmd.date_time as i64),
+            canonical,
+        }
+    }
+}
+
+/// Transactions of all blocks, highest block first
+#[utoipa::path(
+    tag = "transactions",
+    params(PageParams, NetworkParams),
+    responses(
+        (status = 200, body = TransactionPage),
+        (status = 400, description = "Invalid cursor", body = ErrorResponse),
+    )
+)]
+#[get("/transactions")]
+pub async fn get_transactions(store: NetworkStore, params: web::Query<PageParams>) -> HttpResponse {
+    let db = store.as_ref();
+    page_response(paginate_iterator(
+        |mode| db.user_commands_height_iterator(mode),
+        &params,
+        |key, _| {
+            let txn_hash = user_commands_iterator_txn_hash(key)?;
+            let state_hash = user_commands_iterator_state_hash(key)?;
+            let canonical = is_canonical(db, &state_hash)?;
+            if params.canonical && !canonical {
+                return Ok(None);
+            }
+            Ok(db
+                .get_user_command_state_hash(&txn_hash, &state_hash)?
+                .map(|cmd| Transaction::new(cmd, canonical)))
+        },
+    ))
+}
+
+/// Transaction in each of its containing blocks
+#[utoipa::path(
+    tag = "transactions",
+    params(("hash" = String, Path, description = "Transaction hash"), PageParams, NetworkParams),
+    responses(
+        (status = 200, body = TransactionPage),
+        (status = 400, description = "Invalid hash or cursor", body = ErrorResponse),
+        (status = 404, description = "Transaction not found", body = ErrorResponse),
+    )
+)]
+#[get("/transactions/{hash}")]
+pub async fn get_transaction(
+    store: NetworkStore,
+    hash: web::Path<String>,
+    params: web::Query<PageParams>,
+) -> HttpResponse {
+    let db = store.as_ref();
+    if !is_valid_tx_hash(&hash) {
+        return bad_request(format!("Invalid transaction hash {hash}"));
+    }
+
+    let transactions = || -> anyhow::Result<Option<Vec<Transaction>>> {
+        let Some(state_hashes) = db.get_user_command_state_hashes(&hash)? else {
+            return Ok(None);
+        };
+        let mut transactions = vec![];
+        for state_hash in state_hashes {
+            let canonical = is_canonical(db, &state_hash)?;
+            if params.canonical && !canonical {
+                continue;
+            }
+            if let Some(cmd) = db.get_user_command_state_hash(&hash, &state_hash)? {
+                transactions.push(Transaction::new(cmd, canonical));
+            }
+        }
+        Ok(Some(transactions))
+    };
+    match transactions() {
+        Ok(Some(transactions)) => page_response(paginate(transactions, &params)),
+        Ok(None) => not_found(format!("Transaction {hash} not found")),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// Transactions sent or received by the account, highest block first
+///
+/// Served by merging the account's sender & receiver sort-key indexes
+#[utoipa::path(
+    tag = "transactions",
+    params(("public_key" = String, Path, description = "Account public key"), PageParams, NetworkParams),
+    responses(
+        (status = 200, body = TransactionPage),
+        (status = 400, description = "Invalid public key or cursor", body = ErrorResponse),
+    )
+)]
+#[get("/accounts/{public_key}/transactions")]
+pub async fn get_account_transactions(
+    store: NetworkStore,
+    public_key: web::Path<String>,
+    params: web::Query<PageParams>,
+) -> HttpResponse {
+    let db = store.as_ref();
+    if !is_valid_public_key(&public_key) {
+        return bad_request(format!("Invalid public key {public_key}"));
+    }
+
+    let pk: PublicKey = public_key.into_inner().into();
+    let prefix = pk.clone().to_bytes();
+    let end = pk_txn_sort_key_prefix(pk, u32::MAX);
+    page_response(paginate_prefix_iterator(
+        &prefix,
+        Some(&end),
+        |mode| {
+            merge_descending(
+                db.txn_from_height_iterator(mode),
+                db.txn_to_height_iterator(mode),
+            )
+        },
+        &params,
+        |key, _| {
+            let txn_hash = txn_hash_of_key(key);
+            let state_hash = state_hash_pk_txn_sort_key(key);
+            let canonical = is_canonical(db, &state_hash)?;
+            if params.canonical && !canonical {
+                return Ok(None);
+            }
+            Ok(db
+                .get_user_command_state_hash(&txn_hash, &state_hash)?
+                .map(|cmd| Transaction::new(cmd, canonical)))
+        },
+    ))
+}
+
+/// Transactions of the block, in block order
+#[utoipa::path(
+    tag = "transactions",
+    params(("state_hash" = String, Path, description = "Block state hash"), PageParams, NetworkParams),
+    responses(
+        (status = 200, body = TransactionPage),
+        (status = 400, description = "Invalid state hash or cursor", body = ErrorResponse),
+        (status = 404, description = "Block not found", body = ErrorResponse),
+    )
+)]
+#[get("/blocks/{state_hash}/transactions")]
+pub async fn get_block_transactions(
+    store: NetworkStore,
+    state_hash: web::Path<String>,
+    params: web::Query<PageParams>,
+) -> HttpResponse {
+    let db = store.as_ref();
+    if !is_valid_state_hash(&state_hash) {
+        return bad_request(format!("Invalid state hash {state_hash}"));
+    }
+
+    let state_hash: BlockHash = state_hash.into_inner().into();
+    let transactions = || -> anyhow::Result<Option<Vec<Transaction>>> {
+        // pruned blocks keep their user commands, even without the blocks dir
+        let user_commands = match db.get_pruned_block(&state_hash)? {
+            Some(pruned) => pruned.user_commands,
+            None => match db.get_block(&state_hash)? {
+                Some((block, _)) => SignedCommandWithData::from_precomputed(&block),
+                None => return Ok(None),
+            },
+        };
+        let canonical = is_canonical(db, &state_hash)?;
+        if params.canonical && !canonical {
+            return Ok(Some(vec![]));
+        }
+        Ok(Some(
+            user_commands
+                .into_iter()
+                .map(|cmd| Transaction::new(cmd, canonical))
+                .collect(),
+        ))
+    };
+    match transactions() {
+        Ok(Some(transactions)) => page_response(paginate(transactions, &params)),
+        Ok(None) => not_found(format!("Block {state_hash} not found")),
+        Err(e) => internal_error(e),
+    }
+}
@@ -0,0 +1,72 @@
+use super::{
+    pagination::{bad_request, internal_error, json_response, not_found},
+    NetworkParams, NetworkStore,
+};
+use crate::{
+    ledger::public_key::{is_valid_public_key, PublicKey},
+    store::username::UsernameStore,
+};
+use actix_web::{
+    get,
+    web::{self},
+    HttpResponse,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Current username of the account & its update history
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AccountUsername {
+    pub public_key: String,
+    pub username: String,
+    pub num_updates: u32,
+
+    /// Usernames in update order, oldest first
+    pub history: Vec<String>,
+}
+
+/// Username of the account
+#[utoipa::path(
+    tag = "accounts",
+    params(("public_key" = String, Path, description = "Account public key"), NetworkParams),
+    responses(
+        (status = 200, body = AccountUsername),
+        (status = 400, description = "Invalid public key", body = ErrorResponse),
+        (status = 404, description = "Username not found", body = ErrorResponse),
+    )
+)]
+#[get("/usernames/{public_key}")]
+pub async fn get_username(store: NetworkStore, public_key: web::Path<String>) -> HttpResponse {
+    let db = store.as_ref();
+    if !is_valid_public_key(&public_key) {
+        return bad_request(format!("Invalid public key {public_key}"));
+    }
+
+    let pk: PublicKey = public_key.into_inner().into();
+    let username = || -> anyhow::Result<Option<AccountUsername>> {
+        let Some(username) = db.get_username(&pk)? else {
+            return Ok(None);
+        };
+        // the store records the index of the last update
+        let num_updates = db
+            .get_pk_num_username_updates(&pk)?
+            .map_or(0, |last| last + 1);
+        let mut history = vec![];
+        for index in 0..num_updates {
+            if let Some(username) = db.get_pk_username(&pk, index)? {
+                history.push(username.0);
+            }
+        }
+        Ok(Some(AccountUsername {
+            public_key: pk.0.clone(),
+            username: username.0,
+            num_updates,
+            history,
+        }))
+    };
+    match username() {
+        Ok(Some(username)) => json_response(&username),
+        Ok(None) => not_found(format!("Username for {pk} not found")),
+        Err(e) => internal_error(e),
+    }
+}
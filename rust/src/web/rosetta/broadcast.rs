@@ -0,0 +1,98 @@
+//! Submission of signed transactions to the network
+
+use super::construction::SignedTransaction;
+use anyhow::bail;
+use async_graphql::async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// Submits signed transactions for `/construction/submit`
+#[async_trait]
+pub trait Broadcaster: Send + Sync {
+    async fn broadcast(&self, transaction: &SignedTransaction) -> anyhow::Result<()>;
+}
+
+/// Submits transactions via the `sendPayment` & `sendDelegation` mutations of
+/// a daemon's GraphQL API
+pub struct DaemonBroadcaster {
+    url: String,
+    client: reqwest::Client,
+}
+
+const SEND_PAYMENT: &str = "mutation($input: SendPaymentInput!, $signature: SignatureInput) { \
+    sendPayment(input: $input, signature: $signature) { payment { hash } } }";
+
+const SEND_DELEGATION: &str =
+    "mutation($input: SendDelegationInput!, $signature: SignatureInput) { \
+    sendDelegation(input: $input, signature: $signature) { delegation { hash } } }";
+
+impl DaemonBroadcaster {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// The daemon's GraphQL mutation of the transaction
+    fn mutation(transaction: &SignedTransaction) -> anyhow::Result<Value> {
+        let (query, input) = match (&transaction.payment, &transaction.stake_delegation) {
+            (Some(payment), None) => (
+                SEND_PAYMENT,
+                json!({
+                    "from": payment.from,
+                    "to": payment.to,
+                    "amount": payment.amount,
+                    "fee": payment.fee,
+                    "nonce": payment.nonce,
+                    "memo": payment.memo,
+                    "validUntil": payment.valid_until,
+                }),
+            ),
+            (None, Some(delegation)) => (
+                SEND_DELEGATION,
+                json!({
+                    "from": delegation.delegator,
+                    "to": delegation.new_delegate,
+                    "fee": delegation.fee,
+                    "nonce": delegation.nonce,
+                    "memo": delegation.memo,
+                    "validUntil": delegation.valid_until,
+                }),
+            ),
+            _ => bail!("Expected either a payment or a stake delegation"),
+        };
+
+        Ok(json!({
+            "query": query,
+            "variables": {
+                "input": input,
+                "signature": { "rawSignature": transaction.signature },
+            },
+        }))
+    }
+}
+
+#[async_trait]
+impl Broadcaster for DaemonBroadcaster {
+    async fn broadcast(&self, transaction: &SignedTransaction) -> anyhow::Result<()> {
+        let body = serde_json::to_vec(&Self::mutation(transaction)?)?;
+        let response = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            bail!("{} responded with {status}", self.url)
+        }
+
+        let response: Value = serde_json::from_slice(&response.bytes().await?)?;
+        if let Some(errors) = response.get("errors") {
+            bail!("{} rejected the transaction: {errors}", self.url)
+        }
+        Ok(())
+    }
+}
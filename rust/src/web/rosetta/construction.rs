@@ -0,0 +1,850 @@
+//! Rosetta Construction API endpoints
+//!
+//! Constructs legacy payments & stake delegations. Unsigned & signed
+//! transactions are JSON strings holding the daemon's `sendPayment` or
+//! `sendDelegation` input. The signing payload is the command's legacy random
+//! oracle input: the x coordinates of the fee payer, source & receiver (255
+//! bits each) followed by the payload bits, packed little endian.
+//!
+//! Signatures are only checked for their format & signer, the daemon verifies
+//! them when the transaction is submitted.
+
+use super::{
+    broadcast::Broadcaster,
+    check_network,
+    error::RosettaError,
+    models::{self, *},
+    operations::{OperationType, Operations},
+    parse,
+};
+use crate::{
+    block::store::BlockStore,
+    canonicity::store::CanonicityStore,
+    command::{signed::SignedCommand, store::UserCommandStore},
+    constants::MAINNET_ACCOUNT_CREATION_FEE,
+    ledger::{public_key::PublicKey, store::LedgerStore},
+    proof_systems::{
+        signer::{
+            pubkey::CompressedPubKey,
+            signature::{BaseField, ScalarField, Signature as SchnorrSignature},
+        },
+        FieldHelpers,
+    },
+    protocol::serialization_types::{
+        signatures::{PublicKey2V1, PublicKeyV1, SignatureV1},
+        staged_ledger_diff as mina_rs,
+    },
+    store::IndexerStore,
+};
+use actix_web::{
+    post,
+    web::{self, Data, Json},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+pub const CURVE_TYPE: &str = "pallas";
+pub const SIGNATURE_TYPE: &str = "schnorr_poseidon";
+
+/// Id of the default token
+pub const MINA_TOKEN_ID: u64 = 1;
+
+/// Bits of a random oracle input field element
+const FIELD_BITS: usize = 255;
+
+/// Max length (bytes) of a memo
+pub const MEMO_MAX_LEN: usize = 32;
+
+/// Number of best chain blocks the suggested fee is computed from
+pub const SUGGESTED_FEE_BLOCKS: u32 = 10;
+
+/// Lower bound of the suggested fee (nanomina)
+pub const MIN_SUGGESTED_FEE: u64 = 1_000_000;
+
+type RosettaResult<T> = Result<Json<T>, RosettaError>;
+
+/// Payment in the daemon's `sendPayment` input format
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentInput {
+    pub to: String,
+    pub from: String,
+    pub fee: String,
+    pub token: String,
+    pub nonce: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    pub amount: String,
+    pub valid_until: String,
+}
+
+/// Stake delegation in the daemon's `sendDelegation` input format
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegationInput {
+    pub delegator: String,
+    pub new_delegate: String,
+    pub fee: String,
+    pub nonce: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    pub valid_until: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedTransaction {
+    /// Hex encoded signing payload
+    pub random_oracle_input: String,
+    pub payment: Option<PaymentInput>,
+    pub stake_delegation: Option<DelegationInput>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedTransaction {
+    /// Hex encoded signature, see [models::Signature]
+    pub signature: String,
+    pub payment: Option<PaymentInput>,
+    pub stake_delegation: Option<DelegationInput>,
+}
+
+/// Options returned by `/construction/preprocess`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct PreprocessOptions {
+    sender: String,
+    token_id: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    receiver: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid_until: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    memo: Option<String>,
+}
+
+/// Metadata returned by `/construction/metadata`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ConstructionMetadata {
+    sender: String,
+    nonce: String,
+    token_id: String,
+
+    /// Paid by a payment's receiver if it isn't in the ledger
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    account_creation_fee: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid_until: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    memo: Option<String>,
+}
+
+/// Optional transaction metadata of `/construction/preprocess`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct TransactionOptions {
+    #[serde(default)]
+    valid_until: Option<String>,
+
+    #[serde(default)]
+    memo: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CommandKind {
+    Payment { receiver: PublicKey, amount: u64 },
+    Delegation { new_delegate: PublicKey },
+}
+
+/// Payment or delegation described by operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Intent {
+    /// Source & fee payer
+    source: PublicKey,
+    fee: u64,
+    kind: CommandKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UnsignedCommand {
+    intent: Intent,
+    nonce: u32,
+    valid_until: u32,
+    memo: String,
+}
+
+#[post("/construction/derive")]
+pub async fn construction_derive(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<ConstructionDeriveResponse> {
+    let request: ConstructionDeriveRequest = parse(&body)?;
+    check_network(&store, &request.network_identifier)?;
+
+    let pk = compressed_public_key(&request.public_key)?;
+    Ok(Json(ConstructionDeriveResponse {
+        account_identifier: AccountIdentifier::new(pk.into_address()),
+        metadata: None,
+    }))
+}
+
+#[post("/construction/preprocess")]
+pub async fn construction_preprocess(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<ConstructionPreprocessResponse> {
+    let request: ConstructionPreprocessRequest = parse(&body)?;
+    check_network(&store, &request.network_identifier)?;
+
+    let intent = Intent::from_operations(&request.operations)?;
+    let options: TransactionOptions = match request.metadata {
+        Some(metadata) => serde_json::from_value(metadata)
+            .map_err(|e| RosettaError::json_parse().with_details(e))?,
+        None => TransactionOptions::default(),
+    };
+    if let Some(valid_until) = &options.valid_until {
+        parse_u32("valid_until", valid_until)?;
+    }
+    if let Some(memo) = &options.memo {
+        encode_memo(memo)?;
+    }
+
+    let options = PreprocessOptions {
+        sender: intent.source.0.clone(),
+        token_id: MINA_TOKEN_ID.to_string(),
+        receiver: match &intent.kind {
+            CommandKind::Payment { receiver, .. } => Some(receiver.0.clone()),
+            CommandKind::Delegation { .. } => None,
+        },
+        valid_until: options.valid_until,
+        memo: options.memo,
+    };
+    Ok(Json(ConstructionPreprocessResponse {
+        options: Some(serde_json::to_value(options).map_err(anyhow::Error::from)?),
+        required_public_keys: Some(vec![AccountIdentifier::new(intent.source.0)]),
+    }))
+}
+
+/// Sender's nonce from the best ledger & the suggested fee from recent best
+/// chain blocks
+#[post("/construction/metadata")]
+pub async fn construction_metadata(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<ConstructionMetadataResponse> {
+    let db = store.as_ref();
+    let request: ConstructionMetadataRequest = parse(&body)?;
+    check_network(db, &request.network_identifier)?;
+
+    let options: PreprocessOptions = serde_json::from_value(
+        request
+            .options
+            .ok_or_else(RosettaError::no_options_provided)?,
+    )
+    .map_err(|e| RosettaError::json_parse().with_details(e))?;
+    let sender = public_key(&options.sender)?;
+    let receiver = match &options.receiver {
+        Some(receiver) => Some(public_key(receiver)?),
+        None => None,
+    };
+
+    let best_hash = db
+        .get_best_block_hash()?
+        .ok_or_else(RosettaError::chain_info_missing)?;
+    let ledger = db
+        .get_ledger_state_hash(&best_hash, false)?
+        .ok_or_else(|| {
+            RosettaError::chain_info_missing()
+                .with_details(format!("No ledger for block {best_hash}"))
+        })?;
+    let nonce = ledger
+        .accounts
+        .get(&sender)
+        .ok_or_else(|| RosettaError::account_not_found().with_details(&sender))?
+        .nonce
+        .unwrap_or_default();
+    let account_creation_fee = receiver
+        .filter(|receiver| !ledger.accounts.contains_key(receiver))
+        .map(|_| MAINNET_ACCOUNT_CREATION_FEE.0.to_string());
+
+    let metadata = ConstructionMetadata {
+        sender: sender.0,
+        nonce: nonce.0.to_string(),
+        token_id: options.token_id,
+        account_creation_fee,
+        valid_until: options.valid_until,
+        memo: options.memo,
+    };
+    Ok(Json(ConstructionMetadataResponse {
+        metadata: serde_json::to_value(metadata).map_err(anyhow::Error::from)?,
+        suggested_fee: Some(vec![Amount::credit(suggested_fee(db)?)]),
+    }))
+}
+
+#[post("/construction/payloads")]
+pub async fn construction_payloads(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<ConstructionPayloadsResponse> {
+    let request: ConstructionPayloadsRequest = parse(&body)?;
+    check_network(&store, &request.network_identifier)?;
+
+    let intent = Intent::from_operations(&request.operations)?;
+    let metadata: ConstructionMetadata = serde_json::from_value(
+        request
+            .metadata
+            .ok_or_else(RosettaError::no_options_provided)?,
+    )
+    .map_err(|e| RosettaError::json_parse().with_details(e))?;
+    if metadata.sender != intent.source.0 {
+        return Err(RosettaError::operations_not_valid()
+            .with_details("The metadata sender isn't the operations' source"));
+    }
+
+    let command = UnsignedCommand {
+        intent,
+        nonce: parse_u32("nonce", &metadata.nonce)?,
+        valid_until: metadata
+            .valid_until
+            .as_deref()
+            .map_or(Ok(u32::MAX), |valid_until| {
+                parse_u32("valid_until", valid_until)
+            })?,
+        memo: metadata.memo.unwrap_or_default(),
+    };
+    let random_oracle_input = hex::encode(command.random_oracle_input()?);
+    let (payment, stake_delegation) = command.inputs();
+    let unsigned_transaction = UnsignedTransaction {
+        random_oracle_input: random_oracle_input.clone(),
+        payment,
+        stake_delegation,
+    };
+
+    Ok(Json(ConstructionPayloadsResponse {
+        unsigned_transaction: serde_json::to_string(&unsigned_transaction)
+            .map_err(anyhow::Error::from)?,
+        payloads: vec![SigningPayload {
+            account_identifier: AccountIdentifier::new(command.intent.source.0),
+            hex_bytes: random_oracle_input,
+            signature_type: SIGNATURE_TYPE.to_string(),
+        }],
+    }))
+}
+
+#[post("/construction/parse")]
+pub async fn construction_parse(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<ConstructionParseResponse> {
+    let request: ConstructionParseRequest = parse(&body)?;
+    check_network(&store, &request.network_identifier)?;
+
+    let command = if request.signed {
+        let signed: SignedTransaction = parse(request.transaction.as_bytes())?;
+        signature(&signed.signature)?;
+        UnsignedCommand::from_inputs(signed.payment.as_ref(), signed.stake_delegation.as_ref())?
+    } else {
+        let unsigned: UnsignedTransaction = parse(request.transaction.as_bytes())?;
+        UnsignedCommand::from_inputs(
+            unsigned.payment.as_ref(),
+            unsigned.stake_delegation.as_ref(),
+        )?
+    };
+
+    Ok(Json(ConstructionParseResponse {
+        operations: command.intent.operations(),
+        account_identifier_signers: request
+            .signed
+            .then(|| vec![AccountIdentifier::new(command.intent.source.0.clone())]),
+        metadata: (!command.memo.is_empty()).then(|| json!({ "memo": command.memo })),
+    }))
+}
+
+#[post("/construction/combine")]
+pub async fn construction_combine(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<ConstructionCombineResponse> {
+    let request: ConstructionCombineRequest = parse(&body)?;
+    check_network(&store, &request.network_identifier)?;
+
+    let unsigned: UnsignedTransaction = parse(request.unsigned_transaction.as_bytes())?;
+    let command = UnsignedCommand::from_inputs(
+        unsigned.payment.as_ref(),
+        unsigned.stake_delegation.as_ref(),
+    )?;
+
+    let [sig] = request.signatures.as_slice() else {
+        return Err(RosettaError::signature_missing().with_details(format!(
+            "Expected 1 signature, got {}",
+            request.signatures.len()
+        )));
+    };
+    if sig.signature_type != SIGNATURE_TYPE {
+        return Err(RosettaError::signature_invalid()
+            .with_details(format!("Unsupported signature type {}", sig.signature_type)));
+    }
+    if sig.signing_payload.hex_bytes != unsigned.random_oracle_input {
+        return Err(RosettaError::signature_invalid()
+            .with_details("The signing payload isn't the transaction's"));
+    }
+    if compressed_public_key(&sig.public_key)?.into_address() != command.intent.source.0 {
+        return Err(RosettaError::signature_invalid()
+            .with_details("The signer isn't the transaction's fee payer"));
+    }
+    signature(&sig.hex_bytes)?;
+
+    let signed_transaction = SignedTransaction {
+        signature: sig.hex_bytes.to_lowercase(),
+        payment: unsigned.payment,
+        stake_delegation: unsigned.stake_delegation,
+    };
+    Ok(Json(ConstructionCombineResponse {
+        signed_transaction: serde_json::to_string(&signed_transaction)
+            .map_err(anyhow::Error::from)?,
+    }))
+}
+
+#[post("/construction/hash")]
+pub async fn construction_hash(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<TransactionIdentifierResponse> {
+    let request: ConstructionHashRequest = parse(&body)?;
+    check_network(&store, &request.network_identifier)?;
+
+    let signed: SignedTransaction = parse(request.signed_transaction.as_bytes())?;
+    Ok(Json(TransactionIdentifierResponse {
+        transaction_identifier: TransactionIdentifier {
+            hash: transaction_hash(&signed)?,
+        },
+        metadata: None,
+    }))
+}
+
+/// Submit the signed transaction via the configured broadcaster
+#[post("/construction/submit")]
+pub async fn construction_submit(
+    store: Data<Arc<IndexerStore>>,
+    broadcaster: Option<Data<Arc<dyn Broadcaster>>>,
+    body: web::Bytes,
+) -> RosettaResult<TransactionIdentifierResponse> {
+    let request: ConstructionHashRequest = parse(&body)?;
+    check_network(&store, &request.network_identifier)?;
+
+    let broadcaster = broadcaster.ok_or_else(RosettaError::broadcaster_not_set)?;
+    let signed: SignedTransaction = parse(request.signed_transaction.as_bytes())?;
+    let hash = transaction_hash(&signed)?;
+    broadcaster
+        .broadcast(&signed)
+        .await
+        .map_err(|e| RosettaError::transaction_submit_failed().with_details(e))?;
+
+    Ok(Json(TransactionIdentifierResponse {
+        transaction_identifier: TransactionIdentifier { hash },
+        metadata: None,
+    }))
+}
+
+/////////////
+// Helpers //
+/////////////
+
+fn public_key(address: &str) -> Result<PublicKey, RosettaError> {
+    compressed_address(address).map(|_| address.into())
+}
+
+fn compressed_address(address: &str) -> Result<CompressedPubKey, RosettaError> {
+    CompressedPubKey::from_address(address)
+        .map_err(|_| RosettaError::malformed_public_key().with_details(address))
+}
+
+fn compressed_public_key(pk: &models::PublicKey) -> Result<CompressedPubKey, RosettaError> {
+    if pk.curve_type != CURVE_TYPE {
+        return Err(RosettaError::public_key_format_not_valid()
+            .with_details(format!("Unsupported curve type {}", pk.curve_type)));
+    }
+    let invalid = || RosettaError::public_key_format_not_valid().with_details(&pk.hex_bytes);
+    if pk.hex_bytes.len() != 66 {
+        return Err(invalid());
+    }
+    CompressedPubKey::from_hex(&pk.hex_bytes).map_err(|_| invalid())
+}
+
+/// Parse a hex encoded signature
+fn signature(hex_bytes: &str) -> Result<SignatureV1, RosettaError> {
+    let invalid = || RosettaError::signature_invalid().with_details(hex_bytes);
+    if hex_bytes.len() != 128 {
+        return Err(invalid());
+    }
+
+    let (rx, s) = hex_bytes.split_at(64);
+    let rx = BaseField::from_hex(rx).map_err(|_| invalid())?;
+    let s = ScalarField::from_hex(s).map_err(|_| invalid())?;
+    Ok((&SchnorrSignature::new(rx, s)).into())
+}
+
+fn parse_u32(name: &str, value: &str) -> Result<u32, RosettaError> {
+    value.parse().map_err(|_| {
+        RosettaError::invalid_request().with_details(format!("Invalid {name} {value}"))
+    })
+}
+
+fn parse_u64(name: &str, value: &str) -> Result<u64, RosettaError> {
+    value.parse().map_err(|_| {
+        RosettaError::invalid_request().with_details(format!("Invalid {name} {value}"))
+    })
+}
+
+/// Memo bytes: tag, length & zero padded data
+fn encode_memo(memo: &str) -> Result<[u8; MEMO_MAX_LEN + 2], RosettaError> {
+    let bytes = memo.as_bytes();
+    if bytes.len() > MEMO_MAX_LEN {
+        return Err(RosettaError::memo_invalid()
+            .with_details(format!("Memos are at most {MEMO_MAX_LEN} bytes")));
+    }
+
+    let mut encoded = [0; MEMO_MAX_LEN + 2];
+    encoded[0] = 1;
+    encoded[1] = bytes.len() as u8;
+    encoded[2..bytes.len() + 2].copy_from_slice(bytes);
+    Ok(encoded)
+}
+
+/// Hash of the signed command
+fn transaction_hash(signed: &SignedTransaction) -> Result<String, RosettaError> {
+    let command =
+        UnsignedCommand::from_inputs(signed.payment.as_ref(), signed.stake_delegation.as_ref())?;
+    let signed_command = command.signed_command(signature(&signed.signature)?)?;
+    Ok(SignedCommand(signed_command).hash_signed_command()?)
+}
+
+/// Median user command fee of recent best chain blocks
+fn suggested_fee(db: &IndexerStore) -> anyhow::Result<u64> {
+    let best_height = db.get_best_block_height()?.unwrap_or_default();
+    let mut fees = vec![];
+    for height in best_height.saturating_sub(SUGGESTED_FEE_BLOCKS - 1).max(1)..=best_height {
+        if let Some(state_hash) = db.get_canonical_hash_at_height(height)? {
+            fees.extend(
+                db.get_block_user_commands(&state_hash)?
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|cmd| SignedCommand::from(cmd).fee()),
+            );
+        }
+    }
+
+    fees.sort_unstable();
+    Ok(fees
+        .get(fees.len() / 2)
+        .copied()
+        .unwrap_or_default()
+        .max(MIN_SUGGESTED_FEE))
+}
+
+/// Signed nanomina value of the operation
+fn operation_amount(operation: &Operation) -> Result<i128, RosettaError> {
+    let amount = operation.amount.as_ref().ok_or_else(|| {
+        RosettaError::operations_not_valid()
+            .with_details(format!("Missing {} amount", operation.kind))
+    })?;
+    if amount.currency != Currency::mina() {
+        return Err(RosettaError::operations_not_valid()
+            .with_details(format!("Unsupported currency {}", amount.currency.symbol)));
+    }
+    amount.value.parse().map_err(|_| {
+        RosettaError::operations_not_valid().with_details(format!(
+            "Invalid {} amount {}",
+            operation.kind, amount.value
+        ))
+    })
+}
+
+fn operation_account(operation: &Operation) -> Result<PublicKey, RosettaError> {
+    let account = operation.account.as_ref().ok_or_else(|| {
+        RosettaError::operations_not_valid()
+            .with_details(format!("Missing {} account", operation.kind))
+    })?;
+    public_key(&account.address)
+}
+
+impl Intent {
+    /// Payments are `fee_payment`, `payment_source_dec` &
+    /// `payment_receiver_inc`, delegations are `fee_payment` &
+    /// `delegate_change`
+    fn from_operations(operations: &[Operation]) -> Result<Self, RosettaError> {
+        let invalid = |details: &str| RosettaError::operations_not_valid().with_details(details);
+        let find = |kind: OperationType| {
+            let mut ops = operations.iter().filter(|op| op.kind == kind.name());
+            match (ops.next(), ops.next()) {
+                (Some(op), None) => Ok(Some(op)),
+                (None, _) => Ok(None),
+                _ => Err(invalid(&format!("Duplicate {kind} operation"))),
+            }
+        };
+
+        let fee_payment = find(OperationType::FeePayment)?
+            .ok_or_else(|| invalid("Missing fee_payment operation"))?;
+        let source = operation_account(fee_payment)?;
+        let fee = u64::try_from(-operation_amount(fee_payment)?)
+            .map_err(|_| invalid("The fee_payment amount must be a debit"))?;
+
+        let kind = match (
+            find(OperationType::DelegateChange)?,
+            find(OperationType::PaymentSourceDec)?,
+            find(OperationType::PaymentReceiverInc)?,
+        ) {
+            (Some(delegate_change), None, None) if operations.len() == 2 => {
+                if operation_account(delegate_change)? != source {
+                    return Err(invalid("The delegator must be the fee payer"));
+                }
+                let new_delegate = delegate_change
+                    .metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata["delegate_change_target"].as_str())
+                    .ok_or_else(|| invalid("Missing delegate_change_target metadata"))?;
+                CommandKind::Delegation {
+                    new_delegate: public_key(new_delegate)?,
+                }
+            }
+            (None, Some(source_dec), Some(receiver_inc)) if operations.len() == 3 => {
+                if operation_account(source_dec)? != source {
+                    return Err(invalid("The payment source must be the fee payer"));
+                }
+                let amount = operation_amount(receiver_inc)?;
+                if operation_amount(source_dec)? != -amount {
+                    return Err(invalid("The payment source & receiver amounts don't match"));
+                }
+                CommandKind::Payment {
+                    receiver: operation_account(receiver_inc)?,
+                    amount: u64::try_from(amount)
+                        .map_err(|_| invalid("The payment amount must be a credit"))?,
+                }
+            }
+            _ => return Err(invalid("Expected a payment or a stake delegation")),
+        };
+        Ok(Self { source, fee, kind })
+    }
+
+    /// Operations without status, in the order of [Self::from_operations]
+    fn operations(&self) -> Vec<Operation> {
+        let mut operations = Operations::default();
+        operations.push(
+            OperationType::FeePayment,
+            &self.source,
+            Some(Amount::debit(self.fee)),
+            None,
+        );
+
+        match &self.kind {
+            CommandKind::Delegation { new_delegate } => {
+                operations.push(OperationType::DelegateChange, &self.source, None, None);
+                operations.last_mut().metadata =
+                    Some(json!({ "delegate_change_target": new_delegate.0 }));
+            }
+            CommandKind::Payment { receiver, amount } => {
+                let source_dec = operations.push(
+                    OperationType::PaymentSourceDec,
+                    &self.source,
+                    Some(Amount::debit(*amount)),
+                    None,
+                );
+                operations.push(
+                    OperationType::PaymentReceiverInc,
+                    receiver,
+                    Some(Amount::credit(*amount)),
+                    None,
+                );
+                operations.last_mut().related_operations = Some(vec![source_dec]);
+            }
+        }
+        operations.0
+    }
+}
+
+impl UnsignedCommand {
+    fn from_inputs(
+        payment: Option<&PaymentInput>,
+        delegation: Option<&DelegationInput>,
+    ) -> Result<Self, RosettaError> {
+        let (source, kind, fee, nonce, memo, valid_until) = match (payment, delegation) {
+            (Some(payment), None) => {
+                if parse_u64("token", &payment.token)? != MINA_TOKEN_ID {
+                    return Err(RosettaError::unsupported()
+                        .with_details(format!("Unsupported token {}", payment.token)));
+                }
+                (
+                    &payment.from,
+                    CommandKind::Payment {
+                        receiver: public_key(&payment.to)?,
+                        amount: parse_u64("amount", &payment.amount)?,
+                    },
+                    &payment.fee,
+                    &payment.nonce,
+                    &payment.memo,
+                    &payment.valid_until,
+                )
+            }
+            (None, Some(delegation)) => (
+                &delegation.delegator,
+                CommandKind::Delegation {
+                    new_delegate: public_key(&delegation.new_delegate)?,
+                },
+                &delegation.fee,
+                &delegation.nonce,
+                &delegation.memo,
+                &delegation.valid_until,
+            ),
+            _ => {
+                return Err(RosettaError::invalid_request()
+                    .with_details("Expected either a payment or a stake delegation"))
+            }
+        };
+
+        let memo = memo.clone().unwrap_or_default();
+        encode_memo(&memo)?;
+        Ok(Self {
+            intent: Intent {
+                source: public_key(source)?,
+                fee: parse_u64("fee", fee)?,
+                kind,
+            },
+            nonce: parse_u32("nonce", nonce)?,
+            valid_until: parse_u32("valid_until", valid_until)?,
+            memo,
+        })
+    }
+
+    /// Daemon inputs of the command
+    fn inputs(&self) -> (Option<PaymentInput>, Option<DelegationInput>) {
+        let memo = (!self.memo.is_empty()).then(|| self.memo.clone());
+        match &self.intent.kind {
+            CommandKind::Payment { receiver, amount } => (
+                Some(PaymentInput {
+                    to: receiver.0.clone(),
+                    from: self.intent.source.0.clone(),
+                    fee: self.intent.fee.to_string(),
+                    token: MINA_TOKEN_ID.to_string(),
+                    nonce: self.nonce.to_string(),
+                    memo,
+                    amount: amount.to_string(),
+                    valid_until: self.valid_until.to_string(),
+                }),
+                None,
+            ),
+            CommandKind::Delegation { new_delegate } => (
+                None,
+                Some(DelegationInput {
+                    delegator: self.intent.source.0.clone(),
+                    new_delegate: new_delegate.0.clone(),
+                    fee: self.intent.fee.to_string(),
+                    nonce: self.nonce.to_string(),
+                    memo,
+                    valid_until: self.valid_until.to_string(),
+                }),
+            ),
+        }
+    }
+
+    /// Receiver & amount of the payload, a delegation's receiver is the new
+    /// delegate
+    fn receiver_amount(&self) -> (&PublicKey, u64) {
+        match &self.intent.kind {
+            CommandKind::Payment { receiver, amount } => (receiver, *amount),
+            CommandKind::Delegation { new_delegate } => (new_delegate, 0),
+        }
+    }
+
+    /// Legacy random oracle input, packed little endian
+    fn random_oracle_input(&self) -> Result<Vec<u8>, RosettaError> {
+        let source = compressed_address(&self.intent.source.0)?;
+        let (receiver, amount) = self.receiver_amount();
+        let receiver = compressed_address(&receiver.0)?;
+        let tag = match self.intent.kind {
+            CommandKind::Payment { .. } => [false, false, false],
+            CommandKind::Delegation { .. } => [false, false, true],
+        };
+
+        let mut bits = vec![];
+        for x in [&source.x, &source.x, &receiver.x] {
+            bits.extend(&x.to_bits()[..FIELD_BITS]);
+        }
+        let mut append = |bytes: &[u8], num_bits: usize| {
+            bits.extend((0..num_bits).map(|i| bytes[i / 8] >> (i % 8) & 1 == 1));
+        };
+
+        // common
+        append(&self.intent.fee.to_le_bytes(), 64);
+        append(&MINA_TOKEN_ID.to_le_bytes(), 64);
+        append(&[source.is_odd as u8], 1);
+        append(&self.nonce.to_le_bytes(), 32);
+        append(&self.valid_until.to_le_bytes(), 32);
+        let memo = encode_memo(&self.memo)?;
+        append(&memo, memo.len() * 8);
+
+        // body
+        for bit in tag {
+            append(&[bit as u8], 1);
+        }
+        append(&[source.is_odd as u8], 1);
+        append(&[receiver.is_odd as u8], 1);
+        append(&MINA_TOKEN_ID.to_le_bytes(), 64);
+        append(&amount.to_le_bytes(), 64);
+        append(&[0], 1);
+
+        let mut bytes = vec![0; bits.len().div_ceil(8)];
+        for (i, bit) in bits.into_iter().enumerate() {
+            bytes[i / 8] |= (bit as u8) << (i % 8);
+        }
+        Ok(bytes)
+    }
+
+    fn signed_command(
+        &self,
+        signature: SignatureV1,
+    ) -> Result<mina_rs::SignedCommandV1, RosettaError> {
+        let source = compressed_address(&self.intent.source.0)?;
+        let (receiver, amount) = self.receiver_amount();
+        let receiver = compressed_address(&receiver.0)?;
+
+        let body = match self.intent.kind {
+            CommandKind::Payment { .. } => mina_rs::SignedCommandPayloadBody::PaymentPayload(
+                mina_rs::PaymentPayload {
+                    source_pk: (&source).into(),
+                    receiver_pk: (&receiver).into(),
+                    token_id: MINA_TOKEN_ID.into(),
+                    amount: amount.into(),
+                }
+                .into(),
+            ),
+            CommandKind::Delegation { .. } => mina_rs::SignedCommandPayloadBody::StakeDelegation(
+                mina_rs::StakeDelegation::SetDelegate {
+                    delegator: (&source).into(),
+                    new_delegate: (&receiver).into(),
+                }
+                .into(),
+            ),
+        };
+        let common = mina_rs::SignedCommandPayloadCommon {
+            fee: self.intent.fee.into(),
+            fee_token: MINA_TOKEN_ID.into(),
+            fee_payer_pk: PublicKeyV1::from(&source),
+            nonce: (self.nonce as i32).into(),
+            valid_until: (self.valid_until as i32).into(),
+            memo: mina_rs::SignedCommandMemo(encode_memo(&self.memo)?.to_vec()).into(),
+        };
+
+        Ok(mina_rs::SignedCommand {
+            payload: mina_rs::SignedCommandPayload {
+                common: common.into(),
+                body: body.into(),
+            }
+            .into(),
+            signer: PublicKey2V1::from(&source),
+            signature,
+        }
+        .into())
+    }
+}
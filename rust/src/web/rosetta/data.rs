@@ -0,0 +1,681 @@
+//! Rosetta Data API endpoints
+
+use super::{
+    block_identifier, check_network,
+    error::RosettaError,
+    models::*,
+    network_identifier,
+    operations::{
+        internal_command_transaction, is_successful, parse_internal_command_id,
+        user_command_transaction, OperationType, STATUS_FAILED, STATUS_SUCCESS,
+    },
+    parent_block_identifier, parse,
+};
+use crate::{
+    block::{
+        is_valid_state_hash, precomputed::PrecomputedBlock, pruned::PrunedBlock, store::BlockStore,
+        BlockHash, BlockWithoutHeight,
+    },
+    canonicity::store::CanonicityStore,
+    command::{
+        internal::{store::InternalCommandStore, InternalCommandKind, InternalCommandWithData},
+        signed::SignedCommandWithData,
+        store::UserCommandStore,
+    },
+    constants::VERSION,
+    ledger::{
+        public_key::{is_valid_public_key, PublicKey},
+        store::LedgerStore,
+    },
+    store::{
+        block_state_hash_from_key, index_pk_internal_command_sort_key, pk_txn_sort_key_prefix,
+        state_hash_pk_internal_command_sort_key, state_hash_pk_txn_sort_key, txn_hash_of_key,
+        IndexerStore,
+    },
+    web::rest::{is_canonical, pagination::merge_descending},
+};
+use actix_web::{
+    post,
+    web::{self, Data, Json},
+};
+use serde_json::json;
+use speedb::{Direction, IteratorMode};
+use std::{collections::HashMap, sync::Arc};
+
+pub const DEFAULT_SEARCH_LIMIT: u32 = 100;
+pub const MAX_SEARCH_LIMIT: u32 = 1_000;
+
+type RosettaResult<T> = Result<Json<T>, RosettaError>;
+
+#[post("/network/list")]
+pub async fn network_list(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<NetworkListResponse> {
+    let _: MetadataRequest = parse(&body)?;
+    Ok(Json(NetworkListResponse {
+        network_identifiers: vec![network_identifier(&store)],
+    }))
+}
+
+#[post("/network/status")]
+pub async fn network_status(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<NetworkStatusResponse> {
+    let db = store.as_ref();
+    let request: NetworkRequest = parse(&body)?;
+    check_network(db, &request.network_identifier)?;
+
+    let best_tip = db
+        .get_best_block()?
+        .ok_or_else(RosettaError::chain_info_missing)?;
+    let genesis_hash = best_tip.genesis_state_hash();
+    let genesis_height = db.get_block_height(&genesis_hash)?.unwrap_or(1);
+
+    Ok(Json(NetworkStatusResponse {
+        current_block_identifier: block_identifier(&(&best_tip).into()),
+        current_block_timestamp: best_tip.timestamp(),
+        genesis_block_identifier: (genesis_hash, genesis_height).into(),
+        oldest_block_identifier: oldest_block_identifier(db)?,
+        peers: vec![],
+    }))
+}
+
+#[post("/network/options")]
+pub async fn network_options(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<NetworkOptionsResponse> {
+    let request: NetworkRequest = parse(&body)?;
+    check_network(&store, &request.network_identifier)?;
+
+    Ok(Json(NetworkOptionsResponse {
+        version: Version {
+            rosetta_version: ROSETTA_VERSION.to_string(),
+            node_version: VERSION.to_string(),
+        },
+        allow: Allow {
+            operation_statuses: vec![
+                OperationStatus {
+                    status: STATUS_SUCCESS.to_string(),
+                    successful: true,
+                },
+                OperationStatus {
+                    status: STATUS_FAILED.to_string(),
+                    successful: false,
+                },
+            ],
+            operation_types: OperationType::ALL.iter().map(ToString::to_string).collect(),
+            errors: RosettaError::all(),
+            historical_balance_lookup: true,
+            mempool_coins: false,
+        },
+    }))
+}
+
+#[post("/block")]
+pub async fn get_block(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<BlockResponse> {
+    let db = store.as_ref();
+    let request: BlockRequest = parse(&body)?;
+    check_network(db, &request.network_identifier)?;
+
+    let block = best_chain_block(db, &request.block_identifier)?;
+    let summary = BlockWithoutHeight::from(&block);
+    Ok(Json(BlockResponse {
+        block: Block {
+            block_identifier: block_identifier(&summary),
+            parent_block_identifier: parent_block_identifier(db, &summary)?,
+            timestamp: block.timestamp(),
+            transactions: block_transactions(db, &block)?,
+            metadata: Some(json!({
+                "creator": block.creator().0,
+                "winner": block.block_stake_winner().map(|pk| pk.0),
+            })),
+        },
+    }))
+}
+
+#[post("/block/transaction")]
+pub async fn block_transaction(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<BlockTransactionResponse> {
+    let db = store.as_ref();
+    let request: BlockTransactionRequest = parse(&body)?;
+    check_network(db, &request.network_identifier)?;
+
+    let block_identifier = resolve_block_identifier(
+        db,
+        &PartialBlockIdentifier {
+            index: Some(request.block_identifier.index),
+            hash: Some(request.block_identifier.hash),
+        },
+    )?;
+    let state_hash = BlockHash(block_identifier.hash);
+    let txn_hash = &request.transaction_identifier.hash;
+
+    let transaction = match parse_internal_command_id(txn_hash) {
+        Some((kind, cmd_state_hash, index)) if cmd_state_hash == state_hash => {
+            internal_transaction(db, &kind, &state_hash, index)?.map(|(_, txn)| txn)
+        }
+        Some(_) => None,
+        None => db
+            .get_user_command_state_hash(txn_hash, &state_hash)?
+            .map(|cmd| user_command_transaction(&cmd)),
+    };
+
+    transaction
+        .map(|transaction| Json(BlockTransactionResponse { transaction }))
+        .ok_or_else(|| RosettaError::transaction_not_found().with_details(txn_hash))
+}
+
+/// Balance of the account after applying the block (default: best tip)
+#[post("/account/balance")]
+pub async fn account_balance(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<AccountBalanceResponse> {
+    let db = store.as_ref();
+    let request: AccountBalanceRequest = parse(&body)?;
+    check_network(db, &request.network_identifier)?;
+
+    let pk = public_key(&request.account_identifier.address)?;
+    let block_identifier =
+        resolve_block_identifier(db, &request.block_identifier.unwrap_or_default())?;
+    let ledger = db
+        .get_ledger_state_hash(&block_identifier.hash.clone().into(), false)?
+        .ok_or_else(|| {
+            RosettaError::chain_info_missing()
+                .with_details(format!("No ledger for block {}", block_identifier.hash))
+        })?;
+    let account = ledger
+        .accounts
+        .get(&pk)
+        .ok_or_else(|| RosettaError::account_not_found().with_details(&pk))?;
+
+    Ok(Json(AccountBalanceResponse {
+        block_identifier,
+        balances: vec![Amount::credit(account.balance.0)],
+        metadata: Some(json!({
+            "nonce": account.nonce.unwrap_or_default().0.to_string(),
+        })),
+    }))
+}
+
+/// Mina is account based, it has no coins
+#[post("/account/coins")]
+pub async fn account_coins(body: web::Bytes) -> RosettaResult<()> {
+    let _: serde_json::Value = parse(&body)?;
+    Err(RosettaError::unsupported().with_details("Mina is account based"))
+}
+
+/// The indexer only sees transactions included in blocks
+#[post("/mempool")]
+pub async fn mempool(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<MempoolResponse> {
+    let request: NetworkRequest = parse(&body)?;
+    check_network(&store, &request.network_identifier)?;
+
+    Ok(Json(MempoolResponse {
+        transaction_identifiers: vec![],
+    }))
+}
+
+/// Best chain transactions by hash and/or account, highest block first
+///
+/// Account searches stop one match past the requested page rather than
+/// scanning the account's whole history, so their `total_count` is the number
+/// of matches up to `next_offset` + 1, a lower bound of the total whenever
+/// `next_offset` is set. It's exact on the last page.
+#[post("/search/transactions")]
+pub async fn search_transactions(
+    store: Data<Arc<IndexerStore>>,
+    body: web::Bytes,
+) -> RosettaResult<SearchTransactionsResponse> {
+    let db = store.as_ref();
+    let request: SearchTransactionsRequest = parse(&body)?;
+    check_network(db, &request.network_identifier)?;
+
+    let account = match request
+        .account_identifier
+        .as_ref()
+        .map(|account| &account.address)
+        .or(request.address.as_ref())
+    {
+        Some(address) => Some(public_key(address)?),
+        None => None,
+    };
+
+    let offset = request.offset.unwrap_or_default() as usize;
+    let limit = request
+        .limit
+        .map_or(DEFAULT_SEARCH_LIMIT, |limit| limit.min(MAX_SEARCH_LIMIT)) as usize;
+    let next_offset = offset + limit;
+
+    let matches = |txn: &BlockTransaction| search_matches(&request, account.as_ref(), txn);
+    let transactions = match (&request.transaction_identifier, &account) {
+        (Some(txn), _) => {
+            let mut transactions = search_hash(db, &txn.hash)?;
+            transactions.retain(matches);
+            transactions.sort_by(|a, b| b.block_identifier.index.cmp(&a.block_identifier.index));
+            transactions
+        }
+        // one match past the page tells whether there's a next page
+        (None, Some(pk)) => search_account(db, pk, request.max_block, next_offset + 1, matches)?,
+        (None, None) => {
+            return Err(RosettaError::invalid_request()
+                .with_details("Search by transaction_identifier, account_identifier or address"))
+        }
+    };
+
+    let total_count = transactions.len();
+
+    Ok(Json(SearchTransactionsResponse {
+        transactions: transactions.into_iter().skip(offset).take(limit).collect(),
+        total_count: total_count as u64,
+        next_offset: (next_offset < total_count).then_some(next_offset as u32),
+    }))
+}
+
+/////////////
+// Helpers //
+/////////////
+
+fn public_key(address: &str) -> Result<PublicKey, RosettaError> {
+    if !is_valid_public_key(address) {
+        return Err(RosettaError::malformed_public_key().with_details(address));
+    }
+    Ok(address.into())
+}
+
+/// Best chain block identifier via height and/or state hash, default to the
+/// best tip
+fn resolve_block_identifier(
+    db: &IndexerStore,
+    block_identifier: &PartialBlockIdentifier,
+) -> Result<BlockIdentifier, RosettaError> {
+    let missing = || {
+        RosettaError::block_missing().with_details(format!(
+            "index: {:?}, hash: {:?}",
+            block_identifier.index, block_identifier.hash
+        ))
+    };
+
+    match (block_identifier.index, &block_identifier.hash) {
+        (None, None) => match (db.get_best_block_hash()?, db.get_best_block_height()?) {
+            (Some(state_hash), Some(height)) => Ok((state_hash, height).into()),
+            _ => Err(RosettaError::chain_info_missing()),
+        },
+        (index, Some(hash)) => {
+            if !is_valid_state_hash(hash) {
+                return Err(missing());
+            }
+
+            let state_hash = BlockHash::from(hash.as_str());
+            let height = db.get_block_height(&state_hash)?.ok_or_else(missing)?;
+            if index.is_some_and(|index| index != height) || !is_canonical(db, &state_hash)? {
+                return Err(missing());
+            }
+            Ok((state_hash, height).into())
+        }
+        (Some(index), None) => db
+            .get_canonical_hash_at_height(index)?
+            .map(|state_hash| (state_hash, index).into())
+            .ok_or_else(missing),
+    }
+}
+
+/// Full precomputed block, or the compact contents of a pruned block
+enum BestChainBlock {
+    Full(PrecomputedBlock),
+    Pruned(PrunedBlock),
+}
+
+fn best_chain_block(
+    db: &IndexerStore,
+    block_identifier: &PartialBlockIdentifier,
+) -> Result<BestChainBlock, RosettaError> {
+    let block_identifier = resolve_block_identifier(db, block_identifier)?;
+    let state_hash = BlockHash::from(block_identifier.hash.as_str());
+
+    // pruned blocks are served without re-hydrating them from the blocks dir
+    if let Some(pruned) = db.get_pruned_block(&state_hash)? {
+        return Ok(BestChainBlock::Pruned(pruned));
+    }
+    db.get_block(&state_hash)?
+        .map(|(block, _)| BestChainBlock::Full(block))
+        .ok_or_else(|| {
+            RosettaError::block_missing().with_details(format!(
+                "Block {} missing from store",
+                block_identifier.hash
+            ))
+        })
+}
+
+/// Lowest best chain block in the store
+fn oldest_block_identifier(db: &IndexerStore) -> anyhow::Result<Option<BlockIdentifier>> {
+    for (key, _) in db.blocks_height_iterator(IteratorMode::Start).flatten() {
+        let state_hash = block_state_hash_from_key(&key)?;
+        if is_canonical(db, &state_hash)? {
+            if let Some(height) = db.get_block_height(&state_hash)? {
+                return Ok(Some((state_hash, height).into()));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// User commands, followed by internal commands, of the block
+fn block_transactions(
+    db: &IndexerStore,
+    block: &BestChainBlock,
+) -> anyhow::Result<Vec<Transaction>> {
+    let state_hash = block.state_hash();
+    let user_commands = db
+        .get_block_user_commands(&state_hash)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|cmd| {
+            user_command_transaction(&SignedCommandWithData::from(
+                &cmd,
+                &state_hash.0,
+                block.blockchain_length(),
+                block.timestamp(),
+                block.global_slot_since_genesis(),
+            ))
+        });
+
+    let new_coinbase_receiver = block.new_coinbase_receiver();
+    let internal_commands = db
+        .get_internal_commands(&state_hash)?
+        .into_iter()
+        .enumerate()
+        .map(|(index, cmd)| {
+            internal_command_transaction(&cmd, index, new_coinbase_receiver.as_ref())
+        });
+
+    Ok(user_commands.chain(internal_commands).collect())
+}
+
+/// Kind, containing block & height of the internal command
+fn internal_command_block(
+    cmd: &InternalCommandWithData,
+) -> (&InternalCommandKind, &BlockHash, u32) {
+    match cmd {
+        InternalCommandWithData::Coinbase {
+            kind,
+            state_hash,
+            block_height,
+            ..
+        }
+        | InternalCommandWithData::FeeTransfer {
+            kind,
+            state_hash,
+            block_height,
+            ..
+        } => (kind, state_hash, *block_height),
+    }
+}
+
+/// The block's new coinbase receiver, if the command is its coinbase
+fn new_coinbase_receiver(
+    db: &IndexerStore,
+    kind: &InternalCommandKind,
+    state_hash: &BlockHash,
+) -> anyhow::Result<Option<PublicKey>> {
+    Ok(match kind {
+        InternalCommandKind::Coinbase => match db.get_pruned_block(state_hash)? {
+            Some(pruned) => pruned.new_coinbase_receiver,
+            None => db
+                .get_block(state_hash)?
+                .and_then(|(block, _)| block.accounts_created().1),
+        },
+        _ => None,
+    })
+}
+
+/// The block's `index`-th internal command, if it has the given kind
+fn internal_transaction(
+    db: &IndexerStore,
+    kind: &InternalCommandKind,
+    state_hash: &BlockHash,
+    index: usize,
+) -> anyhow::Result<Option<(BlockIdentifier, Transaction)>> {
+    let Some(cmd) = db.get_internal_commands(state_hash)?.into_iter().nth(index) else {
+        return Ok(None);
+    };
+
+    let (cmd_kind, _, height) = internal_command_block(&cmd);
+    if cmd_kind != kind {
+        return Ok(None);
+    }
+
+    let new_coinbase_receiver = new_coinbase_receiver(db, kind, state_hash)?;
+    Ok(Some((
+        (state_hash.clone(), height).into(),
+        internal_command_transaction(&cmd, index, new_coinbase_receiver.as_ref()),
+    )))
+}
+
+/// Best chain transactions with the given hash
+fn search_hash(db: &IndexerStore, txn_hash: &str) -> anyhow::Result<Vec<BlockTransaction>> {
+    if let Some((kind, state_hash, index)) = parse_internal_command_id(txn_hash) {
+        if !is_canonical(db, &state_hash)? {
+            return Ok(vec![]);
+        }
+        return Ok(internal_transaction(db, &kind, &state_hash, index)?
+            .map(|(block_identifier, transaction)| BlockTransaction {
+                block_identifier,
+                transaction,
+            })
+            .into_iter()
+            .collect());
+    }
+
+    let mut transactions = vec![];
+    for state_hash in db
+        .get_user_command_state_hashes(txn_hash)?
+        .unwrap_or_default()
+    {
+        if is_canonical(db, &state_hash)? {
+            if let Some(cmd) = db.get_user_command_state_hash(txn_hash, &state_hash)? {
+                transactions.push(BlockTransaction {
+                    block_identifier: (state_hash, cmd.blockchain_length).into(),
+                    transaction: user_command_transaction(&cmd),
+                });
+            }
+        }
+    }
+    Ok(transactions)
+}
+
+/// Whether the transaction matches the search's filters
+fn search_matches(
+    request: &SearchTransactionsRequest,
+    account: Option<&PublicKey>,
+    txn: &BlockTransaction,
+) -> bool {
+    let ops = &txn.transaction.operations;
+    request
+        .max_block
+        .map_or(true, |max| txn.block_identifier.index <= max)
+        && account.map_or(true, |pk| {
+            ops.iter().any(|op| {
+                op.account
+                    .as_ref()
+                    .is_some_and(|account| account.address == pk.0)
+            })
+        })
+        && request.status.as_ref().map_or(true, |status| {
+            ops.iter().any(|op| op.status.as_ref() == Some(status))
+        })
+        && request
+            .kind
+            .as_ref()
+            .map_or(true, |kind| ops.iter().any(|op| op.kind == *kind))
+        && request
+            .success
+            .map_or(true, |success| is_successful(&txn.transaction) == success)
+}
+
+/// The first `max_matches` best chain user & internal commands involving the
+/// account which match the search, highest block first
+///
+/// The account's sender, receiver & internal command sort keys are iterated
+/// from `max_block` down
+fn search_account(
+    db: &IndexerStore,
+    pk: &PublicKey,
+    max_block: Option<u32>,
+    max_matches: usize,
+    matches: impl Fn(&BlockTransaction) -> bool,
+) -> anyhow::Result<Vec<BlockTransaction>> {
+    let prefix = pk.clone().to_bytes();
+    let start = pk_txn_sort_key_prefix(
+        pk.clone(),
+        max_block.map_or(u32::MAX, |max| max.saturating_add(1)),
+    );
+    let mode = IteratorMode::From(&start, Direction::Reverse);
+    let mut user_commands = merge_descending(
+        db.txn_from_height_iterator(mode),
+        db.txn_to_height_iterator(mode),
+    )
+    .flatten()
+    .peekable();
+    let mut internal_commands = db
+        .internal_commands_pk_height_iterator(mode)
+        .flatten()
+        .peekable();
+
+    let mut canonical = HashMap::new();
+    let mut transactions = vec![];
+    while transactions.len() < max_matches {
+        // both sort keys start with the account & block height
+        let is_user_command = match (user_commands.peek(), internal_commands.peek()) {
+            (None, None) => break,
+            (Some((user, _)), Some((internal, _))) => user >= internal,
+            (user, _) => user.is_some(),
+        };
+        let (key, value) = if is_user_command {
+            user_commands.next()
+        } else {
+            internal_commands.next()
+        }
+        .expect("peeked command");
+        if !key.starts_with(&prefix) {
+            break;
+        }
+
+        let state_hash = if is_user_command {
+            state_hash_pk_txn_sort_key(&key)
+        } else {
+            state_hash_pk_internal_command_sort_key(&key)
+        };
+        let is_block_canonical = match canonical.get(&state_hash) {
+            Some(is_block_canonical) => *is_block_canonical,
+            None => {
+                let is_block_canonical = is_canonical(db, &state_hash)?;
+                canonical.insert(state_hash.clone(), is_block_canonical);
+                is_block_canonical
+            }
+        };
+        if !is_block_canonical {
+            continue;
+        }
+
+        let transaction = if is_user_command {
+            let txn_hash = txn_hash_of_key(&key);
+            let Some(cmd) = db.get_user_command_state_hash(&txn_hash, &state_hash)? else {
+                continue;
+            };
+            BlockTransaction {
+                block_identifier: (state_hash, cmd.blockchain_length).into(),
+                transaction: user_command_transaction(&cmd),
+            }
+        } else {
+            // internal commands are identified by their index in the block
+            let cmd: InternalCommandWithData = serde_json::from_slice(&value)?;
+            let index = index_pk_internal_command_sort_key(&key) as usize;
+            let (kind, _, height) = internal_command_block(&cmd);
+            let new_coinbase_receiver = new_coinbase_receiver(db, kind, &state_hash)?;
+            BlockTransaction {
+                block_identifier: (state_hash, height).into(),
+                transaction: internal_command_transaction(
+                    &cmd,
+                    index,
+                    new_coinbase_receiver.as_ref(),
+                ),
+            }
+        };
+        if matches(&transaction) {
+            transactions.push(transaction);
+        }
+    }
+    Ok(transactions)
+}
+
+impl BestChainBlock {
+    fn state_hash(&self) -> BlockHash {
+        match self {
+            Self::Full(block) => block.state_hash(),
+            Self::Pruned(block) => block.state_hash.clone(),
+        }
+    }
+
+    fn blockchain_length(&self) -> u32 {
+        match self {
+            Self::Full(block) => block.blockchain_length(),
+            Self::Pruned(block) => block.blockchain_length,
+        }
+    }
+
+    fn global_slot_since_genesis(&self) -> u32 {
+        match self {
+            Self::Full(block) => block.global_slot_since_genesis(),
+            Self::Pruned(block) => block.global_slot_since_genesis,
+        }
+    }
+
+    fn timestamp(&self) -> u64 {
+        match self {
+            Self::Full(block) => block.timestamp(),
+            Self::Pruned(block) => block.timestamp,
+        }
+    }
+
+    fn creator(&self) -> PublicKey {
+        match self {
+            Self::Full(block) => block.block_creator(),
+            Self::Pruned(block) => block.creator.clone(),
+        }
+    }
+
+    fn block_stake_winner(&self) -> Option<PublicKey> {
+        match self {
+            Self::Full(block) => Some(block.block_stake_winner()),
+            Self::Pruned(block) => block.block_stake_winner.clone(),
+        }
+    }
+
+    fn new_coinbase_receiver(&self) -> Option<PublicKey> {
+        match self {
+            Self::Full(block) => block.accounts_created().1,
+            Self::Pruned(block) => block.new_coinbase_receiver.clone(),
+        }
+    }
+}
+
+impl From<&BestChainBlock> for BlockWithoutHeight {
+    fn from(value: &BestChainBlock) -> Self {
+        match value {
+            BestChainBlock::Full(block) => block.into(),
+            BestChainBlock::Pruned(block) => block.into(),
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Rosetta errors
+//!
+//! Codes follow the reference Mina Rosetta implementation. Every error is
+//! listed in `/network/options` & returned with status 500.
+
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use log::error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RosettaError {
+    pub code: u32,
+    pub message: String,
+    pub retriable: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+impl RosettaError {
+    const fn new(code: u32, retriable: bool) -> Self {
+        Self {
+            code,
+            message: String::new(),
+            retriable,
+            details: None,
+        }
+    }
+
+    fn with_message(mut self, message: &str) -> Self {
+        self.message = message.to_string();
+        self
+    }
+
+    /// Attach the `error` detail
+    pub fn with_details(mut self, details: impl std::fmt::Display) -> Self {
+        self.details = Some(serde_json::json!({ "error": details.to_string() }));
+        self
+    }
+
+    pub fn json_parse() -> Self {
+        Self::new(2, false).with_message("JSON parse error")
+    }
+
+    pub fn network_doesnt_exist() -> Self {
+        Self::new(4, false).with_message("Network doesn't exist")
+    }
+
+    pub fn chain_info_missing() -> Self {
+        Self::new(5, true).with_message("Chain info missing")
+    }
+
+    pub fn account_not_found() -> Self {
+        Self::new(6, true).with_message("Account not found")
+    }
+
+    pub fn invariant_violation() -> Self {
+        Self::new(7, false).with_message("Internal invariant violation (you found a bug)")
+    }
+
+    pub fn transaction_not_found() -> Self {
+        Self::new(8, true).with_message("Transaction not found")
+    }
+
+    pub fn block_missing() -> Self {
+        Self::new(9, true).with_message("Block not found")
+    }
+
+    pub fn malformed_public_key() -> Self {
+        Self::new(10, false).with_message("Malformed public key")
+    }
+
+    pub fn operations_not_valid() -> Self {
+        Self::new(11, false).with_message("Cannot convert operations to valid transaction")
+    }
+
+    pub fn unsupported() -> Self {
+        Self::new(12, false).with_message("Unsupported operation")
+    }
+
+    pub fn signature_missing() -> Self {
+        Self::new(13, false).with_message("Signature missing")
+    }
+
+    pub fn public_key_format_not_valid() -> Self {
+        Self::new(14, false).with_message("Invalid public key format")
+    }
+
+    pub fn no_options_provided() -> Self {
+        Self::new(15, false).with_message("No options provided")
+    }
+
+    pub fn invalid_request() -> Self {
+        Self::new(16, false).with_message("Invalid request")
+    }
+
+    pub fn signature_invalid() -> Self {
+        Self::new(17, false).with_message("Invalid signature")
+    }
+
+    pub fn memo_invalid() -> Self {
+        Self::new(18, false).with_message("Invalid memo")
+    }
+
+    pub fn broadcaster_not_set() -> Self {
+        Self::new(19, false).with_message("No broadcaster set")
+    }
+
+    pub fn transaction_submit_failed() -> Self {
+        Self::new(20, true).with_message("Can't send transaction")
+    }
+
+    /// Every error returned by the API
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::json_parse(),
+            Self::network_doesnt_exist(),
+            Self::chain_info_missing(),
+            Self::account_not_found(),
+            Self::invariant_violation(),
+            Self::transaction_not_found(),
+            Self::block_missing(),
+            Self::malformed_public_key(),
+            Self::operations_not_valid(),
+            Self::unsupported(),
+            Self::signature_missing(),
+            Self::public_key_format_not_valid(),
+            Self::no_options_provided(),
+            Self::invalid_request(),
+            Self::signature_invalid(),
+            Self::memo_invalid(),
+            Self::broadcaster_not_set(),
+            Self::transaction_submit_failed(),
+        ]
+    }
+}
+
+impl std::fmt::Display for RosettaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+impl ResponseError for RosettaError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::InternalServerError().json(self)
+    }
+}
+
+/// Store errors are logged & reported as invariant violations
+impl From<anyhow::Error> for RosettaError {
+    fn from(e: anyhow::Error) -> Self {
+        error!("Rosetta request failed: {e}");
+        Self::invariant_violation().with_details(e)
+    }
+}
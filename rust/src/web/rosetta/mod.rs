@@ -0,0 +1,103 @@
+//! Rosetta Data & Construction APIs
+//!
+//! Serves the block, transaction & historical balance queries of the Rosetta
+//! spec from the indexer store, in place of the archive's Postgres database.
+//! Only blocks of the best chain are served.
+
+pub mod broadcast;
+pub mod construction;
+pub mod data;
+pub mod error;
+pub mod models;
+pub mod operations;
+
+use self::{error::RosettaError, models::*};
+use crate::{
+    block::{store::BlockStore, BlockHash, BlockWithoutHeight},
+    chain::store::ChainStore,
+    store::IndexerStore,
+};
+use actix_web::web;
+use serde::de::DeserializeOwned;
+
+/// Register the Rosetta endpoints
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(data::network_list)
+        .service(data::network_status)
+        .service(data::network_options)
+        .service(data::get_block)
+        .service(data::block_transaction)
+        .service(data::account_balance)
+        .service(data::account_coins)
+        .service(data::mempool)
+        .service(data::search_transactions)
+        .service(construction::construction_derive)
+        .service(construction::construction_preprocess)
+        .service(construction::construction_metadata)
+        .service(construction::construction_payloads)
+        .service(construction::construction_parse)
+        .service(construction::construction_combine)
+        .service(construction::construction_hash)
+        .service(construction::construction_submit);
+}
+
+/// Parse a request body, reporting malformed bodies as Rosetta errors
+pub(crate) fn parse<T: DeserializeOwned>(body: &[u8]) -> Result<T, RosettaError> {
+    serde_json::from_slice(body).map_err(|e| RosettaError::json_parse().with_details(e))
+}
+
+/// The network served by the indexer
+pub(crate) fn network_identifier(db: &IndexerStore) -> NetworkIdentifier {
+    NetworkIdentifier {
+        blockchain: BLOCKCHAIN.to_string(),
+        network: db.get_current_network().unwrap_or_default().to_string(),
+    }
+}
+
+pub(crate) fn check_network(
+    db: &IndexerStore,
+    network_identifier: &NetworkIdentifier,
+) -> Result<(), RosettaError> {
+    if *network_identifier != self::network_identifier(db) {
+        return Err(RosettaError::network_doesnt_exist().with_details(format!(
+            "{}:{}",
+            network_identifier.blockchain, network_identifier.network
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn block_identifier(block: &BlockWithoutHeight) -> BlockIdentifier {
+    BlockIdentifier {
+        index: block.blockchain_length,
+        hash: block.state_hash.0.clone(),
+    }
+}
+
+/// Identifier of the block's parent, the genesis block is its own parent
+pub(crate) fn parent_block_identifier(
+    db: &IndexerStore,
+    block: &BlockWithoutHeight,
+) -> anyhow::Result<BlockIdentifier> {
+    let parent_hash = &block.parent_hash;
+    match db.get_block_height(parent_hash)? {
+        Some(index) => Ok(BlockIdentifier {
+            index,
+            hash: parent_hash.0.clone(),
+        }),
+        None if block.blockchain_length <= 1 => Ok(block_identifier(block)),
+        None => Ok(BlockIdentifier {
+            index: block.blockchain_length - 1,
+            hash: parent_hash.0.clone(),
+        }),
+    }
+}
+
+impl From<(BlockHash, u32)> for BlockIdentifier {
+    fn from((state_hash, index): (BlockHash, u32)) -> Self {
+        Self {
+            index,
+            hash: state_hash.0,
+        }
+    }
+}
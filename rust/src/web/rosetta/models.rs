@@ -0,0 +1,466 @@
+//! Rosetta API request & response objects
+//!
+//! See https://docs.cdp.coinbase.com/mesh/docs/api-reference
+
+use super::error::RosettaError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const ROSETTA_VERSION: &str = "1.4.9";
+pub const BLOCKCHAIN: &str = "mina";
+pub const MINA_SYMBOL: &str = "MINA";
+pub const MINA_DECIMALS: u32 = 9;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkIdentifier {
+    pub blockchain: String,
+    pub network: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockIdentifier {
+    pub index: u32,
+    pub hash: String,
+}
+
+/// Identifies a block by height and/or state hash, the best tip if neither
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialBlockIdentifier {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionIdentifier {
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountIdentifier {
+    pub address: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Currency {
+    pub symbol: String,
+    pub decimals: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Amount {
+    /// Signed amount in nanomina
+    pub value: String,
+    pub currency: Currency,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationIdentifier {
+    pub index: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Operation {
+    pub operation_identifier: OperationIdentifier,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub related_operations: Option<Vec<OperationIdentifier>>,
+
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<AccountIdentifier>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Amount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub transaction_identifier: TransactionIdentifier,
+    pub operations: Vec<Operation>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Block {
+    pub block_identifier: BlockIdentifier,
+    pub parent_block_identifier: BlockIdentifier,
+
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    pub transactions: Vec<Transaction>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockTransaction {
+    pub block_identifier: BlockIdentifier,
+    pub transaction: Transaction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationStatus {
+    pub status: String,
+    pub successful: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Version {
+    pub rosetta_version: String,
+    pub node_version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Allow {
+    pub operation_statuses: Vec<OperationStatus>,
+    pub operation_types: Vec<String>,
+    pub errors: Vec<RosettaError>,
+    pub historical_balance_lookup: bool,
+    pub mempool_coins: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Peer {
+    pub peer_id: String,
+}
+
+/// Compressed public key: the 32 byte little endian x coordinate followed by
+/// the y coordinate parity byte
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicKey {
+    pub hex_bytes: String,
+    pub curve_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningPayload {
+    pub account_identifier: AccountIdentifier,
+
+    /// Random oracle input of the transaction
+    pub hex_bytes: String,
+    pub signature_type: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signature {
+    pub signing_payload: SigningPayload,
+    pub public_key: PublicKey,
+    pub signature_type: String,
+
+    /// The 32 byte little endian `rx` field element followed by the 32 byte
+    /// little endian `s` scalar
+    pub hex_bytes: String,
+}
+
+//////////////
+// Requests //
+//////////////
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkRequest {
+    pub network_identifier: NetworkIdentifier,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub block_identifier: PartialBlockIdentifier,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockTransactionRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub block_identifier: BlockIdentifier,
+    pub transaction_identifier: TransactionIdentifier,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountBalanceRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub account_identifier: AccountIdentifier,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_identifier: Option<PartialBlockIdentifier>,
+}
+
+/// Conditions are combined with `and`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchTransactionsRequest {
+    pub network_identifier: NetworkIdentifier,
+
+    /// Only search blocks at or below this height
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_block: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_identifier: Option<TransactionIdentifier>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_identifier: Option<AccountIdentifier>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+
+    /// Operation status
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    /// Operation type
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionDeriveRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub public_key: PublicKey,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionPreprocessRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub operations: Vec<Operation>,
+
+    /// Optional `valid_until` & `memo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionMetadataRequest {
+    pub network_identifier: NetworkIdentifier,
+
+    /// Options returned by `/construction/preprocess`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<Value>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_keys: Option<Vec<PublicKey>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionPayloadsRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub operations: Vec<Operation>,
+
+    /// Metadata returned by `/construction/metadata`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_keys: Option<Vec<PublicKey>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionParseRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub signed: bool,
+    pub transaction: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionCombineRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub unsigned_transaction: String,
+    pub signatures: Vec<Signature>,
+}
+
+/// Request of `/construction/hash` & `/construction/submit`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionHashRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub signed_transaction: String,
+}
+
+///////////////
+// Responses //
+///////////////
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkListResponse {
+    pub network_identifiers: Vec<NetworkIdentifier>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkStatusResponse {
+    pub current_block_identifier: BlockIdentifier,
+    pub current_block_timestamp: u64,
+    pub genesis_block_identifier: BlockIdentifier,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_block_identifier: Option<BlockIdentifier>,
+    pub peers: Vec<Peer>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkOptionsResponse {
+    pub version: Version,
+    pub allow: Allow,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockResponse {
+    pub block: Block,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockTransactionResponse {
+    pub transaction: Transaction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountBalanceResponse {
+    pub block_identifier: BlockIdentifier,
+    pub balances: Vec<Amount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MempoolResponse {
+    pub transaction_identifiers: Vec<TransactionIdentifier>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchTransactionsResponse {
+    pub transactions: Vec<BlockTransaction>,
+
+    /// Lower bound of the number of matches of account searches with a next
+    /// page, see [search_transactions](super::data::search_transactions)
+    pub total_count: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionDeriveResponse {
+    pub account_identifier: AccountIdentifier,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionPreprocessResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_public_keys: Option<Vec<AccountIdentifier>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionMetadataResponse {
+    pub metadata: Value,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_fee: Option<Vec<Amount>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionPayloadsResponse {
+    pub unsigned_transaction: String,
+    pub payloads: Vec<SigningPayload>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionParseResponse {
+    pub operations: Vec<Operation>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_identifier_signers: Option<Vec<AccountIdentifier>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionCombineResponse {
+    pub signed_transaction: String,
+}
+
+/// Response of `/construction/hash` & `/construction/submit`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionIdentifierResponse {
+    pub transaction_identifier: TransactionIdentifier,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+impl Currency {
+    pub fn mina() -> Self {
+        Self {
+            symbol: MINA_SYMBOL.to_string(),
+            decimals: MINA_DECIMALS,
+        }
+    }
+}
+
+impl Amount {
+    /// Credit of `nanomina`
+    pub fn credit(nanomina: u64) -> Self {
+        Self {
+            value: nanomina.to_string(),
+            currency: Currency::mina(),
+        }
+    }
+
+    /// Debit of `nanomina`
+    pub fn debit(nanomina: u64) -> Self {
+        Self {
+            value: if nanomina == 0 {
+                "0".to_string()
+            } else {
+                format!("-{nanomina}")
+            },
+            currency: Currency::mina(),
+        }
+    }
+}
+
+impl AccountIdentifier {
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+            metadata: None,
+        }
+    }
+}
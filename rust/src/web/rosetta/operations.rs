@@ -0,0 +1,295 @@
+//! Mina user & internal commands as Rosetta operations
+//!
+//! Operation types & their balance changes follow the reference Mina Rosetta
+//! implementation. A failed user command still pays its fee, so only its
+//! `fee_payment` operation succeeds.
+//!
+//! Internal commands don't have a transaction hash, they're identified by
+//! `<kind>:<state hash>:<index in the block's internal commands>`.
+
+use super::models::{
+    AccountIdentifier, Amount, Operation, OperationIdentifier, Transaction, TransactionIdentifier,
+};
+use crate::{
+    block::{is_valid_state_hash, BlockHash},
+    command::{
+        decode_memo,
+        internal::{InternalCommandKind, InternalCommandWithData},
+        signed::SignedCommandWithData,
+        CommandStatusData,
+    },
+    constants::MAINNET_ACCOUNT_CREATION_FEE,
+    ledger::public_key::PublicKey,
+};
+use serde_json::json;
+use std::fmt;
+
+pub const STATUS_SUCCESS: &str = "Success";
+pub const STATUS_FAILED: &str = "Failed";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationType {
+    FeePayerDec,
+    FeeReceiverInc,
+    CoinbaseInc,
+    AccountCreationFeeViaPayment,
+    AccountCreationFeeViaFeePayer,
+    AccountCreationFeeViaFeeReceiver,
+    PaymentSourceDec,
+    PaymentReceiverInc,
+    FeePayment,
+    DelegateChange,
+    CreateToken,
+    MintTokens,
+}
+
+impl OperationType {
+    pub const ALL: [Self; 12] = [
+        Self::FeePayerDec,
+        Self::FeeReceiverInc,
+        Self::CoinbaseInc,
+        Self::AccountCreationFeeViaPayment,
+        Self::AccountCreationFeeViaFeePayer,
+        Self::AccountCreationFeeViaFeeReceiver,
+        Self::PaymentSourceDec,
+        Self::PaymentReceiverInc,
+        Self::FeePayment,
+        Self::DelegateChange,
+        Self::CreateToken,
+        Self::MintTokens,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::FeePayerDec => "fee_payer_dec",
+            Self::FeeReceiverInc => "fee_receiver_inc",
+            Self::CoinbaseInc => "coinbase_inc",
+            Self::AccountCreationFeeViaPayment => "account_creation_fee_via_payment",
+            Self::AccountCreationFeeViaFeePayer => "account_creation_fee_via_fee_payer",
+            Self::AccountCreationFeeViaFeeReceiver => "account_creation_fee_via_fee_receiver",
+            Self::PaymentSourceDec => "payment_source_dec",
+            Self::PaymentReceiverInc => "payment_receiver_inc",
+            Self::FeePayment => "fee_payment",
+            Self::DelegateChange => "delegate_change",
+            Self::CreateToken => "create_token",
+            Self::MintTokens => "mint_tokens",
+        }
+    }
+}
+
+impl fmt::Display for OperationType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Operations of a transaction, indexed in order
+#[derive(Default)]
+pub(super) struct Operations(pub Vec<Operation>);
+
+impl Operations {
+    /// Operations of constructed transactions don't have a status
+    pub(super) fn push(
+        &mut self,
+        kind: OperationType,
+        account: &PublicKey,
+        amount: Option<Amount>,
+        status: Option<&str>,
+    ) -> OperationIdentifier {
+        let operation_identifier = OperationIdentifier {
+            index: self.0.len() as u32,
+        };
+        self.0.push(Operation {
+            operation_identifier: operation_identifier.clone(),
+            related_operations: None,
+            kind: kind.to_string(),
+            status: status.map(ToString::to_string),
+            account: Some(AccountIdentifier::new(account.0.clone())),
+            amount,
+            metadata: None,
+        });
+        operation_identifier
+    }
+
+    pub(super) fn last_mut(&mut self) -> &mut Operation {
+        self.0.last_mut().expect("pushed operation")
+    }
+}
+
+/// User command operations
+pub fn user_command_transaction(cmd: &SignedCommandWithData) -> Transaction {
+    let command = &cmd.command;
+    let (status, failure_reason) = match &cmd.status {
+        CommandStatusData::Applied { .. } => (STATUS_SUCCESS, None),
+        CommandStatusData::Failed(failed_types, _) => (
+            STATUS_FAILED,
+            failed_types.first().map(|reason| reason.to_string()),
+        ),
+    };
+
+    let mut operations = Operations::default();
+    operations.push(
+        OperationType::FeePayment,
+        &command.fee_payer_pk(),
+        Some(Amount::debit(command.fee())),
+        Some(STATUS_SUCCESS),
+    );
+
+    if command.is_delegation() {
+        operations.push(
+            OperationType::DelegateChange,
+            &command.source_pk(),
+            None,
+            Some(status),
+        );
+        operations.last_mut().metadata =
+            Some(json!({ "delegate_change_target": command.receiver_pk().0 }));
+    } else {
+        let receiver = command.receiver_pk();
+        if let Some(fee) = cmd.status.receiver_account_creation_fee_paid() {
+            operations.push(
+                OperationType::AccountCreationFeeViaPayment,
+                &receiver,
+                Some(Amount::debit(fee)),
+                Some(status),
+            );
+        }
+
+        let source_dec = operations.push(
+            OperationType::PaymentSourceDec,
+            &command.source_pk(),
+            Some(Amount::debit(command.amount())),
+            Some(status),
+        );
+        operations.push(
+            OperationType::PaymentReceiverInc,
+            &receiver,
+            Some(Amount::credit(command.amount())),
+            Some(status),
+        );
+        operations.last_mut().related_operations = Some(vec![source_dec]);
+    }
+
+    let memo = decode_memo(&command.payload_common().memo.t.0);
+    let mut metadata = serde_json::Map::new();
+    if !memo.is_empty() {
+        metadata.insert("memo".into(), memo.into());
+    }
+    if let Some(failure_reason) = failure_reason {
+        metadata.insert("failure_reason".into(), failure_reason.into());
+    }
+
+    Transaction {
+        transaction_identifier: TransactionIdentifier {
+            hash: cmd.tx_hash.clone(),
+        },
+        operations: operations.0,
+        metadata: (!metadata.is_empty()).then_some(metadata.into()),
+    }
+}
+
+/// Internal command operations
+///
+/// `new_coinbase_receiver` pays the account creation fee out of the coinbase
+pub fn internal_command_transaction(
+    cmd: &InternalCommandWithData,
+    index: usize,
+    new_coinbase_receiver: Option<&PublicKey>,
+) -> Transaction {
+    let mut operations = Operations::default();
+    let (kind, state_hash) = match cmd {
+        InternalCommandWithData::Coinbase {
+            receiver,
+            amount,
+            state_hash,
+            kind,
+            ..
+        } => {
+            operations.push(
+                OperationType::CoinbaseInc,
+                receiver,
+                Some(Amount::credit(*amount)),
+                Some(STATUS_SUCCESS),
+            );
+            if new_coinbase_receiver == Some(receiver) {
+                operations.push(
+                    OperationType::AccountCreationFeeViaFeeReceiver,
+                    receiver,
+                    Some(Amount::debit(MAINNET_ACCOUNT_CREATION_FEE.0)),
+                    Some(STATUS_SUCCESS),
+                );
+            }
+            (kind, state_hash)
+        }
+        InternalCommandWithData::FeeTransfer {
+            sender,
+            receiver,
+            amount,
+            state_hash,
+            kind,
+            ..
+        } => {
+            operations.push(
+                OperationType::FeeReceiverInc,
+                receiver,
+                Some(Amount::credit(*amount)),
+                Some(STATUS_SUCCESS),
+            );
+
+            // the coinbase receiver pays the fee transfer out of the coinbase
+            if *kind == InternalCommandKind::FeeTransferViaCoinbase {
+                operations.push(
+                    OperationType::FeePayerDec,
+                    sender,
+                    Some(Amount::debit(*amount)),
+                    Some(STATUS_SUCCESS),
+                );
+            }
+            (kind, state_hash)
+        }
+    };
+
+    Transaction {
+        transaction_identifier: TransactionIdentifier {
+            hash: internal_command_id(kind, state_hash, index),
+        },
+        operations: operations.0,
+        metadata: None,
+    }
+}
+
+/// Identifier of the block's `index`-th internal command
+pub fn internal_command_id(
+    kind: &InternalCommandKind,
+    state_hash: &BlockHash,
+    index: usize,
+) -> String {
+    format!("{kind}:{state_hash}:{index}")
+}
+
+/// Parse an internal command identifier into the block's state hash & the
+/// command's index
+pub fn parse_internal_command_id(id: &str) -> Option<(InternalCommandKind, BlockHash, usize)> {
+    let mut parts = id.split(':');
+    let kind = match parts.next()? {
+        "Coinbase" => InternalCommandKind::Coinbase,
+        "Fee_transfer" => InternalCommandKind::FeeTransfer,
+        "Fee_transfer_via_coinbase" => InternalCommandKind::FeeTransferViaCoinbase,
+        _ => return None,
+    };
+    let state_hash = parts.next()?;
+    let index = parts.next()?.parse().ok()?;
+
+    if parts.next().is_some() || !is_valid_state_hash(state_hash) {
+        return None;
+    }
+    Some((kind, state_hash.into(), index))
+}
+
+/// Whether every operation of the transaction succeeded
+pub fn is_successful(transaction: &Transaction) -> bool {
+    transaction
+        .operations
+        .iter()
+        .all(|op| op.status.as_deref() == Some(STATUS_SUCCESS))
+}
@@ -0,0 +1,362 @@
+//! Signed delivery of webhook payloads with persisted cursors & backoff
+
+use super::{
+    payload::{event_block, webhook_events, WebhookPayload},
+    store::WebhookStore,
+    Webhook, WebhookCursor,
+};
+use crate::{
+    block::{store::BlockStore, BlockHash},
+    canonicity::store::CanonicityStore,
+    event::{broadcast::EventReceiver, store::EventStore, SeqEvent},
+    store::IndexerStore,
+};
+use anyhow::bail;
+use hmac::{Hmac, Mac};
+use log::{debug, error, info, warn};
+use sha2::Sha256;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_graceful_shutdown::SubsystemHandle;
+
+/// Hex HMAC-SHA256 of the payload body, prefixed with `sha256=`
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Mina-Indexer-Signature";
+
+/// Kind of the delivered payload
+pub const WEBHOOK_EVENT_HEADER: &str = "X-Mina-Indexer-Event";
+
+/// Delivery id of the payload
+pub const WEBHOOK_DELIVERY_HEADER: &str = "X-Mina-Indexer-Delivery";
+
+/// Max number of events read from the event log at a time
+const WEBHOOK_EVENT_BATCH_SIZE: usize = 100;
+
+/// Interval between delivery rounds without new events, e.g. for retries
+const WEBHOOK_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Backoff after the first failed delivery, doubled after each
+    /// consecutive failure
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+
+    /// Timeout of a single delivery
+    pub timeout: Duration,
+}
+
+/// Delivers payloads to all registered webhooks
+///
+/// Deliveries are at-least-once: a payload is redelivered if a later payload
+/// of the same event fails. Block events awaiting confirmations are tracked in
+/// the cursor & delivered once confirmed, without holding back later events.
+pub struct WebhookDispatcher {
+    store: Arc<IndexerStore>,
+    client: reqwest::Client,
+    retry: RetryPolicy,
+
+    /// Webhooks backing off until the given instant
+    backoff: HashMap<u32, Instant>,
+}
+
+enum Confirmation {
+    Pending,
+    Confirmed,
+    Orphaned,
+}
+
+impl WebhookDispatcher {
+    pub fn new(store: Arc<IndexerStore>) -> Self {
+        Self::with_retry_policy(store, RetryPolicy::default())
+    }
+
+    pub fn with_retry_policy(store: Arc<IndexerStore>, retry: RetryPolicy) -> Self {
+        Self {
+            store,
+            client: reqwest::Client::new(),
+            retry,
+            backoff: HashMap::new(),
+        }
+    }
+
+    /// Deliver events to webhooks until shutdown, woken by newly recorded
+    /// events
+    pub async fn run(
+        mut self,
+        subsys: SubsystemHandle,
+        mut events: EventReceiver,
+    ) -> anyhow::Result<()> {
+        loop {
+            tokio::select! {
+                _ = subsys.on_shutdown_requested() => break,
+                event = events.recv() => {
+                    // the event log is the source of truth, skipped events are delivered anyway
+                    if let Err(RecvError::Closed) = event {
+                        break;
+                    }
+                }
+                _ = tokio::time::sleep(WEBHOOK_POLL_INTERVAL) => (),
+            }
+
+            if let Err(e) = self.dispatch().await {
+                error!("Webhook delivery failed: {e}");
+            }
+        }
+
+        info!("Webhook dispatcher successfully shutdown");
+        Ok(())
+    }
+
+    /// Deliver all pending events to webhooks which aren't backing off
+    ///
+    /// Returns the number of delivered payloads
+    pub async fn dispatch(&mut self) -> anyhow::Result<u64> {
+        let mut num_delivered = 0;
+        for webhook in self.store.get_webhooks()? {
+            if self
+                .backoff
+                .get(&webhook.id)
+                .is_some_and(|until| *until > Instant::now())
+            {
+                continue;
+            }
+
+            let mut cursor = self
+                .store
+                .get_webhook_cursor(webhook.id)?
+                .unwrap_or_default();
+            let num_delivered_before = cursor.num_delivered;
+            match self.deliver(&webhook, &mut cursor).await {
+                Ok(()) => {
+                    self.backoff.remove(&webhook.id);
+                }
+                Err(e) => {
+                    cursor.num_failures += 1;
+                    cursor.last_error = Some(e.to_string());
+
+                    let backoff = self.retry.backoff(cursor.num_failures);
+                    warn!(
+                        "Webhook {} delivery of event {} failed {} time(s), retrying in {backoff:?}: {e}",
+                        webhook.id, cursor.seq_num, cursor.num_failures
+                    );
+                    self.backoff.insert(webhook.id, Instant::now() + backoff);
+                }
+            }
+
+            num_delivered += cursor.num_delivered - num_delivered_before;
+            self.store.set_webhook_cursor(webhook.id, &cursor)?;
+        }
+        Ok(num_delivered)
+    }
+
+    /// Deliver the webhook's pending events, advancing its cursor
+    async fn deliver(&self, webhook: &Webhook, cursor: &mut WebhookCursor) -> anyhow::Result<()> {
+        self.deliver_unconfirmed(webhook, cursor).await?;
+        loop {
+            let events =
+                self.store
+                    .get_events(cursor.seq_num, None, None, WEBHOOK_EVENT_BATCH_SIZE)?;
+            let num_events = events.len();
+
+            for event in events {
+                if let Some((state_hash, blockchain_length)) = event_block(&event) {
+                    match self.confirmation(
+                        state_hash,
+                        blockchain_length,
+                        webhook.filter.confirmations,
+                    )? {
+                        Confirmation::Pending => {
+                            cursor.unconfirmed.push(event.seq_num);
+                            cursor.seq_num = event.seq_num + 1;
+                            continue;
+                        }
+                        Confirmation::Confirmed => (),
+                        Confirmation::Orphaned => {
+                            debug!(
+                                "Skipping event {} of orphaned block {state_hash}",
+                                event.seq_num
+                            );
+                            cursor.seq_num = event.seq_num + 1;
+                            continue;
+                        }
+                    }
+                }
+
+                let mut best_tip = cursor.best_tip.clone();
+                self.deliver_event(webhook, &event, &mut best_tip, &mut cursor.num_delivered)
+                    .await?;
+                cursor.seq_num = event.seq_num + 1;
+                cursor.best_tip = best_tip;
+                cursor.num_failures = 0;
+                cursor.last_error = None;
+            }
+
+            if num_events < WEBHOOK_EVENT_BATCH_SIZE {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Deliver the block events which have since been confirmed, dropping
+    /// those of orphaned blocks
+    async fn deliver_unconfirmed(
+        &self,
+        webhook: &Webhook,
+        cursor: &mut WebhookCursor,
+    ) -> anyhow::Result<()> {
+        let mut unconfirmed = std::mem::take(&mut cursor.unconfirmed).into_iter();
+        while let Some(seq_num) = unconfirmed.next() {
+            let Some(event) = self
+                .store
+                .get_events(seq_num, Some(seq_num + 1), None, 1)?
+                .pop()
+            else {
+                // only in stores compacted before pending events were kept
+                warn!("Webhook {} event {seq_num} was compacted", webhook.id);
+                continue;
+            };
+            let Some((state_hash, blockchain_length)) = event_block(&event) else {
+                continue;
+            };
+
+            match self.confirmation(state_hash, blockchain_length, webhook.filter.confirmations)? {
+                Confirmation::Pending => cursor.unconfirmed.push(seq_num),
+                Confirmation::Orphaned => {
+                    debug!("Skipping event {seq_num} of orphaned block {state_hash}");
+                }
+                Confirmation::Confirmed => {
+                    // block events don't depend on the best tip
+                    let mut best_tip = cursor.best_tip.clone();
+                    if let Err(e) = self
+                        .deliver_event(webhook, &event, &mut best_tip, &mut cursor.num_delivered)
+                        .await
+                    {
+                        // keep the failed & remaining events for the retry
+                        cursor.unconfirmed.push(seq_num);
+                        cursor.unconfirmed.extend(unconfirmed);
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Deliver the payloads of the event, counting them in `num_delivered`
+    async fn deliver_event(
+        &self,
+        webhook: &Webhook,
+        event: &SeqEvent,
+        best_tip: &mut Option<BlockHash>,
+        num_delivered: &mut u64,
+    ) -> anyhow::Result<()> {
+        let webhook_events = webhook_events(&self.store, &webhook.filter, event, best_tip)?;
+        for (n, webhook_event) in webhook_events.into_iter().enumerate() {
+            let payload = WebhookPayload {
+                webhook_id: webhook.id,
+                seq_num: event.seq_num,
+                delivery_id: format!("{}-{}-{n}", webhook.id, event.seq_num),
+                event: webhook_event,
+            };
+            self.post(webhook, &payload).await?;
+            *num_delivered += 1;
+        }
+        Ok(())
+    }
+
+    /// Whether the block has `confirmations` blocks on top of it in the best
+    /// chain
+    fn confirmation(
+        &self,
+        state_hash: &BlockHash,
+        blockchain_length: u32,
+        confirmations: u32,
+    ) -> anyhow::Result<Confirmation> {
+        if confirmations == 0 {
+            return Ok(Confirmation::Confirmed);
+        }
+
+        let best_tip_height = self.store.get_best_block_height()?.unwrap_or_default();
+        if best_tip_height < blockchain_length + confirmations {
+            return Ok(Confirmation::Pending);
+        }
+
+        // the best chain block at the block's height
+        let canonical_hash = self.store.get_canonical_hash_at_height(blockchain_length)?;
+        Ok(if canonical_hash.as_ref() == Some(state_hash) {
+            Confirmation::Confirmed
+        } else {
+            Confirmation::Orphaned
+        })
+    }
+
+    async fn post(&self, webhook: &Webhook, payload: &WebhookPayload) -> anyhow::Result<()> {
+        let body = serde_json::to_vec(payload)?;
+        let response = self
+            .client
+            .post(&webhook.url)
+            .timeout(self.retry.timeout)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(
+                WEBHOOK_SIGNATURE_HEADER,
+                webhook_signature(&webhook.secret, &body),
+            )
+            .header(WEBHOOK_EVENT_HEADER, payload.event.kind().as_str())
+            .header(WEBHOOK_DELIVERY_HEADER, &payload.delivery_id)
+            .body(body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            bail!("{} responded with {status}", webhook.url)
+        }
+        debug!(
+            "Delivered webhook {} payload {}",
+            webhook.id, payload.delivery_id
+        );
+        Ok(())
+    }
+}
+
+impl RetryPolicy {
+    /// Backoff after `num_failures` consecutive failures
+    pub fn backoff(&self, num_failures: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(num_failures.saturating_sub(1)))
+            .min(self.max_backoff)
+    }
+}
+
+impl std::default::Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(3600),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Signature of a webhook payload body: `sha256={hex HMAC-SHA256}`
+pub fn webhook_signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Verify a webhook payload body's signature in constant time
+pub fn verify_webhook_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(Ok(signature)) = signature.strip_prefix("sha256=").map(hex::decode) else {
+        return false;
+    };
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
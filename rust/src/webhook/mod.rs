@@ -0,0 +1,200 @@
+//! Outbound webhooks for indexer events
+//!
+//! Registered [Webhook]s receive signed JSON [payload::WebhookPayload]s
+//! derived from the event log. Each webhook has its own persisted
+//! [WebhookCursor], so deliveries resume where they left off after a
+//! restart & failing targets are retried with exponential backoff.
+
+pub mod delivery;
+pub mod payload;
+pub mod store;
+
+use crate::{
+    block::BlockHash,
+    ledger::public_key::{is_valid_public_key, PublicKey},
+};
+use anyhow::bail;
+use payload::WebhookEvent;
+use serde::{Deserialize, Serialize};
+
+/// Registered webhook target
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: u32,
+    pub url: String,
+
+    /// Shared secret used to sign payloads
+    pub secret: String,
+    pub filter: WebhookFilter,
+}
+
+/// Determines which payloads are delivered to a webhook
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookFilter {
+    /// Payload kinds to deliver (empty for all)
+    pub kinds: Vec<WebhookEventKind>,
+
+    /// Public keys, one of which must be involved (empty for all)
+    pub public_keys: Vec<PublicKey>,
+
+    /// Minimum amount (nanomina) of user & internal commands
+    pub min_amount: Option<u64>,
+
+    /// Number of blocks built on top of a block before its payloads are
+    /// delivered
+    pub confirmations: u32,
+}
+
+/// Delivery progress of a webhook
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookCursor {
+    /// Sequence number of the next event to deliver
+    pub seq_num: u32,
+
+    /// Best tip as of the last processed event, used to detect reorgs
+    pub best_tip: Option<BlockHash>,
+
+    /// Total number of delivered payloads
+    pub num_delivered: u64,
+
+    /// Number of consecutive failed deliveries
+    pub num_failures: u32,
+    pub last_error: Option<String>,
+
+    /// Sequence numbers of block events awaiting confirmations
+    #[serde(default)]
+    pub unconfirmed: Vec<u32>,
+}
+
+/// Kinds of webhook payloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventKind {
+    NewBlock,
+    NewBestTip,
+    NewCanonicalBlock,
+    NewStakingLedger,
+    UserCommand,
+    InternalCommand,
+    Reorg,
+}
+
+impl Webhook {
+    /// Copy of the webhook without its secret
+    pub fn redacted(&self) -> Self {
+        Self {
+            secret: "<redacted>".to_string(),
+            ..self.clone()
+        }
+    }
+}
+
+impl WebhookFilter {
+    /// Parse the comma-separated kinds & public keys of a filter
+    pub fn parse(
+        kinds: Option<&str>,
+        public_keys: Option<&str>,
+        min_amount: Option<u64>,
+        confirmations: u32,
+    ) -> anyhow::Result<Self> {
+        let kinds = comma_separated(kinds)
+            .map(str::parse)
+            .collect::<anyhow::Result<_>>()?;
+        let public_keys = comma_separated(public_keys)
+            .map(|pk| {
+                if !is_valid_public_key(pk) {
+                    bail!("Invalid public key {pk}")
+                }
+                Ok(pk.into())
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            kinds,
+            public_keys,
+            min_amount,
+            confirmations,
+        })
+    }
+
+    /// Check whether payloads of `kind` are delivered
+    pub fn wants(&self, kind: WebhookEventKind) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
+
+    /// Check whether the payload is delivered
+    ///
+    /// A public key filter only matches payloads which involve one of its
+    /// public keys & a minimum amount only applies to payloads with amounts
+    pub fn matches(&self, event: &WebhookEvent) -> bool {
+        if !self.wants(event.kind()) {
+            return false;
+        }
+        if !self.public_keys.is_empty()
+            && !event
+                .public_keys()
+                .iter()
+                .any(|pk| self.public_keys.contains(pk))
+        {
+            return false;
+        }
+        match (self.min_amount, event.amount()) {
+            (Some(min_amount), Some(amount)) => amount >= min_amount,
+            _ => true,
+        }
+    }
+}
+
+fn comma_separated(input: Option<&str>) -> impl Iterator<Item = &str> {
+    input
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+impl WebhookEventKind {
+    pub const ALL: [Self; 7] = [
+        Self::NewBlock,
+        Self::NewBestTip,
+        Self::NewCanonicalBlock,
+        Self::NewStakingLedger,
+        Self::UserCommand,
+        Self::InternalCommand,
+        Self::Reorg,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NewBlock => "new_block",
+            Self::NewBestTip => "new_best_tip",
+            Self::NewCanonicalBlock => "new_canonical_block",
+            Self::NewStakingLedger => "new_staking_ledger",
+            Self::UserCommand => "user_command",
+            Self::InternalCommand => "internal_command",
+            Self::Reorg => "reorg",
+        }
+    }
+}
+
+impl std::str::FromStr for WebhookEventKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| {
+                let kinds: Vec<&str> = Self::ALL.iter().map(Self::as_str).collect();
+                anyhow::anyhow!(
+                    "Invalid webhook event kind {s}, expected one of {}",
+                    kinds.join(", ")
+                )
+            })
+    }
+}
+
+impl std::fmt::Display for WebhookEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -0,0 +1,314 @@
+//! Webhook payloads derived from [IndexerEvent]s & command data
+
+use super::{WebhookEventKind, WebhookFilter};
+use crate::{
+    block::{precomputed::PrecomputedBlock, store::BlockStore, BlockHash},
+    command::{
+        internal::{InternalCommand, InternalCommandKind},
+        signed::SignedCommandWithData,
+    },
+    event::{db::*, IndexerEvent, SeqEvent},
+    ledger::{public_key::PublicKey, LedgerHash},
+    store::IndexerStore,
+};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+/// JSON body POSTed to a webhook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    pub webhook_id: u32,
+
+    /// Sequence number of the event the payload is derived from
+    pub seq_num: u32,
+
+    /// Unique per webhook, event & payload, for receivers to deduplicate
+    /// redeliveries
+    pub delivery_id: String,
+
+    #[serde(flatten)]
+    pub event: WebhookEvent,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    NewBlock {
+        state_hash: BlockHash,
+        blockchain_length: u32,
+        creator: Option<PublicKey>,
+        coinbase_receiver: Option<PublicKey>,
+    },
+    NewBestTip {
+        state_hash: BlockHash,
+        blockchain_length: u32,
+        previous_state_hash: Option<BlockHash>,
+    },
+    NewCanonicalBlock {
+        state_hash: BlockHash,
+        blockchain_length: u32,
+        creator: Option<PublicKey>,
+        coinbase_receiver: Option<PublicKey>,
+    },
+    NewStakingLedger {
+        epoch: u32,
+        ledger_hash: LedgerHash,
+        genesis_state_hash: BlockHash,
+    },
+    UserCommand {
+        state_hash: BlockHash,
+        blockchain_length: u32,
+        tx_hash: String,
+        is_delegation: bool,
+        sender: PublicKey,
+        receiver: PublicKey,
+        amount: u64,
+        fee: u64,
+        nonce: u32,
+        is_applied: bool,
+    },
+    InternalCommand {
+        state_hash: BlockHash,
+        blockchain_length: u32,
+        internal_command_kind: InternalCommandKind,
+        sender: Option<PublicKey>,
+        receiver: PublicKey,
+        amount: u64,
+    },
+    Reorg {
+        previous_best_tip: BlockHash,
+        best_tip: BlockHash,
+
+        /// Number of blocks of the previous best chain which are no longer
+        /// on the best chain
+        depth: u32,
+
+        /// Blocks no longer on the best chain, highest first
+        orphaned_blocks: Vec<BlockHash>,
+
+        /// Block producers, coinbase receivers & command participants of the
+        /// orphaned blocks
+        public_keys: Vec<PublicKey>,
+    },
+}
+
+impl WebhookEvent {
+    pub fn kind(&self) -> WebhookEventKind {
+        match self {
+            Self::NewBlock { .. } => WebhookEventKind::NewBlock,
+            Self::NewBestTip { .. } => WebhookEventKind::NewBestTip,
+            Self::NewCanonicalBlock { .. } => WebhookEventKind::NewCanonicalBlock,
+            Self::NewStakingLedger { .. } => WebhookEventKind::NewStakingLedger,
+            Self::UserCommand { .. } => WebhookEventKind::UserCommand,
+            Self::InternalCommand { .. } => WebhookEventKind::InternalCommand,
+            Self::Reorg { .. } => WebhookEventKind::Reorg,
+        }
+    }
+
+    /// Public keys involved in the event
+    pub fn public_keys(&self) -> Vec<PublicKey> {
+        match self {
+            Self::NewBlock {
+                creator,
+                coinbase_receiver,
+                ..
+            }
+            | Self::NewCanonicalBlock {
+                creator,
+                coinbase_receiver,
+                ..
+            } => creator.iter().chain(coinbase_receiver).cloned().collect(),
+            Self::UserCommand {
+                sender, receiver, ..
+            } => vec![sender.clone(), receiver.clone()],
+            Self::InternalCommand {
+                sender, receiver, ..
+            } => sender.iter().chain([receiver]).cloned().collect(),
+            Self::Reorg { public_keys, .. } => public_keys.clone(),
+            Self::NewBestTip { .. } | Self::NewStakingLedger { .. } => vec![],
+        }
+    }
+
+    /// Amount transferred by a user or internal command
+    pub fn amount(&self) -> Option<u64> {
+        match self {
+            Self::UserCommand { amount, .. } | Self::InternalCommand { amount, .. } => {
+                Some(*amount)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Block whose confirmations gate the delivery of the event's payloads
+pub fn event_block(event: &SeqEvent) -> Option<(&BlockHash, u32)> {
+    match &event.event {
+        IndexerEvent::Db(DbEvent::Block(DbBlockEvent::NewBlock {
+            state_hash,
+            blockchain_length,
+        }))
+        | IndexerEvent::Db(DbEvent::Canonicity(DbCanonicityEvent::NewCanonicalBlock {
+            state_hash,
+            blockchain_length,
+        })) => Some((state_hash, *blockchain_length)),
+        _ => None,
+    }
+}
+
+/// Derive the payloads of `event` matching `filter`
+///
+/// `best_tip` is the best tip prior to the event & is updated by best tip
+/// events
+pub fn webhook_events(
+    store: &IndexerStore,
+    filter: &WebhookFilter,
+    event: &SeqEvent,
+    best_tip: &mut Option<BlockHash>,
+) -> anyhow::Result<Vec<WebhookEvent>> {
+    let mut events = vec![];
+    match &event.event {
+        IndexerEvent::Db(DbEvent::Block(DbBlockEvent::NewBlock {
+            state_hash,
+            blockchain_length,
+        })) => {
+            events.push(WebhookEvent::NewBlock {
+                state_hash: state_hash.clone(),
+                blockchain_length: *blockchain_length,
+                creator: store.get_block_creator(state_hash)?,
+                coinbase_receiver: store.get_coinbase_receiver(state_hash)?,
+            });
+
+            if filter.wants(WebhookEventKind::UserCommand)
+                || filter.wants(WebhookEventKind::InternalCommand)
+            {
+                match store.get_block(state_hash)? {
+                    Some((block, _)) => events.append(&mut command_events(&block)),
+                    None => warn!("Block unavailable, skipping its command payloads {state_hash}"),
+                }
+            }
+        }
+        IndexerEvent::Db(DbEvent::Block(DbBlockEvent::NewBestTip {
+            state_hash,
+            blockchain_length,
+        })) => {
+            let previous = best_tip.replace(state_hash.clone());
+            if let Some(previous) = previous.as_ref() {
+                if filter.wants(WebhookEventKind::Reorg) {
+                    let orphaned_blocks = store.get_reorged_blocks(previous, state_hash)?;
+                    if !orphaned_blocks.is_empty() {
+                        events.push(WebhookEvent::Reorg {
+                            previous_best_tip: previous.clone(),
+                            best_tip: state_hash.clone(),
+                            depth: orphaned_blocks.len() as u32,
+                            public_keys: orphaned_public_keys(store, &orphaned_blocks)?,
+                            orphaned_blocks,
+                        });
+                    }
+                }
+            }
+            events.push(WebhookEvent::NewBestTip {
+                state_hash: state_hash.clone(),
+                blockchain_length: *blockchain_length,
+                previous_state_hash: previous,
+            });
+        }
+        IndexerEvent::Db(DbEvent::Canonicity(DbCanonicityEvent::NewCanonicalBlock {
+            state_hash,
+            blockchain_length,
+        })) => events.push(WebhookEvent::NewCanonicalBlock {
+            state_hash: state_hash.clone(),
+            blockchain_length: *blockchain_length,
+            creator: store.get_block_creator(state_hash)?,
+            coinbase_receiver: store.get_coinbase_receiver(state_hash)?,
+        }),
+        IndexerEvent::Db(DbEvent::StakingLedger(DbStakingLedgerEvent::NewStakingLedger {
+            epoch,
+            ledger_hash,
+            genesis_state_hash,
+        })) => events.push(WebhookEvent::NewStakingLedger {
+            epoch: *epoch,
+            ledger_hash: ledger_hash.clone(),
+            genesis_state_hash: genesis_state_hash.clone(),
+        }),
+        _ => (),
+    }
+
+    events.retain(|event| filter.matches(event));
+    Ok(events)
+}
+
+fn command_events(block: &PrecomputedBlock) -> Vec<WebhookEvent> {
+    let state_hash = block.state_hash();
+    let blockchain_length = block.blockchain_length();
+    let user_commands = SignedCommandWithData::from_precomputed(block)
+        .into_iter()
+        .map(|cmd| WebhookEvent::UserCommand {
+            state_hash: state_hash.clone(),
+            blockchain_length,
+            is_delegation: cmd.command.is_delegation(),
+            sender: cmd.command.source_pk(),
+            receiver: cmd.command.receiver_pk(),
+            amount: cmd.command.amount(),
+            fee: cmd.command.fee(),
+            nonce: cmd.nonce.0,
+            is_applied: cmd.status.is_applied(),
+            tx_hash: cmd.tx_hash,
+        });
+    let internal_commands = InternalCommand::from_precomputed(block)
+        .into_iter()
+        .map(|cmd| {
+            let (internal_command_kind, sender, receiver, amount) = match cmd {
+                InternalCommand::Coinbase { receiver, amount } => {
+                    (InternalCommandKind::Coinbase, None, receiver, amount)
+                }
+                InternalCommand::FeeTransfer {
+                    sender,
+                    receiver,
+                    amount,
+                } => (
+                    InternalCommandKind::FeeTransfer,
+                    Some(sender),
+                    receiver,
+                    amount,
+                ),
+                InternalCommand::FeeTransferViaCoinbase {
+                    sender,
+                    receiver,
+                    amount,
+                } => (
+                    InternalCommandKind::FeeTransferViaCoinbase,
+                    Some(sender),
+                    receiver,
+                    amount,
+                ),
+            };
+            WebhookEvent::InternalCommand {
+                state_hash: state_hash.clone(),
+                blockchain_length,
+                internal_command_kind,
+                sender,
+                receiver,
+                amount,
+            }
+        });
+    user_commands.chain(internal_commands).collect()
+}
+
+fn orphaned_public_keys(
+    store: &IndexerStore,
+    orphaned_blocks: &[BlockHash],
+) -> anyhow::Result<Vec<PublicKey>> {
+    let mut public_keys = vec![];
+    for state_hash in orphaned_blocks {
+        if let Some((block, _)) = store.get_block(state_hash)? {
+            public_keys.push(block.block_creator());
+            public_keys.push(block.coinbase_receiver());
+            for event in command_events(&block) {
+                public_keys.append(&mut event.public_keys());
+            }
+        }
+    }
+    public_keys.sort();
+    public_keys.dedup();
+    Ok(public_keys)
+}
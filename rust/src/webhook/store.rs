@@ -0,0 +1,29 @@
+use super::{Webhook, WebhookCursor, WebhookFilter};
+
+pub trait WebhookStore {
+    /// Register a webhook, delivering events from the next sequence number
+    fn add_webhook(
+        &self,
+        url: &str,
+        secret: &str,
+        filter: WebhookFilter,
+    ) -> anyhow::Result<Webhook>;
+
+    /// Get a registered webhook
+    fn get_webhook(&self, id: u32) -> anyhow::Result<Option<Webhook>>;
+
+    /// Get all registered webhooks, in registration order
+    fn get_webhooks(&self) -> anyhow::Result<Vec<Webhook>>;
+
+    /// Remove a webhook & its cursor
+    ///
+    /// Returns `true` if the webhook was registered
+    fn remove_webhook(&self, id: u32) -> anyhow::Result<bool>;
+
+    /// Persist a webhook's delivery cursor, unless the webhook has been
+    /// removed
+    fn set_webhook_cursor(&self, id: u32, cursor: &WebhookCursor) -> anyhow::Result<()>;
+
+    /// Get a webhook's delivery cursor
+    fn get_webhook_cursor(&self, id: u32) -> anyhow::Result<Option<WebhookCursor>>;
+}
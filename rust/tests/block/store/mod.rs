@@ -2,3 +2,4 @@ mod blocks;
 mod blocks_at_height;
 mod blocks_at_slot;
 mod genesis;
+mod pruned;
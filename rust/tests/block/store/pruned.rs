@@ -0,0 +1,124 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{
+        parser::BlockParser,
+        precomputed::{PcbVersion, PrecomputedBlock},
+        store::BlockStore,
+    },
+    constants::*,
+    store::{column_families::ColumnFamilyHelpers, IndexerStore},
+};
+use speedb::IteratorMode;
+use std::{collections::HashMap, path::PathBuf};
+
+#[tokio::test]
+async fn prune_and_rehydrate() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("block-store-pruned")?;
+    let blocks_dir = &PathBuf::from("./tests/data/sequential_blocks");
+    let db = IndexerStore::new(store_dir.path())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        blocks_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    let mut blocks = HashMap::new();
+    while let Some((block, block_bytes)) = bp.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        db.add_block(&block, block_bytes)?;
+        blocks.insert(block.state_hash(), (block, block_bytes));
+    }
+
+    // the fixtures start at height 105489, all of them are from epochs below
+    // u32::MAX, so the height alone bounds pruning
+    let min_height = blocks
+        .values()
+        .map(|(block, _)| block.blockchain_length())
+        .min()
+        .unwrap();
+    let max_height = blocks
+        .values()
+        .map(|(block, _)| block.blockchain_length())
+        .max()
+        .unwrap();
+    let prune_height = min_height + 5;
+    assert!(min_height > 100_000);
+
+    let num_pruned = db.prune_blocks(u32::MAX, prune_height)?;
+    let num_below = blocks
+        .values()
+        .filter(|(block, _)| block.blockchain_length() < prune_height)
+        .count();
+    assert!(num_below > 0);
+    assert_eq!(num_pruned as usize, num_below);
+    assert_eq!(
+        db.database
+            .iterator_cf(db.blocks_pruned_cf(), IteratorMode::Start)
+            .count(),
+        num_below
+    );
+
+    // without a blocks dir, pruned blocks cannot be re-hydrated
+    for (block, block_bytes) in blocks.values() {
+        let state_hash = block.state_hash();
+        let full_block = db
+            .database
+            .get_pinned_cf(db.blocks_cf(), state_hash.0.as_bytes())?;
+
+        if block.blockchain_length() < prune_height {
+            assert!(db.is_block_pruned(&state_hash)?);
+            assert!(full_block.is_none());
+            assert_eq!(db.get_block(&state_hash)?, None);
+
+            let pruned = db.get_pruned_block(&state_hash)?.unwrap();
+            assert_eq!(pruned.blockchain_length, block.blockchain_length());
+            assert_eq!(pruned.previous_state_hash, block.previous_state_hash());
+            assert_eq!(pruned.num_block_bytes, *block_bytes);
+            assert_eq!(pruned.user_commands.len(), block.commands().len());
+        } else {
+            assert!(!db.is_block_pruned(&state_hash)?);
+            assert!(full_block.is_some());
+            assert_eq!(
+                db.get_block(&state_hash)?,
+                Some((block.clone(), *block_bytes))
+            );
+        }
+    }
+
+    // the full blocks of pruned blocks are unavailable
+    assert!(db.blocks_via_height(IteratorMode::Start).is_err());
+
+    // prune the remaining blocks
+    let num_pruned = db.prune_blocks(u32::MAX, max_height + 1)?;
+    assert_eq!(num_pruned as usize, blocks.len() - num_below);
+    assert_eq!(
+        db.database
+            .iterator_cf(db.blocks_pruned_cf(), IteratorMode::Start)
+            .count(),
+        blocks.len()
+    );
+    for block in blocks.keys() {
+        assert!(db.is_block_pruned(block)?);
+        assert_eq!(db.get_block(block)?, None);
+    }
+
+    // with a blocks dir, pruned blocks are re-hydrated on demand
+    db.set_blocks_dir(blocks_dir)?;
+    for (block, block_bytes) in blocks.values() {
+        let state_hash = block.state_hash();
+        assert_eq!(
+            db.rehydrate_block(&state_hash)?,
+            Some((block.clone(), *block_bytes))
+        );
+        assert_eq!(
+            db.get_block(&state_hash)?,
+            Some((block.clone(), *block_bytes))
+        );
+    }
+
+    // pruning is idempotent
+    assert_eq!(db.prune_blocks(u32::MAX, max_height + 1)?, 0);
+    Ok(())
+}
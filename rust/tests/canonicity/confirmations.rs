@@ -0,0 +1,57 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{parser::BlockParser, store::BlockStore, BlockHash},
+    canonicity::store::CanonicityStore,
+    constants::*,
+    ledger::genesis::{GenesisLedger, GenesisRoot},
+    server::IndexerVersion,
+    state::IndexerState,
+    store::IndexerStore,
+};
+use std::{path::PathBuf, sync::Arc};
+
+#[tokio::test]
+async fn confirmations_and_finality() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("canonicity-confirmations")?;
+    let log_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&log_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let mut state = IndexerState::new(
+        genesis_ledger.into(),
+        IndexerVersion::new_testing(),
+        indexer_store.clone(),
+        MAINNET_CANONICAL_THRESHOLD,
+        MAINNET_TRANSITION_FRONTIER_K,
+    )?;
+
+    state.add_blocks(&mut block_parser).await?;
+
+    let best_block_height = indexer_store.get_best_block_height()?.unwrap();
+    let root_height = indexer_store
+        .get_max_canonical_blockchain_length()?
+        .unwrap();
+    assert_eq!(root_height, state.canonical_root_block().blockchain_length);
+    assert!(root_height < best_block_height);
+
+    for height in 1..=best_block_height {
+        let state_hash = indexer_store.get_canonical_hash_at_height(height)?.unwrap();
+        assert_eq!(
+            indexer_store.get_block_confirmations(&state_hash)?,
+            Some(best_block_height - height)
+        );
+
+        // far fewer than k confirmations, only blocks up to the root are final
+        assert_eq!(
+            indexer_store.is_block_final(&state_hash)?,
+            height <= root_height
+        );
+    }
+
+    // unknown blocks have no confirmations & aren't final
+    let unknown: BlockHash = "3NKZ6DTHiMtuaeP3tJq2xe4uujVRnGT9FX1rBiZY521uNToSppUA".into();
+    assert_eq!(indexer_store.get_block_confirmations(&unknown)?, None);
+    assert!(!indexer_store.is_block_final(&unknown)?);
+    Ok(())
+}
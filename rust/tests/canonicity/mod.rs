@@ -1,3 +1,5 @@
 pub mod blocks;
 pub mod chain_discovery;
+pub mod confirmations;
 pub mod ledgers;
+pub mod reorgs;
@@ -0,0 +1,133 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PrecomputedBlock, store::BlockStore, BlockHash},
+    canonicity::{reorg::ReorgFilter, store::CanonicityStore},
+    store::IndexerStore,
+};
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn record_and_query() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("canonicity-reorgs")?;
+    let blocks_dir = PathBuf::from("./tests/data/sequential_blocks");
+    let mut bp = BlockParser::new_testing(&blocks_dir)?;
+    let db = IndexerStore::new(store_dir.path())?;
+
+    while let Some((block, block_bytes)) = bp.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        db.add_block(&block, block_bytes)?;
+    }
+
+    // 105493 <- 105494 <- 105495
+    let old_best_tip: BlockHash = "3NL4zEKGtSokPMy29pGv7tm8uJt8GitM9JqrRg6Lkf3tRdnwrjpF".into();
+    db.set_best_block(&old_best_tip)?;
+    assert!(db.get_reorgs(&ReorgFilter::default(), 10)?.is_empty());
+
+    // 105493 <- 105494' <- 105495' <- 105496'
+    let new_best_tip: BlockHash = "3NK7yacg7pjHgV52sUmbNv9p7xxrKUV4sevy4Su5j6CrdTjyzaPL".into();
+    db.set_best_block(&new_best_tip)?;
+
+    let reorg = db.get_reorg(0)?.unwrap();
+    assert_eq!(reorg.index, 0);
+    assert_eq!(reorg.old_best_tip, old_best_tip);
+    assert_eq!(reorg.old_best_tip_height, 105495);
+    assert_eq!(reorg.new_best_tip, new_best_tip);
+    assert_eq!(reorg.new_best_tip_height, 105496);
+    assert_eq!(
+        reorg.common_ancestor.0,
+        "3NKakum3B2Tigw9TSsxwvXvV3x8L2LvrJ3yXFLEAJDMZu2vkn7db"
+    );
+    assert_eq!(reorg.common_ancestor_height, 105493);
+    assert_eq!(reorg.depth, 2);
+    assert_eq!(
+        reorg
+            .uncanonicalized
+            .iter()
+            .map(|block| (block.state_hash.0.as_str(), block.blockchain_length))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                "3NL4zEKGtSokPMy29pGv7tm8uJt8GitM9JqrRg6Lkf3tRdnwrjpF",
+                105495
+            ),
+            (
+                "3NKXsaznJ6WdyA4PHfXxn25RzVanzQsNMZrxjidbhoBug8R4LZDy",
+                105494
+            ),
+        ]
+    );
+    assert_eq!(
+        reorg
+            .recanonicalized
+            .iter()
+            .map(|block| (block.state_hash.0.as_str(), block.blockchain_length))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                "3NK7yacg7pjHgV52sUmbNv9p7xxrKUV4sevy4Su5j6CrdTjyzaPL",
+                105496
+            ),
+            (
+                "3NKmDYoFs5MRNE4PoGMkMT5udM4JrnB5NJYFLJcDUUob363aj5e9",
+                105495
+            ),
+            (
+                "3NKqd3XGqkLmZVmPC3iG6AnrwQoZdBKdmYTzEJT3vwwnn2H1Z4ww",
+                105494
+            ),
+        ]
+    );
+
+    // uncanonicalized user commands are recorded
+    for block in reorg.uncanonicalized.iter() {
+        let (pcb, _) = db.get_block(&block.state_hash)?.unwrap();
+        assert_eq!(block.user_commands.len(), pcb.commands().len());
+    }
+
+    // sibling of the best tip's parent, depth 1 reorg
+    let sibling_child: BlockHash = "3NKjngJTXJzRUXF3uH2nK19iYUVtYBFjLhezSrMMFVQyEGwqEi3c".into();
+    db.set_best_block(&sibling_child)?;
+
+    // extensions aren't reorgs
+    db.set_best_block(&"3NKbLiBHzQrAimK7AkP8qAfQpHnezkdsSm8mkt2TzsbjsLN8Axmt".into())?;
+
+    let reorgs = db.get_reorgs(&ReorgFilter::default(), 10)?;
+    assert_eq!(
+        reorgs.iter().map(|reorg| reorg.index).collect::<Vec<_>>(),
+        vec![1, 0]
+    );
+    assert_eq!(reorgs[0].depth, 1);
+    assert_eq!(reorgs[0].uncanonicalized[0].state_hash, new_best_tip);
+
+    // filters
+    let deep = ReorgFilter {
+        min_depth: Some(2),
+        ..Default::default()
+    };
+    assert_eq!(db.get_reorgs(&deep, 10)?, vec![reorg.clone()]);
+
+    let involving = ReorgFilter {
+        state_hash: Some(new_best_tip.clone()),
+        ..Default::default()
+    };
+    assert_eq!(db.get_reorgs(&involving, 10)?.len(), 2);
+    assert_eq!(db.get_reorgs(&involving, 1)?.len(), 1);
+
+    if let Some(cmd) = reorg.uncanonicalized[0].user_commands.first() {
+        let reversed = ReorgFilter {
+            txn_hash: Some(cmd.txn_hash.clone()),
+            ..Default::default()
+        };
+        assert_eq!(db.get_reorgs(&reversed, 10)?[0].index, 0);
+    }
+
+    // stats
+    let stats = db.get_reorg_stats()?;
+    assert_eq!(stats.num_reorgs, 2);
+    assert_eq!(stats.max_depth, 2);
+    assert_eq!(stats.num_uncanonicalized_blocks, 3);
+    assert_eq!(stats.epochs.len(), 1);
+    assert_eq!(stats.epochs[0].epoch, reorg.epoch);
+    assert_eq!(stats.epochs[0].num_reorgs, 2);
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PrecomputedBlock},
+    constants::*,
+    event::{
+        broadcast::{broadcast_events_since, event_channel},
+        store::EventStore,
+        EventKind,
+    },
+    ledger::genesis::{GenesisLedger, GenesisRoot},
+    server::IndexerVersion,
+    state::{IndexerState, IndexerStateConfig},
+    store::IndexerStore,
+};
+use std::{path::PathBuf, sync::Arc};
+
+#[tokio::test]
+async fn broadcast() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("event-broadcast")?;
+    let log_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&log_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let mut state = IndexerState::new_from_config(IndexerStateConfig::new(
+        genesis_ledger.into(),
+        IndexerVersion::new_testing(),
+        indexer_store.clone(),
+        MAINNET_CANONICAL_THRESHOLD,
+        10,
+    ))?;
+
+    // nothing is sent without receivers
+    let sender = event_channel();
+    assert_eq!(broadcast_events_since(&indexer_store, &sender, 0)?, 0);
+
+    let mut receiver = sender.subscribe();
+    let (block, block_bytes) = block_parser.next_block().await?.unwrap();
+    let block: PrecomputedBlock = block.into();
+
+    // only the events recorded while processing the block are broadcast
+    let seq_num = indexer_store.get_next_seq_num()?;
+    state.block_pipeline(&block, block_bytes)?;
+
+    let num_events = broadcast_events_since(&indexer_store, &sender, seq_num)?;
+    assert!(num_events > 0);
+
+    let mut events = vec![];
+    while let Ok(event) = receiver.try_recv() {
+        events.push(event);
+    }
+    assert_eq!(events.len(), num_events);
+    assert!(events.iter().all(|event| event.seq_num >= seq_num));
+    assert!(events
+        .iter()
+        .any(|event| event.kind == EventKind::NewBlock && event.event.is_new_block_event()));
+    Ok(())
+}
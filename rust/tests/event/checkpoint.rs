@@ -0,0 +1,76 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PrecomputedBlock, BlockWithoutHeight},
+    constants::*,
+    event::store::EventStore,
+    ledger::genesis::{GenesisLedger, GenesisRoot},
+    server::IndexerVersion,
+    state::{store::WitnessTreeStore, IndexerState, IndexerStateConfig},
+    store::IndexerStore,
+};
+use std::{path::PathBuf, sync::Arc};
+
+#[tokio::test]
+async fn restore() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("event-checkpoint")?;
+    let log_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&log_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let config = || {
+        IndexerStateConfig::new(
+            genesis_ledger.clone().into(),
+            IndexerVersion::new_testing(),
+            indexer_store.clone(),
+            MAINNET_CANONICAL_THRESHOLD,
+            10,
+        )
+    };
+    let mut state = IndexerState::new_from_config(config())?;
+
+    // add all blocks to the state, checkpointing the witness tree midway
+    let mut checkpoint_seq_num = None;
+    while let Some((block, block_bytes)) = block_parser.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        state.block_pipeline(&block, block_bytes)?;
+
+        if block.blockchain_length() == 15 {
+            let checkpoint = state.witness_tree_checkpoint()?;
+            indexer_store.set_witness_tree_checkpoint(&checkpoint)?;
+            assert_eq!(
+                indexer_store.get_witness_tree_checkpoint()?,
+                Some(checkpoint.clone())
+            );
+            checkpoint_seq_num = Some(checkpoint.seq_num);
+        }
+    }
+
+    // the remaining blocks are restored from the event log tail
+    assert!(checkpoint_seq_num.unwrap() < indexer_store.get_next_seq_num()?);
+
+    let mut state_restored = IndexerState::new_without_genesis_events(config())?;
+    assert_eq!(state_restored.restore_witness_tree()?, checkpoint_seq_num);
+
+    let mut state_sync = IndexerState::new_without_genesis_events(config())?;
+    state_sync.sync_from_db()?;
+
+    let mut state_replay = IndexerState::new_without_genesis_events(config())?;
+    state_replay.replay_events(&IndexerState::new_without_genesis_events(config())?)?;
+
+    // witness trees are equal
+    for restored in [state_sync, state_replay] {
+        let best_tip: BlockWithoutHeight = state.best_tip_block().clone().into();
+        let canonical_root: BlockWithoutHeight = state.canonical_root_block().clone().into();
+        let restored_best_tip: BlockWithoutHeight = restored.best_tip_block().clone().into();
+        let restored_canonical_root: BlockWithoutHeight =
+            restored.canonical_root_block().clone().into();
+
+        assert_eq!(best_tip, restored_best_tip);
+        assert_eq!(canonical_root, restored_canonical_root);
+        assert_eq!(state.root_branch.blocks(), restored.root_branch.blocks());
+        assert_eq!(state.diffs_map, restored.diffs_map);
+        assert_eq!(state.ledger, restored.ledger);
+    }
+    Ok(())
+}
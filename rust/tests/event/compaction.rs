@@ -0,0 +1,133 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PrecomputedBlock, BlockWithoutHeight},
+    constants::*,
+    event::{store::EventStore, EventKind, SeqEvent},
+    ledger::genesis::{GenesisLedger, GenesisRoot},
+    server::IndexerVersion,
+    state::{store::WitnessTreeStore, IndexerState, IndexerStateConfig},
+    store::IndexerStore,
+    webhook::{store::WebhookStore, WebhookCursor, WebhookFilter},
+};
+use std::{path::PathBuf, sync::Arc};
+
+#[tokio::test]
+async fn compact() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("event-compaction")?;
+    let log_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&log_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let config = || {
+        IndexerStateConfig::new(
+            genesis_ledger.clone().into(),
+            IndexerVersion::new_testing(),
+            indexer_store.clone(),
+            MAINNET_CANONICAL_THRESHOLD,
+            10,
+        )
+    };
+    let mut state = IndexerState::new_from_config(config())?;
+
+    // nothing to compact without a checkpoint
+    assert!(indexer_store.compact_event_log(None).is_err());
+
+    // add all blocks to the state, checkpointing midway
+    let mut num_blocks = 1; // genesis block
+    while let Some((block, block_bytes)) = block_parser.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        state.block_pipeline(&block, block_bytes)?;
+        num_blocks += 1;
+
+        if block.blockchain_length() == 15 {
+            state.checkpoint_witness_tree()?;
+        }
+    }
+
+    // query events via kind & sequence number range
+    let next_seq_num = indexer_store.get_next_seq_num()?;
+    let new_blocks = indexer_store.get_events(0, None, Some(EventKind::NewBlock), 1000)?;
+    assert_eq!(new_blocks.len(), num_blocks);
+    assert!(new_blocks.iter().all(|e| e.event.is_new_block_event()));
+
+    let seq_nums: Vec<u32> = indexer_store
+        .get_events(2, Some(5), None, 100)?
+        .iter()
+        .map(|e| e.seq_num)
+        .collect();
+    assert_eq!(seq_nums, vec![2, 3, 4]);
+    assert_eq!(indexer_store.get_events(0, None, None, 3)?.len(), 3);
+
+    // checkpoint corresponds to the witness tree checkpoint
+    let checkpoints = indexer_store.get_event_checkpoints()?;
+    assert_eq!(checkpoints.len(), 1);
+
+    let checkpoint = indexer_store.get_latest_event_checkpoint()?.unwrap();
+    assert_eq!(checkpoint, checkpoints[0]);
+    assert_eq!(checkpoint.best_tip_height, 15);
+    assert_eq!(
+        Some(checkpoint.seq_num),
+        indexer_store
+            .get_witness_tree_checkpoint()?
+            .map(|checkpoint| checkpoint.seq_num)
+    );
+
+    // events a webhook has yet to deliver are kept
+    let webhook = indexer_store.add_webhook(
+        "http://localhost:8080/hook",
+        "secret",
+        WebhookFilter::default(),
+    )?;
+    indexer_store.set_webhook_cursor(
+        webhook.id,
+        &WebhookCursor {
+            seq_num: 5,
+            unconfirmed: vec![4, 3],
+            ..Default::default()
+        },
+    )?;
+
+    let compaction = indexer_store.compact_event_log(None)?;
+    assert_eq!(compaction.seq_num, 3);
+    assert_eq!(compaction.num_events, 3);
+    assert_eq!(indexer_store.get_first_seq_num()?, Some(3));
+    assert!(indexer_store.get_event(3)?.is_some());
+
+    // compact & archive the remaining events below the checkpoint
+    indexer_store.remove_webhook(webhook.id)?;
+
+    let mut archive = vec![];
+    let compaction = indexer_store.compact_event_log(Some(&mut archive))?;
+    assert_eq!(compaction.seq_num, checkpoint.seq_num);
+    assert_eq!(compaction.num_events, checkpoint.seq_num - 3);
+    assert_eq!(compaction.num_archived, compaction.num_events);
+
+    let archived: Vec<SeqEvent> = String::from_utf8(archive)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(archived.len() as u32, compaction.num_archived);
+    assert_eq!(archived[0].seq_num, 3);
+
+    assert_eq!(indexer_store.get_first_seq_num()?, Some(checkpoint.seq_num));
+    assert_eq!(indexer_store.get_next_seq_num()?, next_seq_num);
+    assert!(indexer_store.get_event(0)?.is_none());
+    assert_eq!(indexer_store.compact_event_log(None)?.num_events, 0);
+
+    // the state is still restored from the compacted event log
+    let mut state_sync = IndexerState::new_without_genesis_events(config())?;
+    state_sync.sync_from_db()?;
+
+    let mut state_replay = IndexerState::new_without_genesis_events(config())?;
+    state_replay.replay_events(&IndexerState::new_without_genesis_events(config())?)?;
+
+    for restored in [state_sync, state_replay] {
+        let best_tip: BlockWithoutHeight = state.best_tip_block().clone().into();
+        let restored_best_tip: BlockWithoutHeight = restored.best_tip_block().clone().into();
+
+        assert_eq!(best_tip, restored_best_tip);
+        assert_eq!(state.diffs_map, restored.diffs_map);
+    }
+    Ok(())
+}
@@ -1,3 +1,6 @@
+mod broadcast;
+mod checkpoint;
+mod compaction;
 mod log;
 mod memoize_ledger;
 mod replay;
@@ -5,9 +5,13 @@ mod event;
 mod ledger;
 #[cfg(all(test, feature = "mina_rs"))]
 mod protocol;
+mod rpc;
 mod snark_work;
 mod state;
+mod store;
 mod usernames;
+mod web;
+mod webhook;
 
 pub mod helpers {
     /// Sets up a new temp dir, deleted when it goes out of scope
@@ -0,0 +1,173 @@
+use crate::helpers::setup_new_db_dir;
+use actix_web::{guard, web, App, HttpServer};
+use async_graphql_actix_web::GraphQL;
+use mina_indexer::{
+    block::{parser::BlockParser, store::BlockStore},
+    constants::*,
+    event::broadcast::event_channel,
+    health::{IndexerHealth, DEFAULT_MAX_SLOT_LAG},
+    ledger::genesis::{GenesisConstants, GenesisLedger, GenesisRoot},
+    server::IndexerVersion,
+    state::{IndexerPhase, IndexerState},
+    store::IndexerStore,
+    unix_socket_server::{create_socket_listener, handle_connection},
+    web::{
+        graphql::{build_schema, limits::GraphQLLimits, IndexerSchema},
+        ENDPOINT_GRAPHQL,
+    },
+};
+use mina_indexer_client::{types::HeightBounds, IndexerClient};
+use serde_json::{json, Value};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::RwLock;
+use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle, Toplevel};
+
+#[tokio::test]
+async fn typed_queries() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("rpc-client")?;
+    let block_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&block_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let mut state = IndexerState::new(
+        genesis_ledger.into(),
+        IndexerVersion::new_testing(),
+        indexer_store.clone(),
+        MAINNET_CANONICAL_THRESHOLD,
+        10,
+    )?;
+    state.add_blocks(&mut block_parser).await?;
+
+    let state = Arc::new(RwLock::new(state));
+    let health = Arc::new(IndexerHealth::new(
+        IndexerPhase::Watching,
+        &GenesisConstants::default(),
+        DEFAULT_MAX_SLOT_LAG,
+    ));
+    let schema = build_schema(
+        indexer_store.clone(),
+        event_channel(),
+        GraphQLLimits::default(),
+    );
+    let base_url = start_graphql_server(schema.clone())?;
+    let socket_path = store_dir.path().join("mina-indexer.sock");
+    let listener = create_socket_listener(&socket_path);
+
+    Toplevel::new(|s| async move {
+        s.start(SubsystemBuilder::new("Socket Listener", move |s| {
+            handle_connection(listener, state, health, schema, event_channel(), s)
+        }));
+        s.start(SubsystemBuilder::new("Client", move |s| {
+            client(s, socket_path, base_url, indexer_store)
+        }));
+    })
+    .handle_shutdown_requests(Duration::from_secs(10))
+    .await
+    .map_err(anyhow::Error::from)
+}
+
+/// Starts a local GraphQL server, returning its base url
+fn start_graphql_server(schema: IndexerSchema) -> anyhow::Result<String> {
+    let server = HttpServer::new(move || {
+        App::new().service(
+            web::resource(ENDPOINT_GRAPHQL)
+                .guard(guard::Post())
+                .to(GraphQL::new(schema.clone())),
+        )
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))?;
+    let addr = server.addrs()[0];
+    tokio::spawn(server.run());
+    Ok(format!("http://{addr}"))
+}
+
+async fn client(
+    _subsys: SubsystemHandle,
+    socket_path: PathBuf,
+    base_url: String,
+    store: Arc<IndexerStore>,
+) -> anyhow::Result<()> {
+    let uds = IndexerClient::connect_uds(&socket_path).await?;
+    let http = IndexerClient::http(&base_url);
+
+    // raw calls are only served over the UDS
+    assert!(uds.call("db-version", None).await.is_ok());
+    assert!(http.call("db-version", None).await.is_err());
+
+    for client in [&uds, &http] {
+        let best_tip = client.best_tip().await?.unwrap();
+        assert_eq!(best_tip.state_hash, store.get_best_block_hash()?.unwrap().0);
+        assert_eq!(best_tip.block_height, 21);
+        assert_eq!(best_tip.confirmations, Some(0));
+        assert_eq!(
+            client.block(&best_tip.state_hash).await?,
+            Some(best_tip.clone())
+        );
+        assert!(client
+            .blocks_at_height(21)
+            .await?
+            .iter()
+            .any(|block| block.state_hash == best_tip.state_hash));
+
+        let account = client.account(&best_tip.creator).await?.unwrap();
+        assert_eq!(account.public_key, best_tip.creator);
+
+        // coinbases of the best tip's creator
+        let internal_commands = client
+            .internal_commands_for(&best_tip.creator, HeightBounds::default(), None)
+            .await?;
+        assert!(!internal_commands.is_empty());
+        assert!(internal_commands
+            .iter()
+            .all(|cmd| cmd.recipient == best_tip.creator));
+        assert!(internal_commands
+            .windows(2)
+            .all(|w| w[0].block_height >= w[1].block_height));
+
+        // sent & received transactions within the bounds
+        let data: Value = client
+            .graphql("{ transactions(limit: 1) { hash from } }", json!({}))
+            .await?;
+        let hash = data["transactions"][0]["hash"].as_str().unwrap();
+        let sender = data["transactions"][0]["from"].as_str().unwrap();
+
+        let transactions = client.transaction(hash).await?;
+        assert!(!transactions.is_empty());
+        assert!(transactions.iter().all(|txn| txn.hash == hash));
+
+        let bounds = HeightBounds {
+            min: Some(2),
+            max: Some(20),
+        };
+        let transactions = client.transactions_for(sender, bounds, None).await?;
+        assert!(!transactions.is_empty());
+        assert!(transactions.iter().all(|txn| {
+            (txn.from == sender || txn.to == sender) && (2..=20).contains(&txn.block_height)
+        }));
+        assert!(transactions
+            .windows(2)
+            .all(|w| w[0].block_height >= w[1].block_height));
+        assert_eq!(
+            client
+                .transactions_for(sender, bounds, Some(1))
+                .await?
+                .len(),
+            1
+        );
+
+        // no staking ledgers were ingested
+        assert!(client.staking_ledger(0, None).await?.is_empty());
+        assert!(client.staking_account(0, sender).await?.is_none());
+
+        // GraphQL errors are returned as such
+        assert!(client
+            .graphql::<Value>("{ nope }", json!({}))
+            .await
+            .is_err());
+    }
+
+    uds.call("shutdown", None).await?;
+    Ok(())
+}
@@ -0,0 +1,217 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PrecomputedBlock},
+    command::signed::SignedCommandWithData,
+    constants::*,
+    event::{
+        broadcast::{broadcast_events_since, event_channel},
+        store::EventStore,
+    },
+    health::{IndexerHealth, DEFAULT_MAX_SLOT_LAG},
+    ledger::genesis::{GenesisConstants, GenesisLedger, GenesisRoot},
+    server::IndexerVersion,
+    state::{IndexerPhase, IndexerState},
+    store::IndexerStore,
+    unix_socket_server::{create_socket_listener, handle_connection},
+    web::graphql::{build_schema, limits::GraphQLLimits},
+};
+use mina_indexer_client::{
+    format::{FollowRenderer, OutputFormat},
+    rpc::{codes, FollowItem, FollowStatus, RpcClient, RpcError},
+};
+use serde_json::{json, Value};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::{Barrier, RwLock};
+use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle, Toplevel};
+
+const FOLLOW_FROM_HEIGHT: u32 = 19;
+
+#[tokio::test]
+async fn follow() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("rpc-follow")?;
+    let block_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&block_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let mut state = IndexerState::new(
+        genesis_ledger.into(),
+        IndexerVersion::new_testing(),
+        indexer_store.clone(),
+        MAINNET_CANONICAL_THRESHOLD,
+        10,
+    )?;
+
+    // blocks from FOLLOW_FROM_HEIGHT are added while following
+    let mut new_blocks = vec![];
+    while let Some((block, block_bytes)) = block_parser.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        if block.blockchain_length() < FOLLOW_FROM_HEIGHT {
+            state.block_pipeline(&block, block_bytes)?;
+        } else {
+            new_blocks.push((block, block_bytes));
+        }
+    }
+    new_blocks.sort_by_key(|(block, _)| block.blockchain_length());
+    let public_key = new_blocks
+        .iter()
+        .flat_map(|(block, _)| SignedCommandWithData::from_precomputed(block))
+        .map(|cmd| cmd.command.receiver_pk().0)
+        .next()
+        .expect("transaction in followed blocks");
+
+    let state = Arc::new(RwLock::new(state));
+    let health = Arc::new(IndexerHealth::new(
+        IndexerPhase::Watching,
+        &GenesisConstants::default(),
+        DEFAULT_MAX_SLOT_LAG,
+    ));
+    let events = event_channel();
+    let schema = build_schema(
+        indexer_store.clone(),
+        events.clone(),
+        GraphQLLimits::default(),
+    );
+    let socket_path = store_dir.path().join("mina-indexer.sock");
+    let listener = create_socket_listener(&socket_path);
+
+    // blocks are added once all clients follow
+    let following = Arc::new(Barrier::new(2));
+    Toplevel::new(|s| async move {
+        s.start(SubsystemBuilder::new("Socket Listener", {
+            let state = state.clone();
+            let events = events.clone();
+            move |s| handle_connection(listener, state, health, schema, events, s)
+        }));
+        s.start(SubsystemBuilder::new("Indexer", {
+            let following = following.clone();
+            move |_| async move {
+                following.wait().await;
+                for (block, block_bytes) in new_blocks {
+                    let seq_num = indexer_store.get_next_seq_num()?;
+                    state.write().await.block_pipeline(&block, block_bytes)?;
+                    broadcast_events_since(&indexer_store, &events, seq_num)?;
+                }
+                anyhow::Ok(())
+            }
+        }));
+        s.start(SubsystemBuilder::new("Client", move |s| {
+            client(s, socket_path, public_key, following)
+        }));
+    })
+    .handle_shutdown_requests(Duration::from_secs(10))
+    .await
+    .map_err(anyhow::Error::from)
+}
+
+/// Items pushed to the follower until `done`
+async fn items_until<F>(client: &mut RpcClient, done: F) -> anyhow::Result<Vec<FollowItem>>
+where
+    F: Fn(&FollowItem) -> bool,
+{
+    let mut items = vec![];
+    while let Some(item) =
+        tokio::time::timeout(Duration::from_secs(30), client.next_item()).await??
+    {
+        items.push(item);
+        if done(items.last().unwrap()) {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+async fn client(
+    _subsys: SubsystemHandle,
+    socket_path: PathBuf,
+    public_key: String,
+    following: Arc<Barrier>,
+) -> anyhow::Result<()> {
+    let mut best_tip = RpcClient::connect(&socket_path).await?;
+    let result = best_tip
+        .call("blocks.best-tip", Some(json!({ "follow": true })))
+        .await?;
+    let result: Value = serde_json::from_str(result.get())?;
+    assert_eq!(result["blockchain_length"], FOLLOW_FROM_HEIGHT - 1);
+
+    let mut transactions = RpcClient::connect(&socket_path).await?;
+    transactions
+        .call(
+            "transactions.public-key",
+            Some(json!({ "public_key": public_key, "follow": true })),
+        )
+        .await?;
+
+    // invalid kinds are rejected instead of following all kinds
+    let error = RpcClient::connect(&socket_path)
+        .await?
+        .call(
+            "events.query",
+            Some(json!({ "kind": "nope", "limit": 0, "follow": true })),
+        )
+        .await
+        .unwrap_err()
+        .downcast::<RpcError>()?;
+    assert_eq!(error.code, codes::INVALID_PARAMS);
+
+    let mut events = RpcClient::connect(&socket_path).await?;
+    events
+        .call(
+            "events.query",
+            Some(json!({ "kind": "new_best_tip", "limit": 0, "follow": true })),
+        )
+        .await?;
+    following.wait().await;
+
+    // each new best tip is pushed
+    let best_tips = items_until(&mut best_tip, |item| item.data["blockchain_length"] == 21).await?;
+    let heights: Vec<&Value> = best_tips
+        .iter()
+        .map(|item| &item.data["blockchain_length"])
+        .collect();
+    assert_eq!(heights, [19, 20, 21]);
+    assert!(best_tips
+        .iter()
+        .all(|item| item.status == FollowStatus::New && item.id == 0));
+
+    let seq_nums: Vec<u32> = best_tips.iter().map(|item| item.seq_num).collect();
+    let new_best_tips = items_until(&mut events, |item| item.seq_num == seq_nums[2]).await?;
+    assert_eq!(
+        new_best_tips
+            .iter()
+            .map(|item| item.seq_num)
+            .collect::<Vec<_>>(),
+        seq_nums
+    );
+    assert!(new_best_tips
+        .iter()
+        .all(|item| item.data["kind"] == "new_best_tip"));
+
+    // transactions of the new best chain blocks involving the public key
+    let item = items_until(&mut transactions, |_| true).await?.remove(0);
+    assert_eq!(item.status, FollowStatus::New);
+    assert_eq!(item.data["Payment"]["receiver"], public_key.as_str());
+
+    // followers render the result & items as rows with their status
+    let mut table = vec![];
+    let mut renderer = FollowRenderer::new(OutputFormat::Table, "blocks.best-tip");
+    renderer.render_result(&result.to_string(), &mut table)?;
+    for item in &best_tips {
+        renderer.render_item(item, &mut table)?;
+    }
+    let table = String::from_utf8(table)?;
+    let rows: Vec<Vec<&str>> = table
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    assert_eq!(rows.len(), 5);
+    assert_eq!(rows[0][..3], ["status", "seq_num", "state_hash"]);
+    assert_eq!(rows[1][0], "current");
+    assert_eq!(rows[4][..2], ["new", seq_nums[2].to_string().as_str()]);
+
+    RpcClient::connect(&socket_path)
+        .await?
+        .call("shutdown", None)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,232 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::parser::BlockParser,
+    client::{ClientCli, Ledgers},
+    constants::*,
+    event::broadcast::event_channel,
+    health::{IndexerHealth, DEFAULT_MAX_SLOT_LAG},
+    ledger::genesis::{GenesisConstants, GenesisLedger, GenesisRoot},
+    server::IndexerVersion,
+    state::{IndexerPhase, IndexerState},
+    store::IndexerStore,
+    unix_socket_server::{create_socket_listener, handle_connection},
+    web::graphql::{build_schema, limits::GraphQLLimits},
+};
+use mina_indexer_client::{
+    format::{OutputFormat, StreamRenderer},
+    IndexerClient,
+};
+use serde_json::{json, Map, Value};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::RwLock;
+use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle, Toplevel};
+
+#[tokio::test]
+async fn output_formats() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("rpc-format")?;
+    let block_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&block_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let mut state = IndexerState::new(
+        genesis_ledger.into(),
+        IndexerVersion::new_testing(),
+        indexer_store.clone(),
+        MAINNET_CANONICAL_THRESHOLD,
+        10,
+    )?;
+    state.add_blocks(&mut block_parser).await?;
+
+    let state = Arc::new(RwLock::new(state));
+    let health = Arc::new(IndexerHealth::new(
+        IndexerPhase::Watching,
+        &GenesisConstants::default(),
+        DEFAULT_MAX_SLOT_LAG,
+    ));
+    let schema = build_schema(
+        indexer_store.clone(),
+        event_channel(),
+        GraphQLLimits::default(),
+    );
+    let socket_path = store_dir.path().join("mina-indexer.sock");
+    let output_dir = store_dir.path().to_path_buf();
+    let listener = create_socket_listener(&socket_path);
+
+    Toplevel::new(|s| async move {
+        s.start(SubsystemBuilder::new("Socket Listener", move |s| {
+            handle_connection(listener, state, health, schema, event_channel(), s)
+        }));
+        s.start(SubsystemBuilder::new("Client", move |s| {
+            client(s, socket_path, output_dir)
+        }));
+    })
+    .handle_shutdown_requests(Duration::from_secs(10))
+    .await
+    .map_err(anyhow::Error::from)
+}
+
+async fn render(
+    client: &IndexerClient,
+    method: &str,
+    params: Value,
+    format: OutputFormat,
+) -> anyhow::Result<String> {
+    let mut output = vec![];
+    client
+        .call_to_writer(method, Some(params), format, &mut output)
+        .await?;
+    Ok(String::from_utf8(output)?)
+}
+
+async fn client(
+    _subsys: SubsystemHandle,
+    socket_path: PathBuf,
+    output_dir: PathBuf,
+) -> anyhow::Result<()> {
+    let client = IndexerClient::connect_uds(&socket_path).await?;
+
+    // JSON is written as sent, streamed or not
+    let best_tip = client.call("blocks.best-tip", None).await?;
+    let json = render(&client, "blocks.best-tip", json!({}), OutputFormat::Json).await?;
+    assert_eq!(json, format!("{}\n", best_tip.get()));
+
+    let ledger: Map<String, Value> =
+        serde_json::from_str(client.call("ledgers.best", None).await?.get())?;
+    let json = render(&client, "ledgers.best", json!({}), OutputFormat::Json).await?;
+    assert_eq!(serde_json::from_str::<Map<String, Value>>(&json)?, ledger);
+
+    // streamed records are written as their chunks arrive
+    let ledger_json = client.call("ledgers.best", None).await?.get().to_string();
+    let mut renderer = StreamRenderer::new(OutputFormat::Ndjson, "ledgers.best");
+    let mut ndjson = vec![];
+    let mut rest = ledger_json.as_str();
+    while !rest.is_empty() {
+        let mut end = rest.len().min(1000);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        renderer.render_chunk(chunk, &mut ndjson)?;
+        if tail.len() < ledger_json.len() - 2000 {
+            assert!(!ndjson.is_empty());
+        }
+        rest = tail;
+    }
+    renderer.finish(&mut ndjson)?;
+    let accounts: Vec<Value> = String::from_utf8(ndjson)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(accounts.len(), ledger.len());
+    for account in accounts {
+        assert_eq!(ledger[account["public_key"].as_str().unwrap()], account);
+    }
+
+    // one record per line
+    let ndjson = render(
+        &client,
+        "chain.best",
+        json!({ "num": 3 }),
+        OutputFormat::Ndjson,
+    )
+    .await?;
+    let blocks: Vec<Value> = ndjson
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[0]["blockchain_length"], 21);
+
+    // block columns lead
+    let table = render(
+        &client,
+        "blocks.height",
+        json!({ "height": 21 }),
+        OutputFormat::Table,
+    )
+    .await?;
+    let mut lines = table.lines();
+    let header: Vec<&str> = lines.next().unwrap().split_whitespace().collect();
+    assert_eq!(
+        header,
+        [
+            "state_hash",
+            "blockchain_length",
+            "global_slot_since_genesis",
+            "parent_hash",
+            "canonicity"
+        ]
+    );
+    let row: Vec<&str> = lines.next().unwrap().split_whitespace().collect();
+    assert_eq!(row[1], "21");
+    assert_eq!(row[4], "Canonical");
+
+    // tagged transactions are untagged into a kind column
+    let state_hash = serde_json::from_str::<Value>(best_tip.get())?["state_hash"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let csv = render(
+        &client,
+        "transactions.state-hash",
+        json!({ "state_hash": state_hash }),
+        OutputFormat::Csv,
+    )
+    .await?;
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    assert_eq!(
+        reader.headers()?.iter().take(4).collect::<Vec<_>>(),
+        ["kind", "source", "receiver", "amount"]
+    );
+    let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+    assert!(!records.is_empty());
+    assert!(records.iter().all(|record| &record[0] == "Payment"));
+
+    let csv = render(
+        &client,
+        "internal-commands.state-hash",
+        json!({ "state_hash": state_hash }),
+        OutputFormat::Csv,
+    )
+    .await?;
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    assert_eq!(&reader.headers()?[0], "kind");
+    assert!(reader
+        .records()
+        .any(|record| &record.unwrap()[0] == "Coinbase"));
+
+    // text is written as is
+    let text = render(&client, "summary", json!({}), OutputFormat::Csv).await?;
+    assert!(text.starts_with("===== Mina-indexer summary ====="));
+
+    // --path output is written by the client, one row per ledger account
+    let path = output_dir.join("ledger.csv");
+    ClientCli::Ledgers(Ledgers::Best {
+        path: Some(path.clone()),
+    })
+    .run_with_format(socket_path.clone(), OutputFormat::Csv, None)
+    .await?;
+    let mut reader = csv::Reader::from_path(&path)?;
+    assert_eq!(
+        reader.headers()?.iter().take(3).collect::<Vec<_>>(),
+        ["public_key", "balance", "nonce"]
+    );
+    let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(records.len(), ledger.len());
+    for record in records {
+        assert_eq!(ledger[&record[0]]["balance"].to_string(), &record[1]);
+    }
+
+    let path = output_dir.join("ledger.json");
+    ClientCli::Ledgers(Ledgers::Best {
+        path: Some(path.clone()),
+    })
+    .run_with_format(socket_path.clone(), OutputFormat::Json, None)
+    .await?;
+    let written: Map<String, Value> = serde_json::from_slice(&std::fs::read(&path)?)?;
+    assert_eq!(written, ledger);
+
+    client.call("shutdown", None).await?;
+    Ok(())
+}
@@ -0,0 +1,5 @@
+mod client;
+mod follow;
+mod format;
+mod networks;
+mod socket;
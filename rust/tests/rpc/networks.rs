@@ -0,0 +1,188 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PrecomputedBlock, store::BlockStore, BlockHash},
+    constants::*,
+    event::broadcast::event_channel,
+    health::{IndexerHealth, DEFAULT_MAX_SLOT_LAG},
+    ledger::genesis::{GenesisConstants, GenesisLedger, GenesisRoot},
+    networks::{IndexedNetwork, IndexedNetworks},
+    rpc::{codes, RpcClient, RpcError},
+    server::IndexerVersion,
+    state::{IndexerPhase, IndexerState},
+    store::IndexerStore,
+    unix_socket_server::{create_socket_listener, handle_network_connections},
+    web::graphql::{build_schema, limits::GraphQLLimits},
+};
+use mina_indexer_client::IndexerClient;
+use serde_json::json;
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::RwLock;
+use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle, Toplevel};
+
+const TESTNET_GENESIS_HASH: &str = "3NKGgTk7en3347KH81yDra876GPAUSoSePrfVKPmwR1KHfMpvJC5";
+const DEVNET_GENESIS_HASH: &str = "3NL93SipJfAMNDBRfQ8Uo8LPovC74mnJZfZYB5SK7mTtkL72dsPx";
+
+/// Only blocks below this height are added to the testnet
+const TESTNET_MAX_HEIGHT: u32 = 10;
+
+fn network(name: &str, store: Arc<IndexerStore>, state: Option<IndexerState>) -> IndexedNetwork {
+    let events = event_channel();
+    IndexedNetwork {
+        name: name.to_string(),
+        genesis_state_hash: store
+            .namespace
+            .clone()
+            .unwrap_or_else(|| MAINNET_GENESIS_HASH.into()),
+        schema: build_schema(store.clone(), events.clone(), GraphQLLimits::default()),
+        health: Arc::new(IndexerHealth::new(
+            IndexerPhase::Watching,
+            &GenesisConstants::default(),
+            DEFAULT_MAX_SLOT_LAG,
+        )),
+        store,
+        events,
+        state: state.map(|state| Arc::new(RwLock::new(state))),
+    }
+}
+
+fn new_state(store: &Arc<IndexerStore>) -> anyhow::Result<IndexerState> {
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    IndexerState::new(
+        genesis_ledger.into(),
+        IndexerVersion::new_testing(),
+        store.clone(),
+        MAINNET_CANONICAL_THRESHOLD,
+        10,
+    )
+}
+
+#[tokio::test]
+async fn networks() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("rpc-networks")?;
+    let block_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&block_dir)?;
+    let namespaces: [BlockHash; 2] = [TESTNET_GENESIS_HASH.into(), DEVNET_GENESIS_HASH.into()];
+    let db = Arc::new(IndexerStore::new_with_networks(
+        store_dir.path(),
+        &namespaces,
+    )?);
+    let testnet = Arc::new(db.network(&namespaces[0])?);
+    let devnet = Arc::new(db.network(&namespaces[1])?);
+
+    // each network has its own ingestion pipeline
+    let mut mainnet_state = new_state(&db)?;
+    let mut testnet_state = new_state(&testnet)?;
+    let mut testnet_only_mainnet = vec![];
+    while let Some((block, block_bytes)) = block_parser.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        if block.blockchain_length() < TESTNET_MAX_HEIGHT {
+            testnet_state.block_pipeline(&block, block_bytes)?;
+        } else {
+            testnet_only_mainnet.push(block.state_hash());
+        }
+        mainnet_state.block_pipeline(&block, block_bytes)?;
+    }
+
+    // keys are namespaced
+    let mainnet_height = db.get_best_block_height()?.unwrap();
+    assert!(mainnet_height >= TESTNET_MAX_HEIGHT);
+    assert_eq!(
+        testnet.get_best_block_height()?,
+        Some(TESTNET_MAX_HEIGHT - 1)
+    );
+    assert_eq!(devnet.get_best_block_height()?, None);
+    for state_hash in &testnet_only_mainnet {
+        assert!(db.get_block(state_hash)?.is_some());
+        assert!(testnet.get_block(state_hash)?.is_none());
+    }
+
+    // devnet is still initializing
+    let networks = IndexedNetworks::new(network("mainnet", db.clone(), Some(mainnet_state)));
+    networks.add(network("testnet", testnet, Some(testnet_state)));
+    networks.add(network("devnet", devnet, None));
+
+    let socket_path = store_dir.path().join("mina-indexer.sock");
+    let listener = create_socket_listener(&socket_path);
+    Toplevel::new(move |s| async move {
+        s.start(SubsystemBuilder::new("Socket Listener", move |s| {
+            handle_network_connections(listener, networks, s)
+        }));
+        s.start(SubsystemBuilder::new("Client", move |s| {
+            client(s, socket_path, mainnet_height)
+        }));
+    })
+    .handle_shutdown_requests(Duration::from_secs(10))
+    .await
+    .map_err(anyhow::Error::from)
+}
+
+#[tokio::test]
+async fn reopen_networks() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("rpc-networks-reopen")?;
+    let block_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&block_dir)?;
+    let namespace: BlockHash = TESTNET_GENESIS_HASH.into();
+    let (block, block_bytes) = block_parser.next_block().await?.unwrap();
+    let block: PrecomputedBlock = block.into();
+    {
+        let db = IndexerStore::new_with_networks(store_dir.path(), &[namespace.clone()])?;
+        db.network(&namespace)?.add_block(&block, block_bytes)?;
+    }
+
+    // namespaced column families are opened with the database
+    let db = IndexerStore::new(store_dir.path())?;
+    assert!(db.get_block(&block.state_hash())?.is_none());
+    assert!(db
+        .network(&namespace)?
+        .get_block(&block.state_hash())?
+        .is_some());
+    assert!(db.network(&DEVNET_GENESIS_HASH.into()).is_err());
+    Ok(())
+}
+
+async fn client(
+    _subsys: SubsystemHandle,
+    socket_path: PathBuf,
+    mainnet_height: u32,
+) -> anyhow::Result<()> {
+    // the primary network is queried by default
+    let best_tip = IndexerClient::connect_uds(&socket_path)
+        .await?
+        .best_tip()
+        .await?;
+    assert_eq!(best_tip.unwrap().block_height, mainnet_height);
+
+    // networks are selected by name or genesis state hash
+    for network in ["testnet", TESTNET_GENESIS_HASH] {
+        let best_tip = IndexerClient::connect_uds(&socket_path)
+            .await?
+            .on_network(network)
+            .best_tip()
+            .await?;
+        assert_eq!(best_tip.unwrap().block_height, TESTNET_MAX_HEIGHT - 1);
+    }
+
+    let mut client = RpcClient::connect(&socket_path).await?;
+    let result = client
+        .call("blocks.best-tip", Some(json!({ "network": "testnet" })))
+        .await?;
+    let best_tip: serde_json::Value = serde_json::from_str(result.get())?;
+    assert_eq!(best_tip["blockchain_length"], TESTNET_MAX_HEIGHT - 1);
+
+    for (network, code) in [
+        (json!("unknown"), codes::UNKNOWN_NETWORK),
+        (json!("devnet"), codes::NETWORK_INITIALIZING),
+        (json!(1), codes::INVALID_PARAMS),
+    ] {
+        let error = client
+            .call("blocks.best-tip", Some(json!({ "network": network })))
+            .await
+            .unwrap_err()
+            .downcast::<RpcError>()?;
+        assert_eq!(error.code, code);
+    }
+
+    client.call("shutdown", None).await?;
+    Ok(())
+}
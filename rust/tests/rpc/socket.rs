@@ -0,0 +1,171 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{parser::BlockParser, store::BlockStore, BlockWithoutHeight},
+    client::{ClientCli, Ledgers, BIN_CODE_CONFIG},
+    constants::*,
+    event::broadcast::event_channel,
+    health::{IndexerHealth, DEFAULT_MAX_SLOT_LAG},
+    ledger::genesis::{GenesisConstants, GenesisLedger, GenesisRoot},
+    rpc::{self, codes, RpcClient, RpcError},
+    server::IndexerVersion,
+    state::{IndexerPhase, IndexerState},
+    store::IndexerStore,
+    unix_socket_server::{create_socket_listener, handle_connection},
+    web::graphql::{build_schema, limits::GraphQLLimits},
+};
+use serde_json::{json, Value};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt, BufReader},
+    net::UnixStream,
+    sync::RwLock,
+};
+use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle, Toplevel};
+
+#[tokio::test]
+async fn json_rpc() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("rpc-socket")?;
+    let block_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&block_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let mut state = IndexerState::new(
+        genesis_ledger.into(),
+        IndexerVersion::new_testing(),
+        indexer_store.clone(),
+        MAINNET_CANONICAL_THRESHOLD,
+        10,
+    )?;
+    state.add_blocks(&mut block_parser).await?;
+
+    let state = Arc::new(RwLock::new(state));
+    let health = Arc::new(IndexerHealth::new(
+        IndexerPhase::Watching,
+        &GenesisConstants::default(),
+        DEFAULT_MAX_SLOT_LAG,
+    ));
+    let schema = build_schema(
+        indexer_store.clone(),
+        event_channel(),
+        GraphQLLimits::default(),
+    );
+    let socket_path = store_dir.path().join("mina-indexer.sock");
+    let listener = create_socket_listener(&socket_path);
+
+    Toplevel::new(|s| async move {
+        s.start(SubsystemBuilder::new("Socket Listener", move |s| {
+            handle_connection(listener, state, health, schema, event_channel(), s)
+        }));
+        s.start(SubsystemBuilder::new("Client", move |s| {
+            client(s, socket_path, indexer_store)
+        }));
+    })
+    .handle_shutdown_requests(Duration::from_secs(10))
+    .await
+    .map_err(anyhow::Error::from)
+}
+
+async fn client(
+    _subsys: SubsystemHandle,
+    socket_path: PathBuf,
+    store: Arc<IndexerStore>,
+) -> anyhow::Result<()> {
+    let mut client = RpcClient::connect(&socket_path).await?;
+
+    // methods mirror the subcommands
+    let (method, params) = ClientCli::Ledgers(Ledgers::Best { path: None }).to_rpc()?;
+    assert_eq!(method, "ledgers.best");
+    assert_eq!(params, Some(json!({ "path": null })));
+
+    let methods: Vec<rpc::MethodInfo> =
+        serde_json::from_str(client.call(rpc::METHODS_METHOD, None).await?.get())?;
+    let best_tip = methods
+        .iter()
+        .find(|method| method.method == "blocks.best-tip")
+        .unwrap();
+    assert!(best_tip
+        .params
+        .iter()
+        .any(|param| param.name == "verbose" && param.flag));
+    assert!(methods.iter().any(|method| method.method == "db-version"));
+
+    // JSON results are returned as is
+    let block: BlockWithoutHeight =
+        serde_json::from_str(client.call("blocks.best-tip", None).await?.get())?;
+    assert_eq!(block.state_hash, store.get_best_block_hash()?.unwrap());
+    assert_eq!(block.blockchain_length, 21);
+
+    // text results are strings
+    let version = client.call("db-version", Some(json!({}))).await?;
+    assert!(serde_json::from_str::<String>(version.get())?.starts_with("mina-indexer database"));
+
+    // structured errors
+    let error = client
+        .call("blocks.state-hash", Some(json!({ "state_hash": "bad" })))
+        .await
+        .unwrap_err()
+        .downcast::<RpcError>()?;
+    assert_eq!(error.code, codes::INVALID_STATE_HASH);
+    assert_eq!(error.data, Some(json!("bad")));
+
+    for method in ["blocks", "blocks.nope", "rpc.nope"] {
+        let error = client.call(method, None).await.unwrap_err();
+        assert_eq!(error.downcast::<RpcError>()?.code, codes::METHOD_NOT_FOUND);
+    }
+    for params in [
+        None,
+        Some(json!({ "height": "x" })),
+        Some(json!({ "height": 1, "nope": 1 })),
+    ] {
+        let error = client.call("blocks.height", params).await.unwrap_err();
+        assert_eq!(error.downcast::<RpcError>()?.code, codes::INVALID_PARAMS);
+    }
+
+    // large results are streamed
+    let mut stream = BufReader::new(UnixStream::connect(&socket_path).await?);
+    stream.write_all(rpc::PREAMBLE).await?;
+    rpc::write_frame(
+        &mut stream,
+        &json!({"jsonrpc": "2.0", "id": "a", "method": "ledgers.best"}),
+    )
+    .await?;
+
+    let mut num_chunks = 0;
+    let mut ledger = String::new();
+    loop {
+        let frame: Value = serde_json::from_slice(&rpc::read_frame(&mut stream).await?.unwrap())?;
+        if frame["method"] == rpc::CHUNK_METHOD {
+            assert_eq!(frame["params"]["id"], "a");
+            assert_eq!(frame["params"]["seq"], num_chunks);
+            ledger.push_str(frame["params"]["data"].as_str().unwrap());
+            num_chunks += 1;
+        } else {
+            assert_eq!(frame, json!({"jsonrpc": "2.0", "id": "a", "result": null}));
+            break;
+        }
+    }
+    assert!(num_chunks > 1);
+    assert!(serde_json::from_str::<Value>(&ledger)?.is_object());
+
+    // unsupported protocol versions are rejected
+    let mut stream = UnixStream::connect(&socket_path).await?;
+    stream.write_all(b"MINA-INDEXER-RPC/2\n").await?;
+    let frame: Value = serde_json::from_slice(&rpc::read_frame(&mut stream).await?.unwrap())?;
+    assert_eq!(frame["error"]["code"], codes::UNSUPPORTED_VERSION);
+
+    // bincode-encoded commands are still served
+    let mut stream = UnixStream::connect(&socket_path).await?;
+    stream
+        .write_all(&bincode::encode_to_vec(
+            ClientCli::DbVersion,
+            BIN_CODE_CONFIG,
+        )?)
+        .await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+    assert!(response.starts_with("mina-indexer database"));
+
+    client.call("shutdown", None).await?;
+    Ok(())
+}
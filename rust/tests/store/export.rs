@@ -0,0 +1,103 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{parser::BlockParser, store::BlockStore},
+    canonicity::store::CanonicityStore,
+    command::store::UserCommandStore,
+    constants::*,
+    ledger::{
+        genesis::{GenesisLedger, GenesisRoot},
+        store::LedgerStore,
+    },
+    server::IndexerVersion,
+    state::IndexerState,
+    store::{
+        export::{ExportRecord, EXPORT_FORMAT_VERSION},
+        IndexerStore,
+    },
+};
+use std::{path::PathBuf, sync::Arc};
+
+#[tokio::test]
+async fn export_and_import() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("export-store")?;
+    let import_dir = setup_new_db_dir("import-store")?;
+    let blocks_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&blocks_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let mut state = IndexerState::new(
+        genesis_ledger.into(),
+        IndexerVersion::new_testing(),
+        indexer_store.clone(),
+        MAINNET_CANONICAL_THRESHOLD,
+        10,
+    )?;
+    state.add_blocks(&mut block_parser).await?;
+
+    // export
+    let mut export = vec![];
+    let summary = indexer_store.export_ndjson(&mut export)?;
+    assert!(summary.blocks > 0);
+    assert!(summary.ledgers > 0);
+
+    let mut lines = export
+        .split(|b| *b == b'\n')
+        .filter(|line| !line.is_empty());
+    match serde_json::from_slice(lines.next().unwrap())? {
+        ExportRecord::Header {
+            format_version,
+            best_tip,
+            ..
+        } => {
+            assert_eq!(format_version, EXPORT_FORMAT_VERSION);
+            assert_eq!(best_tip, indexer_store.get_best_block_hash()?);
+        }
+        _ => panic!("export must start with a header"),
+    }
+    assert_eq!(
+        lines.count() as u32,
+        summary.blocks
+            + summary.user_commands
+            + summary.internal_commands
+            + summary.snarks
+            + summary.ledgers
+            + summary.staking_ledgers
+            + summary.usernames
+    );
+
+    // import into a fresh store
+    let imported = IndexerStore::new(import_dir.path())?;
+    assert_eq!(imported.import_ndjson(export.as_slice())?, summary);
+    assert_eq!(
+        imported.get_best_block_hash()?,
+        indexer_store.get_best_block_hash()?
+    );
+    assert_eq!(
+        imported.get_user_commands_total_count()?,
+        indexer_store.get_user_commands_total_count()?
+    );
+
+    let best_height = indexer_store.get_best_block_height()?.unwrap();
+    for height in 1..=best_height {
+        for state_hash in indexer_store.get_blocks_at_height(height)? {
+            assert_eq!(
+                imported.get_block(&state_hash)?,
+                indexer_store.get_block(&state_hash)?
+            );
+            assert_eq!(
+                imported.get_block_canonicity(&state_hash)?,
+                indexer_store.get_block_canonicity(&state_hash)?
+            );
+        }
+        assert_eq!(
+            imported.get_canonical_hash_at_height(height)?,
+            indexer_store.get_canonical_hash_at_height(height)?
+        );
+    }
+    assert!(imported.get_best_ledger()? == indexer_store.get_best_ledger()?);
+
+    // importing twice is rejected
+    assert!(imported.import_ndjson(export.as_slice()).is_err());
+    Ok(())
+}
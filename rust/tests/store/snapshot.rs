@@ -0,0 +1,87 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{
+        parser::BlockParser,
+        precomputed::{PcbVersion, PrecomputedBlock},
+        store::BlockStore,
+    },
+    constants::*,
+    store::{
+        snapshot::{read_manifest, restore_snapshot, verify_snapshot},
+        IndexerStore,
+    },
+};
+use std::path::{Path, PathBuf};
+
+async fn add_blocks(db: &IndexerStore, blocks_dir: &Path) -> anyhow::Result<Vec<PrecomputedBlock>> {
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        blocks_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    let mut blocks = vec![];
+    while let Some((block, block_bytes)) = bp.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        db.add_block(&block, block_bytes)?;
+        blocks.push(block);
+    }
+    Ok(blocks)
+}
+
+#[tokio::test]
+async fn incremental_snapshot_chain() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("snapshot-store")?;
+    let snapshot_dir = setup_new_db_dir("snapshot-archives")?;
+    let restore_dir = setup_new_db_dir("snapshot-restore")?;
+    let db = IndexerStore::new(store_dir.path())?;
+    std::fs::create_dir_all(snapshot_dir.path())?;
+
+    // full snapshot
+    let mut blocks = add_blocks(&db, &PathBuf::from("./tests/data/sequential_blocks")).await?;
+    let full = snapshot_dir.path().join("full");
+    db.create_snapshot(&full, None)?;
+
+    let full_manifest = verify_snapshot(&full)?;
+    assert!(!full_manifest.is_incremental());
+    assert!(full_manifest.files.iter().all(|file| file.archived));
+    assert!(full_manifest
+        .files
+        .iter()
+        .any(|file| file.name.ends_with(".sst")));
+
+    // incremental snapshot only archives new SST files
+    blocks
+        .append(&mut add_blocks(&db, &PathBuf::from("./tests/data/non_sequential_blocks")).await?);
+    let incremental = snapshot_dir.path().join("incremental");
+    db.create_snapshot(&incremental, Some(&full))?;
+
+    let manifest = verify_snapshot(&incremental)?;
+    assert_eq!(manifest.base.as_ref().unwrap().file_name, "full");
+    assert_eq!(manifest.best_tip_height, db.get_best_block_height()?);
+    assert!(manifest.archived_files().count() < manifest.files.len());
+    assert!(manifest
+        .files
+        .iter()
+        .filter(|file| !file.archived)
+        .all(|file| file.name.ends_with(".sst")));
+    drop(db);
+
+    // restore from the chain
+    let restore_path = restore_dir.path().join("db");
+    restore_snapshot(&incremental, &restore_path)?;
+
+    let restored = IndexerStore::new(&restore_path)?;
+    for block in blocks {
+        assert_eq!(restored.get_block(&block.state_hash())?.unwrap().0, block);
+    }
+
+    // an incremental snapshot is invalid without its base
+    std::fs::remove_file(&full)?;
+    assert!(read_manifest(&incremental)?.is_some());
+    assert!(verify_snapshot(&incremental).is_err());
+    assert!(restore_snapshot(&incremental, &restore_dir.path().join("other")).is_err());
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use crate::helpers::setup_new_db_dir;
+use actix_web::{
+    test::{self, TestRequest},
+    web::{self, Data},
+    App,
+};
+use mina_indexer::{
+    block::{parser::BlockParser, store::BlockStore},
+    constants::*,
+    event::broadcast::event_channel,
+    health::{IndexerHealth, DEFAULT_MAX_SLOT_LAG},
+    ledger::genesis::{GenesisConstants, GenesisLedger, GenesisRoot},
+    networks::{IndexedNetwork, IndexedNetworks},
+    server::IndexerVersion,
+    state::{IndexerPhase, IndexerState},
+    store::IndexerStore,
+    web::{
+        daemon_graphql,
+        graphql::{build_schema, daemon::build_daemon_schema, limits::GraphQLLimits},
+        ENDPOINT_DAEMON_GRAPHQL,
+    },
+};
+use serde_json::{json, Value};
+use std::{path::PathBuf, sync::Arc};
+
+#[tokio::test]
+async fn daemon_queries() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("daemon-graphql")?;
+    let block_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&block_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let mut state = IndexerState::new(
+        genesis_ledger.into(),
+        IndexerVersion::new_testing(),
+        indexer_store.clone(),
+        MAINNET_CANONICAL_THRESHOLD,
+        10,
+    )?;
+    state.add_blocks(&mut block_parser).await?;
+
+    let schema = build_daemon_schema(
+        indexer_store.clone(),
+        GenesisConstants::default(),
+        GraphQLLimits::default(),
+    );
+    macro_rules! query {
+        ($query:expr) => {{
+            let response = schema.execute($query).await;
+            assert!(response.errors.is_empty(), "{:?}", response.errors);
+            serde_json::to_value(&response.data)?
+        }};
+    }
+
+    let best_hash = indexer_store.get_best_block_hash()?.unwrap().0;
+    let data = query!("{ daemonStatus { blockchainLength syncStatus stateHash } }");
+    assert_eq!(
+        data["daemonStatus"],
+        json!({ "blockchainLength": 21, "syncStatus": "SYNCED", "stateHash": best_hash })
+    );
+
+    // unsigned integers are strings
+    let data = query!("{ genesisConstants { accountCreationFee coinbase } }");
+    assert_eq!(
+        data["genesisConstants"],
+        json!({ "accountCreationFee": "1000000000", "coinbase": "720000000000" })
+    );
+
+    // best chain is oldest first
+    let data = query!(
+        "{ bestChain(maxLength: 3) { stateHash protocolState { consensusState { blockHeight } } } }"
+    );
+    let heights: Vec<&Value> = data["bestChain"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|block| &block["protocolState"]["consensusState"]["blockHeight"])
+        .collect();
+    assert_eq!(heights, [&json!("19"), &json!("20"), &json!("21")]);
+    assert_eq!(data["bestChain"][2]["stateHash"], best_hash);
+
+    // blocks by height & state hash agree
+    let data = query!(
+        "{ block(height: 20) { stateHash commandTransactionCount \
+           transactions { userCommands { hash } coinbase } } }"
+    );
+    let state_hash = data["block"]["stateHash"].as_str().unwrap().to_string();
+    let count = data["block"]["commandTransactionCount"].as_u64().unwrap();
+    assert_eq!(
+        data["block"]["transactions"]["userCommands"]
+            .as_array()
+            .unwrap()
+            .len() as u64,
+        count
+    );
+
+    let data = query!(&format!(
+        "{{ block(stateHash: \"{state_hash}\") {{ protocolState {{ consensusState {{ blockHeight }} }} }} }}"
+    ));
+    assert_eq!(
+        data["block"]["protocolState"]["consensusState"]["blockHeight"],
+        "20"
+    );
+
+    // constants & coinbases are the network's
+    let data = query!("{ block(height: 20) { transactions { coinbase } } }");
+    let coinbase: u64 = data["block"]["transactions"]["coinbase"]
+        .as_str()
+        .unwrap()
+        .parse()?;
+    let network_schema = build_daemon_schema(
+        indexer_store.clone(),
+        GenesisConstants {
+            coinbase_amount: Some(2 * MAINNET_COINBASE_REWARD),
+            account_creation_fee: Some(100_000_000),
+            genesis_state_timestamp: Some(0),
+            ..Default::default()
+        },
+        GraphQLLimits::default(),
+    );
+    let response = network_schema
+        .execute(
+            "{ genesisConstants { accountCreationFee coinbase genesisTimestamp } \
+               block(height: 20) { transactions { coinbase } } }",
+        )
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let data = serde_json::to_value(&response.data)?;
+    assert_eq!(
+        data["genesisConstants"],
+        json!({
+            "accountCreationFee": "100000000",
+            "coinbase": (2 * MAINNET_COINBASE_REWARD).to_string(),
+            "genesisTimestamp": "1970-01-01T00:00:00.000Z",
+        })
+    );
+    assert_eq!(
+        data["block"]["transactions"]["coinbase"],
+        (2 * coinbase).to_string()
+    );
+
+    // best ledger accounts
+    let pk = "B62qrecVjpoZ4Re3a5arN6gXZ6orhmj1enUtA887XdG5mtZfdUbBUh4";
+    let data = query!(&format!(
+        "{{ account(publicKey: \"{pk}\") {{ publicKey token balance {{ total blockHeight }} }} }}"
+    ));
+    assert_eq!(data["account"]["publicKey"], pk);
+    assert_eq!(data["account"]["token"], "1");
+    assert_eq!(data["account"]["balance"]["blockHeight"], "21");
+    assert!(data["account"]["balance"]["total"].is_string());
+    Ok(())
+}
+
+#[actix_web::test]
+async fn daemon_queries_of_the_primary_network() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("daemon-graphql-networks")?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let network = |name: &str, genesis_state_hash: &str| {
+        let events = event_channel();
+        IndexedNetwork {
+            name: name.to_string(),
+            genesis_state_hash: genesis_state_hash.into(),
+            schema: build_schema(
+                indexer_store.clone(),
+                events.clone(),
+                GraphQLLimits::default(),
+            ),
+            health: Arc::new(IndexerHealth::new(
+                IndexerPhase::Watching,
+                &GenesisConstants::default(),
+                DEFAULT_MAX_SLOT_LAG,
+            )),
+            store: indexer_store.clone(),
+            events,
+            state: None,
+        }
+    };
+    let networks = IndexedNetworks::new(network("mainnet", MAINNET_GENESIS_HASH));
+    networks.add(network(
+        "testnet",
+        "3NKGgTk7en3347KH81yDra876GPAUSoSePrfVKPmwR1KHfMpvJC5",
+    ));
+
+    let schema = build_daemon_schema(
+        indexer_store.clone(),
+        GenesisConstants::default(),
+        GraphQLLimits::default(),
+    );
+    let app = test::init_service(
+        App::new()
+            .app_data(Data::new(networks))
+            .app_data(Data::new(schema))
+            .route(ENDPOINT_DAEMON_GRAPHQL, web::post().to(daemon_graphql)),
+    )
+    .await;
+    let query = json!({ "query": "{ genesisConstants { coinbase } }" });
+
+    for uri in [
+        ENDPOINT_DAEMON_GRAPHQL.to_string(),
+        format!("{ENDPOINT_DAEMON_GRAPHQL}?network=mainnet"),
+        format!("{ENDPOINT_DAEMON_GRAPHQL}?network={MAINNET_GENESIS_HASH}"),
+    ] {
+        let request = TestRequest::post().uri(&uri).set_json(&query).to_request();
+        let body: Value = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(body["data"]["genesisConstants"]["coinbase"], "720000000000");
+    }
+
+    // other networks are rejected rather than served the primary's data
+    for (network, status) in [("testnet", 400), ("unknown", 404)] {
+        let uri = format!("{ENDPOINT_DAEMON_GRAPHQL}?network={network}");
+        let request = TestRequest::post().uri(&uri).set_json(&query).to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status().as_u16(), status);
+    }
+    let uri = format!("{ENDPOINT_DAEMON_GRAPHQL}?network=testnet");
+    let request = TestRequest::post().uri(&uri).set_json(&query).to_request();
+    let body = test::call_and_read_body(&app, request).await;
+    assert!(String::from_utf8(body.to_vec())?.contains("only serves the primary network mainnet"));
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{
+        parser::BlockParser,
+        precomputed::{PcbVersion, PrecomputedBlock},
+        store::BlockStore,
+    },
+    constants::*,
+    event::broadcast::event_channel,
+    store::IndexerStore,
+    web::graphql::{build_schema, limits::GraphQLLimits},
+};
+use serde_json::Value;
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::PathBuf,
+    sync::Arc,
+};
+
+const FIELDS: &str = "stateHash blockHeight protocolState { consensusState { slotSinceGenesis } }";
+
+#[tokio::test]
+async fn bounded_pages_in_the_other_index_order() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("graphql-bounded-blocks")?;
+    let blocks_dir = &PathBuf::from("./tests/data/sequential_blocks");
+    let db = Arc::new(IndexerStore::new(store_dir.path())?);
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        blocks_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+    let mut blocks = HashMap::new();
+    while let Some((block, block_bytes)) = bp.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        db.add_block(&block, block_bytes)?;
+        blocks.insert(
+            block.state_hash().0,
+            (block.blockchain_length(), block.global_slot_since_genesis()),
+        );
+    }
+    let schema = build_schema(db, event_channel(), GraphQLLimits::default());
+    let execute = |query: String| {
+        let schema = &schema;
+        async move {
+            let response = schema.execute(query).await;
+            assert!(response.errors.is_empty(), "{:?}", response.errors);
+            response.data.into_json()
+        }
+    };
+
+    // state hash, height & slot of the blocks
+    let blocks_of = |nodes: Vec<&Value>| -> Vec<(String, u32, u32)> {
+        nodes
+            .into_iter()
+            .map(|block| {
+                let state_hash = block["stateHash"].as_str().unwrap().to_string();
+                let (height, slot) = blocks[&state_hash];
+                assert_eq!(block["blockHeight"], height);
+                assert_eq!(
+                    block["protocolState"]["consensusState"]["slotSinceGenesis"],
+                    slot
+                );
+                (state_hash, height, slot)
+            })
+            .collect()
+    };
+
+    // following the end cursors
+    let paged = |query: String, sort_by: &'static str| {
+        let (execute, blocks_of) = (&execute, &blocks_of);
+        async move {
+            let mut paged = vec![];
+            let mut after = String::new();
+            loop {
+                let data = execute(format!(
+                    "{{ blocksConnection(query: {query}, sortBy: {sort_by}, first: 2{after}) {{
+                        edges {{ cursor node {{ {FIELDS} }} }}
+                        pageInfo {{ hasNextPage endCursor }}
+                    }} }}"
+                ))
+                .await?;
+                let connection = &data["blocksConnection"];
+                let edges = connection["edges"].as_array().unwrap();
+                paged.extend(blocks_of(edges.iter().map(|edge| &edge["node"]).collect()));
+                if connection["pageInfo"]["hasNextPage"] != true {
+                    break;
+                }
+                after = format!(
+                    ", after: \"{}\"",
+                    connection["pageInfo"]["endCursor"].as_str().unwrap()
+                );
+            }
+            anyhow::Ok(paged)
+        }
+    };
+
+    let heights: BTreeSet<u32> = blocks.values().map(|(height, _)| *height).collect();
+    let slots: BTreeSet<u32> = blocks.values().map(|(_, slot)| *slot).collect();
+    let (min_height, max_height) = (
+        *heights.iter().nth(2).unwrap(),
+        *heights.iter().nth_back(2).unwrap(),
+    );
+    let (min_slot, max_slot) = (
+        *slots.iter().nth(2).unwrap(),
+        *slots.iter().nth_back(2).unwrap(),
+    );
+
+    for (query, sort_by, in_range) in [
+        (
+            format!("{{ blockHeight_gte: {min_height}, blockHeight_lte: {max_height} }}"),
+            "GLOBALSLOT_DESC",
+            Box::new(move |(height, _): (u32, u32)| (min_height..=max_height).contains(&height))
+                as Box<dyn Fn((u32, u32)) -> bool>,
+        ),
+        (
+            format!("{{ blockHeight_gte: {min_height}, blockHeight_lte: {max_height} }}"),
+            "GLOBALSLOT_ASC",
+            Box::new(move |(height, _): (u32, u32)| (min_height..=max_height).contains(&height)),
+        ),
+        (
+            format!("{{ protocolState: {{ consensusState: {{ slotSinceGenesis_gte: {min_slot}, slotSinceGenesis_lte: {max_slot} }} }} }}"),
+            "BLOCKHEIGHT_ASC",
+            Box::new(move |(_, slot): (u32, u32)| (min_slot..=max_slot).contains(&slot)),
+        ),
+        (
+            format!("{{ protocolState: {{ consensusState: {{ slotSinceGenesis_gte: {min_slot}, slotSinceGenesis_lte: {max_slot} }} }} }}"),
+            "BLOCKHEIGHT_DESC",
+            Box::new(move |(_, slot): (u32, u32)| (min_slot..=max_slot).contains(&slot)),
+        ),
+    ] {
+        let paged = paged(query.clone(), sort_by).await?;
+
+        // every block in range, once
+        let expected: BTreeSet<&str> = blocks
+            .iter()
+            .filter(|(_, bounds)| in_range(**bounds))
+            .map(|(state_hash, _)| state_hash.as_str())
+            .collect();
+        let state_hashes: Vec<&str> = paged.iter().map(|(state_hash, ..)| state_hash.as_str()).collect();
+        assert_eq!(state_hashes.len(), expected.len(), "{sort_by}");
+        assert_eq!(state_hashes.into_iter().collect::<BTreeSet<_>>(), expected);
+
+        // in sort order across pages
+        let sort_keys: Vec<u32> = paged
+            .iter()
+            .map(|(_, height, slot)| match sort_by {
+                "GLOBALSLOT_ASC" | "GLOBALSLOT_DESC" => *slot,
+                _ => *height,
+            })
+            .collect();
+        if sort_by.ends_with("ASC") {
+            assert!(sort_keys.windows(2).all(|pair| pair[0] <= pair[1]), "{sort_by}");
+        } else {
+            assert!(sort_keys.windows(2).all(|pair| pair[0] >= pair[1]), "{sort_by}");
+        }
+
+        // pages agree with the list
+        let data = execute(format!(
+            "{{ blocks(query: {query}, sortBy: {sort_by}, limit: 1000) {{ {FIELDS} }} }}"
+        ))
+        .await?;
+        let listed = blocks_of(data["blocks"].as_array().unwrap().iter().collect());
+        assert_eq!(listed, paged);
+    }
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{
+        parser::BlockParser,
+        precomputed::{PcbVersion, PrecomputedBlock},
+        store::BlockStore,
+    },
+    constants::*,
+    event::broadcast::event_channel,
+    store::IndexerStore,
+    web::graphql::{build_schema, limits::GraphQLLimits},
+};
+use serde_json::Value;
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::PathBuf,
+    sync::Arc,
+};
+
+#[tokio::test]
+async fn recipient_fee_transfers_are_sorted() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("graphql-recipient-feetransfers")?;
+    let blocks_dir = &PathBuf::from("./tests/data/sequential_blocks");
+    let db = Arc::new(IndexerStore::new(store_dir.path())?);
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        blocks_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+    while let Some((block, block_bytes)) = bp.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        db.add_block(&block, block_bytes)?;
+    }
+    let schema = build_schema(db, event_channel(), GraphQLLimits::default());
+    let execute = |query: String| {
+        let schema = &schema;
+        async move {
+            let response = schema.execute(query).await;
+            assert!(response.errors.is_empty(), "{:?}", response.errors);
+            response.data.into_json()
+        }
+    };
+
+    // the recipient of fee transfers in the most blocks
+    let data = execute("{ feetransfers(limit: 1000) { recipient blockHeight } }".into()).await?;
+    let mut heights: HashMap<&str, BTreeSet<u64>> = HashMap::new();
+    for ft in data["feetransfers"].as_array().unwrap() {
+        heights
+            .entry(ft["recipient"].as_str().unwrap())
+            .or_default()
+            .insert(ft["blockHeight"].as_u64().unwrap());
+    }
+    let (recipient, recipient_heights) = heights
+        .iter()
+        .max_by_key(|(_, heights)| heights.len())
+        .unwrap();
+    assert!(recipient_heights.len() > 2);
+
+    let block_heights = |fts: &Value| -> Vec<u64> {
+        fts.as_array()
+            .unwrap()
+            .iter()
+            .map(|ft| ft["blockHeight"].as_u64().unwrap())
+            .collect()
+    };
+
+    // highest block first by default
+    let query = format!("{{ recipient: \"{recipient}\" }}");
+    let data = execute(format!(
+        "{{ feetransfers(query: {query}, limit: 1000) {{ blockHeight }} }}"
+    ))
+    .await?;
+    let desc = block_heights(&data["feetransfers"]);
+    assert!(desc.windows(2).all(|pair| pair[0] >= pair[1]));
+    assert_eq!(desc.first(), recipient_heights.last());
+
+    let data = execute(format!(
+        "{{ feetransfers(query: {query}, sortBy: BLOCKHEIGHT_ASC, limit: 1000) {{ blockHeight }} }}"
+    ))
+    .await?;
+    let asc = block_heights(&data["feetransfers"]);
+    assert!(asc.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_eq!(asc.first(), recipient_heights.first());
+    assert_eq!(asc.len(), desc.len());
+
+    // pages follow the sort order
+    let mut paged = vec![];
+    let mut after = String::new();
+    loop {
+        let data = execute(format!(
+            "{{ feetransfersConnection(query: {query}, first: 2{after}) {{
+                edges {{ node {{ blockHeight }} }}
+                pageInfo {{ hasNextPage endCursor }}
+            }} }}"
+        ))
+        .await?;
+        let connection = &data["feetransfersConnection"];
+        for edge in connection["edges"].as_array().unwrap() {
+            paged.push(edge["node"]["blockHeight"].as_u64().unwrap());
+        }
+        if connection["pageInfo"]["hasNextPage"] != true {
+            break;
+        }
+        after = format!(
+            ", after: \"{}\"",
+            connection["pageInfo"]["endCursor"].as_str().unwrap()
+        );
+    }
+    assert_eq!(paged, desc);
+    Ok(())
+}
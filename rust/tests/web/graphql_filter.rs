@@ -0,0 +1,101 @@
+use async_graphql::{InputObject, Result};
+use mina_indexer::web::graphql::filter::{Filter, Filterable};
+use serde_json::{json, Value};
+
+#[derive(InputObject, Default)]
+struct NodeQueryInput {
+    canonical: Option<bool>,
+    block_height_gte: Option<u32>,
+    block_height_lt: Option<u32>,
+    fee_in: Option<Vec<Option<String>>>,
+    memo_exists: Option<bool>,
+    receiver: Option<ReceiverQueryInput>,
+    missing: Option<String>,
+    or: Option<Vec<NodeQueryInput>>,
+}
+
+#[derive(InputObject, Default)]
+struct ReceiverQueryInput {
+    public_key_ne: Option<String>,
+}
+
+struct Node(Value);
+
+impl Filterable for Node {
+    const NAME: &'static str = "Node";
+
+    fn filter_fields(&self) -> Value {
+        self.0.clone()
+    }
+}
+
+fn node() -> Node {
+    Node(json!({
+        "canonical": true,
+        "block_height": 10,
+        "fee": "1000000",
+        "memo": "",
+        "receiver": { "public_key": "B62qrecvr" },
+    }))
+}
+
+#[test]
+fn operators() -> Result<()> {
+    let node = node();
+    let matches = |input: NodeQueryInput| Filter::new(&input).matches(&node);
+
+    assert!(matches(NodeQueryInput::default())?);
+    assert!(matches(NodeQueryInput {
+        canonical: Some(true),
+        block_height_gte: Some(10),
+        block_height_lt: Some(11),
+        ..Default::default()
+    })?);
+    assert!(!matches(NodeQueryInput {
+        block_height_lt: Some(10),
+        ..Default::default()
+    })?);
+    assert!(matches(NodeQueryInput {
+        fee_in: Some(vec![Some("1".into()), Some("1000000".into())]),
+        memo_exists: Some(false),
+        ..Default::default()
+    })?);
+    assert!(!matches(NodeQueryInput {
+        receiver: Some(ReceiverQueryInput {
+            public_key_ne: Some("B62qrecvr".into()),
+        }),
+        ..Default::default()
+    })?);
+    Ok(())
+}
+
+#[test]
+fn or_combines_conditions() -> Result<()> {
+    let node = node();
+    let input = NodeQueryInput {
+        canonical: Some(true),
+        or: Some(vec![
+            NodeQueryInput {
+                block_height_lt: Some(5),
+                ..Default::default()
+            },
+            NodeQueryInput {
+                block_height_gte: Some(10),
+                ..Default::default()
+            },
+        ]),
+        ..Default::default()
+    };
+    assert!(Filter::new(&input).matches(&node)?);
+    Ok(())
+}
+
+#[test]
+fn unsupported_field_is_an_error() {
+    let input = NodeQueryInput {
+        missing: Some("value".into()),
+        ..Default::default()
+    };
+    let err = Filter::new(&input).matches(&node()).unwrap_err();
+    assert_eq!(err.message, "Filter missing is not supported on Node");
+}
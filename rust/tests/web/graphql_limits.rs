@@ -0,0 +1,78 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PrecomputedBlock, store::BlockStore},
+    event::broadcast::event_channel,
+    store::IndexerStore,
+    web::graphql::{build_schema, limits::GraphQLLimits, IndexerSchema},
+};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+fn schema(name: &str) -> anyhow::Result<(IndexerSchema, tempfile::TempDir)> {
+    let store_dir = setup_new_db_dir(name)?;
+    let store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let limits = GraphQLLimits {
+        max_depth: 3,
+        max_complexity: 50,
+        ..Default::default()
+    };
+    Ok((build_schema(store, event_channel(), limits), store_dir))
+}
+
+#[tokio::test]
+async fn cost_in_extensions() -> anyhow::Result<()> {
+    let (schema, _store_dir) = schema("graphql-limits-cost")?;
+    let response = schema.execute("{ version }").await;
+    assert!(response.errors.is_empty());
+
+    let analyzer = serde_json::to_value(&response.extensions["analyzer"])?;
+    assert_eq!(analyzer, serde_json::json!({ "complexity": 1, "depth": 1 }));
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_expensive_queries() -> anyhow::Result<()> {
+    let (schema, _store_dir) = schema("graphql-limits-rejects")?;
+
+    // list cost scales with the requested limit
+    let response = schema.execute("{ blocks(limit: 100) { stateHash } }").await;
+    assert_eq!(response.errors[0].message, "Query is too complex.");
+
+    let response = schema
+        .execute("{ blocks(limit: 1) { protocolState { consensusState { blockHeight } } } }")
+        .await;
+    assert_eq!(response.errors[0].message, "Query is nested too deep.");
+    Ok(())
+}
+
+#[tokio::test]
+async fn slow_queries_time_out() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("graphql-limits-timeout")?;
+    let block_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&block_dir)?;
+    let store = Arc::new(IndexerStore::new(store_dir.path())?);
+    while let Some((block, block_bytes)) = block_parser.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        store.add_block(&block, block_bytes)?;
+    }
+    let query = "{ blocks(limit: 10) { stateHash } }";
+
+    let schema = build_schema(store.clone(), event_channel(), GraphQLLimits::default());
+    let response = schema.execute(query).await;
+    assert!(response.errors.is_empty());
+
+    // the resolver stops iterating the store at the deadline, even though its
+    // synchronous iteration is never interrupted by the async timeout
+    let limits = GraphQLLimits {
+        timeout: Duration::ZERO,
+        ..Default::default()
+    };
+    let schema = build_schema(store, event_channel(), limits);
+    let response = schema.execute(query).await;
+    assert_eq!(response.errors.len(), 1);
+    assert_eq!(response.errors[0].message, "Query timed out after 0ms");
+    assert_eq!(
+        serde_json::to_value(&response.errors[0].path)?,
+        serde_json::json!(["blocks"])
+    );
+    Ok(())
+}
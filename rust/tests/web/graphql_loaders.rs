@@ -0,0 +1,56 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{
+        parser::BlockParser,
+        precomputed::{PcbVersion, PrecomputedBlock},
+        store::BlockStore,
+        BlockHash,
+    },
+    constants::*,
+    store::IndexerStore,
+    web::graphql::loaders::PrecomputedBlockCache,
+};
+use std::{path::PathBuf, sync::Arc};
+
+#[tokio::test]
+async fn block_cache_evicts_least_recently_used() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("graphql-block-cache")?;
+    let block_dir = &PathBuf::from("./tests/data/sequential_blocks");
+
+    let db = IndexerStore::new(store_dir.path())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        block_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    let mut state_hashes = vec![];
+    while let Some((block, block_bytes)) = bp.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        db.add_block(&block, block_bytes)?;
+        state_hashes.push(block.state_hash());
+    }
+
+    let cache = PrecomputedBlockCache::new(1);
+    let first = cache.get(&db, &state_hashes[0])?.unwrap();
+    assert_eq!(first.state_hash(), state_hashes[0]);
+
+    // hits share the deserialized block
+    assert!(Arc::ptr_eq(
+        &first,
+        &cache.get(&db, &state_hashes[0])?.unwrap()
+    ));
+
+    // the first block is evicted by the second
+    cache.get(&db, &state_hashes[1])?.unwrap();
+    let reread = cache.get(&db, &state_hashes[0])?.unwrap();
+    assert!(!Arc::ptr_eq(&first, &reread));
+    assert_eq!(*first, *reread);
+
+    // unknown blocks aren't cached
+    let missing = BlockHash("3NKBBXt3uDK6pkfR6dKTjYL6NCh4h7PUWzX7dbVfUeqmfhh5zmRf".into());
+    assert!(cache.get(&db, &missing)?.is_none());
+    Ok(())
+}
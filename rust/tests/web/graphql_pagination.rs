@@ -0,0 +1,78 @@
+use async_graphql::{connection::CursorType, Result};
+use mina_indexer::web::graphql::pagination::{Page, SortKeyCursor};
+
+const KEYS: [&[u8]; 5] = [b"a0", b"a1", b"b0", b"b1", b"c0"];
+
+fn page_of(first: usize, after: Option<String>) -> Result<Page<String>> {
+    let mut page = Page::new(first, after)?;
+    let start = page.seek(b"a0").to_vec();
+    for key in KEYS.iter().filter(|key| **key >= start.as_slice()) {
+        if page.push(key, String::from_utf8(key.to_vec())?) {
+            break;
+        }
+    }
+    Ok(page)
+}
+
+#[test]
+fn cursor_round_trip() {
+    let cursor = SortKeyCursor(vec![0, 1, 2, 255]);
+    let encoded = cursor.encode_cursor();
+
+    assert_eq!(SortKeyCursor::decode_cursor(&encoded), Ok(cursor));
+    assert!(SortKeyCursor::decode_cursor("").is_err());
+    assert!(SortKeyCursor::decode_cursor("not base64!").is_err());
+}
+
+#[test]
+fn end_cursor_resumes() -> Result<()> {
+    let first = page_of(2, None)?.into_connection(Some(KEYS.len() as u32));
+    assert!(first.has_next_page);
+    assert!(!first.has_previous_page);
+    assert_eq!(first.additional_fields.total_count, Some(5));
+
+    let after = first.edges.last().unwrap().cursor.encode_cursor();
+    let second = page_of(2, Some(after))?.into_connection(None);
+    let nodes: Vec<_> = second.edges.iter().map(|edge| edge.node.as_str()).collect();
+    assert_eq!(nodes, vec!["b0", "b1"]);
+    assert!(second.has_next_page);
+    assert!(second.has_previous_page);
+
+    let after = second.edges.last().unwrap().cursor.encode_cursor();
+    let last = page_of(2, Some(after))?.into_connection(None);
+    let nodes: Vec<_> = last.edges.iter().map(|edge| edge.node.as_str()).collect();
+    assert_eq!(nodes, vec!["c0"]);
+    assert!(!last.has_next_page);
+    Ok(())
+}
+
+#[test]
+fn offset_and_grouped_cursors() -> Result<()> {
+    let nodes = || ["x", "y", "z"].map(String::from);
+
+    // offset cursor
+    let mut page = Page::new(1, None)?;
+    page.extend(nodes())?;
+    let after = page.into_connection(None).edges[0].cursor.encode_cursor();
+
+    let mut page = Page::new(5, Some(after))?;
+    page.extend(nodes())?;
+    assert_eq!(page.into_nodes(), vec!["y", "z"]);
+
+    // several nodes per iterator entry
+    let mut page = Page::new(2, None)?;
+    for (n, node) in nodes().into_iter().enumerate() {
+        if page.push_nth(b"key", n as u32, node) {
+            break;
+        }
+    }
+    let after = page.into_connection(None).edges[1].cursor.encode_cursor();
+
+    let mut page = Page::new(2, Some(after))?;
+    assert_eq!(page.seek_group(b"start"), b"key");
+    for (n, node) in nodes().into_iter().enumerate() {
+        page.push_nth(b"key", n as u32, node);
+    }
+    assert_eq!(page.into_nodes(), vec!["z"]);
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{
+        parser::BlockParser,
+        precomputed::{PcbVersion, PrecomputedBlock},
+        store::BlockStore,
+    },
+    constants::*,
+    event::broadcast::event_channel,
+    store::IndexerStore,
+    web::graphql::{build_schema, limits::GraphQLLimits},
+};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+#[tokio::test]
+async fn pruned_blocks_fail_only_full_block_fields() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("graphql-pruned-blocks")?;
+    let blocks_dir = &PathBuf::from("./tests/data/sequential_blocks");
+    let db = Arc::new(IndexerStore::new(store_dir.path())?);
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        blocks_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    let mut blocks = HashMap::new();
+    while let Some((block, block_bytes)) = bp.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        db.add_block(&block, block_bytes)?;
+        blocks.insert(block.state_hash().0, block);
+    }
+
+    // prune the lowest blocks, without a blocks dir to re-hydrate them
+    let min_height = blocks
+        .values()
+        .map(|block| block.blockchain_length())
+        .min()
+        .unwrap();
+    assert!(db.prune_blocks(u32::MAX, min_height + 1)? > 0);
+
+    let schema = build_schema(db.clone(), event_channel(), GraphQLLimits::default());
+    let response = schema
+        .execute(
+            "{ blocks(limit: 1, sortBy: BLOCKHEIGHT_ASC) {
+                stateHash
+                blockHeight
+                protocolState {
+                    previousStateHash
+                    consensusState { slotSinceGenesis totalCurrency }
+                }
+            } }",
+        )
+        .await;
+
+    // the list is served from the pruned block
+    let data = response.data.into_json()?;
+    let block = &data["blocks"][0];
+    let pcb = &blocks[block["stateHash"].as_str().unwrap()];
+    assert_eq!(block["blockHeight"], min_height);
+    assert_eq!(
+        block["protocolState"]["previousStateHash"],
+        pcb.previous_state_hash().0
+    );
+    assert_eq!(
+        block["protocolState"]["consensusState"]["slotSinceGenesis"],
+        pcb.global_slot_since_genesis()
+    );
+
+    // only the field which needs the full block fails
+    assert!(block["protocolState"]["consensusState"]["totalCurrency"].is_null());
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].message.contains("has been pruned"));
+
+    // with a blocks dir, full block fields & filters re-hydrate the block
+    db.set_blocks_dir(blocks_dir)?;
+    let response = schema
+        .execute(
+            "{ blocks(
+                limit: 1,
+                sortBy: BLOCKHEIGHT_ASC,
+                query: { protocolState: { consensusState: { totalCurrency_exists: true } } }
+            ) {
+                stateHash
+                protocolState { consensusState { totalCurrency } }
+            } }",
+        )
+        .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+    let data = response.data.into_json()?;
+    let block = &data["blocks"][0];
+    let pcb = &blocks[block["stateHash"].as_str().unwrap()];
+    assert_eq!(pcb.blockchain_length(), min_height);
+    assert_eq!(
+        block["protocolState"]["consensusState"]["totalCurrency"],
+        pcb.consensus_state().total_currency.t.t
+    );
+    Ok(())
+}
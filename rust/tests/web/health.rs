@@ -0,0 +1,77 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::{parser::BlockParser, store::BlockStore},
+    constants::*,
+    health::{IndexerHealth, DEFAULT_MAX_SLOT_LAG},
+    ledger::genesis::{GenesisConstants, GenesisLedger, GenesisRoot},
+    server::IndexerVersion,
+    state::{IndexerPhase, IndexerState},
+    store::IndexerStore,
+};
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[tokio::test]
+async fn readiness() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("health-readiness")?;
+    let block_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&block_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let mut state = IndexerState::new(
+        genesis_ledger.into(),
+        IndexerVersion::new_testing(),
+        indexer_store.clone(),
+        MAINNET_CANONICAL_THRESHOLD,
+        10,
+    )?;
+    state.add_blocks(&mut block_parser).await?;
+
+    // mainnet's wall-clock slot is far beyond the fixture blocks
+    let health = IndexerHealth::new(
+        IndexerPhase::SyncingFromDB,
+        &GenesisConstants::default(),
+        DEFAULT_MAX_SLOT_LAG,
+    );
+    assert!(health.health(&indexer_store).healthy);
+
+    let status = health.readiness(&indexer_store)?;
+    assert!(!status.ready);
+    assert_eq!(status.best_tip_height, Some(21));
+    assert!(status.slot_lag.unwrap() > DEFAULT_MAX_SLOT_LAG);
+
+    // genesis such that the best tip's slot is the wall-clock slot
+    let best_tip_slot = indexer_store.get_best_block_global_slot()?.unwrap() as u64;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+    let constants = GenesisConstants {
+        genesis_state_timestamp: Some(now - best_tip_slot * MAINNET_BLOCK_SLOT_TIME_MILLIS),
+        ..Default::default()
+    };
+    let health = IndexerHealth::new(IndexerPhase::SyncingFromDB, &constants, 2);
+    assert!(!health.readiness(&indexer_store)?.ready);
+
+    // ready once watching with the UDS server up
+    health.set_phase(IndexerPhase::Watching);
+    assert!(!health.readiness(&indexer_store)?.ready);
+
+    // additional networks follow the primary network's UDS server
+    let additional =
+        IndexerHealth::new(IndexerPhase::Watching, &constants, 2).with_uds_server_of(&health);
+    assert!(!additional.readiness(&indexer_store)?.uds_server_up);
+
+    health.set_uds_server_up(true);
+    assert!(additional.readiness(&indexer_store)?.ready);
+
+    let status = health.status(&indexer_store)?;
+    assert!(status.readiness.ready);
+    assert_eq!(status.readiness.slot_lag, Some(0));
+    assert_eq!(
+        serde_json::to_value(&status)?["readiness"]["phase"],
+        "Watching"
+    );
+    Ok(())
+}
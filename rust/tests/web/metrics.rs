@@ -0,0 +1,97 @@
+use crate::helpers::setup_new_db_dir;
+use mina_indexer::{
+    block::parser::BlockParser,
+    constants::*,
+    event::broadcast::event_channel,
+    ledger::genesis::{GenesisLedger, GenesisRoot},
+    metrics::metrics,
+    server::IndexerVersion,
+    state::IndexerState,
+    store::IndexerStore,
+    web::{
+        graphql::{build_schema, limits::GraphQLLimits},
+        ENDPOINT_GRAPHQL,
+    },
+};
+use std::{path::PathBuf, sync::Arc};
+
+/// Value of the sample starting with `prefix`
+fn sample(text: &str, prefix: &str) -> Option<f64> {
+    text.lines()
+        .find(|line| line.starts_with(prefix))
+        .and_then(|line| line.rsplit(' ').next()?.parse().ok())
+}
+
+#[tokio::test]
+async fn prometheus_text() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("metrics")?;
+    let block_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&block_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let mut state = IndexerState::new(
+        genesis_ledger.into(),
+        IndexerVersion::new_testing(),
+        indexer_store.clone(),
+        MAINNET_CANONICAL_THRESHOLD,
+        10,
+    )?;
+    state.add_blocks(&mut block_parser).await?;
+
+    let text = metrics().gather(&indexer_store)?;
+    assert_eq!(sample(&text, "mina_indexer_best_tip_height "), Some(21.0));
+    assert_eq!(
+        sample(&text, "mina_indexer_canonical_root_height "),
+        Some(11.0)
+    );
+    assert_eq!(
+        sample(&text, "mina_indexer_witness_tree_blocks "),
+        Some(state.len() as f64)
+    );
+    assert!(sample(&text, "mina_indexer_best_tip_lag_seconds ").unwrap() > 0.0);
+
+    // the registry is shared with other tests' ingestion
+    assert!(sample(&text, "mina_indexer_blocks_ingested_total{kind=\"recent\"}").unwrap() >= 20.0);
+    assert!(sample(
+        &text,
+        "mina_indexer_block_ingestion_seconds_count{kind=\"recent\"}"
+    )
+    .is_some());
+
+    // per column family store stats
+    assert!(text.contains("mina_indexer_speedb_estimated_keys{cf=\"blocks-state-hash\"}"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn graphql_requests_by_root_field() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("metrics-graphql")?;
+    let store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let schema = build_schema(store.clone(), event_channel(), GraphQLLimits::default());
+    let field_sample = |text: &str, field: &str| {
+        sample(
+            text,
+            &format!(
+                "mina_indexer_graphql_requests_total{{endpoint=\"{ENDPOINT_GRAPHQL}\",field=\"{field}\"}}"
+            ),
+        )
+        .unwrap_or_default()
+    };
+
+    let before = metrics().gather(&store)?;
+    for query in [
+        "query ClientChosenName1 { version }",
+        "query ClientChosenName2 { ...Version } fragment Version on Query { version }",
+        "{ version __typename }",
+    ] {
+        assert!(schema.execute(query).await.errors.is_empty());
+    }
+
+    // operation names aren't labels
+    let after = metrics().gather(&store)?;
+    assert!(!after.contains("ClientChosenName"));
+    assert!(field_sample(&after, "version") >= field_sample(&before, "version") + 2.0);
+    assert!(field_sample(&after, "multiple") >= field_sample(&before, "multiple") + 1.0);
+    Ok(())
+}
@@ -0,0 +1,14 @@
+mod daemon_graphql;
+mod graphql_blocks;
+mod graphql_feetransfers;
+mod graphql_filter;
+mod graphql_limits;
+mod graphql_loaders;
+mod graphql_pagination;
+mod graphql_pruned;
+mod health;
+mod metrics;
+mod openapi;
+mod rest_pagination;
+mod rest_pruned;
+mod rosetta;
@@ -0,0 +1,64 @@
+use mina_indexer::web::rest::openapi::openapi;
+use serde_json::Value;
+
+fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    ("$ref", Value::String(reference)) => refs.push(reference),
+                    _ => collect_refs(value, refs),
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| collect_refs(value, refs)),
+        _ => (),
+    }
+}
+
+#[test]
+fn document_is_complete() -> anyhow::Result<()> {
+    let doc = serde_json::to_value(openapi())?;
+    assert_eq!(doc["openapi"], "3.0.3");
+
+    // every endpoint is documented
+    let paths = doc["paths"].as_object().unwrap();
+    for path in [
+        "/accounts/{public_key}",
+        "/accounts/{public_key}/transactions",
+        "/blocks",
+        "/blocks/{input}",
+        "/internal-commands",
+        "/ledgers/{input}",
+        "/reorgs",
+        "/reorgs/stats",
+        "/staking-ledgers/{epoch}",
+        "/staking-ledgers/{epoch}/delegations",
+        "/summary",
+        "/transactions/{hash}",
+        "/usernames/{public_key}",
+    ] {
+        assert!(paths.contains_key(path), "{path} is missing");
+    }
+
+    // every referenced schema exists
+    let schemas = doc["components"]["schemas"].as_object().unwrap();
+    let mut refs = vec![];
+    collect_refs(&doc, &mut refs);
+    assert!(!refs.is_empty());
+
+    for reference in refs {
+        let name = reference.trim_start_matches("#/components/schemas/");
+        assert!(schemas.contains_key(name), "{reference} is missing");
+    }
+
+    // cursor pagination & network params
+    let params = doc["paths"]["/transactions"]["get"]["parameters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|param| param["name"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(params, vec!["limit", "cursor", "canonical", "network"]);
+    Ok(())
+}
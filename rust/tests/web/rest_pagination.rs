@@ -0,0 +1,244 @@
+use crate::helpers::setup_new_db_dir;
+use actix_web::{
+    test::{self, TestRequest},
+    web::Data,
+    App,
+};
+use mina_indexer::{
+    block::{
+        parser::BlockParser,
+        precomputed::{PcbVersion, PrecomputedBlock},
+        store::BlockStore,
+    },
+    command::store::UserCommandStore,
+    constants::*,
+    event::broadcast::event_channel,
+    health::{IndexerHealth, DEFAULT_MAX_SLOT_LAG},
+    ledger::genesis::GenesisConstants,
+    networks::{IndexedNetwork, IndexedNetworks},
+    state::IndexerPhase,
+    store::{pk_key_prefix, state_hash_pk_txn_sort_key, txn_hash_of_key, IndexerStore},
+    web::{
+        graphql::{build_schema, limits::GraphQLLimits},
+        rest::{
+            pagination::{
+                merge_descending, paginate_prefix_iterator, IteratorEntry, PageParams,
+                MAX_PAGE_LIMIT,
+            },
+            transactions,
+        },
+    },
+};
+use serde_json::Value;
+use speedb::{Direction, IteratorMode};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    sync::Arc,
+};
+
+/// Descending iterator over `keys` in the given mode
+fn iter(keys: &[&[u8]], mode: IteratorMode) -> impl Iterator<Item = IteratorEntry> {
+    let mut keys: Vec<Box<[u8]>> = keys.iter().map(|key| (*key).into()).collect();
+    keys.sort();
+    keys.reverse();
+    let start: Option<Vec<u8>> = match mode {
+        IteratorMode::From(key, Direction::Reverse) => Some(key.to_vec()),
+        _ => None,
+    };
+    keys.into_iter()
+        .filter(move |key| start.as_ref().map_or(true, |start| **key <= **start))
+        .map(|key| Ok((key, Box::default())))
+}
+
+fn params(limit: u32, cursor: Option<String>) -> PageParams {
+    PageParams {
+        limit: Some(limit),
+        cursor,
+        canonical: false,
+    }
+}
+
+#[test]
+fn merge_descending_dedups() {
+    let merged: Vec<_> = merge_descending(
+        iter(&[b"a1", b"b0", b"b2"], IteratorMode::End),
+        iter(&[b"a0", b"b0", b"c0"], IteratorMode::End),
+    )
+    .map(|entry| entry.unwrap().0.to_vec())
+    .collect();
+    assert_eq!(
+        merged,
+        [b"c0", b"b2", b"b0", b"a1", b"a0"].map(|key| key.to_vec())
+    );
+}
+
+#[test]
+fn prefix_pages() -> anyhow::Result<()> {
+    const KEYS: [&[u8]; 6] = [b"a0", b"b0", b"b1", b"b2", b"b3", b"c0"];
+    let page_of = |params: &PageParams| {
+        paginate_prefix_iterator(
+            b"b",
+            Some(b"b\xff".as_slice()),
+            |mode| iter(&KEYS, mode),
+            params,
+            |key, _| Ok(Some(String::from_utf8(key.to_vec())?)),
+        )
+    };
+
+    // the cursor resumes at the next key
+    let first = page_of(&params(2, None))?;
+    assert_eq!(first.data, vec!["b3", "b2"]);
+    assert_eq!(first.next_cursor, Some(hex::encode(b"b1")));
+
+    // pages stop at the end of the prefix
+    let second = page_of(&params(2, first.next_cursor))?;
+    assert_eq!(second.data, vec!["b1", "b0"]);
+    assert_eq!(second.next_cursor, None);
+
+    let all = page_of(&params(4, None))?;
+    assert_eq!(all.data.len(), 4);
+    assert_eq!(all.next_cursor, None);
+
+    // cursors outside the prefix or which aren't hex are invalid
+    for cursor in [
+        hex::encode(b"c0"),
+        hex::encode(b"a0"),
+        "xyz".into(),
+        "".into(),
+    ] {
+        assert!(page_of(&params(2, Some(cursor))).is_err());
+    }
+    Ok(())
+}
+
+#[actix_web::test]
+async fn account_transactions() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("rest-account-transactions")?;
+    let blocks_dir = &PathBuf::from("./tests/data/sequential_blocks");
+    let db = Arc::new(IndexerStore::new(store_dir.path())?);
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        blocks_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+    while let Some((block, block_bytes)) = bp.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        db.add_block(&block, block_bytes)?;
+    }
+
+    // an account with more than 2 transactions which fit in one page, in sort
+    // key order, highest block first
+    let mut accounts: BTreeMap<_, BTreeSet<_>> = BTreeMap::new();
+    for (key, _) in db
+        .txn_from_height_iterator(IteratorMode::Start)
+        .chain(db.txn_to_height_iterator(IteratorMode::Start))
+        .flatten()
+    {
+        accounts.entry(pk_key_prefix(&key)).or_default().insert(key);
+    }
+    let (pk, keys) = accounts
+        .iter()
+        .find(|(_, keys)| (3..=MAX_PAGE_LIMIT as usize).contains(&keys.len()))
+        .unwrap();
+    let prefix = pk.clone().to_bytes();
+    let expected: Vec<_> = keys
+        .iter()
+        .rev()
+        .map(|key| (txn_hash_of_key(key), state_hash_pk_txn_sort_key(key).0))
+        .collect();
+
+    let events = event_channel();
+    let networks = IndexedNetworks::new(IndexedNetwork {
+        name: "mainnet".into(),
+        genesis_state_hash: MAINNET_GENESIS_HASH.into(),
+        schema: build_schema(db.clone(), events.clone(), GraphQLLimits::default()),
+        health: Arc::new(IndexerHealth::new(
+            IndexerPhase::Watching,
+            &GenesisConstants::default(),
+            DEFAULT_MAX_SLOT_LAG,
+        )),
+        store: db.clone(),
+        events,
+        state: None,
+    });
+    let app = test::init_service(
+        App::new()
+            .app_data(Data::new(networks))
+            .service(transactions::get_account_transactions),
+    )
+    .await;
+    macro_rules! get {
+        ($query:expr) => {{
+            let uri = format!("/accounts/{}/transactions?{}", pk, $query);
+            let response =
+                test::call_service(&app, TestRequest::get().uri(&uri).to_request()).await;
+            let status = response.status().as_u16();
+            (status, test::read_body_json::<Value, _>(response).await)
+        }};
+    }
+    let hashes = |body: &Value| -> Vec<(String, String)> {
+        body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|txn| {
+                (
+                    txn["hash"].as_str().unwrap().to_string(),
+                    txn["state_hash"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect()
+    };
+
+    // following the cursors visits every transaction once, in order
+    let mut paged = vec![];
+    let (status, mut body) = get!("limit=2");
+    assert_eq!(status, 200);
+    loop {
+        let page = hashes(&body);
+        assert!(!page.is_empty() && page.len() <= 2);
+        paged.extend(page);
+        match body["next_cursor"].as_str().map(str::to_string) {
+            Some(cursor) => body = get!(format!("limit=2&cursor={cursor}")).1,
+            None => break,
+        }
+    }
+    assert_eq!(paged, expected);
+
+    // page boundaries
+    let n = expected.len();
+    let (_, body) = get!(format!("limit={n}"));
+    assert_eq!(hashes(&body), expected);
+    assert!(body["next_cursor"].is_null());
+
+    let (_, body) = get!(format!("limit={}", n - 1));
+    assert_eq!(hashes(&body), expected[..n - 1]);
+    let cursor = body["next_cursor"].as_str().unwrap().to_string();
+    let (_, body) = get!(format!("limit={n}&cursor={cursor}"));
+    assert_eq!(hashes(&body), expected[n - 1..]);
+    assert!(body["next_cursor"].is_null());
+
+    // none of the blocks are canonical
+    let (status, body) = get!("canonical=true");
+    assert_eq!(status, 200);
+    assert!(hashes(&body).is_empty());
+    assert!(body["next_cursor"].is_null());
+
+    // invalid cursors
+    let other_pk = hex::encode(
+        db.txn_from_height_iterator(IteratorMode::Start)
+            .flatten()
+            .map(|(key, _)| key)
+            .find(|key| !key.starts_with(&prefix))
+            .unwrap(),
+    );
+    for cursor in ["xyz", "00", other_pk.as_str()] {
+        let (status, body) = get!(format!("cursor={cursor}"));
+        assert_eq!(status, 400);
+        assert!(body["error"].as_str().unwrap().contains("Invalid cursor"));
+    }
+    Ok(())
+}
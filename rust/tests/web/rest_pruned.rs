@@ -0,0 +1,100 @@
+use crate::helpers::setup_new_db_dir;
+use actix_web::{
+    test::{self, TestRequest},
+    web::Data,
+    App,
+};
+use mina_indexer::{
+    block::{
+        parser::BlockParser,
+        precomputed::{PcbVersion, PrecomputedBlock},
+        store::BlockStore,
+    },
+    command::signed::SignedCommandWithData,
+    constants::*,
+    event::broadcast::event_channel,
+    health::{IndexerHealth, DEFAULT_MAX_SLOT_LAG},
+    ledger::genesis::GenesisConstants,
+    networks::{IndexedNetwork, IndexedNetworks},
+    state::IndexerPhase,
+    store::IndexerStore,
+    web::{
+        graphql::{build_schema, limits::GraphQLLimits},
+        rest::{pagination::MAX_PAGE_LIMIT, transactions},
+    },
+};
+use serde_json::Value;
+use std::{path::PathBuf, sync::Arc};
+
+#[actix_web::test]
+async fn pruned_block_transactions() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("rest-pruned-block-transactions")?;
+    let blocks_dir = &PathBuf::from("./tests/data/sequential_blocks");
+    let db = Arc::new(IndexerStore::new(store_dir.path())?);
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        blocks_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    let mut blocks = vec![];
+    while let Some((block, block_bytes)) = bp.next_block().await? {
+        let block: PrecomputedBlock = block.into();
+        db.add_block(&block, block_bytes)?;
+        blocks.push(block);
+    }
+
+    // prune the block with the most transactions, without a blocks dir to
+    // re-hydrate it
+    let block = blocks
+        .iter()
+        .max_by_key(|block| block.commands().len())
+        .unwrap();
+    let expected: Vec<String> = SignedCommandWithData::from_precomputed(block)
+        .into_iter()
+        .map(|cmd| cmd.tx_hash)
+        .collect();
+    assert!(!expected.is_empty());
+    assert!(db.prune_block(&block.state_hash())?);
+
+    let events = event_channel();
+    let networks = IndexedNetworks::new(IndexedNetwork {
+        name: "mainnet".into(),
+        genesis_state_hash: MAINNET_GENESIS_HASH.into(),
+        schema: build_schema(db.clone(), events.clone(), GraphQLLimits::default()),
+        health: Arc::new(IndexerHealth::new(
+            IndexerPhase::Watching,
+            &GenesisConstants::default(),
+            DEFAULT_MAX_SLOT_LAG,
+        )),
+        store: db.clone(),
+        events,
+        state: None,
+    });
+    let app = test::init_service(
+        App::new()
+            .app_data(Data::new(networks))
+            .service(transactions::get_block_transactions),
+    )
+    .await;
+
+    // the transactions are served from the pruned block
+    let uri = format!(
+        "/blocks/{}/transactions?limit={MAX_PAGE_LIMIT}",
+        block.state_hash()
+    );
+    let response = test::call_service(&app, TestRequest::get().uri(&uri).to_request()).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body: Value = test::read_body_json(response).await;
+    let hashes: Vec<String> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|txn| txn["hash"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(hashes, expected);
+    Ok(())
+}
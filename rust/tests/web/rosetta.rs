@@ -0,0 +1,561 @@
+use crate::helpers::setup_new_db_dir;
+use actix_web::{
+    test::{self, TestRequest},
+    web::Data,
+    App,
+};
+use async_graphql::async_trait::async_trait;
+use mina_indexer::{
+    block::{parser::BlockParser, store::BlockStore},
+    canonicity::store::CanonicityStore,
+    command::{decode_memo, signed::SignedCommand, store::UserCommandStore},
+    constants::*,
+    ledger::{
+        genesis::{GenesisLedger, GenesisRoot},
+        store::LedgerStore,
+    },
+    proof_systems::{signer::pubkey::CompressedPubKey, FieldHelpers},
+    server::IndexerVersion,
+    state::IndexerState,
+    store::IndexerStore,
+    web::rosetta::{self, broadcast::Broadcaster, construction::SignedTransaction},
+};
+use serde_json::{json, Value};
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+#[actix_web::test]
+async fn data_api() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("rosetta-data-api")?;
+    let block_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&block_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let mut state = IndexerState::new(
+        genesis_ledger.into(),
+        IndexerVersion::new_testing(),
+        indexer_store.clone(),
+        MAINNET_CANONICAL_THRESHOLD,
+        10,
+    )?;
+    state.add_blocks(&mut block_parser).await?;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(Data::new(indexer_store.clone()))
+            .configure(rosetta::configure),
+    )
+    .await;
+    macro_rules! post {
+        ($path:expr, $body:expr) => {{
+            let request = TestRequest::post().uri($path).set_json($body).to_request();
+            let response = test::call_service(&app, request).await;
+            let status = response.status().as_u16();
+            (status, test::read_body_json::<Value, _>(response).await)
+        }};
+    }
+
+    let network = json!({ "blockchain": "mina", "network": "mainnet" });
+    let (status, body) = post!("/network/list", json!({}));
+    assert_eq!(status, 200);
+    assert_eq!(body["network_identifiers"], json!([network]));
+
+    // unknown networks are errors
+    let (status, body) = post!(
+        "/network/status",
+        json!({ "network_identifier": { "blockchain": "mina", "network": "devnet" } })
+    );
+    assert_eq!(status, 500);
+    assert_eq!(body["code"], 4);
+
+    let (_, body) = post!("/network/status", json!({ "network_identifier": network }));
+    assert_eq!(body["current_block_identifier"]["index"], 21);
+    assert_eq!(
+        body["genesis_block_identifier"]["hash"],
+        MAINNET_GENESIS_HASH
+    );
+
+    let (_, body) = post!("/network/options", json!({ "network_identifier": network }));
+    let operation_types = body["allow"]["operation_types"].as_array().unwrap();
+    assert!(operation_types.contains(&json!("coinbase_inc")));
+    assert!(operation_types.contains(&json!("payment_receiver_inc")));
+    assert_eq!(body["allow"]["historical_balance_lookup"], true);
+
+    // block by height, with its coinbase
+    let (status, body) = post!(
+        "/block",
+        json!({ "network_identifier": network, "block_identifier": { "index": 10 } })
+    );
+    assert_eq!(status, 200);
+    let block = &body["block"];
+    let block_identifier = block["block_identifier"].clone();
+    assert_eq!(block_identifier["index"], 10);
+    assert_eq!(block["parent_block_identifier"]["index"], 9);
+
+    let transactions = block["transactions"].as_array().unwrap();
+    let coinbase = transactions
+        .iter()
+        .find(|txn| txn["operations"][0]["type"] == "coinbase_inc")
+        .unwrap();
+    let coinbase_receiver = coinbase["operations"][0]["account"]["address"].clone();
+
+    // same block by hash
+    let (_, body) = post!(
+        "/block",
+        json!({ "network_identifier": network, "block_identifier": { "hash": block_identifier["hash"] } })
+    );
+    assert_eq!(body["block"]["block_identifier"], block_identifier);
+
+    // transaction of the block
+    let (status, body) = post!(
+        "/block/transaction",
+        json!({
+            "network_identifier": network,
+            "block_identifier": block_identifier,
+            "transaction_identifier": coinbase["transaction_identifier"],
+        })
+    );
+    assert_eq!(status, 200);
+    assert_eq!(body["transaction"], *coinbase);
+
+    let (_, body) = post!(
+        "/block",
+        json!({ "network_identifier": network, "block_identifier": { "index": 100 } })
+    );
+    assert_eq!(body["code"], 9);
+
+    // historical balance
+    let (status, body) = post!(
+        "/account/balance",
+        json!({
+            "network_identifier": network,
+            "account_identifier": { "address": coinbase_receiver },
+            "block_identifier": { "index": 10 },
+        })
+    );
+    assert_eq!(status, 200);
+    assert_eq!(body["block_identifier"], block_identifier);
+
+    let ledger = indexer_store.get_ledger_at_height(10, false)?.unwrap();
+    let balance = ledger.accounts[&coinbase_receiver.as_str().unwrap().into()]
+        .balance
+        .0;
+    assert_eq!(body["balances"][0]["value"], balance.to_string());
+    assert_eq!(body["balances"][0]["currency"]["symbol"], "MINA");
+
+    let (_, body) = post!(
+        "/account/balance",
+        json!({ "network_identifier": network, "account_identifier": { "address": "B62" } })
+    );
+    assert_eq!(body["code"], 10);
+
+    let (_, body) = post!("/account/coins", json!({ "network_identifier": network }));
+    assert_eq!(body["code"], 12);
+
+    let (_, body) = post!("/mempool", json!({ "network_identifier": network }));
+    assert_eq!(body["transaction_identifiers"], json!([]));
+
+    // search
+    let (status, body) = post!(
+        "/search/transactions",
+        json!({
+            "network_identifier": network,
+            "address": coinbase_receiver,
+            "type": "coinbase_inc",
+            "limit": 1,
+        })
+    );
+    assert_eq!(status, 200);
+    assert!(body["total_count"].as_u64().unwrap() >= 1);
+    assert_eq!(body["transactions"].as_array().unwrap().len(), 1);
+
+    let (_, body) = post!(
+        "/search/transactions",
+        json!({
+            "network_identifier": network,
+            "address": coinbase_receiver,
+            "type": "coinbase_inc",
+            "max_block": 10,
+            "limit": 1,
+        })
+    );
+    assert_eq!(
+        body["transactions"][0]["block_identifier"],
+        block_identifier
+    );
+
+    // account search pages, highest block first
+    let search = |offset: u32, limit: u32| {
+        json!({
+            "network_identifier": network,
+            "address": coinbase_receiver,
+            "offset": offset,
+            "limit": limit,
+        })
+    };
+    let (_, body) = post!("/search/transactions", search(0, 1_000));
+    let all = body["transactions"].as_array().unwrap().clone();
+    assert!(all.len() >= 2);
+    assert_eq!(body["total_count"], all.len());
+    assert!(all.windows(2).all(|txns| {
+        txns[0]["block_identifier"]["index"].as_u64()
+            >= txns[1]["block_identifier"]["index"].as_u64()
+    }));
+
+    let mut paged = vec![];
+    let mut offset = Some(0);
+    while let Some(page_offset) = offset {
+        let (_, body) = post!("/search/transactions", search(page_offset, 1));
+        paged.extend(body["transactions"].as_array().unwrap().clone());
+        offset = body["next_offset"].as_u64().map(|offset| offset as u32);
+    }
+    assert_eq!(paged, all);
+
+    let (_, body) = post!(
+        "/search/transactions",
+        json!({
+            "network_identifier": network,
+            "transaction_identifier": coinbase["transaction_identifier"],
+        })
+    );
+    assert_eq!(body["total_count"], 1);
+    assert_eq!(
+        body["transactions"][0]["block_identifier"],
+        block_identifier
+    );
+
+    // pruned blocks are served without a blocks dir to re-hydrate them
+    let block_request =
+        json!({ "network_identifier": network, "block_identifier": block_identifier });
+    let (_, block) = post!("/block", block_request.clone());
+    assert!(indexer_store.prune_block(&block_identifier["hash"].as_str().unwrap().into())?);
+
+    let (status, body) = post!("/block", block_request);
+    assert_eq!(status, 200);
+    assert_eq!(body, block);
+
+    // malformed bodies are Rosetta errors
+    let request = TestRequest::post()
+        .uri("/block")
+        .set_payload("{")
+        .to_request();
+    let body: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(body["code"], 2);
+    Ok(())
+}
+
+/// Records submitted transactions, failing if `fail` is set
+#[derive(Default)]
+struct StubBroadcaster {
+    submitted: Mutex<Vec<SignedTransaction>>,
+    fail: bool,
+}
+
+#[async_trait]
+impl Broadcaster for StubBroadcaster {
+    async fn broadcast(&self, transaction: &SignedTransaction) -> anyhow::Result<()> {
+        if self.fail {
+            anyhow::bail!("stub failure");
+        }
+        self.submitted.lock().unwrap().push(transaction.clone());
+        Ok(())
+    }
+}
+
+#[actix_web::test]
+async fn construction_api() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("rosetta-construction-api")?;
+    let block_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&block_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let mut state = IndexerState::new(
+        genesis_ledger.into(),
+        IndexerVersion::new_testing(),
+        indexer_store.clone(),
+        MAINNET_CANONICAL_THRESHOLD,
+        10,
+    )?;
+    state.add_blocks(&mut block_parser).await?;
+
+    // a payment of the best chain
+    let command = (2..=21)
+        .filter_map(|height| indexer_store.get_canonical_hash_at_height(height).unwrap())
+        .flat_map(|state_hash| {
+            indexer_store
+                .get_block_user_commands(&state_hash)
+                .unwrap()
+                .unwrap_or_default()
+        })
+        .map(SignedCommand::from)
+        .find(|cmd| !cmd.is_delegation())
+        .unwrap();
+    let common = command.payload_common();
+    let (rx, s) = command
+        .0
+        .clone()
+        .inner()
+        .inner()
+        .signature
+        .0
+        .inner()
+        .inner();
+    let signature = format!("{}{}", hex::encode(rx), hex::encode(s));
+    let source = command.source_pk().0;
+    let receiver = command.receiver_pk().0;
+
+    let stub = Arc::new(StubBroadcaster::default());
+    let app = test::init_service(
+        App::new()
+            .app_data(Data::new(indexer_store.clone()))
+            .app_data(Data::new(stub.clone() as Arc<dyn Broadcaster>))
+            .configure(rosetta::configure),
+    )
+    .await;
+    macro_rules! post {
+        ($path:expr, $body:expr) => {{
+            let request = TestRequest::post().uri($path).set_json($body).to_request();
+            let response = test::call_service(&app, request).await;
+            let status = response.status().as_u16();
+            (status, test::read_body_json::<Value, _>(response).await)
+        }};
+    }
+
+    let network = json!({ "blockchain": "mina", "network": "mainnet" });
+    let public_key = json!({
+        "hex_bytes": CompressedPubKey::from_address(&source)?.to_hex(),
+        "curve_type": "pallas",
+    });
+    let (status, body) = post!(
+        "/construction/derive",
+        json!({ "network_identifier": network, "public_key": public_key })
+    );
+    assert_eq!(status, 200);
+    assert_eq!(body["account_identifier"]["address"], source);
+
+    let operations = json!([
+        {
+            "operation_identifier": { "index": 0 },
+            "type": "fee_payment",
+            "account": { "address": source },
+            "amount": { "value": format!("-{}", command.fee()), "currency": { "symbol": "MINA", "decimals": 9 } },
+        },
+        {
+            "operation_identifier": { "index": 1 },
+            "type": "payment_source_dec",
+            "account": { "address": source },
+            "amount": { "value": format!("-{}", command.amount()), "currency": { "symbol": "MINA", "decimals": 9 } },
+        },
+        {
+            "operation_identifier": { "index": 2 },
+            "related_operations": [{ "index": 1 }],
+            "type": "payment_receiver_inc",
+            "account": { "address": receiver },
+            "amount": { "value": command.amount().to_string(), "currency": { "symbol": "MINA", "decimals": 9 } },
+        },
+    ]);
+    let memo = decode_memo(&common.memo.t.0);
+    let valid_until = (common.valid_until.t.t as u32).to_string();
+
+    let (status, body) = post!(
+        "/construction/preprocess",
+        json!({
+            "network_identifier": network,
+            "operations": operations,
+            "metadata": { "memo": memo, "valid_until": valid_until },
+        })
+    );
+    assert_eq!(status, 200);
+    assert_eq!(body["required_public_keys"][0]["address"], source);
+    let options = body["options"].clone();
+
+    // nonce from the best ledger
+    let (status, body) = post!(
+        "/construction/metadata",
+        json!({ "network_identifier": network, "options": options })
+    );
+    assert_eq!(status, 200);
+    let best_hash = indexer_store.get_best_block_hash()?.unwrap();
+    let ledger = indexer_store
+        .get_ledger_state_hash(&best_hash, false)?
+        .unwrap();
+    let nonce = ledger.accounts[&source.as_str().into()]
+        .nonce
+        .unwrap_or_default();
+    assert_eq!(body["metadata"]["nonce"], nonce.0.to_string());
+    assert!(
+        body["suggested_fee"][0]["value"]
+            .as_str()
+            .unwrap()
+            .parse::<u64>()?
+            >= 1_000_000
+    );
+
+    // reconstruct the included payment
+    let mut metadata = body["metadata"].clone();
+    metadata["nonce"] = (common.nonce.t.t as u32).to_string().into();
+    let (status, body) = post!(
+        "/construction/payloads",
+        json!({ "network_identifier": network, "operations": operations, "metadata": metadata })
+    );
+    assert_eq!(status, 200);
+    let unsigned_transaction = body["unsigned_transaction"].clone();
+    let signing_payload = body["payloads"][0].clone();
+    assert_eq!(signing_payload["account_identifier"]["address"], source);
+
+    // the signing payload is the included payment's legacy random oracle
+    // input: the fee payer, source & receiver x coordinates (255 bits each)
+    // followed by the payload bits, packed little endian
+    let payload = hex::decode(signing_payload["hex_bytes"].as_str().unwrap())?;
+    let mut payload_bits = (0..payload.len() * 8).map(|i| payload[i / 8] >> (i % 8) & 1 == 1);
+    let mut take =
+        |num_bits: usize| -> Vec<bool> { payload_bits.by_ref().take(num_bits).collect() };
+    let le_bits = |bytes: &[u8], num_bits: usize| -> Vec<bool> {
+        (0..num_bits)
+            .map(|i| bytes[i / 8] >> (i % 8) & 1 == 1)
+            .collect()
+    };
+    let source_pk = CompressedPubKey::from_address(&source)?;
+    let receiver_pk = CompressedPubKey::from_address(&receiver)?;
+    for pk in [&source_pk, &source_pk, &receiver_pk] {
+        assert_eq!(take(255), le_bits(&pk.x.to_bytes(), 255));
+    }
+
+    // common
+    assert_eq!(take(64), le_bits(&command.fee().to_le_bytes(), 64));
+    assert_eq!(take(64), le_bits(&1u64.to_le_bytes(), 64));
+    assert_eq!(take(1), [source_pk.is_odd]);
+    assert_eq!(
+        take(32),
+        le_bits(&(common.nonce.t.t as u32).to_le_bytes(), 32)
+    );
+    assert_eq!(
+        take(32),
+        le_bits(&(common.valid_until.t.t as u32).to_le_bytes(), 32)
+    );
+    assert_eq!(
+        take(common.memo.t.0.len() * 8),
+        le_bits(&common.memo.t.0, common.memo.t.0.len() * 8)
+    );
+
+    // payment body
+    assert_eq!(take(3), [false; 3]);
+    assert_eq!(take(2), [source_pk.is_odd, receiver_pk.is_odd]);
+    assert_eq!(take(64), le_bits(&1u64.to_le_bytes(), 64));
+    assert_eq!(take(64), le_bits(&command.amount().to_le_bytes(), 64));
+    assert_eq!(take(1), [false]);
+
+    // only padding remains
+    assert!(take(8).iter().all(|bit| !bit));
+    assert!(take(1).is_empty());
+
+    let (_, body) = post!(
+        "/construction/parse",
+        json!({ "network_identifier": network, "signed": false, "transaction": unsigned_transaction })
+    );
+    assert_eq!(body["operations"], operations);
+    assert!(body.get("account_identifier_signers").is_none());
+
+    let (status, body) = post!(
+        "/construction/combine",
+        json!({
+            "network_identifier": network,
+            "unsigned_transaction": unsigned_transaction,
+            "signatures": [{
+                "signing_payload": signing_payload,
+                "public_key": public_key,
+                "signature_type": "schnorr_poseidon",
+                "hex_bytes": signature,
+            }],
+        })
+    );
+    assert_eq!(status, 200);
+    let signed_transaction = body["signed_transaction"].clone();
+
+    let (_, body) = post!(
+        "/construction/parse",
+        json!({ "network_identifier": network, "signed": true, "transaction": signed_transaction })
+    );
+    assert_eq!(body["account_identifier_signers"][0]["address"], source);
+
+    // same hash as the included payment
+    let tx_hash = command.hash_signed_command()?;
+    let (status, body) = post!(
+        "/construction/hash",
+        json!({ "network_identifier": network, "signed_transaction": signed_transaction })
+    );
+    assert_eq!(status, 200);
+    assert_eq!(body["transaction_identifier"]["hash"], tx_hash);
+
+    let (status, body) = post!(
+        "/construction/submit",
+        json!({ "network_identifier": network, "signed_transaction": signed_transaction })
+    );
+    assert_eq!(status, 200);
+    assert_eq!(body["transaction_identifier"]["hash"], tx_hash);
+    assert_eq!(
+        serde_json::to_value(&stub.submitted.lock().unwrap()[0])?,
+        serde_json::from_str::<Value>(signed_transaction.as_str().unwrap())?
+    );
+
+    // invalid requests
+    let (_, body) = post!(
+        "/construction/payloads",
+        json!({ "network_identifier": network, "operations": operations.as_array().unwrap()[..2] })
+    );
+    assert_eq!(body["code"], 11);
+
+    metadata["memo"] = "a".repeat(33).into();
+    let (_, body) = post!(
+        "/construction/payloads",
+        json!({ "network_identifier": network, "operations": operations, "metadata": metadata })
+    );
+    assert_eq!(body["code"], 18);
+
+    let (_, body) = post!(
+        "/construction/derive",
+        json!({ "network_identifier": network, "public_key": { "hex_bytes": "00", "curve_type": "pallas" } })
+    );
+    assert_eq!(body["code"], 14);
+
+    // submission failures
+    let app = test::init_service(
+        App::new()
+            .app_data(Data::new(indexer_store.clone()))
+            .configure(rosetta::configure),
+    )
+    .await;
+    let request = TestRequest::post()
+        .uri("/construction/submit")
+        .set_json(
+            json!({ "network_identifier": network, "signed_transaction": signed_transaction }),
+        )
+        .to_request();
+    let body: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(body["code"], 19);
+
+    let failing = Arc::new(StubBroadcaster {
+        fail: true,
+        ..Default::default()
+    });
+    let app = test::init_service(
+        App::new()
+            .app_data(Data::new(indexer_store.clone()))
+            .app_data(Data::new(failing as Arc<dyn Broadcaster>))
+            .configure(rosetta::configure),
+    )
+    .await;
+    let request = TestRequest::post()
+        .uri("/construction/submit")
+        .set_json(
+            json!({ "network_identifier": network, "signed_transaction": signed_transaction }),
+        )
+        .to_request();
+    let body: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(body["code"], 20);
+    Ok(())
+}
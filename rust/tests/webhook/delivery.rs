@@ -0,0 +1,276 @@
+use crate::helpers::setup_new_db_dir;
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PrecomputedBlock, store::BlockStore},
+    command::internal::InternalCommand,
+    constants::*,
+    event::{
+        db::{DbBlockEvent, DbEvent},
+        store::EventStore,
+        IndexerEvent,
+    },
+    ledger::genesis::{GenesisLedger, GenesisRoot},
+    server::IndexerVersion,
+    state::{IndexerState, IndexerStateConfig},
+    store::IndexerStore,
+    webhook::{
+        delivery::{
+            verify_webhook_signature, RetryPolicy, WebhookDispatcher, WEBHOOK_SIGNATURE_HEADER,
+        },
+        payload::{WebhookEvent, WebhookPayload},
+        store::WebhookStore,
+        WebhookCursor, WebhookFilter,
+    },
+};
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+const SECRET: &str = "webhook-test-secret";
+
+type Received = Arc<Mutex<Vec<(Option<String>, web::Bytes)>>>;
+
+async fn receive(
+    req: HttpRequest,
+    body: web::Bytes,
+    received: web::Data<Received>,
+) -> HttpResponse {
+    let signature = req
+        .headers()
+        .get(WEBHOOK_SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    received.lock().unwrap().push((signature, body));
+    HttpResponse::Ok().finish()
+}
+
+/// Starts a local HTTP receiver, returning its base url
+fn start_receiver(received: Received) -> anyhow::Result<String> {
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(web::Data::new(received.clone()))
+            .route("/hook", web::post().to(receive))
+            .route("/fail", web::post().to(HttpResponse::InternalServerError))
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))?;
+    let addr = server.addrs()[0];
+    tokio::spawn(server.run());
+    Ok(format!("http://{addr}"))
+}
+
+#[tokio::test]
+async fn deliver() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("webhook-delivery")?;
+    let log_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+    let mut block_parser = BlockParser::new_testing(&log_dir)?;
+    let indexer_store = Arc::new(IndexerStore::new(store_dir.path())?);
+    let genesis_ledger =
+        serde_json::from_str::<GenesisRoot>(GenesisLedger::MAINNET_V1_GENESIS_LEDGER_CONTENTS)?;
+    let mut state = IndexerState::new_from_config(IndexerStateConfig::new(
+        genesis_ledger.into(),
+        IndexerVersion::new_testing(),
+        indexer_store.clone(),
+        MAINNET_CANONICAL_THRESHOLD,
+        10,
+    ))?;
+
+    let received: Received = Arc::default();
+    let url = start_receiver(received.clone())?;
+
+    // register webhooks prior to ingesting blocks
+    let mut blocks = vec![];
+    let (block, block_bytes) = block_parser.next_block().await?.unwrap();
+    let first_block: PrecomputedBlock = block.into();
+    let coinbase_receiver = first_block.coinbase_receiver();
+    blocks.push((first_block, block_bytes));
+
+    let all_blocks = indexer_store.add_webhook(
+        &format!("{url}/hook"),
+        SECRET,
+        WebhookFilter::parse(Some("new_block"), None, None, 0)?,
+    )?;
+    let coinbase = indexer_store.add_webhook(
+        &format!("{url}/hook"),
+        SECRET,
+        WebhookFilter::parse(
+            Some("internal_command"),
+            Some(&coinbase_receiver.0),
+            Some(1),
+            0,
+        )?,
+    )?;
+    let confirmed = indexer_store.add_webhook(
+        &format!("{url}/hook"),
+        SECRET,
+        WebhookFilter::parse(Some("new_block"), None, None, 5)?,
+    )?;
+    let failing =
+        indexer_store.add_webhook(&format!("{url}/fail"), SECRET, WebhookFilter::default())?;
+
+    // invalid registrations
+    assert!(WebhookFilter::parse(Some("not_a_kind"), None, None, 0).is_err());
+    assert!(WebhookFilter::parse(None, Some("B62invalid"), None, 0).is_err());
+    assert!(indexer_store
+        .add_webhook("ftp://localhost", SECRET, WebhookFilter::default())
+        .is_err());
+
+    while let Some((block, block_bytes)) = block_parser.next_block().await? {
+        blocks.push((block.into(), block_bytes));
+    }
+    for (block, block_bytes) in blocks.iter() {
+        state.block_pipeline(block, *block_bytes)?;
+    }
+
+    let mut dispatcher = WebhookDispatcher::with_retry_policy(
+        indexer_store.clone(),
+        RetryPolicy {
+            initial_backoff: Duration::from_secs(60),
+            ..Default::default()
+        },
+    );
+    let num_delivered = dispatcher.dispatch().await?;
+
+    // all payloads are signed
+    let payloads: Vec<WebhookPayload> = received
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(signature, body)| {
+            assert!(verify_webhook_signature(
+                SECRET,
+                body,
+                signature.as_deref().unwrap()
+            ));
+            assert!(!verify_webhook_signature(
+                "wrong-secret",
+                body,
+                signature.as_deref().unwrap()
+            ));
+            serde_json::from_slice(body).unwrap()
+        })
+        .collect();
+    assert_eq!(payloads.len() as u64, num_delivered);
+
+    let delivery_ids: HashSet<_> = payloads.iter().map(|p| p.delivery_id.clone()).collect();
+    assert_eq!(delivery_ids.len(), payloads.len());
+
+    // every new block is delivered
+    let next_seq_num = indexer_store.get_next_seq_num()?;
+    let new_blocks: Vec<_> = payloads
+        .iter()
+        .filter(|p| p.webhook_id == all_blocks.id)
+        .collect();
+    assert_eq!(new_blocks.len(), blocks.len());
+    assert!(new_blocks
+        .iter()
+        .all(|p| matches!(p.event, WebhookEvent::NewBlock { .. })));
+    assert_eq!(
+        indexer_store
+            .get_webhook_cursor(all_blocks.id)?
+            .unwrap()
+            .seq_num,
+        next_seq_num
+    );
+
+    // internal commands involving the coinbase receiver
+    let num_internal_commands: usize = blocks
+        .iter()
+        .map(|(block, _)| {
+            InternalCommand::from_precomputed(block)
+                .into_iter()
+                .filter(|cmd| match cmd {
+                    InternalCommand::Coinbase { receiver, .. } => *receiver == coinbase_receiver,
+                    InternalCommand::FeeTransfer {
+                        sender, receiver, ..
+                    }
+                    | InternalCommand::FeeTransferViaCoinbase {
+                        sender, receiver, ..
+                    } => *sender == coinbase_receiver || *receiver == coinbase_receiver,
+                })
+                .count()
+        })
+        .sum();
+    let internal_commands: Vec<_> = payloads
+        .iter()
+        .filter(|p| p.webhook_id == coinbase.id)
+        .collect();
+    assert!(num_internal_commands > 0);
+    assert_eq!(internal_commands.len(), num_internal_commands);
+
+    // only best chain blocks with enough confirmations are delivered
+    let best_tip_height = indexer_store.get_best_block_height()?.unwrap();
+    let mut best_chain = HashSet::new();
+    let mut ancestor = indexer_store.get_best_block_hash()?;
+    while let Some(state_hash) = ancestor {
+        ancestor = indexer_store.get_block_parent_hash(&state_hash)?;
+        best_chain.insert(state_hash);
+    }
+
+    let confirmed_blocks: Vec<_> = payloads
+        .iter()
+        .filter(|p| p.webhook_id == confirmed.id)
+        .collect();
+    assert!(!confirmed_blocks.is_empty());
+    assert!(confirmed_blocks.iter().all(|p| match &p.event {
+        WebhookEvent::NewBlock {
+            state_hash,
+            blockchain_length,
+            ..
+        } => *blockchain_length + 5 <= best_tip_height && best_chain.contains(state_hash),
+        _ => false,
+    }));
+
+    // every confirmed block is delivered, unconfirmed blocks don't hold back
+    // later events
+    let num_confirmed = blocks
+        .iter()
+        .filter(|(block, _)| {
+            block.blockchain_length() + 5 <= best_tip_height
+                && best_chain.contains(&block.state_hash())
+        })
+        .count();
+    assert_eq!(confirmed_blocks.len(), num_confirmed);
+
+    let cursor = indexer_store.get_webhook_cursor(confirmed.id)?.unwrap();
+    assert_eq!(cursor.seq_num, next_seq_num);
+    assert!(!cursor.unconfirmed.is_empty());
+    for seq_num in cursor.unconfirmed {
+        match indexer_store.get_event(seq_num)? {
+            Some(IndexerEvent::Db(DbEvent::Block(DbBlockEvent::NewBlock {
+                blockchain_length,
+                ..
+            }))) => assert!(blockchain_length + 5 > best_tip_height),
+            event => panic!("Unexpected unconfirmed event {event:?}"),
+        }
+    }
+
+    // failed deliveries back off without advancing the cursor
+    let cursor = indexer_store.get_webhook_cursor(failing.id)?.unwrap();
+    assert_eq!(cursor.num_failures, 1);
+    assert!(cursor.last_error.is_some());
+    assert_eq!(cursor.num_delivered, 0);
+
+    assert_eq!(dispatcher.dispatch().await?, 0);
+    let cursor = indexer_store.get_webhook_cursor(failing.id)?.unwrap();
+    assert_eq!(cursor.num_failures, 1);
+
+    // removed webhooks receive nothing
+    assert!(indexer_store.remove_webhook(failing.id)?);
+    assert!(!indexer_store.remove_webhook(failing.id)?);
+    assert_eq!(indexer_store.get_webhook_cursor(failing.id)?, None);
+    assert_eq!(indexer_store.get_webhooks()?.len(), 3);
+
+    // nor are their cursors written back
+    indexer_store.set_webhook_cursor(failing.id, &WebhookCursor::default())?;
+    assert_eq!(indexer_store.get_webhook_cursor(failing.id)?, None);
+
+    // ids of removed webhooks aren't reused
+    let added =
+        indexer_store.add_webhook(&format!("{url}/hook"), SECRET, WebhookFilter::default())?;
+    assert!(added.id > failing.id);
+    Ok(())
+}